@@ -0,0 +1,11 @@
+#import bevy_ascii::cell::AsciiCell
+
+// Cycles the characters of the scene through the rainbow, with the colors moving across the
+// columns over time.
+fn cell_hook(cell: AsciiCell) -> AsciiCell {
+    var out = cell;
+    let hue = fract(f32(cell.position.x) / f32(cell.grid_size.x) + cell.time * 0.25);
+    let rainbow = clamp(abs(fract(hue + vec3<f32>(1.0, 2.0 / 3.0, 1.0 / 3.0)) * 6.0 - 3.0) - 1.0, vec3<f32>(0.0), vec3<f32>(1.0));
+    out.color = vec4<f32>(rainbow, 1.0);
+    return out;
+}
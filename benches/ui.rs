@@ -0,0 +1,157 @@
+use bevy::prelude::*;
+use bevy_ascii::prelude::*;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+// Roughly the grid of a 1080p window at the default column count.
+const WIDTH: u32 = 160;
+const HEIGHT: u32 = 90;
+
+const TEXT: &str = "The quick brown fox jumps over the lazy dog, again and again, until the line has to wrap.";
+
+//=============================================================================
+//             Surface
+//=============================================================================
+
+fn set_character(c: &mut Criterion) {
+    let surface = AsciiSurface::new(WIDTH, HEIGHT);
+    let character = AsciiCharacter::new('#'.into(), AsciiColor::White, AsciiColor::Black);
+
+    c.bench_function("surface/set_character", |b| {
+        b.iter(|| {
+            for y in 0..HEIGHT as i32 {
+                for x in 0..WIDTH as i32 {
+                    surface.set_character(black_box(x), black_box(y), character);
+                }
+            }
+        })
+    });
+}
+
+fn as_byte_vec(c: &mut Criterion) {
+    let mut group = c.benchmark_group("surface/as_byte_vec");
+    for scale in [1, 2, 4] {
+        let surface = AsciiSurface::new(WIDTH * scale, HEIGHT * scale);
+        surface.fill(AsciiCharacter::new('#'.into(), AsciiColor::White, AsciiColor::Black));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("{}x{}", WIDTH * scale, HEIGHT * scale)),
+            &surface,
+            |b, surface| b.iter(|| black_box(surface.as_byte_vec())),
+        );
+    }
+    group.finish();
+}
+
+//=============================================================================
+//             Drawing
+//=============================================================================
+
+fn draw_text(c: &mut Criterion) {
+    let surface = AsciiSurface::new(WIDTH, HEIGHT);
+    let bounds = AsciiBounds::new(10, 10, 40, 10, 0);
+
+    c.bench_function("draw/text", |b| {
+        b.iter(|| {
+            let buffer = AsciiBuffer::new(&surface, &bounds, None);
+            buffer.text(black_box(TEXT)).draw();
+        })
+    });
+
+    c.bench_function("draw/text_wrapped", |b| {
+        b.iter(|| {
+            let buffer = AsciiBuffer::new(&surface, &bounds, None);
+            buffer.text(black_box(TEXT)).wrap().draw();
+        })
+    });
+
+    let cache = AsciiTextCache::default();
+    c.bench_function("draw/text_wrapped_cached", |b| {
+        b.iter(|| {
+            let buffer = AsciiBuffer::new(&surface, &bounds, None);
+            buffer.text(black_box(TEXT)).wrap().cached(&cache).draw();
+        })
+    });
+}
+
+fn draw_box(c: &mut Criterion) {
+    let surface = AsciiSurface::new(WIDTH, HEIGHT);
+    let bounds = AsciiBounds::new(0, 0, WIDTH, HEIGHT, 0);
+
+    c.bench_function("draw/box_fullscreen", |b| {
+        b.iter(|| {
+            let buffer = AsciiBuffer::new(&surface, &bounds, None);
+            black_box(buffer.square().title("Title").draw());
+        })
+    });
+}
+
+// The cells of a fullscreen drawing written one by one, which locks the surface for every cell,
+// and through a `DrawBatch`. The surface is filled on layer 1 first, so the drawing on layer 2
+// can skip the layer test and the one on layer 0 has to test every cell.
+fn draw_cells(c: &mut Criterion) {
+    let mut group = c.benchmark_group("draw/cells");
+    let fill = AsciiCharacter::new('.'.into(), AsciiColor::Grey, AsciiColor::Black).with_layer(1);
+    let character = AsciiCharacter::new('#'.into(), AsciiColor::White, AsciiColor::Black);
+
+    for (name, layer) in [("on_top", 2), ("under", 0)] {
+        let surface = AsciiSurface::new(WIDTH, HEIGHT);
+        let bounds = AsciiBounds::new(0, 0, WIDTH, HEIGHT, layer);
+
+        group.bench_function(format!("unbatched/{name}"), |b| {
+            b.iter(|| {
+                surface.fill(fill);
+                let buffer = AsciiBuffer::new(&surface, &bounds, None);
+                for y in 0..HEIGHT as i32 {
+                    for x in 0..WIDTH as i32 {
+                        buffer.set_character(black_box(x), black_box(y), character);
+                    }
+                }
+            })
+        });
+
+        group.bench_function(format!("batched/{name}"), |b| {
+            b.iter(|| {
+                surface.fill(fill);
+                let buffer = AsciiBuffer::new(&surface, &bounds, None);
+                let mut batch = buffer.batch();
+                for y in 0..HEIGHT as i32 {
+                    for x in 0..WIDTH as i32 {
+                        batch.set_character(black_box(x), black_box(y), character);
+                    }
+                }
+                batch.commit();
+            })
+        });
+    }
+    group.finish();
+}
+
+// Does the same work as extracting the ui for a camera: every node is drawn into a fresh surface
+// through its own buffer. The real extraction also walks the hierarchy to find the target camera.
+fn extract_components(c: &mut Criterion) {
+    let mut group = c.benchmark_group("extract/buttons");
+    for count in [100, 1_000] {
+        let mut world = World::new();
+        for i in 0..count {
+            let x = (i * 8) % WIDTH as i32;
+            let y = (i * 8) / WIDTH as i32 * 3 % HEIGHT as i32;
+            world.spawn((AsciiNode::new(x, y, 8, 3, 0), AsciiButton::from_string("Button")));
+        }
+        let mut query = world.query::<(&AsciiNode, &AsciiButton)>();
+
+        group.bench_function(BenchmarkId::from_parameter(count), |b| {
+            b.iter(|| {
+                let surface = AsciiSurface::new(WIDTH, HEIGHT);
+                for (node, button) in query.iter(&world) {
+                    let mut buffer = AsciiBuffer::new(&surface, &node.bounds, None);
+                    AsciiComponent::render(button, &mut buffer);
+                }
+                black_box(surface)
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(surface, set_character, as_byte_vec);
+criterion_group!(drawing, draw_text, draw_box, draw_cells, extract_components);
+criterion_main!(surface, drawing);
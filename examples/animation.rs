@@ -0,0 +1,56 @@
+use bevy::{prelude::*, window::close_on_esc};
+use bevy_ascii::prelude::*;
+
+// A row of torches over a strip of water. The torches flicker on their own and the water rolls
+// back and forth, the ui is only redrawn when one of them shows a new frame.
+pub fn main() {
+    let mut app = App::new();
+
+    app
+        .add_plugins(DefaultPlugins)
+        .add_plugins(AsciiEffectPluginGroup)
+
+        .add_systems(Startup, init)
+        .add_systems(Update, close_on_esc)
+    ;
+
+    app.run();
+}
+
+fn init(mut commands : Commands, mut clips : ResMut<Assets<AsciiAnimationClip>>) {
+    let camera = commands.spawn((
+        Camera3dBundle::default(),
+        AsciiCamera::default(),
+        AsciiUi::default(),
+        VisibilityBundle::default(),
+    )).id();
+
+    let torch = clips.add(
+        AsciiAnimationClip::new(1, 1)
+            .with_frame(0.15, [(Character::Asterisk, AsciiColor::Yellow, AsciiColor::Black)])
+            .with_frame(0.1, [(Character::Asterisk, AsciiColor::Orange, AsciiColor::Black)])
+            .with_frame(0.2, [(Character::Plus, AsciiColor::Red, AsciiColor::Black)])
+            .with_frame(0.1, [(Character::Asterisk, AsciiColor::Orange, AsciiColor::Black)]),
+    );
+
+    let wave = |offset : usize| {
+        (0..12).map(move |x| {
+            let character = if (x + offset) % 4 == 3 { Character::Equal } else { Character::Hyphen };
+            (character, AsciiColor::LightBlue, AsciiColor::Blue)
+        })
+    };
+    let water = clips.add(
+        AsciiAnimationClip::new(12, 1)
+            .with_frame(0.3, wave(0))
+            .with_frame(0.3, wave(1))
+            .with_frame(0.3, wave(2))
+            .with_frame(0.3, wave(3))
+            .mode(AsciiAnimationMode::PingPong),
+    );
+
+    let mut ui = commands.ascii_ui_with_parent(camera);
+    for i in 0..4 {
+        ui.relative(2 + i * 3, 2, 1, 1, AsciiAnimatedGlyph::new(torch.clone()).offset(i as f32 * 0.13)).pop();
+    }
+    ui.relative(2, 4, 12, 1, AsciiAnimatedGlyph::new(water));
+}
@@ -0,0 +1,57 @@
+use bevy::prelude::*;
+use bevy_ascii::prelude::*;
+
+// A command palette. Click the input and type, the commands that match are listed under it. Pick
+// one with the arrow keys and tab or enter, or click it.
+pub fn main() {
+    let mut app = App::new();
+
+    app
+        .add_plugins(DefaultPlugins)
+        .add_plugins(AsciiEffectPluginGroup)
+
+        .add_systems(Startup, init)
+        .add_systems(Update, log_commands)
+    ;
+
+    app.run();
+}
+
+fn init(mut commands : Commands) {
+    let camera = commands.spawn((
+        Camera3dBundle::default(),
+        AsciiCamera::default(),
+        AsciiUi::default(),
+        VisibilityBundle::default(),
+    )).id();
+
+    let palette = AsciiAutocomplete::new([
+        "open file",
+        "open recent",
+        "save",
+        "save as",
+        "close window",
+        "toggle fullscreen",
+        "toggle minimap",
+        "reload shaders",
+        "quit",
+    ])
+    .placeholder("Type a command")
+    .max_visible(5);
+
+    commands.ascii_ui_with_parent(camera)
+        .centered(30, 1, palette)
+    ;
+}
+
+fn log_commands(
+    mut selected : EventReader<AsciiAutocompleteSelectedEvent>,
+    mut submitted : EventReader<AsciiAutocompleteSubmittedEvent>,
+) {
+    for event in selected.read() {
+        info!("picked {} (#{})", event.text, event.index);
+    }
+    for event in submitted.read() {
+        info!("no command named {}", event.text);
+    }
+}
@@ -0,0 +1,42 @@
+use bevy::{prelude::*, window::close_on_esc};
+use bevy_ascii::prelude::*;
+
+// A title screen with the name of the game in the large font with a shadow, and a glowing
+// subtitle in the small one.
+pub fn main() {
+    let mut app = App::new();
+
+    app
+        .add_plugins(DefaultPlugins)
+        .add_plugins(AsciiEffectPluginGroup)
+
+        .add_systems(Startup, init)
+        .add_systems(Update, close_on_esc)
+    ;
+
+    app.run();
+}
+
+fn init(mut commands : Commands) {
+    let camera = commands.spawn((
+        Camera3dBundle::default(),
+        AsciiCamera::default(),
+        AsciiUi::default(),
+        VisibilityBundle::default(),
+    )).id();
+
+    commands.ascii_ui_with_parent(camera)
+        .relative(0, 2, 1.0, 5, AsciiBigText::new("Dungeon")
+            .text_color(AsciiColor::Yellow)
+            .shadow(AsciiColor::Brown)
+            .horizontal_alignment(HorizontalAlignment::Center)
+        )
+        .pop()
+        .relative(0, 9, 1.0, 3, AsciiBigText::new("Press start")
+            .small()
+            .text_color(AsciiColor::LightGrey)
+            .glow(AsciiColor::DarkGrey)
+            .horizontal_alignment(HorizontalAlignment::Center)
+        )
+    ;
+}
@@ -0,0 +1,97 @@
+use bevy::{core_pipeline::prepass::DepthPrepass, prelude::*, window::close_on_esc};
+use bevy_ascii::prelude::*;
+
+// Cubes that circle behind a wall with their health above them. The labels fade out with distance
+// and are hidden while the wall is in front of them.
+pub fn main() {
+    let mut app = App::new();
+
+    app
+        // The depth can only be read back without multisampling.
+        .insert_resource(Msaa::Off)
+        .add_plugins(DefaultPlugins)
+        .add_plugins(AsciiEffectPluginGroup)
+
+        .add_systems(Startup, init)
+        .add_systems(Update, (close_on_esc, circle, count_down))
+    ;
+
+    app.run();
+}
+
+#[derive(Component)]
+struct Circling(f32);
+
+#[derive(Component)]
+struct Health(u32);
+
+fn init(
+    mut commands : Commands,
+    mut meshes : ResMut<Assets<Mesh>>,
+    mut materials : ResMut<Assets<StandardMaterial>>
+) {
+    let camera = commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_xyz(0.0, 2.0, 8.0).looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        },
+        DepthPrepass,
+        AsciiCamera::default(),
+        AsciiUi::default(),
+    )).id();
+
+    commands.spawn(PbrBundle {
+        mesh: meshes.add(Cuboid::new(3.0, 2.0, 0.2)),
+        material: materials.add(Color::GRAY),
+        transform: Transform::from_xyz(0.0, 0.0, 1.5),
+        ..Default::default()
+    });
+
+    let cube = meshes.add(Cuboid::new(0.6, 0.6, 0.6));
+    let material = materials.add(Color::ORANGE_RED);
+    for index in 0..3 {
+        commands.spawn((
+            PbrBundle {
+                mesh: cube.clone(),
+                material: material.clone(),
+                ..Default::default()
+            },
+            Circling(index as f32 * 2.1),
+        )).with_children(|cube| {
+            cube.spawn((
+                SpatialBundle::from_transform(Transform::from_xyz(0.0, 0.6, 0.0)),
+                Health(100),
+                AsciiBillboard::new(camera, "100")
+                    .text_color(AsciiColor::LightRed)
+                    .border(BorderStyle::ROUNDED)
+                    .occluded(0.5)
+                    .fade(8.0, 12.0),
+            ));
+        });
+    }
+
+    commands.spawn(PointLightBundle {
+        transform: Transform::from_xyz(2.0, 4.0, 4.0),
+        ..default()
+    });
+}
+
+fn circle(mut cubes : Query<(&mut Transform, &Circling)>, time : Res<Time>) {
+    for (mut transform, circling) in cubes.iter_mut() {
+        let angle = circling.0 + time.elapsed_seconds() * 0.5;
+        transform.translation = Vec3::new(angle.cos() * 3.5, 0.0, angle.sin() * 3.5);
+    }
+}
+
+fn count_down(mut labels : Query<(&mut Health, &mut AsciiBillboard)>, time : Res<Time>, mut timer : Local<f32>) {
+    *timer += time.delta_seconds();
+    if *timer < 1.0 {
+        return;
+    }
+    *timer = 0.0;
+
+    for (mut health, mut billboard) in labels.iter_mut() {
+        health.0 = if health.0 <= 7 { 100 } else { health.0 - 7 };
+        billboard.set_text(health.0.to_string());
+    }
+}
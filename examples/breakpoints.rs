@@ -0,0 +1,45 @@
+use bevy::{prelude::*, window::close_on_esc};
+use bevy_ascii::prelude::*;
+
+// A menu that is a column on small windows and a row on bigger ones, with a sidebar that is only
+// shown from 60 columns on. Resize the window to see the layout change.
+pub fn main() {
+    let mut app = App::new();
+
+    app
+        .add_plugins(DefaultPlugins)
+        .add_plugins(AsciiEffectPluginGroup)
+
+        .add_systems(Startup, init)
+        .add_systems(Update, close_on_esc)
+    ;
+
+    app.run();
+}
+
+fn init(mut commands : Commands) {
+    let camera = commands.spawn((
+        Camera3dBundle::default(),
+        AsciiCamera::default(),
+        AsciiUi::default(),
+        VisibilityBundle::default(),
+    )).id();
+
+    commands.ascii_ui_with_parent(camera)
+        .left(20, AsciiWindow::new("Sidebar"))
+        .insert(AsciiResponsive::new(Visibility::Hidden).medium(Visibility::Inherited));
+
+    let menu = commands.spawn((
+        AsciiNode::default(),
+        AsciiPosition::bottom(9),
+        AsciiFlex::column(),
+        AsciiResponsive::new(AsciiFlex::column()).medium(AsciiFlex::row().gap(1).align(AsciiFlexAlign::End)),
+        VisibilityBundle::default(),
+    )).set_parent(camera).id();
+
+    let height = AsciiDesiredSize { width: 0, height: 3 };
+    commands.ascii_ui_with_parent(menu)
+        .grow(1, AsciiButton::from_string("New Game")).insert(height).pop()
+        .grow(1, AsciiButton::from_string("Options")).insert(height).pop()
+        .grow(1, AsciiButton::from_string("Quit")).insert(height);
+}
@@ -0,0 +1,60 @@
+use bevy::{pbr::CascadeShadowConfigBuilder, prelude::*, window::close_on_esc};
+use bevy_ascii::prelude::*;
+
+// Colors the scene with assets/shaders/rainbow_cells.wgsl, which is run on every cell after its
+// character was picked.
+pub fn main() {
+    let mut app = App::new();
+
+    app
+        .add_plugins(DefaultPlugins)
+        .add_plugins(AsciiShaderPlugin::default().cell_hook("shaders/rainbow_cells.wgsl"))
+
+        .add_systems(Startup, init)
+        .add_systems(Update, close_on_esc)
+    ;
+
+    app.run();
+}
+
+fn init(
+    mut commands : Commands,
+    mut meshes : ResMut<Assets<Mesh>>,
+    mut materials : ResMut<Assets<StandardMaterial>>
+) {
+    commands.spawn(PbrBundle {
+        mesh: meshes.add(Sphere::new(1.0)),
+        material: materials.add(Color::WHITE),
+        ..Default::default()
+    });
+
+    // light
+    commands.spawn(DirectionalLightBundle {
+            directional_light: DirectionalLight {
+                shadows_enabled: true,
+                ..default()
+            },
+            cascade_shadow_config: CascadeShadowConfigBuilder {
+                num_cascades: 1,
+                maximum_distance: 1.6,
+                ..default()
+            }
+            .into(),
+            ..default()
+        });
+
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_translation(Vec3::new(0.0, 1.5, 5.0)).looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        },
+        AsciiCamera::default(),
+        AsciiUi::default(),
+        VisibilityBundle::default(),
+    ));
+
+    commands.insert_resource(AmbientLight {
+        color: Color::WHITE,
+        brightness: 8000.0,
+    });
+}
@@ -0,0 +1,59 @@
+use bevy::{prelude::*, window::close_on_esc};
+use bevy_ascii::prelude::*;
+
+// The 16 named colors of the palette. C cycles through the color blind filters of the camera.
+pub fn main() {
+    let mut app = App::new();
+
+    app
+        .add_plugins(DefaultPlugins)
+        .add_plugins(AsciiEffectPluginGroup)
+
+        .add_systems(Startup, init)
+        .add_systems(Update, (close_on_esc, cycle_filter, draw))
+    ;
+
+    app.run();
+}
+
+const FILTERS: [ColorBlindFilter; 7] = [
+    ColorBlindFilter::Off,
+    ColorBlindFilter::Simulate(ColorBlindness::Protanopia),
+    ColorBlindFilter::Simulate(ColorBlindness::Deuteranopia),
+    ColorBlindFilter::Simulate(ColorBlindness::Tritanopia),
+    ColorBlindFilter::Compensate(ColorBlindness::Protanopia),
+    ColorBlindFilter::Compensate(ColorBlindness::Deuteranopia),
+    ColorBlindFilter::Compensate(ColorBlindness::Tritanopia),
+];
+
+fn init(mut commands : Commands) {
+    commands.spawn((
+        Camera3dBundle::default(),
+        AsciiCamera::default(),
+        AsciiUi::default(),
+        VisibilityBundle::default(),
+    ));
+}
+
+fn cycle_filter(mut cameras : Query<&mut AsciiCamera>, keys : Res<ButtonInput<KeyCode>>) {
+    if !keys.just_pressed(KeyCode::KeyC) {
+        return;
+    }
+    for mut camera in cameras.iter_mut() {
+        let index = FILTERS.iter().position(|filter| *filter == camera.color_blind_filter).unwrap_or(0);
+        camera.color_blind_filter = FILTERS[(index + 1) % FILTERS.len()];
+    }
+}
+
+fn draw(gizmos : AsciiGizmos, cameras : Query<&AsciiCamera>) {
+    let Ok(camera) = cameras.get_single() else {
+        return;
+    };
+    gizmos.label(IVec2::new(2, 1), &format!("{:?} (C to change)", camera.color_blind_filter), AsciiColor::White);
+
+    for index in 0..16 {
+        let color = AsciiColor::from_index(index);
+        let position = IVec2::new(2 + (index as i32 / 8) * 24, 3 + index as i32 % 8);
+        gizmos.label(position, &format!("######## {:?}", color), color);
+    }
+}
@@ -0,0 +1,66 @@
+use bevy::{
+    diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin},
+    prelude::*,
+    window::close_on_esc,
+};
+use bevy_ascii::prelude::*;
+
+// A dense grid with the characters picked in a compute pass. The character in the middle of the
+// screen is read back from the gpu and logged when space is pressed.
+pub fn main() {
+    let mut app = App::new();
+
+    app
+        .add_plugins(DefaultPlugins)
+        .add_plugins(AsciiShaderPlugin::default().compute_cells())
+        .add_plugins(AsciiSceneGridPlugin::default())
+        .add_plugins((FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin::default()))
+
+        .add_systems(Startup, init)
+        .add_systems(Update, (close_on_esc, log_center_cell))
+    ;
+
+    app.run();
+}
+
+fn init(
+    mut commands : Commands,
+    mut meshes : ResMut<Assets<Mesh>>,
+    mut materials : ResMut<Assets<StandardMaterial>>
+) {
+    commands.spawn(PbrBundle {
+        mesh: meshes.add(Torus::default()),
+        material: materials.add(Color::ORANGE),
+        transform: Transform::from_rotation(Quat::from_rotation_x(1.0)),
+        ..Default::default()
+    });
+
+    commands.spawn(PointLightBundle {
+        transform: Transform::from_xyz(2.0, 4.0, 4.0),
+        ..default()
+    });
+
+    let mut settings = AsciiCamera::default();
+    settings.sizing = GridSizing::CellPixels(4);
+
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_translation(Vec3::new(0.0, 0.0, 3.0)).looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        },
+        settings,
+        AsciiSceneGridSource,
+        VisibilityBundle::default(),
+    ));
+}
+
+fn log_center_cell(grid : Res<AsciiSceneGrid>, keys : Res<ButtonInput<KeyCode>>) {
+    if !keys.just_pressed(KeyCode::Space) || grid.is_empty() {
+        return;
+    }
+
+    let (x, y) = (grid.width() as i32 / 2, grid.height() as i32 / 2);
+    if let Some(cell) = grid.get(x, y) {
+        info!("{}x{} grid, the middle cell is {:?} in {:?}", grid.width(), grid.height(), cell.character, cell.color);
+    }
+}
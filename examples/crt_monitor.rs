@@ -0,0 +1,126 @@
+use bevy::{
+    prelude::*,
+    render::{
+        camera::RenderTarget,
+        render_resource::{Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages},
+        view::RenderLayers,
+    },
+    window::close_on_esc,
+};
+use bevy_ascii::prelude::*;
+
+// An ascii camera that renders into an image instead of the window. The image is the screen of a
+// monitor in a normal 3d scene, so the ascii frame is drawn on the monitor, and its ui with it.
+pub fn main() {
+    let mut app = App::new();
+
+    app
+        .add_plugins(DefaultPlugins)
+        .add_plugins(AsciiEffectPluginGroup)
+
+        .add_systems(Startup, init)
+        .add_systems(Update, (close_on_esc, spin))
+    ;
+
+    app.run();
+}
+
+#[derive(Component)]
+struct Spinning;
+
+// What the ascii camera sees is kept apart from the room the monitor stands in.
+const SCREEN_LAYER: u8 = 1;
+
+fn init(
+    mut commands : Commands,
+    mut meshes : ResMut<Assets<Mesh>>,
+    mut materials : ResMut<Assets<StandardMaterial>>,
+    mut images : ResMut<Assets<Image>>,
+) {
+    let size = Extent3d {
+        width: 640,
+        height: 480,
+        ..default()
+    };
+    let mut screen = Image {
+        texture_descriptor: TextureDescriptor {
+            label: Some("crt_screen"),
+            size,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Bgra8UnormSrgb,
+            mip_level_count: 1,
+            sample_count: 1,
+            usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST | TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        },
+        ..default()
+    };
+    screen.resize(size);
+    let screen = images.add(screen);
+
+    // The scene on the monitor.
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(Cuboid::new(1.0, 1.0, 1.0)),
+            material: materials.add(Color::ORANGE_RED),
+            ..default()
+        },
+        Spinning,
+        RenderLayers::layer(SCREEN_LAYER),
+    ));
+
+    let ascii_camera = commands.spawn((
+        Camera3dBundle {
+            camera: Camera {
+                order: -1,
+                target: RenderTarget::Image(screen.clone()),
+                clear_color: ClearColorConfig::Custom(Color::BLACK),
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, 1.5, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        },
+        AsciiCamera::default(),
+        AsciiUi::default(),
+        VisibilityBundle::default(),
+        RenderLayers::layer(SCREEN_LAYER),
+    )).id();
+
+    commands.ascii_ui_with_parent(ascii_camera)
+        .bottom(1, AsciiRichText::new("READY."))
+    ;
+
+    // The monitor, with the image as its screen.
+    commands.spawn(PbrBundle {
+        mesh: meshes.add(Cuboid::new(1.9, 1.5, 1.2)),
+        material: materials.add(Color::rgb(0.8, 0.78, 0.7)),
+        transform: Transform::from_xyz(0.0, 0.0, -0.61),
+        ..default()
+    });
+    commands.spawn(PbrBundle {
+        mesh: meshes.add(Rectangle::new(1.6, 1.2)),
+        material: materials.add(StandardMaterial {
+            base_color_texture: Some(screen),
+            unlit: true,
+            ..default()
+        }),
+        ..default()
+    });
+
+    commands.spawn(PointLightBundle {
+        transform: Transform::from_xyz(2.0, 3.0, 4.0),
+        ..default()
+    });
+
+    commands.spawn(Camera3dBundle {
+        transform: Transform::from_xyz(1.2, 0.6, 2.8).looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+}
+
+fn spin(mut cubes : Query<&mut Transform, With<Spinning>>, time : Res<Time>) {
+    for mut transform in cubes.iter_mut() {
+        transform.rotate_y(time.delta_seconds());
+        transform.rotate_x(time.delta_seconds() * 0.5);
+    }
+}
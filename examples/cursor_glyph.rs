@@ -0,0 +1,31 @@
+use bevy::{prelude::*, window::close_on_esc};
+use bevy_ascii::prelude::*;
+
+// The mouse cursor is drawn as a yellow block on the grid, in place of the cursor of the system.
+// It lines up with the cell the buttons are hovered from.
+pub fn main() {
+    let mut app = App::new();
+
+    app
+        .add_plugins(DefaultPlugins)
+        .add_plugins(AsciiEffectPluginGroup)
+
+        .add_systems(Startup, init)
+        .add_systems(Update, close_on_esc)
+    ;
+
+    app.run();
+}
+
+fn init(mut commands : Commands) {
+    let camera = commands.spawn((
+        Camera3dBundle::default(),
+        AsciiCamera::default(),
+        AsciiUi::default(),
+        AsciiCursorGlyph::block(AsciiColor::Yellow),
+        VisibilityBundle::default(),
+    )).id();
+
+    commands.ascii_ui_with_parent(camera)
+        .centered(Value::Auto, Value::Auto, AsciiButton::from_string("Hover me"));
+}
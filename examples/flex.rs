@@ -0,0 +1,34 @@
+use bevy::{prelude::*, window::close_on_esc};
+use bevy_ascii::prelude::*;
+
+// Three buttons that share the bottom of the screen equally, with a cell between them. They are
+// three rows tall, the rest of the height is left to the scene.
+pub fn main() {
+    let mut app = App::new();
+
+    app
+        .add_plugins(DefaultPlugins)
+        .add_plugins(AsciiEffectPluginGroup)
+
+        .add_systems(Startup, init)
+        .add_systems(Update, close_on_esc)
+    ;
+
+    app.run();
+}
+
+fn init(mut commands : Commands) {
+    let camera = commands.spawn((
+        Camera3dBundle::default(),
+        AsciiCamera::default(),
+        AsciiUi::default(),
+        AsciiFlex::row().gap(1).align(AsciiFlexAlign::End),
+        VisibilityBundle::default(),
+    )).id();
+
+    let height = AsciiDesiredSize { width: 0, height: 3 };
+    commands.ascii_ui_with_parent(camera)
+        .grow(1, AsciiButton::from_string("New Game")).insert(height).pop()
+        .grow(1, AsciiButton::from_string("Options")).insert(height).pop()
+        .grow(1, AsciiButton::from_string("Quit")).insert(height);
+}
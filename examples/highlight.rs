@@ -0,0 +1,89 @@
+use bevy::{prelude::*, window::close_on_esc};
+use bevy_ascii::prelude::*;
+
+// A row of shapes where the selected one is outlined. Left and right move the selection.
+pub fn main() {
+    let mut app = App::new();
+
+    app
+        .add_plugins(DefaultPlugins)
+        .add_plugins(AsciiShaderPlugin::default())
+        .init_resource::<Selected>()
+
+        .add_systems(Startup, init)
+        .add_systems(Update, (close_on_esc, move_selection))
+    ;
+
+    app.run();
+}
+
+#[derive(Resource, Default)]
+struct Selected(usize);
+
+#[derive(Component)]
+struct Selectable(usize);
+
+fn init(
+    mut commands : Commands,
+    mut meshes : ResMut<Assets<Mesh>>,
+    mut materials : ResMut<Assets<StandardMaterial>>
+) {
+    let shapes = [
+        meshes.add(Cuboid::new(0.8, 0.8, 0.8)),
+        meshes.add(Sphere::new(0.5)),
+        meshes.add(Torus::new(0.2, 0.45)),
+    ];
+    let material = materials.add(Color::GRAY);
+
+    for (index, shape) in shapes.into_iter().enumerate() {
+        commands.spawn((
+            PbrBundle {
+                mesh: shape,
+                material: material.clone(),
+                transform: Transform::from_xyz(index as f32 * 1.8 - 1.8, 0.0, 0.0),
+                ..Default::default()
+            },
+            Selectable(index),
+        ));
+    }
+
+    commands.spawn(PointLightBundle {
+        transform: Transform::from_xyz(2.0, 4.0, 4.0),
+        ..default()
+    });
+
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_translation(Vec3::new(0.0, 1.0, 5.0)).looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        },
+        AsciiCamera::default(),
+    ));
+}
+
+fn move_selection(
+    mut commands : Commands,
+    mut selected : ResMut<Selected>,
+    selectables : Query<(Entity, &Selectable)>,
+    keys : Res<ButtonInput<KeyCode>>,
+) {
+    let count = selectables.iter().count();
+    if count == 0 {
+        return;
+    }
+    if keys.just_pressed(KeyCode::ArrowRight) {
+        selected.0 = (selected.0 + 1) % count;
+    } else if keys.just_pressed(KeyCode::ArrowLeft) {
+        selected.0 = (selected.0 + count - 1) % count;
+    } else if !selected.is_added() {
+        return;
+    }
+
+    for (entity, selectable) in selectables.iter() {
+        if selectable.0 == selected.0 {
+            commands.entity(entity).insert(AsciiHighlight::new(AsciiColor::Yellow).border(BorderStyle::ROUNDED));
+        } else {
+            commands.entity(entity).remove::<AsciiHighlight>();
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use bevy::{prelude::*, window::close_on_esc};
+use bevy_ascii::prelude::*;
+
+// A list of ten thousand items. Only the rows in view are drawn, scroll with the mouse wheel or
+// click the list and use the arrow keys, page up and down, home and end.
+pub fn main() {
+    let mut app = App::new();
+
+    app
+        .add_plugins(DefaultPlugins)
+        .add_plugins(AsciiEffectPluginGroup)
+
+        .add_systems(Startup, init)
+        .add_systems(Update, (close_on_esc, log_selection))
+    ;
+
+    app.run();
+}
+
+fn init(mut commands : Commands) {
+    let camera = commands.spawn((
+        Camera3dBundle::default(),
+        AsciiCamera::default(),
+        AsciiUi::default(),
+        VisibilityBundle::default(),
+    )).id();
+
+    let items = (0..10_000).map(|i| format!("Item #{:05}  weight {:>3}", i, (i * 37) % 250));
+    commands.ascii_ui_with_parent(camera)
+        .centered(40, 20, AsciiList::new(items).selected(0))
+    ;
+}
+
+fn log_selection(
+    mut selected : EventReader<AsciiListSelectedEvent>,
+    mut submitted : EventReader<AsciiListSubmittedEvent>,
+    lists : Query<&AsciiList>,
+) {
+    for event in selected.read() {
+        if let Some(item) = lists.get(event.entity).ok().and_then(|list| list.selected_item()) {
+            info!("selected {}", item);
+        }
+    }
+    for event in submitted.read() {
+        info!("submitted row {}", event.index);
+    }
+}
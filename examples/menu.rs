@@ -0,0 +1,36 @@
+use bevy::{app::AppExit, prelude::*};
+use bevy_ascii::prelude::*;
+
+// A menu that reacts to its buttons with callbacks instead of a system reading click events.
+pub fn main() {
+    let mut app = App::new();
+
+    app
+        .add_plugins(DefaultPlugins)
+        .add_plugins(AsciiEffectPluginGroup)
+
+        .add_systems(Startup, init)
+    ;
+
+    app.run();
+}
+
+fn init(mut commands : Commands) {
+    let camera = commands.spawn((
+        Camera3dBundle::default(),
+        AsciiCamera::default(),
+        AsciiUi::default(),
+        VisibilityBundle::default(),
+    )).id();
+
+    commands.ascii_ui_with_parent(camera)
+        .top(0.5, AsciiButton::from_string("Say hello"))
+        .on_click(|_| info!("hello"))
+        .pop()
+        .bottom(0.5, AsciiButton::from_string("Quit"))
+        .insert(AsciiHotkey(KeyCode::KeyQ))
+        .on_click(|commands| commands.add(|world: &mut World| {
+            world.send_event(AppExit);
+        }))
+    ;
+}
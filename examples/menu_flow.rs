@@ -0,0 +1,94 @@
+use bevy::{app::AppExit, prelude::*, window::close_on_esc};
+use bevy_ascii::prelude::*;
+
+// The screens of a whole game: a main menu, an options screen and the game itself, which opens a
+// pause menu on escape. Every screen follows the state, and the buttons only change the state.
+pub fn main() {
+    let mut app = App::new();
+
+    app
+        .add_plugins(DefaultPlugins)
+        .add_plugins(AsciiEffectPluginGroup)
+        .add_plugins(AsciiScreenPlugin::<GameState>::default())
+        .init_state::<GameState>()
+
+        .add_systems(Startup, init)
+        .add_systems(Update, (
+            close_on_esc.run_if(in_state(GameState::MainMenu)),
+            pause.run_if(in_state(GameState::Playing).or_else(in_state(GameState::Paused))),
+        ))
+    ;
+
+    app.run();
+}
+
+#[derive(States, Default, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+enum GameState {
+    #[default]
+    MainMenu,
+    Options,
+    Playing,
+    Paused,
+}
+
+fn go_to(state : GameState) -> impl Fn(&mut Commands) + Send + Sync + 'static {
+    move |commands| commands.add(move |world: &mut World| {
+        world.resource_mut::<NextState<GameState>>().set(state);
+    })
+}
+
+fn init(mut commands : Commands) {
+    let camera = commands.spawn((
+        Camera3dBundle::default(),
+        AsciiCamera::default(),
+        AsciiUi::default(),
+        VisibilityBundle::default(),
+    )).id();
+
+    commands.ascii_ui_with_parent(camera)
+        .centered(30, 11, AsciiWindow::new("Main Menu"))
+        .insert((
+            AsciiScreen::new(GameState::MainMenu).transition(AsciiTransitionEffect::Curtain, 0.5),
+            AsciiFlex::column().gap(1),
+        ))
+            .flex(1, 1, 1, AsciiButton::from_string("Play")).on_click(go_to(GameState::Playing)).pop()
+            .flex(1, 1, 1, AsciiButton::from_string("Options")).on_click(go_to(GameState::Options)).pop()
+            .flex(1, 1, 1, AsciiButton::from_string("Quit")).on_click(|commands| commands.add(|world: &mut World| {
+                world.send_event(AppExit);
+            })).pop()
+        .pop()
+        .centered(36, 11, AsciiWindow::new("Options"))
+        .insert((
+            AsciiScreen::new(GameState::Options).transition(AsciiTransitionEffect::Dissolve, 0.4),
+            AsciiFlex::column().gap(1),
+        ))
+            .flex(1, 0, 1, AsciiToggle::new(true).label("Music")).pop()
+            .flex(1, 0, 1, AsciiSlider::new(0.8, 0.0, 1.0).step(0.1)).pop()
+            .flex(3, 0, 1, AsciiButton::from_string("Back")).on_click(go_to(GameState::MainMenu)).pop()
+        .pop()
+        .centered(24, 9, AsciiWindow::new("Paused"))
+        .insert((
+            AsciiScreen::new(GameState::Paused),
+            AsciiFlex::column().gap(1),
+        ))
+            .flex(1, 1, 1, AsciiButton::from_string("Resume")).on_click(go_to(GameState::Playing)).pop()
+            .flex(1, 1, 1, AsciiButton::from_string("Main Menu")).on_click(go_to(GameState::MainMenu)).pop()
+        .pop()
+        .padded(2, AsciiWindow::new("Game"))
+        .insert(AsciiScreen::new(GameState::Playing).transition(AsciiTransitionEffect::Dissolve, 0.5))
+            .fill(AsciiRichText::new("Escape opens the pause menu."))
+    ;
+}
+
+fn pause(
+    keys : Res<ButtonInput<KeyCode>>,
+    state : Res<State<GameState>>,
+    mut next : ResMut<NextState<GameState>>,
+) {
+    if keys.just_pressed(KeyCode::Escape) {
+        next.set(match state.get() {
+            GameState::Paused => GameState::Playing,
+            _ => GameState::Paused,
+        });
+    }
+}
@@ -0,0 +1,41 @@
+use bevy::{prelude::*, window::close_on_esc};
+use bevy_ascii::prelude::*;
+
+// Everything is drawn with the names of `assets/palettes/dungeon.palette`. Run with the
+// `bevy/file_watcher` feature to see changes to the file without restarting.
+pub fn main() {
+    let mut app = App::new();
+
+    app
+        .add_plugins(DefaultPlugins)
+        .add_plugins(AsciiEffectPluginGroup)
+
+        .add_systems(Startup, init)
+        .add_systems(Update, (close_on_esc, draw))
+    ;
+
+    app.run();
+}
+
+// Keeps the palette loaded. Its names are drawn violet until it is.
+#[derive(Resource)]
+#[allow(dead_code)]
+struct DungeonPalette(Handle<AsciiPalette>);
+
+fn init(mut commands : Commands, asset_server : Res<AssetServer>) {
+    commands.insert_resource(DungeonPalette(asset_server.load("palettes/dungeon.palette")));
+
+    commands.spawn((
+        Camera3dBundle::default(),
+        AsciiCamera::default(),
+        AsciiUi::default().with_true_color(),
+        VisibilityBundle::default(),
+    ));
+}
+
+fn draw(gizmos : AsciiGizmos) {
+    gizmos.rect(IVec2::new(2, 1), UVec2::new(24, 7), AsciiColor::named("stone"));
+    gizmos.label(IVec2::new(4, 3), "The walls are wet", AsciiColor::named("moss"));
+    gizmos.label(IVec2::new(4, 4), "with blood", AsciiColor::named("blood_red"));
+    gizmos.label(IVec2::new(4, 5), "A torch flickers", AsciiColor::named("torch"));
+}
@@ -0,0 +1,40 @@
+use bevy::{prelude::*, window::close_on_esc};
+use bevy_ascii::prelude::*;
+
+// A line of dialogue with links in it. Clicking a link logs what it points to.
+pub fn main() {
+    let mut app = App::new();
+
+    app
+        .add_plugins(DefaultPlugins)
+        .add_plugins(AsciiEffectPluginGroup)
+
+        .add_systems(Startup, init)
+        .add_systems(Update, (close_on_esc, log_links))
+    ;
+
+    app.run();
+}
+
+fn init(mut commands : Commands) {
+    let camera = commands.spawn((
+        Camera3dBundle::default(),
+        AsciiCamera::default(),
+        AsciiUi::default(),
+        VisibilityBundle::default(),
+    )).id();
+
+    commands.ascii_ui_with_parent(camera)
+        .relative(2, 2, 40, 10, AsciiWindow::new("Old Man"))
+        .relative(0, 0, 1.0, 1.0, AsciiRichText::new(
+            "It is dangerous to go alone. Find the {link=item_sword}sword{/link} in the \
+            {link=place_cave}cave to the east{/link}, then ask about the {link=quest_42}lost crown{/link}."
+        ))
+    ;
+}
+
+fn log_links(mut clicks : EventReader<AsciiTextSpanClicked>) {
+    for click in clicks.read() {
+        info!("Clicked on {}", click.link);
+    }
+}
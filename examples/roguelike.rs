@@ -0,0 +1,160 @@
+use bevy::{prelude::*, window::close_on_esc};
+use bevy_ascii::prelude::*;
+
+// A dungeon crawl with a side panel, the way most roguelikes lay out their screen. Walk with the
+// arrow keys, pick up gold and keep away from the traps. What happens is logged under the stats.
+pub fn main() {
+    let mut app = App::new();
+
+    app
+        .add_plugins(DefaultPlugins)
+        .add_plugins(AsciiEffectPluginGroup)
+
+        .insert_resource(Hero { position: IVec2::new(2, 2), health: 20 })
+        .add_systems(Startup, init)
+        .add_systems(Update, (close_on_esc, walk))
+    ;
+
+    app.run();
+}
+
+#[derive(Resource)]
+struct Hero {
+    position: IVec2,
+    health: i32,
+}
+
+#[derive(Component)]
+struct Health;
+
+#[derive(Component)]
+struct Gold;
+
+#[derive(Component)]
+struct Log;
+
+const WIDTH: i32 = 80;
+const HEIGHT: i32 = 40;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Cell {
+    Wall,
+    Floor,
+    Gold,
+    Trap,
+}
+
+fn cell(x: i32, y: i32) -> Cell {
+    let is_edge = x == 0 || y == 0 || x == WIDTH - 1 || y == HEIGHT - 1;
+    // Rooms of 16 by 10 cells, with a door in the middle of every wall.
+    let is_wall = (x % 16 == 0 && y % 10 != 5) || (y % 10 == 0 && x % 16 != 8);
+    let hash = (x * 73_856_093) ^ (y * 19_349_663);
+    match () {
+        _ if is_edge || is_wall => Cell::Wall,
+        _ if hash % 37 == 0 => Cell::Gold,
+        _ if hash % 53 == 0 => Cell::Trap,
+        _ => Cell::Floor,
+    }
+}
+
+// Gold that was picked up leaves the floor behind.
+fn cell_at(position: IVec2, picked_up: &[IVec2]) -> Cell {
+    match cell(position.x, position.y) {
+        Cell::Gold if picked_up.contains(&position) => Cell::Floor,
+        cell => cell,
+    }
+}
+
+fn tile(cell: Cell) -> AsciiTile {
+    match cell {
+        Cell::Wall => AsciiTile::new('#', AsciiColor::LightGrey, AsciiColor::DarkGrey),
+        Cell::Floor => AsciiTile::new('.', AsciiColor::Grey, AsciiColor::Black),
+        Cell::Gold => AsciiTile::new('$', AsciiColor::Yellow, AsciiColor::Black),
+        Cell::Trap => AsciiTile::new('^', AsciiColor::LightRed, AsciiColor::Black),
+    }
+}
+
+fn hero_tile() -> AsciiTile {
+    AsciiTile::new('@', AsciiColor::White, AsciiColor::Black)
+}
+
+fn init(mut commands : Commands, hero : Res<Hero>) {
+    let camera = commands.spawn((
+        Camera3dBundle::default(),
+        AsciiCamera::default(),
+        AsciiUi::default(),
+        AsciiFlex::row(),
+        VisibilityBundle::default(),
+    )).id();
+
+    let mut map = AsciiTilemap::new(WIDTH as u32, HEIGHT as u32);
+    for y in 0..HEIGHT {
+        for x in 0..WIDTH {
+            map.set(x, y, tile(cell(x, y)));
+        }
+    }
+    map.set(hero.position.x, hero.position.y, hero_tile());
+
+    commands.ascii_ui_with_parent(camera)
+        .grow(1, map.follow(hero.position)).pop()
+        .flex(26, 0, 0, AsciiWindow::new("Hero")).insert(AsciiFlex::column())
+            .flex(1, 0, 0, AsciiNumber::new(hero.health).prefix("HP   ").text_color(AsciiColor::LightRed)).insert(Health).pop()
+            .flex(1, 0, 0, AsciiNumber::new(0).prefix("Gold ").count_duration(0.4).text_color(AsciiColor::Yellow)).insert(Gold).pop()
+            .grow(1, AsciiConsole::new(100)).insert(Log)
+    ;
+}
+
+#[allow(clippy::too_many_arguments)]
+fn walk(
+    keys : Res<ButtonInput<KeyCode>>,
+    mut hero : ResMut<Hero>,
+    mut maps : Query<&mut AsciiTilemap>,
+    mut health : Query<&mut AsciiNumber, (With<Health>, Without<Gold>)>,
+    mut gold : Query<&mut AsciiNumber, (With<Gold>, Without<Health>)>,
+    mut log : Query<&mut AsciiConsole, With<Log>>,
+    mut picked_up : Local<Vec<IVec2>>,
+) {
+    let step = if keys.just_pressed(KeyCode::ArrowUp) {
+        IVec2::NEG_Y
+    } else if keys.just_pressed(KeyCode::ArrowDown) {
+        IVec2::Y
+    } else if keys.just_pressed(KeyCode::ArrowLeft) {
+        IVec2::NEG_X
+    } else if keys.just_pressed(KeyCode::ArrowRight) {
+        IVec2::X
+    } else {
+        return;
+    };
+    if hero.health <= 0 {
+        return;
+    }
+
+    let from = hero.position;
+    let to = from + step;
+    let Ok(mut log) = log.get_single_mut() else { return };
+    match cell_at(to, &picked_up) {
+        Cell::Wall => return,
+        Cell::Gold => {
+            let amount = 5 + (to.x + to.y) % 20;
+            log.push_line(&format!("You find {} gold.", amount), AsciiColor::Yellow);
+            gold.iter_mut().for_each(|mut gold| gold.add(amount));
+            picked_up.push(to);
+        }
+        Cell::Trap => {
+            hero.health -= 3;
+            log.push_line("A dart hits you!", AsciiColor::LightRed);
+            health.iter_mut().for_each(|mut health| health.set_value(hero.health.max(0)));
+            if hero.health <= 0 {
+                log.push_line("You die...", AsciiColor::Red);
+            }
+        }
+        Cell::Floor => {}
+    }
+
+    for mut map in maps.iter_mut() {
+        map.set(from.x, from.y, tile(cell_at(from, &picked_up)));
+        map.set(to.x, to.y, hero_tile());
+        map.set_follow(Some(to));
+    }
+    hero.position = to;
+}
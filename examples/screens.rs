@@ -0,0 +1,70 @@
+use bevy::{prelude::*, window::close_on_esc};
+use bevy_ascii::prelude::*;
+
+// A menu that is spawned once and a game screen that is spawned every time the game starts.
+// Space switches between them, the screens follow the state on their own.
+pub fn main() {
+    let mut app = App::new();
+
+    app
+        .add_plugins(DefaultPlugins)
+        .add_plugins(AsciiEffectPluginGroup)
+        .add_plugins(AsciiScreenPlugin::<GameState>::default())
+        .init_state::<GameState>()
+
+        .add_systems(Startup, init)
+        .add_systems(OnEnter(GameState::Playing), spawn_game)
+        .add_systems(Update, (close_on_esc, switch_state))
+    ;
+
+    app.run();
+}
+
+#[derive(States, Default, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+enum GameState {
+    #[default]
+    Menu,
+    Playing,
+}
+
+#[derive(Resource)]
+struct UiCamera(Entity);
+
+fn init(mut commands : Commands) {
+    let camera = commands.spawn((
+        Camera3dBundle::default(),
+        AsciiCamera::default(),
+        AsciiUi::default(),
+        VisibilityBundle::default(),
+    )).id();
+    commands.insert_resource(UiCamera(camera));
+
+    commands.ascii_ui_with_parent(camera)
+        .relative(2, 2, 30, 8, AsciiWindow::new("Main Menu"))
+        .insert(AsciiScreen::new(GameState::Menu).transition(AsciiTransitionEffect::Curtain, 0.6))
+    ;
+}
+
+fn spawn_game(mut commands : Commands, camera : Res<UiCamera>) {
+    commands.ascii_ui_with_parent(camera.0)
+        .relative(2, 2, 40, 12, AsciiWindow::new("Game"))
+        .insert(
+            AsciiScreen::new(GameState::Playing)
+                .despawn_on_exit()
+                .transition(AsciiTransitionEffect::Dissolve, 0.6)
+        )
+    ;
+}
+
+fn switch_state(
+    keys : Res<ButtonInput<KeyCode>>,
+    state : Res<State<GameState>>,
+    mut next : ResMut<NextState<GameState>>,
+) {
+    if keys.just_pressed(KeyCode::Space) {
+        next.set(match state.get() {
+            GameState::Menu => GameState::Playing,
+            GameState::Playing => GameState::Menu,
+        });
+    }
+}
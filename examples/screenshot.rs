@@ -0,0 +1,60 @@
+use bevy::{prelude::*, window::close_on_esc};
+use bevy_ascii::prelude::*;
+
+// A spinning cube. F12 saves the ascii frame to screenshot.png.
+pub fn main() {
+    let mut app = App::new();
+
+    app
+        .add_plugins(DefaultPlugins)
+        .add_plugins(AsciiShaderPlugin::default())
+
+        .add_systems(Startup, init)
+        .add_systems(Update, (close_on_esc, spin, take_screenshot))
+    ;
+
+    app.run();
+}
+
+#[derive(Component)]
+struct Spinning;
+
+fn init(
+    mut commands : Commands,
+    mut meshes : ResMut<Assets<Mesh>>,
+    mut materials : ResMut<Assets<StandardMaterial>>
+) {
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(Cuboid::new(1.0, 1.0, 1.0)),
+            material: materials.add(Color::ORANGE_RED),
+            ..Default::default()
+        },
+        Spinning,
+    ));
+
+    commands.spawn(PointLightBundle {
+        transform: Transform::from_xyz(2.0, 4.0, 4.0),
+        ..default()
+    });
+
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_xyz(0.0, 1.5, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        },
+        AsciiCamera::default(),
+    ));
+}
+
+fn spin(mut cubes : Query<&mut Transform, With<Spinning>>, time : Res<Time>) {
+    for mut transform in cubes.iter_mut() {
+        transform.rotate_y(time.delta_seconds());
+    }
+}
+
+fn take_screenshot(keys : Res<ButtonInput<KeyCode>>, mut screenshots : EventWriter<AsciiScreenshotEvent>) {
+    if keys.just_pressed(KeyCode::F12) {
+        screenshots.send(AsciiScreenshotEvent::new("screenshot.png"));
+    }
+}
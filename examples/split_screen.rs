@@ -0,0 +1,120 @@
+use bevy::{
+    pbr::CascadeShadowConfigBuilder, prelude::*, render::camera::{ClearColorConfig, Viewport},
+    window::{close_on_esc, WindowResized},
+};
+use bevy_ascii::prelude::*;
+
+pub fn main() {
+    let mut app = App::new();
+
+    app
+        .add_plugins(DefaultPlugins)
+        .add_plugins(AsciiEffectPluginGroup)
+
+        .add_systems(Startup, init)
+        .add_systems(Update, (set_camera_viewports, close_on_esc))
+    ;
+
+    app.run();
+}
+
+#[derive(Component)]
+struct Player(u32);
+
+fn init(
+    mut commands : Commands,
+    mut meshes : ResMut<Assets<Mesh>>,
+    mut materials : ResMut<Assets<StandardMaterial>>
+) {
+    let mesh = meshes.add(Cuboid::new(1.0, 1.0, 1.0));
+    let red_material = materials.add(Color::RED);
+
+    commands.spawn(PbrBundle {
+        mesh,
+        material: red_material,
+        ..Default::default()
+    });
+
+    // light
+    commands.spawn(DirectionalLightBundle {
+            directional_light: DirectionalLight {
+                shadows_enabled: true,
+                ..default()
+            },
+            cascade_shadow_config: CascadeShadowConfigBuilder {
+                num_cascades: 1,
+                maximum_distance: 1.6,
+                ..default()
+            }
+            .into(),
+            ..default()
+        });
+
+    // The left player sees a bright, coarse grid.
+    let mut left_settings = AsciiCamera::default();
+    left_settings.sizing = GridSizing::Columns(60.0);
+    left_settings.exposure = 1.5;
+
+    let left = commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_translation(Vec3::new(-2.0, 1.5, 5.0)).looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        },
+        left_settings,
+        AsciiUi::default(),
+        VisibilityBundle::default(),
+        Player(0),
+    )).id();
+
+    // The right player sees an inverted, finer grid. It draws after the left camera and must not
+    // clear the left half of the window.
+    let mut right_settings = AsciiCamera::default();
+    right_settings.sizing = GridSizing::Columns(90.0);
+    right_settings.invert = true;
+
+    let right = commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_translation(Vec3::new(2.0, 1.5, 5.0)).looking_at(Vec3::ZERO, Vec3::Y),
+            camera: Camera {
+                order: 1,
+                clear_color: ClearColorConfig::None,
+                ..default()
+            },
+            ..default()
+        },
+        right_settings,
+        AsciiUi::default(),
+        VisibilityBundle::default(),
+        Player(1),
+    )).id();
+
+    // Ui spawned under a camera is only drawn on that camera.
+    commands.ascii_ui_with_parent(left)
+        .bottom(3, AsciiButton::from_string("Player 1"));
+
+    commands.ascii_ui_with_parent(right)
+        .bottom(3, AsciiButton::from_string("Player 2"));
+
+    commands.insert_resource(AmbientLight {
+        color: Color::WHITE,
+        brightness: 8000.0,
+    });
+}
+
+fn set_camera_viewports(
+    windows: Query<&Window>,
+    mut resize_events: EventReader<WindowResized>,
+    mut cameras: Query<(&mut Camera, &Player)>,
+) {
+    for resize_event in resize_events.read() {
+        let Ok(window) = windows.get(resize_event.window) else { continue };
+        let half_width = window.physical_width() / 2;
+        for (mut camera, player) in cameras.iter_mut() {
+            camera.viewport = Some(Viewport {
+                physical_position: UVec2::new(player.0 * half_width, 0),
+                physical_size: UVec2::new(half_width, window.physical_height()),
+                ..default()
+            });
+        }
+    }
+}
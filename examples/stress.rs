@@ -0,0 +1,75 @@
+use bevy::{
+    diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin},
+    prelude::*,
+    window::close_on_esc,
+};
+use bevy_ascii::prelude::*;
+
+// Spawns thousands of ui nodes and logs the frame time. Pass the number of nodes as the first
+// argument, and press space to switch between redrawing the ui every frame and only when dirty.
+pub fn main() {
+    let mut app = App::new();
+
+    let count = std::env::args()
+        .nth(1)
+        .and_then(|count| count.parse().ok())
+        .unwrap_or(5000);
+
+    app
+        .add_plugins(DefaultPlugins)
+        .add_plugins(AsciiEffectPluginGroup)
+        .add_plugins((FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin::default()))
+
+        .insert_resource(StressSettings { count, redraw_every_frame: false })
+        .add_systems(Startup, init)
+        .add_systems(Update, (toggle_redraw, redraw, close_on_esc))
+    ;
+
+    app.run();
+}
+
+#[derive(Resource)]
+struct StressSettings {
+    count: u32,
+    redraw_every_frame: bool,
+}
+
+fn init(
+    mut commands : Commands,
+    settings : Res<StressSettings>,
+) {
+    let camera = commands.spawn((
+        Camera3dBundle::default(),
+        AsciiCamera::default(),
+        AsciiUi::default(),
+        VisibilityBundle::default(),
+    )).id();
+
+    let mut ui = commands.ascii_ui_with_parent(camera);
+    for i in 0..settings.count as i32 {
+        let x = (i * 8) % 160;
+        let y = (i * 8) / 160 * 3 % 90;
+        ui.relative(x, y, 8, 3, AsciiButton::from_string(&i.to_string())).pop();
+    }
+
+    info!("Spawned {} ui nodes", settings.count);
+}
+
+fn toggle_redraw(
+    mut settings : ResMut<StressSettings>,
+    keys : Res<ButtonInput<KeyCode>>,
+) {
+    if keys.just_pressed(KeyCode::Space) {
+        settings.redraw_every_frame = !settings.redraw_every_frame;
+        info!("Redraw every frame: {}", settings.redraw_every_frame);
+    }
+}
+
+fn redraw(
+    settings : Res<StressSettings>,
+    mut dirty : EventWriter<AsciiMarkDirtyEvent>,
+) {
+    if settings.redraw_every_frame {
+        dirty.send(AsciiMarkDirtyEvent);
+    }
+}
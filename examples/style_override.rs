@@ -0,0 +1,74 @@
+use bevy::{prelude::*, window::close_on_esc};
+use bevy_ascii::prelude::*;
+
+// A row of grey cubes with one spinning pickup that is always drawn in yellow dollar signs, so it
+// stands out from the rest of the scene.
+pub fn main() {
+    let mut app = App::new();
+
+    app
+        .add_plugins(DefaultPlugins)
+        .add_plugins(AsciiShaderPlugin::default())
+
+        .add_systems(Startup, init)
+        .add_systems(Update, (close_on_esc, spin_pickups))
+    ;
+
+    app.run();
+}
+
+#[derive(Component)]
+struct Pickup;
+
+fn init(
+    mut commands : Commands,
+    mut meshes : ResMut<Assets<Mesh>>,
+    mut materials : ResMut<Assets<StandardMaterial>>
+) {
+    let cube = meshes.add(Cuboid::new(0.6, 0.6, 0.6));
+    let grey = materials.add(Color::GRAY);
+
+    for x in -3..=3 {
+        if x == 0 {
+            continue;
+        }
+        commands.spawn(PbrBundle {
+            mesh: cube.clone(),
+            material: grey.clone(),
+            transform: Transform::from_xyz(x as f32, 0.0, 0.0),
+            ..Default::default()
+        });
+    }
+
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(Torus::new(0.15, 0.35)),
+            material: grey.clone(),
+            ..Default::default()
+        },
+        Pickup,
+        AsciiStyleOverride3d::new()
+            .color(AsciiColor::Yellow)
+            .characters([Character::Period, Character::Plus, Character::Dollar]),
+    ));
+
+    commands.spawn(PointLightBundle {
+        transform: Transform::from_xyz(2.0, 4.0, 4.0),
+        ..default()
+    });
+
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_translation(Vec3::new(0.0, 1.5, 5.0)).looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        },
+        AsciiCamera::default(),
+    ));
+}
+
+fn spin_pickups(mut pickups : Query<&mut Transform, With<Pickup>>, time : Res<Time>) {
+    for mut transform in pickups.iter_mut() {
+        transform.rotate_x(time.delta_seconds());
+        transform.rotate_y(time.delta_seconds() * 0.7);
+    }
+}
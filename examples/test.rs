@@ -1,5 +1,5 @@
-use bevy::{pbr::CascadeShadowConfigBuilder, prelude::*, render::mesh::shape::Cube, window::close_on_esc};
-use bevy_ascii::prelude::{AsciiCamera, AsciiShaderPlugin};
+use bevy::{pbr::CascadeShadowConfigBuilder, prelude::*, window::close_on_esc};
+use bevy_ascii::prelude::{AsciiCamera, AsciiColor, AsciiEffectPluginGroup};
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
 use bevy_panorbit_camera::{PanOrbitCamera, PanOrbitCameraPlugin};
 
@@ -8,12 +8,12 @@ pub fn main() {
     
     app
         .add_plugins(DefaultPlugins)
-        .add_plugins(AsciiShaderPlugin)
+        .add_plugins(AsciiEffectPluginGroup)
         .add_plugins(PanOrbitCameraPlugin)
         .add_plugins(WorldInspectorPlugin::new())
         
         .add_systems(Startup, init)
-        .add_systems(Update, close_on_esc)
+        .add_systems(Update, (close_on_esc, camera_effects))
         
         .run()
     ;
@@ -26,7 +26,7 @@ fn init(
     mut meshes : ResMut<Assets<Mesh>>,
     mut materials : ResMut<Assets<StandardMaterial>>
 ) {
-    let mesh = meshes.add(Cube::new(1.0));
+    let mesh = meshes.add(Cuboid::new(1.0, 1.0, 1.0));
     let red_material = materials.add(Color::RED);
     
     commands.spawn(PbrBundle {
@@ -67,4 +67,25 @@ fn init(
         color: Color::WHITE,
         brightness: 8000.0,
     });
-}
\ No newline at end of file
+}
+
+// 1 shakes the camera, 2 flashes it red, 3 inverts it and 4 sends a wave through it.
+fn camera_effects(
+    mut cameras : Query<&mut AsciiCamera>,
+    input : Res<ButtonInput<KeyCode>>,
+) {
+    for mut camera in cameras.iter_mut() {
+        if input.just_pressed(KeyCode::Digit1) {
+            camera.shake(2.0, 0.5);
+        }
+        if input.just_pressed(KeyCode::Digit2) {
+            camera.flash(AsciiColor::Red, 4);
+        }
+        if input.just_pressed(KeyCode::Digit3) {
+            camera.flash_invert(4);
+        }
+        if input.just_pressed(KeyCode::Digit4) {
+            camera.wave(2.0, 12.0, 8.0, 2.0);
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use bevy::{prelude::*, window::close_on_esc};
+use bevy_ascii::prelude::*;
+
+// A dungeon that is bigger than the screen. Walk around with the arrow keys, the view follows the
+// player and only what they can see is lit. Tiles they saw before stay dimly lit.
+pub fn main() {
+    let mut app = App::new();
+
+    app
+        .add_plugins(DefaultPlugins)
+        .add_plugins(AsciiEffectPluginGroup)
+
+        .insert_resource(Player(IVec2::new(4, 4)))
+        .add_systems(Startup, init)
+        .add_systems(Update, (close_on_esc, move_player))
+    ;
+
+    app.run();
+}
+
+#[derive(Resource)]
+struct Player(IVec2);
+
+const WIDTH: i32 = 120;
+const HEIGHT: i32 = 60;
+
+fn is_wall(x: i32, y: i32) -> bool {
+    let is_edge = x == 0 || y == 0 || x == WIDTH - 1 || y == HEIGHT - 1;
+    let is_pillar = x % 7 == 3 && y % 5 == 2;
+    let is_wall = (x % 24 == 12 && y % 12 != 6) || (y % 16 == 8 && x % 20 != 10);
+    is_edge || is_pillar || is_wall
+}
+
+fn floor(x: i32, y: i32) -> AsciiTile {
+    if is_wall(x, y) {
+        AsciiTile::new('#', AsciiColor::LightGrey, AsciiColor::DarkGrey)
+    } else {
+        AsciiTile::new('.', AsciiColor::Grey, AsciiColor::Black)
+    }
+}
+
+fn init(mut commands : Commands, player : Res<Player>) {
+    let camera = commands.spawn((
+        Camera3dBundle::default(),
+        AsciiCamera::default(),
+        AsciiUi::default(),
+        VisibilityBundle::default(),
+    )).id();
+
+    let mut map = AsciiTilemap::new(WIDTH as u32, HEIGHT as u32)
+        .with_lightmap(AsciiLightmap::new(WIDTH as u32, HEIGHT as u32).outside(0));
+    for y in 0..HEIGHT {
+        for x in 0..WIDTH {
+            map.set(x, y, floor(x, y));
+        }
+    }
+    if let Some(lightmap) = map.lightmap_mut() {
+        lightmap.fill(0);
+    }
+    light(&mut map, player.0);
+
+    commands.ascii_ui_with_parent(camera)
+        .grow(1, map.follow(player.0))
+    ;
+}
+
+fn light(map : &mut AsciiTilemap, player : IVec2) {
+    if let Some(lightmap) = map.lightmap_mut() {
+        // What was seen before is remembered, but dark.
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                if lightmap.get(x, y) > 0 {
+                    lightmap.set(x, y, 50);
+                }
+            }
+        }
+        lightmap.light_line_of_sight(player, 10, |cell| is_wall(cell.x, cell.y));
+    }
+    map.set(player.x, player.y, AsciiTile::new('@', AsciiColor::Yellow, AsciiColor::Black));
+}
+
+fn move_player(
+    keys : Res<ButtonInput<KeyCode>>,
+    mut player : ResMut<Player>,
+    mut maps : Query<&mut AsciiTilemap>,
+) {
+    let step = if keys.just_pressed(KeyCode::ArrowUp) {
+        IVec2::NEG_Y
+    } else if keys.just_pressed(KeyCode::ArrowDown) {
+        IVec2::Y
+    } else if keys.just_pressed(KeyCode::ArrowLeft) {
+        IVec2::NEG_X
+    } else if keys.just_pressed(KeyCode::ArrowRight) {
+        IVec2::X
+    } else {
+        return;
+    };
+
+    let next = player.0 + step;
+    if is_wall(next.x, next.y) {
+        return;
+    }
+
+    for mut map in maps.iter_mut() {
+        map.set(player.0.x, player.0.y, floor(player.0.x, player.0.y));
+        map.set_follow(Some(next));
+        light(&mut map, next);
+    }
+    player.0 = next;
+}
@@ -0,0 +1,69 @@
+use bevy::{prelude::*, window::close_on_esc};
+use bevy_ascii::prelude::*;
+
+// Two screens that take turns. Every press of space switches to the other one with the next
+// transition effect.
+pub fn main() {
+    let mut app = App::new();
+
+    app
+        .add_plugins(DefaultPlugins)
+        .add_plugins(AsciiEffectPluginGroup)
+
+        .add_systems(Startup, init)
+        .add_systems(Update, (close_on_esc, switch_screens))
+    ;
+
+    app.run();
+}
+
+#[derive(Component)]
+struct Screen(usize);
+
+const EFFECTS : [AsciiTransitionEffect; 5] = [
+    AsciiTransitionEffect::Wipe(AsciiWipeDirection::Right),
+    AsciiTransitionEffect::Dissolve,
+    AsciiTransitionEffect::Typewriter,
+    AsciiTransitionEffect::Curtain,
+    AsciiTransitionEffect::Wipe(AsciiWipeDirection::Up),
+];
+
+fn init(mut commands : Commands) {
+    let camera = commands.spawn((
+        Camera3dBundle::default(),
+        AsciiCamera::default(),
+        AsciiUi::default(),
+        VisibilityBundle::default(),
+    )).id();
+
+    commands.ascii_ui_with_parent(camera)
+        .relative(2, 2, 30, 8, AsciiWindow::new("Title Screen"))
+        .insert(Screen(0))
+        .pop()
+        .relative(6, 4, 30, 8, AsciiWindow::new("Options"))
+        .insert(Screen(1))
+        .hidden()
+    ;
+}
+
+fn switch_screens(
+    keys : Res<ButtonInput<KeyCode>>,
+    screens : Query<(Entity, &Screen)>,
+    mut current : Local<usize>,
+    mut effect : Local<usize>,
+    mut transitions : EventWriter<AsciiTransitionEvent>,
+) {
+    if !keys.just_pressed(KeyCode::Space) {
+        return;
+    }
+
+    let next = (*current + 1) % 2;
+    let find = |index : usize| screens.iter().find(|(_, screen)| screen.0 == index).map(|(entity, _)| entity);
+    let (Some(from), Some(to)) = (find(*current), find(next)) else {
+        return;
+    };
+
+    transitions.send(AsciiTransitionEvent::new(from, to, EFFECTS[*effect]).duration(0.8));
+    *current = next;
+    *effect = (*effect + 1) % EFFECTS.len();
+}
@@ -0,0 +1,48 @@
+use bevy::{prelude::*, window::close_on_esc};
+use bevy_ascii::prelude::*;
+
+// The top half is drawn with rgb colors on a true color camera, the bottom half with the 256
+// colors of the palette.
+pub fn main() {
+    let mut app = App::new();
+
+    app
+        .add_plugins(DefaultPlugins)
+        .add_plugins(AsciiEffectPluginGroup)
+
+        .add_systems(Startup, init)
+        .add_systems(Update, (close_on_esc, draw_gradients))
+    ;
+
+    app.run();
+}
+
+fn init(mut commands : Commands) {
+    commands.spawn((
+        Camera3dBundle::default(),
+        AsciiCamera::default(),
+        AsciiUi::default().with_true_color(),
+        VisibilityBundle::default(),
+    ));
+}
+
+fn draw_gradients(overlay : Res<AsciiOverlay>, cameras : Query<(Entity, &AsciiGridInfo)>, time : Res<Time>) {
+    let shift = time.elapsed_seconds() * 0.2;
+    for (camera, grid) in cameras.iter() {
+        let (columns, rows) = (grid.ui_columns, grid.ui_rows);
+        overlay.draw(camera, move |buffer| {
+            for y in 0..rows {
+                for x in 0..columns {
+                    let color = if y < rows / 2 {
+                        let hue = (x as f32 / columns as f32 + shift).fract() * 360.0;
+                        let [r, g, b, _] = Color::hsl(hue, 0.8, 0.5).as_rgba_u8();
+                        AsciiColor::Rgb(r, g, b)
+                    } else {
+                        AsciiColor::Indexed(16 + (x * 216 / columns.max(1)) as u8)
+                    };
+                    buffer.set_character(x as i32, y as i32, (' ', color, color));
+                }
+            }
+        });
+    }
+}
@@ -1,4 +1,4 @@
-use bevy::{pbr::CascadeShadowConfigBuilder, prelude::*, render::mesh::shape::Cube, window::close_on_esc};
+use bevy::{pbr::CascadeShadowConfigBuilder, prelude::*, window::close_on_esc};
 use bevy_ascii::prelude::*;
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
 use bevy_panorbit_camera::{PanOrbitCamera, PanOrbitCameraPlugin};
@@ -8,7 +8,9 @@ pub fn main() {
     
     app
         .add_plugins(DefaultPlugins)
-        .add_plugins(AsciiShaderPlugin)
+        .add_plugins(AsciiEffectPluginGroup)
+        .add_plugins(AsciiDiagnosticsOverlayPlugin::default())
+        .add_plugins(AsciiDebugOverlayPlugin::default())
         .add_plugins(PanOrbitCameraPlugin)
         .add_plugins(WorldInspectorPlugin::new())
         
@@ -26,7 +28,7 @@ fn init(
     mut meshes : ResMut<Assets<Mesh>>,
     mut materials : ResMut<Assets<StandardMaterial>>
 ) {
-    let mesh = meshes.add(Cube::new(1.0));
+    let mesh = meshes.add(Cuboid::new(1.0, 1.0, 1.0));
     let red_material = materials.add(Color::RED);
     
     commands.spawn(PbrBundle {
@@ -69,7 +71,6 @@ fn init(
         .aligned(20, 20, HorizontalAlignment::Center, VerticalAlignment::Center, AsciiButton::from_string("Test Button"))
         .pop()
         .bottom(10, AsciiButton::from_string("Test Button"));
-    ;
     
     commands.insert_resource(AmbientLight {
         color: Color::WHITE,
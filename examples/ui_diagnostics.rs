@@ -0,0 +1,34 @@
+use bevy::{diagnostic::LogDiagnosticsPlugin, prelude::*, window::close_on_esc};
+use bevy_ascii::prelude::*;
+
+// Falling noise that redraws the ui many times a second, with the work the ui does shown in the
+// top right corner and logged to the console.
+pub fn main() {
+    let mut app = App::new();
+
+    app
+        .add_plugins(DefaultPlugins)
+        .add_plugins(AsciiEffectPluginGroup)
+        .add_plugins(AsciiUiDiagnosticsPlugin::with_panel(HorizontalAlignment::Right, VerticalAlignment::Top))
+        .add_plugins(LogDiagnosticsPlugin::default())
+
+        .add_systems(Startup, init)
+        .add_systems(Update, close_on_esc)
+    ;
+
+    app.run();
+}
+
+fn init(mut commands : Commands) {
+    let camera = commands.spawn((
+        Camera3dBundle::default(),
+        AsciiCamera::default(),
+        AsciiUi::default(),
+        VisibilityBundle::default(),
+    )).id();
+
+    commands.ascii_ui_with_parent(camera)
+        .relative(2, 2, 30, 12, AsciiWindow::new("Rain"))
+        .relative(0, 0, 1.0, 1.0, AsciiNoisePanel::new(7).density(0.2).animated(12.0).falling(8.0))
+    ;
+}
@@ -0,0 +1,53 @@
+use bevy::{prelude::*, window::close_on_esc};
+use bevy_ascii::prelude::*;
+
+// A scene of 120 columns with a ui of 40. Every cell of the ui covers three by three cells of
+// the scene.
+pub fn main() {
+    let mut app = App::new();
+
+    app
+        .add_plugins(DefaultPlugins)
+        .add_plugins(AsciiEffectPluginGroup)
+
+        .add_systems(Startup, init)
+        .add_systems(Update, close_on_esc)
+    ;
+
+    app.run();
+}
+
+fn init(
+    mut commands : Commands,
+    mut meshes : ResMut<Assets<Mesh>>,
+    mut materials : ResMut<Assets<StandardMaterial>>
+) {
+    commands.spawn(PbrBundle {
+        mesh: meshes.add(Torus::default()),
+        material: materials.add(Color::ORANGE),
+        transform: Transform::from_rotation(Quat::from_rotation_x(1.0)),
+        ..Default::default()
+    });
+
+    commands.spawn(PointLightBundle {
+        transform: Transform::from_xyz(2.0, 4.0, 4.0),
+        ..default()
+    });
+
+    let mut settings = AsciiCamera::default();
+    settings.sizing = GridSizing::Columns(120.0);
+
+    let camera = commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_translation(Vec3::new(0.0, 0.0, 3.0)).looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        },
+        settings,
+        AsciiUi::default(),
+        AsciiUiScale(3),
+        VisibilityBundle::default(),
+    )).id();
+
+    commands.ascii_ui_with_parent(camera)
+        .bottom(5, AsciiButton::from_string("Start"));
+}
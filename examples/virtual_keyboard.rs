@@ -0,0 +1,49 @@
+use bevy::{prelude::*, window::close_on_esc};
+use bevy_ascii::prelude::*;
+
+// Name entry without a keyboard. Pick the letters with the d-pad of a gamepad and the south button,
+// or tap them. Done, or start on the gamepad, submits the name.
+pub fn main() {
+    let mut app = App::new();
+
+    app
+        .add_plugins(DefaultPlugins)
+        .add_plugins(AsciiEffectPluginGroup)
+
+        .add_systems(Startup, init)
+        .add_systems(Update, (close_on_esc, greet.after(AsciiUiSet::Interaction)))
+    ;
+
+    app.run();
+}
+
+#[derive(Component)]
+struct Greeting;
+
+fn init(mut commands : Commands) {
+    let camera = commands.spawn((
+        Camera3dBundle::default(),
+        AsciiCamera::default(),
+        AsciiUi::default(),
+        VisibilityBundle::default(),
+    )).id();
+
+    let mut name = AsciiTextInput::new().placeholder("Your name").max_length(16);
+    // Gamepads can not click the input, so it starts out focused.
+    name.focus();
+
+    commands.ascii_ui_with_parent(camera)
+        .centered(40, 12, AsciiWindow::new("Who are you?")).insert(AsciiFlex::column().gap(1))
+            .flex(1, 0, 0, name).pop()
+            .grow(1, AsciiVirtualKeyboard::new()).pop()
+            .flex(1, 0, 0, AsciiRichText::new("")).insert(Greeting)
+    ;
+}
+
+fn greet(mut submitted : EventReader<AsciiTextInputSubmittedEvent>, mut greetings : Query<&mut AsciiRichText, With<Greeting>>) {
+    for event in submitted.read() {
+        for mut greeting in greetings.iter_mut() {
+            greeting.set_markup(&format!("Hello, {}!", event.text));
+        }
+    }
+}
@@ -0,0 +1,95 @@
+// Runs in the browser. With `wasm-server-runner` set as the runner for wasm32:
+//
+//     cargo run --example wasm --target wasm32-unknown-unknown --features webgl2
+//
+// Leave out `webgl2` for browsers with WebGPU. The scene grid is read back here too, since that is
+// the part that works differently on the web.
+use bevy::{pbr::CascadeShadowConfigBuilder, prelude::*};
+use bevy_ascii::prelude::*;
+
+pub fn main() {
+    let mut app = App::new();
+
+    app
+        .add_plugins(DefaultPlugins.set(WindowPlugin {
+            primary_window: Some(Window {
+                canvas: Some("#bevy".to_string()),
+                prevent_default_event_handling: false,
+                ..default()
+            }),
+            ..default()
+        }))
+        .add_plugins(AsciiEffectPluginGroup)
+        .add_plugins(AsciiSceneGridPlugin::default())
+
+        .add_systems(Startup, init)
+        .add_systems(Update, (rotate_cube, log_scene_grid))
+    ;
+
+    app.run();
+}
+
+#[derive(Component)]
+struct Spinning;
+
+fn init(
+    mut commands : Commands,
+    mut meshes : ResMut<Assets<Mesh>>,
+    mut materials : ResMut<Assets<StandardMaterial>>
+) {
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(Cuboid::new(1.0, 1.0, 1.0)),
+            material: materials.add(Color::RED),
+            ..Default::default()
+        },
+        Spinning,
+    ));
+
+    // light
+    commands.spawn(DirectionalLightBundle {
+            directional_light: DirectionalLight {
+                shadows_enabled: true,
+                ..default()
+            },
+            cascade_shadow_config: CascadeShadowConfigBuilder {
+                num_cascades: 1,
+                maximum_distance: 1.6,
+                ..default()
+            }
+            .into(),
+            ..default()
+        });
+
+    let camera = commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_translation(Vec3::new(0.0, 1.5, 5.0)).looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        },
+        AsciiCamera::default(),
+        AsciiUi::default(),
+        AsciiSceneGridSource,
+        VisibilityBundle::default(),
+    )).id();
+
+    commands.ascii_ui_with_parent(camera)
+        .bottom(3, AsciiButton::from_string("Hello from the browser"))
+    ;
+
+    commands.insert_resource(AmbientLight {
+        color: Color::WHITE,
+        brightness: 8000.0,
+    });
+}
+
+fn rotate_cube(mut cubes : Query<&mut Transform, With<Spinning>>, time : Res<Time>) {
+    for mut transform in cubes.iter_mut() {
+        transform.rotate_y(time.delta_seconds() * 0.5);
+    }
+}
+
+fn log_scene_grid(grid : Res<AsciiSceneGrid>) {
+    if grid.is_changed() && !grid.is_added() {
+        info!("scene grid is {}x{}", grid.width(), grid.height());
+    }
+}
@@ -0,0 +1,92 @@
+use bevy::{prelude::*, window::close_on_esc};
+use bevy_ascii::prelude::*;
+
+// Every input widget of the crate next to each other. What they send is written to the console
+// on the right, and the counter below it counts the clicks of the button.
+pub fn main() {
+    let mut app = App::new();
+
+    app
+        .add_plugins(DefaultPlugins)
+        .add_plugins(AsciiEffectPluginGroup)
+
+        .add_systems(Startup, init)
+        .add_systems(Update, (close_on_esc, log_events.after(AsciiUiSet::Interaction)))
+    ;
+
+    app.run();
+}
+
+#[derive(Component)]
+struct Output;
+
+#[derive(Component)]
+struct Clicks;
+
+fn init(mut commands : Commands) {
+    let camera = commands.spawn((
+        Camera3dBundle::default(),
+        AsciiCamera::default(),
+        AsciiUi::default(),
+        AsciiFlex::row().gap(1),
+        VisibilityBundle::default(),
+    )).id();
+
+    commands.ascii_ui_with_parent(camera)
+        .grow(1, AsciiWindow::new("Inputs")).insert(AsciiFlex::column().gap(1))
+            .flex(1, 0, 1, AsciiToggle::new(true).label("Sound")).pop()
+            .flex(1, 0, 1, AsciiSlider::new(0.5, 0.0, 1.0).step(0.05)).pop()
+            .flex(1, 0, 1, AsciiSpinner::new(3.0, 1.0, 10.0).precision(0)).pop()
+            .flex(1, 0, 1, AsciiSegmented::new(&["Easy", "Normal", "Hard"]).selected(1)).pop()
+            .flex(1, 0, 1, AsciiTextInput::new().placeholder("Your name")).pop()
+            .flex(3, 0, 1, AsciiButton::from_string("Apply")).pop()
+        .pop()
+        .grow(1, AsciiWindow::new("Pages"))
+            .fill(AsciiTabs::new(&["List", "Text", "Noise"]))
+                .fill(AsciiList::new((1..=50).map(|i| format!("Entry {}", i))).selected(0)).pop()
+                .fill(AsciiRichText::new("Rich text wraps on its own and can have {link=help}links{/link} in it.")).pop()
+                .fill(AsciiNoisePanel::new(7).density(0.3).charset(".:*").falling(4.0)).pop()
+            .pop()
+        .pop()
+        .grow(1, AsciiWindow::new("Output")).insert(AsciiFlex::column().gap(1))
+            .flex(1, 0, 1, AsciiNumber::new(0).prefix("Clicks: ").count_duration(0.3)).insert(Clicks).pop()
+            .grow(1, AsciiConsole::new(200)).insert(Output)
+    ;
+}
+
+#[allow(clippy::too_many_arguments)]
+fn log_events(
+    mut consoles : Query<&mut AsciiConsole, With<Output>>,
+    mut clicks : Query<&mut AsciiNumber, With<Clicks>>,
+    mut toggles : EventReader<AsciiToggleChangedEvent>,
+    mut sliders : EventReader<AsciiSliderChangedEvent>,
+    mut spinners : EventReader<AsciiSpinnerChangedEvent>,
+    mut segments : EventReader<AsciiSegmentedChangedEvent>,
+    mut inputs : EventReader<AsciiTextInputSubmittedEvent>,
+    mut lists : EventReader<AsciiListSelectedEvent>,
+    mut tabs : EventReader<AsciiTabChangedEvent>,
+    mut buttons : EventReader<AsciiComponentButtonClickedEvent>,
+) {
+    let mut lines = Vec::new();
+    lines.extend(toggles.read().map(|event| (format!("toggle {}", event.is_on), AsciiColor::LightGreen)));
+    lines.extend(sliders.read().map(|event| (format!("slider {:.2}", event.value), AsciiColor::Cyan)));
+    lines.extend(spinners.read().map(|event| (format!("spinner {}", event.value), AsciiColor::Cyan)));
+    lines.extend(segments.read().map(|event| (format!("difficulty {}", event.index), AsciiColor::Violet)));
+    lines.extend(inputs.read().map(|event| (format!("name {:?}", event.text), AsciiColor::Yellow)));
+    lines.extend(lists.read().map(|event| (format!("entry {}", event.index + 1), AsciiColor::White)));
+    lines.extend(tabs.read().map(|event| (format!("page {}", event.index), AsciiColor::LightGrey)));
+
+    let clicked = buttons.read().count();
+    if clicked > 0 {
+        lines.push(("apply".to_string(), AsciiColor::LightRed));
+        for mut number in clicks.iter_mut() {
+            number.add(clicked as f64);
+        }
+    }
+
+    for mut console in consoles.iter_mut() {
+        for (line, color) in &lines {
+            console.push_line(line, *color);
+        }
+    }
+}
@@ -1,34 +1,162 @@
 use bevy::{
-    app::Plugin,
-    core_pipeline::prepass::{DepthPrepass},
+    app::{Plugin, PluginGroup, PluginGroupBuilder},
+    input::InputSystem,
     prelude::*,
     render::{
-        camera::RenderTarget,
-        render_resource::{DynamicUniformBuffer, ShaderType},
-        renderer::{RenderDevice, RenderQueue},
+        camera::{CameraMainTextureUsages, RenderTarget},
+        render_resource::TextureUsages,
     },
-    window::{PrimaryWindow, WindowRef, WindowResized},
+    window::{PrimaryWindow, WindowRef},
 };
+#[cfg(feature = "inspector")]
 use bevy_inspector_egui::InspectorOptions;
 
 use crate::{
-    render::AsciiRendererPlugin,
-    ui::AsciiUiPlugin,
+    render::{font::AsciiFont, AsciiNodePlacement, AsciiRendererPlugin},
+    ui::{
+        character::Color,
+        component::{AsciiComponents, AsciiDefaultWidgetsPlugin},
+        util::{noise_hash, AsciiInputPlugin},
+        AsciiUiPlugin, AsciiUiScale,
+    },
 };
 
 //=============================================================================
-//             Acsii Shader Plugin
+//             Acsii Effect Plugin Group
 //=============================================================================
 
-pub struct AsciiShaderPlugin;
+// Everything the effect needs. Pieces can be left out with `disable`, for example the
+// `AsciiRenderPlugin` for a game that only uses the ui.
+pub struct AsciiEffectPluginGroup;
+
+impl PluginGroup for AsciiEffectPluginGroup {
+    fn build(self) -> PluginGroupBuilder {
+        PluginGroupBuilder::start::<Self>()
+            .add(AsciiRenderPlugin::default())
+            .add(AsciiUiPlugin)
+            .add(AsciiInputPlugin)
+            .add(AsciiDefaultWidgetsPlugin)
+    }
+}
+
+// Adds the whole `AsciiEffectPluginGroup`, with the ascii node at `placement` on the render graph.
+#[derive(Default)]
+pub struct AsciiShaderPlugin {
+    pub placement: AsciiNodePlacement,
+    // The asset path of a shader that is run on every cell of the scene, see `AsciiCellHook`.
+    pub cell_hook: Option<String>,
+    // Picks the character of every cell in a compute pass, see `AsciiRenderPlugin::compute_cells`.
+    pub compute_cells: bool,
+    // Custom components registered along with the built-in widgets.
+    pub components: AsciiComponents,
+}
+
+impl AsciiShaderPlugin {
+    pub fn before_tonemapping(mut self) -> Self {
+        self.placement = AsciiNodePlacement::BeforeTonemapping;
+        self
+    }
+
+    pub fn after_bloom(mut self) -> Self {
+        self.placement = AsciiNodePlacement::AfterBloom;
+        self
+    }
+
+    pub fn after_tonemapping(mut self) -> Self {
+        self.placement = AsciiNodePlacement::AfterTonemapping;
+        self
+    }
+
+    pub fn after_ui(mut self) -> Self {
+        self.placement = AsciiNodePlacement::AfterUi;
+        self
+    }
+
+    // Adds the node without edges, so `AsciiShaderNodeId` can be placed on the graph by hand.
+    pub fn without_graph_edges(mut self) -> Self {
+        self.placement = AsciiNodePlacement::Manual;
+        self
+    }
+
+    pub fn cell_hook(mut self, path: impl Into<String>) -> Self {
+        self.cell_hook = Some(path.into());
+        self
+    }
+
+    pub fn compute_cells(mut self) -> Self {
+        self.compute_cells = true;
+        self
+    }
+
+    // Registers custom components, like `.components(|c| c.add::<Health>().add::<Inventory>())`.
+    pub fn components(mut self, add: impl FnOnce(&mut AsciiComponents) -> &mut AsciiComponents) -> Self {
+        add(&mut self.components);
+        self
+    }
+}
 
-//This plugin will add the settings required for the AsciiShader and add the post precess shader to the right spot on the render graph.
 impl Plugin for AsciiShaderPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(AsciiUiPlugin)
-            .register_type::<AsciiCamera>()
-            .add_plugins(AsciiRendererPlugin)
-            .add_systems(PreUpdate, update_target_resolution);
+        app.add_plugins(AsciiEffectPluginGroup.build().set(AsciiRenderPlugin {
+            placement: self.placement,
+            cell_hook: self.cell_hook.clone(),
+            compute_cells: self.compute_cells,
+        }));
+        self.components.register(app);
+    }
+}
+
+//=============================================================================
+//             Acsii Render Plugin
+//=============================================================================
+
+// Adds the post process shader to the render graph, without any of the ui.
+#[derive(Default)]
+pub struct AsciiRenderPlugin {
+    pub placement: AsciiNodePlacement,
+    pub cell_hook: Option<String>,
+    // Picks the character of every cell once in a compute pass, instead of for every pixel in
+    // the ascii shader. This is faster on big windows, and the `AsciiSceneGrid` gets the exact
+    // characters that were drawn, cell hook included. Not available with `webgl2`.
+    pub compute_cells: bool,
+}
+
+impl Plugin for AsciiRenderPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<AsciiCameraPlugin>() {
+            app.add_plugins(AsciiCameraPlugin);
+        }
+
+        app.add_plugins(AsciiRendererPlugin {
+            placement: self.placement,
+            cell_hook: self.cell_hook.clone(),
+            compute_cells: self.compute_cells,
+        });
+    }
+}
+
+// Keeps the target resolution and the effects of the `AsciiCamera` up to date. This is shared by
+// the renderer and the ui.
+pub(crate) struct AsciiCameraPlugin;
+
+// In `PreUpdate`, after bevy's input: the grid size of every camera and its effects. The ui reads
+// the grid in `AsciiUiSet::Input`, which runs after this.
+#[derive(SystemSet, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct AsciiCameraSet;
+
+impl Plugin for AsciiCameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<AsciiCamera>()
+            .register_type::<AsciiCameraEffect>()
+            .register_type::<AsciiGridInfo>()
+            .register_type::<GridSizing>()
+            .register_type::<ColorBlindFilter>()
+            .add_event::<AsciiCameraEffectEvent>()
+            .add_event::<AsciiGridResized>()
+            .configure_sets(PreUpdate, AsciiCameraSet.after(InputSystem))
+            .add_systems(First, clear_redraw_requests)
+            .add_systems(PreUpdate, (update_target_resolution, tick_camera_effects).in_set(AsciiCameraSet))
+            .add_systems(PostUpdate, (update_camera_effects, update_main_texture_usages));
     }
 }
 
@@ -36,121 +164,849 @@ impl Plugin for AsciiShaderPlugin {
 //             Ascii Camera Bundle
 //=============================================================================
 
-#[derive(Bundle)]
+#[derive(Bundle, Default)]
 pub struct AsciiCameraBundle {
     pub camera_bundle: Camera3dBundle,
     pub ascii_cam: AsciiCamera,
 }
 
-impl Default for AsciiCameraBundle {
+//=============================================================================
+//             Shader Settings
+//=============================================================================
+
+// How the size of the character grid is picked. Cells are square, except with `Fixed`, so the
+// axis that is not given follows from the size of the viewport.
+#[derive(Clone, Copy, PartialEq, Debug, Reflect)]
+pub enum GridSizing {
+    // This many columns across the viewport.
+    Columns(f32),
+    // This many rows down the viewport, like exactly 25 lines.
+    Rows(f32),
+    // Cells of this many physical pixels.
+    CellPixels(u32),
+    // Always this many columns and rows. The cells are stretched to fill the viewport.
+    Fixed { cols: u32, rows: u32 },
+}
+
+impl Default for GridSizing {
     fn default() -> Self {
-        AsciiCameraBundle {
-            camera_bundle: Camera3dBundle::default(),
-            ascii_cam: AsciiCamera::default(),
+        GridSizing::Columns(80.0)
+    }
+}
+
+impl GridSizing {
+    // The columns and rows of the grid and the width of a cell in pixels, for a viewport of
+    // `resolution` physical pixels. Cells of `Fixed` can be taller than they are wide, their
+    // height is given by `grid_with_aspect`.
+    pub fn grid(&self, resolution: Vec2) -> (Vec2, f32) {
+        let (grid, cell_size) = self.grid_with_aspect(resolution, 1.0);
+        (grid, cell_size.x)
+    }
+
+    // Like `grid`, for cells that are `aspect` times as tall as they are wide, like the ones of
+    // an `AsciiFont` with tall glyphs. Returns the width and height of a cell.
+    pub fn grid_with_aspect(&self, resolution: Vec2, aspect: f32) -> (Vec2, Vec2) {
+        let aspect = aspect.max(0.01);
+        let cell_width = match *self {
+            GridSizing::Columns(columns) => (resolution.x / columns.max(1.0)).floor(),
+            GridSizing::Rows(rows) => (resolution.y / rows.max(1.0)).floor() / aspect,
+            GridSizing::CellPixels(pixels) => pixels as f32,
+            GridSizing::Fixed { cols, rows } => {
+                let size = Vec2::new(cols.max(1) as f32, rows.max(1) as f32);
+                return (size, resolution / size);
+            }
         }
+        .max(1.0);
+        let cell_size = Vec2::new(cell_width, cell_width * aspect);
+
+        ((resolution / cell_size).floor(), cell_size)
     }
 }
 
-//=============================================================================
-//             Shader Settings
-//=============================================================================
+// A kind of color blindness, for `ColorBlindFilter`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Reflect)]
+pub enum ColorBlindness {
+    // No red cones, red and green are hard to tell apart and red looks dark.
+    Protanopia,
+    // No green cones, red and green are hard to tell apart.
+    Deuteranopia,
+    // No blue cones, blue and green and yellow and pink are hard to tell apart.
+    Tritanopia,
+}
 
-#[derive(Component, Clone, Reflect, InspectorOptions)]
+impl ColorBlindness {
+    // How the colors look with this color blindness, from Machado et al. 2009 at full severity.
+    fn simulation(&self) -> Mat3 {
+        let rows = match self {
+            ColorBlindness::Protanopia => [
+                [0.152286, 1.052583, -0.204868],
+                [0.114503, 0.786281, 0.099216],
+                [-0.003882, -0.048116, 1.051998],
+            ],
+            ColorBlindness::Deuteranopia => [
+                [0.367322, 0.860646, -0.227968],
+                [0.280085, 0.672501, 0.047413],
+                [-0.011820, 0.042940, 0.968881],
+            ],
+            ColorBlindness::Tritanopia => [
+                [1.255528, -0.076749, -0.178779],
+                [-0.078411, 0.930809, 0.147602],
+                [0.004733, 0.691367, 0.303900],
+            ],
+        };
+        Mat3::from_cols_array_2d(&rows).transpose()
+    }
+
+    // Where the difference that is lost is moved to, so it shows up in channels that can still
+    // be seen.
+    fn shift(&self) -> Mat3 {
+        let rows = match self {
+            ColorBlindness::Protanopia | ColorBlindness::Deuteranopia => [
+                [0.0, 0.0, 0.0],
+                [0.7, 1.0, 0.0],
+                [0.7, 0.0, 1.0],
+            ],
+            ColorBlindness::Tritanopia => [
+                [1.0, 0.0, 0.7],
+                [0.0, 1.0, 0.7],
+                [0.0, 0.0, 0.0],
+            ],
+        };
+        Mat3::from_cols_array_2d(&rows).transpose()
+    }
+}
+
+// Remaps every color the ascii shader draws, scene and ui alike, after it was picked. With only 16
+// colors some pairs of the palette look the same with color blindness, so games can offer this as
+// an accessibility option. It can be changed at any time.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, Reflect)]
+pub enum ColorBlindFilter {
+    #[default]
+    Off,
+    // Shows how the frame looks to players with the color blindness, for testing.
+    Simulate(ColorBlindness),
+    // Moves the differences players with the color blindness can not see into colors they can.
+    Compensate(ColorBlindness),
+}
+
+impl ColorBlindFilter {
+    // The matrix the linear rgb of every drawn color is multiplied with.
+    pub fn matrix(&self) -> Mat3 {
+        match self {
+            ColorBlindFilter::Off => Mat3::IDENTITY,
+            ColorBlindFilter::Simulate(blindness) => blindness.simulation(),
+            ColorBlindFilter::Compensate(blindness) => {
+                Mat3::IDENTITY + blindness.shift() * (Mat3::IDENTITY - blindness.simulation())
+            }
+        }
+    }
+}
+
+#[derive(Component, Clone, Reflect)]
+#[cfg_attr(feature = "inspector", derive(InspectorOptions))]
 pub struct AsciiCamera {
-    #[inspector(min = 1.0)]
-    pub screen_colummns: f32,
+    pub sizing: GridSizing,
     pub should_render: bool,
+    // Image controls that are applied to the brightness of the scene before a character is picked.
+    #[cfg_attr(feature = "inspector", inspector(min = 0.0))]
+    pub exposure: f32,
+    #[cfg_attr(feature = "inspector", inspector(min = 0.0))]
+    pub contrast: f32,
+    #[cfg_attr(feature = "inspector", inspector(min = 0.01))]
+    pub gamma: f32,
+    pub invert: bool,
+    // Scales the exposure so the average brightness of the scene sits in the middle of the
+    // character ramp. The scale adapts by `auto_exposure_speed` per second and stays between
+    // `auto_exposure_min` and `auto_exposure_max`.
+    pub auto_exposure: bool,
+    #[cfg_attr(feature = "inspector", inspector(min = 0.0))]
+    pub auto_exposure_speed: f32,
+    #[cfg_attr(feature = "inspector", inspector(min = 0.0))]
+    pub auto_exposure_min: f32,
+    #[cfg_attr(feature = "inspector", inspector(min = 0.0))]
+    pub auto_exposure_max: f32,
+    // Keeps the character of a cell until its brightness moved more than this from the
+    // brightness the character was picked at, so small movements do not make the scene shimmer.
+    // 0 turns it off, 0.05 is a good start. The `AsciiSceneGrid` only sees the kept characters
+    // with `AsciiRenderPlugin::compute_cells`.
+    #[cfg_attr(feature = "inspector", inspector(min = 0.0, max = 1.0))]
+    pub temporal_stability: f32,
+    // Only runs the effect when the camera moved, its settings changed, the ui is dirty or a
+    // redraw was requested, and shows the last output again otherwise. This is for menus, paused
+    // games and other scenes that stand still most of the time.
+    pub on_demand: bool,
+    pub color_blind_filter: ColorBlindFilter,
     #[reflect(ignore)]
     target_resolution: Vec2,
+    // Copied from the `AsciiUiScale` of the camera, so the render world sees it.
+    #[reflect(ignore)]
+    ui_scale: u32,
+    #[reflect(ignore)]
+    redraw_requested: bool,
+    #[reflect(ignore)]
+    effects: AsciiCameraEffects,
 }
 
 impl Default for AsciiCamera {
     fn default() -> Self {
         AsciiCamera {
-            screen_colummns: 80.0,
+            sizing: GridSizing::default(),
             should_render: true,
+            exposure: 1.0,
+            contrast: 1.0,
+            gamma: 1.0,
+            invert: false,
+            auto_exposure: false,
+            auto_exposure_speed: 1.0,
+            auto_exposure_min: 0.5,
+            auto_exposure_max: 4.0,
+            temporal_stability: 0.0,
+            on_demand: false,
+            color_blind_filter: ColorBlindFilter::Off,
             target_resolution: Vec2::ZERO,
+            ui_scale: 1,
+            redraw_requested: false,
+            effects: AsciiCameraEffects::default(),
         }
     }
 }
 
 impl AsciiCamera {
-    pub fn buffer(
-        &self,
-        device: &RenderDevice,
-        queue: &RenderQueue,
-        width: u32,
-    ) -> DynamicUniformBuffer<AsciiShaderSettingsBuffer> {
-        let pixels_per_character = self.screen_colummns / width as f32;
-        let ascii_buffer = AsciiShaderSettingsBuffer {
+    // The uniform the shader is given for a camera with the given viewport.
+    pub fn settings(&self, viewport: UVec4) -> AsciiShaderSettingsBuffer {
+        // The shader does not know the font, so the cells are square unless they are stretched by
+        // `GridSizing::Fixed`.
+        let (_, pixels_per_character) = self.sizing.grid_with_aspect(viewport.zw().as_vec2(), 1.0);
+        let effects = &self.effects;
+        let (flash, flash_color) = match (effects.flash, effects.invert_frames) {
+            (Some((color, _)), _) => (2, color.index() as u32),
+            (None, frames) if frames > 0 => (1, 0),
+            _ => (0, 0),
+        };
+        AsciiShaderSettingsBuffer {
+            viewport: viewport.as_vec4(),
             pixels_per_character,
+            exposure: self.exposure,
+            contrast: self.contrast,
+            gamma: self.gamma.max(0.01),
+            invert: self.invert as u32,
+            auto_exposure: self.auto_exposure as u32,
+            auto_exposure_min: self.auto_exposure_min,
+            auto_exposure_max: self.auto_exposure_max.max(self.auto_exposure_min),
+            shake_offset: effects.shake_offset.as_vec2(),
+            wave_amplitude: effects.wave.map(|wave| wave.amplitude()).unwrap_or(0.0),
+            wave_frequency: effects.wave.map(|wave| std::f32::consts::TAU / wave.wavelength).unwrap_or(0.0),
+            wave_phase: effects.wave.map(|wave| wave.elapsed * wave.speed).unwrap_or(0.0),
+            flash,
+            flash_color,
+            time: 0.0,
+            stability: self.temporal_stability.max(0.0),
+            ui_scale: self.ui_scale,
+            color_matrix: self.color_blind_filter.matrix(),
             #[cfg(feature = "webgl2")]
-            _webgl2_padding: Vec3::ZERO,
+            _webgl2_padding: Vec2::ZERO,
+        }
+    }
+
+    pub fn target_res(&self) -> &Vec2 {
+        &self.target_resolution
+    }
+
+    // The columns and rows of the ui, which are fewer than the ones of the scene when the camera
+    // has an `AsciiUiScale`. Cells that do not fit at the right and bottom are left to the scene.
+    pub fn ui_res(&self) -> Vec2 {
+        (self.target_resolution / self.ui_scale as f32).floor()
+    }
+
+    // How many cells of the scene a cell of the ui covers across and down.
+    pub fn ui_scale(&self) -> u32 {
+        self.ui_scale
+    }
+
+    // Runs the effect on the next frame of an `on_demand` camera, for when something in the scene
+    // changed that the camera can not see on its own, like an animation.
+    pub fn request_redraw(&mut self) {
+        self.redraw_requested = true;
+    }
+
+    pub fn is_redraw_requested(&self) -> bool {
+        self.redraw_requested
+    }
+
+    // The exposure after auto exposure is applied for a scene with the given average brightness.
+    pub fn exposure_for(&self, average: f32) -> f32 {
+        if !self.auto_exposure {
+            return self.exposure;
+        }
+        let scale = (0.5 / average.max(0.001)).clamp(self.auto_exposure_min, self.auto_exposure_max.max(self.auto_exposure_min));
+        self.exposure * scale
+    }
+
+    // The share of the new average brightness that is blended into the adapted one this frame.
+    pub fn auto_exposure_blend(&self, delta_seconds: f32) -> f32 {
+        1.0 - (-self.auto_exposure_speed * delta_seconds).exp()
+    }
+
+    // Starts an effect on the scene of this camera. An effect of the same kind that is still
+    // running is replaced. The ui on the overlay is never moved or flashed.
+    pub fn trigger(&mut self, effect: AsciiCameraEffect) {
+        let effects = &mut self.effects;
+        match effect {
+            AsciiCameraEffect::Shake { cells, duration } => {
+                effects.shake = Some(AsciiShake { cells: cells.abs(), duration: duration.max(0.0), elapsed: 0.0 });
+            }
+            AsciiCameraEffect::Flash { color, frames } => {
+                effects.flash = (frames > 0).then_some((color, frames));
+            }
+            AsciiCameraEffect::Invert { frames } => {
+                effects.invert_frames = frames;
+            }
+            AsciiCameraEffect::Wave { amplitude, wavelength, speed, duration } => {
+                effects.wave = Some(AsciiWave {
+                    amplitude,
+                    wavelength: wavelength.abs().max(0.01),
+                    speed,
+                    duration: duration.max(0.0),
+                    elapsed: 0.0,
+                });
+            }
+        }
+    }
+
+    // Moves the scene by up to `cells` whole cells in a random direction every frame, calming
+    // down over `seconds`.
+    pub fn shake(&mut self, cells: f32, seconds: f32) {
+        self.trigger(AsciiCameraEffect::Shake { cells, duration: seconds });
+    }
+
+    // Draws every character of the scene in `color` for the next `frames` frames.
+    pub fn flash(&mut self, color: Color, frames: u32) {
+        self.trigger(AsciiCameraEffect::Flash { color, frames });
+    }
+
+    // Swaps the characters of the scene with their background for the next `frames` frames.
+    pub fn flash_invert(&mut self, frames: u32) {
+        self.trigger(AsciiCameraEffect::Invert { frames });
+    }
+
+    // Moves each row of the scene sideways along a sine wave that is `wavelength` rows long and up
+    // to `amplitude` cells high. The wave scrolls by `speed` radians a second and fades out over
+    // `seconds`, or keeps going when `seconds` is 0.0.
+    pub fn wave(&mut self, amplitude: f32, wavelength: f32, speed: f32, seconds: f32) {
+        self.trigger(AsciiCameraEffect::Wave { amplitude, wavelength, speed, duration: seconds });
+    }
+
+    // The old way of sizing the grid, before it could be sized by anything but its columns.
+    #[deprecated(note = "set `sizing` to `GridSizing::Columns` instead")]
+    pub fn set_screen_colummns(&mut self, columns: f32) {
+        self.sizing = GridSizing::Columns(columns);
+    }
+
+    // The columns of the grid. Only the same as the columns that were set while the sizing is
+    // `GridSizing::Columns`.
+    #[deprecated(note = "read `sizing`, or the columns of the `AsciiGridInfo` of the camera")]
+    pub fn screen_colummns(&self) -> f32 {
+        match self.sizing {
+            GridSizing::Columns(columns) => columns,
+            _ => self.target_resolution.x,
+        }
+    }
+
+    pub fn clear_effects(&mut self) {
+        self.effects = AsciiCameraEffects { seed: self.effects.seed, ..default() };
+    }
+
+    pub fn has_effects(&self) -> bool {
+        self.effects.is_active()
+    }
+
+    // How many whole cells the scene is moved by the shake this frame.
+    pub fn shake_offset(&self) -> IVec2 {
+        self.effects.shake_offset
+    }
+
+    // Applies exposure, contrast, gamma and invert to the brightness of a scene pixel, where
+    // `average` is the average brightness of the scene. This needs to match ascii.wgsl.
+    pub fn adjust_value(&self, value: f32, average: f32) -> f32 {
+        let value = value * self.exposure_for(average);
+        let value = ((value - 0.5) * self.contrast + 0.5).clamp(0.0, 1.0);
+        let value = value.powf(1.0 / self.gamma.max(0.01));
+        if self.invert {
+            1.0 - value
+        } else {
+            value
+        }
+    }
+}
+
+// encase checks every field with a function that is never called. It puts those next to the
+// struct rather than in its impl, so no attribute on the struct or a field reaches them.
+#[allow(dead_code)]
+mod settings_buffer {
+    use bevy::{prelude::*, render::render_resource::ShaderType};
+
+    #[derive(ShaderType, Clone, PartialEq, Default, Debug)]
+    pub struct AsciiShaderSettingsBuffer {
+        // The x, y, width and height of the camera viewport in pixels.
+        pub viewport: Vec4,
+        // The width and height of a cell in pixels.
+        pub pixels_per_character: Vec2,
+        pub exposure: f32,
+        pub contrast: f32,
+        pub gamma: f32,
+        pub invert: u32,
+        pub auto_exposure: u32,
+        pub auto_exposure_min: f32,
+        pub auto_exposure_max: f32,
+        // The shake, in whole cells.
+        pub shake_offset: Vec2,
+        pub wave_amplitude: f32,
+        pub wave_frequency: f32,
+        pub wave_phase: f32,
+        // 0 is no flash, 1 inverts the scene and 2 draws it in `flash_color`.
+        pub flash: u32,
+        pub flash_color: u32,
+        // Seconds since the app started. This is only kept up to date when there is an `AsciiCellHook`.
+        pub time: f32,
+        // How far the brightness of a cell has to move before it gets a new character, 0 is off.
+        pub stability: f32,
+        // How many cells of the scene a cell of the overlay covers across and down.
+        pub ui_scale: u32,
+        // Applied to every color the shader draws, see `ColorBlindFilter`.
+        pub color_matrix: Mat3,
+        // WebGL2 structs must be 16 byte aligned.
+        #[cfg(feature = "webgl2")]
+        pub _webgl2_padding: Vec2,
+    }
+}
+
+pub use settings_buffer::AsciiShaderSettingsBuffer;
+
+//=============================================================================
+//             Camera Effects
+//=============================================================================
+
+// Feedback effects that work on the character grid of the scene. They can be started with the
+// methods on `AsciiCamera`, or by sending an `AsciiCameraEffectEvent`.
+#[derive(Clone, Copy, Debug, PartialEq, Reflect)]
+pub enum AsciiCameraEffect {
+    Shake { cells: f32, duration: f32 },
+    Flash { color: Color, frames: u32 },
+    Invert { frames: u32 },
+    Wave { amplitude: f32, wavelength: f32, speed: f32, duration: f32 },
+}
+
+#[derive(Event, Clone, Copy, Debug, PartialEq)]
+pub struct AsciiCameraEffectEvent {
+    pub camera: Entity,
+    pub effect: AsciiCameraEffect,
+}
+
+impl AsciiCameraEffectEvent {
+    pub fn new(camera: Entity, effect: AsciiCameraEffect) -> Self {
+        AsciiCameraEffectEvent { camera, effect }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct AsciiShake {
+    cells: f32,
+    duration: f32,
+    elapsed: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct AsciiWave {
+    amplitude: f32,
+    wavelength: f32,
+    speed: f32,
+    duration: f32,
+    elapsed: f32,
+}
+
+impl AsciiWave {
+    fn amplitude(&self) -> f32 {
+        if self.duration <= 0.0 {
+            return self.amplitude;
+        }
+        self.amplitude * (1.0 - self.elapsed / self.duration).max(0.0)
+    }
+}
+
+#[derive(Clone, Default, Debug, PartialEq)]
+struct AsciiCameraEffects {
+    shake: Option<AsciiShake>,
+    shake_offset: IVec2,
+    flash: Option<(Color, u32)>,
+    invert_frames: u32,
+    wave: Option<AsciiWave>,
+    seed: u64,
+}
+
+impl AsciiCameraEffects {
+    fn is_active(&self) -> bool {
+        self.shake.is_some()
+            || self.shake_offset != IVec2::ZERO
+            || self.flash.is_some()
+            || self.invert_frames > 0
+            || self.wave.is_some()
+    }
+
+    // Moves every effect on by a frame and throws away the ones that are done. Flashes are
+    // counted in frames that were drawn, so this runs before anything can start a new one.
+    fn tick(&mut self, delta: f32) {
+        self.shake_offset = IVec2::ZERO;
+        if let Some(shake) = self.shake.as_mut() {
+            shake.elapsed += delta;
+            if shake.elapsed >= shake.duration {
+                self.shake = None;
+            }
+        }
+
+        if let Some((_, frames)) = self.flash.as_mut() {
+            *frames -= 1;
+            if *frames == 0 {
+                self.flash = None;
+            }
+        }
+        self.invert_frames = self.invert_frames.saturating_sub(1);
+
+        if let Some(wave) = self.wave.as_mut() {
+            wave.elapsed += delta;
+            if wave.duration > 0.0 && wave.elapsed >= wave.duration {
+                self.wave = None;
+            }
+        }
+    }
+
+    // What the shader is given from the effects, the rest only keeps count.
+    #[allow(clippy::type_complexity)]
+    fn drawn(&self) -> (IVec2, Option<Color>, bool, Option<(f32, f32, f32)>) {
+        (
+            self.shake_offset,
+            self.flash.map(|(color, _)| color),
+            self.invert_frames > 0,
+            self.wave.map(|wave| (wave.amplitude(), wave.wavelength, wave.elapsed * wave.speed)),
+        )
+    }
+
+    // Picks the whole cell offset of the shake for this frame, so a shake that was just started
+    // is already seen.
+    fn roll_shake(&mut self) {
+        let Some(shake) = self.shake else {
+            return;
         };
+        let cells = shake.cells * (1.0 - shake.elapsed / shake.duration.max(f32::EPSILON)).max(0.0);
+        self.seed = noise_hash(self.seed, 0, 0);
+        let x = (self.seed >> 40) as f32 / (1u64 << 24) as f32 * 2.0 - 1.0;
+        let y = ((self.seed >> 16) & 0xFFFFFF) as f32 / (1u64 << 24) as f32 * 2.0 - 1.0;
+        self.shake_offset = (Vec2::new(x, y) * cells).round().as_ivec2();
+    }
+}
 
-        let mut dyn_buffer: DynamicUniformBuffer<AsciiShaderSettingsBuffer> =
-            DynamicUniformBuffer::default();
-        let writer = dyn_buffer.get_writer(1, device, queue);
-        writer.unwrap().write(&ascii_buffer);
+// The timers of the effects move every frame, but the camera is only changed when what is drawn
+// changes, so cameras that only redraw on change are not redrawn for a flash counting down.
+fn tick_camera_effects(mut ascii_cameras: Query<&mut AsciiCamera>, time: Res<Time>) {
+    for mut ascii_camera in ascii_cameras.iter_mut() {
+        if !ascii_camera.effects.is_active() {
+            continue;
+        }
+        let drawn = ascii_camera.effects.drawn();
+        ascii_camera.bypass_change_detection().effects.tick(time.delta_seconds());
+        if ascii_camera.effects.drawn() != drawn {
+            ascii_camera.set_changed();
+        }
+    }
+}
 
-        dyn_buffer
+// Runs after gameplay so effects that are started this frame are seen this frame.
+fn update_camera_effects(
+    mut ascii_cameras: Query<(Entity, &mut AsciiCamera)>,
+    mut events: EventReader<AsciiCameraEffectEvent>,
+) {
+    for event in events.read() {
+        if let Ok((_, mut ascii_camera)) = ascii_cameras.get_mut(event.camera) {
+            ascii_camera.trigger(event.effect);
+        }
     }
 
-    pub fn target_res(&self) -> &Vec2 {
-        &self.target_resolution
+    for (entity, mut ascii_camera) in ascii_cameras.iter_mut() {
+        if ascii_camera.effects.shake.is_none() {
+            continue;
+        }
+        let drawn = ascii_camera.effects.drawn();
+        let effects = &mut ascii_camera.bypass_change_detection().effects;
+        if effects.seed == 0 {
+            effects.seed = entity.to_bits();
+        }
+        effects.roll_shake();
+        if ascii_camera.effects.drawn() != drawn {
+            ascii_camera.set_changed();
+        }
     }
 }
 
-#[derive(ShaderType)]
-pub struct AsciiShaderSettingsBuffer {
-    pub pixels_per_character: f32,
-    // WebGL2 structs must be 16 byte aligned.
-    #[cfg(feature = "webgl2")]
-    pub _webgl2_padding: Vec3,
+//=============================================================================
+//             Grid Info
+//=============================================================================
+
+// The size of the character grid of an `AsciiCamera` and of its cells. It is added to every ascii
+// camera and kept up to date, so it does not have to be worked out from the window again.
+#[derive(Component, Clone, Copy, Default, PartialEq, Debug, Reflect)]
+pub struct AsciiGridInfo {
+    pub columns: u32,
+    pub rows: u32,
+    // The width of a cell in physical pixels.
+    pub cell_size: f32,
+    // The height of a cell in physical pixels. It is the width, unless the `AsciiFont` has glyphs
+    // that are not square or the sizing is `GridSizing::Fixed`.
+    pub cell_height: f32,
+    // The scale factor of the window the camera renders to, 1.0 for images.
+    pub scale_factor: f32,
+    // The columns and rows of the ui, see `AsciiUiScale`.
+    pub ui_columns: u32,
+    pub ui_rows: u32,
+}
+
+impl AsciiGridInfo {
+    pub fn size(&self) -> UVec2 {
+        UVec2::new(self.columns, self.rows)
+    }
+
+    pub fn ui_size(&self) -> UVec2 {
+        UVec2::new(self.ui_columns, self.ui_rows)
+    }
+
+    // The width of a cell in logical pixels, like the ones of the cursor position.
+    pub fn logical_cell_size(&self) -> f32 {
+        self.cell_size / self.scale_factor.max(f32::EPSILON)
+    }
+
+    // The width and height of a cell in logical pixels.
+    pub fn logical_cell_pixels(&self) -> Vec2 {
+        Vec2::new(self.cell_size, self.cell_height) / self.scale_factor.max(f32::EPSILON)
+    }
+
+    // How much taller than wide a cell is.
+    pub fn cell_aspect(&self) -> f32 {
+        self.cell_height / self.cell_size.max(f32::EPSILON)
+    }
+}
+
+// Sent when the grid of a camera changes size, like when the window is resized or
+// the `sizing` or `AsciiUiScale` is changed, or when its cells change shape with a new
+// `AsciiFont`. It is also sent for the first grid of a new camera.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct AsciiGridResized {
+    pub camera: Entity,
+    pub grid: AsciiGridInfo,
 }
 
 //=============================================================================
 //             Shader Settings
 //=============================================================================
 
-fn update_target_resolution(
-    mut ascii_cameras: Query<(&mut AsciiCamera, &Camera)>,
+#[allow(clippy::type_complexity)]
+pub(crate) fn update_target_resolution(
+    mut commands: Commands,
+    mut ascii_cameras: Query<(Entity, &mut AsciiCamera, &Camera, Option<&mut AsciiGridInfo>, Option<&AsciiUiScale>)>,
     windows: Query<&Window, Without<PrimaryWindow>>,
     primary_window: Query<&Window, With<PrimaryWindow>>,
     images: Res<Assets<Image>>,
+    font: Option<Res<AsciiFont>>,
+    mut resized: EventWriter<AsciiGridResized>,
 ) {
-    for (mut ascii_camera, camera) in ascii_cameras.iter_mut() {
-        let res: (f32, f32) = match &camera.target {
+    let cell_aspect = font.map(|font| font.cell_aspect()).unwrap_or(1.0);
+    for (entity, mut ascii_camera, camera, grid_info, ui_scale) in ascii_cameras.iter_mut() {
+        let (res, scale_factor): ((f32, f32), f32) = match &camera.target {
             RenderTarget::Window(window_ref) => match window_ref {
                 WindowRef::Primary => {
                     let primary_window = primary_window.single();
                     (
-                        primary_window.physical_width() as f32,
-                        primary_window.physical_height() as f32,
+                        (primary_window.physical_width() as f32, primary_window.physical_height() as f32),
+                        primary_window.scale_factor(),
                     )
                 }
                 WindowRef::Entity(entity) => {
                     let window = windows.get(*entity).unwrap();
                     (
-                        window.physical_width() as f32,
-                        window.physical_height() as f32,
+                        (window.physical_width() as f32, window.physical_height() as f32),
+                        window.scale_factor(),
                     )
                 }
             },
             RenderTarget::Image(image) => {
                 let image = images.get(image.id()).unwrap();
-                (image.width() as f32, image.height() as f32)
+                ((image.width() as f32, image.height() as f32), 1.0)
             }
             RenderTarget::TextureView(_) => return,
         };
 
-        let pixels_per_character = (res.0 / ascii_camera.screen_colummns).floor();
+        // Cameras that only render to part of the target, like in split screen, get a grid for
+        // their viewport.
+        let res = camera
+            .viewport
+            .as_ref()
+            .map(|viewport| (viewport.physical_size.x as f32, viewport.physical_size.y as f32))
+            .unwrap_or(res);
+
+        let (target_resolution, cell_size) = ascii_camera.sizing.grid_with_aspect(Vec2::new(res.0, res.1), cell_aspect);
+
+        // Only written when it changed, so on demand cameras are not redrawn every frame.
+        if ascii_camera.target_resolution != target_resolution {
+            ascii_camera.target_resolution = target_resolution;
+        }
+        let ui_scale = ui_scale.map(|scale| scale.0.max(1)).unwrap_or(1);
+        if ascii_camera.ui_scale != ui_scale {
+            ascii_camera.ui_scale = ui_scale;
+        }
+
+        let ui_resolution = ascii_camera.ui_res();
+        let grid = AsciiGridInfo {
+            columns: target_resolution.x as u32,
+            rows: target_resolution.y as u32,
+            cell_size: cell_size.x,
+            cell_height: cell_size.y,
+            scale_factor,
+            ui_columns: ui_resolution.x as u32,
+            ui_rows: ui_resolution.y as u32,
+        };
+        match grid_info {
+            Some(mut grid_info) if *grid_info != grid => {
+                let is_reshaped = (grid_info.cell_aspect() - grid.cell_aspect()).abs() > 0.001;
+                if grid_info.size() != grid.size() || grid_info.ui_size() != grid.ui_size() || is_reshaped {
+                    resized.send(AsciiGridResized { camera: entity, grid });
+                }
+                *grid_info = grid;
+            }
+            Some(_) => {}
+            None => {
+                commands.entity(entity).insert(grid);
+                resized.send(AsciiGridResized { camera: entity, grid });
+            }
+        }
+    }
+}
+
+// Requests were seen by the render world at the end of the last frame.
+fn clear_redraw_requests(mut ascii_cameras: Query<&mut AsciiCamera>) {
+    for mut ascii_camera in ascii_cameras.iter_mut() {
+        if ascii_camera.redraw_requested {
+            ascii_camera.bypass_change_detection().redraw_requested = false;
+        }
+    }
+}
+
+// On demand cameras copy their last output back onto the screen, so their main textures have to
+// allow being copied into.
+fn update_main_texture_usages(
+    mut ascii_cameras: Query<(&AsciiCamera, &mut CameraMainTextureUsages), Changed<AsciiCamera>>,
+) {
+    for (ascii_camera, mut usages) in ascii_cameras.iter_mut() {
+        if ascii_camera.on_demand && !usages.0.contains(TextureUsages::COPY_DST) {
+            usages.0 |= TextureUsages::COPY_DST;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+
+    fn effects_world() -> (World, Entity) {
+        let mut world = World::new();
+        world.init_resource::<Time>();
+        world.init_resource::<Events<AsciiCameraEffectEvent>>();
+        let camera = world.spawn(AsciiCamera::default()).id();
+        world.clear_trackers();
+        (world, camera)
+    }
+
+    // Runs a frame of the effects and tells if the camera was changed.
+    fn frame(world: &mut World, camera: Entity) -> bool {
+        world.run_system_once(tick_camera_effects);
+        world.run_system_once(update_camera_effects);
+        world.resource_mut::<Events<AsciiCameraEffectEvent>>().clear();
+        let is_changed = world.entity(camera).get_ref::<AsciiCamera>().unwrap().is_changed();
+        world.clear_trackers();
+        is_changed
+    }
+
+    #[test]
+    fn grid_sizing_modes() {
+        let resolution = Vec2::new(800.0, 600.0);
+        assert_eq!(GridSizing::Columns(80.0).grid(resolution), (Vec2::new(80.0, 60.0), 10.0));
+        assert_eq!(GridSizing::Rows(25.0).grid(resolution), (Vec2::new(33.0, 25.0), 24.0));
+        assert_eq!(GridSizing::CellPixels(16).grid(resolution), (Vec2::new(50.0, 37.0), 16.0));
+        assert_eq!(GridSizing::Fixed { cols: 100, rows: 30 }.grid(resolution), (Vec2::new(100.0, 30.0), 8.0));
+        // Less than one column is one column.
+        assert_eq!(GridSizing::Columns(0.0).grid(resolution), (Vec2::new(1.0, 0.0), 800.0));
+    }
+
+    #[test]
+    fn grid_sizing_with_tall_cells() {
+        let resolution = Vec2::new(800.0, 600.0);
+        assert_eq!(GridSizing::Columns(80.0).grid_with_aspect(resolution, 2.0), (Vec2::new(80.0, 30.0), Vec2::new(10.0, 20.0)));
+        // The rows are kept and the cells get narrower.
+        assert_eq!(GridSizing::Rows(25.0).grid_with_aspect(resolution, 2.0), (Vec2::new(66.0, 25.0), Vec2::new(12.0, 24.0)));
+        assert_eq!(GridSizing::CellPixels(16).grid_with_aspect(resolution, 2.0), (Vec2::new(50.0, 18.0), Vec2::new(16.0, 32.0)));
+        // A fixed grid is stretched over the viewport, whatever the font.
+        let fixed = GridSizing::Fixed { cols: 100, rows: 30 };
+        assert_eq!(fixed.grid_with_aspect(resolution, 2.0), (Vec2::new(100.0, 30.0), Vec2::new(8.0, 20.0)));
+    }
+
+    #[test]
+    fn fixed_grids_give_the_shader_the_height_of_a_cell() {
+        let camera = AsciiCamera { sizing: GridSizing::Fixed { cols: 100, rows: 30 }, ..default() };
+        assert_eq!(camera.settings(UVec4::new(0, 0, 800, 600)).pixels_per_character, Vec2::new(8.0, 20.0));
+        let camera = AsciiCamera { sizing: GridSizing::Columns(80.0), ..default() };
+        assert_eq!(camera.settings(UVec4::new(0, 0, 800, 600)).pixels_per_character, Vec2::new(10.0, 10.0));
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn screen_colummns_still_sets_the_columns() {
+        let mut camera = AsciiCamera::default();
+        camera.set_screen_colummns(120.0);
+        assert_eq!(camera.sizing, GridSizing::Columns(120.0));
+        assert_eq!(camera.screen_colummns(), 120.0);
+    }
+
+    #[test]
+    fn cameras_without_effects_are_not_changed() {
+        let (mut world, camera) = effects_world();
+        assert!(!frame(&mut world, camera));
+        assert!(!frame(&mut world, camera));
+    }
+
+    #[test]
+    fn effects_only_change_the_camera_when_what_is_drawn_changes() {
+        let (mut world, camera) = effects_world();
+        world.send_event(AsciiCameraEffectEvent::new(camera, AsciiCameraEffect::Flash { color: Color::Red, frames: 3 }));
+        assert!(frame(&mut world, camera));
+
+        // The flash counts down without changing how it looks, until it is gone.
+        assert!(!frame(&mut world, camera));
+        assert!(!frame(&mut world, camera));
+        assert!(frame(&mut world, camera));
+        assert!(!world.get::<AsciiCamera>(camera).unwrap().has_effects());
+        assert!(!frame(&mut world, camera));
+    }
 
-        let target_resolution = Vec2::new(
-            (res.0 / pixels_per_character).floor(),
-            (res.1 / pixels_per_character).floor(),
-        );
+    #[test]
+    fn effects_run_without_the_ui() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), bevy::input::InputPlugin, AsciiCameraPlugin))
+            .init_asset::<Image>();
+        let camera = app.world.spawn(AsciiCamera::default()).id();
+        app.world.send_event(AsciiCameraEffectEvent::new(camera, AsciiCameraEffect::Flash { color: Color::Red, frames: 2 }));
 
-        ascii_camera.target_resolution = target_resolution
+        app.update();
+        assert!(app.world.get::<AsciiCamera>(camera).unwrap().has_effects());
+        app.update();
+        app.update();
+        assert!(!app.world.get::<AsciiCamera>(camera).unwrap().has_effects());
     }
 }
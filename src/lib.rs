@@ -1,31 +1,203 @@
 mod render;
 mod ui;
 mod ascii;
+mod record;
+#[cfg(all(feature = "terminal", not(target_arch = "wasm32")))]
+mod terminal;
 
 pub mod prelude {
     pub use crate::ascii::*;
+    pub use crate::render::AsciiNodePlacement;
+    pub use crate::render::AsciiShaderNodeId;
+    pub use crate::render::ascii::AsciiCellHook;
+    pub use crate::render::font::AsciiFont;
+    pub use crate::render::font::AsciiFontPages;
+    pub use crate::render::font::AsciiTileAtlas;
+    pub use crate::render::style::AsciiStyleOverride3d;
+    pub use crate::render::style::AsciiHighlight;
+    pub use crate::render::grid::AsciiSceneGridPlugin;
+    pub use crate::render::grid::AsciiSceneGridSource;
+    pub use crate::render::grid::AsciiSceneGrid;
+    pub use crate::render::grid::AsciiSceneCell;
+    pub use crate::render::screenshot::AsciiScreenshotEvent;
+    pub use crate::record::AsciiRecorderPlugin;
+    pub use crate::record::AsciiRecorder;
+    pub use crate::record::AsciiRecording;
+    pub use crate::record::AsciiRecordedFrame;
+    #[cfg(all(feature = "terminal", not(target_arch = "wasm32")))]
+    pub use crate::terminal::AsciiTerminalPlugin;
+    #[cfg(all(feature = "terminal", not(target_arch = "wasm32")))]
+    pub use crate::terminal::AsciiTerminal;
     pub use crate::ui::bounds::AsciiNode;
     pub use crate::ui::component::button::AsciiButton;
+    pub use crate::ui::component::window::AsciiWindow;
+    pub use crate::ui::component::window::AsciiWindowClosedEvent;
+    pub use crate::ui::component::window::AsciiWindowMinimizedEvent;
+    pub use crate::ui::component::slider::AsciiSlider;
+    pub use crate::ui::component::slider::AsciiSliderChangedEvent;
+    pub use crate::ui::component::spinner::AsciiSpinner;
+    pub use crate::ui::component::spinner::AsciiSpinnerChangedEvent;
+    pub use crate::ui::component::tabs::AsciiTabs;
+    pub use crate::ui::component::tabs::AsciiTabChangedEvent;
+    pub use crate::ui::component::toggle::AsciiToggle;
+    pub use crate::ui::component::toggle::AsciiToggleChangedEvent;
+    pub use crate::ui::component::segmented::AsciiSegmented;
+    pub use crate::ui::component::segmented::AsciiSegmentedChangedEvent;
+    pub use crate::ui::component::number::AsciiNumber;
+    pub use crate::ui::component::list::AsciiList;
+    pub use crate::ui::component::list::AsciiListSelectedEvent;
+    pub use crate::ui::component::list::AsciiListSubmittedEvent;
+    pub use crate::ui::component::text_input::AsciiTextInput;
+    pub use crate::ui::component::text_input::AsciiTextInputChangedEvent;
+    pub use crate::ui::component::text_input::AsciiTextInputSubmittedEvent;
+    pub use crate::ui::component::console::AsciiConsole;
+    pub use crate::ui::component::console::AsciiConsoleCommandEvent;
+    pub use crate::ui::component::autocomplete::AsciiAutocomplete;
+    pub use crate::ui::component::autocomplete::AsciiAutocompleteSelectedEvent;
+    pub use crate::ui::component::autocomplete::AsciiAutocompleteSubmittedEvent;
+    pub use crate::ui::component::diagnostics::AsciiDiagnosticsOverlayPlugin;
+    pub use crate::ui::component::diagnostics::AsciiDiagnosticsPanel;
+    pub use crate::ui::component::diagnostics::AsciiUiDiagnosticsPanel;
+    pub use crate::ui::diagnostics::AsciiUiDiagnosticsPlugin;
+    pub use crate::ui::diagnostics::AsciiUiDiagnostics;
+    pub use crate::ui::component::bevy_ui::AsciiBevyUiBridgePlugin;
+    pub use crate::ui::component::bevy_ui::AsciiBevyUiBridge;
+    pub use crate::ui::component::replay::AsciiReplay;
+    pub use crate::ui::component::minimap::AsciiMinimap;
+    pub use crate::ui::component::minimap::AsciiMinimapMarker;
+    pub use crate::ui::component::minimap::AsciiMinimapAxes;
+    pub use crate::ui::component::noise::AsciiNoisePanel;
+    pub use crate::ui::component::tilemap::AsciiTilemap;
+    pub use crate::ui::component::tilemap::AsciiTile;
+    pub use crate::ui::component::animation::AsciiAnimatedGlyph;
+    pub use crate::ui::component::animation::AsciiAnimationClip;
+    pub use crate::ui::component::animation::AsciiAnimationFrame;
+    pub use crate::ui::component::animation::AsciiAnimationMode;
+    pub use crate::ui::component::animation::AsciiAnimationFinishedEvent;
+    pub use crate::ui::component::big_text::AsciiBigText;
+    pub use crate::ui::component::rich_text::AsciiRichText;
+    pub use crate::ui::component::rich_text::AsciiTextSpanClicked;
+    pub use crate::ui::component::virtual_keyboard::AsciiVirtualKeyboard;
+    pub use crate::ui::component::virtual_keyboard::AsciiVirtualKey;
+    pub use crate::ui::component::virtual_keyboard::AsciiVirtualKeyPressedEvent;
+    pub use crate::ui::markup::AsciiMarkup;
+    pub use crate::ui::big_text::AsciiBigFont;
     pub use crate::ui::position::AsciiPosition;
+    pub use crate::ui::position::AsciiDesiredSize;
+    pub use crate::ui::position::AsciiContentPadding;
+    pub use crate::ui::position::AsciiIntrinsicSize;
+    pub use crate::ui::validation::AsciiUiValidation;
+    pub use crate::ui::bounds::AsciiPinned;
+    pub use crate::ui::flex::AsciiFlex;
+    pub use crate::ui::flex::AsciiFlexDirection;
+    pub use crate::ui::flex::AsciiFlexJustify;
+    pub use crate::ui::flex::AsciiFlexAlign;
+    pub use crate::ui::breakpoint::AsciiBreakpoint;
+    pub use crate::ui::breakpoint::AsciiBreakpoints;
+    pub use crate::ui::breakpoint::AsciiResponsive;
+    pub use crate::ui::breakpoint::AsciiResponsivePlugin;
     pub use crate::ui::HorizontalAlignment;
     pub use crate::ui::VerticalAlignment;
-    pub use crate::ui::command::AsciiUiCommandExtention;
+    pub use crate::ui::Padding;
+    pub use crate::ui::command::{AsciiUiCommandExtention, AsciiUiCommands};
     pub use crate::ui::AsciiUi;
+    pub use crate::ui::AsciiUiScale;
     pub use crate::ui::util::Value;
     pub use crate::ui::component::AsciiComponent;
+    pub use crate::ui::component::AsciiWidget;
+    pub use crate::ui::component::AsciiUpdateMode;
+    pub use crate::ui::component::AsciiUpdateRequestEvent;
+    pub use crate::ui::component::AsciiComponentAppExtention;
+    pub use crate::ui::event::AsciiUiEvent;
+    pub use crate::ui::event::AsciiUiEventPlugin;
+    pub use crate::ui::event::AsciiUiEventAppExtention;
+    pub use crate::ui::event::AsciiEventListener;
+    pub use crate::ui::event::AsciiEventContext;
+    pub use crate::ui::event::AsciiEventPhase;
+    pub use crate::ui::gizmos::AsciiGizmos;
+    pub use crate::ui::gizmos::AsciiGizmoDraw;
+    pub use crate::ui::palette::AsciiPalettePlugin;
+    pub use crate::ui::palette::AsciiPalette;
+    pub use crate::ui::palette::ColorRef;
+    pub use crate::ui::palette::register_color;
     pub use crate::ui::bounds::AsciiBounds;
     pub use crate::ui::util::Variable;
     pub use crate::ui::util::break_string_into_lines;
     pub use crate::ui::util::AsciiComponentHoverExitedEvent;
     pub use crate::ui::util::AsciiComponentHoverEnteredEvent;
     pub use crate::ui::util::AsciiComponentButtonClicked as AsciiComponentButtonClickedEvent;
+    pub use crate::ui::util::AsciiHitTest;
+    pub use crate::ui::cursor::AsciiCursorGlyph;
+    pub use crate::ui::util::{AsciiHotkey, AsciiHotkeyDisabled, AsciiKeyboardFocus};
+    pub use crate::ui::clipboard::AsciiClipboard;
     pub use crate::ui::buffer::AsciiBuffer;
+    pub use crate::ui::buffer::AsciiSurface;
     pub use crate::ui::buffer::AsciiBoxDrawer;
     pub use crate::ui::buffer::AsciiTextDrawer;
+    pub use crate::ui::buffer::AsciiTextCache;
+    pub use crate::ui::buffer::AsciiChartDrawer;
+    pub use crate::ui::buffer::AsciiNoiseDrawer;
+    pub use crate::ui::buffer::DrawBatch;
+    pub use crate::ui::buffer::AsciiTextLayout;
     pub use crate::ui::component::AsciiComponentPlugin;
+    pub use crate::ui::component::AsciiDefaultWidgetsPlugin;
+    pub use crate::ui::AsciiUiPlugin;
+    pub use crate::ui::AsciiUiSchedule;
+    pub use crate::ui::AsciiUiSet;
+    pub use crate::ui::AsciiUiTarget;
+    pub use crate::ui::AsciiUiRoot;
+    pub use crate::ui::AsciiRedrawTimer;
+    pub use crate::ui::TextOverflow;
+    pub use crate::ui::util::AsciiInputPlugin;
+    pub use crate::ui::touch::AsciiTouchPlugin;
+    pub use crate::ui::touch::AsciiTouchSettings;
+    pub use crate::ui::touch::AsciiTouch;
+    pub use crate::ui::touch::AsciiComponentLongPressedEvent;
+    pub use crate::ui::component::AsciiComponentRegistry;
+    pub use crate::ui::component::AsciiComponents;
     pub use crate::ui::AsciiMarkDirtyEvent;
+    pub use crate::ui::AsciiMarkLiveDirtyEvent;
     pub use crate::ui::BorderType;
+    pub use crate::ui::BorderStyle;
     pub use crate::ui::character::Color as AsciiColor;
     pub use crate::ui::character::AsciiCharacter;
+    pub use crate::ui::character::AsciiBlendMode;
+    pub use crate::ui::character::AsciiGlyph;
+    pub use crate::ui::encoding::CellEncoding;
+    pub use crate::ui::encoding::EncodedCell;
+    pub use crate::ui::opacity::AsciiOpacity;
+    pub use crate::ui::lightmap::AsciiLightmap;
+    pub use crate::ui::meta::AsciiCellMeta;
+    pub use crate::ui::meta::AsciiMetaGrid;
+    pub use crate::ui::transition::AsciiTransitionEffect;
+    pub use crate::ui::transition::AsciiWipeDirection;
+    pub use crate::ui::transition::AsciiTransitionEvent;
+    pub use crate::ui::transition::AsciiTransitionFinishedEvent;
+    pub use crate::ui::screen::AsciiScreenPlugin;
+    pub use crate::ui::screen::AsciiScreen;
+    pub use crate::ui::screen::AsciiScreenMode;
+    pub use crate::ui::opacity::AsciiFade;
+    pub use crate::ui::overlay::AsciiOverlay;
+    pub use crate::ui::particles::AsciiParticleEmitter;
+    pub use crate::ui::particles::AsciiEmitterPosition;
+    pub use crate::ui::billboard::AsciiBillboard;
+    pub use crate::ui::time::AsciiUiTime;
+    pub use crate::ui::time::AsciiUiTimeSource;
+    pub use crate::ui::accessibility::AsciiAccessibilityPlugin;
+    pub use crate::ui::accessibility::AsciiAccessibilityTree;
+    pub use crate::ui::accessibility::AsciiAccessibilityNode;
+    pub use crate::ui::accessibility::AsciiAccessible;
+    pub use crate::ui::accessibility::AsciiAccessibleRole;
+    pub use crate::ui::accessibility::AsciiAccessibilityFocusChangedEvent;
+    pub use crate::ui::accessibility::AsciiAccessibilityValueChangedEvent;
     pub use crate::ui::character::Character;
+    pub use crate::ui::debug::AsciiDebugOverlayPlugin;
+    pub use crate::ui::debug::AsciiDebugOverlay;
+    pub use crate::ui::debug::AsciiDebugColors;
+    #[cfg(feature = "serialize")]
+    pub use crate::ui::serialize::AsciiUiSnapshot;
+    #[cfg(feature = "serialize")]
+    pub use crate::ui::serialize::AsciiUiSnapshotNode;
+    #[cfg(feature = "serialize")]
+    pub use crate::ui::serialize::AsciiWidgetSnapshot;
 }
\ No newline at end of file
@@ -0,0 +1,428 @@
+use std::{
+    fmt::Write as _,
+    fs,
+    io::{self, ErrorKind},
+    path::Path,
+};
+
+use bevy::prelude::*;
+
+use crate::{
+    render::grid::{AsciiSceneGrid, AsciiSceneGridPlugin},
+    ui::character::{Character, Color},
+};
+
+//=============================================================================
+//             Recorder Plugin
+//=============================================================================
+
+// Records the character grid of the camera with an `AsciiSceneGridSource` while the
+// `AsciiRecorder` is running. Frames are taken from the `AsciiSceneGrid`, so a frame is
+// captured every `interval` rendered frames.
+pub struct AsciiRecorderPlugin {
+    pub interval: u32,
+}
+
+impl Default for AsciiRecorderPlugin {
+    fn default() -> Self {
+        AsciiRecorderPlugin { interval: 2 }
+    }
+}
+
+impl Plugin for AsciiRecorderPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<AsciiSceneGridPlugin>() {
+            app.add_plugins(AsciiSceneGridPlugin {
+                interval: self.interval,
+            });
+        }
+
+        app.init_resource::<AsciiRecorder>()
+            .add_systems(Update, record_frames.run_if(resource_changed::<AsciiSceneGrid>));
+    }
+}
+
+//=============================================================================
+//             Recorder
+//=============================================================================
+
+#[derive(Resource, Default)]
+pub struct AsciiRecorder {
+    recording: Option<AsciiRecording>,
+    started_at: Option<f32>,
+    changes_only: bool,
+}
+
+impl AsciiRecorder {
+    // Starts a new recording, dropping the one that was in progress.
+    pub fn start(&mut self) {
+        self.recording = Some(AsciiRecording::default());
+        self.started_at = None;
+    }
+
+    // Stops recording and returns what was recorded.
+    pub fn stop(&mut self) -> Option<AsciiRecording> {
+        self.started_at = None;
+        self.recording.take()
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    // Only keep frames that differ from the previous frame.
+    pub fn changes_only(&mut self, changes_only: bool) {
+        self.changes_only = changes_only;
+    }
+
+    pub fn recording(&self) -> Option<&AsciiRecording> {
+        self.recording.as_ref()
+    }
+}
+
+fn record_frames(mut recorder: ResMut<AsciiRecorder>, grid: Res<AsciiSceneGrid>, time: Res<Time<Real>>) {
+    if !recorder.is_recording() || grid.is_empty() {
+        return;
+    }
+
+    let now = time.elapsed_seconds();
+    let started_at = *recorder.started_at.get_or_insert(now);
+    let changes_only = recorder.changes_only;
+    let Some(recording) = &mut recorder.recording else { return };
+
+    if recording.frames.is_empty() {
+        recording.width = grid.width();
+        recording.height = grid.height();
+    } else if recording.width != grid.width() || recording.height != grid.height() {
+        // The recording keeps the size of its first frame.
+        return;
+    }
+
+    let mut cells = Vec::with_capacity((grid.width() * grid.height()) as usize);
+    for y in 0..grid.height() as i32 {
+        for x in 0..grid.width() as i32 {
+            cells.push(grid.visible(x, y).unwrap_or((Character::Space, Color::White, Color::Black)));
+        }
+    }
+
+    if changes_only && recording.frames.last().map(|frame| frame.cells == cells).unwrap_or(false) {
+        return;
+    }
+
+    recording.frames.push(AsciiRecordedFrame {
+        time: now - started_at,
+        cells,
+    });
+}
+
+//=============================================================================
+//             Recording
+//=============================================================================
+
+#[derive(Clone, PartialEq)]
+pub struct AsciiRecordedFrame {
+    // Seconds since the start of the recording.
+    pub time: f32,
+    // Character, text color and background color of every cell, row by row.
+    pub cells: Vec<(Character, Color, Color)>,
+}
+
+#[derive(Clone, Default, PartialEq)]
+pub struct AsciiRecording {
+    width: u32,
+    height: u32,
+    frames: Vec<AsciiRecordedFrame>,
+}
+
+impl AsciiRecording {
+    const MAGIC: &'static [u8; 4] = b"ASCR";
+    const VERSION: u8 = 1;
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn frames(&self) -> &[AsciiRecordedFrame] {
+        &self.frames
+    }
+
+    pub fn duration(&self) -> f32 {
+        self.frames.last().map(|frame| frame.time).unwrap_or(0.0)
+    }
+
+    // The index of the frame that is showing at the given time.
+    pub fn frame_index_at(&self, time: f32) -> Option<usize> {
+        if self.frames.is_empty() {
+            return None;
+        }
+        Some(self.frames.partition_point(|frame| frame.time <= time).saturating_sub(1))
+    }
+
+    pub fn frame_at(&self, time: f32) -> Option<&AsciiRecordedFrame> {
+        self.frame_index_at(time).and_then(|index| self.frames.get(index))
+    }
+
+    // Writes the recording as an asciinema v2 `.cast` file. Only the cells that changed since
+    // the previous frame are written for every frame after the first.
+    pub fn to_cast(&self) -> String {
+        let mut cast = format!("{{\"version\": 2, \"width\": {}, \"height\": {}}}\n", self.width, self.height);
+        let mut previous: Option<&AsciiRecordedFrame> = None;
+
+        for frame in self.frames.iter() {
+            let mut output = String::new();
+            let mut colors = None;
+            let mut cursor = None;
+
+            if previous.is_none() {
+                output.push_str("\u{1b}[2J");
+            }
+
+            for (index, cell) in frame.cells.iter().enumerate() {
+                if previous.map(|previous| previous.cells.get(index) == Some(cell)).unwrap_or(false) {
+                    continue;
+                }
+
+                let (x, y) = (index as u32 % self.width, index as u32 / self.width);
+                if cursor != Some((x, y)) {
+                    let _ = write!(output, "\u{1b}[{};{}H", y + 1, x + 1);
+                }
+
+                let (character, text_color, bg_color) = *cell;
+                if colors != Some((text_color, bg_color)) {
+                    let [fr, fg, fb] = text_color.rgb().map(|value| (value * 255.0).round() as u8);
+                    let [br, bg, bb] = bg_color.rgb().map(|value| (value * 255.0).round() as u8);
+                    let _ = write!(output, "\u{1b}[38;2;{};{};{};48;2;{};{};{}m", fr, fg, fb, br, bg, bb);
+                    colors = Some((text_color, bg_color));
+                }
+
+                output.push(character.into());
+                cursor = Some((x + 1, y));
+            }
+
+            if !output.is_empty() {
+                output.push_str("\u{1b}[0m");
+                let _ = writeln!(cast, "[{:.6}, \"o\", \"{}\"]", frame.time, escape_json(&output));
+            }
+            previous = Some(frame);
+        }
+
+        cast
+    }
+
+    pub fn save_cast(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_cast())
+    }
+
+    // A compact binary format that can be loaded back for an `AsciiReplay`. `Rgb` colors are
+    // stored as the closest palette color.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let cell_count = self.width as usize * self.height as usize;
+        let mut bytes = Vec::with_capacity(17 + self.frames.len() * (4 + cell_count * 3));
+        bytes.extend_from_slice(Self::MAGIC);
+        bytes.push(Self::VERSION);
+        bytes.extend_from_slice(&self.width.to_le_bytes());
+        bytes.extend_from_slice(&self.height.to_le_bytes());
+        bytes.extend_from_slice(&(self.frames.len() as u32).to_le_bytes());
+
+        for frame in self.frames.iter() {
+            bytes.extend_from_slice(&frame.time.to_le_bytes());
+            for (character, text_color, bg_color) in frame.cells.iter() {
+                bytes.extend_from_slice(&[*character as u8, text_color.index(), bg_color.index()]);
+            }
+        }
+
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<Self> {
+        let invalid = |message: &str| io::Error::new(ErrorKind::InvalidData, message.to_string());
+        let read_u32 = |offset: usize| -> io::Result<u32> {
+            bytes
+                .get(offset..offset + 4)
+                .map(|slice| u32::from_le_bytes([slice[0], slice[1], slice[2], slice[3]]))
+                .ok_or_else(|| invalid("Recording ended unexpectedly."))
+        };
+
+        if bytes.get(0..4) != Some(Self::MAGIC.as_slice()) {
+            return Err(invalid("Not an ascii recording."));
+        }
+        if bytes.get(4) != Some(&Self::VERSION) {
+            return Err(invalid("Unsupported ascii recording version."));
+        }
+
+        let width = read_u32(5)?;
+        let height = read_u32(9)?;
+        let frame_count = read_u32(13)?;
+        // The header can not be trusted, a frame of a size that does not fit is not a recording.
+        let frame_size = (width as usize)
+            .checked_mul(height as usize)
+            .and_then(|cell_count| cell_count.checked_mul(3))
+            .and_then(|size| size.checked_add(4))
+            .ok_or_else(|| invalid("Recording frames are too big."))?;
+
+        let mut offset: usize = 17;
+        // No more frames than the bytes can hold are made room for.
+        let mut frames = Vec::with_capacity((frame_count as usize).min(bytes.len() / frame_size));
+        for _ in 0..frame_count {
+            let frame = offset
+                .checked_add(frame_size)
+                .and_then(|end| bytes.get(offset..end))
+                .ok_or_else(|| invalid("Recording ended unexpectedly."))?;
+            offset += frame_size;
+
+            let time = f32::from_le_bytes([frame[0], frame[1], frame[2], frame[3]]);
+            let cells = frame[4..]
+                .chunks_exact(3)
+                .map(|cell| {
+                    Some((
+                        Character::from_index(cell[0])?,
+                        Color::from_index(cell[1]),
+                        Color::from_index(cell[2]),
+                    ))
+                })
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(|| invalid("Recording contains an unknown character."))?;
+
+            frames.push(AsciiRecordedFrame { time, cells });
+        }
+
+        Ok(AsciiRecording {
+            width,
+            height,
+            frames,
+        })
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_bytes())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+}
+
+fn escape_json(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cell(c: char, text_color: Color) -> (Character, Color, Color) {
+        (Character::from(c), text_color, Color::Black)
+    }
+
+    // Two frames of a 2 by 1 grid, where only the second cell changes.
+    fn recording() -> AsciiRecording {
+        AsciiRecording {
+            width: 2,
+            height: 1,
+            frames: vec![
+                AsciiRecordedFrame { time: 0.0, cells: vec![cell('A', Color::White), cell('B', Color::White)] },
+                AsciiRecordedFrame { time: 0.5, cells: vec![cell('A', Color::White), cell('C', Color::Red)] },
+            ],
+        }
+    }
+
+    fn error_of(bytes: &[u8]) -> String {
+        match AsciiRecording::from_bytes(bytes) {
+            Ok(_) => panic!("the recording should not load"),
+            Err(error) => {
+                assert_eq!(error.kind(), ErrorKind::InvalidData);
+                error.to_string()
+            }
+        }
+    }
+
+    // A header for the given size and frame count, without any frames.
+    fn header(width: u32, height: u32, frame_count: u32) -> Vec<u8> {
+        let mut bytes = AsciiRecording::MAGIC.to_vec();
+        bytes.push(AsciiRecording::VERSION);
+        for value in [width, height, frame_count] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes
+    }
+
+    #[test]
+    fn recordings_round_trip_through_bytes() {
+        let recording = recording();
+        let bytes = recording.to_bytes();
+        assert_eq!(bytes.len(), 17 + 2 * (4 + 2 * 3));
+        assert!(AsciiRecording::from_bytes(&bytes).unwrap() == recording);
+
+        let empty = AsciiRecording::default();
+        assert!(AsciiRecording::from_bytes(&empty.to_bytes()).unwrap() == empty);
+    }
+
+    #[test]
+    fn truncated_recordings_are_errors() {
+        let bytes = recording().to_bytes();
+        assert_eq!(error_of(&bytes[..3]), "Not an ascii recording.");
+        for length in [5, 10, 17, 20, bytes.len() - 1] {
+            assert_eq!(error_of(&bytes[..length]), "Recording ended unexpectedly.", "{length} bytes");
+        }
+    }
+
+    #[test]
+    fn garbage_is_an_error() {
+        assert_eq!(error_of(b"GIF89a garbage"), "Not an ascii recording.");
+
+        let mut bytes = recording().to_bytes();
+        bytes[4] = 9;
+        assert_eq!(error_of(&bytes), "Unsupported ascii recording version.");
+
+        let mut bytes = recording().to_bytes();
+        bytes[17 + 4] = 255;
+        assert_eq!(error_of(&bytes), "Recording contains an unknown character.");
+    }
+
+    #[test]
+    fn hostile_headers_are_errors() {
+        // Frames bigger than memory, and more frames than the file holds.
+        assert_eq!(error_of(&header(u32::MAX, u32::MAX, 1)), "Recording frames are too big.");
+        assert_eq!(error_of(&header(2, 1, u32::MAX)), "Recording ended unexpectedly.");
+        assert_eq!(error_of(&header(0, 0, u32::MAX)), "Recording ended unexpectedly.");
+    }
+
+    #[test]
+    fn casts_only_write_the_cells_that_changed() {
+        let cast = recording().to_cast();
+        let lines = cast.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "{\"version\": 2, \"width\": 2, \"height\": 1}");
+
+        // The first frame clears the screen and writes both cells with one color change.
+        assert!(lines[1].starts_with("[0.000000, \"o\", \"\\u001b[2J\\u001b[1;1H"));
+        assert!(lines[1].ends_with("mAB\\u001b[0m\"]"));
+        assert_eq!(lines[1].matches("38;2;").count(), 1);
+        // The second frame moves to the cell that changed.
+        assert!(lines[2].starts_with("[0.500000, \"o\", \"\\u001b[1;2H"));
+        assert!(lines[2].ends_with("mC\\u001b[0m\"]"));
+
+        // A frame without changes writes nothing.
+        let mut recording = recording();
+        let still = AsciiRecordedFrame { time: 1.0, ..recording.frames[1].clone() };
+        recording.frames.push(still);
+        assert_eq!(recording.to_cast().lines().count(), 3);
+    }
+}
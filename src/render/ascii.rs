@@ -1,27 +1,42 @@
 use crate::{
-    ascii::{AsciiCamera, AsciiShaderSettingsBuffer},
-    ui::buffer::AsciiSurface,
+    ascii::AsciiShaderSettingsBuffer,
+    ui::{bounds::AsciiBounds, buffer::AsciiSurface, character::Color, encoding::CellEncoding},
 };
 use bevy::{
-    asset::AssetServer,
     core_pipeline::fullscreen_vertex_shader::fullscreen_shader_vertex_state,
     ecs::world::FromWorld,
     prelude::*,
     render::{
-        self, render_asset::RenderAssetUsages, render_resource::{
-            BindGroupLayout, BindGroupLayoutDescriptor, BindGroupLayoutEntry, BindingType,
+        render_resource::{
+            BindGroupLayout, BindGroupLayoutEntry, BindingType,
             CachedRenderPipelineId, ColorTargetState, ColorWrites, Extent3d, FragmentState,
-            ImageCopyTexture, ImageDataLayout, MultisampleState, Origin3d, PipelineCache,
+            ImageDataLayout, MultisampleState, PipelineCache,
             PrimitiveState, RenderPipelineDescriptor, Sampler, SamplerBindingType,
-            SamplerDescriptor, ShaderStages, ShaderType, Texture, TextureAspect, TextureFormat,
-            TextureSampleType, TextureView, TextureViewDescriptor, TextureViewDimension,
-        }, renderer::{RenderDevice, RenderQueue}, texture::{
-            BevyDefault, CompressedImageFormats, Image, ImageFormat, ImageSampler, ImageType,
-        }
+            SamplerDescriptor, ShaderStages, ShaderType, Texture, TextureFormat,
+            TextureDescriptor, TextureDimension, TextureSampleType, TextureUsages, TextureView,
+            TextureViewDescriptor, TextureViewDimension, UniformBuffer,
+        }, renderer::{RenderDevice, RenderQueue}, texture::BevyDefault
     },
     utils::hashbrown::HashMap,
 };
 
+use super::{
+    classify::ClassifyShaderPipeline,
+    font::{builtin_font, BUILTIN_FONT_ROWS, create_atlas_texture, empty_tile_atlas, font_atlas},
+    style::StyleTextures,
+};
+
+//=============================================================================
+//             Ascii Cell Hook
+//=============================================================================
+
+// A shader asset that can change the character and color of every cell of the scene, without
+// replacing the whole ascii shader. It is given to the `AsciiRenderPlugin` as a path, and needs a
+// `fn cell_hook(cell: AsciiCell) -> AsciiCell`, where `AsciiCell` is imported from
+// `bevy_ascii::cell`. The shader must not have a `#define_import_path`, it is imported by its path.
+#[derive(Resource, Clone)]
+pub struct AsciiCellHook(pub Handle<Shader>);
+
 //=============================================================================
 //             Ascii Shader Pipeline
 //=============================================================================
@@ -30,15 +45,43 @@ use bevy::{
 #[derive(Resource)]
 pub(crate) struct AsciiShaderPipeline {
     pub overlay_textures: HashMap<Entity, Texture>,
-    pub target_size: Vec2,
+    // The exact colors of the overlay, for cameras with `AsciiUi::true_color`.
+    pub rgb_overlay_textures: HashMap<Entity, Texture>,
+    // The part of the overlay textures that was written by the last upload. Only it and what
+    // changes are uploaded again, the rest stays unset.
+    pub overlay_regions: HashMap<Entity, AsciiBounds>,
+    pub rgb_overlay_regions: HashMap<Entity, AsciiBounds>,
+    // What the overlay textures were last made from.
+    pub overlay_surfaces: HashMap<Entity, OverlaySurfaces>,
+    // The last output of every on demand camera, shown again on frames that are skipped.
+    pub output_textures: HashMap<Entity, Texture>,
+    pub settings_uniforms: HashMap<Entity, AsciiSettingsUniform>,
     pub layout: BindGroupLayout,
     pub sampler: Sampler,
     pub font_texture: TextureView,
+    pub tile_texture: TextureView,
+    pub palette_texture: TextureView,
+    // Bound in place of the overlay of cameras without a ui. It is smaller than the grid, which
+    // the shader takes as an overlay with nothing on it.
+    pub empty_overlay_texture: TextureView,
+    // Bound in place of the rgb overlay of cameras without true color. It is smaller than the
+    // grid, which the shader takes as having no rgb colors.
+    pub empty_rgb_overlay: TextureView,
+    // Bound in place of the cells of the compute pass when the characters are picked in the
+    // ascii shader. The shader does not read it then.
+    pub empty_cell_texture: TextureView,
+    // The styles of the cameras that show an `AsciiStyleOverride3d`.
+    pub style_textures: HashMap<Entity, StyleTextures>,
+    // Bound in place of the styles of cameras without any. They are smaller than the grid, which
+    // the shader takes as no cell having a style.
+    pub empty_style_ids: TextureView,
+    pub empty_style_table: TextureView,
     pub pipeline_id: CachedRenderPipelineId,
 }
 
 impl FromWorld for AsciiShaderPipeline {
     fn from_world(world: &mut World) -> Self {
+        let compute_cells = world.contains_resource::<ClassifyShaderPipeline>();
         let queue = world.get_resource::<RenderQueue>().unwrap();
         let render_device = world.resource::<RenderDevice>();
 
@@ -103,46 +146,229 @@ impl FromWorld for AsciiShaderPipeline {
                     },
                     count: None,
                 },
+                // The adapted average brightness of the scene, used for auto exposure
+                BindGroupLayoutEntry {
+                    binding: 5,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                // The full resolution scene, drawn as it is outside of the camera viewport
+                BindGroupLayoutEntry {
+                    binding: 6,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                // The 256 colors of the palette, in the order of `Color::from_index`
+                BindGroupLayoutEntry {
+                    binding: 7,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: false },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                // The rgb colors of the overlay, two texels for every cell
+                BindGroupLayoutEntry {
+                    binding: 8,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Uint,
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                // The cells picked by the compute pass
+                BindGroupLayoutEntry {
+                    binding: 9,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Uint,
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                // The brightness of the cells with temporal stability
+                BindGroupLayoutEntry {
+                    binding: 10,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                // The sprites of the tile atlas
+                BindGroupLayoutEntry {
+                    binding: 11,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                // The style of every cell
+                BindGroupLayoutEntry {
+                    binding: 12,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Uint,
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                // The styles the cells point to
+                BindGroupLayoutEntry {
+                    binding: 13,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Uint,
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
             ],
         );
 
         let sampler = render_device.create_sampler(&SamplerDescriptor::default());
 
-        let font_texture = Image::from_buffer(
-            include_bytes!("font.png"),
-            ImageType::Format(ImageFormat::Png),
-            CompressedImageFormats::default(),
-            true,
-            ImageSampler::nearest(),
-            RenderAssetUsages::RENDER_WORLD
-        )
-        .expect("There was an error reading an internal texture.");
-
-        let texture = render_device.create_texture(&font_texture.texture_descriptor);
-        queue.write_texture(
-            ImageCopyTexture {
-                texture: &texture,
-                mip_level: 0,
-                origin: Origin3d::ZERO,
-                aspect: TextureAspect::All,
+        // The pages set in `AsciiFontPages` are added once their images are loaded.
+        let font_texture =
+            create_atlas_texture(render_device, queue, &font_atlas(&builtin_font(), BUILTIN_FONT_ROWS, &[]), "ascii_font_texture");
+        // The `AsciiTileAtlas` is bound once its image is loaded.
+        let tile_texture = create_atlas_texture(render_device, queue, &empty_tile_atlas(), "ascii_tile_texture");
+
+        let palette_texture = render_device.create_texture(&TextureDescriptor {
+            label: "ascii_palette_texture".into(),
+            size: Extent3d {
+                width: 256,
+                height: 1,
+                depth_or_array_layers: 1,
             },
-            &font_texture.data,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba8Unorm,
+            usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        queue.write_texture(
+            palette_texture.as_image_copy(),
+            &Color::palette_bytes(),
             ImageDataLayout {
                 offset: 0,
-                bytes_per_row: Some(4 * font_texture.width()),
-                rows_per_image: Some(font_texture.height()),
+                bytes_per_row: Some(256 * 4),
+                rows_per_image: Some(1),
             },
-            Extent3d {
-                width: font_texture.width(),
-                height: font_texture.height(),
+            palette_texture.size(),
+        );
+        let palette_texture = palette_texture.create_view(&TextureViewDescriptor {
+            label: "ascii_palette_texture".into(),
+            ..Default::default()
+        });
+
+        let empty_overlay_texture = render_device.create_texture(&TextureDescriptor {
+            label: "ascii_empty_overlay_texture".into(),
+            size: Extent3d {
+                width: 1,
+                height: 1,
                 depth_or_array_layers: 1,
             },
-        );
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba8Uint,
+            usage: TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let empty_overlay_texture = empty_overlay_texture.create_view(&TextureViewDescriptor::default());
 
-        let font_texture = texture.create_view(&TextureViewDescriptor {
-            label: "ascii_font_texture".into(),
-            ..Default::default()
+        let empty_rgb_overlay = render_device.create_texture(&TextureDescriptor {
+            label: "ascii_empty_rgb_overlay".into(),
+            size: Extent3d {
+                width: 1,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba8Uint,
+            usage: TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
         });
+        let empty_rgb_overlay = empty_rgb_overlay.create_view(&TextureViewDescriptor::default());
+
+        let empty_cell_texture = render_device.create_texture(&TextureDescriptor {
+            label: "ascii_empty_cell_texture".into(),
+            size: Extent3d {
+                width: 1,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: ClassifyShaderPipeline::FORMAT,
+            usage: TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let empty_cell_texture = empty_cell_texture.create_view(&TextureViewDescriptor::default());
+
+        let empty_style_ids = render_device.create_texture(&TextureDescriptor {
+            label: "ascii_empty_style_ids".into(),
+            size: Extent3d {
+                width: 1,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::R8Uint,
+            usage: TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let empty_style_ids = empty_style_ids.create_view(&TextureViewDescriptor::default());
+
+        let empty_style_table = render_device.create_texture(&TextureDescriptor {
+            label: "ascii_empty_style_table".into(),
+            size: Extent3d {
+                width: 1,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba32Uint,
+            usage: TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let empty_style_table = empty_style_table.create_view(&TextureViewDescriptor::default());
+
+        let mut shader_defs = CellEncoding::shader_defs();
+        if compute_cells {
+            shader_defs.push("COMPUTE_CELLS".into());
+        }
 
         let pipeline_id = world
             .resource_mut::<PipelineCache>()
@@ -154,7 +380,7 @@ impl FromWorld for AsciiShaderPipeline {
                 vertex: fullscreen_shader_vertex_state(),
                 fragment: Some(FragmentState {
                     shader : super::ASCII_SHADER_HANDLE,
-                    shader_defs: vec![],
+                    shader_defs,
                     // Make sure this matches the entry point of your shader.
                     // It can be anything as long as it matches here and in the shader.
                     entry_point: "fragment".into(),
@@ -179,17 +405,91 @@ impl FromWorld for AsciiShaderPipeline {
 
         AsciiShaderPipeline {
             overlay_textures: HashMap::new(),
-            target_size: Vec2::ZERO,
+            rgb_overlay_textures: HashMap::new(),
+            overlay_regions: HashMap::new(),
+            rgb_overlay_regions: HashMap::new(),
+            overlay_surfaces: HashMap::new(),
+            output_textures: HashMap::new(),
+            settings_uniforms: HashMap::new(),
             layout,
             sampler,
             font_texture,
+            tile_texture,
+            palette_texture,
+            empty_overlay_texture,
+            empty_rgb_overlay,
+            empty_cell_texture,
+            style_textures: HashMap::new(),
+            empty_style_ids,
+            empty_style_table,
             pipeline_id,
         }
     }
 }
+//=============================================================================
+//             Settings Uniform
+//=============================================================================
+
+// The settings and viewport uniforms of a camera. These are kept between frames and only written
+// to the gpu when the settings change.
+pub(crate) struct AsciiSettingsUniform {
+    settings: AsciiShaderSettingsBuffer,
+    pub settings_buffer: UniformBuffer<AsciiShaderSettingsBuffer>,
+    pub viewport_buffer: UniformBuffer<Vec4>,
+}
+
+impl AsciiSettingsUniform {
+    pub fn new(settings: AsciiShaderSettingsBuffer, device: &RenderDevice, queue: &RenderQueue) -> Self {
+        let mut uniform = AsciiSettingsUniform {
+            settings: AsciiShaderSettingsBuffer::default(),
+            settings_buffer: UniformBuffer::default(),
+            viewport_buffer: UniformBuffer::default(),
+        };
+        uniform.set(settings, device, queue);
+        uniform
+    }
+
+    pub fn set(&mut self, settings: AsciiShaderSettingsBuffer, device: &RenderDevice, queue: &RenderQueue) {
+        self.viewport_buffer.set(settings.viewport);
+        self.viewport_buffer.write_buffer(device, queue);
+        self.settings_buffer.set(settings.clone());
+        self.settings_buffer.write_buffer(device, queue);
+        self.settings = settings;
+    }
+
+    pub fn is_outdated(&self, settings: &AsciiShaderSettingsBuffer) -> bool {
+        self.settings != *settings
+    }
+}
+
 //=============================================================================
 //             OverlayBuffer
 //=============================================================================
 
 #[derive(Component)]
 pub struct OverlayBuffer(pub AsciiSurface);
+
+// The part of the overlay that is drawn again on its own, without the rest of the ui, like the
+// nodes with an `AsciiRedrawTimer`. It is put over the `OverlayBuffer` when the overlay is
+// uploaded, and is only there on frames where it is drawn again.
+#[derive(Component)]
+pub struct LiveOverlayBuffer(pub AsciiSurface);
+
+// The last ui and live layer of a camera. When only one of them is drawn again, the other one is
+// still needed to put the overlay together.
+#[derive(Default)]
+pub(crate) struct OverlaySurfaces {
+    pub ui: AsciiSurface,
+    pub live: AsciiSurface,
+    // The cells the live layer drew last time, which are uploaded again to take them away.
+    pub live_region: Option<AsciiBounds>,
+}
+
+// Added to the cameras of the render world that have an `AsciiUi`. The rest are drawn without an
+// overlay.
+#[derive(Component)]
+pub struct OverlayCamera;
+
+// Added to the cameras of the render world whose `AsciiUi` uses true color.
+#[derive(Component)]
+pub struct OverlayTrueColor;
@@ -1,108 +1,264 @@
 #import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+#import bevy_ascii::cell::{AsciiCell, settings, adjust_value, stable_value, ramp_character, palette_color}
+// Replaced with the shader of the `AsciiCellHook` when there is one.
+#import bevy_ascii::cell_hook::cell_hook
 
 @group(0) @binding(0) var screen_texture: texture_2d<f32>;
 @group(0) @binding(1) var font_texture: texture_2d<f32>;
 @group(0) @binding(2) var overlay_texture: texture_2d<u32>;
 @group(0) @binding(3) var texture_sampler: sampler;
-
-struct PostProcessSettings {
-    pixels_per_character: f32,
-#ifdef SIXTEEN_BYTE_ALIGNMENT
-    // WebGL2 structs must be 16 byte aligned.
-    _webgl2_padding: vec3<f32>
+@group(0) @binding(6) var source_texture: texture_2d<f32>;
+// The text and background color of every overlay cell next to each other. The last channel is
+// 0 where the palette color should be used. Cameras without true color get a texture smaller
+// than the grid.
+@group(0) @binding(8) var rgb_overlay_texture: texture_2d<u32>;
+#ifdef COMPUTE_CELLS
+// The cells picked by the classify pass, see ascii_classify.wgsl.
+@group(0) @binding(9) var cell_texture: texture_2d<u32>;
 #endif
-}
+// The sprites of the `AsciiTileAtlas`, 16 on every row. It is a single transparent texel when
+// there is no atlas.
+@group(0) @binding(11) var tile_texture: texture_2d<f32>;
+// The style of every cell of the scene, 0 for none, and the styles they point to. See
+// style.rs. The ids are smaller than the grid when nothing has a style.
+@group(0) @binding(12) var style_ids: texture_2d<u32>;
+@group(0) @binding(13) var style_table: texture_2d<u32>;
 
-@group(0) @binding(4) var<uniform> settings: PostProcessSettings;
+// Every page of the font has 16 by 16 glyphs, and the pages are stacked from top to bottom. The
+// number of pages is `AsciiGlyph::PAGE_COUNT`.
+const GLYPHS_PER_ROW : f32 = 16.0;
+const FONT_PAGES : f32 = #{FONT_PAGES}.0;
 
-const TEXTURE_RESOLUTION : vec2<f32> = vec2<f32>(384.0, 192.0);
-const CHARACTER_DIMENSIONS = vec2<f32>(24.0, 24.0);
+// How the last channel of a cell of the overlay is packed, from `CellEncoding::shader_defs`.
+const CELL_BLEND_SHIFT : u32 = #{CELL_BLEND_SHIFT}u;
+const CELL_BLEND_MASK : u32 = #{CELL_BLEND_MASK}u;
+const CELL_TRANSPARENCY_SHIFT : u32 = #{CELL_TRANSPARENCY_SHIFT}u;
+const CELL_TRANSPARENCY_MASK : u32 = #{CELL_TRANSPARENCY_MASK}u;
+const CELL_TILE_SHIFT : u32 = #{CELL_TILE_SHIFT}u;
+const CELL_PAGE_SHIFT : u32 = #{CELL_PAGE_SHIFT}u;
+const CELL_PAGE_MASK : u32 = #{CELL_PAGE_MASK}u;
+const CELL_RGB_SET : u32 = #{CELL_RGB_SET}u;
 
 @fragment
 fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
 
+    // Everything outside of the viewport of this camera belongs to other cameras and is left as
+    // it is.
+    let uv = (in.position.xy - settings.viewport.xy) / settings.viewport.zw;
+    if (any(uv < vec2<f32>(0.0, 0.0)) || any(uv >= vec2<f32>(1.0, 1.0))) {
+        return textureLoad(source_texture, vec2<u32>(in.position.xy), 0);
+    }
+
     let output_dims = vec2<f32>(textureDimensions(screen_texture));
-    let screen_pos = vec2<u32>(floor(output_dims * in.uv));
+    let screen_pos = vec2<u32>(floor(output_dims * uv));
     
-    let overlay_info = textureLoad(overlay_texture, screen_pos, 0);
-        
-    let screen_color = textureSampleLevel(screen_texture, texture_sampler, in.uv, 0.0);
+    // With an `AsciiUiScale` every cell of the overlay covers several cells of the scene. The
+    // cells that do not fit at the right and bottom have no overlay.
+    let ui_scale = f32(max(settings.ui_scale, 1u));
+    let ui_dims = floor(output_dims / ui_scale);
+    let ui_cell = uv * output_dims / ui_scale;
+    let ui_pos = vec2<u32>(floor(ui_cell));
 
-    let o_index = overlay_info.x;
-    
-    let current_pixel = vec2<u32>(
-        u32(floor(settings.pixels_per_character * (floor(in.position.x / settings.pixels_per_character)))),
-        u32(floor(settings.pixels_per_character * (floor(in.position.y / settings.pixels_per_character))))
-    );
-    
-    let value = screen_color.w;
-    
-    var indices = array<f32, 10>(
-        46.0,
-        58.0,
-        45.0,
-        43.0,
-        42.0,
-        88.0,
-        87.0,
-        81.0,
-        86.0,
-        102.0
-    );
-    
-    var colors = array<vec3<f32>, 16>(
-        vec3<f32>(0.0, 0.0, 0.0), //Black
-        vec3<f32>(1.0, 1.0, 1.0), //White
-        vec3<f32>(0.533, 0.0, 0.0), //Red
-        vec3<f32>(0.667, 1.0, 0.933), //Cyan
-        vec3<f32>(0.8, 0.267, 0.8), //Violet
-        vec3<f32>(0.0, 0.8, 0.333), //Green
-        vec3<f32>(0.0, 0.0, 0.667), //Blue
-        vec3<f32>(0.933, 0.933, 0.467), //Yellow
-        vec3<f32>(0.867, 0.533, 0.333), //Orange
-        vec3<f32>(0.4, 0.267, 0.0), //Brown
-        vec3<f32>(1.0, 0.467, 0.467), //Light Red
-        vec3<f32>(0.2, 0.2, 0.2), //Dark Grey
-        vec3<f32>(0.467, 0.467, 0.467), //Grey
-        vec3<f32>(0.667, 1.0, 0.4), //Light Green
-        vec3<f32>(0.0, 0.533, 1.0), //Light Blue
-        vec3<f32>(0.733, 0.733, 0.733) //Light Grey
-    );
+    // Cameras without a ui get an overlay smaller than the ui, which has nothing on it.
+    var overlay_info = vec4<u32>(0u, 0u, 0u, 0u);
+    if (all(textureDimensions(overlay_texture) >= vec2<u32>(ui_dims)) && all(ui_cell < ui_dims)) {
+        overlay_info = textureLoad(overlay_texture, ui_pos, 0);
+    }
+
+    // The camera effects move the scene by whole cells, so the characters stay on the grid. Each
+    // row is moved by the wave on its own. The overlay is left where it is.
+    let wave_offset = round(settings.wave_amplitude * sin(floor(uv.y * output_dims.y) * settings.wave_frequency + settings.wave_phase));
+    let scene_uv = uv - (settings.shake_offset + vec2<f32>(wave_offset, 0.0)) / output_dims;
+    let is_scene_outside = any(scene_uv < vec2<f32>(0.0, 0.0)) || any(scene_uv >= vec2<f32>(1.0, 1.0));
 
-    var index = indices[i32(floor(value / 0.1))];
+    let current_pixel = vec2<u32>(floor(settings.pixels_per_character * floor(in.position.xy / settings.pixels_per_character)));
 
-    if(overlay_info.w == u32(1)) {
-        index = f32(min(overlay_info.x, u32(127)));
+    var cell: AsciiCell;
+    cell.position = screen_pos;
+    cell.grid_size = vec2<u32>(output_dims);
+    cell.time = settings.time;
+
+#ifdef COMPUTE_CELLS
+    // The character of every cell was already picked, so the cell is only looked up.
+    if (is_scene_outside) {
+        cell.value = adjust_value(0.0);
+        cell.character = ramp_character(cell.value);
+        cell.color = vec4<f32>(0.0, 0.0, 0.0, 0.0);
+        cell = cell_hook(cell);
+    } else {
+        let classified = textureLoad(cell_texture, vec2<u32>(floor(scene_uv * output_dims)), 0);
+        cell.character = classified.x;
+        cell.color = unpack4x8unorm(classified.y);
+        cell.value = bitcast<f32>(classified.z);
     }
-      
-    let character_uv = vec2<f32>(
-        ((index % 16.0) * CHARACTER_DIMENSIONS.x) / TEXTURE_RESOLUTION.x, 
-        (floor(index / 16.0) * CHARACTER_DIMENSIONS.y) / TEXTURE_RESOLUTION.y
-    );
-    let character_size_uv = CHARACTER_DIMENSIONS / TEXTURE_RESOLUTION;
+#else
+    var screen_color = textureSampleLevel(screen_texture, texture_sampler, scene_uv, 0.0);
+    if (is_scene_outside) {
+        screen_color = vec4<f32>(0.0, 0.0, 0.0, 0.0);
+    }
+
+    cell.value = adjust_value(screen_color.w);
+    if (!is_scene_outside) {
+        cell.value = stable_value(vec2<u32>(floor(scene_uv * output_dims)), cell.value);
+    }
+    cell.character = ramp_character(cell.value);
+    cell.color = screen_color;
+
+    // The cell hook can change the character and color of every cell of the scene.
+    cell = cell_hook(cell);
+#endif
+
+    // The styles move with the scene, like the characters they replace.
+    if (!is_scene_outside && all(textureDimensions(style_ids) == vec2<u32>(output_dims))) {
+        let style_id = textureLoad(style_ids, vec2<u32>(floor(scene_uv * output_dims)), 0).x;
+        if (style_id != 0u) {
+            cell = apply_style(cell, textureLoad(style_table, vec2<u32>(style_id - 1u, 0u), 0));
+        }
+    }
+
+    let overlay_index = f32(overlay_info.x);
+    let overlay_page = f32((overlay_info.w >> CELL_PAGE_SHIFT) & CELL_PAGE_MASK);
 
     let screen_pixel_uv = vec2<f32>(1.0, 1.0) / output_dims;
 
     // This value is 0.0 - 1.0 depending on how far along a pixel we are
-    let inner_pixel_uv = (in.uv % screen_pixel_uv) / screen_pixel_uv;
+    let inner_pixel_uv = (uv % screen_pixel_uv) / screen_pixel_uv;
 
-    let font_uv = character_uv + (character_size_uv * inner_pixel_uv);
-    
-    let font_color = textureSampleLevel(font_texture, texture_sampler, font_uv, 1.0);
+    let scene_font_color = sample_font(f32(min(cell.character, u32(127))), 0.0, inner_pixel_uv);
+    // The characters of the overlay are scaled up without smoothing, like its cells.
+    let overlay_font_color = sample_font(overlay_index, overlay_page, fract(ui_cell));
+    let is_tile = ((overlay_info.w >> CELL_TILE_SHIFT) & 1u) != 0u;
+
+    var text_color = vec4<f32>(palette_color(overlay_info.y), 1.0);
+    var background_color = vec4<f32>(palette_color(overlay_info.z), 1.0);
+    if (textureDimensions(rgb_overlay_texture).x >= u32(ui_dims.x) * 2u && overlay_info.w != 0u) {
+        let rgb_text = textureLoad(rgb_overlay_texture, vec2<u32>(ui_pos.x * 2u, ui_pos.y), 0);
+        let rgb_background = textureLoad(rgb_overlay_texture, vec2<u32>(ui_pos.x * 2u + 1u, ui_pos.y), 0);
+        if (rgb_text.w == CELL_RGB_SET) {
+            text_color = vec4<f32>(vec3<f32>(rgb_text.xyz) / 255.0, 1.0);
+        }
+        if (rgb_background.w == CELL_RGB_SET) {
+            background_color = vec4<f32>(vec3<f32>(rgb_background.xyz) / 255.0, 1.0);
+        }
+    }
+
+    var scene_color = scene_font_color;
+    if (scene_font_color.x == 1.0) {
+        scene_color = cell.color;
+    }
 
-    if(overlay_info.w == u32(1)) {      
-        if (font_color.x == 1.0) {
-            var color_index = min(u32(15), overlay_info.y);
-            return vec4<f32>(colors[i32(color_index)], 1.0);
+    // A flash of 1 swaps the characters of the scene with their background and 2 draws them in
+    // one color of the palette.
+    if (settings.flash == u32(1)) {
+        if (scene_font_color.x == 1.0) {
+            scene_color = vec4<f32>(0.0, 0.0, 0.0, 1.0);
         } else {
-            var color_index = min(u32(15), overlay_info.z);
-            return vec4<f32>(colors[i32(color_index)], 1.0);
+            scene_color = vec4<f32>(cell.color.xyz, 1.0);
+        }
+    } else if (settings.flash == u32(2) && scene_font_color.x == 1.0) {
+        scene_color = vec4<f32>(palette_color(settings.flash_color), 1.0);
+    }
+
+    // The blend mode is 0 when the cell is unset. 1 replaces the scene, 2 tints the scene
+    // character and 3 draws behind it.
+    let blend_mode = (overlay_info.w >> CELL_BLEND_SHIFT) & CELL_BLEND_MASK;
+    let transparency = (overlay_info.w >> CELL_TRANSPARENCY_SHIFT) & CELL_TRANSPARENCY_MASK;
+    let opacity = 1.0 - f32(transparency) / f32(CELL_TRANSPARENCY_MASK);
+
+    if (blend_mode == u32(0)) {
+        return filter_color(scene_color);
+    }
+
+    var overlay_color = background_color;
+
+    if (is_tile) {
+        // Tiles are drawn in the colors of their sprite, tinted by the text color, over the
+        // background. The blend mode only decides whether the cell is drawn at all.
+        let sprite = sample_tile(overlay_index, fract(ui_cell));
+        overlay_color = vec4<f32>(mix(background_color.xyz, sprite.xyz * text_color.xyz, vec3<f32>(sprite.w)), 1.0);
+    } else if (blend_mode == u32(1)) {
+        if (overlay_font_color.x == 1.0) {
+            overlay_color = text_color;
+        }
+    } else if (blend_mode == u32(2)) {
+        if (scene_font_color.x == 1.0) {
+            overlay_color = text_color;
         }
-    } 
-    
-    if (font_color.x == 1.0) {
-        return screen_color;
     } else {
-        return font_color;
+        if (scene_font_color.x == 1.0) {
+            overlay_color = cell.color;
+        } else if (overlay_font_color.x == 1.0) {
+            overlay_color = text_color;
+        }
+    }
+
+    // Transparent cells are dithered with the scene so the output stays within the palette.
+    var bayer = array<f32, 16>(
+        0.0, 8.0, 2.0, 10.0,
+        12.0, 4.0, 14.0, 6.0,
+        3.0, 11.0, 1.0, 9.0,
+        15.0, 7.0, 13.0, 5.0
+    );
+    let dither_pos = vec2<u32>(in.position.xy) % u32(4);
+    let threshold = (bayer[dither_pos.y * u32(4) + dither_pos.x] + 0.5) / 16.0;
+
+    if (opacity >= threshold) {
+        return filter_color(overlay_color);
+    } else {
+        return filter_color(scene_color);
     }
 }
+
+// Remaps the colors for the `ColorBlindFilter` of the camera, once they were picked. The matrix is
+// the identity without a filter.
+fn filter_color(color: vec4<f32>) -> vec4<f32> {
+    return vec4<f32>(max(settings.color_matrix * color.xyz, vec3<f32>(0.0)), color.w);
+}
+
+// The lowest bit of the style is set when it has a color, which is the rgb of the second value.
+// The next bit is set when it has characters, which are packed into the last two values, with how
+// many there are in the second byte of the first.
+fn apply_style(cell: AsciiCell, style: vec4<u32>) -> AsciiCell {
+    var styled = cell;
+    if ((style.x & 1u) != 0u) {
+        styled.color = vec4<f32>(unpack4x8unorm(style.y).xyz, cell.color.w);
+    }
+    let count = (style.x >> 8u) & 15u;
+    if ((style.x & 2u) != 0u && count > 0u) {
+        let index = min(u32(floor(clamp(cell.value, 0.0, 1.0) * f32(count))), count - 1u);
+        let characters = select(style.w, style.z, index < 4u);
+        styled.character = (characters >> ((index % 4u) * 8u)) & 255u;
+    }
+    return styled;
+}
+
+fn sample_font(index: f32, page: f32, inner_pixel_uv: vec2<f32>) -> vec4<f32> {
+    // The atlas always has every page, see `font_atlas`.
+    let rows = GLYPHS_PER_ROW * FONT_PAGES;
+    if (page >= FONT_PAGES) {
+        return vec4<f32>(0.0, 0.0, 0.0, 0.0);
+    }
+    let character_uv = vec2<f32>(
+        (index % GLYPHS_PER_ROW) / GLYPHS_PER_ROW,
+        (floor(index / GLYPHS_PER_ROW) + page * GLYPHS_PER_ROW) / rows
+    );
+    let character_size_uv = vec2<f32>(1.0 / GLYPHS_PER_ROW, 1.0 / rows);
+
+    let font_uv = character_uv + (character_size_uv * inner_pixel_uv);
+    
+    return textureSampleLevel(font_texture, texture_sampler, font_uv, 1.0);
+}
+
+fn sample_tile(index: f32, inner_pixel_uv: vec2<f32>) -> vec4<f32> {
+    let dimensions = vec2<f32>(textureDimensions(tile_texture));
+    let rows = GLYPHS_PER_ROW * dimensions.y / dimensions.x;
+    // Sprites past the end of the atlas are left transparent.
+    if (floor(index / GLYPHS_PER_ROW) >= rows) {
+        return vec4<f32>(0.0, 0.0, 0.0, 0.0);
+    }
+    let tile_uv = vec2<f32>(
+        (index % GLYPHS_PER_ROW + inner_pixel_uv.x) / GLYPHS_PER_ROW,
+        (floor(index / GLYPHS_PER_ROW) + inner_pixel_uv.y) / rows
+    );
+    return textureSampleLevel(tile_texture, texture_sampler, tile_uv, 0.0);
+}
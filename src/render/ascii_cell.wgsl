@@ -0,0 +1,94 @@
+#define_import_path bevy_ascii::cell
+
+// A cell of the scene after its character was picked. A cell hook is given every cell before it
+// is drawn and returns it with its changes.
+struct AsciiCell {
+    // The cell on the character grid, from the top left.
+    position: vec2<u32>,
+    // The number of columns and rows of the grid.
+    grid_size: vec2<u32>,
+    // The index of the character in the font.
+    character: u32,
+    // The color the character is drawn in.
+    color: vec4<f32>,
+    // The brightness the character was picked from, between 0 and 1.
+    value: f32,
+    // Seconds since the app started.
+    time: f32,
+}
+
+// The settings of the camera, shared by the ascii shader and the pass that picks the characters.
+struct PostProcessSettings {
+    viewport: vec4<f32>,
+    pixels_per_character: vec2<f32>,
+    exposure: f32,
+    contrast: f32,
+    gamma: f32,
+    invert: u32,
+    auto_exposure: u32,
+    auto_exposure_min: f32,
+    auto_exposure_max: f32,
+    shake_offset: vec2<f32>,
+    wave_amplitude: f32,
+    wave_frequency: f32,
+    wave_phase: f32,
+    flash: u32,
+    flash_color: u32,
+    time: f32,
+    stability: f32,
+    ui_scale: u32,
+    color_matrix: mat3x3<f32>,
+#ifdef SIXTEEN_BYTE_ALIGNMENT
+    // WebGL2 structs must be 16 byte aligned.
+    _webgl2_padding: vec2<f32>
+#endif
+}
+
+@group(0) @binding(4) var<uniform> settings: PostProcessSettings;
+@group(0) @binding(5) var average_texture: texture_2d<f32>;
+
+// Applies the image controls of the camera before a character is picked. This needs to match
+// `AsciiCamera::adjust_value`.
+fn adjust_value(value: f32) -> f32 {
+    var exposure = settings.exposure;
+    if (settings.auto_exposure != u32(0)) {
+        let average = textureLoad(average_texture, vec2<u32>(0u, 0u), 0).x;
+        exposure *= clamp(0.5 / max(average, 0.001), settings.auto_exposure_min, settings.auto_exposure_max);
+    }
+
+    var adjusted = value * exposure;
+    adjusted = clamp((adjusted - 0.5) * settings.contrast + 0.5, 0.0, 1.0);
+    adjusted = pow(adjusted, 1.0 / settings.gamma);
+    if (settings.invert != u32(0)) {
+        adjusted = 1.0 - adjusted;
+    }
+    return adjusted;
+}
+
+// The brightness of every cell the last time it was given a new character, for cameras with
+// `AsciiCamera::temporal_stability`. See stability.wgsl.
+@group(0) @binding(10) var stable_texture: texture_2d<f32>;
+
+// The brightness a character is picked from for the cell, given its adjusted brightness this
+// frame. With temporal stability this is the brightness it was last given a character at.
+fn stable_value(position: vec2<u32>, value: f32) -> f32 {
+    if (settings.stability <= 0.0) {
+        return value;
+    }
+    return textureLoad(stable_texture, position, 0).x;
+}
+
+// The character the scene uses for a brightness between 0 and 1. This needs to match
+// `SCENE_CHARACTERS` in grid.rs.
+fn ramp_character(value: f32) -> u32 {
+    var indices = array<u32, 10>(46u, 58u, 45u, 43u, 42u, 88u, 87u, 81u, 86u, 102u);
+    return indices[min(i32(floor(value / 0.1)), 9)];
+}
+
+// The 256 colors of the palette, made from `Color::palette_bytes`.
+@group(0) @binding(7) var palette_texture: texture_2d<f32>;
+
+// A color of the palette, in the same order as `Color::from_index`.
+fn palette_color(index: u32) -> vec3<f32> {
+    return textureLoad(palette_texture, vec2<u32>(min(index, 255u), 0u), 0).xyz;
+}
@@ -0,0 +1,8 @@
+#define_import_path bevy_ascii::cell_hook
+
+#import bevy_ascii::cell::AsciiCell
+
+// Leaves the cells as they are. This is used when there is no `AsciiCellHook`.
+fn cell_hook(cell: AsciiCell) -> AsciiCell {
+    return cell;
+}
@@ -0,0 +1,33 @@
+#import bevy_ascii::cell::{AsciiCell, settings, adjust_value, stable_value, ramp_character}
+// Replaced with the shader of the `AsciiCellHook` when there is one.
+#import bevy_ascii::cell_hook::cell_hook
+
+// Picks the character of every cell once, instead of once for every pixel of the cell. The ascii
+// shader only looks the cells up afterwards.
+
+@group(0) @binding(0) var screen_texture: texture_2d<f32>;
+// The character, the color packed into 8 bits a channel and the bits of the value of every cell.
+@group(0) @binding(9) var cell_texture: texture_storage_2d<rgba32uint, write>;
+
+@compute @workgroup_size(8, 8, 1)
+fn classify(@builtin(global_invocation_id) id: vec3<u32>) {
+    let grid_size = textureDimensions(screen_texture);
+    if (any(id.xy >= grid_size)) {
+        return;
+    }
+
+    let screen_color = textureLoad(screen_texture, id.xy, 0);
+
+    var cell: AsciiCell;
+    cell.position = id.xy;
+    cell.grid_size = grid_size;
+    cell.value = stable_value(id.xy, adjust_value(screen_color.w));
+    cell.character = ramp_character(cell.value);
+    cell.color = screen_color;
+    cell.time = settings.time;
+
+    // The cell hook can change the character and color of every cell of the scene.
+    cell = cell_hook(cell);
+
+    textureStore(cell_texture, id.xy, vec4<u32>(cell.character, pack4x8unorm(cell.color), bitcast<u32>(cell.value), 1u));
+}
@@ -0,0 +1,177 @@
+use bevy::{
+    ecs::world::FromWorld,
+    prelude::*,
+    render::{
+        render_resource::{
+            BindGroupEntries, BindGroupLayout, BindGroupLayoutEntry, BindingResource, BindingType,
+            CachedComputePipelineId, ComputePassDescriptor, ComputePipeline, ComputePipelineDescriptor,
+            PipelineCache, ShaderStages, ShaderType, StorageTextureAccess, Texture, TextureFormat, TextureSampleType,
+            TextureView, TextureViewDescriptor, TextureViewDimension,
+        },
+        renderer::{RenderContext, RenderDevice},
+    },
+    utils::hashbrown::HashMap,
+};
+
+use crate::ascii::AsciiShaderSettingsBuffer;
+
+use super::ascii::AsciiShaderPipeline;
+
+//=============================================================================
+//             Classify Shader Pipeline
+//=============================================================================
+
+// Picks the character and color of every cell in a compute pass, before the ascii shader runs.
+// The ascii shader then only looks the cells up, instead of picking a character for every pixel.
+// The cells are kept in a texture per camera, which the scene grid is read back from.
+#[derive(Resource)]
+pub(crate) struct ClassifyShaderPipeline {
+    pub cell_textures: HashMap<Entity, Texture>,
+    pub layout: BindGroupLayout,
+    pub pipeline_id: CachedComputePipelineId,
+}
+
+impl ClassifyShaderPipeline {
+    // The character, the packed color and the bits of the value of the cell.
+    pub const FORMAT: TextureFormat = TextureFormat::Rgba32Uint;
+    pub const WORKGROUP_SIZE: u32 = 8;
+}
+
+impl FromWorld for ClassifyShaderPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+
+        let layout = render_device.create_bind_group_layout(
+            "classify_shader_bind_group_layout",
+            &[
+                // This is the low res texture
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                // The settings of the camera
+                BindGroupLayoutEntry {
+                    binding: 4,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Buffer {
+                        ty: bevy::render::render_resource::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: Some(AsciiShaderSettingsBuffer::min_size()),
+                    },
+                    count: None,
+                },
+                // The adapted average brightness, used for auto exposure
+                BindGroupLayoutEntry {
+                    binding: 5,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                // The palette, for cell hooks that pick a color from it
+                BindGroupLayoutEntry {
+                    binding: 7,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: false },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                // The brightness of the cells with temporal stability
+                BindGroupLayoutEntry {
+                    binding: 10,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                // The cells that are written
+                BindGroupLayoutEntry {
+                    binding: 9,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::StorageTexture {
+                        access: StorageTextureAccess::WriteOnly,
+                        format: Self::FORMAT,
+                        view_dimension: TextureViewDimension::D2,
+                    },
+                    count: None,
+                },
+            ],
+        );
+
+        let pipeline_id = world
+            .resource_mut::<PipelineCache>()
+            .queue_compute_pipeline(ComputePipelineDescriptor {
+                label: Some("classify_shader_pipeline".into()),
+                layout: vec![layout.clone()],
+                push_constant_ranges: vec![],
+                shader: super::ASCII_CLASSIFY_SHADER_HANDLE,
+                shader_defs: vec![],
+                entry_point: "classify".into(),
+            });
+
+        ClassifyShaderPipeline {
+            cell_textures: HashMap::new(),
+            layout,
+            pipeline_id,
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn classify_pass(
+    low_res_texture: &TextureView,
+    average_texture: &TextureView,
+    cell_texture: &Texture,
+    stable_texture: &TextureView,
+    settings: BindingResource,
+    render_context: &mut RenderContext,
+    classify_pipeline: &ComputePipeline,
+    classify_pipeline_resource: &ClassifyShaderPipeline,
+    ascii_pipeline_resource: &AsciiShaderPipeline,
+) {
+    let cell_view = cell_texture.create_view(&TextureViewDescriptor {
+        label: Some("ascii_cell_texture"),
+        ..Default::default()
+    });
+
+    let bind_group = render_context.render_device().create_bind_group(
+        "classify_shader_bind_group",
+        &classify_pipeline_resource.layout,
+        &BindGroupEntries::with_indices((
+            (0, low_res_texture),
+            (4, settings),
+            (5, average_texture),
+            (7, &ascii_pipeline_resource.palette_texture),
+            (9, &cell_view),
+            (10, stable_texture),
+        )),
+    );
+
+    let mut compute_pass = render_context.command_encoder().begin_compute_pass(&ComputePassDescriptor {
+        label: Some("classify_shader_compute_pass"),
+        timestamp_writes: None,
+    });
+
+    compute_pass.set_pipeline(classify_pipeline);
+    compute_pass.set_bind_group(0, &bind_group, &[]);
+    compute_pass.dispatch_workgroups(
+        cell_texture.width().div_ceil(ClassifyShaderPipeline::WORKGROUP_SIZE),
+        cell_texture.height().div_ceil(ClassifyShaderPipeline::WORKGROUP_SIZE),
+        1,
+    );
+}
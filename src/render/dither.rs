@@ -1,8 +1,5 @@
-use bevy::{
-    prelude::*,
-    render::renderer::{RenderDevice, RenderQueue},
-};
-
+// Not part of the render graph yet.
+#[allow(dead_code)]
 pub(crate) struct DitherShaderPipeline {}
 
 // impl FromWorld for DitherShaderPipeline {
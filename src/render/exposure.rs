@@ -0,0 +1,172 @@
+use bevy::{
+    core_pipeline::fullscreen_vertex_shader::fullscreen_shader_vertex_state,
+    ecs::world::FromWorld,
+    prelude::*,
+    render::{
+        render_resource::{
+            BindGroupEntries, BindGroupLayout, BindGroupLayoutEntry, BindingType, BlendComponent,
+            BlendFactor, BlendOperation, BlendState, CachedRenderPipelineId, ColorTargetState,
+            ColorWrites, Extent3d, FragmentState, LoadOp, MultisampleState, Operations,
+            PipelineCache, PrimitiveState, RenderPassColorAttachment, RenderPassDescriptor,
+            RenderPipeline, RenderPipelineDescriptor, ShaderStages, StoreOp, Texture,
+            TextureDescriptor, TextureDimension, TextureFormat, TextureSampleType, TextureUsages,
+            TextureView, TextureViewDimension, ImageDataLayout,
+        },
+        renderer::{RenderContext, RenderDevice, RenderQueue},
+    },
+    utils::hashbrown::HashMap,
+};
+
+//=============================================================================
+//             Exposure Shader Pipeline
+//=============================================================================
+
+// Averages the brightness of the low res texture into a 1x1 texture per camera. The new average
+// is blended with the last one, so the exposure adapts over time instead of jumping.
+#[derive(Resource)]
+pub(crate) struct ExposureShaderPipeline {
+    pub average_textures: HashMap<Entity, Texture>,
+    pub layout: BindGroupLayout,
+    pub pipeline_id: CachedRenderPipelineId,
+}
+
+impl ExposureShaderPipeline {
+    #[cfg(not(feature = "webgl2"))]
+    pub const FORMAT: TextureFormat = TextureFormat::R16Float;
+    // WebGL2 can only render to half floats with an extension, so the average is kept in 8 bits.
+    #[cfg(feature = "webgl2")]
+    pub const FORMAT: TextureFormat = TextureFormat::Rgba8Unorm;
+
+    // Creates the average texture for the camera, starting at the middle of the character ramp.
+    pub fn create_average_texture(&mut self, entity: Entity, render_device: &RenderDevice, render_queue: &RenderQueue) {
+        let size = Extent3d {
+            width: 1,
+            height: 1,
+            depth_or_array_layers: 1,
+        };
+        let texture = render_device.create_texture(&TextureDescriptor {
+            label: "ascii_average_texture".into(),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: Self::FORMAT,
+            usage: TextureUsages::TEXTURE_BINDING | TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+
+        // 0.5 as a half float, or in 8 bits on WebGL2.
+        #[cfg(not(feature = "webgl2"))]
+        let middle = 0x3800u16.to_le_bytes().to_vec();
+        #[cfg(feature = "webgl2")]
+        let middle = vec![128u8, 128, 128, 255];
+
+        render_queue.write_texture(
+            texture.as_image_copy(),
+            &middle,
+            ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(middle.len() as u32),
+                rows_per_image: Some(1),
+            },
+            size,
+        );
+
+        self.average_textures.insert(entity, texture);
+    }
+}
+
+impl FromWorld for ExposureShaderPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+
+        let layout = render_device.create_bind_group_layout(
+            "exposure_shader_bind_group_layout",
+            &[
+                //This is the low res texture
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+            ],
+        );
+
+        let pipeline_id = world
+            .resource_mut::<PipelineCache>()
+            .queue_render_pipeline(RenderPipelineDescriptor {
+                label: Some("exposure_shader_pipeline".into()),
+                layout: vec![layout.clone()],
+                vertex: fullscreen_shader_vertex_state(),
+                fragment: Some(FragmentState {
+                    shader: super::EXPOSURE_SHADER_HANDLE,
+                    shader_defs: vec![],
+                    entry_point: "fragment".into(),
+                    targets: vec![Some(ColorTargetState {
+                        format: Self::FORMAT,
+                        // new * constant + old * (1 - constant), where the constant is set every
+                        // frame from the adaptation speed.
+                        blend: Some(BlendState {
+                            color: BlendComponent {
+                                src_factor: BlendFactor::Constant,
+                                dst_factor: BlendFactor::OneMinusConstant,
+                                operation: BlendOperation::Add,
+                            },
+                            alpha: BlendComponent::REPLACE,
+                        }),
+                        write_mask: ColorWrites::ALL,
+                    })],
+                }),
+                primitive: PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: MultisampleState::default(),
+                push_constant_ranges: vec![],
+            });
+
+        ExposureShaderPipeline {
+            average_textures: HashMap::new(),
+            layout,
+            pipeline_id,
+        }
+    }
+}
+
+pub(crate) fn exposure_pass(
+    low_res_texture: &TextureView,
+    average_texture: &TextureView,
+    render_context: &mut RenderContext,
+    exposure_pipeline: &RenderPipeline,
+    exposure_pipeline_resource: &ExposureShaderPipeline,
+    blend: f32,
+) {
+    let bind_group = render_context.render_device().create_bind_group(
+        "exposure_shader_bind_group",
+        &exposure_pipeline_resource.layout,
+        &BindGroupEntries::single(low_res_texture),
+    );
+
+    let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+        label: Some("exposure_shader_render_pass"),
+        color_attachments: &[Some(RenderPassColorAttachment {
+            view: average_texture,
+            resolve_target: None,
+            ops: Operations {
+                load: LoadOp::Load,
+                store: StoreOp::Store,
+            },
+        })],
+        depth_stencil_attachment: None,
+        timestamp_writes: None,
+        occlusion_query_set: None,
+    });
+
+    render_pass.set_render_pipeline(exposure_pipeline);
+    render_pass.set_bind_group(0, &bind_group, &[]);
+    render_pass.set_blend_constant(Color::rgba_linear(blend, blend, blend, blend));
+    render_pass.draw(0..3, 0..1);
+}
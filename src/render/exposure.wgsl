@@ -0,0 +1,24 @@
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+
+@group(0) @binding(0) var low_res_texture: texture_2d<f32>;
+
+// The most pixels that are read along each axis. Bigger textures are sampled with gaps.
+const MAX_SAMPLES : u32 = 64u;
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let dims = textureDimensions(low_res_texture);
+    let step = max(vec2<u32>(1u, 1u), dims / MAX_SAMPLES);
+
+    var total = 0.0;
+    var count = 0.0;
+    for (var y = 0u; y < dims.y; y += step.y) {
+        for (var x = 0u; x < dims.x; x += step.x) {
+            total += textureLoad(low_res_texture, vec2<u32>(x, y), 0).w;
+            count += 1.0;
+        }
+    }
+
+    let average = total / max(count, 1.0);
+    return vec4<f32>(average, average, average, 1.0);
+}
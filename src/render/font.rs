@@ -0,0 +1,413 @@
+use bevy::{
+    prelude::*,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{
+            Extent3d, ImageCopyTexture, ImageDataLayout, Origin3d, TextureAspect, TextureDimension, TextureFormat,
+            TextureView, TextureViewDescriptor,
+        },
+        renderer::{RenderDevice, RenderQueue},
+        texture::{CompressedImageFormats, ImageFormat, ImageSampler, ImageType},
+        Extract,
+    },
+};
+
+use crate::{ascii::AsciiCamera, ui::character::AsciiGlyph};
+
+use super::ascii::AsciiShaderPipeline;
+
+//=============================================================================
+//             Ascii Font
+//=============================================================================
+
+// The font the scene and the ui are drawn with, which is page 0 of the font atlas. The image has
+// 16 glyphs on every row and `rows` rows of them, in the order of the built-in font, and its glyphs
+// can be any size. Cells take the shape of the glyphs, so a font with 8 by 16 glyphs gives cells
+// that are twice as tall as they are wide. It can be swapped at any time, like from a settings
+// menu. The built-in font is used until the image is loaded, or when it does not fit its rows.
+// The image has to stay in the main world to be checked, which it does when loaded as usual.
+#[derive(Resource, Clone, Debug)]
+pub struct AsciiFont {
+    image: Option<Handle<Image>>,
+    rows: u32,
+    // The size of a glyph of the image once it was loaded and checked.
+    glyph_size: Option<UVec2>,
+}
+
+impl Default for AsciiFont {
+    fn default() -> Self {
+        AsciiFont {
+            image: None,
+            rows: BUILTIN_FONT_ROWS,
+            glyph_size: None,
+        }
+    }
+}
+
+impl AsciiFont {
+    pub fn new(image: Handle<Image>, rows: u32) -> Self {
+        AsciiFont {
+            image: Some(image),
+            rows: rows.clamp(1, GLYPHS_PER_ROW),
+            glyph_size: None,
+        }
+    }
+
+    pub fn image(&self) -> Option<&Handle<Image>> {
+        self.image.as_ref()
+    }
+
+    pub fn rows(&self) -> u32 {
+        self.rows
+    }
+
+    // Draws with the image from the next frame on, or with the built-in font for `None`.
+    pub fn set(&mut self, image: Option<Handle<Image>>, rows: u32) {
+        *self = match image {
+            Some(image) => AsciiFont::new(image, rows),
+            None => AsciiFont::default(),
+        };
+    }
+
+    // The size of a glyph in pixels, or `None` while the built-in font is drawn.
+    pub fn glyph_size(&self) -> Option<UVec2> {
+        self.glyph_size
+    }
+
+    // How much taller than wide the cells are.
+    pub fn cell_aspect(&self) -> f32 {
+        self.glyph_size.map(|size| size.y as f32 / size.x as f32).unwrap_or(1.0)
+    }
+}
+
+// Checks the glyphs of the image whenever the font is set or its image is loaded or changed, and
+// redraws the cameras, which do not notice a new font on their own.
+pub(crate) fn update_font_metrics(
+    mut font: ResMut<AsciiFont>,
+    images: Res<Assets<Image>>,
+    mut image_events: EventReader<AssetEvent<Image>>,
+    mut cameras: Query<&mut AsciiCamera>,
+) {
+    let image_id = font.image.as_ref().map(|image| image.id());
+    let is_image_changed = image_events.read().any(|event| match event {
+        AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } | AssetEvent::Removed { id } => {
+            image_id == Some(*id)
+        }
+        _ => false,
+    });
+    if !font.is_changed() && !is_image_changed {
+        return;
+    }
+
+    let glyph_size = image_id.and_then(|id| images.get(id)).and_then(|image| {
+        let (width, height) = (image.width(), image.height());
+        if image.texture_descriptor.format.block_copy_size(None) != Some(4) {
+            warn!("The font is not an 8 bit rgba image, the built-in font is drawn instead");
+            return None;
+        }
+        if width % GLYPHS_PER_ROW != 0 || height % font.rows != 0 || width == 0 || height == 0 {
+            warn!(
+                "The font is {}x{} pixels, which does not split into {} by {} glyphs, the built-in font is drawn instead",
+                width, height, GLYPHS_PER_ROW, font.rows
+            );
+            return None;
+        }
+        Some(UVec2::new(width / GLYPHS_PER_ROW, height / font.rows))
+    });
+    font.bypass_change_detection().glyph_size = glyph_size;
+
+    for mut camera in cameras.iter_mut() {
+        camera.request_redraw();
+    }
+}
+
+//=============================================================================
+//             Ascii Font Pages
+//=============================================================================
+
+// The pages of the font that glyphs are drawn from with `AsciiGlyph`. Page 0 is always the
+// built-in font, pages 1 to 3 can be set to tilesets with 16 by 16 glyphs each. A tileset is
+// scaled to the width of the built-in font, so its glyphs should be square.
+#[derive(Resource, Default, Clone, Debug)]
+pub struct AsciiFontPages {
+    pages: [Option<Handle<Image>>; AsciiGlyph::PAGE_COUNT as usize - 1],
+}
+
+impl AsciiFontPages {
+    pub fn with_page(mut self, page: u8, image: Handle<Image>) -> Self {
+        self.set_page(page, image);
+        self
+    }
+
+    pub fn set_page(&mut self, page: u8, image: Handle<Image>) {
+        match self.slot(page) {
+            Some(slot) => *slot = Some(image),
+            None => warn!("Font page {} can not be set, only pages 1 to {} can", page, AsciiGlyph::PAGE_COUNT - 1),
+        }
+    }
+
+    pub fn remove_page(&mut self, page: u8) {
+        if let Some(slot) = self.slot(page) {
+            *slot = None;
+        }
+    }
+
+    pub fn page(&self, page: u8) -> Option<&Handle<Image>> {
+        self.pages.get((page as usize).checked_sub(1)?)?.as_ref()
+    }
+
+    fn slot(&mut self, page: u8) -> Option<&mut Option<Handle<Image>>> {
+        self.pages.get_mut((page as usize).checked_sub(1)?)
+    }
+}
+
+// The sprites that cells drawn with `AsciiGlyph::tile` show. The image has 16 square sprites on
+// every row and as many rows as it needs, so an 8 by 8 tileset with 256 sprites is 128 by 128.
+// Sprites are drawn in their own colors, which suits colored 1-bit tilesets.
+#[derive(Resource, Default, Clone, Debug)]
+pub struct AsciiTileAtlas {
+    image: Option<Handle<Image>>,
+}
+
+impl AsciiTileAtlas {
+    pub fn new(image: Handle<Image>) -> Self {
+        AsciiTileAtlas { image: Some(image) }
+    }
+
+    pub fn image(&self) -> Option<&Handle<Image>> {
+        self.image.as_ref()
+    }
+
+    pub fn set_image(&mut self, image: Option<Handle<Image>>) {
+        self.image = image;
+    }
+}
+
+//=============================================================================
+//             Font Atlas
+//=============================================================================
+
+const GLYPHS_PER_ROW: u32 = 16;
+pub(crate) const BUILTIN_FONT_ROWS: u32 = 8;
+
+pub(crate) fn builtin_font() -> Image {
+    Image::from_buffer(
+        include_bytes!("font.png"),
+        ImageType::Format(ImageFormat::Png),
+        CompressedImageFormats::default(),
+        true,
+        ImageSampler::nearest(),
+        RenderAssetUsages::RENDER_WORLD,
+    )
+    .expect("There was an error reading an internal texture.")
+}
+
+// Bound while there is no tile atlas, so tiles only show their background.
+pub(crate) fn empty_tile_atlas() -> Image {
+    Image::new(
+        Extent3d {
+            width: 1,
+            height: 1,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        vec![0; 4],
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    )
+}
+
+// Stacks the pages into one texture from top to bottom, each 16 by 16 glyphs of the size of the
+// glyphs of the font, which has `rows` rows of them. There is always a page for every page of
+// `AsciiGlyph`, and pages that are `None` are left empty.
+pub(crate) fn font_atlas(font: &Image, rows: u32, pages: &[Option<&Image>]) -> Image {
+    let page_size = UVec2::new(font.width(), font.height() / rows.max(1) * GLYPHS_PER_ROW);
+    let page_count = AsciiGlyph::PAGE_COUNT as u32;
+    let mut data = vec![0; (page_size.x * page_size.y * page_count * 4) as usize];
+
+    // The font is copied as it is, it already has the size of the page.
+    let font_bytes = (font.width() * font.height().min(page_size.y) * 4) as usize;
+    if let Some(font_data) = font.data.get(..font_bytes) {
+        data[..font_bytes].copy_from_slice(font_data);
+    }
+    for (page, image) in pages.iter().enumerate().take(page_count as usize - 1) {
+        let Some(image) = image else {
+            continue;
+        };
+        if image.texture_descriptor.format.block_copy_size(None) != Some(4) {
+            warn!("Font page {} is not an 8 bit rgba image and is left empty", page + 1);
+            continue;
+        }
+        copy_page(&mut data, page_size, page as u32 + 1, image);
+    }
+
+    Image::new(
+        Extent3d {
+            width: page_size.x,
+            height: page_size.y * page_count,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        font.texture_descriptor.format,
+        RenderAssetUsages::RENDER_WORLD,
+    )
+}
+
+// The image is scaled so that as wide a square fills the page, so the glyphs of a square tileset
+// take the shape of the cells and a shorter image leaves the bottom of its page empty.
+fn copy_page(data: &mut [u8], page_size: UVec2, page: u32, image: &Image) {
+    let (width, height) = (image.width(), image.height());
+    if width == 0 {
+        return;
+    }
+    for y in 0..page_size.y {
+        let from_y = y * width / page_size.y;
+        if from_y >= height {
+            break;
+        }
+        for x in 0..page_size.x {
+            let from = ((from_y * width + x * width / page_size.x) * 4) as usize;
+            let to = (((page * page_size.y + y) * page_size.x + x) * 4) as usize;
+            if let Some(pixel) = image.data.get(from..from + 4) {
+                data[to..to + 4].copy_from_slice(pixel);
+            }
+        }
+    }
+}
+
+pub(crate) fn create_atlas_texture(
+    render_device: &RenderDevice,
+    queue: &RenderQueue,
+    atlas: &Image,
+    label: &'static str,
+) -> TextureView {
+    let texture = render_device.create_texture(&atlas.texture_descriptor);
+    queue.write_texture(
+        ImageCopyTexture {
+            texture: &texture,
+            mip_level: 0,
+            origin: Origin3d::ZERO,
+            aspect: TextureAspect::All,
+        },
+        &atlas.data,
+        ImageDataLayout {
+            offset: 0,
+            bytes_per_row: Some(4 * atlas.width()),
+            rows_per_image: Some(atlas.height()),
+        },
+        Extent3d {
+            width: atlas.width(),
+            height: atlas.height(),
+            depth_or_array_layers: 1,
+        },
+    );
+
+    texture.create_view(&TextureViewDescriptor {
+        label: label.into(),
+        ..Default::default()
+    })
+}
+
+// The font and the pages that were loaded when the atlas was last built.
+#[derive(Default)]
+pub(crate) struct BuiltFontAtlas {
+    font: Option<AssetId<Image>>,
+    pages: Vec<Option<AssetId<Image>>>,
+}
+
+// Builds the atlas again whenever the font or a page is set, removed, loaded or changed.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn extract_font_pages(
+    font: Extract<Option<Res<AsciiFont>>>,
+    font_pages: Extract<Option<Res<AsciiFontPages>>>,
+    images: Extract<Res<Assets<Image>>>,
+    mut image_events: Extract<EventReader<AssetEvent<Image>>>,
+    pipeline: Option<ResMut<AsciiShaderPipeline>>,
+    render_device: Res<RenderDevice>,
+    queue: Res<RenderQueue>,
+    mut built: Local<BuiltFontAtlas>,
+) {
+    let Some(mut pipeline) = pipeline else {
+        return;
+    };
+
+    let mut loaded: Vec<Option<AssetId<Image>>> = font_pages
+        .as_ref()
+        .map(|font_pages| {
+            font_pages
+                .pages
+                .iter()
+                .map(|page| page.as_ref().map(|page| page.id()).filter(|id| images.contains(*id)))
+                .collect()
+        })
+        .unwrap_or_default();
+    while loaded.last().is_some_and(|page| page.is_none()) {
+        loaded.pop();
+    }
+    // Fonts that did not pass `update_font_metrics` are drawn with the built-in font.
+    let font = font.as_ref().filter(|font| font.glyph_size.is_some());
+    let loaded_font = font
+        .and_then(|font| font.image.as_ref())
+        .map(|image| image.id())
+        .filter(|id| images.contains(*id));
+
+    let modified = image_events.read().any(|event| match event {
+        AssetEvent::Modified { id } => loaded.contains(&Some(*id)) || loaded_font == Some(*id),
+        _ => false,
+    });
+    if !modified && built.font == loaded_font && built.pages == loaded {
+        return;
+    }
+
+    let pages: Vec<Option<&Image>> = loaded.iter().map(|id| id.and_then(|id| images.get(id))).collect();
+    let atlas = match (font, loaded_font.and_then(|id| images.get(id))) {
+        (Some(font), Some(image)) => font_atlas(image, font.rows, &pages),
+        _ => font_atlas(&builtin_font(), BUILTIN_FONT_ROWS, &pages),
+    };
+    pipeline.font_texture = create_atlas_texture(&render_device, &queue, &atlas, "ascii_font_texture");
+    *built = BuiltFontAtlas { font: loaded_font, pages: loaded };
+}
+
+// Uploads the tile atlas again whenever it is set, removed, loaded or changed.
+pub(crate) fn extract_tile_atlas(
+    tile_atlas: Extract<Option<Res<AsciiTileAtlas>>>,
+    images: Extract<Res<Assets<Image>>>,
+    mut image_events: Extract<EventReader<AssetEvent<Image>>>,
+    pipeline: Option<ResMut<AsciiShaderPipeline>>,
+    render_device: Res<RenderDevice>,
+    queue: Res<RenderQueue>,
+    // The atlas that was loaded when it was last uploaded.
+    mut uploaded: Local<Option<AssetId<Image>>>,
+) {
+    let Some(mut pipeline) = pipeline else {
+        return;
+    };
+
+    let loaded = tile_atlas
+        .as_ref()
+        .and_then(|tile_atlas| tile_atlas.image.as_ref())
+        .map(|image| image.id())
+        .filter(|id| images.contains(*id));
+
+    let modified = image_events.read().any(|event| match event {
+        AssetEvent::Modified { id } => loaded == Some(*id),
+        _ => false,
+    });
+    if !modified && *uploaded == loaded {
+        return;
+    }
+
+    let image = loaded.and_then(|id| images.get(id)).filter(|image| {
+        let is_rgba = image.texture_descriptor.format.block_copy_size(None) == Some(4);
+        if !is_rgba {
+            warn!("The tile atlas is not an 8 bit rgba image and is not drawn");
+        }
+        is_rgba
+    });
+    pipeline.tile_texture = match image {
+        Some(image) => create_atlas_texture(&render_device, &queue, image, "ascii_tile_texture"),
+        None => create_atlas_texture(&render_device, &queue, &empty_tile_atlas(), "ascii_tile_texture"),
+    };
+    *uploaded = loaded;
+}
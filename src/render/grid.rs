@@ -0,0 +1,436 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    mpsc::{channel, Receiver, Sender},
+    Arc, Mutex,
+};
+
+use bevy::{
+    prelude::*,
+    render::{
+        extract_component::{ExtractComponent, ExtractComponentPlugin},
+        render_resource::{
+            Buffer, BufferDescriptor, BufferUsages, Extent3d, ImageCopyBuffer, ImageDataLayout,
+            Maintain, MapMode, Texture,
+        },
+        renderer::{RenderContext, RenderDevice},
+        Render, RenderApp, RenderSet,
+    },
+};
+
+use crate::{
+    ascii::AsciiCamera,
+    ui::{
+        buffer::AsciiSurface,
+        character::{AsciiBlendMode, AsciiCharacter, Character, Color},
+    },
+};
+
+use super::{ascii::OverlayBuffer, classify::ClassifyShaderPipeline, prepare_shader_textures};
+
+// The characters the ascii shader picks from, from darkest to brightest. This needs to match
+// `ramp_character` in ascii_cell.wgsl.
+const SCENE_CHARACTERS: [Character; 10] = [
+    Character::Period,
+    Character::Colon,
+    Character::Hyphen,
+    Character::Plus,
+    Character::Asterisk,
+    Character::Sign,
+    Character::Doughnut,
+    Character::Circle,
+    Character::DiagonalCross,
+    Character::Dither,
+];
+
+//=============================================================================
+//             Scene Grid Plugin
+//=============================================================================
+
+// Reads the character grid the ascii shader produced for the scene back from the gpu and
+// stores it in the `AsciiSceneGrid` resource. Only the camera with an `AsciiSceneGridSource`
+// is read back. With `AsciiRenderPlugin::compute_cells` the cells the gpu picked are read as
+// they are, otherwise the characters are picked again from the scene on the cpu. Reading back stalls the render thread, so it is only done every `interval`
+// frames.
+pub struct AsciiSceneGridPlugin {
+    pub interval: u32,
+}
+
+impl Default for AsciiSceneGridPlugin {
+    fn default() -> Self {
+        AsciiSceneGridPlugin { interval: 10 }
+    }
+}
+
+impl Plugin for AsciiSceneGridPlugin {
+    fn build(&self, app: &mut App) {
+        let (sender, receiver) = channel();
+
+        app.init_resource::<AsciiSceneGrid>()
+            .insert_resource(SceneGridReceiver(Mutex::new(receiver)))
+            .register_type::<AsciiSceneGridSource>()
+            .add_plugins(ExtractComponentPlugin::<AsciiSceneGridSource>::default())
+            .add_systems(PreUpdate, receive_scene_grid);
+
+        let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app
+            .insert_resource(SceneGridReadback {
+                interval: self.interval.max(1),
+                frame: 0,
+                sender,
+                buffer: None,
+                is_classified: false,
+                camera: None,
+                settings: AsciiCamera::default(),
+                overlay: AsciiSurface::default(),
+                is_copied: AtomicBool::new(false),
+                mapping: None,
+            })
+            .add_systems(
+                Render,
+                prepare_scene_grid_readback
+                    .in_set(RenderSet::PrepareResources)
+                    .after(prepare_shader_textures),
+            )
+            .add_systems(Render, read_scene_grid.in_set(RenderSet::Cleanup));
+    }
+}
+
+//=============================================================================
+//             Scene Grid
+//=============================================================================
+
+// Marks the ascii camera whose scene should be read back into the `AsciiSceneGrid`.
+#[derive(Component, Clone, Copy, Default, Reflect, ExtractComponent)]
+pub struct AsciiSceneGridSource;
+
+#[derive(Clone, Copy, PartialEq)]
+pub struct AsciiSceneCell {
+    pub character: Character,
+    pub color: Color,
+    // The brightness the character was picked from, between 0 and 1.
+    pub value: f32,
+}
+
+// The characters and colors the ascii shader picked for every cell of the scene. The ui
+// overlay is kept separately, so gameplay code can look at the scene alone. The grid is a few
+// frames behind what is on screen.
+#[derive(Resource, Default, Clone)]
+pub struct AsciiSceneGrid {
+    width: u32,
+    height: u32,
+    cells: Vec<AsciiSceneCell>,
+    overlay: Vec<AsciiCharacter>,
+}
+
+impl AsciiSceneGrid {
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn get(&self, x: i32, y: i32) -> Option<&AsciiSceneCell> {
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return None;
+        }
+        self.cells.get((y as u32 * self.width + x as u32) as usize)
+    }
+
+    // The ui character drawn over the cell, if there is one.
+    pub fn overlay(&self, x: i32, y: i32) -> Option<(Character, Color, Color)> {
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return None;
+        }
+        match self.overlay.get((y as u32 * self.width + x as u32) as usize) {
+            Some(AsciiCharacter::Set { index, text_color, background_color, .. }) => {
+                Some((*index, *text_color, *background_color))
+            }
+            _ => None,
+        }
+    }
+
+    // The character, text color and background color that end up on screen for the cell.
+    // Overlay cells that are less than half opaque count as hidden.
+    pub fn visible(&self, x: i32, y: i32) -> Option<(Character, Color, Color)> {
+        let scene = self.get(x, y);
+        let (blend, opacity) = match self.overlay.get((y.max(0) as u32 * self.width + x.max(0) as u32) as usize) {
+            Some(AsciiCharacter::Set { blend, opacity, .. }) => (*blend, *opacity),
+            _ => (AsciiBlendMode::Replace, 1.0),
+        };
+        let overlay = self.overlay(x, y).filter(|_| opacity >= 0.5);
+
+        match (overlay, scene) {
+            (Some((_, text_color, bg_color)), Some(scene)) if blend == AsciiBlendMode::Tint => {
+                Some((scene.character, text_color, bg_color))
+            }
+            (Some((_, _, bg_color)), Some(scene)) if blend == AsciiBlendMode::Behind => {
+                Some((scene.character, scene.color, bg_color))
+            }
+            (Some(overlay), _) => Some(overlay),
+            (None, scene) => scene.map(|cell| (cell.character, cell.color, Color::Black)),
+        }
+    }
+
+    pub fn cells(&self) -> impl Iterator<Item = (i32, i32, &AsciiSceneCell)> {
+        let width = self.width.max(1);
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| ((i as u32 % width) as i32, (i as u32 / width) as i32, cell))
+    }
+
+    // Converts the rgba8 srgb pixels of the low res texture into cells. `bytes_per_row`
+    // includes the padding the gpu adds to every row.
+    fn from_pixels(data: &[u8], width: u32, height: u32, bytes_per_row: u32, camera: &AsciiCamera) -> Self {
+        let to_linear = |value: u8| {
+            let value = value as f32 / 255.0;
+            if value <= 0.04045 {
+                value / 12.92
+            } else {
+                ((value + 0.055) / 1.055).powf(2.4)
+            }
+        };
+
+        // The gpu adapts its average over time, this uses the average of the frame itself.
+        let mut total = 0.0;
+        for y in 0..height {
+            let row = &data[(y * bytes_per_row) as usize..];
+            for x in 0..width as usize {
+                total += row[x * 4 + 3] as f32 / 255.0;
+            }
+        }
+        let average = total / (width * height).max(1) as f32;
+
+        let mut cells = Vec::with_capacity((width * height) as usize);
+        for y in 0..height {
+            let row = &data[(y * bytes_per_row) as usize..];
+            for x in 0..width as usize {
+                let pixel = &row[x * 4..x * 4 + 4];
+                let value = camera.adjust_value(pixel[3] as f32 / 255.0, average);
+                let index = ((value / 0.1).floor() as usize).min(SCENE_CHARACTERS.len() - 1);
+                cells.push(AsciiSceneCell {
+                    character: SCENE_CHARACTERS[index],
+                    color: Color::nearest(to_linear(pixel[0]), to_linear(pixel[1]), to_linear(pixel[2])),
+                    value,
+                });
+            }
+        }
+
+        AsciiSceneGrid {
+            width,
+            height,
+            cells,
+            overlay: Vec::new(),
+        }
+    }
+
+    // Converts the rgba32 cells of the compute pass into cells. Each cell is the character, the
+    // color packed into 8 bits a channel and the bits of the value.
+    fn from_cells(data: &[u8], width: u32, height: u32, bytes_per_row: u32) -> Self {
+        let mut cells = Vec::with_capacity((width * height) as usize);
+        for y in 0..height {
+            let row = &data[(y * bytes_per_row) as usize..];
+            for x in 0..width as usize {
+                let channel = |i: usize| {
+                    let start = x * 16 + i * 4;
+                    u32::from_le_bytes([row[start], row[start + 1], row[start + 2], row[start + 3]])
+                };
+                let [r, g, b, _] = channel(1).to_le_bytes().map(|value| value as f32 / 255.0);
+                cells.push(AsciiSceneCell {
+                    character: Character::from_index(channel(0).min(255) as u8).unwrap_or(Character::Space),
+                    color: Color::nearest(r, g, b),
+                    value: f32::from_bits(channel(2)),
+                });
+            }
+        }
+
+        AsciiSceneGrid {
+            width,
+            height,
+            cells,
+            overlay: Vec::new(),
+        }
+    }
+}
+
+#[derive(Resource)]
+struct SceneGridReceiver(Mutex<Receiver<AsciiSceneGrid>>);
+
+fn receive_scene_grid(receiver: Res<SceneGridReceiver>, mut grid: ResMut<AsciiSceneGrid>) {
+    let Ok(receiver) = receiver.0.lock() else { return };
+    if let Some(latest) = receiver.try_iter().last() {
+        *grid = latest;
+    }
+}
+
+//=============================================================================
+//             Render World
+//=============================================================================
+
+#[derive(Resource)]
+pub(crate) struct SceneGridReadback {
+    interval: u32,
+    frame: u32,
+    sender: Sender<AsciiSceneGrid>,
+    buffer: Option<(Buffer, UVec2)>,
+    // Whether the cells of the compute pass are copied, instead of the low res texture.
+    is_classified: bool,
+    // The camera that is being copied this frame.
+    camera: Option<Entity>,
+    // The image settings of that camera, so the characters match the screen.
+    settings: AsciiCamera,
+    // The last overlay of the camera. It is only extracted on frames where the ui changed.
+    overlay: AsciiSurface,
+    // Set by the ascii node once the copy of this frame is in its commands. The camera is not
+    // drawn on every frame, and a buffer nothing was copied to is not mapped.
+    is_copied: AtomicBool,
+    // Set while the buffer is being mapped, and holds whether mapping worked once it is done.
+    mapping: Option<Arc<Mutex<Option<bool>>>>,
+}
+
+impl SceneGridReadback {
+    fn bytes_per_row(&self, width: u32) -> u32 {
+        let texel_size = if self.is_classified { 16 } else { 4 };
+        RenderDevice::align_copy_bytes_per_row(width as usize * texel_size) as u32
+    }
+
+    pub(crate) fn copy_from(&self, entity: Entity, texture: &Texture, render_context: &mut RenderContext) {
+        let (Some(camera), Some((buffer, size))) = (self.camera, &self.buffer) else { return };
+        if camera != entity || texture.width() != size.x || texture.height() != size.y {
+            return;
+        }
+
+        render_context.command_encoder().copy_texture_to_buffer(
+            texture.as_image_copy(),
+            ImageCopyBuffer {
+                buffer,
+                layout: ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(self.bytes_per_row(size.x)),
+                    rows_per_image: None,
+                },
+            },
+            Extent3d {
+                width: size.x,
+                height: size.y,
+                depth_or_array_layers: 1,
+            },
+        );
+        self.is_copied.store(true, Ordering::Release);
+    }
+}
+
+fn prepare_scene_grid_readback(
+    mut readback: ResMut<SceneGridReadback>,
+    cameras: Query<(Entity, &AsciiCamera, Option<&OverlayBuffer>), With<AsciiSceneGridSource>>,
+    classify_pipeline: Option<Res<ClassifyShaderPipeline>>,
+    render_device: Res<RenderDevice>,
+) {
+    readback.camera = None;
+    *readback.is_copied.get_mut() = false;
+    if let Some(overlay) = cameras.iter().find_map(|(_, _, overlay)| overlay) {
+        readback.overlay = overlay.0.clone();
+    }
+
+    readback.frame = readback.frame.wrapping_add(1);
+    // The buffer can not be copied to until the last copy was read.
+    if !readback.frame.is_multiple_of(readback.interval) || readback.mapping.is_some() {
+        return;
+    }
+
+    let Some((entity, camera, _)) = cameras.iter().next() else { return };
+    let size = UVec2::new(camera.target_res().x as u32, camera.target_res().y as u32);
+    if size.x == 0 || size.y == 0 {
+        return;
+    }
+
+    readback.is_classified = classify_pipeline.is_some();
+    if readback.buffer.as_ref().map(|(_, buffer_size)| *buffer_size != size).unwrap_or(true) {
+        let buffer = render_device.create_buffer(&BufferDescriptor {
+            label: Some("ascii_scene_grid_buffer"),
+            size: (readback.bytes_per_row(size.x) * size.y) as u64,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        readback.buffer = Some((buffer, size));
+    }
+
+    readback.camera = Some(entity);
+    readback.settings = camera.clone();
+}
+
+// Runs after the frame was submitted, so the copy made by the ascii node is ready to be mapped.
+fn read_scene_grid(mut readback: ResMut<SceneGridReadback>, render_device: Res<RenderDevice>) {
+    if readback.mapping.is_none() {
+        if !readback.is_copied.swap(false, Ordering::Acquire) {
+            return;
+        }
+        let Some((buffer, _)) = &readback.buffer else { return };
+
+        let mapping = Arc::new(Mutex::new(None));
+        let result = mapping.clone();
+        buffer.slice(..).map_async(MapMode::Read, move |mapped| {
+            if let Ok(mut result) = result.lock() {
+                *result = Some(mapped.is_ok());
+            }
+        });
+        readback.mapping = Some(mapping);
+    }
+
+    // Browsers can not wait on the gpu, so there the buffer is mapped on a later frame instead.
+    #[cfg(not(target_arch = "wasm32"))]
+    render_device.poll(Maintain::Wait);
+    #[cfg(target_arch = "wasm32")]
+    render_device.poll(Maintain::Poll);
+
+    let Some(is_mapped) = readback.mapping.as_ref().and_then(|mapping| *mapping.lock().ok()?) else {
+        return;
+    };
+    readback.mapping = None;
+
+    let Some((buffer, size)) = &readback.buffer else { return };
+    if !is_mapped {
+        return;
+    }
+
+    let mut grid = {
+        let data = buffer.slice(..).get_mapped_range();
+        let bytes_per_row = readback.bytes_per_row(size.x);
+        if readback.is_classified {
+            AsciiSceneGrid::from_cells(&data, size.x, size.y, bytes_per_row)
+        } else {
+            AsciiSceneGrid::from_pixels(&data, size.x, size.y, bytes_per_row, &readback.settings)
+        }
+    };
+    buffer.unmap();
+
+    // A scaled ui is scaled up to the grid of the scene, the same way the shader draws it.
+    let scale = readback.settings.ui_scale();
+    let ui_size = readback.settings.ui_res();
+    if readback.overlay.width() == ui_size.x as u32 && readback.overlay.height() == ui_size.y as u32 {
+        let overlay = readback.overlay.characters();
+        grid.overlay = if scale == 1 {
+            overlay
+        } else {
+            (0..size.x * size.y)
+                .map(|index| {
+                    let (x, y) = (index % size.x / scale, index / size.x / scale);
+                    match x < readback.overlay.width() && y < readback.overlay.height() {
+                        true => overlay[(y * readback.overlay.width() + x) as usize],
+                        false => AsciiCharacter::default(),
+                    }
+                })
+                .collect()
+        };
+    }
+    let _ = readback.sender.send(grid);
+}
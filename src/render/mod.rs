@@ -1,29 +1,47 @@
 pub mod ascii;
+mod classify;
 mod dither;
+mod exposure;
+pub mod font;
+pub mod grid;
 mod pixel;
+pub mod screenshot;
+mod stability;
+pub mod style;
 
 use bevy::{
-    app::Plugin, asset::load_internal_asset, core_pipeline::core_3d::{self, graph::{Core3d, Node3d}}, prelude::*, render::{
-        render_asset::RenderAssetUsages, render_graph::{RenderGraphApp, RenderLabel, ViewNode, ViewNodeRunner}, render_resource::{
-            BindGroupEntries, Extent3d, ImageDataLayout, Operations, PipelineCache, RenderPassColorAttachment, RenderPassDescriptor, RenderPipeline,
-            TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+    app::Plugin, asset::load_internal_asset, core_pipeline::core_3d::graph::{Core3d, Node3d}, prelude::*, ui::graph::NodeUi, render::{
+        camera::CameraUpdateSystem, render_asset::RenderAssetUsages, render_graph::{RenderGraph, RenderGraphApp, RenderLabel, ViewNode, ViewNodeRunner}, render_resource::{
+            BindGroupEntries, BindingResource, Extent3d, ImageCopyTexture, ImageDataLayout, Operations, Origin3d, TextureAspect, PipelineCache, RenderPassColorAttachment, RenderPassDescriptor, RenderPipeline,
+            Texture, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
             TextureView, TextureViewDescriptor,
-        }, renderer::{RenderContext, RenderDevice, RenderQueue}, texture::{BevyDefault, CompressedImageFormats, ImageFormat, ImageSampler, ImageType}, view::{ExtractedWindows, PostProcessWrite, RenderLayers, ViewTarget}, Extract, Render, RenderApp, RenderSet
-    }
+        }, renderer::{RenderContext, RenderDevice, RenderQueue}, texture::{BevyDefault, CompressedImageFormats, ImageFormat, ImageSampler, ImageType}, view::{ExtractedView, PostProcessWrite, RenderLayers, ViewDepthTexture, ViewTarget, VisibilitySystems}, Extract, Render, RenderApp, RenderSet
+    }, transform::TransformSystem, utils::{HashMap, HashSet}
 };
 
 
 use crate::{
-    ascii::AsciiCamera,
+    ascii::{update_target_resolution, AsciiCamera, AsciiCameraSet},
     ui::{
+        billboard::BillboardDepthReadback,
+        bounds::AsciiBounds,
         buffer::AsciiSurface,
+        diagnostics::AsciiUiRenderStats,
+        time::AsciiUiTime,
         AsciiUi,
     },
 };
 
 use self::{
-    ascii::{AsciiShaderPipeline, OverlayBuffer},
+    ascii::{AsciiCellHook, AsciiSettingsUniform, AsciiShaderPipeline, LiveOverlayBuffer, OverlayBuffer, OverlayCamera, OverlayTrueColor},
+    classify::{classify_pass, ClassifyShaderPipeline},
+    exposure::{exposure_pass, ExposureShaderPipeline},
+    font::{extract_font_pages, extract_tile_atlas, update_font_metrics, AsciiFont, AsciiFontPages, AsciiTileAtlas},
+    grid::SceneGridReadback,
     pixel::PixelShaderPipeline,
+    screenshot::{extract_screenshot_requests, prepare_screenshots, read_screenshots, AsciiScreenshotEvent, ScreenshotReadback},
+    stability::{stability_pass, StabilityShaderPipeline},
+    style::{extract_style_grids, prepare_style_textures, update_style_grids, AsciiHighlight, AsciiStyleOverride3d},
 };
 
 //=============================================================================
@@ -32,9 +50,18 @@ use self::{
 
 pub const PIXEL_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(11079857277321826659);
 pub const ASCII_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(11079037277321826659);
+pub const EXPOSURE_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(11079037277321826771);
+pub const ASCII_CELL_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(11079037277321826883);
+pub const ASCII_CELL_HOOK_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(11079037277321826884);
+pub const ASCII_CLASSIFY_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(11079037277321826995);
+pub const STABILITY_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(11079037277321827107);
 pub const ASCII_FONT_HANDLE: Handle<Image> = Handle::weak_from_u128(11068737277321826659);
 
-pub(crate) struct AsciiRendererPlugin;
+pub(crate) struct AsciiRendererPlugin {
+    pub placement: AsciiNodePlacement,
+    pub cell_hook: Option<String>,
+    pub compute_cells: bool,
+}
 
 impl Plugin for AsciiRendererPlugin {
     fn build(&self, app: &mut App) {
@@ -53,6 +80,55 @@ impl Plugin for AsciiRendererPlugin {
             Shader::from_wgsl
         );
         
+        load_internal_asset!(
+            app,
+            EXPOSURE_SHADER_HANDLE,
+            "exposure.wgsl",
+            Shader::from_wgsl
+        );
+
+        load_internal_asset!(
+            app,
+            ASCII_CELL_SHADER_HANDLE,
+            "ascii_cell.wgsl",
+            Shader::from_wgsl
+        );
+
+        load_internal_asset!(
+            app,
+            ASCII_CELL_HOOK_SHADER_HANDLE,
+            "ascii_cell_hook.wgsl",
+            Shader::from_wgsl
+        );
+
+        load_internal_asset!(
+            app,
+            ASCII_CLASSIFY_SHADER_HANDLE,
+            "ascii_classify.wgsl",
+            Shader::from_wgsl
+        );
+
+        load_internal_asset!(
+            app,
+            STABILITY_SHADER_HANDLE,
+            "stability.wgsl",
+            Shader::from_wgsl
+        );
+
+        // The hook is imported by its asset path in place of the one that does nothing. Imports
+        // by path are waited on, so the pipeline is made once the hook is loaded.
+        if let Some(path) = &self.cell_hook {
+            let mut shaders = app.world.resource_mut::<Assets<Shader>>();
+            shaders.insert(ASCII_SHADER_HANDLE, Shader::from_wgsl(with_cell_hook(include_str!("ascii.wgsl"), path), "ascii.wgsl"));
+            shaders.insert(
+                ASCII_CLASSIFY_SHADER_HANDLE,
+                Shader::from_wgsl(with_cell_hook(include_str!("ascii_classify.wgsl"), path), "ascii_classify.wgsl"),
+            );
+
+            let shader = app.world.resource::<AssetServer>().load(path.clone());
+            app.insert_resource(AsciiCellHook(shader));
+        }
+        
         let mut assets = app.world.resource_mut::<Assets<Image>>();
         let image = Image::from_buffer(
             include_bytes!("font.png"),
@@ -63,6 +139,24 @@ impl Plugin for AsciiRendererPlugin {
             RenderAssetUsages::RENDER_WORLD
         ).expect("Should load raindrops successfully");
         assets.insert(ASCII_FONT_HANDLE, image);
+
+        app.init_resource::<AsciiFont>()
+            .init_resource::<AsciiFontPages>()
+            .init_resource::<AsciiTileAtlas>()
+            .register_type::<AsciiStyleOverride3d>()
+            .register_type::<AsciiHighlight>()
+            .add_event::<AsciiScreenshotEvent>()
+            // The grid of every camera follows the shape of the glyphs of the font.
+            .add_systems(PreUpdate, update_font_metrics.in_set(AsciiCameraSet).before(update_target_resolution))
+            // The styles are drawn once the cameras and transforms are up to date, and it is known
+            // which entities can be seen.
+            .add_systems(
+                PostUpdate,
+                update_style_grids
+                    .after(TransformSystem::TransformPropagate)
+                    .after(CameraUpdateSystem)
+                    .after(VisibilitySystems::CheckVisibility),
+            );
         
         // We need to get the render app from the main app
         let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
@@ -71,34 +165,57 @@ impl Plugin for AsciiRendererPlugin {
         
 
         render_app
+            .init_resource::<ScreenshotReadback>()
             .add_systems(
                 Render,
-                prepare_shader_textures.in_set(RenderSet::PrepareResources),
+                (prepare_shader_textures, prepare_style_textures, prepare_screenshots).in_set(RenderSet::PrepareResources),
+            )
+            .add_systems(Render, read_screenshots.in_set(RenderSet::Cleanup))
+            .add_systems(
+                ExtractSchedule,
+                (
+                    extract_camera,
+                    extract_font_pages,
+                    extract_tile_atlas,
+                    extract_style_grids,
+                    extract_screenshot_requests,
+                    apply_deferred,
+                ),
             )
-            .add_systems(ExtractSchedule, (extract_camera, apply_deferred))
             .add_render_graph_node::<ViewNodeRunner<AsciiShaderNode>>(
                 Core3d,
                 AsciiShaderNodeId,
-            )
-            .add_render_graph_edges(
-                Core3d,
-                (
-                    Node3d::Tonemapping,
-                    AsciiShaderNodeId,
-                    Node3d::EndMainPassPostProcessing,
-                ),
             );
     }
 
     fn finish(&self, app: &mut App) {
+        let cell_hook = app.world.get_resource::<AsciiCellHook>().cloned();
         let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
             return;
         };
 
+        // The pipeline is only made once, so the hook has to be known before it is.
+        if let Some(cell_hook) = cell_hook {
+            render_app.insert_resource(cell_hook);
+        }
+
+        // The edges are added here, so the ui pass exists no matter which plugin was added first.
+        add_node_edges(render_app, self.placement);
+
+        // WebGL2 has no compute shaders, so the characters are always picked in the ascii shader.
+        if self.compute_cells && cfg!(feature = "webgl2") {
+            warn!("Compute cells are not supported on WebGL2, the characters are picked in the ascii shader instead");
+        } else if self.compute_cells {
+            // The ascii shader is made to read the cells when this exists, so it goes first.
+            render_app.init_resource::<ClassifyShaderPipeline>();
+        }
+
         render_app
             // Initialize the pipeline
             .init_resource::<AsciiShaderPipeline>()
-            .init_resource::<PixelShaderPipeline>();
+            .init_resource::<PixelShaderPipeline>()
+            .init_resource::<ExposureShaderPipeline>()
+            .init_resource::<StabilityShaderPipeline>();
     }
 }
 
@@ -109,6 +226,65 @@ impl Plugin for AsciiRendererPlugin {
 #[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
 pub struct AsciiShaderNodeId;
 
+// Where the ascii node goes on the `Core3d` render graph. The node only supports cameras without
+// hdr, wherever it is placed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum AsciiNodePlacement {
+    // Before bloom and tonemapping, so the characters themselves glow and are tonemapped.
+    BeforeTonemapping,
+    // Between bloom and tonemapping, so the glow of the scene is turned into characters.
+    AfterBloom,
+    #[default]
+    AfterTonemapping,
+    // After the bevy ui was drawn, so the bevy ui is turned into characters too.
+    AfterUi,
+    // The node is added without any edges, and `AsciiShaderNodeId` has to be placed by hand.
+    Manual,
+}
+
+fn add_node_edges(render_app: &mut App, placement: AsciiNodePlacement) {
+    match placement {
+        AsciiNodePlacement::BeforeTonemapping => {
+            render_app.add_render_graph_edges(
+                Core3d,
+                (Node3d::EndMainPass, AsciiShaderNodeId, Node3d::Bloom, Node3d::Tonemapping),
+            );
+        }
+        AsciiNodePlacement::AfterBloom => {
+            render_app.add_render_graph_edges(
+                Core3d,
+                (Node3d::Bloom, AsciiShaderNodeId, Node3d::Tonemapping),
+            );
+        }
+        AsciiNodePlacement::AfterTonemapping => {
+            render_app.add_render_graph_edges(
+                Core3d,
+                (Node3d::Tonemapping, AsciiShaderNodeId, Node3d::EndMainPassPostProcessing),
+            );
+        }
+        AsciiNodePlacement::AfterUi => {
+            let has_ui_pass = render_app
+                .world
+                .get_resource::<RenderGraph>()
+                .and_then(|graph| graph.get_sub_graph(Core3d))
+                .map(|graph| graph.get_node_state(NodeUi::UiPass).is_ok())
+                .unwrap_or(false);
+
+            if !has_ui_pass {
+                warn!("The ascii node can not go after the ui without the bevy UiPlugin, it is placed after tonemapping instead");
+                add_node_edges(render_app, AsciiNodePlacement::AfterTonemapping);
+                return;
+            }
+
+            render_app.add_render_graph_edges(
+                Core3d,
+                (NodeUi::UiPass, AsciiShaderNodeId, Node3d::Upscaling),
+            );
+        }
+        AsciiNodePlacement::Manual => {}
+    }
+}
+
 #[derive(Default)]
 pub struct AsciiShaderNode;
 // impl AsciiShaderNode {
@@ -116,21 +292,30 @@ pub struct AsciiShaderNode;
 // }
 
 impl ViewNode for AsciiShaderNode {
-    type ViewQuery = (Entity, &'static ViewTarget, &'static AsciiCamera);
+    type ViewQuery = (
+        Entity,
+        &'static ViewTarget,
+        &'static AsciiCamera,
+        &'static ExtractedView,
+        Option<&'static ViewDepthTexture>,
+        Option<&'static AsciiNeedsRedraw>,
+    );
 
     fn run(
         &self,
-        graph: &mut bevy::render::render_graph::RenderGraphContext,
+        _graph: &mut bevy::render::render_graph::RenderGraphContext,
         render_context: &mut bevy::render::renderer::RenderContext,
         view_query: bevy::ecs::query::QueryItem<Self::ViewQuery>,
         world: &World,
     ) -> Result<(), bevy::render::render_graph::NodeRunError> {
-        let (entity, view_target, ascii_camera) = view_query;
+        let (entity, view_target, ascii_camera, view, depth, needs_redraw) = view_query;
 
         // Get the pipeline resource that contains the global data we need
         // to create the render pipeline
         let ascii_pipeline_resource = world.resource::<AsciiShaderPipeline>();
         let pixel_pipeline_resource = world.resource::<PixelShaderPipeline>();
+        let exposure_pipeline_resource = world.resource::<ExposureShaderPipeline>();
+        let stability_pipeline_resource = world.resource::<StabilityShaderPipeline>();
 
         // The pipeline cache is a cache of all previously created pipelines.
         // It is required to avoid creating a new pipeline each frame,
@@ -150,31 +335,101 @@ impl ViewNode for AsciiShaderNode {
             return Ok(());
         };
 
-        // let acsii_camera = world.resource::<AsciiCamera>();
-        // Get the settings uniform binding
-        let settings_uniforms = ascii_camera.buffer(
-            render_context.render_device(),
-            world.get_resource::<RenderQueue>().unwrap(),
-            view_target.main_texture().width(),
-        );
+        // With compute cells the characters are picked before the ascii shader, which has to
+        // wait for the compute pipeline and the cells of the camera.
+        let classify = match world.get_resource::<ClassifyShaderPipeline>() {
+            Some(classify_pipeline_resource) => {
+                let Some(classify_pipeline) = pipeline_cache.get_compute_pipeline(classify_pipeline_resource.pipeline_id) else {
+                    return Ok(());
+                };
+                let Some(cell_texture) = classify_pipeline_resource.cell_textures.get(&entity) else {
+                    return Ok(());
+                };
+                Some((classify_pipeline_resource, classify_pipeline, cell_texture))
+            }
+            None => None,
+        };
+
+        // Cameras with temporal stability wait for its pipeline, the characters would all be
+        // picked from a brightness of 0 without it.
+        let stability = match stability_pipeline_resource.stable_textures.get(&entity) {
+            Some(stable_textures) => {
+                let Some(stability_pipeline) = pipeline_cache.get_render_pipeline(stability_pipeline_resource.pipeline_id) else {
+                    return Ok(());
+                };
+                Some((stability_pipeline, stable_textures))
+            }
+            None => None,
+        };
+
+        // Every camera has its own settings, so cameras sharing a window can look different.
+        // They are uploaded in `prepare_shader_textures`.
+        let Some(settings_uniform) = ascii_pipeline_resource.settings_uniforms.get(&entity) else {
+            return Ok(());
+        };
+
+        let Some(viewport_binding) = settings_uniform.viewport_buffer.binding() else {
+            return Ok(());
+        };
 
         let Some(low_res_texture) = pixel_pipeline_resource.low_res_textures.get(&entity) else {
             return Ok(());
         };
 
-        let Some(overlay_texture) = ascii_pipeline_resource.overlay_textures.get(&entity) else {
+        let low_res_view = low_res_texture.create_view(&TextureViewDescriptor {
+            label: Some("low_res_texture"),
+            ..Default::default()
+        });
+
+        let Some(average_texture) = exposure_pipeline_resource.average_textures.get(&entity) else {
             return Ok(());
         };
 
-        let overlay_texture = overlay_texture.create_view(&TextureViewDescriptor {
-            label: None,
+        let average_view = average_texture.create_view(&TextureViewDescriptor {
+            label: Some("ascii_average_texture"),
             ..Default::default()
         });
 
-        let Some(settings_binding) = settings_uniforms.binding() else {
+        // Cameras without an `AsciiUi` have no overlay, and are given one that is empty instead.
+        let overlay_texture = ascii_pipeline_resource.overlay_textures.get(&entity).map(|texture| {
+            texture.create_view(&TextureViewDescriptor {
+                label: Some("overlay_texture"),
+                ..Default::default()
+            })
+        });
+
+        let rgb_overlay_texture = ascii_pipeline_resource.rgb_overlay_textures.get(&entity).map(|texture| {
+            texture.create_view(&TextureViewDescriptor {
+                label: Some("rgb_overlay_texture"),
+                ..Default::default()
+            })
+        });
+
+        let Some(settings_binding) = settings_uniform.settings_buffer.binding() else {
             return Ok(());
         };
 
+        // On demand cameras that have nothing new to show put their last output back and skip
+        // the passes.
+        let output_texture = ascii_pipeline_resource
+            .output_textures
+            .get(&entity)
+            .filter(|_| ascii_camera.on_demand);
+        let can_copy_back = view_target.main_texture().usage().contains(TextureUsages::COPY_DST);
+        if let Some(output_texture) = output_texture {
+            if can_copy_back && !needs_redraw.map(|redraw| redraw.0).unwrap_or(true) {
+                render_context.command_encoder().copy_texture_to_texture(
+                    output_texture.as_image_copy(),
+                    viewport_image_copy(view_target.main_texture(), view.viewport),
+                    output_texture.size(),
+                );
+                if let Some(screenshots) = world.get_resource::<ScreenshotReadback>() {
+                    screenshots.copy_from(entity, view_target.main_texture(), view.viewport, render_context);
+                }
+                return Ok(());
+            }
+        }
+
         // This will start a new "post process write", obtaining two texture
         // views from the view target - a `source` and a `destination`.
         // `source` is the "current" main texture and you _must_ write into
@@ -185,13 +440,92 @@ impl ViewNode for AsciiShaderNode {
         let post_process = view_target.post_process_write();
 
         pixel_pass(
-            low_res_texture,
+            &low_res_view,
             render_context,
             pixel_pipeline,
             pixel_pipeline_resource,
             &post_process,
+            viewport_binding,
         );
 
+        // Blend the average brightness of this frame into the adapted average for auto exposure.
+        if ascii_camera.auto_exposure {
+            if let Some(exposure_pipeline) = pipeline_cache.get_render_pipeline(exposure_pipeline_resource.pipeline_id) {
+                let delta_seconds = world.resource::<Time>().delta_seconds();
+                exposure_pass(
+                    &low_res_view,
+                    &average_view,
+                    render_context,
+                    exposure_pipeline,
+                    exposure_pipeline_resource,
+                    ascii_camera.auto_exposure_blend(delta_seconds),
+                );
+            }
+        }
+
+        if let Some((stability_pipeline, stable_textures)) = stability {
+            stability_pass(
+                &low_res_view,
+                &average_view,
+                stable_textures,
+                settings_binding.clone(),
+                render_context,
+                stability_pipeline,
+                stability_pipeline_resource,
+            );
+        }
+
+        // The brightness the characters are picked from this frame, if the camera has temporal
+        // stability.
+        let stable_view = stability.map(|(_, stable_textures)| {
+            stable_textures.current().create_view(&TextureViewDescriptor {
+                label: Some("ascii_stable_texture"),
+                ..Default::default()
+            })
+        });
+        let stable_view = stable_view.as_ref().unwrap_or(&stability_pipeline_resource.empty_stable_texture);
+
+        if let Some((classify_pipeline_resource, classify_pipeline, cell_texture)) = classify {
+            classify_pass(
+                &low_res_view,
+                &average_view,
+                cell_texture,
+                stable_view,
+                settings_binding.clone(),
+                render_context,
+                classify_pipeline,
+                classify_pipeline_resource,
+                ascii_pipeline_resource,
+            );
+        }
+
+        // If the scene grid is being read back this frame, copy out the cells, or the low res
+        // texture the characters are picked from when there are no cells.
+        if let Some(readback) = world.get_resource::<SceneGridReadback>() {
+            let source = classify.map(|(_, _, cell_texture)| cell_texture).unwrap_or(low_res_texture);
+            readback.copy_from(entity, source, render_context);
+        }
+
+        // Billboards that can be hidden by the scene read the depth under them.
+        if let (Some(readback), Some(depth)) = (world.get_resource::<BillboardDepthReadback>(), depth) {
+            readback.copy_from(entity, &depth.texture, render_context);
+        }
+
+        // The styles of the cells, if anything the camera draws has an `AsciiStyleOverride3d`.
+        let style_views = ascii_pipeline_resource.style_textures.get(&entity).map(|textures| {
+            (
+                textures.ids.create_view(&TextureViewDescriptor::default()),
+                textures.table.create_view(&TextureViewDescriptor::default()),
+            )
+        });
+
+        let cell_view = classify.map(|(_, _, cell_texture)| {
+            cell_texture.create_view(&TextureViewDescriptor {
+                label: Some("ascii_cell_texture"),
+                ..Default::default()
+            })
+        });
+
         // The bind_group gets created each frame.
         //
         // Normally, you would create a bind_group in the Queue set,
@@ -205,15 +539,32 @@ impl ViewNode for AsciiShaderNode {
             // It's important for this to match the BindGroupLayout defined in the PostProcessPipeline
             &BindGroupEntries::sequential((
                 // Make sure to use the source view
-                low_res_texture,
+                &low_res_view,
                 // use the font texture
                 &ascii_pipeline_resource.font_texture,
                 //The overlay texture
-                &overlay_texture,
+                overlay_texture.as_ref().unwrap_or(&ascii_pipeline_resource.empty_overlay_texture),
                 // Use the sampler created for the pipeline
                 &ascii_pipeline_resource.sampler,
                 // Set the settings binding
                 settings_binding.clone(),
+                // The adapted average brightness for auto exposure
+                &average_view,
+                // The full resolution scene for the parts outside of the viewport
+                post_process.source,
+                // The colors of the palette
+                &ascii_pipeline_resource.palette_texture,
+                // The exact colors of the overlay, if the camera uses true color
+                rgb_overlay_texture.as_ref().unwrap_or(&ascii_pipeline_resource.empty_rgb_overlay),
+                // The cells picked by the compute pass, if the characters are picked there
+                cell_view.as_ref().unwrap_or(&ascii_pipeline_resource.empty_cell_texture),
+                // The brightness of the cells with temporal stability
+                stable_view,
+                // The sprites of the tile atlas
+                &ascii_pipeline_resource.tile_texture,
+                // The style of every cell and the styles they point to
+                style_views.as_ref().map(|(ids, _)| ids).unwrap_or(&ascii_pipeline_resource.empty_style_ids),
+                style_views.as_ref().map(|(_, table)| table).unwrap_or(&ascii_pipeline_resource.empty_style_table),
             )),
         );
 
@@ -237,22 +588,52 @@ impl ViewNode for AsciiShaderNode {
         render_pass.set_render_pipeline(ascii_pipeline);
         render_pass.set_bind_group(0, &bind_group, &[]);
         render_pass.draw(0..3, 0..1);
+        drop(render_pass);
+
+        // The destination is the main texture now, keep a copy of it for the frames that are
+        // skipped.
+        if let Some(output_texture) = output_texture.filter(|_| can_copy_back) {
+            render_context.command_encoder().copy_texture_to_texture(
+                viewport_image_copy(view_target.main_texture(), view.viewport),
+                output_texture.as_image_copy(),
+                output_texture.size(),
+            );
+        }
+
+        if let Some(screenshots) = world.get_resource::<ScreenshotReadback>() {
+            screenshots.copy_from(entity, view_target.main_texture(), view.viewport, render_context);
+        }
 
         Ok(())
     }
 }
 
+// The part of the texture that is inside of the viewport of the camera.
+fn viewport_image_copy(texture: &Texture, viewport: UVec4) -> ImageCopyTexture<'_> {
+    ImageCopyTexture {
+        texture,
+        mip_level: 0,
+        origin: Origin3d {
+            x: viewport.x,
+            y: viewport.y,
+            z: 0,
+        },
+        aspect: TextureAspect::All,
+    }
+}
+
 fn pixel_pass(
     low_res_texture: &TextureView,
     render_context: &mut RenderContext,
     pixel_pipeline: &RenderPipeline,
     pixel_pipeline_resource: &PixelShaderPipeline,
     post_process: &PostProcessWrite,
+    viewport: BindingResource,
 ) {
     let pixel_bind_group = render_context.render_device().create_bind_group(
         "pixel_shader_bind_group",
         &pixel_pipeline_resource.layout,
-        &BindGroupEntries::sequential((post_process.source, &pixel_pipeline_resource.sampler)),
+        &BindGroupEntries::sequential((post_process.source, &pixel_pipeline_resource.sampler, viewport)),
     );
 
     let mut pixel_render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
@@ -278,64 +659,148 @@ fn pixel_pass(
 //             Extract Step
 //=============================================================================
 
+#[allow(clippy::type_complexity)]
 pub(crate) fn extract_camera(
     mut commands: Commands,
     cameras: Extract<
         Query<(
             Entity,
-            &Camera,
-            &AsciiCamera,
+            Ref<Camera>,
+            Ref<AsciiCamera>,
             Option<&AsciiUi>,
             Option<&RenderLayers>,
+            Option<Ref<GlobalTransform>>,
+            Option<Ref<Projection>>,
         )>,
     >,
-    mut is_initialized: Local<bool>,
-    mut last_surface: Local<AsciiSurface>,
+    mut initialized: Local<HashSet<Entity>>,
+    mut live_surfaces: Local<HashMap<Entity, AsciiSurface>>,
+    time: Extract<Res<Time>>,
+    ui_time: Extract<Option<Res<AsciiUiTime>>>,
 ) {
-    for (entity, camera, pixel_camera, ascii_ui, render_layers) in &cameras {
+    // Marquees on the overlay follow the clock of the ui.
+    let ui_elapsed = ui_time.as_ref().map(|ui_time| ui_time.elapsed_seconds()).unwrap_or(time.elapsed_seconds());
+
+    // Cameras that lost their ui, or were despawned, get a new overlay if they get a ui again.
+    initialized.retain(|entity| {
+        cameras
+            .get(*entity)
+            .map(|(_, _, _, ascii_ui, ..)| ascii_ui.is_some())
+            .unwrap_or(false)
+    });
+    live_surfaces.retain(|entity, _| initialized.contains(entity));
+
+    for (entity, camera, pixel_camera, ascii_ui, render_layers, transform, projection) in &cameras {
         if camera.is_active && pixel_camera.should_render {
             let mut entity = commands.get_or_spawn(entity);
             entity.insert(pixel_camera.clone());
 
+            if pixel_camera.on_demand {
+                let needs_redraw = pixel_camera.is_added()
+                    || camera.is_changed()
+                    || pixel_camera.is_changed()
+                    || pixel_camera.is_redraw_requested()
+                    || transform.map(|transform| transform.is_changed()).unwrap_or(false)
+                    || projection.map(|projection| projection.is_changed()).unwrap_or(false)
+                    || ascii_ui.map(|ui| ui.is_live_dirty()).unwrap_or(false);
+                entity.insert(AsciiNeedsRedraw(needs_redraw));
+            }
+
             if let Some(render_layer) = render_layers {
-                entity.insert(render_layer.clone());
+                entity.insert(*render_layer);
             }
 
             if let Some(ascii_ui) = ascii_ui {
-                if ascii_ui.is_dirty() || !*is_initialized {
-                    *last_surface = AsciiSurface::new(
-                        pixel_camera.target_res().x as u32,
-                        pixel_camera.target_res().y as u32,
-                    );
-                    entity.insert(OverlayBuffer(last_surface.clone()));
+                entity.insert(OverlayCamera);
+                if ascii_ui.true_color {
+                    entity.insert(OverlayTrueColor);
+                }
+
+                // Every camera needs an overlay on its first frame, after that only when the ui
+                // changed.
+                let (width, height) = (pixel_camera.ui_res().x as u32, pixel_camera.ui_res().y as u32);
+                let is_new = initialized.insert(entity.id());
+                if ascii_ui.is_dirty() || is_new {
+                    let surface = AsciiSurface::new(width, height);
+                    entity.insert(OverlayBuffer(surface.with_time(ui_elapsed)));
                 }
 
-                *is_initialized = true;
+                // The live layer is kept, and only what it drew last time is cleared.
+                if ascii_ui.is_live_dirty() || is_new {
+                    let live = live_surfaces.entry(entity.id()).or_default();
+                    if live.width() != width || live.height() != height {
+                        *live = AsciiSurface::new(width, height);
+                    } else {
+                        live.reset();
+                    }
+                    entity.insert(LiveOverlayBuffer(live.clone().with_time(ui_elapsed)));
+                }
             }
         }
     }
 }
 
+// Whether an on demand camera has to run the effect this frame.
+#[derive(Component)]
+pub struct AsciiNeedsRedraw(pub bool);
+
 //=============================================================================
 //             Prepare Step
 //=============================================================================
 
 // Thiw will calculate the target resolution for the effect. If this resolution changes,
 // it will remake the texture.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub fn prepare_shader_textures(
     mut pixel_shader_pipeline: ResMut<PixelShaderPipeline>,
     mut ascii_shader_pipeline: ResMut<AsciiShaderPipeline>,
-    acsii_cameras: Query<(Entity, &AsciiCamera, Option<&OverlayBuffer>)>,
+    mut exposure_shader_pipeline: ResMut<ExposureShaderPipeline>,
+    mut stability_shader_pipeline: ResMut<StabilityShaderPipeline>,
+    mut acsii_cameras: Query<(
+        Entity,
+        &AsciiCamera,
+        &ExtractedView,
+        Option<&OverlayBuffer>,
+        Option<&LiveOverlayBuffer>,
+        Option<&ViewTarget>,
+        Option<&mut AsciiNeedsRedraw>,
+        Has<OverlayCamera>,
+        Has<OverlayTrueColor>,
+    )>,
     render_device: ResMut<RenderDevice>,
     render_queue: ResMut<RenderQueue>,
+    mut classify_shader_pipeline: Option<ResMut<ClassifyShaderPipeline>>,
+    cell_hook: Option<Res<AsciiCellHook>>,
+    time: Res<Time>,
+    ui_stats: Option<Res<AsciiUiRenderStats>>,
 ) {
-    for (entity, ascii_camera, overlay_buffer) in acsii_cameras.iter() {
+    // Cameras that were despawned keep nothing on the gpu.
+    exposure_shader_pipeline.average_textures.retain(|entity, _| acsii_cameras.contains(*entity));
+    ascii_shader_pipeline.settings_uniforms.retain(|entity, _| acsii_cameras.contains(*entity));
+    ascii_shader_pipeline.output_textures.retain(|entity, _| acsii_cameras.contains(*entity));
+    ascii_shader_pipeline.overlay_surfaces.retain(|entity, _| acsii_cameras.contains(*entity));
+    ascii_shader_pipeline.overlay_textures.retain(|entity, _| acsii_cameras.contains(*entity));
+    ascii_shader_pipeline.overlay_regions.retain(|entity, _| acsii_cameras.contains(*entity));
+    ascii_shader_pipeline.rgb_overlay_textures.retain(|entity, _| acsii_cameras.contains(*entity));
+    ascii_shader_pipeline.rgb_overlay_regions.retain(|entity, _| acsii_cameras.contains(*entity));
+    pixel_shader_pipeline.low_res_textures.retain(|entity, _| acsii_cameras.contains(*entity));
+    stability_shader_pipeline.stable_textures.retain(|entity, _| acsii_cameras.contains(*entity));
+    if let Some(classify_shader_pipeline) = classify_shader_pipeline.as_mut() {
+        classify_shader_pipeline.cell_textures.retain(|entity, _| acsii_cameras.contains(*entity));
+    }
+
+    for (entity, ascii_camera, view, overlay_buffer, live_buffer, view_target, mut needs_redraw, has_overlay, true_color) in acsii_cameras.iter_mut() {
         let target_resolution = ascii_camera.target_res();
+        let is_outdated = |texture: Option<&Texture>| {
+            texture
+                .map(|texture| texture.width() != target_resolution.x as u32 || texture.height() != target_resolution.y as u32)
+                .unwrap_or(true)
+        };
+        // The overlay is the size of the ui, which is smaller than the grid with an `AsciiUiScale`.
+        let ui_resolution = ascii_camera.ui_res();
+
         //First check to see if the render texture for the pixel shader needs updating.
-        if *target_resolution != pixel_shader_pipeline.target_size
-            || !pixel_shader_pipeline.low_res_textures.contains_key(&entity)
-        {
-            pixel_shader_pipeline.target_size = *target_resolution;
+        if is_outdated(pixel_shader_pipeline.low_res_textures.get(&entity)) {
             let low_res_texture = render_device
                 .create_texture(&TextureDescriptor {
                     label: "low_res_texture".into(),
@@ -348,12 +813,10 @@ pub fn prepare_shader_textures(
                     sample_count: 1,
                     dimension: TextureDimension::D2,
                     format: TextureFormat::bevy_default(),
-                    usage: TextureUsages::TEXTURE_BINDING | TextureUsages::RENDER_ATTACHMENT,
+                    usage: TextureUsages::TEXTURE_BINDING
+                        | TextureUsages::RENDER_ATTACHMENT
+                        | TextureUsages::COPY_SRC,
                     view_formats: &[TextureFormat::bevy_default()],
-                })
-                .create_view(&TextureViewDescriptor {
-                    label: Some("low_res_texture"),
-                    ..TextureViewDescriptor::default()
                 });
 
             pixel_shader_pipeline
@@ -361,16 +824,24 @@ pub fn prepare_shader_textures(
                 .insert(entity, low_res_texture);
         }
 
-        //Then do the same thing with the overlay shaders
-        if *target_resolution != ascii_shader_pipeline.target_size
-            || !ascii_shader_pipeline.overlay_textures.contains_key(&entity)
-        {
-            ascii_shader_pipeline.target_size = *target_resolution;
+        //Then do the same thing with the overlay shaders. Cameras without a ui do not need one.
+        let is_overlay_outdated = ascii_shader_pipeline
+            .overlay_textures
+            .get(&entity)
+            .map(|texture| texture.width() != ui_resolution.x as u32 || texture.height() != ui_resolution.y as u32)
+            .unwrap_or(true);
+        if !has_overlay || is_overlay_outdated {
+            ascii_shader_pipeline.overlay_regions.remove(&entity);
+        }
+        if !has_overlay {
+            ascii_shader_pipeline.overlay_textures.remove(&entity);
+            ascii_shader_pipeline.overlay_surfaces.remove(&entity);
+        } else if is_overlay_outdated {
             let overlay_texture = render_device.create_texture(&TextureDescriptor {
                 label: "overlay_texture".into(),
                 size: Extent3d {
-                    width: target_resolution.x as u32,
-                    height: target_resolution.y as u32,
+                    width: ui_resolution.x as u32,
+                    height: ui_resolution.y as u32,
                     depth_or_array_layers: 1,
                 },
                 mip_level_count: 1,
@@ -386,24 +857,287 @@ pub fn prepare_shader_textures(
                 .insert(entity, overlay_texture);
         }
 
-        //Here we need to update the overlay textures:
-        if let Some(overlay_buffer) = overlay_buffer {
-            if let Some(overlay_texture) = ascii_shader_pipeline.overlay_textures.get(&entity) {
-                render_queue.write_texture(
-                    overlay_texture.as_image_copy(),
-                    &overlay_buffer.0.as_byte_vec(),
-                    ImageDataLayout {
-                        offset: 0,
-                        bytes_per_row: Some((target_resolution.x * 4.0) as u32),
-                        rows_per_image: Some(target_resolution.y as u32),
-                    },
-                    Extent3d {
+        // The cells the compute pass picks, when the characters are picked there.
+        if let Some(classify_shader_pipeline) = classify_shader_pipeline.as_mut() {
+            if is_outdated(classify_shader_pipeline.cell_textures.get(&entity)) {
+                let cell_texture = render_device.create_texture(&TextureDescriptor {
+                    label: "ascii_cell_texture".into(),
+                    size: Extent3d {
                         width: target_resolution.x as u32,
                         height: target_resolution.y as u32,
                         depth_or_array_layers: 1,
                     },
-                )
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: TextureDimension::D2,
+                    format: ClassifyShaderPipeline::FORMAT,
+                    usage: TextureUsages::STORAGE_BINDING | TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_SRC,
+                    view_formats: &[],
+                });
+
+                classify_shader_pipeline
+                    .cell_textures
+                    .insert(entity, cell_texture);
+            }
+        }
+
+        // The rgb overlay has two texels for every cell, the text and the background color.
+        let is_rgb_outdated = ascii_shader_pipeline
+            .rgb_overlay_textures
+            .get(&entity)
+            .map(|texture| texture.width() != ui_resolution.x as u32 * 2 || texture.height() != ui_resolution.y as u32)
+            .unwrap_or(true);
+        if !true_color || !has_overlay || is_rgb_outdated {
+            ascii_shader_pipeline.rgb_overlay_regions.remove(&entity);
+        }
+        if !true_color || !has_overlay {
+            ascii_shader_pipeline.rgb_overlay_textures.remove(&entity);
+        } else if is_rgb_outdated {
+            let rgb_overlay_texture = render_device.create_texture(&TextureDescriptor {
+                label: "rgb_overlay_texture".into(),
+                size: Extent3d {
+                    width: ui_resolution.x as u32 * 2,
+                    height: ui_resolution.y as u32,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: TextureFormat::Rgba8Uint,
+                usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+                view_formats: &[],
+            });
+
+            ascii_shader_pipeline
+                .rgb_overlay_textures
+                .insert(entity, rgb_overlay_texture);
+        }
+
+        // Only upload the settings when they changed since the last frame.
+        let mut settings = ascii_camera.settings(view.viewport);
+        // Without a hook nothing reads the time, and the uniform does not have to be uploaded again.
+        if cell_hook.is_some() {
+            settings.time = time.elapsed_seconds_wrapped();
+        }
+        match ascii_shader_pipeline.settings_uniforms.get_mut(&entity) {
+            Some(uniform) => {
+                if uniform.is_outdated(&settings) {
+                    uniform.set(settings, &render_device, &render_queue);
+                }
+            }
+            None => {
+                let uniform = AsciiSettingsUniform::new(settings, &render_device, &render_queue);
+                ascii_shader_pipeline.settings_uniforms.insert(entity, uniform);
+            }
+        }
+
+        // On demand cameras keep a copy of the viewport they drew last. A new copy is empty, so
+        // the effect has to run to fill it.
+        match view_target.filter(|_| ascii_camera.on_demand) {
+            Some(view_target) => {
+                let size = view.viewport.zw();
+                let format = view_target.main_texture_format();
+                let is_output_outdated = ascii_shader_pipeline
+                    .output_textures
+                    .get(&entity)
+                    .map(|texture| texture.width() != size.x || texture.height() != size.y || texture.format() != format)
+                    .unwrap_or(true);
+                if is_output_outdated && size.x > 0 && size.y > 0 {
+                    let output_texture = render_device.create_texture(&TextureDescriptor {
+                        label: "ascii_output_texture".into(),
+                        size: Extent3d {
+                            width: size.x,
+                            height: size.y,
+                            depth_or_array_layers: 1,
+                        },
+                        mip_level_count: 1,
+                        sample_count: 1,
+                        dimension: TextureDimension::D2,
+                        format,
+                        usage: TextureUsages::COPY_SRC | TextureUsages::COPY_DST,
+                        view_formats: &[],
+                    });
+                    ascii_shader_pipeline.output_textures.insert(entity, output_texture);
+                    if let Some(needs_redraw) = needs_redraw.as_mut() {
+                        needs_redraw.0 = true;
+                    }
+                }
+            }
+            None => {
+                ascii_shader_pipeline.output_textures.remove(&entity);
+            }
+        }
+
+        if !exposure_shader_pipeline.average_textures.contains_key(&entity) {
+            exposure_shader_pipeline.create_average_texture(entity, &render_device, &render_queue);
+        }
+
+        // The stable brightness is kept at the size of the grid. The textures swap on every frame
+        // the effect runs, so last frame is always the last frame that was drawn.
+        if ascii_camera.temporal_stability <= 0.0 {
+            stability_shader_pipeline.stable_textures.remove(&entity);
+        } else if is_outdated(stability_shader_pipeline.stable_textures.get(&entity).map(|stable| stable.current())) {
+            let size = UVec2::new(target_resolution.x as u32, target_resolution.y as u32);
+            stability_shader_pipeline.create_stable_textures(entity, size, &render_device);
+        } else if needs_redraw.as_ref().map(|redraw| redraw.0).unwrap_or(true) {
+            if let Some(stable_textures) = stability_shader_pipeline.stable_textures.get_mut(&entity) {
+                stable_textures.swap();
+            }
+        }
+
+        //Here we need to update the overlay textures:
+        if has_overlay && (overlay_buffer.is_some() || live_buffer.is_some()) {
+            let surfaces = ascii_shader_pipeline.overlay_surfaces.entry(entity).or_default();
+            if let Some(overlay_buffer) = overlay_buffer {
+                surfaces.ui = overlay_buffer.0.clone();
+            }
+            // What the live layer drew last time is uploaded again, to take it away.
+            let mut live_region = None;
+            if let Some(live_buffer) = live_buffer {
+                surfaces.live = live_buffer.0.clone();
+                let changed = surfaces.live.changed_region();
+                live_region = union_regions(surfaces.live_region.take(), changed.clone());
+                surfaces.live_region = changed;
+            }
+            let (surface, live) = (surfaces.ui.clone(), surfaces.live.clone());
+            let is_redrawn = overlay_buffer.is_some();
+
+            if let Some(overlay_texture) = ascii_shader_pipeline.overlay_textures.get(&entity) {
+                // The surface is made the frame the resolution changes, so it can be a different size
+                // than the texture for a frame. Uploading it anyway would write past the texture.
+                let size = overlay_texture.size();
+                if surface.width() != size.width || surface.height() != size.height {
+                    continue;
+                }
+
+                let previous = ascii_shader_pipeline.overlay_regions.get(&entity);
+                let region = union_regions(overlay_upload_region(previous, &surface, is_redrawn), live_region.clone());
+                if let Some(region) = clamp_region(region, &surface) {
+                    let bytes = surface
+                        .composite_region(&live, &region)
+                        .iter()
+                        .flat_map(|character| character.into_u8())
+                        .collect::<Vec<_>>();
+                    if let Some(ui_stats) = &ui_stats {
+                        ui_stats.add_upload(bytes.len());
+                    }
+                    render_queue.write_texture(
+                        overlay_region_copy(overlay_texture, &region, 1),
+                        &bytes,
+                        ImageDataLayout {
+                            offset: 0,
+                            bytes_per_row: Some(region.width * 4),
+                            rows_per_image: Some(region.height),
+                        },
+                        overlay_region_size(&region, 1),
+                    );
+                }
+                ascii_shader_pipeline.overlay_regions.insert(entity, surface.changed_region().unwrap_or_default());
+            }
+
+            if let Some(rgb_overlay_texture) = ascii_shader_pipeline.rgb_overlay_textures.get(&entity) {
+                let previous = ascii_shader_pipeline.rgb_overlay_regions.get(&entity);
+                let region = union_regions(overlay_upload_region(previous, &surface, is_redrawn), live_region);
+                if let Some(region) = clamp_region(region, &surface) {
+                    let bytes = surface
+                        .composite_region(&live, &region)
+                        .iter()
+                        .flat_map(|character| character.into_rgb_u8())
+                        .collect::<Vec<_>>();
+                    if let Some(ui_stats) = &ui_stats {
+                        ui_stats.add_upload(bytes.len());
+                    }
+                    render_queue.write_texture(
+                        overlay_region_copy(rgb_overlay_texture, &region, 2),
+                        &bytes,
+                        ImageDataLayout {
+                            offset: 0,
+                            bytes_per_row: Some(region.width * 8),
+                            rows_per_image: Some(region.height),
+                        },
+                        overlay_region_size(&region, 2),
+                    );
+                }
+                ascii_shader_pipeline.rgb_overlay_regions.insert(entity, surface.changed_region().unwrap_or_default());
             }
         }
     }
 }
+
+// The cells of the ui that have to be uploaded, or `None` when the texture is up to date. A
+// surface starts out unset, so the cells that were drawn last time have to be uploaded again to
+// clear them, along with the cells drawn now. A texture nothing was uploaded to gets all of it.
+fn overlay_upload_region(previous: Option<&AsciiBounds>, surface: &AsciiSurface, is_redrawn: bool) -> Option<AsciiBounds> {
+    let Some(previous) = previous else {
+        return Some(AsciiBounds::from_dims(surface.width(), surface.height()));
+    };
+    if !is_redrawn {
+        return None;
+    }
+    Some(previous.union(&surface.changed_region().unwrap_or_default()))
+}
+
+fn union_regions(a: Option<AsciiBounds>, b: Option<AsciiBounds>) -> Option<AsciiBounds> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.union(&b)),
+        (a, b) => a.or(b),
+    }
+}
+
+// The part of the region that is on the surface, or `None` when nothing is left of it.
+fn clamp_region(region: Option<AsciiBounds>, surface: &AsciiSurface) -> Option<AsciiBounds> {
+    let region = region?.intersection(&AsciiBounds::from_dims(surface.width(), surface.height()));
+    (!region.is_empty()).then_some(region)
+}
+
+// Where a region of the overlay goes in a texture with `texels` texels for every cell.
+fn overlay_region_copy<'a>(texture: &'a Texture, region: &AsciiBounds, texels: u32) -> ImageCopyTexture<'a> {
+    ImageCopyTexture {
+        texture,
+        mip_level: 0,
+        origin: Origin3d {
+            x: region.x as u32 * texels,
+            y: region.y as u32,
+            z: 0,
+        },
+        aspect: TextureAspect::All,
+    }
+}
+
+fn overlay_region_size(region: &AsciiBounds, texels: u32) -> Extent3d {
+    Extent3d {
+        width: region.width * texels,
+        height: region.height,
+        depth_or_array_layers: 1,
+    }
+}
+
+// The import of the cell hook that does nothing, every shader that calls `cell_hook` has it.
+const CELL_HOOK_IMPORT: &str = "#import bevy_ascii::cell_hook::cell_hook";
+
+// The shader source with the cell hook at `path` imported in place of the one that does nothing.
+// A shader that lost the import would quietly ignore the hook, so that is a bug in the crate.
+fn with_cell_hook(source: &str, path: &str) -> String {
+    assert!(source.contains(CELL_HOOK_IMPORT), "the shader does not import `bevy_ascii::cell_hook::cell_hook`");
+    source.replace(CELL_HOOK_IMPORT, &format!("#import \"{}\"::cell_hook", path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_cell_hook_is_imported_by_every_shader_that_calls_it() {
+        for source in [include_str!("ascii.wgsl"), include_str!("ascii_classify.wgsl")] {
+            let hooked = with_cell_hook(source, "shaders/rainbow_cells.wgsl");
+            assert!(hooked.contains("#import \"shaders/rainbow_cells.wgsl\"::cell_hook"));
+            assert!(!hooked.contains(CELL_HOOK_IMPORT));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn shaders_without_the_cell_hook_import_are_refused() {
+        with_cell_hook("fn main() {}", "shaders/rainbow_cells.wgsl");
+    }
+}
@@ -1,17 +1,15 @@
 use bevy::{
-    asset::{load_internal_asset, AssetServer},
     core_pipeline::fullscreen_vertex_shader::fullscreen_shader_vertex_state,
     ecs::world::FromWorld,
     prelude::*,
     render::{
         render_resource::{
-            BindGroupLayout, BindGroupLayoutDescriptor, BindGroupLayoutEntry, BindingType,
+            BindGroupLayout, BindGroupLayoutEntry, BindingType, BufferBindingType,
             CachedRenderPipelineId, ColorTargetState, ColorWrites, FragmentState, MultisampleState,
             PipelineCache, PrimitiveState, RenderPipelineDescriptor, Sampler, SamplerBindingType,
-            SamplerDescriptor, ShaderStages, TextureFormat, TextureSampleType, TextureView,
-            TextureViewDimension,
+            SamplerDescriptor, ShaderStages, ShaderType, Texture, TextureFormat, TextureSampleType, TextureViewDimension,
         },
-        renderer::{RenderDevice, RenderQueue},
+        renderer::RenderDevice,
         texture::BevyDefault,
     },
     utils::hashbrown::HashMap,
@@ -23,8 +21,7 @@ use bevy::{
 
 #[derive(Resource)]
 pub(crate) struct PixelShaderPipeline {
-    pub low_res_textures: HashMap<Entity, TextureView>,
-    pub target_size: Vec2,
+    pub low_res_textures: HashMap<Entity, Texture>,
     pub layout: BindGroupLayout,
     pub sampler: Sampler,
     pub pipeline_id: CachedRenderPipelineId,
@@ -57,6 +54,17 @@ impl FromWorld for PixelShaderPipeline {
                     ty: BindingType::Sampler(SamplerBindingType::Filtering),
                     count: None,
                 },
+                // The viewport of the camera
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: Some(Vec4::min_size()),
+                    },
+                    count: None,
+                },
             ],
         );
 
@@ -90,7 +98,6 @@ impl FromWorld for PixelShaderPipeline {
 
         PixelShaderPipeline {
             low_res_textures: HashMap::new(),
-            target_size: Vec2 { x: 1.0, y: 1.0 },
             layout,
             sampler,
             pipeline_id,
@@ -3,6 +3,8 @@
 
 @group(0) @binding(0) var screen_texture: texture_2d<f32>;
 @group(0) @binding(1) var texture_sampler: sampler;
+// The x, y, width and height of the camera viewport in pixels.
+@group(0) @binding(2) var<uniform> viewport: vec4<f32>;
 
 
 @fragment
@@ -26,7 +28,9 @@ fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
         vec3<f32>(0.733, 0.733, 0.733) //Light Grey
     );
 
-    let base_color = textureSample(screen_texture, texture_sampler, in.uv);
+    let screen_dims = vec2<f32>(textureDimensions(screen_texture));
+    let screen_uv = (viewport.xy + in.uv * viewport.zw) / screen_dims;
+    let base_color = textureSample(screen_texture, texture_sampler, screen_uv);
         
     var closest_color_index = 0;
     var value = 0.0;
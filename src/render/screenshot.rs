@@ -0,0 +1,230 @@
+use std::{
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use bevy::{
+    prelude::*,
+    render::{
+        render_resource::{
+            Buffer, BufferDescriptor, BufferUsages, Extent3d, ImageCopyBuffer, ImageDataLayout, Maintain, MapMode,
+            Texture, TextureFormat, TextureUsages,
+        },
+        renderer::{RenderContext, RenderDevice},
+        view::{ExtractedView, ViewTarget},
+        Extract,
+    },
+    tasks::IoTaskPool,
+};
+
+use crate::ascii::AsciiCamera;
+
+use super::viewport_image_copy;
+
+//=============================================================================
+//             Ascii Screenshot Event
+//=============================================================================
+
+// Saves the next frame of an ascii camera as a png, exactly as the ascii node drew it, at the
+// resolution of its viewport. Without a camera the first ascii camera is used. Cameras with hdr
+// can not be captured.
+#[derive(Event, Clone, Debug)]
+pub struct AsciiScreenshotEvent {
+    pub path: PathBuf,
+    pub camera: Option<Entity>,
+    // Keeps the alpha the ascii shader wrote, instead of saving every pixel opaque.
+    pub keep_alpha: bool,
+}
+
+impl AsciiScreenshotEvent {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        AsciiScreenshotEvent {
+            path: path.into(),
+            camera: None,
+            keep_alpha: false,
+        }
+    }
+
+    pub fn camera(mut self, camera: Entity) -> Self {
+        self.camera = Some(camera);
+        self
+    }
+
+    pub fn keep_alpha(mut self) -> Self {
+        self.keep_alpha = true;
+        self
+    }
+}
+
+//=============================================================================
+//             Render World
+//=============================================================================
+
+struct Screenshot {
+    request: AsciiScreenshotEvent,
+    camera: Entity,
+    buffer: Buffer,
+    size: UVec2,
+    is_bgra: bool,
+    // Set once the frame was copied and the buffer is being mapped, and holds whether mapping
+    // worked once it is done.
+    mapping: Option<Arc<Mutex<Option<bool>>>>,
+}
+
+impl Screenshot {
+    fn bytes_per_row(width: u32) -> u32 {
+        RenderDevice::align_copy_bytes_per_row(width as usize * 4) as u32
+    }
+}
+
+#[derive(Resource, Default)]
+pub(crate) struct ScreenshotReadback {
+    requests: Vec<AsciiScreenshotEvent>,
+    screenshots: Vec<Screenshot>,
+}
+
+impl ScreenshotReadback {
+    // Copies the viewport of the camera once the ascii node drew it. Screenshots that are already
+    // being read are not copied to again.
+    pub(crate) fn copy_from(&self, entity: Entity, texture: &Texture, viewport: UVec4, render_context: &mut RenderContext) {
+        if !texture.usage().contains(TextureUsages::COPY_SRC) {
+            return;
+        }
+        for screenshot in self.screenshots.iter().filter(|screenshot| screenshot.camera == entity && screenshot.mapping.is_none()) {
+            render_context.command_encoder().copy_texture_to_buffer(
+                viewport_image_copy(texture, viewport),
+                ImageCopyBuffer {
+                    buffer: &screenshot.buffer,
+                    layout: ImageDataLayout {
+                        offset: 0,
+                        bytes_per_row: Some(Screenshot::bytes_per_row(screenshot.size.x)),
+                        rows_per_image: None,
+                    },
+                },
+                Extent3d {
+                    width: screenshot.size.x,
+                    height: screenshot.size.y,
+                    depth_or_array_layers: 1,
+                },
+            );
+        }
+    }
+}
+
+pub(crate) fn extract_screenshot_requests(
+    mut events: Extract<EventReader<AsciiScreenshotEvent>>,
+    mut readback: ResMut<ScreenshotReadback>,
+) {
+    readback.requests.extend(events.read().cloned());
+}
+
+// Requests for cameras that are not rendered this frame wait until they are.
+pub(crate) fn prepare_screenshots(
+    mut readback: ResMut<ScreenshotReadback>,
+    cameras: Query<(Entity, &ExtractedView, &ViewTarget), With<AsciiCamera>>,
+    render_device: Res<RenderDevice>,
+) {
+    let readback = readback.as_mut();
+    readback.requests.retain(|request| {
+        let camera = match request.camera {
+            Some(camera) => cameras.get(camera).ok(),
+            None => cameras.iter().next(),
+        };
+        let Some((camera, view, view_target)) = camera else {
+            return true;
+        };
+
+        let is_bgra = match view_target.main_texture_format() {
+            TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => false,
+            TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb => true,
+            format => {
+                warn!("Can not save a screenshot of {:?}, the camera renders to {:?}", request.path, format);
+                return false;
+            }
+        };
+
+        let size = view.viewport.zw();
+        if size.x == 0 || size.y == 0 {
+            return true;
+        }
+        let buffer = render_device.create_buffer(&BufferDescriptor {
+            label: Some("ascii_screenshot_buffer"),
+            size: (Screenshot::bytes_per_row(size.x) * size.y) as u64,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        readback.screenshots.push(Screenshot {
+            request: request.clone(),
+            camera,
+            buffer,
+            size,
+            is_bgra,
+            mapping: None,
+        });
+        false
+    });
+}
+
+// Runs after the frame was submitted, so the copies made by the ascii node are ready to be
+// mapped. The png is written on the io task pool.
+pub(crate) fn read_screenshots(mut readback: ResMut<ScreenshotReadback>, render_device: Res<RenderDevice>) {
+    if readback.screenshots.is_empty() {
+        return;
+    }
+
+    for screenshot in readback.screenshots.iter_mut().filter(|screenshot| screenshot.mapping.is_none()) {
+        let mapping = Arc::new(Mutex::new(None));
+        let result = mapping.clone();
+        screenshot.buffer.slice(..).map_async(MapMode::Read, move |mapped| {
+            if let Ok(mut result) = result.lock() {
+                *result = Some(mapped.is_ok());
+            }
+        });
+        screenshot.mapping = Some(mapping);
+    }
+
+    // Browsers can not wait on the gpu, so there the buffers are read on a later frame instead.
+    #[cfg(not(target_arch = "wasm32"))]
+    render_device.poll(Maintain::Wait);
+    #[cfg(target_arch = "wasm32")]
+    render_device.poll(Maintain::Poll);
+
+    readback.screenshots.retain(|screenshot| {
+        let Some(is_mapped) = screenshot.mapping.as_ref().and_then(|mapping| *mapping.lock().ok()?) else {
+            return true;
+        };
+        if !is_mapped {
+            error!("Could not read the screenshot for {:?} back from the gpu", screenshot.request.path);
+            return false;
+        }
+
+        let pixels = {
+            let data = screenshot.buffer.slice(..).get_mapped_range();
+            let bytes_per_row = Screenshot::bytes_per_row(screenshot.size.x) as usize;
+            let mut pixels = Vec::with_capacity((screenshot.size.x * screenshot.size.y * 4) as usize);
+            for row in data.chunks(bytes_per_row).take(screenshot.size.y as usize) {
+                for pixel in row[..screenshot.size.x as usize * 4].chunks_exact(4) {
+                    let [r, g, b, a] = match screenshot.is_bgra {
+                        true => [pixel[2], pixel[1], pixel[0], pixel[3]],
+                        false => [pixel[0], pixel[1], pixel[2], pixel[3]],
+                    };
+                    pixels.extend([r, g, b, if screenshot.request.keep_alpha { a } else { 255 }]);
+                }
+            }
+            pixels
+        };
+        screenshot.buffer.unmap();
+
+        let path = screenshot.request.path.clone();
+        let size = screenshot.size;
+        IoTaskPool::get()
+            .spawn(async move {
+                match image::save_buffer(&path, &pixels, size.x, size.y, image::ColorType::Rgba8) {
+                    Ok(()) => info!("Saved a screenshot to {:?}", path),
+                    Err(error) => error!("Could not save the screenshot to {:?}: {}", path, error),
+                }
+            })
+            .detach();
+        false
+    });
+}
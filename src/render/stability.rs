@@ -0,0 +1,233 @@
+use bevy::{
+    core_pipeline::fullscreen_vertex_shader::fullscreen_shader_vertex_state,
+    ecs::world::FromWorld,
+    prelude::*,
+    render::{
+        render_resource::{
+            BindGroupEntries, BindGroupLayout, BindGroupLayoutEntry, BindingResource, BindingType,
+            CachedRenderPipelineId, ColorTargetState, ColorWrites, Extent3d, FragmentState,
+            MultisampleState, Operations, PipelineCache, PrimitiveState, RenderPassColorAttachment,
+            RenderPassDescriptor, RenderPipeline, RenderPipelineDescriptor, ShaderStages, ShaderType,
+            Texture, TextureDescriptor, TextureDimension, TextureFormat, TextureSampleType,
+            TextureUsages, TextureView, TextureViewDescriptor, TextureViewDimension,
+        },
+        renderer::{RenderContext, RenderDevice},
+    },
+    utils::hashbrown::HashMap,
+};
+
+use crate::ascii::AsciiShaderSettingsBuffer;
+
+//=============================================================================
+//             Stability Shader Pipeline
+//=============================================================================
+
+// Keeps the brightness every cell was last given a character at, for cameras with
+// `AsciiCamera::temporal_stability`. Each camera has two textures, one is read as last frame
+// while the other is written, and they swap every frame.
+#[derive(Resource)]
+pub(crate) struct StabilityShaderPipeline {
+    pub stable_textures: HashMap<Entity, StableTextures>,
+    // Bound in place of the stable brightness of cameras without temporal stability. The shaders
+    // do not read it then.
+    pub empty_stable_texture: TextureView,
+    pub layout: BindGroupLayout,
+    pub pipeline_id: CachedRenderPipelineId,
+}
+
+pub(crate) struct StableTextures {
+    pub textures: [Texture; 2],
+    // The texture that is written this frame.
+    pub current: usize,
+}
+
+impl StableTextures {
+    pub fn current(&self) -> &Texture {
+        &self.textures[self.current]
+    }
+
+    pub fn previous(&self) -> &Texture {
+        &self.textures[1 - self.current]
+    }
+
+    pub fn swap(&mut self) {
+        self.current = 1 - self.current;
+    }
+}
+
+impl StabilityShaderPipeline {
+    // The same as the average of the exposure pass, WebGL2 can only render to half floats with an
+    // extension.
+    #[cfg(not(feature = "webgl2"))]
+    pub const FORMAT: TextureFormat = TextureFormat::R16Float;
+    #[cfg(feature = "webgl2")]
+    pub const FORMAT: TextureFormat = TextureFormat::Rgba8Unorm;
+
+    pub fn create_stable_textures(&mut self, entity: Entity, size: UVec2, render_device: &RenderDevice) {
+        let create = || {
+            render_device.create_texture(&TextureDescriptor {
+                label: "ascii_stable_texture".into(),
+                size: Extent3d {
+                    width: size.x,
+                    height: size.y,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: Self::FORMAT,
+                usage: TextureUsages::TEXTURE_BINDING | TextureUsages::RENDER_ATTACHMENT,
+                view_formats: &[],
+            })
+        };
+
+        self.stable_textures.insert(
+            entity,
+            StableTextures {
+                textures: [create(), create()],
+                current: 0,
+            },
+        );
+    }
+}
+
+impl FromWorld for StabilityShaderPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+
+        let layout = render_device.create_bind_group_layout(
+            "stability_shader_bind_group_layout",
+            &[
+                // This is the low res texture
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                // What this pass wrote last frame
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                // The settings of the camera
+                BindGroupLayoutEntry {
+                    binding: 4,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: bevy::render::render_resource::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: Some(AsciiShaderSettingsBuffer::min_size()),
+                    },
+                    count: None,
+                },
+                // The adapted average brightness, used for auto exposure
+                BindGroupLayoutEntry {
+                    binding: 5,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+            ],
+        );
+
+        let empty_stable_texture = render_device.create_texture(&TextureDescriptor {
+            label: "ascii_empty_stable_texture".into(),
+            size: Extent3d {
+                width: 1,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: Self::FORMAT,
+            usage: TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let empty_stable_texture = empty_stable_texture.create_view(&TextureViewDescriptor::default());
+
+        let pipeline_id = world
+            .resource_mut::<PipelineCache>()
+            .queue_render_pipeline(RenderPipelineDescriptor {
+                label: Some("stability_shader_pipeline".into()),
+                layout: vec![layout.clone()],
+                vertex: fullscreen_shader_vertex_state(),
+                fragment: Some(FragmentState {
+                    shader: super::STABILITY_SHADER_HANDLE,
+                    shader_defs: vec![],
+                    entry_point: "fragment".into(),
+                    targets: vec![Some(ColorTargetState {
+                        format: Self::FORMAT,
+                        blend: None,
+                        write_mask: ColorWrites::ALL,
+                    })],
+                }),
+                primitive: PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: MultisampleState::default(),
+                push_constant_ranges: vec![],
+            });
+
+        StabilityShaderPipeline {
+            stable_textures: HashMap::new(),
+            empty_stable_texture,
+            layout,
+            pipeline_id,
+        }
+    }
+}
+
+pub(crate) fn stability_pass(
+    low_res_texture: &TextureView,
+    average_texture: &TextureView,
+    stable_textures: &StableTextures,
+    settings: BindingResource,
+    render_context: &mut RenderContext,
+    stability_pipeline: &RenderPipeline,
+    stability_pipeline_resource: &StabilityShaderPipeline,
+) {
+    let previous_view = stable_textures.previous().create_view(&TextureViewDescriptor::default());
+    let current_view = stable_textures.current().create_view(&TextureViewDescriptor::default());
+
+    let bind_group = render_context.render_device().create_bind_group(
+        "stability_shader_bind_group",
+        &stability_pipeline_resource.layout,
+        &BindGroupEntries::with_indices((
+            (0, low_res_texture),
+            (1, &previous_view),
+            (4, settings),
+            (5, average_texture),
+        )),
+    );
+
+    let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+        label: Some("stability_shader_render_pass"),
+        color_attachments: &[Some(RenderPassColorAttachment {
+            view: &current_view,
+            resolve_target: None,
+            ops: Operations::default(),
+        })],
+        depth_stencil_attachment: None,
+        timestamp_writes: None,
+        occlusion_query_set: None,
+    });
+
+    render_pass.set_render_pipeline(stability_pipeline);
+    render_pass.set_bind_group(0, &bind_group, &[]);
+    render_pass.draw(0..3, 0..1);
+}
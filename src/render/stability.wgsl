@@ -0,0 +1,20 @@
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+#import bevy_ascii::cell::{settings, adjust_value}
+
+@group(0) @binding(0) var low_res_texture: texture_2d<f32>;
+// What this pass wrote last frame.
+@group(0) @binding(1) var previous_texture: texture_2d<f32>;
+
+// Keeps the brightness every cell was last given a character at. A cell only takes the new
+// brightness once it moved further than the stability of the camera, so a cell that sits
+// between two characters does not flip between them every frame.
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let position = vec2<u32>(in.position.xy);
+    let value = adjust_value(textureLoad(low_res_texture, position, 0).w);
+    var stable = textureLoad(previous_texture, position, 0).x;
+    if (abs(value - stable) > settings.stability) {
+        stable = value;
+    }
+    return vec4<f32>(stable, stable, stable, 1.0);
+}
@@ -0,0 +1,553 @@
+use bevy::{
+    prelude::*,
+    render::{
+        mesh::{PrimitiveTopology, VertexAttributeValues},
+        primitives::Aabb,
+        render_resource::{
+            Extent3d, ImageCopyTexture, ImageDataLayout, Origin3d, Texture, TextureAspect, TextureDescriptor,
+            TextureDimension, TextureFormat, TextureUsages,
+        },
+        renderer::{RenderDevice, RenderQueue},
+        view::RenderLayers,
+        Extract,
+    },
+};
+
+use crate::{
+    ascii::AsciiCamera,
+    ui::{
+        character::{Character, Color},
+        BorderStyle,
+    },
+};
+
+use super::ascii::AsciiShaderPipeline;
+
+//=============================================================================
+//             Ascii Style Override
+//=============================================================================
+
+// Draws the cells an entity covers in its own color or with its own characters, so the things
+// that matter, like enemies and pickups, stand out from the rest of the scene. The cells are the
+// ones inside of the screen space bounds of its `Aabb`, or the one cell under its position when
+// it has none. Nearer entities are drawn over farther ones, but the scene does not hide them, so
+// an entity behind a wall is still styled where the wall is. The style is applied after the
+// `AsciiCellHook`.
+#[derive(Component, Clone, Default, PartialEq, Debug, Reflect)]
+#[reflect(Component)]
+pub struct AsciiStyleOverride3d {
+    // The color the characters are drawn in, in place of the color of the scene.
+    pub color: Option<Color>,
+    // The characters picked from by brightness, from darkest to brightest, in place of the ones of
+    // the scene. Only the first 8 are used.
+    pub characters: Vec<Character>,
+}
+
+impl AsciiStyleOverride3d {
+    pub const MAX_CHARACTERS: usize = 8;
+
+    pub fn new() -> Self {
+        AsciiStyleOverride3d::default()
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn characters(mut self, characters: impl IntoIterator<Item = Character>) -> Self {
+        self.characters = characters.into_iter().take(Self::MAX_CHARACTERS).collect();
+        self
+    }
+
+    // The style as it is stored in the style table of the shader. The first value has a bit for
+    // the color and a bit for the characters, and the number of characters in the second byte.
+    // The second value is the rgb color and the last two are the characters, a byte each.
+    fn packed(&self) -> [u32; 4] {
+        let characters = &self.characters[..self.characters.len().min(Self::MAX_CHARACTERS)];
+        let mut flags = (characters.len() as u32) << 8;
+        let mut rgb = 0;
+        if let Some(color) = self.color {
+            let [r, g, b] = color.rgb().map(|value| (value.clamp(0.0, 1.0) * 255.0).round() as u32);
+            rgb = r | g << 8 | b << 16;
+            flags |= 1;
+        }
+        if !characters.is_empty() {
+            flags |= 2;
+        }
+
+        let mut packed_characters = [0u32; 2];
+        for (index, character) in characters.iter().enumerate() {
+            packed_characters[index / 4] |= (*character as u32) << ((index % 4) * 8);
+        }
+        [flags, rgb, packed_characters[0], packed_characters[1]]
+    }
+}
+
+//=============================================================================
+//             Ascii Highlight
+//=============================================================================
+
+// Outlines the silhouette of an entity with the characters of a `BorderStyle`, in the cells just
+// outside of it. The silhouette is the mesh of the entity rasterized at the size of the grid, or
+// its `Aabb` when the mesh is not kept in the main world. Like `AsciiStyleOverride3d` the scene
+// does not hide it, and outlines are drawn over every other style. The color of the border wins
+// over `color` when it is set.
+#[derive(Component, Clone, Copy, PartialEq, Debug, Reflect)]
+#[reflect(Component)]
+pub struct AsciiHighlight {
+    pub color: Color,
+    pub border: BorderStyle,
+}
+
+impl Default for AsciiHighlight {
+    fn default() -> Self {
+        AsciiHighlight {
+            color: Color::Yellow,
+            border: BorderStyle::CENTERED,
+        }
+    }
+}
+
+impl AsciiHighlight {
+    pub fn new(color: Color) -> Self {
+        AsciiHighlight {
+            color,
+            ..Default::default()
+        }
+    }
+
+    pub fn border(mut self, border: BorderStyle) -> Self {
+        self.border = border;
+        self
+    }
+
+    // The top of the border goes above the silhouette, the left of it to the left and so on. A
+    // corner goes where the silhouette only touches the cell diagonally.
+    fn draw(&self, grid: &mut AsciiStyleGrid, mask: &[bool]) {
+        let (width, height) = (grid.width as i32, grid.height as i32);
+        let color = self.border.color.unwrap_or(self.color);
+        let is_masked = |x: i32, y: i32| x >= 0 && y >= 0 && x < width && y < height && mask[(y * width + x) as usize];
+
+        for y in 0..height {
+            for x in 0..width {
+                if is_masked(x, y) {
+                    continue;
+                }
+                let character = if is_masked(x, y + 1) {
+                    self.border.top
+                } else if is_masked(x, y - 1) {
+                    self.border.bottom
+                } else if is_masked(x + 1, y) {
+                    self.border.left
+                } else if is_masked(x - 1, y) {
+                    self.border.right
+                } else if is_masked(x + 1, y + 1) {
+                    self.border.top_left
+                } else if is_masked(x - 1, y + 1) {
+                    self.border.top_right
+                } else if is_masked(x + 1, y - 1) {
+                    self.border.bottom_left
+                } else if is_masked(x - 1, y - 1) {
+                    self.border.bottom_right
+                } else {
+                    continue;
+                };
+                if character == Character::Nil {
+                    continue;
+                }
+
+                let style = AsciiStyleOverride3d::new().color(color).characters([character]);
+                grid.set(x as u32, y as u32, style.packed());
+            }
+        }
+    }
+}
+
+//=============================================================================
+//             Style Grid
+//=============================================================================
+
+// The style of every cell of the grid of a camera. 0 is no style, every other id is the style at
+// that id - 1. A camera only has this while there are entities with an `AsciiStyleOverride3d` or
+// an `AsciiHighlight`.
+#[derive(Component, Clone, Default, PartialEq)]
+pub(crate) struct AsciiStyleGrid {
+    width: u32,
+    height: u32,
+    ids: Vec<u8>,
+    styles: Vec<[u32; 4]>,
+}
+
+impl AsciiStyleGrid {
+    // Ids are a byte, so a camera can show this many different styles at once.
+    const MAX_STYLES: usize = 255;
+
+    fn new(width: u32, height: u32) -> Self {
+        AsciiStyleGrid {
+            width,
+            height,
+            ids: vec![0; (width * height) as usize],
+            styles: Vec::new(),
+        }
+    }
+
+    // Styles that are the same share an id. Once all ids are taken the rest are not drawn.
+    fn style_id(&mut self, style: [u32; 4]) -> Option<u8> {
+        let index = match self.styles.iter().position(|existing| *existing == style) {
+            Some(index) => index,
+            None if self.styles.len() < Self::MAX_STYLES => {
+                self.styles.push(style);
+                self.styles.len() - 1
+            }
+            None => return None,
+        };
+        Some(index as u8 + 1)
+    }
+
+    fn fill(&mut self, min: UVec2, max: UVec2, style: [u32; 4]) {
+        let Some(id) = self.style_id(style) else {
+            return;
+        };
+        for y in min.y..max.y.min(self.height) {
+            for x in min.x..max.x.min(self.width) {
+                self.ids[(y * self.width + x) as usize] = id;
+            }
+        }
+    }
+
+    fn set(&mut self, x: u32, y: u32, style: [u32; 4]) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+        if let Some(id) = self.style_id(style) {
+            self.ids[(y * self.width + x) as usize] = id;
+        }
+    }
+}
+
+// The cells the bounds of an entity cover, as a min and an exclusive max, or `None` when none of
+// it is in front of the camera.
+fn covered_cells(
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+    transform: &GlobalTransform,
+    aabb: Option<&Aabb>,
+    grid_size: Vec2,
+) -> Option<(UVec2, UVec2)> {
+    let corners: Vec<Vec3> = match aabb {
+        Some(aabb) => (0..8)
+            .map(|corner| {
+                let sign = Vec3::new(
+                    if corner & 1 == 0 { -1.0 } else { 1.0 },
+                    if corner & 2 == 0 { -1.0 } else { 1.0 },
+                    if corner & 4 == 0 { -1.0 } else { 1.0 },
+                );
+                transform.transform_point(Vec3::from(aabb.center) + Vec3::from(aabb.half_extents) * sign)
+            })
+            .collect(),
+        None => vec![transform.translation()],
+    };
+
+    // Corners behind the camera are left out, the bounds are made from the ones in front.
+    let mut min = Vec2::splat(f32::MAX);
+    let mut max = Vec2::splat(f32::MIN);
+    for ndc in corners.into_iter().filter_map(|corner| camera.world_to_ndc(camera_transform, corner)) {
+        if !(0.0..=1.0).contains(&ndc.z) {
+            continue;
+        }
+        let cell = Vec2::new((ndc.x + 1.0) / 2.0, (1.0 - ndc.y) / 2.0) * grid_size;
+        min = min.min(cell);
+        max = max.max(cell);
+    }
+    if min.x > max.x {
+        return None;
+    }
+
+    let min = min.floor().clamp(Vec2::ZERO, grid_size);
+    // A single point still covers the cell it is in.
+    let max = (max.floor() + Vec2::ONE).clamp(Vec2::ZERO, grid_size);
+    (min.x < max.x && min.y < max.y).then(|| (min.as_uvec2(), max.as_uvec2()))
+}
+
+// The cells the mesh of an entity covers, one for every cell of the grid. Only triangle lists are
+// rasterized, every other entity covers the cells of its `Aabb`. The cell of every vertex is
+// covered as well, so triangles smaller than a cell still show up.
+fn silhouette(
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+    transform: &GlobalTransform,
+    mesh: Option<&Mesh>,
+    aabb: Option<&Aabb>,
+    grid_size: Vec2,
+) -> Vec<bool> {
+    let (width, height) = (grid_size.x as usize, grid_size.y as usize);
+    let mut mask = vec![false; width * height];
+
+    let triangles = mesh
+        .filter(|mesh| mesh.primitive_topology() == PrimitiveTopology::TriangleList)
+        .and_then(|mesh| match mesh.attribute(Mesh::ATTRIBUTE_POSITION) {
+            Some(VertexAttributeValues::Float32x3(positions)) => Some((mesh, positions)),
+            _ => None,
+        });
+    let Some((mesh, positions)) = triangles else {
+        if let Some((min, max)) = covered_cells(camera, camera_transform, transform, aabb, grid_size) {
+            for y in min.y..max.y {
+                for x in min.x..max.x {
+                    mask[y as usize * width + x as usize] = true;
+                }
+            }
+        }
+        return mask;
+    };
+
+    // Vertices behind the camera leave out the triangles they are part of.
+    let cells: Vec<Option<Vec2>> = positions
+        .iter()
+        .map(|position| {
+            camera
+                .world_to_ndc(camera_transform, transform.transform_point(Vec3::from(*position)))
+                .filter(|ndc| (0.0..=1.0).contains(&ndc.z))
+                .map(|ndc| Vec2::new((ndc.x + 1.0) / 2.0, (1.0 - ndc.y) / 2.0) * grid_size)
+        })
+        .collect();
+    let indices: Vec<usize> = match mesh.indices() {
+        Some(indices) => indices.iter().collect(),
+        None => (0..positions.len()).collect(),
+    };
+
+    for triangle in indices.chunks_exact(3) {
+        let corners = [triangle[0], triangle[1], triangle[2]].map(|index| cells.get(index).copied().flatten());
+        let [Some(a), Some(b), Some(c)] = corners else {
+            continue;
+        };
+        fill_triangle(&mut mask, width, height, [a, b, c]);
+    }
+    mask
+}
+
+// Covers the cells whose middle is inside of the triangle, whichever way it winds.
+fn fill_triangle(mask: &mut [bool], width: usize, height: usize, [a, b, c]: [Vec2; 3]) {
+    let size = Vec2::new(width as f32, height as f32);
+    let min = a.min(b).min(c).floor().clamp(Vec2::ZERO, size);
+    let max = (a.max(b).max(c).floor() + Vec2::ONE).clamp(Vec2::ZERO, size);
+    let edge = |from: Vec2, to: Vec2, point: Vec2| (to - from).perp_dot(point - from);
+
+    for y in min.y as usize..max.y as usize {
+        for x in min.x as usize..max.x as usize {
+            let point = Vec2::new(x as f32 + 0.5, y as f32 + 0.5);
+            let edges = [edge(a, b, point), edge(b, c, point), edge(c, a, point)];
+            if edges.iter().all(|edge| *edge >= 0.0) || edges.iter().all(|edge| *edge <= 0.0) {
+                mask[y * width + x] = true;
+            }
+        }
+    }
+
+    for corner in [a, b, c] {
+        if corner.x >= 0.0 && corner.y >= 0.0 && corner.x < size.x && corner.y < size.y {
+            mask[corner.y as usize * width + corner.x as usize] = true;
+        }
+    }
+}
+
+// Draws the styled entities into the grid of every camera. The grid is only changed when a cell
+// or a style is different, so it is only uploaded again then.
+#[allow(clippy::type_complexity)]
+pub(crate) fn update_style_grids(
+    mut commands: Commands,
+    mut cameras: Query<(
+        Entity,
+        &Camera,
+        &GlobalTransform,
+        &AsciiCamera,
+        Option<&RenderLayers>,
+        Option<&mut AsciiStyleGrid>,
+    )>,
+    overrides: Query<(
+        &AsciiStyleOverride3d,
+        &GlobalTransform,
+        Option<&Aabb>,
+        Option<&ViewVisibility>,
+        Option<&RenderLayers>,
+    )>,
+    highlights: Query<(
+        &AsciiHighlight,
+        &GlobalTransform,
+        Option<&Handle<Mesh>>,
+        Option<&Aabb>,
+        Option<&ViewVisibility>,
+        Option<&RenderLayers>,
+    )>,
+    meshes: Res<Assets<Mesh>>,
+) {
+    for (entity, camera, camera_transform, ascii_camera, camera_layers, grid) in cameras.iter_mut() {
+        if overrides.is_empty() && highlights.is_empty() {
+            if grid.is_some() {
+                commands.entity(entity).remove::<AsciiStyleGrid>();
+            }
+            continue;
+        }
+
+        let grid_size = *ascii_camera.target_res();
+        let camera_layers = camera_layers.copied().unwrap_or_default();
+        let camera_position = camera_transform.translation();
+
+        let is_seen = |visibility: Option<&ViewVisibility>, layers: Option<&RenderLayers>| {
+            visibility.map(|visibility| visibility.get()).unwrap_or(true)
+                && camera_layers.intersects(&layers.copied().unwrap_or_default())
+        };
+
+        let mut visible: Vec<_> = overrides
+            .iter()
+            .filter(|(_, _, _, visibility, layers)| is_seen(*visibility, *layers))
+            .map(|(style, transform, aabb, ..)| {
+                (transform.translation().distance_squared(camera_position), style, transform, aabb)
+            })
+            .collect();
+        // The farthest entities are drawn first, so the nearer ones end up on top.
+        visible.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        let mut new_grid = AsciiStyleGrid::new(grid_size.x as u32, grid_size.y as u32);
+        for (_, style, transform, aabb) in visible {
+            if let Some((min, max)) = covered_cells(camera, camera_transform, transform, aabb, grid_size) {
+                new_grid.fill(min, max, style.packed());
+            }
+        }
+
+        // Outlines go over every other style.
+        for (highlight, transform, mesh, aabb, visibility, layers) in highlights.iter() {
+            if !is_seen(visibility, layers) {
+                continue;
+            }
+            let mesh = mesh.and_then(|mesh| meshes.get(mesh));
+            let mask = silhouette(camera, camera_transform, transform, mesh, aabb, grid_size);
+            highlight.draw(&mut new_grid, &mask);
+        }
+
+        match grid {
+            Some(mut grid) => {
+                grid.set_if_neq(new_grid);
+            }
+            None => {
+                commands.entity(entity).insert(new_grid);
+            }
+        }
+    }
+}
+
+//=============================================================================
+//             Render World
+//=============================================================================
+
+// The textures of a styled camera. The ids are one byte for every cell of the grid, the table has
+// a texel for every style.
+pub(crate) struct StyleTextures {
+    pub ids: Texture,
+    pub table: Texture,
+}
+
+// Marks the cameras that have a style grid, whether or not it changed this frame.
+#[derive(Component)]
+pub(crate) struct AsciiStyled;
+
+// Only a grid that changed is sent to the render world, the textures keep the one before.
+pub(crate) fn extract_style_grids(mut commands: Commands, cameras: Extract<Query<(Entity, Ref<AsciiStyleGrid>)>>) {
+    for (entity, grid) in cameras.iter() {
+        let mut entity = commands.get_or_spawn(entity);
+        entity.insert(AsciiStyled);
+        if grid.is_changed() {
+            entity.insert(grid.clone());
+        }
+    }
+}
+
+pub(crate) fn prepare_style_textures(
+    mut ascii_shader_pipeline: ResMut<AsciiShaderPipeline>,
+    styled: Query<Entity, With<AsciiStyled>>,
+    grids: Query<(Entity, &AsciiStyleGrid)>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+) {
+    ascii_shader_pipeline.style_textures.retain(|entity, _| styled.contains(*entity));
+
+    for (entity, grid) in grids.iter() {
+        if grid.width == 0 || grid.height == 0 {
+            ascii_shader_pipeline.style_textures.remove(&entity);
+            continue;
+        }
+
+        let is_outdated = ascii_shader_pipeline
+            .style_textures
+            .get(&entity)
+            .map(|textures| textures.ids.width() != grid.width || textures.ids.height() != grid.height)
+            .unwrap_or(true);
+        if is_outdated {
+            let ids = render_device.create_texture(&TextureDescriptor {
+                label: "ascii_style_id_texture".into(),
+                size: Extent3d {
+                    width: grid.width,
+                    height: grid.height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: TextureFormat::R8Uint,
+                usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+                view_formats: &[],
+            });
+            let table = render_device.create_texture(&TextureDescriptor {
+                label: "ascii_style_table_texture".into(),
+                size: Extent3d {
+                    width: AsciiStyleGrid::MAX_STYLES as u32,
+                    height: 1,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: TextureFormat::Rgba32Uint,
+                usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+                view_formats: &[],
+            });
+            ascii_shader_pipeline.style_textures.insert(entity, StyleTextures { ids, table });
+        }
+
+        let Some(textures) = ascii_shader_pipeline.style_textures.get(&entity) else {
+            continue;
+        };
+        render_queue.write_texture(
+            textures.ids.as_image_copy(),
+            &grid.ids,
+            ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(grid.width),
+                rows_per_image: Some(grid.height),
+            },
+            textures.ids.size(),
+        );
+
+        if !grid.styles.is_empty() {
+            let bytes: Vec<u8> = grid.styles.iter().flatten().flat_map(|value| value.to_le_bytes()).collect();
+            render_queue.write_texture(
+                ImageCopyTexture {
+                    texture: &textures.table,
+                    mip_level: 0,
+                    origin: Origin3d::ZERO,
+                    aspect: TextureAspect::All,
+                },
+                &bytes,
+                ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(grid.styles.len() as u32 * 16),
+                    rows_per_image: Some(1),
+                },
+                Extent3d {
+                    width: grid.styles.len() as u32,
+                    height: 1,
+                    depth_or_array_layers: 1,
+                },
+            );
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use std::io::{self, Stdout, Write};
+
+use bevy::{app::AppExit, prelude::*};
+use crossterm::{
+    cursor::{Hide, MoveTo, Show},
+    queue,
+    style::{Color as TerminalColor, Print, ResetColor, SetBackgroundColor, SetForegroundColor},
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+
+use crate::{
+    render::grid::{AsciiSceneGrid, AsciiSceneGridPlugin},
+    ui::character::{Character, Color},
+};
+
+//=============================================================================
+//             Terminal Plugin
+//=============================================================================
+
+// Mirrors the character grid of the camera with an `AsciiSceneGridSource` to the terminal the
+// app was started from. Only the cells that changed since the last frame are written.
+pub struct AsciiTerminalPlugin {
+    pub interval: u32,
+}
+
+impl Default for AsciiTerminalPlugin {
+    fn default() -> Self {
+        AsciiTerminalPlugin { interval: 2 }
+    }
+}
+
+impl Plugin for AsciiTerminalPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<AsciiSceneGridPlugin>() {
+            app.add_plugins(AsciiSceneGridPlugin {
+                interval: self.interval,
+            });
+        }
+
+        app.init_resource::<AsciiTerminal>()
+            .add_systems(Startup, enter_terminal)
+            .add_systems(Update, mirror_to_terminal.run_if(resource_changed::<AsciiSceneGrid>))
+            .add_systems(Last, leave_terminal.run_if(on_event::<AppExit>()));
+    }
+}
+
+//=============================================================================
+//             Terminal
+//=============================================================================
+
+#[derive(Resource, Default)]
+pub struct AsciiTerminal {
+    previous: Vec<(Character, Color, Color)>,
+    size: (u16, u16),
+    is_active: bool,
+}
+
+impl AsciiTerminal {
+    pub fn is_active(&self) -> bool {
+        self.is_active
+    }
+
+    // Redraws every cell on the next frame, for example after the terminal was cleared.
+    pub fn redraw(&mut self) {
+        self.previous.clear();
+    }
+}
+
+fn to_terminal_color(color: Color) -> TerminalColor {
+    let [r, g, b] = color.rgb().map(|value| (value * 255.0).round() as u8);
+    TerminalColor::Rgb { r, g, b }
+}
+
+fn enter_terminal(mut ascii_terminal: ResMut<AsciiTerminal>) {
+    let mut stdout = io::stdout();
+    let result = queue!(stdout, EnterAlternateScreen, Hide, Clear(ClearType::All)).and_then(|_| stdout.flush());
+    match result {
+        Ok(_) => ascii_terminal.is_active = true,
+        Err(error) => error!("Unable to set up the terminal: {}", error),
+    }
+}
+
+fn leave_terminal(mut ascii_terminal: ResMut<AsciiTerminal>) {
+    if !ascii_terminal.is_active {
+        return;
+    }
+
+    let mut stdout = io::stdout();
+    let _ = queue!(stdout, ResetColor, Show, LeaveAlternateScreen).and_then(|_| stdout.flush());
+    ascii_terminal.is_active = false;
+}
+
+fn mirror_to_terminal(mut ascii_terminal: ResMut<AsciiTerminal>, grid: Res<AsciiSceneGrid>) {
+    if !ascii_terminal.is_active || grid.is_empty() {
+        return;
+    }
+
+    let size = terminal::size().unwrap_or(ascii_terminal.size);
+    let cell_count = (grid.width() * grid.height()) as usize;
+    if size != ascii_terminal.size || ascii_terminal.previous.len() != cell_count {
+        ascii_terminal.size = size;
+        ascii_terminal.previous.clear();
+    }
+
+    let mut stdout = io::stdout();
+    if let Err(error) = draw_grid(&mut stdout, &mut ascii_terminal, &grid) {
+        error!("Unable to write to the terminal: {}", error);
+        ascii_terminal.is_active = false;
+    }
+}
+
+fn draw_grid(stdout: &mut Stdout, ascii_terminal: &mut AsciiTerminal, grid: &AsciiSceneGrid) -> io::Result<()> {
+    let redraw_all = ascii_terminal.previous.is_empty();
+    if redraw_all {
+        queue!(stdout, ResetColor, Clear(ClearType::All))?;
+    }
+
+    let (columns, rows) = ascii_terminal.size;
+    let width = grid.width().min(columns as u32);
+    let height = grid.height().min(rows as u32);
+
+    let mut cells = Vec::with_capacity((grid.width() * grid.height()) as usize);
+    let mut colors = None;
+    let mut cursor = None;
+
+    for y in 0..grid.height() {
+        for x in 0..grid.width() {
+            let cell = grid
+                .visible(x as i32, y as i32)
+                .unwrap_or((Character::Space, Color::White, Color::Black));
+            let index = cells.len();
+            cells.push(cell);
+
+            if x >= width || y >= height {
+                continue;
+            }
+            if !redraw_all && ascii_terminal.previous.get(index) == Some(&cell) {
+                continue;
+            }
+
+            if cursor != Some((x, y)) {
+                queue!(stdout, MoveTo(x as u16, y as u16))?;
+            }
+
+            let (character, text_color, bg_color) = cell;
+            if colors != Some((text_color, bg_color)) {
+                queue!(
+                    stdout,
+                    SetForegroundColor(to_terminal_color(text_color)),
+                    SetBackgroundColor(to_terminal_color(bg_color))
+                )?;
+                colors = Some((text_color, bg_color));
+            }
+
+            queue!(stdout, Print(char::from(character)))?;
+            cursor = Some((x + 1, y));
+        }
+    }
+
+    ascii_terminal.previous = cells;
+    queue!(stdout, ResetColor)?;
+    stdout.flush()
+}
@@ -0,0 +1,392 @@
+use bevy::{a11y::accesskit::Role, prelude::*, utils::HashMap};
+
+use super::{bounds::{AsciiBounds, AsciiNode}, component::AsciiComponent, AsciiUiSchedule, AsciiUiSet};
+
+//=============================================================================
+//             Plugin and Systems
+//=============================================================================
+
+// Keeps an `AsciiAccessibilityTree` of every ui component that describes itself through
+// `AsciiComponent::accessibility`. The characters on screen mean nothing to a screen reader, the
+// tree is a way to tell it what is there instead, for example by feeding it to AccessKit.
+pub struct AsciiAccessibilityPlugin;
+
+impl Plugin for AsciiAccessibilityPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<AsciiAccessibilityTree>()
+            .add_event::<AsciiAccessibilityFocusChangedEvent>()
+            .add_event::<AsciiAccessibilityValueChangedEvent>()
+            .register_type::<AsciiAccessibleRole>()
+            .add_systems(AsciiUiSchedule, resolve_accessibility_tree.after(AsciiUiSet::Extract))
+        ;
+    }
+}
+
+// Added for every component by the `AsciiComponentPlugin`, it only runs when the tree exists.
+// The parents and the focus are worked out afterwards in `resolve_accessibility_tree`, once every
+// type of component is in the tree.
+pub(crate) fn update_accessibility_tree<C: AsciiComponent>(
+    nodes: Query<(Entity, &C, &AsciiNode, Option<&InheritedVisibility>)>,
+    mut removed: RemovedComponents<C>,
+    mut tree: ResMut<AsciiAccessibilityTree>,
+    mut value_changed: EventWriter<AsciiAccessibilityValueChangedEvent>,
+) {
+    // Only touched when something changed, so `is_changed` on the tree can be trusted.
+    for entity in removed.read() {
+        if tree.nodes.contains_key(&entity) {
+            tree.remove(entity);
+        }
+    }
+
+    for (entity, component, node, visibility) in nodes.iter() {
+        let is_visible = visibility.map(|v| v.get()).unwrap_or(true);
+        let Some(accessible) = component.accessibility().filter(|_| is_visible) else {
+            if tree.nodes.contains_key(&entity) {
+                tree.remove(entity);
+            }
+            continue;
+        };
+
+        let old = tree.nodes.get(&entity);
+        let new = AsciiAccessibilityNode {
+            entity,
+            parent: old.and_then(|old| old.parent),
+            bounds: node.bounds.clone(),
+            accessible,
+        };
+
+        if let Some(old) = old {
+            if *old == new {
+                continue;
+            }
+            if old.accessible.value != new.accessible.value {
+                value_changed.send(AsciiAccessibilityValueChangedEvent {
+                    entity,
+                    value: new.accessible.value.clone(),
+                });
+            }
+        }
+
+        tree.nodes.insert(entity, new);
+    }
+}
+
+// Links every node to the closest ancestor that is also in the tree and finds the focused node.
+// Runs after the tree got every type of component, so the order of those systems does not
+// matter, and the focus is only sent when it is another node than in the last frame.
+fn resolve_accessibility_tree(
+    parents: Query<&Parent>,
+    mut tree: ResMut<AsciiAccessibilityTree>,
+    mut focus_changed: EventWriter<AsciiAccessibilityFocusChangedEvent>,
+) {
+    let entities = tree.nodes.keys().copied().collect::<Vec<_>>();
+    for entity in entities {
+        let mut parent = parents.get(entity).ok().map(|parent| parent.get());
+        while let Some(ancestor) = parent {
+            if tree.nodes.contains_key(&ancestor) {
+                break;
+            }
+            parent = parents.get(ancestor).ok().map(|parent| parent.get());
+        }
+        if tree.nodes[&entity].parent != parent {
+            tree.nodes.get_mut(&entity).unwrap().parent = parent;
+        }
+    }
+
+    // The node that had the focus keeps it while it says it is focused.
+    let is_focused = |entity: &Entity| tree.nodes.get(entity).is_some_and(|node| node.accessible.is_focused);
+    let focused = tree.focused.filter(is_focused).or_else(|| {
+        tree.nodes()
+            .into_iter()
+            .find(|node| node.accessible.is_focused)
+            .map(|node| node.entity)
+    });
+    if tree.focused != focused {
+        tree.focused = focused;
+        focus_changed.send(AsciiAccessibilityFocusChangedEvent { entity: focused });
+    }
+}
+
+//=============================================================================
+//             Accessible Description
+//=============================================================================
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Reflect)]
+pub enum AsciiAccessibleRole {
+    Button,
+    Slider,
+    SpinButton,
+    TabList,
+    Switch,
+    RadioGroup,
+    TextInput,
+    Log,
+    Window,
+    Group,
+    Image,
+    Text,
+    ListBox,
+    ComboBox,
+}
+
+impl AsciiAccessibleRole {
+    pub fn to_accesskit(&self) -> Role {
+        match self {
+            AsciiAccessibleRole::Button => Role::Button,
+            AsciiAccessibleRole::Slider => Role::Slider,
+            AsciiAccessibleRole::SpinButton => Role::SpinButton,
+            AsciiAccessibleRole::TabList => Role::TabList,
+            AsciiAccessibleRole::Switch => Role::Switch,
+            AsciiAccessibleRole::RadioGroup => Role::RadioGroup,
+            AsciiAccessibleRole::TextInput => Role::TextInput,
+            AsciiAccessibleRole::Log => Role::Log,
+            AsciiAccessibleRole::Window => Role::Window,
+            AsciiAccessibleRole::Group => Role::Group,
+            AsciiAccessibleRole::Image => Role::Image,
+            AsciiAccessibleRole::Text => Role::StaticText,
+            AsciiAccessibleRole::ListBox => Role::ListBox,
+            AsciiAccessibleRole::ComboBox => Role::ComboBox,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            AsciiAccessibleRole::Button => "button",
+            AsciiAccessibleRole::Slider => "slider",
+            AsciiAccessibleRole::SpinButton => "spin button",
+            AsciiAccessibleRole::TabList => "tab list",
+            AsciiAccessibleRole::Switch => "switch",
+            AsciiAccessibleRole::RadioGroup => "radio group",
+            AsciiAccessibleRole::TextInput => "text field",
+            AsciiAccessibleRole::Log => "log",
+            AsciiAccessibleRole::Window => "window",
+            AsciiAccessibleRole::Group => "group",
+            AsciiAccessibleRole::Image => "image",
+            AsciiAccessibleRole::Text => "text",
+            AsciiAccessibleRole::ListBox => "list",
+            AsciiAccessibleRole::ComboBox => "combo box",
+        }
+    }
+}
+
+// What a component is, as returned from `AsciiComponent::accessibility`.
+#[derive(Clone, PartialEq, Debug)]
+pub struct AsciiAccessible {
+    pub role: AsciiAccessibleRole,
+    pub label: String,
+    pub value: Option<String>,
+    pub is_focused: bool,
+}
+
+impl AsciiAccessible {
+    pub fn new(role: AsciiAccessibleRole, label: &str) -> Self {
+        AsciiAccessible {
+            role,
+            label: label.to_string(),
+            value: None,
+            is_focused: false,
+        }
+    }
+
+    pub fn value(mut self, value: impl ToString) -> Self {
+        self.value = Some(value.to_string());
+        self
+    }
+
+    pub fn focused(mut self, is_focused: bool) -> Self {
+        self.is_focused = is_focused;
+        self
+    }
+
+    // A short sentence a screen reader can read out, like "Volume, slider, 0.5".
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.label.is_empty() {
+            parts.push(self.label.clone());
+        }
+        parts.push(self.role.name().to_string());
+        if let Some(value) = &self.value {
+            parts.push(value.clone());
+        }
+        if self.is_focused {
+            parts.push("focused".to_string());
+        }
+        parts.join(", ")
+    }
+}
+
+//=============================================================================
+//             Accessibility Tree
+//=============================================================================
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct AsciiAccessibilityNode {
+    pub entity: Entity,
+    // The closest ancestor that is also in the tree.
+    pub parent: Option<Entity>,
+    pub bounds: AsciiBounds,
+    pub accessible: AsciiAccessible,
+}
+
+#[derive(Resource, Default)]
+pub struct AsciiAccessibilityTree {
+    nodes: HashMap<Entity, AsciiAccessibilityNode>,
+    focused: Option<Entity>,
+}
+
+impl AsciiAccessibilityTree {
+    pub fn get(&self, entity: Entity) -> Option<&AsciiAccessibilityNode> {
+        self.nodes.get(&entity)
+    }
+
+    pub fn focused(&self) -> Option<&AsciiAccessibilityNode> {
+        self.focused.and_then(|entity| self.nodes.get(&entity))
+    }
+
+    // Every node, in reading order: top to bottom, then left to right.
+    pub fn nodes(&self) -> Vec<&AsciiAccessibilityNode> {
+        let mut nodes = self.nodes.values().collect::<Vec<_>>();
+        nodes.sort_by_key(|node| (node.bounds.y, node.bounds.x, node.entity));
+        nodes
+    }
+
+    pub fn roots(&self) -> Vec<&AsciiAccessibilityNode> {
+        self.nodes().into_iter().filter(|node| node.parent.is_none()).collect()
+    }
+
+    pub fn children(&self, entity: Entity) -> Vec<&AsciiAccessibilityNode> {
+        self.nodes().into_iter().filter(|node| node.parent == Some(entity)).collect()
+    }
+
+    // The whole ui as text, one node per line and indented by depth.
+    pub fn to_text(&self) -> String {
+        let mut lines = Vec::new();
+        for root in self.roots() {
+            self.write_text(root, 0, &mut lines);
+        }
+        lines.join("\n")
+    }
+
+    fn write_text(&self, node: &AsciiAccessibilityNode, depth: usize, lines: &mut Vec<String>) {
+        lines.push(format!("{}{}", "  ".repeat(depth), node.accessible.describe()));
+        for child in self.children(node.entity) {
+            self.write_text(child, depth + 1, lines);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    fn remove(&mut self, entity: Entity) {
+        self.nodes.remove(&entity);
+    }
+}
+
+//=============================================================================
+//             Accessibility Events
+//=============================================================================
+
+#[derive(Event, Clone, Debug, PartialEq, Eq)]
+pub struct AsciiAccessibilityFocusChangedEvent {
+    pub entity: Option<Entity>,
+}
+
+#[derive(Event, Clone, Debug, PartialEq, Eq)]
+pub struct AsciiAccessibilityValueChangedEvent {
+    pub entity: Entity,
+    pub value: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+
+    #[derive(Component)]
+    struct Panel;
+
+    impl AsciiComponent for Panel {
+        type UpdateQuery<'w, 's> = ();
+
+        fn accessibility(&self) -> Option<AsciiAccessible> {
+            Some(AsciiAccessible::new(AsciiAccessibleRole::Group, "Panel"))
+        }
+    }
+
+    #[derive(Component)]
+    struct Field(bool);
+
+    impl AsciiComponent for Field {
+        type UpdateQuery<'w, 's> = ();
+
+        fn accessibility(&self) -> Option<AsciiAccessible> {
+            Some(AsciiAccessible::new(AsciiAccessibleRole::TextInput, "Field").focused(self.0))
+        }
+    }
+
+    fn tree_world() -> World {
+        let mut world = World::new();
+        world.init_resource::<AsciiAccessibilityTree>();
+        world.init_resource::<Events<AsciiAccessibilityFocusChangedEvent>>();
+        world.init_resource::<Events<AsciiAccessibilityValueChangedEvent>>();
+        world
+    }
+
+    // The fields are put in the tree before the panel they are in.
+    fn update(world: &mut World) -> Vec<Option<Entity>> {
+        world.run_system_once(update_accessibility_tree::<Field>);
+        world.run_system_once(update_accessibility_tree::<Panel>);
+        world.run_system_once(resolve_accessibility_tree);
+        world
+            .resource_mut::<Events<AsciiAccessibilityFocusChangedEvent>>()
+            .drain()
+            .map(|event| event.entity)
+            .collect()
+    }
+
+    #[test]
+    fn parents_of_another_type_are_found() {
+        let mut world = tree_world();
+        let panel = world.spawn((Panel, AsciiNode::default())).id();
+        let group = world.spawn(AsciiNode::default()).id();
+        let field = world.spawn((Field(false), AsciiNode::default())).id();
+        world.entity_mut(panel).add_child(group);
+        world.entity_mut(group).add_child(field);
+        update(&mut world);
+
+        let tree = world.resource::<AsciiAccessibilityTree>();
+        assert_eq!(tree.get(field).unwrap().parent, Some(panel));
+        assert_eq!(tree.get(panel).unwrap().parent, None);
+        assert_eq!(tree.to_text(), "Panel, group\n  Field, text field");
+
+        // The field is moved out of the panel.
+        world.entity_mut(group).remove_children(&[field]);
+        update(&mut world);
+        assert_eq!(world.resource::<AsciiAccessibilityTree>().get(field).unwrap().parent, None);
+    }
+
+    #[test]
+    fn focus_changes_are_sent_once() {
+        let mut world = tree_world();
+        let first = world.spawn((Field(true), AsciiNode::default())).id();
+        let second = world.spawn((Field(false), AsciiNode::default())).id();
+        assert_eq!(update(&mut world), vec![Some(first)]);
+        assert!(update(&mut world).is_empty());
+
+        // The focus moves in a single frame, without a frame of nothing focused in between.
+        world.get_mut::<Field>(first).unwrap().0 = false;
+        world.get_mut::<Field>(second).unwrap().0 = true;
+        assert_eq!(update(&mut world), vec![Some(second)]);
+
+        world.entity_mut(second).despawn();
+        assert_eq!(update(&mut world), vec![None]);
+        assert_eq!(world.resource::<AsciiAccessibilityTree>().focused(), None);
+    }
+}
@@ -0,0 +1,247 @@
+use std::{collections::BTreeMap, io};
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+    utils::BoxedFuture,
+};
+
+use super::character::glyph_fallback;
+
+//=============================================================================
+//             Ascii Big Font
+//=============================================================================
+
+// A font for banner text that takes up several cells per character, like FIGlet. Every glyph is
+// a few rows of text: `#` fills the cell with the text color, a space leaves it as it is, and any
+// other character is drawn as itself. Fonts can be made in code and added to
+// `Assets<AsciiBigFont>`, or loaded from `.font.ron` files with the `serialize` feature.
+#[derive(Asset, TypePath, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct AsciiBigFont {
+    pub width: u32,
+    pub height: u32,
+    // The number of empty columns between glyphs.
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub spacing: u32,
+    pub glyphs: BTreeMap<char, Vec<String>>,
+}
+
+impl AsciiBigFont {
+    pub const FILL: char = '#';
+
+    pub fn new(width: u32, height: u32) -> Self {
+        AsciiBigFont {
+            width,
+            height,
+            spacing: 1,
+            glyphs: BTreeMap::new(),
+        }
+    }
+
+    pub fn spacing(mut self, spacing: u32) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    // Rows that are shorter than the font is wide are filled up with spaces.
+    pub fn with_glyph(mut self, c: char, rows: &[&str]) -> Self {
+        self.glyphs.insert(c, rows.iter().map(|row| row.to_string()).collect());
+        self
+    }
+
+    // The glyph for a character. Letters the font only has in one case use the other case, and
+    // accented letters use the letter without the accent.
+    pub fn glyph(&self, c: char) -> Option<&[String]> {
+        let get = |c: char| {
+            self.glyphs
+                .get(&c)
+                .or_else(|| self.glyphs.get(&c.to_ascii_uppercase()))
+                .or_else(|| self.glyphs.get(&c.to_ascii_lowercase()))
+        };
+        get(c)
+            .or_else(|| glyph_fallback(c).and_then(get))
+            .map(|rows| rows.as_slice())
+    }
+
+    // The number of cells the text takes up. Every line of the text is a row of glyphs, with an
+    // empty row between them.
+    pub fn measure(&self, text: &str) -> UVec2 {
+        let lines = text.lines().count().max(1) as u32;
+        let columns = text.lines().map(|line| line.chars().count() as u32).max().unwrap_or(0);
+        UVec2::new(
+            (columns * (self.width + self.spacing)).saturating_sub(self.spacing),
+            lines * (self.height + 1) - 1,
+        )
+    }
+
+    // Every cell of the text that is not empty, with the character it shows or `None` when it is
+    // filled.
+    pub fn cells<'a>(&'a self, text: &'a str) -> impl Iterator<Item = (IVec2, Option<char>)> + 'a {
+        let (width, height) = (self.width as i32, self.height as i32);
+        text.lines().enumerate().flat_map(move |(line, text)| {
+            text.chars().enumerate().flat_map(move |(column, c)| {
+                let origin = IVec2::new(column as i32 * (width + self.spacing as i32), line as i32 * (height + 1));
+                self.glyph(c)
+                    .unwrap_or_default()
+                    .iter()
+                    .take(height as usize)
+                    .enumerate()
+                    .flat_map(move |(y, row)| {
+                        row.chars()
+                            .take(width as usize)
+                            .enumerate()
+                            .filter(|(_, c)| *c != ' ')
+                            .map(move |(x, c)| {
+                                let cell = origin + IVec2::new(x as i32, y as i32);
+                                (cell, (c != Self::FILL).then_some(c))
+                            })
+                    })
+            })
+        })
+    }
+
+    // A font of 3 by 3 cells, with upper case letters, digits and some punctuation.
+    pub fn small() -> Self {
+        SMALL_GLYPHS.iter().fold(AsciiBigFont::new(3, 3), |font, (c, rows)| font.with_glyph(*c, rows))
+    }
+
+    // A font of 5 by 5 cells, with upper case letters, digits and some punctuation.
+    pub fn large() -> Self {
+        LARGE_GLYPHS.iter().fold(AsciiBigFont::new(5, 5), |font, (c, rows)| font.with_glyph(*c, rows))
+    }
+
+    // Reads the `.font.ron` format.
+    #[cfg(feature = "serialize")]
+    pub fn from_ron(text: &str) -> io::Result<Self> {
+        ron::from_str(text).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+    }
+}
+
+#[derive(Default)]
+pub struct AsciiBigFontLoader;
+
+impl AssetLoader for AsciiBigFontLoader {
+    type Asset = AsciiBigFont;
+    type Settings = ();
+    type Error = io::Error;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a Self::Settings,
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut text = String::new();
+            reader.read_to_string(&mut text).await?;
+
+            #[cfg(feature = "serialize")]
+            return AsciiBigFont::from_ron(&text);
+            #[cfg(not(feature = "serialize"))]
+            Err(io::Error::new(io::ErrorKind::Unsupported, "loading .font.ron needs the serialize feature"))
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["font.ron"]
+    }
+}
+
+//=============================================================================
+//             Built-in Fonts
+//=============================================================================
+
+const SMALL_GLYPHS: &[(char, [&str; 3])] = &[
+    ('A', [" # ", "###", "# #"]),
+    ('B', ["## ", "###", "###"]),
+    ('C', ["###", "#  ", "###"]),
+    ('D', ["## ", "# #", "## "]),
+    ('E', ["###", "## ", "###"]),
+    ('F', ["###", "## ", "#  "]),
+    ('G', ["## ", "# #", "###"]),
+    ('H', ["# #", "###", "# #"]),
+    ('I', ["###", " # ", "###"]),
+    ('J', ["  #", "  #", "## "]),
+    ('K', ["# #", "## ", "# #"]),
+    ('L', ["#  ", "#  ", "###"]),
+    ('M', ["###", "###", "# #"]),
+    ('N', ["## ", "# #", "# #"]),
+    ('O', ["###", "# #", "###"]),
+    ('P', ["###", "###", "#  "]),
+    ('Q', ["###", "###", "  #"]),
+    ('R', ["## ", "###", "# #"]),
+    ('S', [" ##", " # ", "## "]),
+    ('T', ["###", " # ", " # "]),
+    ('U', ["# #", "# #", "###"]),
+    ('V', ["# #", "# #", " # "]),
+    ('W', ["# #", "###", "###"]),
+    ('X', ["# #", " # ", "# #"]),
+    ('Y', ["# #", " # ", " # "]),
+    ('Z', ["## ", " # ", " ##"]),
+    ('0', ["###", "# #", "###"]),
+    ('1', ["## ", " # ", "###"]),
+    ('2', ["## ", " # ", " ##"]),
+    ('3', ["###", " ##", "###"]),
+    ('4', ["# #", "###", "  #"]),
+    ('5', [" ##", " # ", "## "]),
+    ('6', ["#  ", "###", "###"]),
+    ('7', ["###", "  #", "  #"]),
+    ('8', ["###", "###", "###"]),
+    ('9', ["###", "###", "  #"]),
+    (' ', ["", "", ""]),
+    ('!', [" # ", " # ", " . "]),
+    ('?', ["## ", " # ", " . "]),
+    ('.', ["", "", " # "]),
+    (',', ["", "", " , "]),
+    ('-', ["", "###", ""]),
+    (':', [" # ", "", " # "]),
+    ('\'', [" # ", "", ""]),
+];
+
+const LARGE_GLYPHS: &[(char, [&str; 5])] = &[
+    ('A', [" ### ", "#   #", "#####", "#   #", "#   #"]),
+    ('B', ["#### ", "#   #", "#### ", "#   #", "#### "]),
+    ('C', [" ####", "#    ", "#    ", "#    ", " ####"]),
+    ('D', ["#### ", "#   #", "#   #", "#   #", "#### "]),
+    ('E', ["#####", "#    ", "#### ", "#    ", "#####"]),
+    ('F', ["#####", "#    ", "#### ", "#    ", "#    "]),
+    ('G', [" ####", "#    ", "#  ##", "#   #", " ####"]),
+    ('H', ["#   #", "#   #", "#####", "#   #", "#   #"]),
+    ('I', ["#####", "  #  ", "  #  ", "  #  ", "#####"]),
+    ('J', ["#####", "   # ", "   # ", "#  # ", " ##  "]),
+    ('K', ["#   #", "#  # ", "###  ", "#  # ", "#   #"]),
+    ('L', ["#    ", "#    ", "#    ", "#    ", "#####"]),
+    ('M', ["#   #", "## ##", "# # #", "#   #", "#   #"]),
+    ('N', ["#   #", "##  #", "# # #", "#  ##", "#   #"]),
+    ('O', [" ### ", "#   #", "#   #", "#   #", " ### "]),
+    ('P', ["#### ", "#   #", "#### ", "#    ", "#    "]),
+    ('Q', [" ### ", "#   #", "# # #", "#  # ", " ## #"]),
+    ('R', ["#### ", "#   #", "#### ", "#  # ", "#   #"]),
+    ('S', [" ####", "#    ", " ### ", "    #", "#### "]),
+    ('T', ["#####", "  #  ", "  #  ", "  #  ", "  #  "]),
+    ('U', ["#   #", "#   #", "#   #", "#   #", " ### "]),
+    ('V', ["#   #", "#   #", "#   #", " # # ", "  #  "]),
+    ('W', ["#   #", "#   #", "# # #", "## ##", "#   #"]),
+    ('X', ["#   #", " # # ", "  #  ", " # # ", "#   #"]),
+    ('Y', ["#   #", " # # ", "  #  ", "  #  ", "  #  "]),
+    ('Z', ["#####", "   # ", "  #  ", " #   ", "#####"]),
+    ('0', [" ### ", "#  ##", "# # #", "##  #", " ### "]),
+    ('1', ["  #  ", " ##  ", "  #  ", "  #  ", " ### "]),
+    ('2', [" ### ", "#   #", "  ## ", " #   ", "#####"]),
+    ('3', ["#### ", "    #", " ### ", "    #", "#### "]),
+    ('4', ["#   #", "#   #", "#####", "    #", "    #"]),
+    ('5', ["#####", "#    ", "#### ", "    #", "#### "]),
+    ('6', [" ### ", "#    ", "#### ", "#   #", " ### "]),
+    ('7', ["#####", "    #", "   # ", "  #  ", "  #  "]),
+    ('8', [" ### ", "#   #", " ### ", "#   #", " ### "]),
+    ('9', [" ### ", "#   #", " ####", "    #", " ### "]),
+    (' ', ["", "", "", "", ""]),
+    ('!', ["  #  ", "  #  ", "  #  ", "", "  #  "]),
+    ('?', [" ### ", "#   #", "  ## ", "", "  #  "]),
+    ('.', ["", "", "", "", "  #  "]),
+    (',', ["", "", "", "  #  ", " #   "]),
+    ('-', ["", "", " ### ", "", ""]),
+    (':', ["", "  #  ", "", "  #  ", ""]),
+    ('\'', ["  #  ", "  #  ", "", "", ""]),
+];
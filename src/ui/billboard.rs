@@ -0,0 +1,408 @@
+use std::sync::{
+    mpsc::{channel, Receiver, Sender},
+    Arc, Mutex,
+};
+
+use bevy::{
+    prelude::*,
+    render::{
+        render_resource::{
+            Buffer, BufferDescriptor, BufferUsages, Extent3d, ImageCopyBuffer, ImageCopyTexture, ImageDataLayout,
+            Maintain, MapMode, Origin3d, Texture, TextureAspect, TextureUsages,
+        },
+        renderer::{RenderContext, RenderDevice},
+        Extract, Render, RenderApp, RenderSet,
+    },
+    utils::HashMap,
+};
+
+use crate::ascii::AsciiCamera;
+
+use super::{
+    character::{AsciiBlendMode, Color},
+    overlay::{mark_overlay_dirty, AsciiOverlay},
+    AsciiUiSchedule, AsciiUiSet, BorderStyle,
+};
+
+//=============================================================================
+//             Plugin and Systems
+//=============================================================================
+
+pub(crate) struct AsciiBillboardPlugin;
+
+impl Plugin for AsciiBillboardPlugin {
+    fn build(&self, app: &mut App) {
+        let (sender, receiver) = channel();
+
+        app
+            .register_type::<AsciiBillboard>()
+            .init_resource::<BillboardDepthProbes>()
+            .init_resource::<BillboardOcclusion>()
+            .insert_resource(BillboardOcclusionReceiver(Mutex::new(receiver)))
+            .add_systems(PreUpdate, receive_billboard_occlusion)
+            .add_systems(AsciiUiSchedule, draw_billboards.in_set(AsciiUiSet::Layout).before(mark_overlay_dirty))
+        ;
+
+        let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app
+            .insert_resource(BillboardDepthReadback {
+                sender,
+                probes: Vec::new(),
+                copied: Vec::new(),
+                buffer: None,
+                mapping: None,
+            })
+            .add_systems(ExtractSchedule, extract_billboard_probes)
+            .add_systems(Render, prepare_billboard_readback.in_set(RenderSet::PrepareResources))
+            .add_systems(Render, read_billboard_depths.in_set(RenderSet::Cleanup));
+    }
+}
+
+fn draw_billboards(
+    billboards: Query<(Entity, &AsciiBillboard, &GlobalTransform, Option<&InheritedVisibility>)>,
+    cameras: Query<(&Camera, &GlobalTransform, &AsciiCamera)>,
+    occlusion: Res<BillboardOcclusion>,
+    mut probes: ResMut<BillboardDepthProbes>,
+    overlay: Res<AsciiOverlay>,
+) {
+    probes.0.clear();
+
+    for (entity, billboard, transform, visibility) in billboards.iter() {
+        if !visibility.map(|visibility| visibility.get()).unwrap_or(true) || billboard.text.is_empty() {
+            continue;
+        }
+        let Ok((camera, camera_transform, ascii_camera)) = cameras.get(billboard.camera) else {
+            continue;
+        };
+
+        let position = transform.translation();
+        let distance = position.distance(camera_transform.translation());
+        let opacity = match billboard.fade {
+            Some((start, end)) if end > start => 1.0 - ((distance - start) / (end - start)).clamp(0.0, 1.0),
+            Some((_, end)) => if distance < end { 1.0 } else { 0.0 },
+            None => 1.0,
+        };
+        if opacity <= 0.0 {
+            continue;
+        }
+
+        // The depth is tested at a point moved towards the camera by the bias, so the mesh the
+        // billboard belongs to does not hide it.
+        if let Some(bias) = billboard.occlusion_bias {
+            let towards_camera = (camera_transform.translation() - position).normalize_or_zero();
+            let probe = camera.world_to_ndc(camera_transform, position + towards_camera * bias);
+            if let (Some(ndc), Some(viewport)) = (probe, camera.physical_viewport_rect()) {
+                let size = viewport.size().as_vec2();
+                let pixel = viewport.min.as_vec2() + Vec2::new((ndc.x + 1.0) / 2.0, (1.0 - ndc.y) / 2.0) * size;
+                let is_inside = (0.0..=1.0).contains(&ndc.z)
+                    && pixel.cmpge(viewport.min.as_vec2()).all()
+                    && pixel.cmplt(viewport.max.as_vec2()).all();
+                if is_inside {
+                    probes.0.push(DepthProbe {
+                        billboard: entity,
+                        camera: billboard.camera,
+                        pixel: pixel.as_uvec2(),
+                        depth: ndc.z,
+                    });
+                }
+            }
+            if occlusion.0.get(&entity).copied().unwrap_or(false) {
+                continue;
+            }
+        }
+
+        let Some(viewport_position) = camera.world_to_viewport(camera_transform, position) else {
+            continue;
+        };
+        let Some(viewport_size) = camera.logical_viewport_size() else {
+            continue;
+        };
+        let cell = (viewport_position / viewport_size * *ascii_camera.target_res() / ascii_camera.ui_scale() as f32)
+            .floor()
+            .as_ivec2();
+
+        // The text is centered on the cell of the entity, inside of the border if there is one.
+        let border = if billboard.border.is_some() { 1 } else { 0 };
+        let width = billboard.text.chars().count() as u32 + border * 2;
+        let height = 1 + border * 2;
+        let x = cell.x - width as i32 / 2 + billboard.offset.x;
+        let y = cell.y - height as i32 / 2 + billboard.offset.y;
+
+        let billboard = billboard.clone();
+        overlay.draw_on_layer(billboard.camera, billboard.layer, move |buffer| {
+            let buffer = buffer.blend(billboard.blend).opacity(opacity).sub_buffer(x, y, width, height);
+            let inner = match billboard.border {
+                Some(border) => buffer
+                    .square()
+                    .border(border)
+                    .border_color(billboard.text_color)
+                    .bg_color(billboard.bg_color)
+                    .draw(),
+                None => buffer,
+            };
+            inner
+                .text(&billboard.text)
+                .text_color(billboard.text_color)
+                .bg_color(billboard.bg_color)
+                .draw();
+        });
+    }
+}
+
+fn receive_billboard_occlusion(receiver: Res<BillboardOcclusionReceiver>, mut occlusion: ResMut<BillboardOcclusion>) {
+    let Ok(receiver) = receiver.0.lock() else { return };
+    if let Some(latest) = receiver.try_iter().last() {
+        occlusion.0 = latest;
+    }
+}
+
+//=============================================================================
+//             Ascii Billboard
+//=============================================================================
+
+// A line of text drawn on the overlay of a camera at the cell the entity is in, like damage
+// numbers or a prompt over a door. It moves a whole cell at a time, like everything on the grid.
+// The entity needs a `GlobalTransform`.
+#[derive(Component, Clone, Debug, PartialEq, Reflect)]
+pub struct AsciiBillboard {
+    camera: Entity,
+    text: String,
+    text_color: Color,
+    bg_color: Color,
+    border: Option<BorderStyle>,
+    // Moves the text by whole cells from the cell of the entity.
+    offset: IVec2,
+    blend: AsciiBlendMode,
+    layer: u32,
+    occlusion_bias: Option<f32>,
+    fade: Option<(f32, f32)>,
+}
+
+impl AsciiBillboard {
+    pub fn new(camera: Entity, text: impl Into<String>) -> Self {
+        AsciiBillboard {
+            camera,
+            text: text.into(),
+            text_color: Color::White,
+            bg_color: Color::Black,
+            border: None,
+            offset: IVec2::ZERO,
+            blend: AsciiBlendMode::Replace,
+            layer: AsciiOverlay::LAYER,
+            occlusion_bias: None,
+            fade: None,
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn set_text(&mut self, text: impl Into<String>) {
+        self.text = text.into();
+    }
+
+    pub fn text_color(mut self, color: Color) -> Self {
+        self.text_color = color;
+        self
+    }
+
+    pub fn bg_color(mut self, color: Color) -> Self {
+        self.bg_color = color;
+        self
+    }
+
+    // Draws a box around the text, in the text color.
+    pub fn border(mut self, border: BorderStyle) -> Self {
+        self.border = Some(border);
+        self
+    }
+
+    pub fn offset(mut self, x: i32, y: i32) -> Self {
+        self.offset = IVec2::new(x, y);
+        self
+    }
+
+    pub fn blend(mut self, blend: AsciiBlendMode) -> Self {
+        self.blend = blend;
+        self
+    }
+
+    pub fn layer(mut self, layer: u32) -> Self {
+        self.layer = layer;
+        self
+    }
+
+    // Hides the billboard while the scene is in front of it. This reads the depth of the camera
+    // back from the gpu, so the camera needs a `DepthPrepass` and `Msaa::Off`, and the billboard
+    // hides and shows a few frames late. The depth is tested `bias` units towards the camera, so
+    // it is not hidden by the mesh it is placed on.
+    pub fn occluded(mut self, bias: f32) -> Self {
+        self.occlusion_bias = Some(bias);
+        self
+    }
+
+    // Fades the billboard out between `start` and `end` units away from the camera. The overlay
+    // is dithered, so it fades in steps.
+    pub fn fade(mut self, start: f32, end: f32) -> Self {
+        self.fade = Some((start, end));
+        self
+    }
+}
+
+//=============================================================================
+//             Depth Readback
+//=============================================================================
+
+// A point of the depth texture of a camera that is read to find out if a billboard is hidden.
+#[derive(Clone, Copy)]
+struct DepthProbe {
+    billboard: Entity,
+    camera: Entity,
+    // The physical pixel of the render target.
+    pixel: UVec2,
+    // The depth of the billboard itself.
+    depth: f32,
+}
+
+#[derive(Resource, Default, Clone)]
+struct BillboardDepthProbes(Vec<DepthProbe>);
+
+// Whether every billboard that is occluded by the scene was hidden the last time it was read.
+#[derive(Resource, Default)]
+struct BillboardOcclusion(HashMap<Entity, bool>);
+
+#[derive(Resource)]
+struct BillboardOcclusionReceiver(Mutex<Receiver<HashMap<Entity, bool>>>);
+
+#[derive(Resource)]
+pub(crate) struct BillboardDepthReadback {
+    sender: Sender<HashMap<Entity, bool>>,
+    // The probes of this frame.
+    probes: Vec<DepthProbe>,
+    // The probes that are copied into the buffer, one depth after the other.
+    copied: Vec<DepthProbe>,
+    buffer: Option<Buffer>,
+    // Set while the buffer is being mapped, and holds whether mapping worked once it is done.
+    mapping: Option<Arc<Mutex<Option<bool>>>>,
+}
+
+impl BillboardDepthReadback {
+    // Copies the depth under every probe of the camera. Multisampled depth can not be copied, and
+    // the depth texture can only be copied from when the camera has a depth prepass.
+    pub(crate) fn copy_from(&self, entity: Entity, depth: &Texture, render_context: &mut RenderContext) {
+        let Some(buffer) = self.buffer.as_ref().filter(|_| self.mapping.is_none()) else { return };
+        if depth.sample_count() != 1 || !depth.usage().contains(TextureUsages::COPY_SRC) {
+            return;
+        }
+
+        for (index, probe) in self.copied.iter().enumerate() {
+            if probe.camera != entity || probe.pixel.x >= depth.width() || probe.pixel.y >= depth.height() {
+                continue;
+            }
+            render_context.command_encoder().copy_texture_to_buffer(
+                ImageCopyTexture {
+                    texture: depth,
+                    mip_level: 0,
+                    origin: Origin3d {
+                        x: probe.pixel.x,
+                        y: probe.pixel.y,
+                        z: 0,
+                    },
+                    aspect: TextureAspect::DepthOnly,
+                },
+                ImageCopyBuffer {
+                    buffer,
+                    layout: ImageDataLayout {
+                        offset: index as u64 * 4,
+                        bytes_per_row: None,
+                        rows_per_image: None,
+                    },
+                },
+                Extent3d {
+                    width: 1,
+                    height: 1,
+                    depth_or_array_layers: 1,
+                },
+            );
+        }
+    }
+}
+
+fn extract_billboard_probes(
+    probes: Extract<Res<BillboardDepthProbes>>,
+    mut readback: ResMut<BillboardDepthReadback>,
+) {
+    readback.probes.clone_from(&probes.0);
+}
+
+// The buffer can not be copied to until the last copy was read, so the probes of the frames in
+// between are skipped.
+fn prepare_billboard_readback(mut readback: ResMut<BillboardDepthReadback>, render_device: Res<RenderDevice>) {
+    if readback.mapping.is_some() {
+        return;
+    }
+    readback.copied = std::mem::take(&mut readback.probes);
+    if readback.copied.is_empty() {
+        return;
+    }
+
+    let size = readback.copied.len() as u64 * 4;
+    if readback.buffer.as_ref().map(|buffer| buffer.size() < size).unwrap_or(true) {
+        readback.buffer = Some(render_device.create_buffer(&BufferDescriptor {
+            label: Some("ascii_billboard_depth_buffer"),
+            size: size.next_power_of_two().max(64),
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        }));
+    }
+}
+
+// The depth is mapped without waiting on the gpu, and read on the frame it is ready. Depth is
+// reversed, so the scene is in front of a billboard where its depth is bigger.
+fn read_billboard_depths(mut readback: ResMut<BillboardDepthReadback>, render_device: Res<RenderDevice>) {
+    if readback.mapping.is_none() {
+        let Some(buffer) = readback.buffer.as_ref().filter(|_| !readback.copied.is_empty()) else { return };
+
+        let mapping = Arc::new(Mutex::new(None));
+        let result = mapping.clone();
+        buffer.slice(..).map_async(MapMode::Read, move |mapped| {
+            if let Ok(mut result) = result.lock() {
+                *result = Some(mapped.is_ok());
+            }
+        });
+        readback.mapping = Some(mapping);
+    }
+
+    render_device.poll(Maintain::Poll);
+
+    let Some(is_mapped) = readback.mapping.as_ref().and_then(|mapping| *mapping.lock().ok()?) else {
+        return;
+    };
+    readback.mapping = None;
+
+    let Some(buffer) = &readback.buffer else { return };
+    if !is_mapped {
+        return;
+    }
+
+    let occlusion = {
+        let data = buffer.slice(..).get_mapped_range();
+        readback
+            .copied
+            .iter()
+            .enumerate()
+            .map(|(index, probe)| {
+                let start = index * 4;
+                let scene = f32::from_le_bytes([data[start], data[start + 1], data[start + 2], data[start + 3]]);
+                (probe.billboard, scene > probe.depth)
+            })
+            .collect()
+    };
+    buffer.unmap();
+    readback.copied.clear();
+    let _ = readback.sender.send(occlusion);
+}
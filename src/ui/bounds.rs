@@ -1,8 +1,4 @@
-use std::ops::{Deref, DerefMut};
-
-use bevy::{prelude::*, utils::HashMap};
-
-use crate::ascii::AsciiCamera;
+use bevy::prelude::*;
 
 use super::{position::AsciiPosition, util::{Value, Variable}, HorizontalAlignment, VerticalAlignment};
 
@@ -17,6 +13,7 @@ impl Plugin for AsciiBoundsPlugin {
         app
             .register_type::<AsciiBounds>()
             .register_type::<AsciiNode>()
+            .register_type::<AsciiPinned>()
         ;
     }
 }
@@ -26,6 +23,7 @@ impl Plugin for AsciiBoundsPlugin {
 //=============================================================================
 
 #[derive(Clone, Default, Debug, Reflect, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct AsciiBounds {
     pub x: i32,
     pub y: i32,
@@ -60,20 +58,65 @@ impl AsciiBounds {
         self
     }
 
+    // Bounds cover the cells from `x` up to but not including `x + width`, and the same for `y`.
+    // Global coordinates are cells on the screen, local coordinates are relative to the top left
+    // corner of the bounds.
+
+    // The first column to the right of the bounds.
+    pub fn right(&self) -> i32 {
+        self.x + self.width as i32
+    }
+
+    // The first row below the bounds.
+    pub fn bottom(&self) -> i32 {
+        self.y + self.height as i32
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    // Whether the global cell is inside of the bounds.
     pub fn is_within(&self, x: i32, y: i32) -> bool {
-        x >= self.x
-            && x <= self.x + self.width as i32
-            && y >= self.y
-            && y <= self.y + self.height as i32
+        x >= self.x && x < self.right() && y >= self.y && y < self.bottom()
     }
 
+    // Whether the local cell is inside of the bounds.
     pub fn is_within_local(&self, x: i32, y: i32) -> bool {
-        let x = self.x + x;
-        let y = self.y + y;
-        x >= self.x
-            && x <= self.x + self.width as i32
-            && y >= self.y
-            && y <= self.y + self.height as i32
+        x >= 0 && x < self.width as i32 && y >= 0 && y < self.height as i32
+    }
+
+    pub fn to_local(&self, x: i32, y: i32) -> (i32, i32) {
+        (x - self.x, y - self.y)
+    }
+
+    pub fn to_global(&self, x: i32, y: i32) -> (i32, i32) {
+        (self.x + x, self.y + y)
+    }
+
+    // The part of these bounds that is also inside of `other`, keeping the layer of these bounds.
+    pub fn intersection(&self, other: &AsciiBounds) -> AsciiBounds {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = self.right().min(other.right()).max(x);
+        let bottom = self.bottom().min(other.bottom()).max(y);
+        AsciiBounds::new(x, y, (right - x) as u32, (bottom - y) as u32, self.layer)
+    }
+
+    // The smallest bounds that hold both of them, keeping the layer of these bounds. Empty bounds
+    // are left out.
+    pub fn union(&self, other: &AsciiBounds) -> AsciiBounds {
+        if other.is_empty() {
+            return self.clone();
+        }
+        if self.is_empty() {
+            return other.clone().with_layer(self.layer);
+        }
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        let right = self.right().max(other.right());
+        let bottom = self.bottom().max(other.bottom());
+        AsciiBounds::new(x, y, (right - x) as u32, (bottom - y) as u32, self.layer)
     }
 
     pub fn relative(&self, child: &AsciiBounds) -> AsciiBounds {
@@ -111,6 +154,8 @@ impl AsciiBounds {
 pub struct AsciiNode {
     pub bounds: AsciiBounds,
     pub is_dirty: bool,
+    // Keeps everything the nodes under this one draw inside of its bounds, like the content of a
+    // panel that scrolls. Nodes with `AsciiPinned` are not clipped.
     pub clip_bounds: Variable<bool>,
 }
 
@@ -123,6 +168,11 @@ impl AsciiNode {
         }
     }
 
+    pub fn clipped(mut self) -> Self {
+        self.clip_bounds = true.into();
+        self
+    }
+
     pub fn set_bounds_from(&mut self, bounds: &AsciiBounds) {
         self.bounds = bounds.clone();
         self.is_dirty = true;
@@ -136,3 +186,71 @@ impl AsciiNode {
         self.clip_bounds.reset();
     }
 }
+
+// Draws the node outside of the bounds of the nodes it is under, even when they clip their
+// children. Together with `AsciiPosition::Absolute` this is for hud elements that belong to a node,
+// but are drawn somewhere else on the screen.
+#[derive(Component, Clone, Copy, Default, Debug, Reflect)]
+pub struct AsciiPinned;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn right_and_bottom_edges_are_exclusive() {
+        let bounds = AsciiBounds::new(2, 3, 4, 5, 0);
+        assert_eq!(bounds.right(), 6);
+        assert_eq!(bounds.bottom(), 8);
+
+        assert!(bounds.is_within(2, 3));
+        assert!(bounds.is_within(5, 7));
+        assert!(!bounds.is_within(6, 7));
+        assert!(!bounds.is_within(5, 8));
+        assert!(!bounds.is_within(1, 3));
+        assert!(!bounds.is_within(2, 2));
+
+        assert!(bounds.is_within_local(0, 0));
+        assert!(bounds.is_within_local(3, 4));
+        assert!(!bounds.is_within_local(4, 4));
+        assert!(!bounds.is_within_local(3, 5));
+        assert!(!bounds.is_within_local(-1, 0));
+    }
+
+    #[test]
+    fn empty_bounds_hold_no_cells() {
+        let bounds = AsciiBounds::new(2, 3, 0, 5, 0);
+        assert!(bounds.is_empty());
+        assert!(!bounds.is_within(2, 3));
+        assert!(!bounds.is_within_local(0, 0));
+    }
+
+    #[test]
+    fn local_and_global_round_trip() {
+        let bounds = AsciiBounds::new(-3, 7, 10, 4, 2);
+        assert_eq!(bounds.to_local(-3, 7), (0, 0));
+        assert_eq!(bounds.to_global(0, 0), (-3, 7));
+        for (x, y) in [(0, 0), (9, 3), (-5, 12), (4, -1)] {
+            let (gx, gy) = bounds.to_global(x, y);
+            assert_eq!(bounds.to_local(gx, gy), (x, y));
+            assert_eq!(bounds.is_within(gx, gy), bounds.is_within_local(x, y));
+        }
+    }
+
+    #[test]
+    fn intersection_stops_at_the_exclusive_edges() {
+        let a = AsciiBounds::new(0, 0, 5, 5, 3);
+        let b = AsciiBounds::new(3, 4, 5, 5, 1);
+        assert_eq!(a.intersection(&b), AsciiBounds::new(3, 4, 2, 1, 3));
+
+        // Bounds that only touch share no cells.
+        let touching = AsciiBounds::new(5, 0, 5, 5, 0);
+        assert!(a.intersection(&touching).is_empty());
+
+        let apart = AsciiBounds::new(10, 10, 2, 2, 0);
+        assert!(a.intersection(&apart).is_empty());
+
+        let inside = AsciiBounds::new(1, 1, 2, 2, 0);
+        assert_eq!(a.intersection(&inside), inside.clone().with_layer(3));
+    }
+}
@@ -0,0 +1,187 @@
+use std::marker::PhantomData;
+
+use bevy::prelude::*;
+
+use crate::ascii::{update_target_resolution, AsciiCamera, AsciiGridInfo};
+
+use super::{flex::AsciiFlex, position::{AsciiDesiredSize, AsciiPosition}, ui_target, AsciiUiSet, AsciiUiTarget};
+
+//=============================================================================
+//             Ascii Breakpoint Plugin
+//=============================================================================
+
+// Keeps the `AsciiBreakpoint` of every camera up to date, and swaps the positions, flex layouts,
+// desired sizes and visibilities of nodes with an `AsciiResponsive` of them. Other components can
+// be made responsive with an `AsciiResponsivePlugin`.
+pub struct AsciiBreakpointPlugin;
+
+impl Plugin for AsciiBreakpointPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .register_type::<AsciiBreakpoint>()
+            .register_type::<AsciiBreakpoints>()
+            .register_type::<AsciiResponsive<AsciiPosition>>()
+            .register_type::<AsciiResponsive<AsciiFlex>>()
+            .register_type::<AsciiResponsive<AsciiDesiredSize>>()
+            .register_type::<AsciiResponsive<Visibility>>()
+            .add_systems(PreUpdate, update_breakpoints.after(update_target_resolution).in_set(AsciiUiSet::Input))
+
+            .add_plugins(AsciiResponsivePlugin::<AsciiPosition>::default())
+            .add_plugins(AsciiResponsivePlugin::<AsciiFlex>::default())
+            .add_plugins(AsciiResponsivePlugin::<AsciiDesiredSize>::default())
+            .add_plugins(AsciiResponsivePlugin::<Visibility>::default())
+        ;
+    }
+}
+
+// Applies the `AsciiResponsive<T>` of nodes when the breakpoint of their camera changes. The
+// layout picks the new value up like any other change, so nothing else has to be done.
+pub struct AsciiResponsivePlugin<T>(PhantomData<T>);
+
+impl<T> Default for AsciiResponsivePlugin<T> {
+    fn default() -> Self {
+        AsciiResponsivePlugin(PhantomData)
+    }
+}
+
+impl<T: Component + Clone + PartialEq> Plugin for AsciiResponsivePlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            apply_responsive::<T>.after(update_breakpoints).in_set(AsciiUiSet::Input),
+        );
+    }
+}
+
+//=============================================================================
+//             Ascii Breakpoints
+//=============================================================================
+
+// The size class of the ui of a camera, worked out from its ui columns. It is added to every
+// ascii camera and kept up to date, and should not be inserted by hand.
+#[derive(Component, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum AsciiBreakpoint {
+    Small,
+    #[default]
+    Medium,
+    Large,
+}
+
+// The ui columns at which a camera goes from small to medium, and from medium to large. Cameras
+// without it use 60 and 120 columns.
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct AsciiBreakpoints {
+    pub medium: u32,
+    pub large: u32,
+}
+
+impl Default for AsciiBreakpoints {
+    fn default() -> Self {
+        AsciiBreakpoints {
+            medium: 60,
+            large: 120,
+        }
+    }
+}
+
+impl AsciiBreakpoints {
+    pub fn breakpoint(&self, columns: u32) -> AsciiBreakpoint {
+        if columns >= self.large {
+            AsciiBreakpoint::Large
+        } else if columns >= self.medium {
+            AsciiBreakpoint::Medium
+        } else {
+            AsciiBreakpoint::Small
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn update_breakpoints(
+    mut commands: Commands,
+    mut cameras: Query<(Entity, &AsciiGridInfo, Option<&AsciiBreakpoints>, Option<&mut AsciiBreakpoint>), With<AsciiCamera>>,
+) {
+    for (entity, grid, breakpoints, current) in cameras.iter_mut() {
+        let breakpoint = breakpoints.copied().unwrap_or_default().breakpoint(grid.ui_columns);
+        match current {
+            Some(mut current) if *current != breakpoint => *current = breakpoint,
+            Some(_) => {}
+            None => {
+                commands.entity(entity).insert(breakpoint);
+            }
+        }
+    }
+}
+
+//=============================================================================
+//             Ascii Responsive
+//=============================================================================
+
+// Alternate values of a component of a node for the breakpoints of its camera. Like css, a value
+// counts for its breakpoint and the ones above it, until a bigger breakpoint sets its own. A
+// sidebar that is hidden below 60 columns is an
+// `AsciiResponsive::new(Visibility::Hidden).medium(Visibility::Inherited)`.
+#[derive(Component, Clone, Debug, PartialEq, Reflect)]
+pub struct AsciiResponsive<T> {
+    // The value for small grids, and for bigger ones that do not set their own.
+    pub base: T,
+    pub medium: Option<T>,
+    pub large: Option<T>,
+}
+
+impl<T> AsciiResponsive<T> {
+    pub fn new(base: T) -> Self {
+        AsciiResponsive {
+            base,
+            medium: None,
+            large: None,
+        }
+    }
+
+    pub fn medium(mut self, value: T) -> Self {
+        self.medium = Some(value);
+        self
+    }
+
+    pub fn large(mut self, value: T) -> Self {
+        self.large = Some(value);
+        self
+    }
+
+    pub fn get(&self, breakpoint: AsciiBreakpoint) -> &T {
+        match breakpoint {
+            AsciiBreakpoint::Small => &self.base,
+            AsciiBreakpoint::Medium => self.medium.as_ref().unwrap_or(&self.base),
+            AsciiBreakpoint::Large => self.large.as_ref().or(self.medium.as_ref()).unwrap_or(&self.base),
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn apply_responsive<T: Component + Clone + PartialEq>(
+    mut commands: Commands,
+    mut nodes: Query<(Entity, Ref<AsciiResponsive<T>>, Option<&mut T>)>,
+    breakpoints: Query<Ref<AsciiBreakpoint>>,
+    hierarchy: Query<(Option<&Parent>, Option<&AsciiUiTarget>, Has<AsciiCamera>)>,
+) {
+    for (entity, responsive, current) in nodes.iter_mut() {
+        // Nodes that are not drawn on a camera have no breakpoint, they are left as they are.
+        let Some(breakpoint) = ui_target(entity, &hierarchy).and_then(|camera| breakpoints.get(camera).ok()) else {
+            continue;
+        };
+        if !responsive.is_changed() && !breakpoint.is_changed() {
+            continue;
+        }
+
+        let value = responsive.get(*breakpoint);
+        match current {
+            Some(mut current) if *current != *value => *current = value.clone(),
+            Some(_) => {}
+            None => {
+                commands.entity(entity).insert(value.clone());
+            }
+        }
+    }
+}
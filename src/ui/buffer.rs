@@ -1,8 +1,8 @@
-use bevy::{ecs::component::Component, reflect::Reflect};
+use bevy::{math::{IVec2, UVec2}, utils::HashSet};
 use std::sync::{Arc, Mutex};
 
 use super::{
-    bounds::AsciiBounds, character::{AsciiCharacter, Color}, position::AsciiPosition, util::Value, BorderType, Character, HorizontalAlignment, Padding, TextOverflow, VerticalAlignment
+    big_text::AsciiBigFont, bounds::AsciiBounds, character::{AsciiBlendMode, AsciiCharacter, Color}, lightmap::AsciiLightmap, markup::AsciiMarkup, position::AsciiPosition, transition::AsciiTransitionMask, util::{noise_hash, Value}, BorderStyle, Character, HorizontalAlignment, Padding, TextOverflow, VerticalAlignment
 };
 
 //=============================================================================
@@ -14,14 +14,31 @@ pub struct AsciiBuffer {
     surface: AsciiSurface,
     pub bounds: AsciiBounds,
     clip_bounds: Option<AsciiBounds>,
+    blend: AsciiBlendMode,
+    opacity: f32,
+    // The lightmap that darkens what is drawn, and what to add to a global cell to get its cell
+    // on the lightmap.
+    lightmap: Option<(Arc<AsciiLightmap>, IVec2)>,
+    // Hides the cells of the other screen while a transition runs.
+    mask: Option<AsciiTransitionMask>,
+    // Written to the metadata of every cell that is drawn, see `with_meta`.
+    meta: Option<u16>,
 }
 
 impl AsciiBuffer {
+    // Popups are drawn over the ui nodes, and under the `AsciiOverlay`.
+    pub const POPUP_LAYER: u32 = 500_000;
+
     pub fn new(surface: &AsciiSurface, bounds: &AsciiBounds, clip_bounds : Option<AsciiBounds>) -> Self {
         AsciiBuffer {
             surface: surface.clone(),
             bounds: bounds.clone(),
             clip_bounds,
+            blend: AsciiBlendMode::Replace,
+            opacity: 1.0,
+            lightmap: None,
+            mask: None,
+            meta: None,
         }
     }
     
@@ -30,47 +47,173 @@ impl AsciiBuffer {
             surface: self.surface.clone(),
             bounds: self.bounds.clone(),
             clip_bounds: Some(self.bounds.clone()),
+            blend: self.blend,
+            opacity: self.opacity,
+            lightmap: self.lightmap.clone(),
+            mask: self.mask,
+            meta: self.meta,
         }
     }
 
     pub fn set_character(&self, x: i32, y: i32, character: impl Into<AsciiCharacter>) {
+        self.set_characters(std::iter::once((x, y, character)));
+    }
+
+    // Sets every character in one go, the same as calling `set_character` for each of them. The
+    // surface is only locked once, which is a lot faster for big drawings.
+    pub fn set_characters<C: Into<AsciiCharacter>>(&self, characters: impl IntoIterator<Item = (i32, i32, C)>) {
+        let mut batch = self.batch();
+        for (x, y, character) in characters {
+            batch.set_character(x, y, character);
+        }
+        batch.commit();
+    }
+
+    // Collects the characters drawn to it, and writes them to the surface when it is committed.
+    // Drawers use it to draw all of their cells with one lock of the surface.
+    pub fn batch(&self) -> DrawBatch<'_> {
+        DrawBatch {
+            buffer: self,
+            cells: Vec::new(),
+        }
+    }
+
+    // The global cell and the character that is drawn there for a local cell, or `None` when the
+    // cell is clipped.
+    fn place(&self, x: i32, y: i32, character: AsciiCharacter) -> Option<(i32, i32, AsciiCharacter)> {
         let x = self.bounds.x + x;
         let y = self.bounds.y + y;
         
         if let Some(clip_bounds) = &self.clip_bounds {
             if !clip_bounds.is_within(x, y) {
-                return;
+                return None;
+            }
+        }
+        if let Some(mask) = &self.mask {
+            if !mask.shows(x, y, self.surface.width(), self.surface.height()) {
+                return None;
             }
         }
         
-        let character = character.into().with_layer(self.bounds.layer);
-        self.surface
-            .set_character(x, y, character);
-            
+        let mut character = character.with_layer(self.bounds.layer);
+        if self.blend != AsciiBlendMode::Replace {
+            character = character.with_blend(self.blend);
+        }
+        if self.opacity < 1.0 {
+            character = character.with_opacity(self.opacity);
+        }
+        if let Some((lightmap, offset)) = &self.lightmap {
+            character = lightmap.darken(x + offset.x, y + offset.y, character);
+        }
+        Some((x, y, character))
+    }
+
+    // A copy of this buffer where everything that is drawn uses the given blend mode.
+    pub fn blend(&self, blend: AsciiBlendMode) -> AsciiBuffer {
+        AsciiBuffer {
+            blend,
+            ..self.clone()
+        }
     }
 
-    pub fn sub_buffer(&self, x: i32, y: i32, width: u32, height: u32) -> Option<AsciiBuffer> {
-        if self.bounds.is_within(x, y) {
-            // let width = self.bounds.width.saturating_sub(x).min(width);
-            // let height = self.bounds.height.saturating_sub(y).min(height);
+    // A copy of this buffer where everything that is drawn is faded to the given opacity.
+    pub fn opacity(&self, opacity: f32) -> AsciiBuffer {
+        AsciiBuffer {
+            opacity: opacity.clamp(0.0, 1.0),
+            ..self.clone()
+        }
+    }
 
-            return Some(AsciiBuffer {
-                surface: self.surface.clone(),
-                bounds: AsciiBounds::new(x, y, width, height, self.bounds.layer + 1),
-                clip_bounds: self.clip_bounds.clone(),
-            });
+    // A copy of this buffer where everything that is drawn is darkened by the lightmap. `origin` is
+    // the cell of the lightmap at the top left corner of this buffer, and it stays with the cells
+    // in the buffers made from this one. Popups are not darkened.
+    pub fn lightmap(&self, lightmap: &AsciiLightmap, origin: IVec2) -> AsciiBuffer {
+        AsciiBuffer {
+            lightmap: Some((Arc::new(lightmap.clone()), origin - IVec2::new(self.bounds.x, self.bounds.y))),
+            ..self.clone()
+        }
+    }
+
+    // A copy of this buffer that tags every cell it draws with `meta`, like the index of a tile or
+    // of an entity. The tag can be looked up with `AsciiSurface::meta_at`, or in the main world with
+    // `AsciiCellMeta`, to find out what was drawn under the cursor. 0 is the same as no tag.
+    pub fn with_meta(&self, meta: u16) -> AsciiBuffer {
+        AsciiBuffer {
+            meta: Some(meta),
+            ..self.clone()
+        }
+    }
+
+    // A copy of this buffer that only draws the cells the mask shows.
+    pub fn transition_mask(&self, mask: AsciiTransitionMask) -> AsciiBuffer {
+        AsciiBuffer {
+            mask: Some(mask),
+            ..self.clone()
         }
+    }
+
+    // A buffer for the area at the local `x` and `y` of this buffer, one layer up. The area is
+    // cut off at the edges of this buffer. What is left of an area that is partly or fully outside
+    // can have no cells, which the drawers draw nothing in.
+    pub fn sub_buffer(&self, x: i32, y: i32, width: u32, height: u32) -> AsciiBuffer {
+        let clamp = |start: i32, size: u32, parent_size: u32| {
+            let parent_size = parent_size as i64;
+            let from = (start as i64).clamp(0, parent_size);
+            let to = (start as i64 + size as i64).clamp(from, parent_size);
+            (from as i32, (to - from) as u32)
+        };
+        let (x, width) = clamp(x, width, self.bounds.width);
+        let (y, height) = clamp(y, height, self.bounds.height);
+        let (x, y) = self.bounds.to_global(x, y);
 
-        None
+        AsciiBuffer {
+            surface: self.surface.clone(),
+            bounds: AsciiBounds::new(x, y, width, height, self.bounds.layer + 1),
+            clip_bounds: self.clip_bounds.clone(),
+            blend: self.blend,
+            opacity: self.opacity,
+            lightmap: self.lightmap.clone(),
+            mask: self.mask,
+            meta: self.meta,
+        }
     }
     
+    // A buffer for an area at the local `x` and `y` of this buffer that is drawn over the rest of
+    // the ui, like the list of a dropdown. It is not cut off at the edges of this buffer, or by
+    // the nodes that clip this one.
+    pub fn popup(&self, x: i32, y: i32, width: u32, height: u32) -> AsciiBuffer {
+        let (x, y) = self.bounds.to_global(x, y);
+        AsciiBuffer {
+            surface: self.surface.clone(),
+            bounds: AsciiBounds::new(x, y, width, height, Self::POPUP_LAYER + self.bounds.layer),
+            clip_bounds: None,
+            blend: self.blend,
+            opacity: self.opacity,
+            lightmap: None,
+            mask: self.mask,
+            meta: self.meta,
+        }
+    }
+
+    // Values relative to the screen are parts of the surface, which is the grid of the camera.
+    fn on_screen(&self, width: impl Into<Value>, height: impl Into<Value>) -> (Value, Value) {
+        let screen = UVec2::new(self.surface.width(), self.surface.height());
+        (width.into().on_screen(screen.x, screen), height.into().on_screen(screen.y, screen))
+    }
+
     pub fn relative(&self, x: i32, y: i32, width: impl Into<Value>, height: impl Into<Value>) -> AsciiBuffer {
+        let (width, height) = self.on_screen(width, height);
         let mut child_bounds = AsciiBounds::default();
         AsciiPosition::relative(x, y, width, height, self.bounds.layer).format_bounds(self.bounds(), &mut child_bounds);
         AsciiBuffer {
             surface: self.surface.clone(),
             bounds: child_bounds,
             clip_bounds: self.clip_bounds.clone(),
+            blend: self.blend,
+            opacity: self.opacity,
+            lightmap: self.lightmap.clone(),
+            mask: self.mask,
+            meta: self.meta,
         }
     }
 
@@ -81,6 +224,11 @@ impl AsciiBuffer {
             surface: self.surface.clone(),
             bounds: child_bounds,
             clip_bounds: self.clip_bounds.clone(),
+            blend: self.blend,
+            opacity: self.opacity,
+            lightmap: self.lightmap.clone(),
+            mask: self.mask,
+            meta: self.meta,
         }
     }
     
@@ -91,6 +239,11 @@ impl AsciiBuffer {
             surface: self.surface.clone(),
             bounds: child_bounds,
             clip_bounds: self.clip_bounds.clone(),
+            blend: self.blend,
+            opacity: self.opacity,
+            lightmap: self.lightmap.clone(),
+            mask: self.mask,
+            meta: self.meta,
         }
     }
     
@@ -101,6 +254,11 @@ impl AsciiBuffer {
             surface: self.surface.clone(),
             bounds: child_bounds,
             clip_bounds: self.clip_bounds.clone(),
+            blend: self.blend,
+            opacity: self.opacity,
+            lightmap: self.lightmap.clone(),
+            mask: self.mask,
+            meta: self.meta,
         }
     }
     
@@ -111,16 +269,27 @@ impl AsciiBuffer {
             surface: self.surface.clone(),
             bounds: child_bounds,
             clip_bounds: self.clip_bounds.clone(),
+            blend: self.blend,
+            opacity: self.opacity,
+            lightmap: self.lightmap.clone(),
+            mask: self.mask,
+            meta: self.meta,
         }
     }
 
     pub fn center(&self, width: impl Into<Value>, height: impl Into<Value>) -> AsciiBuffer {
+        let (width, height) = self.on_screen(width, height);
         let mut child_bounds = AsciiBounds::default();
         AsciiPosition::format_bounds_aligned(width, height, HorizontalAlignment::Center, VerticalAlignment::Center, self.bounds(), &mut child_bounds);
         AsciiBuffer {
             surface: self.surface.clone(),
             bounds: child_bounds,
             clip_bounds: self.clip_bounds.clone(),
+            blend: self.blend,
+            opacity: self.opacity,
+            lightmap: self.lightmap.clone(),
+            mask: self.mask,
+            meta: self.meta,
         }
     }
 
@@ -201,12 +370,12 @@ impl AsciiBuffer {
         buffer
     }
     
-    pub fn border(&self, border_type : BorderType) -> AsciiBorderDrawer {
+    pub fn border(&self, border_style : impl Into<BorderStyle>) -> AsciiBorderDrawer<'_> {
         AsciiBorderDrawer {
             buffer: self,
             border_color: Color::White,
             bg_color: Color::Black,
-            border_type,
+            border_style: border_style.into(),
             top: false,
             bottom: false,
             left: false,
@@ -214,7 +383,7 @@ impl AsciiBuffer {
         }
     }
 
-    pub fn square(&self) -> AsciiBoxDrawer {
+    pub fn square(&self) -> AsciiBoxDrawer<'_> {
         AsciiBoxDrawer {
             buffer: self,
             bg_color: Color::Black,
@@ -224,11 +393,11 @@ impl AsciiBuffer {
             title: None,
             title_alignment: HorizontalAlignment::Left,
             title_overflow: TextOverflow::default(),
-            border: BorderType::None,
+            border: BorderStyle::NONE,
         }
     }
 
-    pub fn text(&self, text: &str) -> AsciiTextDrawer {
+    pub fn text(&self, text: &str) -> AsciiTextDrawer<'_> {
         AsciiTextDrawer {
             buffer: self,
             text_color: Color::White,
@@ -238,23 +407,164 @@ impl AsciiBuffer {
             vertical_alignment: VerticalAlignment::Top,
             overflow: TextOverflow::default(),
             should_wrap: false,
+            cache: None,
+            markup: None,
+            link_color: Color::LightBlue,
+            highlighted_link: None,
+            effects: TextEffects::default(),
+        }
+    }
+
+    // Draws the text of the markup, with its links in the link color. The cells of a link are
+    // tagged with its index counted from 1, see `with_meta`.
+    pub fn markup<'b>(&'b self, markup: &'b AsciiMarkup) -> AsciiTextDrawer<'b> {
+        AsciiTextDrawer {
+            markup: Some(markup),
+            ..self.text(markup.text())
+        }
+    }
+
+    // Draws a vertical bar for every value, from the bottom of the buffer up.
+    pub fn bar_chart(&self, values: &[f32]) -> AsciiChartDrawer<'_> {
+        AsciiChartDrawer {
+            buffer: self,
+            values: values.to_vec(),
+            range: None,
+            color: Color::White,
+            bg_color: Color::Black,
+            thresholds: Vec::new(),
+            bar_width: 1,
+            gap: 1,
+        }
+    }
+
+    // Draws the values as a dense graph with one column per value. When there are more values
+    // than columns, only the newest ones at the end of the slice are shown.
+    pub fn sparkline(&self, values: &[f32]) -> AsciiChartDrawer<'_> {
+        let skip = values.len().saturating_sub(self.bounds.width as usize);
+        AsciiChartDrawer {
+            buffer: self,
+            values: values[skip..].to_vec(),
+            range: None,
+            color: Color::White,
+            bg_color: Color::Black,
+            thresholds: Vec::new(),
+            bar_width: 1,
+            gap: 0,
+        }
+    }
+
+    // Fills about `density` (0.0 to 1.0) of the cells with characters from `charset` in one of
+    // `colors`. The same seed always gives the same noise, the other cells are left as they are.
+    pub fn noise_fill(&self, seed: u64, density: f32, charset: &[Character], colors: &[Color]) -> AsciiNoiseDrawer<'_> {
+        AsciiNoiseDrawer {
+            buffer: self,
+            seed,
+            density,
+            charset: charset.to_vec(),
+            colors: colors.to_vec(),
+            bg_color: Color::Black,
+            offset: IVec2::ZERO,
+        }
+    }
+
+    // Draws the text with a font that is several cells tall, for titles and banners.
+    pub fn big_text<'b>(&'b self, text: &str, font: &'b AsciiBigFont) -> AsciiBigTextDrawer<'b> {
+        AsciiBigTextDrawer {
+            buffer: self,
+            font,
+            text: text.to_string(),
+            text_color: Color::White,
+            bg_color: Color::Black,
+            horizontal_alignment: HorizontalAlignment::Left,
+            vertical_alignment: VerticalAlignment::Top,
+            effects: TextEffects::default(),
         }
     }
 
     pub fn bounds(&self) -> &AsciiBounds {
         &self.bounds
     }
+
+    // The time in seconds this buffer is drawn at.
+    pub fn time(&self) -> f32 {
+        self.surface.time()
+    }
+}
+
+//=============================================================================
+//             Draw Batch
+//=============================================================================
+
+// The cells a buffer drew, that are not on the surface yet. Every cell is clipped and given the
+// layer of the buffer when it is drawn, and written on `commit`. Cells that are not committed are
+// thrown away.
+pub struct DrawBatch<'b> {
+    buffer: &'b AsciiBuffer,
+    cells: Vec<(i32, i32, AsciiCharacter, u16)>,
+}
+
+impl<'b> DrawBatch<'b> {
+    pub fn set_character(&mut self, x: i32, y: i32, character: impl Into<AsciiCharacter>) {
+        self.set_character_with_meta(x, y, character, self.buffer.meta.unwrap_or(0));
+    }
+
+    // Draws the cell with `meta` as its metadata, in place of the one of the buffer.
+    pub fn set_character_with_meta(&mut self, x: i32, y: i32, character: impl Into<AsciiCharacter>, meta: u16) {
+        if let Some((x, y, character)) = self.buffer.place(x, y, character.into()) {
+            self.cells.push((x, y, character, meta));
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn commit(self) {
+        self.buffer.surface.write_batch(&self.cells, self.buffer.bounds.layer);
+    }
 }
 
 //=============================================================================
 //             Ascii UiSurface
 //=============================================================================
 
+// Every buffer of a surface shares its characters, and writes to them behind a lock. Systems that
+// write a lot of cells at once, like a whole map, should use `set_characters` or `apply_diff`,
+// which take the lock once for all of them.
 #[derive(Clone)]
 pub struct AsciiSurface {
     width: u32,
     height: u32,
-    data: Arc<Mutex<Vec<AsciiCharacter>>>,
+    time: f32,
+    data: Arc<Mutex<AsciiSurfaceData>>,
+}
+
+#[derive(Default)]
+struct AsciiSurfaceData {
+    characters: Vec<AsciiCharacter>,
+    // The smallest bounds that hold every cell that was written, for uploading only that part.
+    changed: Option<AsciiBounds>,
+    // The metadata of every cell, only made once the first cell with metadata is drawn.
+    meta: Vec<u16>,
+    // Every cell drawn since the surface was made, a cell drawn twice counted twice.
+    cells_written: u32,
+    // No character on the surface is above this layer. Batches on this layer or above it can
+    // skip the layer test of their cells.
+    top_layer: u32,
+}
+
+impl AsciiSurfaceData {
+    fn mark_changed(&mut self, bounds: AsciiBounds) {
+        self.changed = Some(match self.changed.take() {
+            Some(changed) => changed.union(&bounds),
+            None => bounds,
+        });
+    }
 }
 
 impl Default for AsciiSurface {
@@ -262,99 +572,400 @@ impl Default for AsciiSurface {
         Self {
             width: 0,
             height: 0,
-            data: Arc::new(Mutex::new(Vec::new())),
+            time: 0.0,
+            data: Arc::new(Mutex::new(AsciiSurfaceData::default())),
         }
     }
 }
 
 impl AsciiSurface {
     pub fn new(width: u32, height: u32) -> Self {
-        let data = vec![AsciiCharacter::default(); (width * height) as usize];
+        let characters = vec![AsciiCharacter::default(); (width * height) as usize];
         Self {
             width,
             height,
-            data: Arc::new(Mutex::new(data)),
+            time: 0.0,
+            data: Arc::new(Mutex::new(AsciiSurfaceData { characters, changed: None, meta: Vec::new(), cells_written: 0, top_layer: 0 })),
         }
     }
 
+    // The time in seconds the surface is drawn at, used by things that animate.
+    pub fn with_time(mut self, time: f32) -> Self {
+        self.time = time;
+        self
+    }
+
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
     pub fn set_character(&self, x: i32, y: i32, character: AsciiCharacter) {
         let Ok(mut data) = self.data.lock() else {
             return;
         };
-        let Some(index) = self.calc_index(x, y) else {
+        self.write(&mut data, x, y, character, 0);
+    }
+
+    // Sets every character in one go, the same as calling `set_character` for each of them.
+    pub fn set_characters(&self, characters: impl IntoIterator<Item = (i32, i32, AsciiCharacter)>) {
+        self.set_characters_with_meta(characters, 0);
+    }
+
+    // The same as `set_characters`, where every cell that is drawn gets `meta` as its metadata.
+    pub fn set_characters_with_meta(&self, characters: impl IntoIterator<Item = (i32, i32, AsciiCharacter)>, meta: u16) {
+        let Ok(mut data) = self.data.lock() else {
+            return;
+        };
+        for (x, y, character) in characters {
+            self.write(&mut data, x, y, character, meta);
+        }
+    }
+
+    // Overwrites the cells of the diff, ignoring layers. Made for systems that work out which
+    // cells changed since their last frame themselves. The metadata of the cells is kept.
+    pub fn apply_diff(&self, diff: &[(i32, i32, AsciiCharacter)]) {
+        let Ok(mut data) = self.data.lock() else {
             return;
         };
-        if index < data.len() {
-            let data = &mut data[index];
-            let layer_test = match (&character, &data) {
-                (
-                    AsciiCharacter::Set {
-                        index: _,
-                        text_color: _,
-                        background_color: _,
-                        layer: input_layer,
-                    },
-                    AsciiCharacter::Set {
-                        index: _,
-                        text_color: _,
-                        background_color: _,
-                        layer: data_layer,
-                    },
-                ) => input_layer >= data_layer,
-                (
-                    AsciiCharacter::Set {
-                        index: _,
-                        text_color: _,
-                        background_color: _,
-                        layer: _,
-                    },
-                    AsciiCharacter::Unset,
-                ) => true,
-                (
-                    AsciiCharacter::Unset,
-                    AsciiCharacter::Set {
-                        index: _,
-                        text_color: _,
-                        background_color: _,
-                        layer: _,
-                    },
-                ) => true,
-                (AsciiCharacter::Unset, AsciiCharacter::Unset) => false,
+        for (x, y, character) in diff {
+            let Some(current) = self.calc_index(*x, *y).and_then(|index| data.characters.get_mut(index)) else {
+                continue;
             };
+            *current = *character;
+            data.mark_changed(AsciiBounds::new(*x, *y, 1, 1, 0));
+            data.cells_written += 1;
+            data.top_layer = data.top_layer.max(layer_of(character));
+        }
+    }
+
+    // Writes the cells of a batch, which were all drawn on `layer`. The region that changed is
+    // marked once for all of them.
+    fn write_batch(&self, cells: &[(i32, i32, AsciiCharacter, u16)], layer: u32) {
+        if cells.is_empty() {
+            return;
+        }
+        let Ok(mut data) = self.data.lock() else {
+            return;
+        };
+        let data = &mut *data;
+        let is_on_top = layer >= data.top_layer;
 
-            if layer_test {
-                *data = character;
+        let (mut min, mut max) = (IVec2::MAX, IVec2::MIN);
+        for (x, y, character, meta) in cells {
+            let Some(index) = self.calc_index(*x, *y) else {
+                continue;
+            };
+            let Some(current) = data.characters.get_mut(index) else {
+                continue;
+            };
+            let passes = match (is_on_top, character, &*current) {
+                (true, AsciiCharacter::Set { .. }, _) => true,
+                _ => layer_test(character, current),
+            };
+            if !passes {
+                continue;
             }
+            *current = *character;
+            data.cells_written += 1;
+            // Like `write`, the metadata is only kept once a cell with metadata is drawn.
+            if *meta != 0 && data.meta.is_empty() {
+                data.meta = vec![0; data.characters.len()];
+            }
+            if let Some(current) = data.meta.get_mut(index) {
+                *current = *meta;
+            }
+            min = min.min(IVec2::new(*x, *y));
+            max = max.max(IVec2::new(*x, *y));
+        }
+
+        if min.x <= max.x {
+            let size = (max - min).as_uvec2() + 1;
+            data.mark_changed(AsciiBounds::new(min.x, min.y, size.x, size.y, 0));
+            data.top_layer = data.top_layer.max(layer);
+        }
+    }
+
+    fn write(&self, data: &mut AsciiSurfaceData, x: i32, y: i32, character: AsciiCharacter, meta: u16) {
+        let Some(index) = self.calc_index(x, y) else {
+            return;
+        };
+        let Some(current) = data.characters.get_mut(index) else {
+            return;
+        };
+        if !layer_test(&character, current) {
+            return;
+        }
+        *current = character;
+        data.mark_changed(AsciiBounds::new(x, y, 1, 1, 0));
+        data.cells_written += 1;
+        data.top_layer = data.top_layer.max(layer_of(&character));
+
+        // A cell without metadata that is drawn over one with metadata clears it.
+        if meta != 0 && data.meta.is_empty() {
+            data.meta = vec![0; data.characters.len()];
         }
+        if let Some(current) = data.meta.get_mut(index) {
+            *current = meta;
+        }
+    }
+
+    // The metadata of the global cell, or `None` when the cell was drawn without any.
+    pub fn meta_at(&self, x: i32, y: i32) -> Option<u16> {
+        let index = self.calc_index(x, y)?;
+        let data = self.data.lock().expect("Error while reading surface: data is poisoned.");
+        data.meta.get(index).copied().filter(|meta| *meta != 0)
+    }
+
+    // A copy of the metadata of every cell, row by row, or an empty list when no cell has any.
+    pub fn meta(&self) -> Vec<u16> {
+        self.data
+            .lock()
+            .expect("Error while reading surface: data is poisoned.")
+            .meta
+            .clone()
     }
 
     fn calc_index(&self, x: i32, y: i32) -> Option<usize> {
         if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
-            return None;
+            None
         } else {
             Some((x as u32 + (y as u32 * self.width)) as usize)
         }
     }
 
+    // The smallest bounds that hold every cell written since the surface was made, or `None` when
+    // nothing was written. The cells outside of it are all unset.
+    pub fn changed_region(&self) -> Option<AsciiBounds> {
+        self.data
+            .lock()
+            .expect("Error while reading surface: data is poisoned.")
+            .changed
+            .clone()
+    }
+
+    // How many cells were drawn on the surface since it was made. Cells that were drawn more than
+    // once are counted every time, cells a layer kept from being drawn are not counted.
+    pub fn cells_written(&self) -> u32 {
+        self.data
+            .lock()
+            .expect("Error while reading surface: data is poisoned.")
+            .cells_written
+    }
+
     pub fn as_byte_vec(&self) -> Vec<u8> {
         let result = self
             .data
             .lock()
             .expect("Error while writing surface: data is poisoned.")
+            .characters
             .iter()
-            .map(|value| value.into_u8())
-            .flatten()
+            .flat_map(|value| value.into_u8())
             .collect();
         result
     }
     
+    // The rgb colors of every character, two texels per character. See `AsciiCharacter::into_rgb_u8`.
+    pub fn as_rgb_byte_vec(&self) -> Vec<u8> {
+        self
+            .data
+            .lock()
+            .expect("Error while writing surface: data is poisoned.")
+            .characters
+            .iter()
+            .flat_map(|value| value.into_rgb_u8())
+            .collect()
+    }
+
+    // The same as `as_byte_vec`, for only the cells inside of the global bounds, row by row.
+    pub fn region_byte_vec(&self, bounds: &AsciiBounds) -> Vec<u8> {
+        self.region(bounds, |value| value.into_u8().to_vec())
+    }
+
+    // The same as `as_rgb_byte_vec`, for only the cells inside of the global bounds, row by row.
+    pub fn region_rgb_byte_vec(&self, bounds: &AsciiBounds) -> Vec<u8> {
+        self.region(bounds, |value| value.into_rgb_u8().to_vec())
+    }
+
+    fn region(&self, bounds: &AsciiBounds, bytes: impl Fn(&AsciiCharacter) -> Vec<u8>) -> Vec<u8> {
+        let region = bounds.intersection(&AsciiBounds::new(0, 0, self.width, self.height, 0));
+        let data = self.data.lock().expect("Error while reading surface: data is poisoned.");
+        let mut result = Vec::new();
+        for y in region.y..region.bottom() {
+            let start = self.calc_index(region.x, y).unwrap_or(0);
+            for value in &data.characters[start..start + region.width as usize] {
+                result.extend(bytes(value));
+            }
+        }
+        result
+    }
+
+    // Resets every character on the surface to unset. The size of the surface stays the same.
     pub fn clear(&self) {
-        self.data.lock().expect("Error while clearing surface: data is poisoned.").clear();
+        self.fill(AsciiCharacter::Unset);
+    }
+
+    // Overwrites every character on the surface, ignoring layers.
+    pub fn fill(&self, character: AsciiCharacter) {
+        let mut data = self.data.lock().expect("Error while filling surface: data is poisoned.");
+        data.characters.clear();
+        data.characters.resize(self.len(), character);
+        data.meta.clear();
+        data.top_layer = layer_of(&character);
+        data.mark_changed(AsciiBounds::new(0, 0, self.width, self.height, 0));
+    }
+
+    // Resets the cells that were written since the surface was made or last reset, so it can be
+    // drawn on again like a new one without making one. The changed region is empty after it.
+    pub fn reset(&self) {
+        let mut data = self.data.lock().expect("Error while resetting surface: data is poisoned.");
+        let data = &mut *data;
+        if let Some(changed) = data.changed.take() {
+            let changed = changed.intersection(&AsciiBounds::new(0, 0, self.width, self.height, 0));
+            for y in changed.y..changed.bottom() {
+                let Some(start) = self.calc_index(changed.x, y) else {
+                    continue;
+                };
+                let end = start + changed.width as usize;
+                data.characters[start..end].fill(AsciiCharacter::Unset);
+                if let Some(meta) = data.meta.get_mut(start..end) {
+                    meta.fill(0);
+                }
+            }
+        }
+        data.cells_written = 0;
+        data.top_layer = 0;
+    }
+
+    // Resets the characters inside of the global bounds to unset, ignoring layers.
+    pub fn clear_region(&self, bounds: &AsciiBounds) {
+        let region = bounds.intersection(&AsciiBounds::new(0, 0, self.width, self.height, 0));
+        let mut data = self.data.lock().expect("Error while clearing surface: data is poisoned.");
+        for y in region.y..region.bottom() {
+            for x in region.x..region.right() {
+                let Some(index) = self.calc_index(x, y) else {
+                    continue;
+                };
+                if let Some(character) = data.characters.get_mut(index) {
+                    *character = AsciiCharacter::Unset;
+                }
+                if let Some(meta) = data.meta.get_mut(index) {
+                    *meta = 0;
+                }
+            }
+        }
+        data.mark_changed(region.with_layer(0));
+    }
+
+    // The cells inside of the global bounds, row by row, with the cells of `over` put on top of
+    // them. A set cell of `over` wins over a cell of this surface on the same layer or below, the
+    // same as if it was drawn on it.
+    pub fn composite_region(&self, over: &AsciiSurface, bounds: &AsciiBounds) -> Vec<AsciiCharacter> {
+        let region = bounds.intersection(&AsciiBounds::new(0, 0, self.width, self.height, 0));
+        let data = self.data.lock().expect("Error while reading surface: data is poisoned.");
+        let over_data = (!Arc::ptr_eq(&self.data, &over.data))
+            .then(|| over.data.lock().expect("Error while reading surface: data is poisoned."));
+
+        let mut result = Vec::with_capacity((region.width * region.height) as usize);
+        for y in region.y..region.bottom() {
+            for x in region.x..region.right() {
+                let Some(character) = self.calc_index(x, y).and_then(|index| data.characters.get(index)) else {
+                    continue;
+                };
+                let top = over_data
+                    .as_ref()
+                    .and_then(|over_data| over.calc_index(x, y).and_then(|index| over_data.characters.get(index)));
+                match top {
+                    Some(top @ AsciiCharacter::Set { .. }) if layer_test(top, character) => result.push(*top),
+                    _ => result.push(*character),
+                }
+            }
+        }
+        result
     }
 
     pub fn len(&self) -> usize {
         (self.width * self.height) as usize
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    // A copy of every character on the surface, row by row.
+    pub fn characters(&self) -> Vec<AsciiCharacter> {
+        self.data
+            .lock()
+            .expect("Error while reading surface: data is poisoned.")
+            .characters
+            .clone()
+    }
+}
+
+fn layer_of(character: &AsciiCharacter) -> u32 {
+    match character {
+        AsciiCharacter::Set { layer, .. } => *layer,
+        AsciiCharacter::Unset => 0,
+    }
+}
+
+// Whether a character can be drawn over the one that is there. Higher layers win, and unset
+// characters always replace and are always replaced.
+fn layer_test(character: &AsciiCharacter, current: &AsciiCharacter) -> bool {
+    match (character, current) {
+        (
+            AsciiCharacter::Set {
+                index: _,
+                text_color: _,
+                background_color: _,
+                layer: input_layer,
+                blend: _,
+                opacity: _,
+                glyph: _,
+            },
+            AsciiCharacter::Set {
+                index: _,
+                text_color: _,
+                background_color: _,
+                layer: data_layer,
+                blend: _,
+                opacity: _,
+                glyph: _,
+            },
+        ) => input_layer >= data_layer,
+        (
+            AsciiCharacter::Set {
+                index: _,
+                text_color: _,
+                background_color: _,
+                layer: _,
+                blend: _,
+                opacity: _,
+                glyph: _,
+            },
+            AsciiCharacter::Unset,
+        ) => true,
+        (
+            AsciiCharacter::Unset,
+            AsciiCharacter::Set {
+                index: _,
+                text_color: _,
+                background_color: _,
+                layer: _,
+                blend: _,
+                opacity: _,
+                glyph: _,
+            },
+        ) => true,
+        (AsciiCharacter::Unset, AsciiCharacter::Unset) => false,
+    }
 }
 
 //=============================================================================
@@ -370,21 +981,24 @@ pub struct AsciiBoxDrawer<'b> {
     title: Option<String>,
     title_alignment: HorizontalAlignment,
     title_overflow: TextOverflow,
-    border: BorderType,
+    border: BorderStyle,
 }
 
 impl<'b> AsciiBoxDrawer<'b> {
-    pub fn draw(mut self) -> Option<AsciiBuffer> {
+    // Draws the box and gives the buffer inside of its border.
+    pub fn draw(mut self) -> AsciiBuffer {
+        let mut batch = self.buffer.batch();
         for y in 0..self.buffer.bounds.height {
             for x in 0..self.buffer.bounds.width {
                 let character = self.calc_character(x, y);
-                self.buffer.set_character(x as i32, y as i32, character);
+                batch.set_character(x as i32, y as i32, character);
             }
         }
+        batch.commit();
 
         self.buffer.sub_buffer(
-            self.buffer.bounds.x + 1,
-            self.buffer.bounds.y + 1,
+            1,
+            1,
             self.buffer.bounds.width.saturating_sub(2),
             self.buffer.bounds.height.saturating_sub(2),
         )
@@ -395,13 +1009,17 @@ impl<'b> AsciiBoxDrawer<'b> {
         let character =
             self.border
                 .get_character(x, y, self.buffer.bounds.width, self.buffer.bounds.height);
+        let is_edge = x == 0 || y == 0 || x + 1 == self.buffer.bounds.width || y + 1 == self.buffer.bounds.height;
+        let border_color = self.border.color.unwrap_or(self.border_color);
+        let bg_color = if is_edge { self.border.bg_color.unwrap_or(self.bg_color) } else { self.bg_color };
         if max_title_width < 2 {
-            return (character, self.border_color, self.bg_color).into();
+            return (character, border_color, bg_color).into();
         }
 
         if let Some(title) = &self.title {
-            if y == 0 && x >= 2 && x <= self.buffer.bounds.width - 2 {
-                let title_len = title.len().min(max_title_width as usize);
+            if y == 0 && x >= 2 && x + 2 <= self.buffer.bounds.width {
+                let title = self.title_overflow.fit(title, max_title_width as usize, self.buffer.time());
+                let title_len = title.chars().count();
                 // let difference = title_len as i32 - max_title_width;
                 let x_start = match self.title_alignment {
                     HorizontalAlignment::Left => 2,
@@ -420,14 +1038,14 @@ impl<'b> AsciiBoxDrawer<'b> {
                     return (
                         c,
                         self.title_color,
-                        self.title_bg_color.unwrap_or(self.border_color),
+                        self.title_bg_color.unwrap_or(border_color),
                     )
                         .into();
                 }
             }
         }
 
-        (character, self.border_color, self.bg_color).into()
+        (character, border_color, bg_color).into()
     }
 
     pub fn title_alignment(mut self, alignment: HorizontalAlignment) -> Self {
@@ -450,8 +1068,8 @@ impl<'b> AsciiBoxDrawer<'b> {
         self
     }
 
-    pub fn border(mut self, border_type: BorderType) -> Self {
-        self.border = border_type;
+    pub fn border(mut self, border_style: impl Into<BorderStyle>) -> Self {
+        self.border = border_style.into();
         self
     }
 
@@ -484,53 +1102,114 @@ pub struct AsciiTextDrawer<'b> {
     vertical_alignment: VerticalAlignment,
     overflow: TextOverflow,
     should_wrap: bool,
+    cache: Option<&'b AsciiTextCache>,
+    // The links of the text, for text from `AsciiBuffer::markup`.
+    markup: Option<&'b AsciiMarkup>,
+    link_color: Color,
+    highlighted_link: Option<(usize, Color)>,
+    effects: TextEffects,
 }
 
 impl <'b> AsciiTextDrawer<'b> {
     pub fn draw(self) {
-        let lines: Vec<String> = if self.should_wrap {
-            let lines = textwrap::wrap(self.text.as_str(), self.buffer.bounds.width as usize);
-            lines.iter().map(|s| s.to_string()).collect()
-        } else {
-            self.text
-                .lines()
-                .map(|s| s.to_string())
-                .collect::<Vec<String>>()
+        let layout = match self.cache {
+            Some(cache) => cache.layout(
+                &self.text,
+                &self.buffer.bounds,
+                self.should_wrap,
+                self.horizontal_alignment,
+                self.vertical_alignment,
+            ),
+            None => Arc::new(AsciiTextLayout::new(
+                &self.text,
+                &self.buffer.bounds,
+                self.should_wrap,
+                self.horizontal_alignment,
+                self.vertical_alignment,
+            )),
         };
 
-        for line in 0..self.buffer.bounds.height as usize {
-            let Some(text) = lines.get(line) else { break };
-
-            let start_x = match self.horizontal_alignment {
-                HorizontalAlignment::Left => 0,
-                HorizontalAlignment::Center => {
-                    (self.buffer.bounds.width as f32 / 2.0 - text.len() as f32 / 2.0).floor() as i32
-                }
-                HorizontalAlignment::Right => {
-                    self.buffer.bounds.width as i32 - text.len() as i32 - 1
-                }
-            };
-
-            let start_y = match self.vertical_alignment {
-                VerticalAlignment::Top => 0,
-                VerticalAlignment::Center => (self.buffer.bounds.height as f32 / 2.0
-                    - lines.len() as f32 / 2.0)
-                    .floor() as i32,
-                VerticalAlignment::Bottom => {
-                    self.buffer.bounds.height as i32 - lines.len() as i32 - 1
-                }
-            };
+        let width = self.buffer.bounds.width as usize;
+        if self.effects.is_some() {
+            let cells = layout
+                .lines()
+                .flat_map(|(start, line)| {
+                    let text = self.overflow.fit(line, width, self.buffer.time());
+                    text.chars()
+                        .enumerate()
+                        .filter(|(_, c)| !c.is_whitespace())
+                        .map(|(column, c)| (start + IVec2::new(column as i32, 0), Character::from(c), false))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+            self.effects.draw(self.buffer, &cells, self.bg_color);
+        }
 
-            for column in 0..(self.buffer.bounds.width - 1) as usize {
-                if let Some(c) = text.chars().nth(column) {
-                    self.buffer.set_character(
-                        start_x + column as i32,
-                        start_y + line as i32,
-                        (c, self.text_color, self.bg_color),
-                    );
+        let mut batch = self.buffer.batch();
+        let mut search_from = 0;
+        for (start, line) in layout.lines() {
+            // Where the line starts in the text, to find the links its characters are part of.
+            let line_start = self.markup.and_then(|_| {
+                let line_start = self.text.get(search_from..)?.find(line)? + search_from;
+                search_from = line_start + line.len();
+                Some(line_start)
+            });
+            let offsets = line.char_indices().map(|(offset, _)| offset).collect::<Vec<_>>();
+
+            let text = self.overflow.fit(line, width, self.buffer.time());
+            for (column, c) in text.chars().enumerate() {
+                let link = match (self.markup, line_start) {
+                    (Some(markup), Some(line_start)) => offsets
+                        .get(column)
+                        .and_then(|offset| markup.link_at(line_start + offset)),
+                    _ => None,
+                };
+                let (x, y) = (start.x + column as i32, start.y);
+                match link {
+                    // Links are tagged with their index, counted from 1, so they can be clicked.
+                    Some(link) => {
+                        let color = match self.highlighted_link {
+                            Some((highlighted, color)) if highlighted == link => color,
+                            _ => self.link_color,
+                        };
+                        let meta = u16::try_from(link + 1).unwrap_or(u16::MAX);
+                        batch.set_character_with_meta(x, y, (c, color, self.bg_color), meta);
+                    }
+                    None => batch.set_character(x, y, (c, self.text_color, self.bg_color)),
                 }
             }
         }
+        batch.commit();
+    }
+
+    pub fn link_color(mut self, link_color: Color) -> Self {
+        self.link_color = link_color;
+        self
+    }
+
+    // Draws the text again one cell down and to the right in `color`, under the text.
+    pub fn shadow(mut self, color: Color) -> Self {
+        self.effects.shadow = Some(color);
+        self
+    }
+
+    // Gives the cells around the text a background of `color`, which should be a dim one.
+    pub fn glow(mut self, color: Color) -> Self {
+        self.effects.glow = Some(color);
+        self
+    }
+
+    // Draws the link with the index in another color, like the one under the cursor.
+    pub fn highlight_link(mut self, link: usize, color: Color) -> Self {
+        self.highlighted_link = Some((link, color));
+        self
+    }
+
+    // Reuses the layout in `cache` as long as the text, size and alignment stay the same, instead
+    // of wrapping the text again every time it is drawn.
+    pub fn cached(mut self, cache: &'b AsciiTextCache) -> Self {
+        self.cache = Some(cache);
+        self
     }
 
     pub fn bg_color(mut self, bg_color: Color) -> Self {
@@ -565,58 +1244,508 @@ impl <'b> AsciiTextDrawer<'b> {
 }
 
 //=============================================================================
-//             Border Drawer
+//             Ascii Chart Drawer
 //=============================================================================
 
-pub struct AsciiBorderDrawer<'b> {
-    buffer : &'b AsciiBuffer,
-    border_color : Color,
-    bg_color : Color,
-    border_type : BorderType,
-    top : bool,
-    bottom : bool,
-    left : bool,
-    right : bool,
+pub struct AsciiChartDrawer<'b> {
+    buffer: &'b AsciiBuffer,
+    values: Vec<f32>,
+    range: Option<(f32, f32)>,
+    color: Color,
+    bg_color: Color,
+    thresholds: Vec<(f32, Color)>,
+    bar_width: u32,
+    gap: u32,
 }
 
-impl <'b> AsciiBorderDrawer<'b> {
-    pub fn draw(self) -> Option<AsciiBuffer> {
-        let width = self.buffer.bounds.width;
-        let height = self.buffer.bounds.height;
-        
-        if self.top {
-            let character = self.border_type.top();
+impl <'b> AsciiChartDrawer<'b> {
+    pub fn draw(self) {
+        let width = self.buffer.bounds.width as i32;
+        let height = self.buffer.bounds.height as i32;
+        let (min, max) = self.range.unwrap_or_else(|| {
+            let max = self.values.iter().copied().fold(0.0, f32::max);
+            let min = self.values.iter().copied().fold(0.0, f32::min);
+            (min, max)
+        });
+
+        let mut batch = self.buffer.batch();
+        for y in 0..height {
             for x in 0..width {
-                self.buffer.set_character(x as i32, 0, (character, Color::White, Color::Black));
+                batch.set_character(x, y, (Character::Space, self.color, self.bg_color));
             }
         }
 
-        if self.bottom {
-            let character = self.border_type.bottom();
+        let step = (self.bar_width + self.gap) as i32;
+        for (index, value) in self.values.iter().enumerate() {
+            let start_x = index as i32 * step;
+            if start_x >= width {
+                break;
+            }
+
+            let fraction = if max > min { ((value - min) / (max - min)).clamp(0.0, 1.0) } else { 0.0 };
+            let bar_height = fraction * height as f32;
+            let color = self.color_for(*value);
+
+            for y in 0..height {
+                // How much of this row, counted from the bottom, the bar covers.
+                let fill = (bar_height - y as f32).clamp(0.0, 1.0);
+                let character: AsciiCharacter = if fill >= 0.75 {
+                    (Character::Space, color, color).into()
+                } else if fill >= 0.375 {
+                    (Character::HalfS, color, self.bg_color).into()
+                } else if fill >= 0.125 {
+                    (Character::ThinBorderS, color, self.bg_color).into()
+                } else {
+                    continue;
+                };
+
+                for x in start_x..(start_x + self.bar_width as i32).min(width) {
+                    batch.set_character(x, height - 1 - y, character);
+                }
+            }
+        }
+        batch.commit();
+    }
+
+    fn color_for(&self, value: f32) -> Color {
+        self.thresholds
+            .iter()
+            .filter(|(threshold, _)| value >= *threshold)
+            .max_by(|(a, _), (b, _)| a.total_cmp(b))
+            .map(|(_, color)| *color)
+            .unwrap_or(self.color)
+    }
+
+    // The values that map to the bottom and the top of the buffer. Without it, the range goes
+    // from 0 to the largest value.
+    pub fn range(mut self, min: f32, max: f32) -> Self {
+        self.range = Some((min.min(max), min.max(max)));
+        self
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn bg_color(mut self, bg_color: Color) -> Self {
+        self.bg_color = bg_color;
+        self
+    }
+
+    // Values at or above `value` are drawn in `color`. The highest threshold a value reaches wins.
+    pub fn threshold(mut self, value: f32, color: Color) -> Self {
+        self.thresholds.push((value, color));
+        self
+    }
+
+    pub fn bar_width(mut self, width: u32) -> Self {
+        self.bar_width = width.max(1);
+        self
+    }
+
+    // The number of empty columns between bars.
+    pub fn gap(mut self, gap: u32) -> Self {
+        self.gap = gap;
+        self
+    }
+}
+
+//=============================================================================
+//             Ascii Noise Drawer
+//=============================================================================
+
+pub struct AsciiNoiseDrawer<'b> {
+    buffer: &'b AsciiBuffer,
+    seed: u64,
+    density: f32,
+    charset: Vec<Character>,
+    colors: Vec<Color>,
+    bg_color: Color,
+    offset: IVec2,
+}
+
+impl <'b> AsciiNoiseDrawer<'b> {
+    pub fn draw(self) {
+        if self.charset.is_empty() || self.colors.is_empty() || self.density <= 0.0 {
+            return;
+        }
+
+        let threshold = (self.density.clamp(0.0, 1.0) as f64 * u32::MAX as f64) as u64;
+        let mut batch = self.buffer.batch();
+        for y in 0..self.buffer.bounds.height as i32 {
+            for x in 0..self.buffer.bounds.width as i32 {
+                let hash = noise_hash(self.seed, x - self.offset.x, y - self.offset.y);
+                // The low bits decide if the cell is filled, the high bits what it is filled with.
+                if hash & 0xFFFF_FFFF > threshold {
+                    continue;
+                }
+                let high = (hash >> 32) as usize;
+                let character = self.charset[high % self.charset.len()];
+                let color = self.colors[(high / self.charset.len()) % self.colors.len()];
+                batch.set_character(x, y, (character, color, self.bg_color));
+            }
+        }
+        batch.commit();
+    }
+
+    pub fn bg_color(mut self, bg_color: Color) -> Self {
+        self.bg_color = bg_color;
+        self
+    }
+
+    // Moves the noise by a number of cells, to scroll it without changing the pattern.
+    pub fn offset(mut self, x: i32, y: i32) -> Self {
+        self.offset = IVec2::new(x, y);
+        self
+    }
+}
+
+//=============================================================================
+//             Ascii Big Text Drawer
+//=============================================================================
+
+pub struct AsciiBigTextDrawer<'b> {
+    buffer: &'b AsciiBuffer,
+    font: &'b AsciiBigFont,
+    text: String,
+    text_color: Color,
+    bg_color: Color,
+    horizontal_alignment: HorizontalAlignment,
+    vertical_alignment: VerticalAlignment,
+    effects: TextEffects,
+}
+
+impl <'b> AsciiBigTextDrawer<'b> {
+    pub fn draw(self) {
+        let size = self.font.measure(&self.text).as_ivec2();
+        let (width, height) = (self.buffer.bounds.width as i32, self.buffer.bounds.height as i32);
+        let y = match self.vertical_alignment {
+            VerticalAlignment::Top => 0,
+            VerticalAlignment::Center => (height - size.y) / 2,
+            VerticalAlignment::Bottom => height - size.y,
+        };
+        // Every line is aligned on its own.
+        let offsets = self.text.lines().map(|line| {
+            let line_width = self.font.measure(line).x as i32;
+            match self.horizontal_alignment {
+                HorizontalAlignment::Left => 0,
+                HorizontalAlignment::Center => (width - line_width) / 2,
+                HorizontalAlignment::Right => width - line_width,
+            }
+        }).collect::<Vec<_>>();
+
+        let line_height = self.font.height as i32 + 1;
+        let cells = self.font.cells(&self.text).map(|(cell, c)| {
+            let x = cell.x + offsets.get((cell.y / line_height) as usize).copied().unwrap_or(0);
+            let position = IVec2::new(x, y + cell.y);
+            match c {
+                Some(c) => (position, Character::from(c), false),
+                None => (position, Character::Space, true),
+            }
+        }).collect::<Vec<_>>();
+        if self.effects.is_some() {
+            self.effects.draw(self.buffer, &cells, self.bg_color);
+        }
+        self.buffer.set_characters(cells.into_iter().map(|(position, c, is_filled)| {
+            let bg_color = if is_filled { self.text_color } else { self.bg_color };
+            (position.x, position.y, (c, self.text_color, bg_color))
+        }));
+    }
+
+    // Draws the text again one cell down and to the right in `color`, under the text.
+    pub fn shadow(mut self, color: Color) -> Self {
+        self.effects.shadow = Some(color);
+        self
+    }
+
+    // Gives the cells around the text a background of `color`, which should be a dim one.
+    pub fn glow(mut self, color: Color) -> Self {
+        self.effects.glow = Some(color);
+        self
+    }
+
+    pub fn text_color(mut self, text_color: Color) -> Self {
+        self.text_color = text_color;
+        self
+    }
+
+    // The background of the cells with a character other than `#` in the font.
+    pub fn bg_color(mut self, bg_color: Color) -> Self {
+        self.bg_color = bg_color;
+        self
+    }
+
+    pub fn horizontal_alignment(mut self, alignment: HorizontalAlignment) -> Self {
+        self.horizontal_alignment = alignment;
+        self
+    }
+
+    pub fn vertical_alignment(mut self, alignment: VerticalAlignment) -> Self {
+        self.vertical_alignment = alignment;
+        self
+    }
+}
+
+//=============================================================================
+//             Text Effects
+//=============================================================================
+
+// The drop shadow and glow of a text drawer. They are drawn on the layer of the text before the
+// text itself, so the text covers them.
+#[derive(Clone, Copy, Default)]
+struct TextEffects {
+    shadow: Option<Color>,
+    glow: Option<Color>,
+}
+
+impl TextEffects {
+    fn is_some(&self) -> bool {
+        self.shadow.is_some() || self.glow.is_some()
+    }
+
+    // The cells are the local cells of the text that are not empty, with their character and
+    // whether they are filled with the text color, like the `#` of a big font.
+    fn draw(&self, buffer: &AsciiBuffer, cells: &[(IVec2, Character, bool)], bg_color: Color) {
+        if let Some(glow) = self.glow {
+            let text: HashSet<IVec2> = cells.iter().map(|(cell, ..)| *cell).collect();
+            let halo: HashSet<IVec2> = cells
+                .iter()
+                .flat_map(|(cell, ..)| (-1..=1).flat_map(move |y| (-1..=1).map(move |x| *cell + IVec2::new(x, y))))
+                .filter(|cell| !text.contains(cell))
+                .collect();
+            buffer.set_characters(halo.into_iter().map(|cell| (cell.x, cell.y, (Character::Space, glow, glow))));
+        }
+
+        if let Some(shadow) = self.shadow {
+            // The shadow always falls on the glow, when there is one.
+            let bg_color = self.glow.unwrap_or(bg_color);
+            buffer.set_characters(cells.iter().map(|(cell, c, is_filled)| {
+                let character = match is_filled {
+                    true => (Character::Space, shadow, shadow),
+                    false => (*c, shadow, bg_color),
+                };
+                (cell.x + 1, cell.y + 1, character)
+            }));
+        }
+    }
+}
+
+//=============================================================================
+//             Ascii Text Layout
+//=============================================================================
+
+// Where every line of a text goes inside of its bounds. The lines are wrapped but not yet fitted
+// to the width, the overflow is applied when the text is drawn. Fitting never changes how many
+// characters a line takes up, so the start positions stay valid for every overflow.
+#[derive(Clone, PartialEq, Debug)]
+pub struct AsciiTextLayout {
+    text: String,
+    width: u32,
+    height: u32,
+    should_wrap: bool,
+    horizontal_alignment: HorizontalAlignment,
+    vertical_alignment: VerticalAlignment,
+    lines: Vec<(IVec2, String)>,
+}
+
+impl AsciiTextLayout {
+    pub fn new(
+        text: &str,
+        bounds: &AsciiBounds,
+        should_wrap: bool,
+        horizontal_alignment: HorizontalAlignment,
+        vertical_alignment: VerticalAlignment,
+    ) -> Self {
+        let lines: Vec<String> = if should_wrap {
+            textwrap::wrap(text, bounds.width.max(1) as usize)
+                .iter()
+                .map(|s| s.to_string())
+                .collect()
+        } else {
+            text.lines().map(|s| s.to_string()).collect()
+        };
+
+        let start_y = match vertical_alignment {
+            VerticalAlignment::Top => 0,
+            VerticalAlignment::Center => {
+                (bounds.height as f32 / 2.0 - lines.len() as f32 / 2.0).floor() as i32
+            }
+            VerticalAlignment::Bottom => bounds.height as i32 - lines.len() as i32,
+        };
+
+        let lines = lines
+            .into_iter()
+            .take(bounds.height as usize)
+            .enumerate()
+            .map(|(row, line)| {
+                let length = line.chars().count().min(bounds.width as usize);
+                let start_x = match horizontal_alignment {
+                    HorizontalAlignment::Left => 0,
+                    HorizontalAlignment::Center => {
+                        (bounds.width as f32 / 2.0 - length as f32 / 2.0).floor() as i32
+                    }
+                    HorizontalAlignment::Right => bounds.width as i32 - length as i32,
+                };
+                (IVec2::new(start_x, start_y + row as i32), line)
+            })
+            .collect();
+
+        AsciiTextLayout {
+            text: text.to_string(),
+            width: bounds.width,
+            height: bounds.height,
+            should_wrap,
+            horizontal_alignment,
+            vertical_alignment,
+            lines,
+        }
+    }
+
+    pub fn is_outdated(
+        &self,
+        text: &str,
+        bounds: &AsciiBounds,
+        should_wrap: bool,
+        horizontal_alignment: HorizontalAlignment,
+        vertical_alignment: VerticalAlignment,
+    ) -> bool {
+        self.width != bounds.width
+            || self.height != bounds.height
+            || self.should_wrap != should_wrap
+            || self.horizontal_alignment != horizontal_alignment
+            || self.vertical_alignment != vertical_alignment
+            || self.text != text
+    }
+
+    // The local position of the first character of each line, and the line itself.
+    pub fn lines(&self) -> impl Iterator<Item = (IVec2, &str)> {
+        self.lines.iter().map(|(start, line)| (*start, line.as_str()))
+    }
+}
+
+// Keeps the last layout of a text around, so it is only laid out again when something changes.
+// Store one on a component and pass it to `AsciiTextDrawer::cached` when rendering.
+#[derive(Default)]
+pub struct AsciiTextCache {
+    layout: Mutex<Option<Arc<AsciiTextLayout>>>,
+}
+
+// A clone starts out with the same layout, and is laid out again on its own from there.
+impl Clone for AsciiTextCache {
+    fn clone(&self) -> Self {
+        let layout = self.layout.lock().ok().and_then(|layout| layout.clone());
+        AsciiTextCache {
+            layout: Mutex::new(layout),
+        }
+    }
+}
+
+impl AsciiTextCache {
+    pub fn layout(
+        &self,
+        text: &str,
+        bounds: &AsciiBounds,
+        should_wrap: bool,
+        horizontal_alignment: HorizontalAlignment,
+        vertical_alignment: VerticalAlignment,
+    ) -> Arc<AsciiTextLayout> {
+        let Ok(mut layout) = self.layout.lock() else {
+            return Arc::new(AsciiTextLayout::new(text, bounds, should_wrap, horizontal_alignment, vertical_alignment));
+        };
+
+        match layout.as_ref() {
+            Some(cached) if !cached.is_outdated(text, bounds, should_wrap, horizontal_alignment, vertical_alignment) => {
+                cached.clone()
+            }
+            _ => {
+                let new = Arc::new(AsciiTextLayout::new(text, bounds, should_wrap, horizontal_alignment, vertical_alignment));
+                *layout = Some(new.clone());
+                new
+            }
+        }
+    }
+
+    pub fn invalidate(&self) {
+        if let Ok(mut layout) = self.layout.lock() {
+            *layout = None;
+        }
+    }
+}
+
+//=============================================================================
+//             Border Drawer
+//=============================================================================
+
+pub struct AsciiBorderDrawer<'b> {
+    buffer : &'b AsciiBuffer,
+    border_color : Color,
+    bg_color : Color,
+    border_style : BorderStyle,
+    top : bool,
+    bottom : bool,
+    left : bool,
+    right : bool,
+}
+
+impl <'b> AsciiBorderDrawer<'b> {
+    // Draws the edges and gives the buffer inside of them.
+    pub fn draw(self) -> AsciiBuffer {
+        let width = self.buffer.bounds.width;
+        let height = self.buffer.bounds.height;
+        // The edges are drawn at the last row and column, which a buffer without cells does not have.
+        if width == 0 || height == 0 {
+            return self.buffer.sub_buffer(0, 0, 0, 0);
+        }
+        let border_color = self.border_style.color.unwrap_or(self.border_color);
+        let bg_color = self.border_style.bg_color.unwrap_or(self.bg_color);
+        let mut batch = self.buffer.batch();
+
+        if self.top {
+            let character = self.border_style.top;
+            for x in 0..width {
+                batch.set_character(x as i32, 0, (character, border_color, bg_color));
+            }
+        }
+
+        if self.bottom {
+            let character = self.border_style.bottom;
             for x in 0..width {
-                self.buffer.set_character(x as i32, height as i32, (character, Color::White, Color::Black));
+                batch.set_character(x as i32, height as i32 - 1, (character, border_color, bg_color));
             }
         }
 
         if self.left {
-            let character = self.border_type.left();
+            let character = self.border_style.left;
             for y in 0..height {
-                self.buffer.set_character(0, y as i32, (character, Color::White, Color::Black));
+                batch.set_character(0, y as i32, (character, border_color, bg_color));
             }
         }
 
         if self.right {
-            let character = self.border_type.right();
+            let character = self.border_style.right;
             for y in 0..height {
-                self.buffer.set_character(width as i32 - 1, y as i32, (character, Color::White, Color::Black));
+                batch.set_character(width as i32 - 1, y as i32, (character, border_color, bg_color));
             }
         }
+
+        // Where two edges meet, use the corner character of the style.
+        let corners = [
+            (self.top && self.left, 0, 0, self.border_style.top_left),
+            (self.top && self.right, width as i32 - 1, 0, self.border_style.top_right),
+            (self.bottom && self.left, 0, height as i32 - 1, self.border_style.bottom_left),
+            (self.bottom && self.right, width as i32 - 1, height as i32 - 1, self.border_style.bottom_right),
+        ];
+        for (is_corner, x, y, character) in corners {
+            if is_corner {
+                batch.set_character(x, y, (character, border_color, bg_color));
+            }
+        }
+        batch.commit();
         
-        let new_width = self.buffer.bounds.width - if self.left { 1 } else { 0 } - if self.right { 1 } else { 0 };
-        let new_height = self.buffer.bounds.height - if self.top { 1 } else { 0 } - if self.bottom { 1 } else { 0 };
-        let new_x = 0 + if self.left { 1 } else { 0 };
-        let new_y = 0 + if self.top { 1 } else { 0 };
-        self.buffer.sub_buffer(new_x, new_y, new_width, new_height)
+        let new_width = self.buffer.bounds.width.saturating_sub(self.left as u32 + self.right as u32);
+        let new_height = self.buffer.bounds.height.saturating_sub(self.top as u32 + self.bottom as u32);
+        self.buffer.sub_buffer(self.left as i32, self.top as i32, new_width, new_height)
     }
 
     pub fn all(mut self) -> Self {
@@ -668,4 +1797,478 @@ impl <'b> AsciiBorderDrawer<'b> {
         self.bg_color = color;
         self
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn character_at(surface: &AsciiSurface, x: u32, y: u32) -> Option<Character> {
+        match surface.characters()[(x + y * surface.width()) as usize] {
+            AsciiCharacter::Set { index, .. } => Some(index),
+            AsciiCharacter::Unset => None,
+        }
+    }
+
+    fn buffer(surface: &AsciiSurface, width: u32, height: u32) -> AsciiBuffer {
+        AsciiBuffer::new(surface, &AsciiBounds::new(1, 1, width, height, 0), None)
+    }
+
+    #[test]
+    fn screen_values_are_parts_of_the_surface() {
+        let surface = AsciiSurface::new(80, 24);
+        let buffer = AsciiBuffer::new(&surface, &AsciiBounds::new(10, 5, 20, 10, 0), None);
+
+        let centered = buffer.center(Value::ScreenPercent(0.125), Value::Vmin(0.25));
+        assert_eq!(centered.bounds, AsciiBounds::new(15, 7, 10, 6, 1));
+        let relative = buffer.relative(1, 1, Value::ScreenPercent(0.25), 2);
+        assert_eq!((relative.bounds.x, relative.bounds.y, relative.bounds.width, relative.bounds.height), (11, 6, 20, 2));
+    }
+
+    #[test]
+    fn sub_buffer_is_cut_off_at_the_parent_edge() {
+        let surface = AsciiSurface::new(10, 10);
+        let parent = buffer(&surface, 5, 4);
+
+        let inside = parent.sub_buffer(1, 1, 2, 2);
+        assert_eq!(inside.bounds, AsciiBounds::new(2, 2, 2, 2, 1));
+
+        let past_right = parent.sub_buffer(3, 0, 5, 2);
+        assert_eq!(past_right.bounds, AsciiBounds::new(4, 1, 2, 2, 1));
+
+        let past_bottom = parent.sub_buffer(0, 2, 1, 10);
+        assert_eq!(past_bottom.bounds, AsciiBounds::new(1, 3, 1, 2, 1));
+
+        let before_top_left = parent.sub_buffer(-2, -1, 4, 3);
+        assert_eq!(before_top_left.bounds, AsciiBounds::new(1, 1, 2, 2, 1));
+
+        // An area at the exclusive edge, or fully outside, keeps no cells.
+        assert!(parent.sub_buffer(5, 0, 2, 2).bounds.is_empty());
+        assert!(parent.sub_buffer(0, 4, 2, 2).bounds.is_empty());
+        assert!(parent.sub_buffer(-3, 0, 2, 2).bounds.is_empty());
+    }
+
+    #[test]
+    fn sub_buffer_of_a_clipped_parent_draws_nothing_outside_of_it() {
+        let surface = AsciiSurface::new(10, 10);
+        let child = buffer(&surface, 3, 3).clip().sub_buffer(2, 2, 4, 4);
+        for y in 0..4 {
+            for x in 0..4 {
+                child.set_character(x, y, Character::A);
+            }
+        }
+        assert_eq!(surface.cells_written(), 1);
+        assert_eq!(character_at(&surface, 3, 3), Some(Character::A));
+        assert_eq!(character_at(&surface, 4, 3), None);
+        assert_eq!(character_at(&surface, 3, 4), None);
+    }
+
+    #[test]
+    fn border_bottom_row_is_the_last_row() {
+        for size in [1, 2] {
+            let surface = AsciiSurface::new(4, 4);
+            buffer(&surface, size, size).border(BorderStyle::SINGLE).bottom().draw();
+            for x in 0..size {
+                assert_eq!(character_at(&surface, 1 + x, size), Some(Character::BorderS));
+                // Nothing is drawn on the row below the buffer.
+                assert_eq!(character_at(&surface, 1 + x, size + 1), None);
+            }
+            assert_eq!(surface.cells_written(), size);
+        }
+    }
+
+    #[test]
+    fn border_corners_stay_inside_of_the_buffer() {
+        let surface = AsciiSurface::new(4, 4);
+        let inner = buffer(&surface, 2, 2).border(BorderStyle::SINGLE).all().draw();
+        assert_eq!(character_at(&surface, 1, 1), Some(Character::LBorderNW));
+        assert_eq!(character_at(&surface, 2, 1), Some(Character::LBorderNE));
+        assert_eq!(character_at(&surface, 1, 2), Some(Character::LBorderSW));
+        assert_eq!(character_at(&surface, 2, 2), Some(Character::LBorderSE));
+        assert_eq!(surface.changed_region(), Some(AsciiBounds::new(1, 1, 2, 2, 0)));
+        assert!(inner.bounds.is_empty());
+
+        // All four corners are the one cell, the last one drawn wins.
+        let surface = AsciiSurface::new(4, 4);
+        let inner = buffer(&surface, 1, 1).border(BorderStyle::SINGLE).all().draw();
+        assert_eq!(character_at(&surface, 1, 1), Some(Character::LBorderSE));
+        assert_eq!(surface.changed_region(), Some(AsciiBounds::new(1, 1, 1, 1, 0)));
+        assert!(inner.bounds.is_empty());
+    }
+
+    #[test]
+    fn border_of_an_empty_buffer_draws_nothing() {
+        let surface = AsciiSurface::new(4, 4);
+        let inner = buffer(&surface, 0, 3).border(BorderStyle::SINGLE).all().draw();
+        assert!(inner.bounds.is_empty());
+        assert_eq!(surface.changed_region(), None);
+    }
+
+    #[test]
+    fn text_is_drawn_up_to_the_last_cell() {
+        let surface = AsciiSurface::new(6, 6);
+        buffer(&surface, 4, 3)
+            .text("AB")
+            .horizontal_alignment(HorizontalAlignment::Right)
+            .vertical_alignment(VerticalAlignment::Bottom)
+            .draw();
+        assert_eq!(character_at(&surface, 3, 3), Some(Character::A));
+        assert_eq!(character_at(&surface, 4, 3), Some(Character::B));
+        let cells_set = surface.characters().iter().filter(|c| matches!(c, AsciiCharacter::Set { .. })).count();
+        assert_eq!(cells_set, 2);
+
+        // Text as wide as the buffer fits in it.
+        let surface = AsciiSurface::new(6, 6);
+        buffer(&surface, 2, 1).text("AB").draw();
+        assert_eq!(character_at(&surface, 2, 1), Some(Character::B));
+    }
+
+    #[test]
+    fn reset_surface_is_like_a_new_one() {
+        let surface = AsciiSurface::new(6, 6);
+        buffer(&surface, 3, 2).text("AB").draw();
+        surface.reset();
+        assert_eq!(surface.changed_region(), None);
+        assert_eq!(surface.cells_written(), 0);
+        assert!(surface.characters().iter().all(|c| matches!(c, AsciiCharacter::Unset)));
+
+        // Cells on a lower layer can be drawn again after it.
+        AsciiBuffer::new(&surface, &AsciiBounds::new(0, 0, 1, 1, 5), None).set_character(0, 0, Character::A);
+        surface.reset();
+        AsciiBuffer::new(&surface, &AsciiBounds::new(0, 0, 1, 1, 0), None).set_character(0, 0, Character::B);
+        assert_eq!(character_at(&surface, 0, 0), Some(Character::B));
+    }
+
+    #[test]
+    fn composite_region_puts_the_cells_of_the_surface_over_on_top() {
+        let under = AsciiSurface::new(4, 1);
+        let over = AsciiSurface::new(4, 1);
+        AsciiBuffer::new(&under, &AsciiBounds::new(0, 0, 4, 1, 2), None).text("AAAA").draw();
+        AsciiBuffer::new(&over, &AsciiBounds::new(0, 0, 1, 1, 3), None).set_character(0, 0, Character::B);
+        AsciiBuffer::new(&over, &AsciiBounds::new(0, 0, 1, 1, 1), None).set_character(1, 0, Character::B);
+
+        let indices = under
+            .composite_region(&over, &AsciiBounds::new(0, 0, 3, 1, 0))
+            .iter()
+            .map(|c| match c {
+                AsciiCharacter::Set { index, .. } => Some(*index),
+                AsciiCharacter::Unset => None,
+            })
+            .collect::<Vec<_>>();
+        // The cell of `over` on a lower layer is hidden, unset cells of `over` show what is under.
+        assert_eq!(indices, vec![Some(Character::B), Some(Character::A), Some(Character::A)]);
+    }
+
+    // The text on every row of the surface, with unset cells as spaces.
+    fn rows(surface: &AsciiSurface) -> Vec<String> {
+        surface
+            .characters()
+            .chunks(surface.width() as usize)
+            .map(|row| {
+                row.iter()
+                    .map(|c| match c {
+                        AsciiCharacter::Set { index, .. } => char::from(*index),
+                        AsciiCharacter::Unset => ' ',
+                    })
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    fn wrapped(text: &str, width: u32, overflow: TextOverflow) -> Vec<String> {
+        let surface = AsciiSurface::new(6, 4);
+        AsciiBuffer::new(&surface, &AsciiBounds::new(0, 0, width, 4, 0), None)
+            .text(text)
+            .overflow(overflow)
+            .wrap()
+            .draw();
+        rows(&surface)
+    }
+
+    #[test]
+    fn wrapped_text_fits_at_the_edge_widths() {
+        assert_eq!(wrapped("AB CD", 0, TextOverflow::Hidden), vec!["", "", "", ""]);
+        // Words longer than the width are broken up.
+        assert_eq!(wrapped("AB CD", 1, TextOverflow::Hidden), vec!["A", "B", "C", "D"]);
+        assert_eq!(wrapped("AB CD", 2, TextOverflow::Hidden), vec!["AB", "CD", "", ""]);
+        assert_eq!(wrapped("AB CD", 3, TextOverflow::Hidden), vec!["AB", "CD", "", ""]);
+        assert_eq!(wrapped("AB CD", 5, TextOverflow::Hidden), vec!["AB CD", "", "", ""]);
+        // Wrapped lines never overflow, so there are no elipses.
+        assert_eq!(wrapped("ABCD", 3, TextOverflow::Elipses), vec!["ABC", "D", "", ""]);
+    }
+
+    #[test]
+    fn clear_keeps_the_size_of_the_surface() {
+        let surface = AsciiSurface::new(4, 3);
+        buffer(&surface, 2, 1).text("AB").draw();
+        surface.clear();
+        assert_eq!(surface.characters().len(), 12);
+        assert_eq!(surface.as_byte_vec().len(), surface.len() * 4);
+        assert!(surface.characters().iter().all(|c| matches!(c, AsciiCharacter::Unset)));
+        assert_eq!(surface.changed_region(), Some(AsciiBounds::new(0, 0, 4, 3, 0)));
+    }
+
+    #[test]
+    fn fill_overwrites_every_cell_whatever_its_layer() {
+        let surface = AsciiSurface::new(3, 2);
+        AsciiBuffer::new(&surface, &AsciiBounds::new(0, 0, 1, 1, 9), None).set_character(0, 0, Character::A);
+        surface.fill(Character::B.into());
+        assert_eq!(rows(&surface), vec!["BBB", "BBB"]);
+    }
+
+    #[test]
+    fn clear_region_only_resets_the_cells_inside_of_it() {
+        let surface = AsciiSurface::new(4, 2);
+        surface.fill(Character::A.into());
+        surface.clear_region(&AsciiBounds::new(1, 0, 2, 1, 0));
+        assert_eq!(rows(&surface), vec!["A  A", "AAAA"]);
+
+        // Bounds that are partly off of the surface are cut off at its edges.
+        surface.clear_region(&AsciiBounds::new(-2, 1, 4, 5, 0));
+        assert_eq!(rows(&surface), vec!["A  A", "  AA"]);
+        assert_eq!(surface.characters().len(), 8);
+    }
+
+    // The character and text color of every cell of the row, from the left.
+    fn colors_of_row(surface: &AsciiSurface, y: u32) -> Vec<(Character, Color)> {
+        let width = surface.width() as usize;
+        surface.characters()[y as usize * width..(y as usize + 1) * width]
+            .iter()
+            .map(|c| match c {
+                AsciiCharacter::Set { index, text_color, .. } => (*index, *text_color),
+                AsciiCharacter::Unset => (Character::Nil, Color::Black),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn bar_chart_scales_bars_from_zero_to_the_largest_value() {
+        let surface = AsciiSurface::new(5, 2);
+        AsciiBuffer::new(&surface, &AsciiBounds::new(0, 0, 5, 2, 0), None)
+            .bar_chart(&[2.0, 1.0, 0.5])
+            .draw();
+        let top = colors_of_row(&surface, 0).into_iter().map(|(c, _)| c).collect::<Vec<_>>();
+        let bottom = colors_of_row(&surface, 1).into_iter().map(|(c, _)| c).collect::<Vec<_>>();
+        // Full cells are spaces with the color as background, a quarter of a cell is a half block.
+        assert_eq!(top, vec![Character::Space, Character::Space, Character::Space, Character::Space, Character::Space]);
+        assert_eq!(bottom, vec![Character::Space, Character::Space, Character::Space, Character::Space, Character::HalfS]);
+        let full = surface.characters()[5];
+        assert!(matches!(full, AsciiCharacter::Set { background_color: Color::White, .. }));
+        let gap = surface.characters()[6];
+        assert!(matches!(gap, AsciiCharacter::Set { background_color: Color::Black, .. }));
+        // Only the largest value reaches the top row.
+        assert!(matches!(surface.characters()[0], AsciiCharacter::Set { background_color: Color::White, .. }));
+        assert!(matches!(surface.characters()[2], AsciiCharacter::Set { background_color: Color::Black, .. }));
+    }
+
+    #[test]
+    fn chart_colors_follow_the_highest_threshold_reached() {
+        let surface = AsciiSurface::new(3, 1);
+        AsciiBuffer::new(&surface, &AsciiBounds::new(0, 0, 3, 1, 0), None)
+            .sparkline(&[1.0, 5.0, 9.0])
+            .range(0.0, 1.0)
+            .threshold(8.0, Color::Red)
+            .threshold(4.0, Color::Yellow)
+            .draw();
+        let colors = colors_of_row(&surface, 0).into_iter().map(|(_, color)| color).collect::<Vec<_>>();
+        assert_eq!(colors, vec![Color::White, Color::Yellow, Color::Red]);
+    }
+
+    #[test]
+    fn sparkline_shows_the_newest_values_that_fit() {
+        let surface = AsciiSurface::new(3, 1);
+        AsciiBuffer::new(&surface, &AsciiBounds::new(0, 0, 3, 1, 0), None)
+            .sparkline(&[1.0, 1.0, 0.0, 1.0, 0.0])
+            .draw();
+        let cells = surface.characters();
+        let is_full = |index: usize| matches!(cells[index], AsciiCharacter::Set { background_color: Color::White, .. });
+        assert_eq!((is_full(0), is_full(1), is_full(2)), (false, true, false));
+    }
+
+    #[test]
+    fn charts_of_equal_values_draw_no_bars() {
+        let surface = AsciiSurface::new(2, 1);
+        AsciiBuffer::new(&surface, &AsciiBounds::new(0, 0, 2, 1, 0), None)
+            .sparkline(&[0.0, 0.0])
+            .draw();
+        assert!(surface.characters().iter().all(|c| matches!(c, AsciiCharacter::Set { background_color: Color::Black, .. })));
+    }
+
+    fn cached_layout(cache: &AsciiTextCache, text: &str, bounds: AsciiBounds) -> Arc<AsciiTextLayout> {
+        cache.layout(text, &bounds, true, HorizontalAlignment::Left, VerticalAlignment::Top)
+    }
+
+    #[test]
+    fn text_cache_is_reused_until_the_text_or_size_changes() {
+        let cache = AsciiTextCache::default();
+        let first = cached_layout(&cache, "AB CD", AsciiBounds::new(0, 0, 3, 2, 0));
+        assert!(Arc::ptr_eq(&first, &cached_layout(&cache, "AB CD", AsciiBounds::new(0, 0, 3, 2, 0))));
+        // The layout is relative to the bounds, moving them does not lay it out again.
+        assert!(Arc::ptr_eq(&first, &cached_layout(&cache, "AB CD", AsciiBounds::new(4, 1, 3, 2, 5))));
+
+        let resized = cached_layout(&cache, "AB CD", AsciiBounds::new(0, 0, 5, 2, 0));
+        assert!(!Arc::ptr_eq(&first, &resized));
+        let changed = cached_layout(&cache, "AB CE", AsciiBounds::new(0, 0, 5, 2, 0));
+        assert!(!Arc::ptr_eq(&resized, &changed));
+        let aligned = cache.layout("AB CE", &AsciiBounds::new(0, 0, 5, 2, 0), true, HorizontalAlignment::Right, VerticalAlignment::Top);
+        assert!(!Arc::ptr_eq(&changed, &aligned));
+
+        cache.invalidate();
+        assert!(!Arc::ptr_eq(&aligned, &cache.layout("AB CE", &AsciiBounds::new(0, 0, 5, 2, 0), true, HorizontalAlignment::Right, VerticalAlignment::Top)));
+    }
+
+    #[test]
+    fn cloned_text_cache_keeps_the_layout() {
+        let cache = AsciiTextCache::default();
+        let layout = cached_layout(&cache, "AB CD", AsciiBounds::new(0, 0, 3, 2, 0));
+        let clone = cache.clone();
+        assert!(Arc::ptr_eq(&layout, &cached_layout(&clone, "AB CD", AsciiBounds::new(0, 0, 3, 2, 0))));
+
+        // From there the two are laid out on their own.
+        cached_layout(&clone, "EF", AsciiBounds::new(0, 0, 3, 2, 0));
+        assert!(Arc::ptr_eq(&layout, &cached_layout(&cache, "AB CD", AsciiBounds::new(0, 0, 3, 2, 0))));
+    }
+
+    fn noise(seed: u64, density: f32, offset: IVec2) -> Vec<Vec<(Character, Color)>> {
+        let surface = AsciiSurface::new(16, 8);
+        let charset = [Character::Zero, Character::One, Character::Hashtag];
+        AsciiBuffer::new(&surface, &AsciiBounds::new(0, 0, 16, 8, 0), None)
+            .noise_fill(seed, density, &charset, &[Color::Green, Color::LightGreen])
+            .offset(offset.x, offset.y)
+            .draw();
+        (0..8).map(|y| colors_of_row(&surface, y)).collect()
+    }
+
+    fn filled(noise: &[Vec<(Character, Color)>]) -> usize {
+        noise.iter().flatten().filter(|(character, _)| *character != Character::Nil).count()
+    }
+
+    #[test]
+    fn noise_is_the_same_for_the_same_seed() {
+        assert_eq!(noise(7, 0.5, IVec2::ZERO), noise(7, 0.5, IVec2::ZERO));
+        assert_ne!(noise(7, 0.5, IVec2::ZERO), noise(8, 0.5, IVec2::ZERO));
+        // The hash is part of the look of a game, it must not change between versions.
+        assert_eq!(noise_hash(7, 3, -2), 12523863568112583718);
+        assert_ne!(noise_hash(7, 3, -2), noise_hash(7, -2, 3));
+    }
+
+    #[test]
+    fn noise_covers_about_its_density() {
+        assert_eq!(filled(&noise(7, 0.0, IVec2::ZERO)), 0);
+        assert_eq!(filled(&noise(7, 1.0, IVec2::ZERO)), 16 * 8);
+        let half = filled(&noise(7, 0.5, IVec2::ZERO));
+        assert!((32..=96).contains(&half), "{half} of 128 cells filled");
+    }
+
+    #[test]
+    fn noise_offset_scrolls_the_same_pattern() {
+        let still = noise(7, 0.5, IVec2::ZERO);
+        let fallen = noise(7, 0.5, IVec2::new(0, 1));
+        assert_eq!(&fallen[1..], &still[..7]);
+    }
+
+    // What decides how every cell of the surface is drawn, and what the surface knows about the
+    // cells written to it.
+    type Snapshot = (Vec<Option<(Character, Color, Color, u32)>>, Vec<u16>, Option<AsciiBounds>, u32);
+
+    fn snapshot(surface: &AsciiSurface) -> Snapshot {
+        let cells = surface
+            .characters()
+            .into_iter()
+            .map(|character| match character {
+                AsciiCharacter::Set { index, text_color, background_color, layer, .. } => {
+                    Some((index, text_color, background_color, layer))
+                }
+                AsciiCharacter::Unset => None,
+            })
+            .collect();
+        (cells, surface.meta(), surface.changed_region(), surface.cells_written())
+    }
+
+    // Rectangles on different layers that overlap each other, some of them out of the surface,
+    // and two that erase. Lower layers are drawn after higher ones, so they have to lose.
+    fn overlapping_draws() -> Vec<(AsciiBounds, AsciiCharacter, u16)> {
+        let set = |c: char, color: Color| AsciiCharacter::from((c, color, Color::Black));
+        vec![
+            (AsciiBounds::new(0, 0, 8, 6, 2), set('A', Color::Red), 0),
+            (AsciiBounds::new(4, 2, 8, 6, 1), set('B', Color::Green), 3),
+            (AsciiBounds::new(2, 1, 4, 4, 3), set('C', Color::Blue), 0),
+            (AsciiBounds::new(6, 4, 6, 4, 2), set('D', Color::Yellow), 5),
+            (AsciiBounds::new(10, 6, 5, 5, 4), set('E', Color::White), 0),
+            (AsciiBounds::new(0, 7, 12, 1, 0), AsciiCharacter::Unset, 0),
+            (AsciiBounds::new(1, 1, 2, 1, 0), AsciiCharacter::Unset, 0),
+        ]
+    }
+
+    // Draws every rectangle cell by cell, then its first cell again. `batched` draws each
+    // rectangle through a `DrawBatch`, otherwise every cell is written on its own like before.
+    fn draw_overlapping(surface: &AsciiSurface, batched: bool) {
+        for (bounds, character, meta) in overlapping_draws() {
+            let buffer = AsciiBuffer::new(surface, &bounds, None);
+            let mut cells = (0..bounds.height as i32)
+                .flat_map(|y| (0..bounds.width as i32).map(move |x| (x, y, character)))
+                .collect::<Vec<_>>();
+            cells.push((0, 0, AsciiCharacter::from(('Z', Color::Red, Color::Black))));
+
+            if batched {
+                let mut batch = buffer.batch();
+                for (x, y, character) in cells {
+                    batch.set_character_with_meta(x, y, character, meta);
+                }
+                batch.commit();
+            } else {
+                for (x, y, character) in cells {
+                    if let Some(cell) = buffer.place(x, y, character) {
+                        surface.set_characters_with_meta(std::iter::once(cell), meta);
+                    }
+                }
+            }
+        }
+    }
+
+    fn overlap(fill: Option<AsciiCharacter>, batched: bool) -> Snapshot {
+        let surface = AsciiSurface::new(12, 8);
+        if let Some(fill) = fill {
+            surface.fill(fill);
+        }
+        draw_overlapping(&surface, batched);
+        snapshot(&surface)
+    }
+
+    #[test]
+    fn batched_draws_match_unbatched_draws_where_layers_overlap() {
+        let batched = overlap(None, true);
+        assert_eq!(batched, overlap(None, false));
+
+        let at = |x: usize, y: usize| batched.0[x + y * 12];
+        // The lower `B` is under `A`, and under `C` on top of both.
+        assert_eq!(at(4, 2).map(|cell| cell.0), Some(Character::C));
+        assert_eq!(at(7, 2).map(|cell| cell.0), Some(Character::A));
+        assert_eq!(at(9, 2).map(|cell| cell.0), Some(Character::B));
+        // `D` wins over `A` on the same layer, and its metadata replaces the one of `B`.
+        assert_eq!(at(7, 5).map(|cell| cell.0), Some(Character::D));
+        assert_eq!(batched.1[7 + 5 * 12], 5);
+        // The first cell drawn twice by one rectangle, and the erased cells.
+        assert_eq!(at(10, 6).map(|cell| cell.0), Some(Character::Z));
+        assert_eq!(at(2, 1), None);
+        assert_eq!(at(5, 7), None);
+    }
+
+    #[test]
+    fn batched_draws_match_unbatched_draws_over_a_filled_surface() {
+        // Everything below the fill loses, so no batch can skip the layer test.
+        let fill = AsciiCharacter::from(('.', Color::Grey, Color::Black)).with_layer(2);
+        let batched = overlap(Some(fill), true);
+        assert_eq!(batched, overlap(Some(fill), false));
+        assert_eq!(batched.0[9 + 2 * 12].map(|cell| cell.0), Some(Character::Period));
+
+        // The metadata of `B` and `D` is under the fill, so the surface keeps none.
+        let covered = overlap(Some(AsciiCharacter::from(('.', Color::Grey, Color::Black)).with_layer(9)), true);
+        assert!(covered.1.is_empty());
+
+        let top = AsciiCharacter::from(('.', Color::Grey, Color::Black)).with_layer(9);
+        assert_eq!(overlap(Some(top), true), overlap(Some(top), false));
+    }
+}
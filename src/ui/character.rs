@@ -2,25 +2,75 @@
 //             Ascii Character
 //=============================================================================
 
-use bevy::reflect::Reflect;
+use bevy::{ecs::component::Component, reflect::Reflect};
 
-#[derive(Clone, Copy)]
+use super::{encoding::{CellEncoding, EncodedCell}, palette::ColorRef};
+
+#[derive(Clone, Copy, Default)]
 pub enum AsciiCharacter {
     Set {
         index: Character,
         text_color: Color,
         background_color: Color,
         layer: u32,
+        blend: AsciiBlendMode,
+        opacity: f32,
+        // Drawn instead of `index` when it is set, for glyphs of the other font pages.
+        glyph: Option<AsciiGlyph>,
     },
+    #[default]
     Unset,
 }
 
-impl Default for AsciiCharacter {
-    fn default() -> Self {
-        AsciiCharacter::Unset
+// A glyph of one of the pages of the font atlas. Page 0 is the built-in font, where the first 128
+// glyphs are the `Character`s. The other pages are the images of the `AsciiFontPages`, each with
+// 16 by 16 glyphs, so custom tiles can be drawn next to the built-in font.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct AsciiGlyph {
+    pub page: u8,
+    pub index: u8,
+    // Draws the sprite of the `AsciiTileAtlas` with this index instead, in its own colors. The
+    // page is not used then.
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub is_tile: bool,
+}
+
+impl AsciiGlyph {
+    // The number of pages of the font atlas, the built-in one included.
+    pub const PAGE_COUNT: u8 = 4;
+
+    pub fn new(page: u8, index: u8) -> Self {
+        AsciiGlyph { page, index, is_tile: false }
+    }
+
+    // A sprite of the `AsciiTileAtlas`. Its colors are multiplied by the text color, and the
+    // background color shows where it is transparent.
+    pub fn tile(index: u8) -> Self {
+        AsciiGlyph { page: 0, index, is_tile: true }
+    }
+}
+
+impl From<Character> for AsciiGlyph {
+    fn from(character: Character) -> Self {
+        AsciiGlyph::new(0, character as u8)
     }
 }
 
+// How an overlay character is combined with the scene character underneath it. Add it to a ui
+// node to change how everything that node draws is blended.
+#[derive(Component, Default, Clone, Copy, PartialEq, Eq, Debug, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum AsciiBlendMode {
+    // The overlay character replaces the scene character.
+    #[default]
+    Replace,
+    // The scene character is kept, but drawn with the text and background color of the overlay.
+    Tint,
+    // The overlay is drawn behind the scene, only showing where the scene character is empty.
+    Behind,
+}
+
 impl AsciiCharacter {
     pub fn new(character: Character, text_color: Color, bg_color: Color) -> AsciiCharacter {
         AsciiCharacter::Set {
@@ -28,40 +78,82 @@ impl AsciiCharacter {
             text_color,
             background_color: bg_color,
             layer: 0,
+            blend: AsciiBlendMode::Replace,
+            opacity: 1.0,
+            glyph: None,
+        }
+    }
+
+    // A character that draws a glyph of any page of the font atlas.
+    pub fn from_glyph(glyph: AsciiGlyph, text_color: Color, bg_color: Color) -> AsciiCharacter {
+        AsciiCharacter::Set {
+            index: Character::Space,
+            text_color,
+            background_color: bg_color,
+            layer: 0,
+            blend: AsciiBlendMode::Replace,
+            opacity: 1.0,
+            glyph: Some(glyph),
+        }
+    }
+
+    // The glyph of the font atlas that is drawn, or `None` for unset characters.
+    pub fn glyph(&self) -> Option<AsciiGlyph> {
+        match self {
+            AsciiCharacter::Set { index, glyph, .. } => Some(glyph.unwrap_or(AsciiGlyph::from(*index))),
+            AsciiCharacter::Unset => None,
         }
     }
 
     pub fn with_layer(mut self, layer: u32) -> AsciiCharacter {
-        match &mut self {
-            AsciiCharacter::Set { layer: l, .. } => {
-                *l = layer;
-            }
-            _ => (),
+        if let AsciiCharacter::Set { layer: l, .. } = &mut self {
+            *l = layer;
         }
         self
     }
 
-    pub fn into_u8(&self) -> [u8; 4] {
+    pub fn with_blend(mut self, blend: AsciiBlendMode) -> AsciiCharacter {
+        if let AsciiCharacter::Set { blend: b, .. } = &mut self {
+            *b = blend;
+        }
+        self
+    }
+
+    pub fn with_opacity(mut self, opacity: f32) -> AsciiCharacter {
+        if let AsciiCharacter::Set { opacity: o, .. } = &mut self {
+            *o = opacity.clamp(0.0, 1.0);
+        }
+        self
+    }
+
+    // The cell as it is stored in the overlay texture, see `CellEncoding`.
+    pub fn encode(&self) -> Option<EncodedCell> {
         match self {
-            AsciiCharacter::Set {
-                index,
-                text_color,
-                background_color,
-                layer: _,
-            } => {
-                if *index as u8 > 127u8
-                    || *text_color as u8 > 15u8
-                    || *background_color as u8 > 15u8
-                {
-                    return [0, 0, 0, 0];
-                } else {
-                    return [*index as u8, *text_color as u8, *background_color as u8, 1];
-                }
-            }
-            _ => (),
+            AsciiCharacter::Set { index, text_color, background_color, blend, opacity, glyph, .. } => Some(EncodedCell {
+                glyph: glyph.unwrap_or(AsciiGlyph::from(*index)),
+                text_color: text_color.index(),
+                background_color: background_color.index(),
+                blend: *blend,
+                transparency: EncodedCell::transparency_of(*opacity),
+            }),
+            AsciiCharacter::Unset => None,
         }
+    }
 
-        [0, 0, 0, 0]
+    pub fn into_u8(&self) -> [u8; 4] {
+        match self.encode() {
+            Some(cell) => CellEncoding::encode(&cell),
+            None => CellEncoding::UNSET,
+        }
+    }
+
+    // The exact text and background color of `Color::Rgb` cells, for the true color overlay. See
+    // `CellEncoding::encode_rgb`.
+    pub fn into_rgb_u8(&self) -> [u8; 8] {
+        match self {
+            AsciiCharacter::Set { text_color, background_color, .. } => CellEncoding::encode_rgb(text_color, background_color),
+            AsciiCharacter::Unset => [0; 8],
+        }
     }
 }
 
@@ -83,6 +175,12 @@ impl From<(Character, Color, Color)> for AsciiCharacter {
     }
 }
 
+impl From<(AsciiGlyph, Color, Color)> for AsciiCharacter {
+    fn from(value: (AsciiGlyph, Color, Color)) -> Self {
+        AsciiCharacter::from_glyph(value.0, value.1, value.2)
+    }
+}
+
 impl From<Color> for AsciiCharacter {
     fn from(value: Color) -> Self {
         AsciiCharacter::new(Character::Dither, value, value)
@@ -108,7 +206,8 @@ impl From<(char, Color, Color)> for AsciiCharacter {
 }
 
 #[repr(u8)]
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Reflect, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum Character {
     AT,
     A,
@@ -309,13 +408,206 @@ impl From<char> for Character {
             '?' => Character::QuestionMark,
             '_' => Character::BorderS,
             '|' => Character::Pipe,
-            _ => Character::Nil,
+            c => match glyph_fallback(c) {
+                Some(fallback) => Character::from(fallback),
+                None => Character::Nil,
+            },
         }
     }
 }
 
-#[repr(u8)]
+// The closest character the font has for characters it does not have, like accented letters that
+// can be typed on other keyboard layouts. The accent is dropped, so `é` is drawn as an `e`.
+pub fn glyph_fallback(c: char) -> Option<char> {
+    let fallback = match c {
+        '\u{C0}'..='\u{C5}' | '\u{E0}'..='\u{E5}' | '\u{100}'..='\u{105}' => 'a',
+        '\u{C6}' | '\u{E6}' => 'a',
+        '\u{C7}' | '\u{E7}' | '\u{106}'..='\u{10D}' => 'c',
+        '\u{10E}'..='\u{111}' | '\u{D0}' | '\u{F0}' => 'd',
+        '\u{C8}'..='\u{CB}' | '\u{E8}'..='\u{EB}' | '\u{112}'..='\u{11B}' => 'e',
+        '\u{11C}'..='\u{123}' => 'g',
+        '\u{124}'..='\u{127}' => 'h',
+        '\u{CC}'..='\u{CF}' | '\u{EC}'..='\u{EF}' | '\u{128}'..='\u{131}' => 'i',
+        '\u{134}' | '\u{135}' => 'j',
+        '\u{136}' | '\u{137}' => 'k',
+        '\u{139}'..='\u{142}' => 'l',
+        '\u{D1}' | '\u{F1}' | '\u{143}'..='\u{148}' => 'n',
+        '\u{D2}'..='\u{D6}' | '\u{D8}' | '\u{F2}'..='\u{F6}' | '\u{F8}' | '\u{14C}'..='\u{151}' => 'o',
+        '\u{154}'..='\u{159}' => 'r',
+        '\u{DF}' | '\u{15A}'..='\u{161}' => 's',
+        '\u{162}'..='\u{167}' => 't',
+        '\u{D9}'..='\u{DC}' | '\u{F9}'..='\u{FC}' | '\u{168}'..='\u{173}' => 'u',
+        '\u{174}' | '\u{175}' => 'w',
+        '\u{DD}' | '\u{FD}' | '\u{FF}' | '\u{176}'..='\u{178}' => 'y',
+        '\u{179}'..='\u{17E}' => 'z',
+        '\u{2018}' | '\u{2019}' | '\u{B4}' | '`' => '\'',
+        '\u{201C}' | '\u{201D}' | '\u{AB}' | '\u{BB}' => '"',
+        '\u{2013}' | '\u{2014}' => '-',
+        '\u{2026}' | '\u{B7}' => '.',
+        '\u{A1}' => '!',
+        '\u{BF}' => '?',
+        '\u{D7}' => '*',
+        '\u{A3}' | '\u{A5}' => '$',
+        _ => return None,
+    };
+    Some(fallback)
+}
+
+impl Character {
+    // Looks up a character by its index in the font.
+    pub fn from_index(index: u8) -> Option<Character> {
+        if index <= Character::QuadCorners as u8 {
+            // Safety: `Character` is `repr(u8)` and every index up to `QuadCorners` is a variant.
+            Some(unsafe { std::mem::transmute::<u8, Character>(index) })
+        } else {
+            None
+        }
+    }
+}
+
+// The closest unicode character to how the glyph looks in the font.
+impl From<Character> for char {
+    fn from(value: Character) -> Self {
+        match value {
+            Character::AT => '@',
+            Character::A => 'A',
+            Character::B => 'B',
+            Character::C => 'C',
+            Character::D => 'D',
+            Character::E => 'E',
+            Character::F => 'F',
+            Character::G => 'G',
+            Character::H => 'H',
+            Character::I => 'I',
+            Character::J => 'J',
+            Character::K => 'K',
+            Character::L => 'L',
+            Character::M => 'M',
+            Character::N => 'N',
+            Character::O => 'O',
+            Character::P => 'P',
+            Character::Q => 'Q',
+            Character::R => 'R',
+            Character::S => 'S',
+            Character::T => 'T',
+            Character::U => 'U',
+            Character::V => 'V',
+            Character::W => 'W',
+            Character::X => 'X',
+            Character::Y => 'Y',
+            Character::Z => 'Z',
+            Character::LeftBracket => '[',
+            Character::Euro => '\u{20AC}',
+            Character::RightBracket => ']',
+            Character::ArrowUp => '\u{2191}',
+            Character::ArrowLeft => '\u{2190}',
+            Character::Space => ' ',
+            Character::ExcalamationMark => '!',
+            Character::DoubleQuotes => '"',
+            Character::Hashtag => '#',
+            Character::Dollar => '$',
+            Character::Percent => '%',
+            Character::Ampersand => '&',
+            Character::Apostrophe => '\'',
+            Character::LeftParenthesis => '(',
+            Character::RightParenthesis => ')',
+            Character::Asterisk => '*',
+            Character::Plus => '+',
+            Character::Comma => ',',
+            Character::Hyphen => '-',
+            Character::Period => '.',
+            Character::ForwardSlash => '/',
+            Character::Zero => '0',
+            Character::One => '1',
+            Character::Two => '2',
+            Character::Three => '3',
+            Character::Four => '4',
+            Character::Five => '5',
+            Character::Six => '6',
+            Character::Seven => '7',
+            Character::Eight => '8',
+            Character::Nine => '9',
+            Character::Colon => ':',
+            Character::SemiColon => ';',
+            Character::LessThan => '<',
+            Character::Equal => '=',
+            Character::GreaterThan => '>',
+            Character::QuestionMark => '?',
+            Character::DashedHorizontalCenter => '\u{2504}',
+            Character::Spade => '\u{2660}',
+            Character::BorderVerticalCenter => '\u{2502}',
+            Character::BorderHorizontalCenter => '\u{2500}',
+            Character::BorderHorizontalN2 => '\u{2500}',
+            Character::BorderHorizontalN4 => '\u{2500}',
+            Character::BorderHorizontalS2 => '\u{2500}',
+            Character::BorderVerticalW2 => '\u{2502}',
+            Character::BorderVerticalE2 => '\u{2502}',
+            Character::RoundedCornerCenterNE => '\u{256E}',
+            Character::RoundedCornerCenterSW => '\u{2570}',
+            Character::RoundedCornerCenterSE => '\u{256F}',
+            Character::LBorderSW => '\u{2514}',
+            Character::DiagonalEB => '\u{2572}',
+            Character::DiagonalWB => '\u{2571}',
+            Character::LBorderNW => '\u{250C}',
+            Character::LBorderNE => '\u{2510}',
+            Character::Circle => '\u{25CB}',
+            Character::BorderHorizontalS4 => '\u{2500}',
+            Character::Heart => '\u{2665}',
+            Character::BorderVerticalW4 => '\u{2502}',
+            Character::RoundedCornerNW => '\u{256D}',
+            Character::DiagonalCross => '\u{2573}',
+            Character::Doughnut => '\u{25CE}',
+            Character::Sign => '\u{2663}',
+            Character::BorderVerticalE4 => '\u{2502}',
+            Character::Ball => '\u{25CF}',
+            Character::Cross => '\u{253C}',
+            Character::DitherW => '\u{2591}',
+            Character::Pipe => '|',
+            Character::Pi => '\u{03C0}',
+            Character::StairNE => '\u{25E5}',
+            Character::Nil => ' ',
+            Character::HalfW => '\u{258C}',
+            Character::HalfS => '\u{2584}',
+            Character::ThinBorderN => '\u{2594}',
+            Character::ThinBorderS => '_',
+            Character::BorderW => '\u{2502}',
+            Character::Dither => '\u{2592}',
+            Character::BorderE => '\u{2502}',
+            Character::DitherS => '\u{2591}',
+            Character::StairsNW => '\u{25E4}',
+            Character::DashedE => '\u{2506}',
+            Character::TBorderNSE => '\u{251C}',
+            Character::QuadSE => '\u{2597}',
+            Character::CornerNE => '\u{2510}',
+            Character::CornerWS => '\u{2514}',
+            Character::BorderS => '\u{2500}',
+            Character::CornerNW => '\u{250C}',
+            Character::TBorderNWE => '\u{2534}',
+            Character::TBorderSWE => '\u{252C}',
+            Character::TBorderNSW => '\u{2524}',
+            Character::DashedW => '\u{2506}',
+            Character::ThickBorderW => '\u{258F}',
+            Character::ThickBorderE => '\u{2595}',
+            Character::BorderN => '\u{2500}',
+            Character::ThickBorderN => '\u{2580}',
+            Character::ThickBorderS => '\u{2584}',
+            Character::LBorderSE => '\u{2518}',
+            Character::QuadSW => '\u{2596}',
+            Character::QuadNE => '\u{259D}',
+            Character::CornerSE => '\u{2518}',
+            Character::QuadNW => '\u{2598}',
+            Character::QuadCorners => '\u{259A}',
+        }
+    }
+}
+
+// The first 16 colors are named, the rest of the 256 color palette is reached with `Indexed`. The
+// palette is laid out like the 256 colors of xterm: after the named colors come a 6x6x6 color
+// cube and 24 greys. `Rgb` colors are drawn exactly on ui cameras with `AsciiUi::true_color`, and
+// as the closest palette color everywhere else. `Named` colors are looked up in the loaded
+// `AsciiPalette`s each time they are drawn.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Reflect, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum Color {
     Black,
     White,
@@ -333,4 +625,167 @@ pub enum Color {
     LightGreen,
     LightBlue,
     LightGrey,
+    Indexed(u8),
+    Rgb(u8, u8, u8),
+    Named(ColorRef),
+}
+
+impl Color {
+    pub const ALL: [Color; 16] = [
+        Color::Black,
+        Color::White,
+        Color::Red,
+        Color::Cyan,
+        Color::Violet,
+        Color::Green,
+        Color::Blue,
+        Color::Yellow,
+        Color::Orange,
+        Color::Brown,
+        Color::LightRed,
+        Color::DarkGrey,
+        Color::Grey,
+        Color::LightGreen,
+        Color::LightBlue,
+        Color::LightGrey,
+    ];
+
+    // The levels of each channel in the color cube of the palette.
+    const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+    // Every index is a color of the palette, the first 16 are returned as the named colors.
+    pub fn from_index(index: u8) -> Color {
+        Color::ALL.get(index as usize).copied().unwrap_or(Color::Indexed(index))
+    }
+
+    // The index of the color in the 256 color palette. `Rgb` colors give the closest palette color.
+    pub fn index(&self) -> u8 {
+        match self {
+            Color::Indexed(index) => *index,
+            Color::Rgb(r, g, b) => Color::nearest_index(*r as f32 / 255.0, *g as f32 / 255.0, *b as f32 / 255.0),
+            Color::Named(name) => name.color().index(),
+            named => Color::ALL.iter().position(|color| color == named).unwrap_or(0) as u8,
+        }
+    }
+
+    // The whole palette as rgba bytes, in order. This is the palette the shader draws with.
+    pub fn palette_bytes() -> Vec<u8> {
+        (0..=255u8)
+            .flat_map(|index| {
+                let [r, g, b] = Color::Indexed(index).rgb().map(|value| (value * 255.0).round() as u8);
+                [r, g, b, 255]
+            })
+            .collect()
+    }
+
+    // The rgb value of the color. The shader gets its palette from `palette_bytes`, so this is what
+    // is drawn.
+    pub fn rgb(&self) -> [f32; 3] {
+        match self {
+            Color::Black => [0.0, 0.0, 0.0],
+            Color::White => [1.0, 1.0, 1.0],
+            Color::Red => [0.533, 0.0, 0.0],
+            Color::Cyan => [0.667, 1.0, 0.933],
+            Color::Violet => [0.8, 0.267, 0.8],
+            Color::Green => [0.0, 0.8, 0.333],
+            Color::Blue => [0.0, 0.0, 0.667],
+            Color::Yellow => [0.933, 0.933, 0.467],
+            Color::Orange => [0.867, 0.533, 0.333],
+            Color::Brown => [0.4, 0.267, 0.0],
+            Color::LightRed => [1.0, 0.467, 0.467],
+            Color::DarkGrey => [0.2, 0.2, 0.2],
+            Color::Grey => [0.467, 0.467, 0.467],
+            Color::LightGreen => [0.667, 1.0, 0.4],
+            Color::LightBlue => [0.0, 0.533, 1.0],
+            Color::LightGrey => [0.733, 0.733, 0.733],
+            Color::Indexed(index @ 0..=15) => Color::ALL[*index as usize].rgb(),
+            Color::Indexed(index @ 16..=231) => {
+                let cube = index - 16;
+                [cube / 36, (cube / 6) % 6, cube % 6].map(|level| Color::CUBE_LEVELS[level as usize] as f32 / 255.0)
+            }
+            Color::Indexed(index) => {
+                let grey = (8 + (index - 232) as u32 * 10) as f32 / 255.0;
+                [grey, grey, grey]
+            }
+            Color::Rgb(r, g, b) => [*r as f32 / 255.0, *g as f32 / 255.0, *b as f32 / 255.0],
+            Color::Named(name) => name.color().rgb(),
+        }
+    }
+
+    // A color from the loaded palettes, like `Color::named("blood_red")`.
+    pub fn named(name: &str) -> Color {
+        Color::Named(ColorRef::new(name))
+    }
+
+    // The color a `Named` color is registered as right now. Every other color is returned as it is.
+    pub fn resolve(&self) -> Color {
+        match self {
+            Color::Named(name) => name.color(),
+            color => *color,
+        }
+    }
+
+    // The color with its rgb value multiplied by `light`, for shading things that are out of sight
+    // or far from a light. A light of 1 or more keeps the color as it is. Palette colors stay in
+    // the palette, so they look the same with and without true color.
+    pub fn dimmed(&self, light: f32) -> Color {
+        if light >= 1.0 {
+            return *self;
+        }
+        let [r, g, b] = self.rgb().map(|value| value * light.max(0.0));
+        match self.resolve() {
+            Color::Rgb(..) => Color::Rgb((r * 255.0).round() as u8, (g * 255.0).round() as u8, (b * 255.0).round() as u8),
+            _ => Color::nearest_indexed(r, g, b),
+        }
+    }
+
+    // Finds the palette color closest to the given rgb value.
+    pub fn nearest(r: f32, g: f32, b: f32) -> Color {
+        let distance = |color: &Color| {
+            let [cr, cg, cb] = color.rgb();
+            (cr - r).powi(2) + (cg - g).powi(2) + (cb - b).powi(2)
+        };
+        Color::ALL
+            .iter()
+            .copied()
+            .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+            .unwrap_or(Color::Black)
+    }
+
+    // Finds the color of the whole 256 color palette closest to the given rgb value.
+    pub fn nearest_indexed(r: f32, g: f32, b: f32) -> Color {
+        Color::from_index(Color::nearest_index(r, g, b))
+    }
+
+    // Only the closest color of the cube and the closest grey can win against the named colors,
+    // so not every color of the palette has to be compared. This runs for every rgb cell that is
+    // drawn.
+    fn nearest_index(r: f32, g: f32, b: f32) -> u8 {
+        let level = |value: f32| {
+            let value = value.clamp(0.0, 1.0) * 255.0;
+            (0..6u8)
+                .min_by(|a, b| {
+                    (Color::CUBE_LEVELS[*a as usize] as f32 - value).abs().total_cmp(&(Color::CUBE_LEVELS[*b as usize] as f32 - value).abs())
+                })
+                .unwrap_or(0)
+        };
+        let cube = 16 + level(r) * 36 + level(g) * 6 + level(b);
+        let grey = 232 + ((((r + g + b) / 3.0).clamp(0.0, 1.0) * 255.0 - 8.0) / 10.0).round().clamp(0.0, 23.0) as u8;
+
+        let distance = |index: &u8| {
+            let [cr, cg, cb] = Color::Indexed(*index).rgb();
+            (cr - r).powi(2) + (cg - g).powi(2) + (cb - b).powi(2)
+        };
+        (0..16u8)
+            .chain([cube, grey])
+            .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+            .unwrap_or(0)
+    }
+}
+
+impl From<bevy::render::color::Color> for Color {
+    fn from(value: bevy::render::color::Color) -> Self {
+        let [r, g, b, _] = value.as_rgba_f32();
+        Color::nearest(r, g, b)
+    }
 }
@@ -0,0 +1,53 @@
+use bevy::prelude::*;
+
+//=============================================================================
+//             Ascii Clipboard
+//=============================================================================
+
+// The text copied from the text widgets. With the `clipboard` feature it is shared with the
+// clipboard of the system, so text can be pasted into the game from other apps and back. Without
+// it, copied text can only be pasted inside of the game.
+#[derive(Resource, Default)]
+pub struct AsciiClipboard {
+    text: String,
+    #[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+    system: Option<arboard::Clipboard>,
+}
+
+impl AsciiClipboard {
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text.to_string();
+
+        #[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+        if let Some(system) = self.system() {
+            if let Err(error) = system.set_text(text) {
+                error!("Unable to copy to the system clipboard: {}", error);
+            }
+        }
+    }
+
+    // The system clipboard is read every time, as other apps can change it at any time. The text
+    // copied last in the game is used when it can not be read.
+    pub fn text(&mut self) -> String {
+        #[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+        if let Some(system) = self.system() {
+            if let Ok(text) = system.get_text() {
+                self.text = text;
+            }
+        }
+
+        self.text.clone()
+    }
+
+    // Opened on first use, so apps that never copy anything do not connect to the clipboard.
+    #[cfg(all(feature = "clipboard", not(target_arch = "wasm32")))]
+    fn system(&mut self) -> Option<&mut arboard::Clipboard> {
+        if self.system.is_none() {
+            match arboard::Clipboard::new() {
+                Ok(clipboard) => self.system = Some(clipboard),
+                Err(error) => error!("Unable to open the system clipboard: {}", error),
+            }
+        }
+        self.system.as_mut()
+    }
+}
@@ -2,12 +2,26 @@ use std::collections::VecDeque;
 
 use bevy::prelude::*;
 
-use super::{bounds::{AsciiBounds, AsciiNode}, component::AsciiComponent, position::AsciiPosition, util::Value, HorizontalAlignment, VerticalAlignment};
+use super::{
+    bounds::{AsciiBounds, AsciiNode, AsciiPinned}, component::{button::AsciiButton, AsciiComponent},
+    event::{AsciiEventContext, AsciiEventListener, AsciiEventPhase, AsciiUiEvent}, position::AsciiPosition,
+    util::{AsciiComponentButtonClicked, Value}, AsciiMarkDirtyEvent, AsciiUiRoot, AsciiUiTarget, HorizontalAlignment, Padding, VerticalAlignment,
+};
 
 pub trait AsciiUiCommandExtention<'w, 's> {
     fn ascii_ui_with_parent<'c>(&'c mut self, parent: Entity) -> AsciiUiCommands<'c, 'w, 's>;
     
     fn ascii_ui<'c>(&'c mut self) -> AsciiUiCommands<'c, 'w, 's>;
+
+    // Makes an entity that is not part of the ui, like an object in the world, the root of a ui
+    // that is drawn on `camera`. The ui under it is laid out in `bounds`, which can be moved later
+    // with `AsciiNode::set_bounds_from`.
+    fn ascii_ui_on<'c>(&'c mut self, entity: Entity, camera: Entity, bounds: AsciiBounds) -> AsciiUiCommands<'c, 'w, 's>;
+
+    // Despawns a node, like an `AsciiUiRoot`, with everything under it and redraws the ui, so
+    // nothing of it is left on the screen. Unlike `despawn_recursive`, this is fine for entities
+    // that are already gone, and for trees despawned after the ui was drawn this frame.
+    fn despawn_ascii_tree(&mut self, root: Entity);
 }
 
 impl<'w, 's> AsciiUiCommandExtention<'w, 's> for Commands<'w, 's> {
@@ -20,13 +34,40 @@ impl<'w, 's> AsciiUiCommandExtention<'w, 's> for Commands<'w, 's> {
     }
     
     fn ascii_ui<'c>(&'c mut self) -> AsciiUiCommands<'c, 'w, 's> {
-        let parent = self.spawn_empty().id();
+        let parent = self.spawn(AsciiUiRoot).id();
         AsciiUiCommands {
             commands: self,
             entity_stack : VecDeque::new(),
             current_entity: parent,
         }
     }
+
+    fn ascii_ui_on<'c>(&'c mut self, entity: Entity, camera: Entity, bounds: AsciiBounds) -> AsciiUiCommands<'c, 'w, 's> {
+        let mut node = AsciiNode::default();
+        node.set_bounds_from(&bounds);
+        self.entity(entity)
+            .insert((node, AsciiUiTarget(camera), AsciiUiRoot))
+            .add(|mut entity: EntityWorldMut| {
+                if !entity.contains::<Visibility>() {
+                    entity.insert(VisibilityBundle::default());
+                }
+            });
+        AsciiUiCommands {
+            commands: self,
+            entity_stack : VecDeque::new(),
+            current_entity: entity,
+        }
+    }
+
+    fn despawn_ascii_tree(&mut self, root: Entity) {
+        self.add(move |world: &mut World| {
+            if let Some(entity) = world.get_entity_mut(root) {
+                entity.despawn_recursive();
+            }
+            // The overlay is drawn again from nothing, which clears where the tree was.
+            world.send_event(AsciiMarkDirtyEvent);
+        });
+    }
 }
 
 pub struct AsciiUiCommands<'c, 'w, 's> {
@@ -66,16 +107,84 @@ impl<'c, 'w, 's> AsciiUiCommands<'c, 'w, 's> {
         self
     }
     
+    pub fn padded(&mut self, padding : impl Into<Padding>, component : impl AsciiComponent + Send + Sync + 'static) -> &mut Self {
+        self.push_from_pos(AsciiPosition::padded(padding), component);
+        self
+    }
+    
     pub fn fill(&mut self, component : impl AsciiComponent + Send + Sync + 'static) -> &mut Self {
         self.push_from_pos(AsciiPosition::fill(), component);
         self
     }
 
+    // Keeps the component `offset` cells in from a corner of the current node, see
+    // `AsciiPosition::Anchored`.
+    pub fn anchored(&mut self, ha : HorizontalAlignment, va : VerticalAlignment, offset : IVec2, width : impl Into<Value>, height : impl Into<Value>, component : impl AsciiComponent + Send + Sync + 'static) -> &mut Self {
+        self.push_from_pos(AsciiPosition::anchored(ha, va, offset, width, height), component);
+        self
+    }
+
+    // A child of the current node, which needs an `AsciiFlex`, that takes its share of the space.
+    pub fn grow(&mut self, grow : u32, component : impl AsciiComponent + Send + Sync + 'static) -> &mut Self {
+        self.push_from_pos(AsciiPosition::grow(grow), component);
+        self
+    }
+
+    pub fn flex(&mut self, basis : u32, grow : u32, shrink : u32, component : impl AsciiComponent + Send + Sync + 'static) -> &mut Self {
+        self.push_from_pos(AsciiPosition::flex(basis, grow, shrink), component);
+        self
+    }
+
     pub fn aligned(&mut self, width : impl Into<Value>, height : impl Into<Value>, ha : HorizontalAlignment, va : VerticalAlignment, component : impl AsciiComponent + Send + Sync + 'static) -> &mut Self {
         self.push_from_pos(AsciiPosition::aligned(width, height, ha, va), component);
         self
     }
     
+    // A button sized to fit its label, centered in the current node.
+    pub fn button(&mut self, label : &str) -> &mut Self {
+        let button = AsciiButton::from_string(label).fit_content();
+        let size = button.desired_size();
+        self.push_from_pos(
+            AsciiPosition::aligned(size.x as i32, size.y as i32, HorizontalAlignment::Center, VerticalAlignment::Center),
+            button,
+        );
+        self
+    }
+
+    // Runs the callback when the current node is clicked, or its hotkey is pressed. Clicks on the
+    // nodes below it do not count. This replaces any other `AsciiEventListener` for clicks on the
+    // node.
+    pub fn on_click(&mut self, callback : impl Fn(&mut Commands) + Send + Sync + 'static) -> &mut Self {
+        self.on::<AsciiComponentButtonClicked>(move |context, commands| {
+            if context.phase == AsciiEventPhase::Target {
+                callback(commands);
+            }
+        })
+    }
+
+    // Adds an `AsciiEventListener` for `E` to the current node. It gets the events of the node
+    // and, after them, the events of the nodes below it.
+    pub fn on<E : AsciiUiEvent>(&mut self, callback : impl Fn(&mut AsciiEventContext<E>, &mut Commands) + Send + Sync + 'static) -> &mut Self {
+        self.commands.entity(self.current_entity).insert(AsciiEventListener::new(callback));
+        self
+    }
+
+    // Keeps everything under the current node inside of its bounds.
+    pub fn clip_children(&mut self) -> &mut Self {
+        self.commands.entity(self.current_entity).add(|mut entity: EntityWorldMut| {
+            if let Some(mut node) = entity.get_mut::<AsciiNode>() {
+                node.clip_bounds = true.into();
+            }
+        });
+        self
+    }
+
+    // Lets the current node draw outside of the nodes above it that clip their children.
+    pub fn pinned(&mut self) -> &mut Self {
+        self.commands.entity(self.current_entity).insert(AsciiPinned);
+        self
+    }
+
     pub fn hidden(&mut self) -> &mut Self {
         self.commands.entity(self.current_entity).insert(Visibility::Hidden);
         self
@@ -91,6 +200,25 @@ impl<'c, 'w, 's> AsciiUiCommands<'c, 'w, 's> {
         self
     }
 
+    // The node the chain is at, to change or despawn it later.
+    pub fn id(&self) -> Entity {
+        self.current_entity
+    }
+
+    // Writes the node the chain is at to `id`, without ending the chain.
+    pub fn store_id(&mut self, id : &mut Entity) -> &mut Self {
+        *id = self.current_entity;
+        self
+    }
+
+    // Goes on under a node that already exists, like one spawned by an earlier chain. `pop` comes
+    // back to the node the chain was at.
+    pub fn with_parent(&mut self, parent : Entity) -> &mut Self {
+        self.entity_stack.push_back(self.current_entity);
+        self.current_entity = parent;
+        self
+    }
+
     pub fn pop(&mut self) -> &mut Self {
         if !self.entity_stack.is_empty() {
             self.current_entity = self.entity_stack.pop_back().unwrap();
@@ -99,14 +227,14 @@ impl<'c, 'w, 's> AsciiUiCommands<'c, 'w, 's> {
     }
     
     fn push_from_pos(&mut self, pos : AsciiPosition, component : impl AsciiComponent + Send + Sync + 'static) {
-        let parent = self.current_entity.clone();
+        let parent = self.current_entity;
         let entity = self.commands.spawn((
             pos,
             AsciiNode::default(),
             component,
             VisibilityBundle::default()
         )).id();
-        self.commands.entity(parent).add_child(entity.clone());
+        self.commands.entity(parent).add_child(entity);
         self.entity_stack.push_back(parent);
         self.current_entity = entity;
     }
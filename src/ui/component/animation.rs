@@ -0,0 +1,308 @@
+use std::io;
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    ecs::system::SystemParam,
+    prelude::*,
+    utils::BoxedFuture,
+};
+
+use crate::ui::{
+    buffer::AsciiBuffer, character::{Character, Color}, time::AsciiUiTime, AsciiMarkDirtyEvent
+};
+
+use super::{super::bounds::AsciiBounds, AsciiComponent};
+
+//=============================================================================
+//             Ascii Animation Clip
+//=============================================================================
+
+// Frames of a small block of cells, like a flickering torch or a water tile, played by an
+// `AsciiAnimatedGlyph`. Every frame has `width * height` cells row by row, and is shown for its
+// duration in seconds. Clips can be made in code and added to `Assets<AsciiAnimationClip>`, or
+// loaded from `.anim.ron` files with the `serialize` feature.
+#[derive(Asset, TypePath, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct AsciiAnimationClip {
+    pub width: u32,
+    pub height: u32,
+    pub frames: Vec<AsciiAnimationFrame>,
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub mode: AsciiAnimationMode,
+}
+
+#[derive(Clone, Debug, PartialEq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct AsciiAnimationFrame {
+    pub duration: f32,
+    pub cells: Vec<(Character, Color, Color)>,
+}
+
+// What happens when the last frame of a clip is done.
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum AsciiAnimationMode {
+    // Starts over from the first frame.
+    #[default]
+    Loop,
+    // Stays on the last frame.
+    Once,
+    // Plays the frames backwards, then forwards again.
+    PingPong,
+}
+
+impl AsciiAnimationClip {
+    pub fn new(width: u32, height: u32) -> Self {
+        AsciiAnimationClip {
+            width,
+            height,
+            frames: Vec::new(),
+            mode: AsciiAnimationMode::default(),
+        }
+    }
+
+    // A clip of a single cell with a frame for every character of the text, like `"|/-\\"`.
+    pub fn from_characters(text: &str, duration: f32, text_color: Color, bg_color: Color) -> Self {
+        text.chars().fold(AsciiAnimationClip::new(1, 1), |clip, c| {
+            clip.with_frame(duration, [(Character::from(c), text_color, bg_color)])
+        })
+    }
+
+    pub fn with_frame(mut self, duration: f32, cells: impl IntoIterator<Item = (Character, Color, Color)>) -> Self {
+        self.frames.push(AsciiAnimationFrame {
+            duration,
+            cells: cells.into_iter().collect(),
+        });
+        self
+    }
+
+    pub fn mode(mut self, mode: AsciiAnimationMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    // The order the frames are played in, before it repeats.
+    fn order(&self) -> Vec<usize> {
+        let count = self.frames.len();
+        match self.mode {
+            AsciiAnimationMode::PingPong => (0..count).chain((1..count.saturating_sub(1)).rev()).collect(),
+            _ => (0..count).collect(),
+        }
+    }
+
+    // How long it takes to play every frame once, in seconds.
+    pub fn duration(&self) -> f32 {
+        self.frames.iter().map(|frame| frame.duration.max(0.0)).sum()
+    }
+
+    // Whether a clip that plays once is done after `elapsed` seconds. Other clips never finish.
+    pub fn is_finished(&self, elapsed: f32) -> bool {
+        self.mode == AsciiAnimationMode::Once && elapsed >= self.duration()
+    }
+
+    // The frame that is shown after `elapsed` seconds, or `None` when the clip has no frames.
+    pub fn frame_at(&self, elapsed: f32) -> Option<usize> {
+        let order = self.order();
+        let cycle = order.iter().map(|index| self.frames[*index].duration.max(0.0)).sum::<f32>();
+        if cycle <= 0.0 || self.is_finished(elapsed) {
+            return order.last().copied();
+        }
+
+        let mut time = elapsed.max(0.0) % cycle;
+        for index in order.iter() {
+            time -= self.frames[*index].duration.max(0.0);
+            if time < 0.0 {
+                return Some(*index);
+            }
+        }
+        order.last().copied()
+    }
+
+    // Reads the `.anim.ron` format.
+    #[cfg(feature = "serialize")]
+    pub fn from_ron(text: &str) -> io::Result<Self> {
+        ron::from_str(text).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+    }
+}
+
+#[derive(Default)]
+pub struct AsciiAnimationClipLoader;
+
+impl AssetLoader for AsciiAnimationClipLoader {
+    type Asset = AsciiAnimationClip;
+    type Settings = ();
+    type Error = io::Error;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a Self::Settings,
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut text = String::new();
+            reader.read_to_string(&mut text).await?;
+
+            #[cfg(feature = "serialize")]
+            return AsciiAnimationClip::from_ron(&text);
+            #[cfg(not(feature = "serialize"))]
+            Err(io::Error::new(io::ErrorKind::Unsupported, "loading .anim.ron needs the serialize feature"))
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["anim.ron"]
+    }
+}
+
+//=============================================================================
+//             Ascii Animated Glyph
+//=============================================================================
+
+// Plays an `AsciiAnimationClip` in the top left corner of its bounds. The ui is only redrawn when
+// the frame changes, not every frame, and clips that are hidden do not redraw it at all.
+#[derive(Component, Reflect)]
+pub struct AsciiAnimatedGlyph {
+    clip: Handle<AsciiAnimationClip>,
+    speed: f32,
+    is_playing: bool,
+    elapsed: f32,
+    #[reflect(ignore)]
+    frame: Option<usize>,
+    // The cells of the frame that is shown, copied from the clip so it can be drawn.
+    #[reflect(ignore)]
+    cells: Vec<(Character, Color, Color)>,
+    #[reflect(ignore)]
+    width: u32,
+}
+
+impl AsciiAnimatedGlyph {
+    pub fn new(clip: Handle<AsciiAnimationClip>) -> Self {
+        AsciiAnimatedGlyph {
+            clip,
+            speed: 1.0,
+            is_playing: true,
+            elapsed: 0.0,
+            frame: None,
+            cells: Vec::new(),
+            width: 0,
+        }
+    }
+
+    pub fn speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    // Starts the clip at a later point, so glyphs with the same clip do not all move together.
+    pub fn offset(mut self, seconds: f32) -> Self {
+        self.elapsed = seconds;
+        self
+    }
+
+    pub fn paused(mut self) -> Self {
+        self.is_playing = false;
+        self
+    }
+
+    pub fn clip(&self) -> &Handle<AsciiAnimationClip> {
+        &self.clip
+    }
+
+    pub fn set_clip(&mut self, clip: Handle<AsciiAnimationClip>) {
+        self.clip = clip;
+        self.restart();
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.is_playing
+    }
+
+    pub fn play(&mut self) {
+        self.is_playing = true;
+    }
+
+    pub fn pause(&mut self) {
+        self.is_playing = false;
+    }
+
+    pub fn restart(&mut self) {
+        self.elapsed = 0.0;
+        self.frame = None;
+        self.is_playing = true;
+    }
+
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+}
+
+// Sent when a clip that plays once shows its last frame to the end.
+#[derive(Event, Reflect, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AsciiAnimationFinishedEvent {
+    pub entity: Entity,
+}
+
+//=============================================================================
+//             Component Impl
+//=============================================================================
+
+impl AsciiComponent for AsciiAnimatedGlyph {
+    type UpdateQuery<'w, 's> = (
+        Res<'w, Assets<AsciiAnimationClip>>,
+        Res<'w, AsciiUiTime>,
+        EventWriter<'w, AsciiAnimationFinishedEvent>,
+        EventWriter<'w, AsciiMarkDirtyEvent>,
+    );
+
+    fn set_up(app: &mut App) {
+        app.init_asset::<AsciiAnimationClip>()
+            .register_asset_loader(AsciiAnimationClipLoader)
+            .register_type::<AsciiAnimationMode>()
+            .add_event::<AsciiAnimationFinishedEvent>();
+    }
+
+    fn render(&self, buffer: &mut AsciiBuffer) {
+        if self.width == 0 {
+            return;
+        }
+        buffer.set_characters(self.cells.iter().enumerate().map(|(index, cell)| {
+            let x = (index as u32 % self.width) as i32;
+            let y = (index as u32 / self.width) as i32;
+            (x, y, *cell)
+        }));
+    }
+
+    fn update(
+        &mut self,
+        query: &mut <Self::UpdateQuery<'_, '_> as SystemParam>::Item<'_, '_>,
+        _bounds: &AsciiBounds,
+        entity: Entity,
+    ) {
+        let (clips, time, finished, dirty) = query;
+        // The clip can still be loading.
+        let Some(clip) = clips.get(&self.clip) else {
+            return;
+        };
+
+        if self.is_playing {
+            let was_finished = clip.is_finished(self.elapsed);
+            self.elapsed += time.delta_seconds() * self.speed;
+            if !was_finished && clip.is_finished(self.elapsed) {
+                self.is_playing = false;
+                finished.send(AsciiAnimationFinishedEvent { entity });
+            }
+        }
+
+        let frame = clip.frame_at(self.elapsed);
+        if frame == self.frame {
+            return;
+        }
+        self.frame = frame;
+        self.width = clip.width;
+        self.cells = frame
+            .map(|frame| clip.frames[frame].cells.clone())
+            .unwrap_or_default();
+        dirty.send(AsciiMarkDirtyEvent);
+    }
+}
@@ -0,0 +1,420 @@
+use bevy::{
+    ecs::system::SystemParam, prelude::*, window::PrimaryWindow
+};
+
+use crate::ui::{
+    buffer::AsciiBuffer, character::Color, clipboard::AsciiClipboard, util::AsciiCursor, AsciiMarkDirtyEvent
+};
+
+use super::{
+    super::{accessibility::{AsciiAccessible, AsciiAccessibleRole}, bounds::AsciiBounds},
+    text_input::{AsciiTextEntry, TextInputAction, TextInputState},
+    AsciiComponent,
+};
+
+//=============================================================================
+//             Ascii Autocomplete
+//=============================================================================
+
+// A text input with a list of suggestions that match what was typed, like a command palette. The
+// suggestions open under the input on the popup layer, so they are drawn over the nodes below
+// it. The arrow keys move through them, and tab or enter picks one.
+#[derive(Component, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct AsciiAutocomplete {
+    input: TextInputState,
+    suggestions: Vec<String>,
+    placeholder: String,
+    // The most suggestions that are shown at once, the rest are scrolled to.
+    max_visible: usize,
+    text_color: Color,
+    placeholder_color: Color,
+    bg_color: Color,
+    focused_bg_color: Color,
+    popup_bg_color: Color,
+    selected_text_color: Color,
+    selected_bg_color: Color,
+    // The color of the characters of a suggestion that matched the typed text.
+    match_color: Color,
+    // The suggestions that match, best first, as indices into `suggestions`.
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    matches: Vec<usize>,
+    // The index into `matches` that is picked with enter.
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    selected: Option<usize>,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    scroll: usize,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    is_open: bool,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    is_focused: bool,
+}
+
+impl AsciiAutocomplete {
+    pub fn new(suggestions: impl IntoIterator<Item = impl ToString>) -> Self {
+        let mut autocomplete = AsciiAutocomplete {
+            input: TextInputState::default(),
+            suggestions: suggestions.into_iter().map(|suggestion| suggestion.to_string()).collect(),
+            placeholder: String::new(),
+            max_visible: 6,
+            text_color: Color::White,
+            placeholder_color: Color::Grey,
+            bg_color: Color::DarkGrey,
+            focused_bg_color: Color::Black,
+            popup_bg_color: Color::DarkGrey,
+            selected_text_color: Color::Black,
+            selected_bg_color: Color::White,
+            match_color: Color::Yellow,
+            matches: Vec::new(),
+            selected: None,
+            scroll: 0,
+            is_open: false,
+            is_focused: false,
+        };
+        autocomplete.update_matches();
+        autocomplete
+    }
+
+    pub fn placeholder(mut self, placeholder: &str) -> Self {
+        self.placeholder = placeholder.to_string();
+        self
+    }
+
+    pub fn max_visible(mut self, max_visible: usize) -> Self {
+        self.max_visible = max_visible.max(1);
+        self
+    }
+
+    pub fn text_color(mut self, color: Color) -> Self {
+        self.text_color = color;
+        self
+    }
+
+    pub fn bg_color(mut self, color: Color) -> Self {
+        self.bg_color = color;
+        self
+    }
+
+    pub fn focused_bg_color(mut self, color: Color) -> Self {
+        self.focused_bg_color = color;
+        self
+    }
+
+    pub fn popup_bg_color(mut self, color: Color) -> Self {
+        self.popup_bg_color = color;
+        self
+    }
+
+    pub fn selected_text_color(mut self, color: Color) -> Self {
+        self.selected_text_color = color;
+        self
+    }
+
+    pub fn selected_bg_color(mut self, color: Color) -> Self {
+        self.selected_bg_color = color;
+        self
+    }
+
+    pub fn match_color(mut self, color: Color) -> Self {
+        self.match_color = color;
+        self
+    }
+
+    pub fn text(&self) -> &str {
+        self.input.text()
+    }
+
+    pub fn set_text(&mut self, text: &str) {
+        self.input.set_text(text);
+        self.update_matches();
+    }
+
+    pub fn suggestions(&self) -> &[String] {
+        &self.suggestions
+    }
+
+    pub fn set_suggestions(&mut self, suggestions: impl IntoIterator<Item = impl ToString>) {
+        self.suggestions = suggestions.into_iter().map(|suggestion| suggestion.to_string()).collect();
+        self.update_matches();
+    }
+
+    // The suggestions that match the text, best first.
+    pub fn matches(&self) -> impl Iterator<Item = &str> {
+        self.matches.iter().map(|index| self.suggestions[*index].as_str())
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.is_open && !self.matches.is_empty()
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.is_focused
+    }
+
+    pub fn focus(&mut self) {
+        self.is_focused = true;
+    }
+
+    fn update_matches(&mut self) {
+        let query = self.input.text();
+        let mut matches = self
+            .suggestions
+            .iter()
+            .enumerate()
+            .filter_map(|(index, suggestion)| fuzzy_match(query, suggestion).map(|(score, _)| (index, score)))
+            .collect::<Vec<_>>();
+        // Ties keep the order the suggestions were given in.
+        matches.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        self.matches = matches.into_iter().map(|(index, _)| index).collect();
+        self.selected = (!self.matches.is_empty()).then_some(0);
+        self.scroll = 0;
+    }
+
+    fn visible_rows(&self) -> usize {
+        self.matches.len().min(self.max_visible)
+    }
+
+    fn select(&mut self, selected: usize) {
+        let rows = self.visible_rows();
+        self.selected = Some(selected.min(self.matches.len().saturating_sub(1)));
+        let selected = self.selected.unwrap_or(0);
+        if selected < self.scroll {
+            self.scroll = selected;
+        } else if rows > 0 && selected >= self.scroll + rows {
+            self.scroll = selected + 1 - rows;
+        }
+    }
+
+    // Puts the suggestion in the input and closes the list.
+    fn accept(&mut self, selected: usize) -> Option<(usize, String)> {
+        let index = *self.matches.get(selected)?;
+        let text = self.suggestions[index].clone();
+        self.input.set_text(&text);
+        self.update_matches();
+        self.is_open = false;
+        Some((index, text))
+    }
+}
+
+// Matches when the characters of `query` appear in `text` in order, ignoring case. Characters
+// that follow each other or start a word score higher, as do shorter texts. Returns the score and
+// the positions of the matched characters.
+pub fn fuzzy_match(query: &str, text: &str) -> Option<(i32, Vec<usize>)> {
+    let mut positions = Vec::new();
+    let mut score = 0;
+    let mut query_chars = query.chars().flat_map(char::to_lowercase).peekable();
+    let mut previous: Option<char> = None;
+    let mut last_match: Option<usize> = None;
+
+    for (position, c) in text.chars().enumerate() {
+        let Some(wanted) = query_chars.peek() else {
+            break;
+        };
+        if c.to_lowercase().eq(std::iter::once(*wanted)) {
+            score += 1;
+            if last_match.map(|last| last + 1 == position).unwrap_or(false) {
+                score += 4;
+            }
+            if previous.map(|previous| !previous.is_alphanumeric()).unwrap_or(true) {
+                score += 6;
+            }
+            positions.push(position);
+            last_match = Some(position);
+            query_chars.next();
+        }
+        previous = Some(c);
+    }
+
+    if query_chars.peek().is_some() {
+        return None;
+    }
+    Some((score * 16 - text.chars().count() as i32, positions))
+}
+
+impl Default for AsciiAutocomplete {
+    fn default() -> Self {
+        Self::new(Vec::<String>::new())
+    }
+}
+
+//=============================================================================
+//             Autocomplete Events
+//=============================================================================
+
+// Sent when a suggestion is picked, `index` is where it is in the suggestions.
+#[derive(Event, Reflect, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AsciiAutocompleteSelectedEvent {
+    pub entity: Entity,
+    pub index: usize,
+    pub text: String,
+}
+
+// Sent when enter is pressed without a suggestion to pick, with the text as it was typed.
+#[derive(Event, Reflect, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AsciiAutocompleteSubmittedEvent {
+    pub entity: Entity,
+    pub text: String,
+}
+
+//=============================================================================
+//             Component Impl
+//=============================================================================
+
+impl AsciiComponent for AsciiAutocomplete {
+    type UpdateQuery<'w, 's> = (
+        Query<'w, 's, &'static AsciiCursor, With<PrimaryWindow>>,
+        Res<'w, ButtonInput<MouseButton>>,
+        AsciiTextEntry<'w>,
+        ResMut<'w, AsciiClipboard>,
+        EventWriter<'w, AsciiAutocompleteSelectedEvent>,
+        EventWriter<'w, AsciiAutocompleteSubmittedEvent>,
+        EventWriter<'w, AsciiMarkDirtyEvent>,
+    );
+
+    fn set_up(app: &mut App) {
+        app.add_event::<AsciiAutocompleteSelectedEvent>()
+            .add_event::<AsciiAutocompleteSubmittedEvent>()
+            .init_resource::<AsciiClipboard>();
+    }
+
+    fn render(&self, buffer: &mut AsciiBuffer) {
+        let width = buffer.bounds.width;
+        if width == 0 || buffer.bounds.height == 0 {
+            return;
+        }
+
+        let bg_color = if self.is_focused { self.focused_bg_color } else { self.bg_color };
+        if self.input.is_empty() && !self.is_focused && !self.placeholder.is_empty() {
+            let mut chars = self.placeholder.chars();
+            for x in 0..width as i32 {
+                let c = chars.next().unwrap_or(' ');
+                buffer.set_character(x, 0, (c, self.placeholder_color, bg_color));
+            }
+        } else {
+            self.input.draw(buffer, self.text_color, bg_color, self.is_focused);
+        }
+
+        if !self.is_open() {
+            return;
+        }
+
+        let rows = self.visible_rows();
+        let popup = buffer.popup(0, 1, width, rows as u32);
+        for y in 0..rows {
+            let match_index = self.scroll + y;
+            let Some(suggestion) = self.matches.get(match_index).map(|index| &self.suggestions[*index]) else {
+                break;
+            };
+            let (text_color, bg_color) = if self.selected == Some(match_index) {
+                (self.selected_text_color, self.selected_bg_color)
+            } else {
+                (self.text_color, self.popup_bg_color)
+            };
+            let matched = fuzzy_match(self.input.text(), suggestion).map(|(_, positions)| positions).unwrap_or_default();
+
+            let mut chars = suggestion.chars();
+            for x in 0..width as usize {
+                let c = chars.next().unwrap_or(' ');
+                let color = if matched.contains(&x) { self.match_color } else { text_color };
+                popup.set_character(x as i32, y as i32, (c, color, bg_color));
+            }
+        }
+
+    }
+
+    fn update(
+        &mut self,
+        query: &mut <Self::UpdateQuery<'_, '_> as SystemParam>::Item<'_, '_>,
+        bounds: &AsciiBounds,
+        entity: Entity,
+    ) {
+        let (cursor, mouse, entry, clipboard, selected, submitted, dirty) = query;
+        let was_focused = self.is_focused;
+        let was_open = self.is_open();
+        let old_selected = self.selected;
+        let mut needs_redraw = false;
+
+        let cursor = match cursor.get_single() {
+            Ok(AsciiCursor::Some { x, y }) => Some((*x as i32 - bounds.x, *y as i32 - bounds.y)),
+            _ => None,
+        };
+        let rows = if self.is_open() { self.visible_rows() as i32 } else { 0 };
+        // The row of the list under the cursor, the list starts on the row under the input.
+        let hovered_row = cursor
+            .filter(|(x, y)| *x >= 0 && *x < bounds.width as i32 && *y >= 1 && *y <= rows)
+            .map(|(_, y)| self.scroll + y as usize - 1);
+
+        if mouse.just_pressed(MouseButton::Left) {
+            if let Some(row) = hovered_row {
+                if let Some((index, text)) = self.accept(row) {
+                    selected.send(AsciiAutocompleteSelectedEvent { entity, index, text });
+                }
+                needs_redraw = true;
+            } else {
+                let on_input = cursor
+                    .map(|(x, y)| x >= 0 && y >= 0 && x < bounds.width as i32 && y < bounds.height as i32)
+                    .unwrap_or(false);
+                self.is_focused = on_input;
+                self.is_open = on_input;
+                if let (true, Some((x, _))) = (on_input, cursor) {
+                    needs_redraw |= self.input.click(x, bounds.width, false);
+                }
+            }
+        } else if let Some(row) = hovered_row {
+            if self.selected != Some(row) {
+                self.select(row);
+            }
+        }
+
+        if self.is_focused {
+            let keys = entry.keys();
+            let picked = self.selected.filter(|_| self.is_open());
+            if keys.just_pressed(KeyCode::Escape) {
+                self.is_open = false;
+            } else if keys.just_pressed(KeyCode::ArrowDown) {
+                self.is_open = true;
+                self.select(self.selected.map(|selected| selected + 1).unwrap_or(0));
+            } else if keys.just_pressed(KeyCode::ArrowUp) {
+                self.select(self.selected.map(|selected| selected.saturating_sub(1)).unwrap_or(0));
+            } else if let (Some(picked), true) = (picked, keys.any_just_pressed([KeyCode::Tab, KeyCode::Enter, KeyCode::NumpadEnter])) {
+                if let Some((index, text)) = self.accept(picked) {
+                    selected.send(AsciiAutocompleteSelectedEvent { entity, index, text });
+                }
+                needs_redraw = true;
+            } else {
+                match self.input.handle_input(entry, clipboard) {
+                    TextInputAction::Changed => {
+                        self.update_matches();
+                        self.is_open = true;
+                        needs_redraw = true;
+                    }
+                    TextInputAction::Submitted(text) => {
+                        submitted.send(AsciiAutocompleteSubmittedEvent { entity, text });
+                        self.is_open = false;
+                        needs_redraw = true;
+                    }
+                    TextInputAction::Moved => needs_redraw = true,
+                    TextInputAction::None => {}
+                }
+            }
+        } else {
+            self.is_open = false;
+        }
+
+        if needs_redraw || self.is_focused != was_focused || self.is_open() != was_open || self.selected != old_selected {
+            dirty.send(AsciiMarkDirtyEvent);
+        }
+    }
+
+    fn keyboard_focus(&self, bounds: &AsciiBounds) -> Option<IVec2> {
+        self.is_focused().then(|| IVec2::new(bounds.x, bounds.y))
+    }
+
+    fn accessibility(&self) -> Option<AsciiAccessible> {
+        Some(
+            AsciiAccessible::new(AsciiAccessibleRole::ComboBox, &self.placeholder)
+                .value(self.input.text())
+                .focused(self.is_focused),
+        )
+    }
+}
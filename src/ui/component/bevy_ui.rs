@@ -0,0 +1,207 @@
+use bevy::{
+    ecs::system::SystemParam, prelude::*, ui::UiStack, utils::HashMap, window::PrimaryWindow
+};
+
+use crate::{
+    ascii::AsciiCamera,
+    ui::{buffer::{AsciiBuffer, AsciiTextCache}, character::{Character, Color}, AsciiMarkDirtyEvent, AsciiUi, BorderStyle},
+};
+
+use super::{super::bounds::AsciiBounds, AsciiComponent, AsciiComponentPlugin};
+
+//=============================================================================
+//             Bevy Ui Bridge Plugin
+//=============================================================================
+
+// Opt-in support for showing `bevy_ui` hierarchies through the ascii overlay. Add an
+// `AsciiBevyUiBridge` node (usually with `fill`) to a camera's ascii ui to mirror the bevy ui.
+pub struct AsciiBevyUiBridgePlugin;
+
+impl Plugin for AsciiBevyUiBridgePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(AsciiComponentPlugin::<AsciiBevyUiBridge>::default())
+            .register_type::<AsciiBevyUiBridge>();
+    }
+}
+
+//=============================================================================
+//             Bevy Ui Bridge
+//=============================================================================
+
+// Rasterizes every visible bevy ui node into the bounds of this component. Background and
+// border colors are snapped to the closest palette color and text is drawn with the color of
+// its first section. Node rects are snapped to the character grid of the ascii camera.
+#[derive(Component, Reflect, Default)]
+pub struct AsciiBevyUiBridge {
+    #[reflect(ignore)]
+    nodes: Vec<BridgedNode>,
+    // One per node, so the text of nodes that did not change is not wrapped again.
+    #[reflect(ignore)]
+    text_caches: Vec<AsciiTextCache>,
+    draw_borders: bool,
+}
+
+impl AsciiBevyUiBridge {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Draws a box border around nodes that have a visible `BorderColor`.
+    pub fn with_borders(mut self) -> Self {
+        self.draw_borders = true;
+        self
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+struct BridgedNode {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+    bg_color: Option<Color>,
+    border_color: Option<Color>,
+    text: Option<(String, Color)>,
+    // The background of the node or of the closest ancestor that has one.
+    inherited_bg_color: Color,
+}
+
+//=============================================================================
+//             Component Impl
+//=============================================================================
+
+impl AsciiComponent for AsciiBevyUiBridge {
+    type UpdateQuery<'w, 's> = (
+        Query<'w, 's, &'static Window, With<PrimaryWindow>>,
+        Query<'w, 's, &'static AsciiCamera, With<AsciiUi>>,
+        Res<'w, UiStack>,
+        Query<'w, 's, (
+            &'static Node,
+            &'static GlobalTransform,
+            &'static InheritedVisibility,
+            Option<&'static BackgroundColor>,
+            Option<&'static BorderColor>,
+            Option<&'static Text>,
+            Option<&'static Parent>,
+        )>,
+        EventWriter<'w, AsciiMarkDirtyEvent>,
+    );
+
+    fn render(&self, buffer: &mut AsciiBuffer) {
+        // Nodes are stored back to front, so later nodes overwrite the earlier ones.
+        for (node, text_cache) in self.nodes.iter().zip(self.text_caches.iter()) {
+            let node_buffer = buffer.relative(node.x, node.y, node.width as i32, node.height as i32).clip();
+
+            if let Some(bg_color) = node.bg_color {
+                for y in 0..node.height as i32 {
+                    for x in 0..node.width as i32 {
+                        node_buffer.set_character(x, y, (Character::Space, Color::White, bg_color));
+                    }
+                }
+            }
+
+            if let Some(border_color) = node.border_color {
+                if node.width >= 2 && node.height >= 2 {
+                    for y in 0..node.height {
+                        for x in 0..node.width {
+                            let character = BorderStyle::SINGLE.get_character(x, y, node.width, node.height);
+                            if character != Character::Nil {
+                                node_buffer.set_character(
+                                    x as i32,
+                                    y as i32,
+                                    (character, border_color, node.inherited_bg_color),
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+
+            if let Some((text, text_color)) = &node.text {
+                node_buffer
+                    .text(text)
+                    .text_color(*text_color)
+                    .bg_color(node.inherited_bg_color)
+                    .wrap()
+                    .cached(text_cache)
+                    .draw();
+            }
+        }
+    }
+
+    fn update(
+        &mut self,
+        query: &mut <Self::UpdateQuery<'_, '_> as SystemParam>::Item<'_, '_>,
+        bounds: &AsciiBounds,
+        _entity: Entity,
+    ) {
+        let (window, cameras, stack, ui_nodes, dirty) = query;
+        let Ok(window) = window.get_single() else { return };
+        let Some(camera) = cameras.iter().find(|camera| camera.target_res().x > 0.0) else { return };
+
+        let cell_width = window.width() / camera.target_res().x * camera.ui_scale() as f32;
+        let cell_height = window.height() / camera.target_res().y * camera.ui_scale() as f32;
+        if cell_width <= 0.0 || cell_height <= 0.0 {
+            return;
+        }
+
+        let mut inherited_bg_colors: HashMap<Entity, Color> = HashMap::new();
+        let mut nodes = Vec::with_capacity(stack.uinodes.len());
+
+        // Parents always come before their children in the ui stack.
+        for entity in stack.uinodes.iter() {
+            let Ok((node, transform, visibility, bg_color, border_color, text, parent)) = ui_nodes.get(*entity) else {
+                continue;
+            };
+
+            let bg_color = bg_color.filter(|color| color.0.a() > 0.0).map(|color| Color::from(color.0));
+            let parent_bg_color = parent.and_then(|parent| inherited_bg_colors.get(&parent.get()).copied());
+            let inherited_bg_color = bg_color.or(parent_bg_color).unwrap_or(Color::Black);
+            inherited_bg_colors.insert(*entity, inherited_bg_color);
+
+            if !visibility.get() {
+                continue;
+            }
+
+            let rect = node.logical_rect(transform);
+            let x = (rect.min.x / cell_width).round() as i32 - bounds.x;
+            let y = (rect.min.y / cell_height).round() as i32 - bounds.y;
+            let width = ((rect.max.x / cell_width).round() as i32 - bounds.x - x).max(0) as u32;
+            let height = ((rect.max.y / cell_height).round() as i32 - bounds.y - y).max(0) as u32;
+            if width == 0 || height == 0 {
+                continue;
+            }
+
+            let border_color = border_color
+                .filter(|color| self.draw_borders && color.0.a() > 0.0)
+                .map(|color| Color::from(color.0));
+
+            let text = text.and_then(|text| {
+                let value: String = text.sections.iter().map(|section| section.value.as_str()).collect();
+                let color = text.sections.first().map(|section| Color::from(section.style.color))?;
+                (!value.trim().is_empty()).then_some((value, color))
+            });
+
+            if bg_color.is_none() && border_color.is_none() && text.is_none() {
+                continue;
+            }
+
+            nodes.push(BridgedNode {
+                x,
+                y,
+                width,
+                height,
+                bg_color,
+                border_color,
+                text,
+                inherited_bg_color,
+            });
+        }
+
+        if nodes != self.nodes {
+            self.text_caches.resize_with(nodes.len(), AsciiTextCache::default);
+            self.nodes = nodes;
+            dirty.send(AsciiMarkDirtyEvent);
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::ui::{
+    big_text::{AsciiBigFont, AsciiBigFontLoader}, buffer::AsciiBuffer, character::Color, AsciiMarkDirtyEvent,
+    HorizontalAlignment, VerticalAlignment,
+};
+
+use super::{super::bounds::AsciiBounds, AsciiComponent};
+
+//=============================================================================
+//             Ascii Big Text
+//=============================================================================
+
+// Text drawn several cells tall, for title screens and banners. It uses the built-in 5 by 5 font
+// unless another one is picked.
+#[derive(Component, Reflect)]
+pub struct AsciiBigText {
+    text: String,
+    text_color: Color,
+    bg_color: Color,
+    horizontal_alignment: HorizontalAlignment,
+    vertical_alignment: VerticalAlignment,
+    shadow: Option<Color>,
+    glow: Option<Color>,
+    // A font from the assets, copied into `font` once it is loaded. The built-in font is used
+    // until then.
+    handle: Option<Handle<AsciiBigFont>>,
+    #[reflect(ignore)]
+    font: AsciiBigFont,
+    #[reflect(ignore)]
+    is_font_loaded: bool,
+}
+
+impl AsciiBigText {
+    pub fn new(text: &str) -> Self {
+        AsciiBigText {
+            text: text.to_string(),
+            text_color: Color::White,
+            bg_color: Color::Black,
+            horizontal_alignment: HorizontalAlignment::Left,
+            vertical_alignment: VerticalAlignment::Top,
+            shadow: None,
+            glow: None,
+            handle: None,
+            font: AsciiBigFont::large(),
+            is_font_loaded: false,
+        }
+    }
+
+    // Uses the built-in 3 by 3 font.
+    pub fn small(mut self) -> Self {
+        self.font = AsciiBigFont::small();
+        self.handle = None;
+        self
+    }
+
+    pub fn font(mut self, font: Handle<AsciiBigFont>) -> Self {
+        self.handle = Some(font);
+        self.is_font_loaded = false;
+        self
+    }
+
+    pub fn text_color(mut self, text_color: Color) -> Self {
+        self.text_color = text_color;
+        self
+    }
+
+    pub fn bg_color(mut self, bg_color: Color) -> Self {
+        self.bg_color = bg_color;
+        self
+    }
+
+    pub fn horizontal_alignment(mut self, alignment: HorizontalAlignment) -> Self {
+        self.horizontal_alignment = alignment;
+        self
+    }
+
+    pub fn vertical_alignment(mut self, alignment: VerticalAlignment) -> Self {
+        self.vertical_alignment = alignment;
+        self
+    }
+
+    // A drop shadow in `color`, one cell down and to the right.
+    pub fn shadow(mut self, color: Color) -> Self {
+        self.shadow = Some(color);
+        self
+    }
+
+    // A dim background of `color` around the text.
+    pub fn glow(mut self, color: Color) -> Self {
+        self.glow = Some(color);
+        self
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text.to_string();
+    }
+
+    // The number of cells the text takes up with the current font.
+    pub fn size(&self) -> UVec2 {
+        self.font.measure(&self.text)
+    }
+}
+
+impl Default for AsciiBigText {
+    fn default() -> Self {
+        Self::new("")
+    }
+}
+
+//=============================================================================
+//             Component Impl
+//=============================================================================
+
+impl AsciiComponent for AsciiBigText {
+    type UpdateQuery<'w, 's> = (
+        Res<'w, Assets<AsciiBigFont>>,
+        EventWriter<'w, AsciiMarkDirtyEvent>,
+    );
+
+    fn set_up(app: &mut App) {
+        app.init_asset::<AsciiBigFont>()
+            .register_asset_loader(AsciiBigFontLoader);
+    }
+
+    fn measure(&self) -> Option<UVec2> {
+        Some(self.size())
+    }
+
+    fn render(&self, buffer: &mut AsciiBuffer) {
+        let mut drawer = buffer
+            .big_text(&self.text, &self.font)
+            .text_color(self.text_color)
+            .bg_color(self.bg_color)
+            .horizontal_alignment(self.horizontal_alignment)
+            .vertical_alignment(self.vertical_alignment);
+        if let Some(shadow) = self.shadow {
+            drawer = drawer.shadow(shadow);
+        }
+        if let Some(glow) = self.glow {
+            drawer = drawer.glow(glow);
+        }
+        drawer.draw();
+    }
+
+    fn update(
+        &mut self,
+        query: &mut <Self::UpdateQuery<'_, '_> as SystemParam>::Item<'_, '_>,
+        _bounds: &AsciiBounds,
+        _entity: Entity,
+    ) {
+        let (fonts, dirty) = query;
+        // The font can still be loading, or be changed after it is loaded.
+        let Some(font) = self.handle.as_ref().and_then(|handle| fonts.get(handle)) else {
+            return;
+        };
+        if self.is_font_loaded && !fonts.is_changed() {
+            return;
+        }
+        self.is_font_loaded = true;
+        if *font != self.font {
+            self.font = font.clone();
+            dirty.send(AsciiMarkDirtyEvent);
+        }
+    }
+}
@@ -2,11 +2,12 @@ use bevy::{
     ecs::system::SystemParam, prelude::*, window::PrimaryWindow
 };
 
-use crate::{prelude::{HorizontalAlignment, VerticalAlignment}, ui::{buffer::AsciiBuffer, character::Color, util::{AsciiComponentButtonClicked, AsciiComponentHoverEnteredEvent, AsciiComponentHoverExitedEvent, AsciiCursor}, AsciiMarkDirtyEvent, BorderType}};
+use crate::{prelude::{HorizontalAlignment, VerticalAlignment}, ui::{buffer::{AsciiBuffer, AsciiTextCache}, character::Color, position::AsciiDesiredSize, time::AsciiUiTime, util::{AsciiComponentButtonClicked, AsciiHotkey, AsciiComponentHoverEnteredEvent, AsciiComponentHoverExitedEvent, AsciiCursor}, AsciiMarkDirtyEvent, BorderType}};
 
-use super::{super::bounds::AsciiBounds, AsciiComponent};
+use super::{super::{accessibility::{AsciiAccessible, AsciiAccessibleRole}, bounds::AsciiBounds}, AsciiComponent};
 
 #[derive(Component, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct AsciiButton {
     bg_color: Color,
     border_color: Color,
@@ -14,8 +15,28 @@ pub struct AsciiButton {
     hover_bg_color: Color,
     hover_border_color: Color,
     hover_text_color: Color,
+    disabled_bg_color: Color,
+    disabled_border_color: Color,
+    disabled_text_color: Color,
+    #[cfg_attr(feature = "serialize", serde(skip))]
     is_hovering: bool,
+    is_disabled: bool,
     button_text: String,
+    left_icon: Option<char>,
+    right_icon: Option<char>,
+    // Asks the layout for just enough room for the label, see `AsciiDesiredSize`.
+    fit_content: bool,
+    // The seconds a held button waits before it repeats, and the seconds between repeats.
+    repeat: Option<(f32, f32)>,
+    hotkey: Option<char>,
+    show_hotkey: bool,
+    hotkey_color: Color,
+    #[reflect(ignore)]
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    held_for: Option<f32>,
+    #[reflect(ignore)]
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    text_cache: AsciiTextCache,
 }
 
 impl AsciiButton {
@@ -27,10 +48,118 @@ impl AsciiButton {
             hover_bg_color: Color::Grey,
             hover_border_color: Color::White,
             hover_text_color: Color::White,
+            disabled_bg_color: Color::Black,
+            disabled_border_color: Color::DarkGrey,
+            disabled_text_color: Color::Grey,
             is_hovering: false,
+            is_disabled: false,
             button_text: text.to_string(),
+            left_icon: None,
+            right_icon: None,
+            fit_content: false,
+            repeat: None,
+            hotkey: None,
+            show_hotkey: true,
+            hotkey_color: Color::Yellow,
+            held_for: None,
+            text_cache: AsciiTextCache::default(),
         }
     }
+
+    // A glyph drawn before the label, like an arrow or a check mark.
+    pub fn left_icon(mut self, icon: char) -> Self {
+        self.left_icon = Some(icon);
+        self
+    }
+
+    pub fn right_icon(mut self, icon: char) -> Self {
+        self.right_icon = Some(icon);
+        self
+    }
+
+    // Sizes the button to its label instead of filling the bounds it is given.
+    pub fn fit_content(mut self) -> Self {
+        self.fit_content = true;
+        self
+    }
+
+    // Keeps sending clicks while the button is held, the first after `delay` seconds and then
+    // every `interval` seconds, like the arrows of a scroll bar.
+    pub fn repeat_on_hold(mut self, delay: f32, interval: f32) -> Self {
+        self.repeat = Some((delay.max(0.0), interval.max(0.01)));
+        self
+    }
+
+    // Starts the button grayed out, see `set_disabled`.
+    pub fn disabled(mut self) -> Self {
+        self.is_disabled = true;
+        self
+    }
+
+    pub fn disabled_colors(mut self, bg_color: Color, border_color: Color, text_color: Color) -> Self {
+        self.disabled_bg_color = bg_color;
+        self.disabled_border_color = border_color;
+        self.disabled_text_color = text_color;
+        self
+    }
+
+    // A disabled button is drawn in its disabled colors and sends no hover or click events, not
+    // even for its `AsciiHotkey`.
+    pub fn set_disabled(&mut self, is_disabled: bool) {
+        self.is_disabled = is_disabled;
+    }
+
+    pub fn is_disabled(&self) -> bool {
+        self.is_disabled
+    }
+
+    pub fn set_text(&mut self, text: &str) {
+        self.button_text = text.to_string();
+    }
+
+    // The size the button needs to show its label with its icons, a space on each side and the
+    // border.
+    pub fn desired_size(&self) -> UVec2 {
+        UVec2::new(self.label().chars().count() as u32 + 4, 3)
+    }
+
+    // The text with the icons around it.
+    fn label(&self) -> String {
+        let mut label = String::new();
+        if let Some(icon) = self.left_icon {
+            label.push(icon);
+            label.push(' ');
+        }
+        label.push_str(&self.button_text);
+        if let Some(icon) = self.right_icon {
+            label.push(' ');
+            label.push(icon);
+        }
+        label
+    }
+
+    // The background, border and text colors for the state the button is in.
+    fn colors(&self) -> (Color, Color, Color) {
+        if self.is_disabled {
+            (self.disabled_bg_color, self.disabled_border_color, self.disabled_text_color)
+        } else if self.is_hovering {
+            (self.hover_bg_color, self.hover_border_color, self.hover_text_color)
+        } else {
+            (self.bg_color, self.border_color, self.text_color)
+        }
+    }
+
+    // The color of the letter of an `AsciiHotkey` in the label.
+    pub fn hotkey_color(mut self, color: Color) -> Self {
+        self.hotkey_color = color;
+        self
+    }
+
+    // Stops the letter of an `AsciiHotkey` from being highlighted in the label.
+    pub fn hide_hotkey(mut self) -> Self {
+        self.show_hotkey = false;
+        self
+    }
 }
 
 impl AsciiComponent for AsciiButton {
@@ -40,24 +169,61 @@ impl AsciiComponent for AsciiButton {
         EventWriter<'w, AsciiComponentHoverEnteredEvent>,
         EventWriter<'w, AsciiComponentHoverExitedEvent>,
         EventWriter<'w, AsciiComponentButtonClicked>,
-        EventWriter<'w, AsciiMarkDirtyEvent>
+        EventWriter<'w, AsciiMarkDirtyEvent>,
+        Query<'w, 's, &'static AsciiHotkey>,
+        Query<'w, 's, &'static AsciiDesiredSize>,
+        Commands<'w, 's>,
+        Res<'w, AsciiUiTime>,
     );
 
+    fn measure(&self) -> Option<UVec2> {
+        Some(self.desired_size())
+    }
+
     fn render(&self, buffer: &mut AsciiBuffer) {
-        if let Some(inner) = buffer
+        let (bg_color, border_color, text_color) = self.colors();
+        let inner = buffer
             .square()
             .border(BorderType::Full)
-            .bg_color(if self.is_hovering { self.hover_bg_color } else { self.bg_color })
-            .border_color(if self.is_hovering { self.hover_border_color } else { self.border_color })
-            .draw()
-        {
-            inner
-                .text(&self.button_text)
-                .vertical_alignment(VerticalAlignment::Center)
-                .horizontal_alignment(HorizontalAlignment::Center)
-                .text_color(if self.is_hovering { self.hover_text_color } else { self.text_color })
-                .bg_color(if self.is_hovering { self.hover_bg_color } else { self.bg_color })
-                .draw();
+            .bg_color(bg_color)
+            .border_color(border_color)
+            .draw();
+        // The text has no room in a button without cells inside of its border.
+        if inner.bounds.is_empty() {
+            return;
+        }
+
+        let label = self.label();
+        inner
+            .text(&label)
+            .vertical_alignment(VerticalAlignment::Center)
+            .horizontal_alignment(HorizontalAlignment::Center)
+            .text_color(text_color)
+            .bg_color(bg_color)
+            .cached(&self.text_cache)
+            .draw();
+
+        if let Some(hotkey) = self.hotkey.filter(|_| self.show_hotkey && !self.is_disabled) {
+            let layout = self.text_cache.layout(
+                &label,
+                &inner.bounds,
+                false,
+                HorizontalAlignment::Center,
+                VerticalAlignment::Center,
+            );
+            // The left icon is not part of the text, so it is never the hotkey letter.
+            let icon_width = if self.left_icon.is_some() { 2 } else { 0 };
+            let mnemonic = layout.lines().enumerate().find_map(|(index, (start, line))| {
+                line.chars()
+                    .take(inner.bounds.width as usize)
+                    .enumerate()
+                    .skip(if index == 0 { icon_width } else { 0 })
+                    .find(|(_, c)| c.eq_ignore_ascii_case(&hotkey))
+                    .map(|(column, c)| (start.x + column as i32, start.y, c))
+            });
+            if let Some((x, y, c)) = mnemonic {
+                inner.set_character(x, y, (c, self.hotkey_color, bg_color));
+            }
         }
     }
 
@@ -67,6 +233,29 @@ impl AsciiComponent for AsciiButton {
         bounds: &AsciiBounds,
         entity : Entity
     ) {
+        let hotkey = query.6.get(entity).ok().and_then(|hotkey| hotkey.character());
+        if hotkey != self.hotkey {
+            self.hotkey = hotkey;
+            query.5.send(AsciiMarkDirtyEvent);
+        }
+
+        if self.fit_content {
+            let size = self.desired_size();
+            let desired_size = AsciiDesiredSize { width: size.x, height: size.y };
+            if query.7.get(entity).ok() != Some(&desired_size) {
+                query.8.entity(entity).insert(desired_size);
+            }
+        }
+
+        if self.is_disabled {
+            if self.is_hovering {
+                self.is_hovering = false;
+                query.3.send(AsciiComponentHoverExitedEvent(entity));
+            }
+            self.held_for = None;
+            return;
+        }
+
         let Ok(cursor) = query.0.get_single() else {return};
         
         if let AsciiCursor::Some { x, y } = cursor {
@@ -85,8 +274,34 @@ impl AsciiComponent for AsciiButton {
             }
         }
         
+        let is_held = self.is_hovering && query.1.pressed(MouseButton::Left);
         if query.1.just_pressed(MouseButton::Left) && self.is_hovering {
             query.4.send(AsciiComponentButtonClicked(entity));
+            self.held_for = Some(0.0);
+        } else if let (Some(held_for), Some((delay, interval)), true) = (self.held_for, self.repeat, is_held) {
+            // How many repeats there should have been after being held for `time` seconds.
+            let repeats = |time: f32| if time < delay { 0 } else { ((time - delay) / interval) as u32 + 1 };
+            let now = held_for + query.9.delta_seconds();
+            for _ in repeats(held_for)..repeats(now) {
+                query.4.send(AsciiComponentButtonClicked(entity));
+            }
+            self.held_for = Some(now);
+        }
+        if !is_held {
+            self.held_for = None;
+        }
+    }
+
+    fn accepts_hotkeys(&self) -> bool {
+        !self.is_disabled()
+    }
+
+    fn accessibility(&self) -> Option<AsciiAccessible> {
+        let accessible = AsciiAccessible::new(AsciiAccessibleRole::Button, &self.button_text);
+        if self.is_disabled {
+            Some(accessible.value("disabled"))
+        } else {
+            Some(accessible)
         }
     }
 }
@@ -0,0 +1,565 @@
+use std::{collections::VecDeque, sync::Mutex};
+
+use bevy::{
+    ecs::system::SystemParam, input::mouse::{MouseScrollUnit, MouseWheel}, prelude::*, window::PrimaryWindow
+};
+
+use crate::ui::{
+    buffer::AsciiBuffer, character::{Character, Color}, clipboard::AsciiClipboard, util::{break_string_into_lines, AsciiCursor}, AsciiMarkDirtyEvent
+};
+
+use super::{super::{accessibility::{AsciiAccessible, AsciiAccessibleRole}, bounds::AsciiBounds}, text_input::{AsciiTextEntry, TextInputAction, TextInputState}, AsciiComponent};
+
+//=============================================================================
+//             Ascii Console
+//=============================================================================
+
+#[derive(Component, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct AsciiConsole {
+    lines: VecDeque<(String, Color)>,
+    max_lines: usize,
+    scroll: usize,
+    wrap: bool,
+    bg_color: Color,
+    prompt: String,
+    prompt_color: Color,
+    input_color: Color,
+    input: Option<TextInputState>,
+    echo_commands: bool,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    is_focused: bool,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    needs_redraw: bool,
+    // The selected part of the log, from where the mouse was pressed to where it is now. Both are
+    // a row of the wrapped lines and a column.
+    #[reflect(ignore)]
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    log_selection: Option<((usize, usize), (usize, usize))>,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    drag: ConsoleDrag,
+    #[reflect(ignore)]
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    rows: Mutex<ConsoleRows>,
+}
+
+// What the mouse is selecting while it is held.
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug, Reflect)]
+enum ConsoleDrag {
+    #[default]
+    None,
+    Log,
+    Input,
+}
+
+// The lines of the console wrapped to the width it was last drawn at. New lines are wrapped as
+// they are pushed, so a long log is not wrapped again every time it is drawn.
+#[derive(Default)]
+struct ConsoleRows {
+    width: Option<u32>,
+    rows: VecDeque<(String, Color)>,
+    // How many rows each line was wrapped into, to remove the rows of the oldest line.
+    line_rows: VecDeque<usize>,
+}
+
+impl ConsoleRows {
+    // Wraps the line onto the rows and returns how many rows it took. Before the console is drawn
+    // the width is not known yet, and every line is one row.
+    fn push_line(&mut self, line: &str, color: Color, wrap: bool) -> usize {
+        let Some(width) = self.width else { return 1 };
+        let wrapped = wrap_line(line, color, width, wrap);
+        let count = wrapped.len();
+        self.line_rows.push_back(count);
+        self.rows.extend(wrapped);
+        count
+    }
+
+    fn pop_line(&mut self) {
+        if let Some(count) = self.line_rows.pop_front() {
+            self.rows.drain(..count.min(self.rows.len()));
+        }
+    }
+}
+
+fn wrap_line(line: &str, color: Color, width: u32, wrap: bool) -> Vec<(String, Color)> {
+    if !wrap || width == 0 {
+        return vec![(line.to_string(), color)];
+    }
+
+    let wrapped = break_string_into_lines(line, width as usize);
+    if wrapped.is_empty() {
+        vec![(String::new(), color)]
+    } else {
+        wrapped.into_iter().map(|row| (row, color)).collect()
+    }
+}
+
+impl AsciiConsole {
+    pub fn new(max_lines: usize) -> Self {
+        AsciiConsole {
+            lines: VecDeque::with_capacity(max_lines.min(1024)),
+            max_lines: max_lines.max(1),
+            scroll: 0,
+            wrap: true,
+            bg_color: Color::Black,
+            prompt: "> ".to_string(),
+            prompt_color: Color::LightGreen,
+            input_color: Color::White,
+            input: None,
+            echo_commands: true,
+            is_focused: false,
+            needs_redraw: false,
+            log_selection: None,
+            drag: ConsoleDrag::None,
+            rows: Mutex::new(ConsoleRows::default()),
+        }
+    }
+
+    // Adds a command line to the bottom row of the console. Submitted commands are sent as
+    // `AsciiConsoleCommandEvent`s.
+    pub fn with_input(mut self) -> Self {
+        self.input = Some(TextInputState::default());
+        self
+    }
+
+    pub fn prompt(mut self, prompt: &str) -> Self {
+        self.prompt = prompt.to_string();
+        self
+    }
+
+    pub fn prompt_color(mut self, color: Color) -> Self {
+        self.prompt_color = color;
+        self
+    }
+
+    pub fn input_color(mut self, color: Color) -> Self {
+        self.input_color = color;
+        self
+    }
+
+    pub fn bg_color(mut self, color: Color) -> Self {
+        self.bg_color = color;
+        self
+    }
+
+    pub fn no_wrap(mut self) -> Self {
+        self.wrap = false;
+        self.rows = Mutex::new(ConsoleRows::default());
+        self
+    }
+
+    pub fn echo_commands(mut self, echo: bool) -> Self {
+        self.echo_commands = echo;
+        self
+    }
+
+    pub fn push_line(&mut self, line: &str, color: Color) {
+        let rows = self.rows.get_mut().expect("Error while pushing console line: rows are poisoned.");
+        for line in line.lines() {
+            if self.lines.len() >= self.max_lines {
+                self.lines.pop_front();
+                rows.pop_line();
+                // The rows move up, the selection would be on other text.
+                self.log_selection = None;
+            }
+            self.lines.push_back((line.to_string(), color));
+            let row_count = rows.push_line(line, color, self.wrap);
+            // Keep the view steady while the user is looking at older lines.
+            if self.scroll > 0 {
+                self.scroll += row_count;
+            }
+        }
+        self.needs_redraw = true;
+    }
+
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.rows = Mutex::new(ConsoleRows::default());
+        self.scroll = 0;
+        self.log_selection = None;
+        self.needs_redraw = true;
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = (&str, Color)> {
+        self.lines.iter().map(|(line, color)| (line.as_str(), *color))
+    }
+
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    // How many rows the view is scrolled up from the newest line. 0 follows new output.
+    pub fn scroll(&self) -> usize {
+        self.scroll
+    }
+
+    pub fn scroll_to_bottom(&mut self) {
+        self.scroll = 0;
+        self.needs_redraw = true;
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.is_focused
+    }
+
+    pub fn input_text(&self) -> Option<&str> {
+        self.input.as_ref().map(|input| input.text())
+    }
+
+    // The text selected in the log, with a line break between rows.
+    pub fn selected_text(&self) -> Option<String> {
+        let (start, end) = self.ordered_selection()?;
+        let width = self.rows.lock().ok()?.width?;
+        self.with_rows(width, |rows| {
+            let text = (start.0..=end.0.min(rows.len().saturating_sub(1)))
+                .filter_map(|row| {
+                    let chars = rows.get(row)?.0.chars();
+                    let from = if row == start.0 { start.1 } else { 0 };
+                    let to = if row == end.0 { end.1 } else { usize::MAX };
+                    Some(chars.skip(from).take(to.saturating_sub(from)).collect::<String>())
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            Some(text)
+        })
+    }
+
+    // The selection from its first cell to one past its last, empty selections are `None`.
+    fn ordered_selection(&self) -> Option<((usize, usize), (usize, usize))> {
+        let (anchor, head) = self.log_selection?;
+        (anchor != head).then(|| (anchor.min(head), anchor.max(head)))
+    }
+
+    // The first row of the wrapped lines that is shown in the log.
+    fn first_row(&self, total_rows: usize, log_height: usize) -> usize {
+        total_rows.saturating_sub(self.scroll).saturating_sub(log_height)
+    }
+
+    fn log_height(&self, bounds: &AsciiBounds) -> u32 {
+        if self.input.is_some() {
+            bounds.height.saturating_sub(1)
+        } else {
+            bounds.height
+        }
+    }
+
+    // Runs `f` with the lines wrapped to `width`. The lines are only wrapped again when the width
+    // changed since the last call.
+    fn with_rows<R>(&self, width: u32, f: impl FnOnce(&VecDeque<(String, Color)>) -> R) -> R {
+        let mut rows = self.rows.lock().expect("Error while wrapping console lines: rows are poisoned.");
+        if rows.width != Some(width) {
+            *rows = ConsoleRows {
+                width: Some(width),
+                ..default()
+            };
+            for (line, color) in self.lines.iter() {
+                rows.push_line(line, *color, self.wrap);
+            }
+        }
+        f(&rows.rows)
+    }
+}
+
+//=============================================================================
+//             Console Events
+//=============================================================================
+
+#[derive(Event, Reflect, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AsciiConsoleCommandEvent {
+    pub entity: Entity,
+    pub command: String,
+}
+
+//=============================================================================
+//             Component Impl
+//=============================================================================
+
+impl AsciiComponent for AsciiConsole {
+    type UpdateQuery<'w, 's> = (
+        Query<'w, 's, &'static AsciiCursor, With<PrimaryWindow>>,
+        Res<'w, ButtonInput<MouseButton>>,
+        AsciiTextEntry<'w>,
+        Res<'w, Events<MouseWheel>>,
+        ResMut<'w, AsciiClipboard>,
+        EventWriter<'w, AsciiConsoleCommandEvent>,
+        EventWriter<'w, AsciiMarkDirtyEvent>,
+    );
+
+    fn set_up(app: &mut App) {
+        app.add_event::<AsciiConsoleCommandEvent>()
+            .init_resource::<AsciiClipboard>();
+    }
+
+    fn render(&self, buffer: &mut AsciiBuffer) {
+        let width = buffer.bounds.width;
+        let log_height = self.log_height(&buffer.bounds);
+        if width == 0 || buffer.bounds.height == 0 {
+            return;
+        }
+
+        let selection = self.ordered_selection();
+        self.with_rows(width, |rows| {
+            let end = rows.len().saturating_sub(self.scroll);
+            let start = self.first_row(rows.len(), log_height as usize);
+
+            for y in 0..log_height as usize {
+                let row = rows.get(start + y).filter(|_| start + y < end);
+                let chars: Vec<char> = row.map(|(text, _)| text.chars().collect()).unwrap_or_default();
+                let color = row.map(|(_, color)| *color).unwrap_or(Color::White);
+                for x in 0..width as usize {
+                    let c = chars.get(x).copied().unwrap_or(' ');
+                    let is_selected = selection
+                        .map(|(from, to)| (from..to).contains(&(start + y, x)))
+                        .unwrap_or(false);
+                    let character = if is_selected {
+                        (c, self.bg_color, color)
+                    } else {
+                        (c, color, self.bg_color)
+                    };
+                    buffer.set_character(x as i32, y as i32, character);
+                }
+            }
+        });
+
+        if self.scroll > 0 && log_height > 0 {
+            buffer.set_character(width as i32 - 1, 0, (Character::ArrowUp, Color::Yellow, self.bg_color));
+        }
+
+        if let Some(input) = &self.input {
+            let input_y = buffer.bounds.height as i32 - 1;
+            let prompt_len = self.prompt.chars().count() as i32;
+            for (x, c) in self.prompt.chars().enumerate() {
+                buffer.set_character(x as i32, input_y, (c, self.prompt_color, self.bg_color));
+            }
+            let input_buffer = buffer.relative(
+                prompt_len,
+                input_y,
+                (width as i32 - prompt_len).max(0),
+                1,
+            );
+            input.draw(&input_buffer, self.input_color, self.bg_color, self.is_focused);
+        }
+    }
+
+    fn update(
+        &mut self,
+        query: &mut <Self::UpdateQuery<'_, '_> as SystemParam>::Item<'_, '_>,
+        bounds: &AsciiBounds,
+        entity: Entity,
+    ) {
+        let (cursor, mouse, entry, wheel, clipboard, commands, dirty) = query;
+        let keys = entry.keys();
+        let old_scroll = self.scroll;
+        let was_focused = self.is_focused;
+
+        let cursor = match cursor.get_single() {
+            Ok(AsciiCursor::Some { x, y }) => Some((*x as i32 - bounds.x, *y as i32 - bounds.y)),
+            _ => None,
+        };
+        let is_hovering = cursor
+            .map(|(x, y)| x >= 0 && y >= 0 && x < bounds.width as i32 && y < bounds.height as i32)
+            .unwrap_or(false);
+
+        if mouse.just_pressed(MouseButton::Left) {
+            self.is_focused = is_hovering;
+        }
+
+        let log_height = self.log_height(bounds) as usize;
+        self.select_with_mouse(cursor.filter(|_| self.is_focused), mouse, bounds, log_height);
+
+        let mut scroll = self.scroll as i32;
+        // Every console reads this frame's wheel events, only the hovered one reacts to them.
+        for event in wheel.iter_current_update_events() {
+            if !is_hovering {
+                continue;
+            }
+            let rows = match event.unit {
+                MouseScrollUnit::Line => event.y.round() as i32,
+                MouseScrollUnit::Pixel => (event.y / 16.0).round() as i32,
+            };
+            scroll += rows;
+        }
+
+        if self.is_focused {
+            if keys.just_pressed(KeyCode::PageUp) {
+                scroll += log_height.max(1) as i32;
+            }
+            if keys.just_pressed(KeyCode::PageDown) {
+                scroll -= log_height.max(1) as i32;
+            }
+        }
+
+        if scroll != self.scroll as i32 {
+            let total_rows = self.with_rows(bounds.width, |rows| rows.len());
+            let max_scroll = total_rows.saturating_sub(log_height);
+            self.scroll = (scroll.max(0) as usize).min(max_scroll);
+        }
+
+        let control = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight, KeyCode::SuperLeft, KeyCode::SuperRight]);
+        if self.is_focused && control && keys.just_pressed(KeyCode::KeyC) {
+            if let Some(text) = self.selected_text() {
+                clipboard.set_text(&text);
+            }
+        }
+
+        if self.is_focused {
+            if let Some(input) = &mut self.input {
+                match input.handle_input(entry, clipboard) {
+                    TextInputAction::Submitted(command) => {
+                        input.clear();
+                        if self.echo_commands {
+                            let echo = format!("{}{}", self.prompt, command);
+                            let color = self.prompt_color;
+                            self.push_line(&echo, color);
+                        }
+                        self.scroll = 0;
+                        commands.send(AsciiConsoleCommandEvent { entity, command });
+                        self.needs_redraw = true;
+                    }
+                    TextInputAction::Changed | TextInputAction::Moved => self.needs_redraw = true,
+                    TextInputAction::None => {}
+                }
+            }
+        }
+
+        if self.needs_redraw || self.scroll != old_scroll || self.is_focused != was_focused {
+            self.needs_redraw = false;
+            dirty.send(AsciiMarkDirtyEvent);
+        }
+    }
+
+    // The caret is on the prompt, in the bottom row.
+    fn keyboard_focus(&self, bounds: &AsciiBounds) -> Option<IVec2> {
+        self.is_focused().then(|| IVec2::new(bounds.x, bounds.y + bounds.height as i32 - 1))
+    }
+
+    fn accessibility(&self) -> Option<AsciiAccessible> {
+        let mut accessible = AsciiAccessible::new(AsciiAccessibleRole::Log, "").focused(self.is_focused);
+        // The newest line, so new output is read out as it comes in.
+        if let Some((line, _)) = self.lines.back() {
+            accessible = accessible.value(line);
+        }
+        Some(accessible)
+    }
+}
+
+impl AsciiConsole {
+    // Pressing the mouse on the log starts a selection of the log, pressing it on the command
+    // line one of the input. The selection follows the mouse while it is held.
+    fn select_with_mouse(
+        &mut self,
+        cursor: Option<(i32, i32)>,
+        mouse: &ButtonInput<MouseButton>,
+        bounds: &AsciiBounds,
+        log_height: usize,
+    ) {
+        let Some((x, y)) = cursor else {
+            if !mouse.pressed(MouseButton::Left) {
+                self.drag = ConsoleDrag::None;
+            }
+            return;
+        };
+
+        let prompt_len = self.prompt.chars().count() as i32;
+        let input_width = (bounds.width as i32 - prompt_len).max(0) as u32;
+        let total_rows = self.with_rows(bounds.width, |rows| rows.len());
+        let first_row = self.first_row(total_rows, log_height);
+        let cell = (
+            (first_row + y.clamp(0, log_height.saturating_sub(1) as i32) as usize).min(total_rows),
+            x.clamp(0, bounds.width as i32) as usize,
+        );
+
+        if mouse.just_pressed(MouseButton::Left) {
+            let on_input = self.input.is_some() && y >= log_height as i32;
+            self.drag = if on_input { ConsoleDrag::Input } else { ConsoleDrag::Log };
+            self.log_selection = (!on_input).then_some((cell, cell));
+            if let Some(input) = &mut self.input {
+                if on_input {
+                    input.click(x - prompt_len, input_width, false);
+                } else {
+                    input.clear_selection();
+                }
+            }
+            self.needs_redraw = true;
+        } else if mouse.pressed(MouseButton::Left) {
+            match self.drag {
+                ConsoleDrag::Log => {
+                    if let Some((_, head)) = &mut self.log_selection {
+                        if *head != cell {
+                            *head = cell;
+                            self.needs_redraw = true;
+                        }
+                    }
+                }
+                ConsoleDrag::Input => {
+                    if let Some(input) = &mut self.input {
+                        self.needs_redraw |= input.click(x - prompt_len, input_width, true);
+                    }
+                }
+                ConsoleDrag::None => {}
+            }
+        } else {
+            self.drag = ConsoleDrag::None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ui::{buffer::AsciiSurface, character::AsciiCharacter};
+
+    use super::*;
+
+    fn rendered(console: &AsciiConsole, width: u32, height: u32) -> Vec<String> {
+        let surface = AsciiSurface::new(width, height);
+        console.render(&mut AsciiBuffer::new(&surface, &AsciiBounds::new(0, 0, width, height, 0), None));
+        surface
+            .characters()
+            .chunks(width as usize)
+            .map(|row| {
+                row.iter()
+                    .map(|c| match c {
+                        AsciiCharacter::Set { index, .. } => char::from(*index),
+                        AsciiCharacter::Unset => ' ',
+                    })
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn scrolled_back_views_stay_put_when_wrapped_lines_arrive() {
+        let mut console = AsciiConsole::new(100);
+        for line in ["ONE", "TWO", "THREE", "FOUR", "FIVE", "SIX"] {
+            console.push_line(line, Color::White);
+        }
+        let before = rendered(&console, 10, 3);
+        console.scroll = 2;
+        let scrolled = rendered(&console, 10, 3);
+        assert_ne!(before, scrolled);
+
+        // A line that wraps into three rows moves the view up by three rows.
+        console.push_line(&"A".repeat(25), Color::White);
+        assert_eq!(console.scroll(), 5);
+        assert_eq!(rendered(&console, 10, 3), scrolled);
+
+        console.push_line("SEVEN", Color::White);
+        assert_eq!(console.scroll(), 6);
+        assert_eq!(rendered(&console, 10, 3), scrolled);
+    }
+
+    #[test]
+    fn views_at_the_bottom_follow_new_lines() {
+        let mut console = AsciiConsole::new(100);
+        rendered(&console, 10, 3);
+        console.push_line(&"A".repeat(25), Color::White);
+        assert_eq!(console.scroll(), 0);
+        assert_eq!(rendered(&console, 10, 3), vec!["AAAAAAAAAA", "AAAAAAAAAA", "AAAAA"]);
+    }
+}
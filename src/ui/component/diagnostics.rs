@@ -0,0 +1,343 @@
+use bevy::{
+    diagnostic::{DiagnosticPath, DiagnosticsStore, EntityCountDiagnosticsPlugin, FrameTimeDiagnosticsPlugin},
+    ecs::system::SystemParam,
+    prelude::*,
+};
+
+use crate::ui::{
+    buffer::AsciiBuffer, character::Color, command::AsciiUiCommandExtention, diagnostics::AsciiUiDiagnosticsPlugin, time::AsciiUiTime, AsciiMarkDirtyEvent, AsciiUi, BorderType, HorizontalAlignment, VerticalAlignment
+};
+
+use super::{super::{accessibility::{AsciiAccessible, AsciiAccessibleRole}, bounds::AsciiBounds}, AsciiComponent, AsciiComponentPlugin};
+
+//=============================================================================
+//             Diagnostics Overlay Plugin
+//=============================================================================
+
+// Shows FPS, frame time and entity count in a corner of every camera with an `AsciiUi`.
+pub struct AsciiDiagnosticsOverlayPlugin {
+    pub horizontal: HorizontalAlignment,
+    pub vertical: VerticalAlignment,
+    pub refresh_interval: f32,
+}
+
+impl Default for AsciiDiagnosticsOverlayPlugin {
+    fn default() -> Self {
+        AsciiDiagnosticsOverlayPlugin {
+            horizontal: HorizontalAlignment::Right,
+            vertical: VerticalAlignment::Top,
+            refresh_interval: 0.25,
+        }
+    }
+}
+
+impl AsciiDiagnosticsOverlayPlugin {
+    pub fn corner(horizontal: HorizontalAlignment, vertical: VerticalAlignment) -> Self {
+        AsciiDiagnosticsOverlayPlugin {
+            horizontal,
+            vertical,
+            ..Default::default()
+        }
+    }
+}
+
+impl Plugin for AsciiDiagnosticsOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<FrameTimeDiagnosticsPlugin>() {
+            app.add_plugins(FrameTimeDiagnosticsPlugin);
+        }
+
+        if !app.is_plugin_added::<EntityCountDiagnosticsPlugin>() {
+            app.add_plugins(EntityCountDiagnosticsPlugin);
+        }
+
+        app.insert_resource(AsciiDiagnosticsOverlayConfig {
+            horizontal: self.horizontal,
+            vertical: self.vertical,
+            refresh_interval: self.refresh_interval,
+        })
+        .add_plugins(AsciiComponentPlugin::<AsciiDiagnosticsPanel>::default())
+        .register_type::<AsciiDiagnosticsPanel>()
+        .add_systems(Update, spawn_diagnostics_panels);
+    }
+}
+
+#[derive(Resource)]
+struct AsciiDiagnosticsOverlayConfig {
+    horizontal: HorizontalAlignment,
+    vertical: VerticalAlignment,
+    refresh_interval: f32,
+}
+
+fn spawn_diagnostics_panels(
+    mut commands: Commands,
+    cameras: Query<Entity, Added<AsciiUi>>,
+    config: Res<AsciiDiagnosticsOverlayConfig>,
+) {
+    for camera in cameras.iter() {
+        commands
+            .ascii_ui_with_parent(camera)
+            .aligned(
+                AsciiDiagnosticsPanel::WIDTH as i32,
+                AsciiDiagnosticsPanel::HEIGHT as i32,
+                config.horizontal,
+                config.vertical,
+                AsciiDiagnosticsPanel::new(config.refresh_interval),
+            )
+            .named("Ascii Diagnostics");
+    }
+}
+
+//=============================================================================
+//             Diagnostics Panel
+//=============================================================================
+
+#[derive(Component, Reflect)]
+pub struct AsciiDiagnosticsPanel {
+    fps: Option<f64>,
+    frame_time: Option<f64>,
+    entity_count: Option<f64>,
+    refresh_interval: f32,
+    since_refresh: f32,
+    text_color: Color,
+    border_color: Color,
+    bg_color: Color,
+}
+
+impl AsciiDiagnosticsPanel {
+    pub const WIDTH: u32 = 20;
+    pub const HEIGHT: u32 = 5;
+
+    pub fn new(refresh_interval: f32) -> Self {
+        AsciiDiagnosticsPanel {
+            fps: None,
+            frame_time: None,
+            entity_count: None,
+            refresh_interval,
+            since_refresh: f32::MAX,
+            text_color: Color::LightGreen,
+            border_color: Color::DarkGrey,
+            bg_color: Color::Black,
+        }
+    }
+
+    pub fn text_color(mut self, color: Color) -> Self {
+        self.text_color = color;
+        self
+    }
+
+    pub fn border_color(mut self, color: Color) -> Self {
+        self.border_color = color;
+        self
+    }
+
+    pub fn bg_color(mut self, color: Color) -> Self {
+        self.bg_color = color;
+        self
+    }
+
+    fn lines(&self) -> [String; 3] {
+        let format = |value: Option<f64>, precision: usize| match value {
+            Some(value) => format!("{:.*}", precision, value),
+            None => "-".to_string(),
+        };
+        [
+            format!("FPS   {}", format(self.fps, 1)),
+            format!("FRAME {}MS", format(self.frame_time, 2)),
+            format!("ENTS  {}", format(self.entity_count, 0)),
+        ]
+    }
+}
+
+impl Default for AsciiDiagnosticsPanel {
+    fn default() -> Self {
+        Self::new(0.25)
+    }
+}
+
+impl AsciiComponent for AsciiDiagnosticsPanel {
+    type UpdateQuery<'w, 's> = (
+        Option<Res<'w, DiagnosticsStore>>,
+        Res<'w, AsciiUiTime>,
+        EventWriter<'w, AsciiMarkDirtyEvent>,
+    );
+
+    fn render(&self, buffer: &mut AsciiBuffer) {
+        let inner = buffer
+            .square()
+            .border(BorderType::Full)
+            .title("STATS")
+            .border_color(self.border_color)
+            .bg_color(self.bg_color)
+            .draw();
+
+        let text = self.lines().join("\n");
+        inner
+            .text(&text)
+            .text_color(self.text_color)
+            .bg_color(self.bg_color)
+            .draw();
+    }
+
+    fn update(
+        &mut self,
+        query: &mut <Self::UpdateQuery<'_, '_> as SystemParam>::Item<'_, '_>,
+        _bounds: &AsciiBounds,
+        _entity: Entity,
+    ) {
+        let (diagnostics, time, dirty) = query;
+        let Some(diagnostics) = diagnostics else { return };
+
+        self.since_refresh += time.delta_seconds();
+        if self.since_refresh < self.refresh_interval {
+            return;
+        }
+        self.since_refresh = 0.0;
+
+        let read = |path: &DiagnosticPath| diagnostics.get(path).and_then(|diagnostic| diagnostic.smoothed());
+        let fps = read(&FrameTimeDiagnosticsPlugin::FPS);
+        let frame_time = read(&FrameTimeDiagnosticsPlugin::FRAME_TIME);
+        let entity_count = read(&EntityCountDiagnosticsPlugin::ENTITY_COUNT);
+
+        if fps != self.fps || frame_time != self.frame_time || entity_count != self.entity_count {
+            self.fps = fps;
+            self.frame_time = frame_time;
+            self.entity_count = entity_count;
+            dirty.send(AsciiMarkDirtyEvent);
+        }
+    }
+
+    fn accessibility(&self) -> Option<AsciiAccessible> {
+        Some(AsciiAccessible::new(AsciiAccessibleRole::Group, "Stats").value(self.lines().join(", ")))
+    }
+}
+
+//=============================================================================
+//             Ui Diagnostics Panel
+//=============================================================================
+
+// Shows the counts of the `AsciiUiDiagnosticsPlugin`, smoothed over the last frames. The panel
+// redraws the ui itself when the counts change, so it adds to them a little.
+#[derive(Component, Reflect)]
+pub struct AsciiUiDiagnosticsPanel {
+    values: [Option<f64>; 5],
+    refresh_interval: f32,
+    since_refresh: f32,
+    text_color: Color,
+    border_color: Color,
+    bg_color: Color,
+}
+
+impl AsciiUiDiagnosticsPanel {
+    pub const WIDTH: u32 = 22;
+    pub const HEIGHT: u32 = 7;
+
+    const PATHS: [DiagnosticPath; 5] = [
+        AsciiUiDiagnosticsPlugin::COMPONENTS_RENDERED,
+        AsciiUiDiagnosticsPlugin::CELLS_WRITTEN,
+        AsciiUiDiagnosticsPlugin::BYTES_UPLOADED,
+        AsciiUiDiagnosticsPlugin::DIRTY_EVENTS,
+        AsciiUiDiagnosticsPlugin::LAYOUT_RECOMPUTES,
+    ];
+    const LABELS: [&'static str; 5] = ["COMPS ", "CELLS ", "BYTES ", "DIRTY ", "LAYOUT"];
+
+    pub fn new(refresh_interval: f32) -> Self {
+        AsciiUiDiagnosticsPanel {
+            values: [None; 5],
+            refresh_interval,
+            since_refresh: f32::MAX,
+            text_color: Color::LightGreen,
+            border_color: Color::DarkGrey,
+            bg_color: Color::Black,
+        }
+    }
+
+    pub fn text_color(mut self, color: Color) -> Self {
+        self.text_color = color;
+        self
+    }
+
+    pub fn border_color(mut self, color: Color) -> Self {
+        self.border_color = color;
+        self
+    }
+
+    pub fn bg_color(mut self, color: Color) -> Self {
+        self.bg_color = color;
+        self
+    }
+
+    fn lines(&self) -> Vec<String> {
+        Self::LABELS
+            .iter()
+            .zip(self.values)
+            .map(|(label, value)| match value {
+                Some(value) => format!("{} {:.0}", label, value),
+                None => format!("{} -", label),
+            })
+            .collect()
+    }
+}
+
+impl Default for AsciiUiDiagnosticsPanel {
+    fn default() -> Self {
+        Self::new(0.25)
+    }
+}
+
+impl AsciiComponent for AsciiUiDiagnosticsPanel {
+    type UpdateQuery<'w, 's> = (
+        Option<Res<'w, DiagnosticsStore>>,
+        Res<'w, AsciiUiTime>,
+        EventWriter<'w, AsciiMarkDirtyEvent>,
+    );
+
+    fn render(&self, buffer: &mut AsciiBuffer) {
+        let inner = buffer
+            .square()
+            .border(BorderType::Full)
+            .title("UI STATS")
+            .border_color(self.border_color)
+            .bg_color(self.bg_color)
+            .draw();
+
+        let text = self.lines().join("\n");
+        inner
+            .text(&text)
+            .text_color(self.text_color)
+            .bg_color(self.bg_color)
+            .draw();
+    }
+
+    fn update(
+        &mut self,
+        query: &mut <Self::UpdateQuery<'_, '_> as SystemParam>::Item<'_, '_>,
+        _bounds: &AsciiBounds,
+        _entity: Entity,
+    ) {
+        let (diagnostics, time, dirty) = query;
+        let Some(diagnostics) = diagnostics else { return };
+
+        self.since_refresh += time.delta_seconds();
+        if self.since_refresh < self.refresh_interval {
+            return;
+        }
+        self.since_refresh = 0.0;
+
+        // Only whole counts are shown, so smaller changes do not redraw the ui.
+        let values = Self::PATHS.map(|path| {
+            diagnostics
+                .get(&path)
+                .and_then(|diagnostic| diagnostic.smoothed())
+                .map(|value| value.round())
+        });
+        if values != self.values {
+            self.values = values;
+            dirty.send(AsciiMarkDirtyEvent);
+        }
+    }
+
+    fn accessibility(&self) -> Option<AsciiAccessible> {
+        Some(AsciiAccessible::new(AsciiAccessibleRole::Group, "Ui Stats").value(self.lines().join(", ")))
+    }
+}
@@ -0,0 +1,471 @@
+use bevy::{
+    ecs::system::SystemParam, input::mouse::{MouseScrollUnit, MouseWheel}, prelude::*, window::PrimaryWindow
+};
+
+use crate::ui::{
+    buffer::AsciiBuffer, character::{Character, Color}, util::AsciiCursor, AsciiMarkDirtyEvent
+};
+
+use super::{super::{accessibility::{AsciiAccessible, AsciiAccessibleRole}, bounds::AsciiBounds}, AsciiComponent};
+
+//=============================================================================
+//             Ascii List
+//=============================================================================
+
+// A scrollable list of rows with a selection. The whole list is a single ui node and only the rows
+// that fit in its bounds are drawn, so it holds thousands of items without slowing down the
+// layout or the extraction the way one node per row would.
+#[derive(Component, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct AsciiList {
+    items: Vec<String>,
+    selected: Option<usize>,
+    // The index of the first visible row.
+    scroll: usize,
+    text_color: Color,
+    bg_color: Color,
+    selected_text_color: Color,
+    selected_bg_color: Color,
+    hover_bg_color: Color,
+    scrollbar_color: Color,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    hovered: Option<usize>,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    is_focused: bool,
+    // Set when something other than `update` moved the selection, so the next update can bring it
+    // into view once the height of the list is known.
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    reveal_selected: bool,
+}
+
+impl AsciiList {
+    pub fn new(items: impl IntoIterator<Item = impl ToString>) -> Self {
+        AsciiList {
+            items: items.into_iter().map(|item| item.to_string()).collect(),
+            selected: None,
+            scroll: 0,
+            text_color: Color::LightGrey,
+            bg_color: Color::Black,
+            selected_text_color: Color::Black,
+            selected_bg_color: Color::White,
+            hover_bg_color: Color::DarkGrey,
+            scrollbar_color: Color::LightGrey,
+            hovered: None,
+            is_focused: false,
+            reveal_selected: false,
+        }
+    }
+
+    pub fn selected(mut self, index: usize) -> Self {
+        self.select(Some(index));
+        self
+    }
+
+    pub fn text_color(mut self, color: Color) -> Self {
+        self.text_color = color;
+        self
+    }
+
+    pub fn bg_color(mut self, color: Color) -> Self {
+        self.bg_color = color;
+        self
+    }
+
+    pub fn selected_text_color(mut self, color: Color) -> Self {
+        self.selected_text_color = color;
+        self
+    }
+
+    pub fn selected_bg_color(mut self, color: Color) -> Self {
+        self.selected_bg_color = color;
+        self
+    }
+
+    pub fn hover_bg_color(mut self, color: Color) -> Self {
+        self.hover_bg_color = color;
+        self
+    }
+
+    pub fn scrollbar_color(mut self, color: Color) -> Self {
+        self.scrollbar_color = color;
+        self
+    }
+
+    pub fn items(&self) -> &[String] {
+        &self.items
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn selected_index(&self) -> Option<usize> {
+        self.selected
+    }
+
+    pub fn selected_item(&self) -> Option<&str> {
+        self.selected.and_then(|index| self.items.get(index)).map(|item| item.as_str())
+    }
+
+    pub fn scroll(&self) -> usize {
+        self.scroll
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.is_focused
+    }
+
+    // Selects the row and scrolls it into view the next time the list is updated.
+    pub fn select(&mut self, index: Option<usize>) {
+        self.selected = index.filter(|index| *index < self.items.len());
+        self.reveal_selected = self.selected.is_some();
+    }
+
+    pub fn scroll_to(&mut self, index: usize) {
+        self.scroll = index.min(self.items.len().saturating_sub(1));
+    }
+
+    pub fn push(&mut self, item: impl ToString) {
+        self.items.push(item.to_string());
+    }
+
+    // Rows after the new one move down, the selection and the scroll position move with them so
+    // the same rows stay selected and on screen.
+    pub fn insert(&mut self, index: usize, item: impl ToString) {
+        let index = index.min(self.items.len());
+        self.items.insert(index, item.to_string());
+        if let Some(selected) = self.selected.as_mut() {
+            if *selected >= index {
+                *selected += 1;
+            }
+        }
+        if self.scroll > index {
+            self.scroll += 1;
+        }
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<String> {
+        if index >= self.items.len() {
+            return None;
+        }
+        let item = self.items.remove(index);
+        self.selected = match self.selected {
+            Some(selected) if selected == index => None,
+            Some(selected) if selected > index => Some(selected - 1),
+            selected => selected,
+        };
+        if self.scroll > index {
+            self.scroll -= 1;
+        }
+        self.scroll = self.scroll.min(self.items.len().saturating_sub(1));
+        Some(item)
+    }
+
+    // Replaces every item. The selected item stays selected if it is still in the list, and the
+    // list keeps its scroll position as far as the new items allow.
+    pub fn set_items(&mut self, items: impl IntoIterator<Item = impl ToString>) {
+        let selected_item = self.selected_item().map(|item| item.to_string());
+        let old_selected = self.selected;
+        self.items = items.into_iter().map(|item| item.to_string()).collect();
+
+        self.selected = selected_item.and_then(|selected_item| {
+            // Items that were added or removed before the selection usually only move it a little,
+            // so the search starts from where it was.
+            let start = old_selected.unwrap_or(0).min(self.items.len());
+            let (before, after) = self.items.split_at(start);
+            after
+                .iter()
+                .position(|item| *item == selected_item)
+                .map(|index| start + index)
+                .or_else(|| before.iter().rposition(|item| *item == selected_item))
+        });
+        self.scroll = self.scroll.min(self.items.len().saturating_sub(1));
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.selected = None;
+        self.scroll = 0;
+    }
+
+    // The indices of the items in view of a list `height` rows tall. Rows past the last item are
+    // left empty.
+    pub fn visible_range(&self, height: usize) -> std::ops::Range<usize> {
+        let start = self.scroll.min(self.items.len());
+        start..(start + height).min(self.items.len())
+    }
+
+    fn max_scroll(&self, height: usize) -> usize {
+        self.items.len().saturating_sub(height)
+    }
+
+    // Scrolls as little as possible to get the selected row inside a view `height` rows tall.
+    fn scroll_to_selected(&mut self, height: usize) {
+        let Some(selected) = self.selected else { return };
+        if selected < self.scroll {
+            self.scroll = selected;
+        } else if height > 0 && selected >= self.scroll + height {
+            self.scroll = selected + 1 - height;
+        }
+    }
+
+    fn has_scrollbar(&self, bounds: &AsciiBounds) -> bool {
+        self.items.len() > bounds.height as usize && bounds.width > 1
+    }
+}
+
+//=============================================================================
+//             List Events
+//=============================================================================
+
+#[derive(Event, Reflect, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AsciiListSelectedEvent {
+    pub entity: Entity,
+    pub index: usize,
+}
+
+// Sent when enter is pressed on the selected row.
+#[derive(Event, Reflect, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AsciiListSubmittedEvent {
+    pub entity: Entity,
+    pub index: usize,
+}
+
+//=============================================================================
+//             Component Impl
+//=============================================================================
+
+impl AsciiComponent for AsciiList {
+    type UpdateQuery<'w, 's> = (
+        Query<'w, 's, &'static AsciiCursor, With<PrimaryWindow>>,
+        Res<'w, ButtonInput<MouseButton>>,
+        Res<'w, ButtonInput<KeyCode>>,
+        Res<'w, Events<MouseWheel>>,
+        EventWriter<'w, AsciiListSelectedEvent>,
+        EventWriter<'w, AsciiListSubmittedEvent>,
+        EventWriter<'w, AsciiMarkDirtyEvent>,
+    );
+
+    fn set_up(app: &mut App) {
+        app
+            .add_event::<AsciiListSelectedEvent>()
+            .add_event::<AsciiListSubmittedEvent>()
+        ;
+    }
+
+    fn render(&self, buffer: &mut AsciiBuffer) {
+        let width = buffer.bounds.width as i32;
+        let height = buffer.bounds.height as usize;
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        let has_scrollbar = self.has_scrollbar(&buffer.bounds);
+        let text_width = if has_scrollbar { width - 1 } else { width };
+
+        // Only the rows in view are touched, however long the list is.
+        let visible = self.visible_range(height);
+        for y in 0..height {
+            let index = visible.start + y;
+            let (text_color, bg_color) = if self.selected == Some(index) {
+                (self.selected_text_color, self.selected_bg_color)
+            } else if self.hovered == Some(index) {
+                (self.text_color, self.hover_bg_color)
+            } else {
+                (self.text_color, self.bg_color)
+            };
+
+            let mut chars = self.items.get(index).map(|item| item.chars()).into_iter().flatten();
+            for x in 0..text_width {
+                let c = chars.next().unwrap_or(' ');
+                buffer.set_character(x, y as i32, (c, text_color, bg_color));
+            }
+        }
+
+        if has_scrollbar {
+            let thumb_height = ((height * height) / self.items.len()).max(1);
+            let max_scroll = self.max_scroll(height).max(1);
+            let thumb_start = (self.scroll.min(max_scroll) * (height - thumb_height)) / max_scroll;
+            for y in 0..height {
+                let character = if y >= thumb_start && y < thumb_start + thumb_height {
+                    (Character::Space, self.scrollbar_color, self.scrollbar_color)
+                } else {
+                    (Character::Pipe, self.scrollbar_color, self.bg_color)
+                };
+                buffer.set_character(width - 1, y as i32, character);
+            }
+        }
+    }
+
+    fn update(
+        &mut self,
+        query: &mut <Self::UpdateQuery<'_, '_> as SystemParam>::Item<'_, '_>,
+        bounds: &AsciiBounds,
+        entity: Entity,
+    ) {
+        let (cursor, mouse, keys, wheel, selected, submitted, dirty) = query;
+        let old_selected = self.selected;
+        let old_scroll = self.scroll;
+        let old_hovered = self.hovered;
+        let was_focused = self.is_focused;
+        let height = bounds.height as usize;
+
+        let cursor = match cursor.get_single() {
+            Ok(AsciiCursor::Some { x, y }) => Some((*x as i32 - bounds.x, *y as i32 - bounds.y)),
+            _ => None,
+        };
+        let is_hovering = cursor
+            .map(|(x, y)| x >= 0 && y >= 0 && x < bounds.width as i32 && y < bounds.height as i32)
+            .unwrap_or(false);
+        let text_width = if self.has_scrollbar(bounds) { bounds.width as i32 - 1 } else { bounds.width as i32 };
+
+        self.hovered = match cursor {
+            Some((x, y)) if is_hovering && x < text_width => Some(self.scroll + y as usize).filter(|index| *index < self.items.len()),
+            _ => None,
+        };
+
+        if mouse.just_pressed(MouseButton::Left) {
+            self.is_focused = is_hovering;
+            if let Some(hovered) = self.hovered {
+                self.selected = Some(hovered);
+            }
+        }
+
+        let mut scroll = self.scroll as i32;
+        // Every list reads this frame's wheel events, only the hovered one reacts to them.
+        for event in wheel.iter_current_update_events() {
+            if !is_hovering {
+                continue;
+            }
+            let rows = match event.unit {
+                MouseScrollUnit::Line => event.y.round() as i32,
+                MouseScrollUnit::Pixel => (event.y / 16.0).round() as i32,
+            };
+            scroll -= rows;
+        }
+        self.scroll = (scroll.max(0) as usize).min(self.max_scroll(height));
+
+        if self.is_focused && !self.items.is_empty() {
+            let last = self.items.len() - 1;
+            let page = height.max(1);
+            let current = self.selected;
+            let next = if keys.just_pressed(KeyCode::ArrowUp) {
+                Some(current.map(|index| index.saturating_sub(1)).unwrap_or(0))
+            } else if keys.just_pressed(KeyCode::ArrowDown) {
+                Some(current.map(|index| (index + 1).min(last)).unwrap_or(0))
+            } else if keys.just_pressed(KeyCode::PageUp) {
+                Some(current.map(|index| index.saturating_sub(page)).unwrap_or(0))
+            } else if keys.just_pressed(KeyCode::PageDown) {
+                Some(current.map(|index| (index + page).min(last)).unwrap_or(0))
+            } else if keys.just_pressed(KeyCode::Home) {
+                Some(0)
+            } else if keys.just_pressed(KeyCode::End) {
+                Some(last)
+            } else {
+                None
+            };
+            if next.is_some() {
+                self.selected = next;
+                self.reveal_selected = true;
+            }
+
+            if let (Some(index), true) = (self.selected, keys.just_pressed(KeyCode::Enter)) {
+                submitted.send(AsciiListSubmittedEvent { entity, index });
+            }
+        }
+
+        if self.reveal_selected {
+            self.reveal_selected = false;
+            self.scroll_to_selected(height);
+        }
+
+        if self.selected != old_selected {
+            if let Some(index) = self.selected {
+                selected.send(AsciiListSelectedEvent { entity, index });
+            }
+            dirty.send(AsciiMarkDirtyEvent);
+        } else if self.scroll != old_scroll || self.hovered != old_hovered || self.is_focused != was_focused {
+            dirty.send(AsciiMarkDirtyEvent);
+        }
+    }
+
+    fn accessibility(&self) -> Option<AsciiAccessible> {
+        let mut accessible = AsciiAccessible::new(AsciiAccessibleRole::ListBox, "").focused(self.is_focused);
+        if let Some(item) = self.selected_item() {
+            accessible = accessible.value(item);
+        }
+        Some(accessible)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ui::{buffer::AsciiSurface, character::AsciiCharacter};
+
+    use super::*;
+
+    fn numbers(count: usize) -> AsciiList {
+        AsciiList::new((0..count).map(|i| i.to_string()))
+    }
+
+    // The text of each row, without the scrollbar.
+    fn rendered(list: &AsciiList, width: u32, height: u32) -> Vec<String> {
+        let surface = AsciiSurface::new(width, height);
+        list.render(&mut AsciiBuffer::new(&surface, &AsciiBounds::new(0, 0, width, height, 0), None));
+        surface
+            .characters()
+            .chunks(width as usize)
+            .map(|row| {
+                row[..width as usize - 1]
+                    .iter()
+                    .map(|c| match c {
+                        AsciiCharacter::Set { index, .. } => char::from(*index),
+                        AsciiCharacter::Unset => ' ',
+                    })
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn visible_range_follows_the_scroll() {
+        let mut list = numbers(1000);
+        assert_eq!(list.visible_range(10), 0..10);
+
+        list.scroll_to(500);
+        assert_eq!(list.visible_range(10), 500..510);
+        assert_eq!(rendered(&list, 6, 3), vec!["500", "501", "502"]);
+
+        // Selecting the last row scrolls so it is at the bottom.
+        list.select(Some(999));
+        list.scroll_to_selected(10);
+        assert_eq!(list.visible_range(10), 990..1000);
+        assert_eq!(list.visible_range(20), 990..1000);
+    }
+
+    #[test]
+    fn lists_shorter_than_the_view_show_every_item() {
+        let list = numbers(3);
+        assert_eq!(list.visible_range(10), 0..3);
+        assert_eq!(rendered(&list, 6, 5), vec!["0", "1", "2", "", ""]);
+        assert_eq!(AsciiList::new(Vec::<String>::new()).visible_range(10), 0..0);
+    }
+
+    #[test]
+    fn removing_items_keeps_the_range_in_the_list() {
+        let mut list = numbers(20);
+        list.scroll_to(19);
+        for _ in 0..15 {
+            list.remove(0);
+        }
+        assert_eq!(list.visible_range(10), 4..5);
+        list.clear();
+        assert_eq!(list.visible_range(10), 0..0);
+    }
+}
@@ -0,0 +1,276 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::ui::{
+    buffer::AsciiBuffer, character::{Character, Color}, AsciiMarkDirtyEvent, BorderType
+};
+
+use super::{super::{accessibility::{AsciiAccessible, AsciiAccessibleRole}, bounds::AsciiBounds}, AsciiComponent};
+
+//=============================================================================
+//             Ascii Minimap
+//=============================================================================
+
+// A top down map of every entity with an `AsciiMinimapMarker`. `area` is the part of the world
+// that is shown, in the two world axes of the map, and is scaled to the bounds of the minimap.
+// Each category of marker can have its own character and color.
+#[derive(Component, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct AsciiMinimap {
+    area: Rect,
+    axes: AsciiMinimapAxes,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    follow: Option<Entity>,
+    categories: Vec<(String, Character, Color)>,
+    default_character: Character,
+    default_color: Color,
+    follow_character: Character,
+    follow_color: Color,
+    bg_color: Color,
+    border: BorderType,
+    border_color: Color,
+    title: Option<String>,
+    #[reflect(ignore)]
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    blips: Vec<(IVec2, Character, Color)>,
+}
+
+// Which two axes of the world the map is looking down onto.
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum AsciiMinimapAxes {
+    // The ground plane of a 3d world, -z is up on the map.
+    #[default]
+    XZ,
+    // A 2d world, +y is up on the map.
+    XY,
+}
+
+impl AsciiMinimap {
+    pub fn new(area: Rect) -> Self {
+        AsciiMinimap {
+            area,
+            axes: AsciiMinimapAxes::default(),
+            follow: None,
+            categories: Vec::new(),
+            default_character: Character::Asterisk,
+            default_color: Color::White,
+            follow_character: Character::AT,
+            follow_color: Color::Yellow,
+            bg_color: Color::Black,
+            border: BorderType::Full,
+            border_color: Color::White,
+            title: None,
+            blips: Vec::new(),
+        }
+    }
+
+    pub fn axes(mut self, axes: AsciiMinimapAxes) -> Self {
+        self.axes = axes;
+        self
+    }
+
+    // Keeps the entity in the middle of the map. The area is moved along with it, only its size
+    // is used.
+    pub fn follow(mut self, entity: Entity) -> Self {
+        self.follow = Some(entity);
+        self
+    }
+
+    pub fn follow_character(mut self, character: impl Into<Character>, color: Color) -> Self {
+        self.follow_character = character.into();
+        self.follow_color = color;
+        self
+    }
+
+    // How markers of `category` are drawn. Categories added later are drawn over earlier ones.
+    pub fn category(mut self, category: &str, character: impl Into<Character>, color: Color) -> Self {
+        let character = character.into();
+        match self.categories.iter_mut().find(|(name, _, _)| name == category) {
+            Some(existing) => *existing = (category.to_string(), character, color),
+            None => self.categories.push((category.to_string(), character, color)),
+        }
+        self
+    }
+
+    // How markers without a matching category are drawn.
+    pub fn default_character(mut self, character: impl Into<Character>, color: Color) -> Self {
+        self.default_character = character.into();
+        self.default_color = color;
+        self
+    }
+
+    pub fn bg_color(mut self, color: Color) -> Self {
+        self.bg_color = color;
+        self
+    }
+
+    pub fn border(mut self, border: BorderType) -> Self {
+        self.border = border;
+        self
+    }
+
+    pub fn border_color(mut self, color: Color) -> Self {
+        self.border_color = color;
+        self
+    }
+
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    pub fn area(&self) -> Rect {
+        self.area
+    }
+
+    pub fn set_area(&mut self, area: Rect) {
+        self.area = area;
+    }
+
+    fn has_border(&self) -> bool {
+        self.border != BorderType::None
+    }
+
+    fn project(&self, position: Vec3) -> Vec2 {
+        match self.axes {
+            AsciiMinimapAxes::XZ => Vec2::new(position.x, position.z),
+            AsciiMinimapAxes::XY => Vec2::new(position.x, position.y),
+        }
+    }
+
+    // The cell of the map the projected position is in, or none if it is off the map.
+    fn world_to_cell(&self, area: Rect, position: Vec2, width: u32, height: u32) -> Option<IVec2> {
+        if area.width() <= 0.0 || area.height() <= 0.0 {
+            return None;
+        }
+
+        let mut uv = (position - area.min) / area.size();
+        if self.axes == AsciiMinimapAxes::XY {
+            uv.y = 1.0 - uv.y;
+        }
+        if uv.x < 0.0 || uv.y < 0.0 || uv.x >= 1.0 || uv.y >= 1.0 {
+            return None;
+        }
+
+        Some(IVec2::new((uv.x * width as f32) as i32, (uv.y * height as f32) as i32))
+    }
+
+    fn style(&self, category: &str) -> (Character, Color, usize) {
+        self.categories
+            .iter()
+            .enumerate()
+            .find(|(_, (name, _, _))| name == category)
+            .map(|(order, (_, character, color))| (*character, *color, order + 1))
+            .unwrap_or((self.default_character, self.default_color, 0))
+    }
+}
+
+// Shows the entity on every `AsciiMinimap`. The category picks how it is drawn.
+#[derive(Component, Reflect, Clone, Debug, PartialEq, Eq, Default)]
+pub struct AsciiMinimapMarker {
+    pub category: String,
+}
+
+impl AsciiMinimapMarker {
+    pub fn new(category: &str) -> Self {
+        AsciiMinimapMarker {
+            category: category.to_string(),
+        }
+    }
+}
+
+//=============================================================================
+//             Component Impl
+//=============================================================================
+
+impl AsciiComponent for AsciiMinimap {
+    type UpdateQuery<'w, 's> = (
+        Query<'w, 's, (Entity, &'static GlobalTransform, &'static AsciiMinimapMarker, Option<&'static InheritedVisibility>)>,
+        Query<'w, 's, &'static GlobalTransform>,
+        EventWriter<'w, AsciiMarkDirtyEvent>,
+    );
+
+    fn set_up(app: &mut App) {
+        app.register_type::<AsciiMinimapMarker>();
+    }
+
+    fn render(&self, buffer: &mut AsciiBuffer) {
+        let map = if self.has_border() {
+            let mut square = buffer
+                .square()
+                .border(self.border)
+                .border_color(self.border_color)
+                .bg_color(self.bg_color);
+            if let Some(title) = &self.title {
+                square = square.title(title).title_text_color(self.border_color).title_bg_color(self.bg_color);
+            }
+            square.draw()
+        } else {
+            for y in 0..buffer.bounds.height as i32 {
+                for x in 0..buffer.bounds.width as i32 {
+                    buffer.set_character(x, y, (Character::Space, Color::White, self.bg_color));
+                }
+            }
+            buffer.clone()
+        };
+
+        for (cell, character, color) in self.blips.iter() {
+            map.set_character(cell.x, cell.y, (*character, *color, self.bg_color));
+        }
+    }
+
+    fn update(
+        &mut self,
+        query: &mut <Self::UpdateQuery<'_, '_> as SystemParam>::Item<'_, '_>,
+        bounds: &AsciiBounds,
+        _entity: Entity,
+    ) {
+        let (markers, transforms, dirty) = query;
+        let inset = if self.has_border() { 2 } else { 0 };
+        let width = bounds.width.saturating_sub(inset);
+        let height = bounds.height.saturating_sub(inset);
+
+        let follow_position = self
+            .follow
+            .and_then(|entity| transforms.get(entity).ok())
+            .map(|transform| self.project(transform.translation()));
+        let area = match follow_position {
+            Some(center) => Rect::from_center_size(center, self.area.size()),
+            None => self.area,
+        };
+
+        let mut blips = markers
+            .iter()
+            .filter(|(entity, _, _, visibility)| {
+                Some(*entity) != self.follow && visibility.map(|v| v.get()).unwrap_or(true)
+            })
+            .filter_map(|(_, transform, marker, _)| {
+                let cell = self.world_to_cell(area, self.project(transform.translation()), width, height)?;
+                let (character, color, order) = self.style(&marker.category);
+                Some((order, cell, character, color))
+            })
+            .collect::<Vec<_>>();
+        // Later categories are drawn last, so they end up on top.
+        blips.sort_by_key(|(order, cell, _, _)| (*order, cell.y, cell.x));
+
+        let mut blips = blips
+            .into_iter()
+            .map(|(_, cell, character, color)| (cell, character, color))
+            .collect::<Vec<_>>();
+        if let Some(center) = follow_position {
+            if let Some(cell) = self.world_to_cell(area, center, width, height) {
+                blips.push((cell, self.follow_character, self.follow_color));
+            }
+        }
+
+        if blips != self.blips {
+            self.blips = blips;
+            dirty.send(AsciiMarkDirtyEvent);
+        }
+    }
+
+    fn accessibility(&self) -> Option<AsciiAccessible> {
+        let label = self.title.as_deref().unwrap_or("Minimap");
+        Some(AsciiAccessible::new(AsciiAccessibleRole::Image, label))
+    }
+}
@@ -1,40 +1,114 @@
 pub mod button;
+pub mod window;
+pub mod slider;
+pub mod spinner;
+pub mod tabs;
+pub mod toggle;
+pub mod segmented;
+pub mod number;
+pub mod list;
+pub mod text_input;
+pub mod console;
+pub mod autocomplete;
+pub mod big_text;
+pub mod diagnostics;
+pub mod bevy_ui;
+pub mod replay;
+pub mod rich_text;
+pub mod minimap;
+pub mod noise;
+pub mod tilemap;
+pub mod animation;
+pub mod virtual_keyboard;
 
-use std::marker::PhantomData;
+use std::{any::TypeId, marker::PhantomData};
 
 use bevy::{
-    ecs::
-        system::{StaticSystemParam, SystemParam}
-    ,
+    ecs::{
+        schedule::SystemConfigs,
+        system::{StaticSystemParam, SystemParam},
+    },
     prelude::*,
-    render::{view::{visibility, RenderLayers}, Extract, RenderApp},
+    render::{view::RenderLayers, Extract, RenderApp},
+    utils::{HashMap, HashSet},
 };
 
 use crate::{
     ascii::AsciiCamera,
-    render::ascii::OverlayBuffer,
+    render::ascii::{LiveOverlayBuffer, OverlayBuffer},
 };
 
-use self::button::AsciiButton;
+use self::{button::AsciiButton, window::AsciiWindow, slider::AsciiSlider, spinner::AsciiSpinner, tabs::AsciiTabs, toggle::AsciiToggle, segmented::AsciiSegmented, number::AsciiNumber, list::AsciiList, text_input::AsciiTextInput, console::AsciiConsole, autocomplete::AsciiAutocomplete, replay::AsciiReplay, minimap::AsciiMinimap, noise::AsciiNoisePanel, tilemap::AsciiTilemap, animation::AsciiAnimatedGlyph, big_text::AsciiBigText, rich_text::AsciiRichText, virtual_keyboard::AsciiVirtualKeyboard};
 
 use super::{
-    bounds::{AsciiBounds, AsciiNode},
-    buffer::AsciiBuffer, AsciiMarkDirtyEvent,
+    accessibility::{update_accessibility_tree, AsciiAccessibilityTree, AsciiAccessible},
+    bounds::{AsciiBounds, AsciiNode, AsciiPinned},
+    buffer::AsciiBuffer, character::AsciiBlendMode, diagnostics::AsciiUiRenderStats,
+    position::{mark_positions_dirty, AsciiContentPadding, AsciiIntrinsicSize}, event::register_widget_events, lightmap::AsciiLightmap, transition::AsciiTransitionMask, opacity::AsciiInheritedOpacity, util::{fire_hotkeys, AsciiCursor, AsciiHotkeyDisabled, AsciiInputPlugin, AsciiKeyboardFocus}, validation::{validate_component, validation_enabled},
+    AsciiMarkDirtyEvent, AsciiRedrawTimer, AsciiUiPlugin, AsciiUiSchedule, AsciiUiSet, AsciiUiTarget, Padding, is_detached, ui_target,
 };
 
 //=============================================================================
 //             Components Plugin
 //=============================================================================
 
-pub struct AsciiDefaultComponentsPlugin;
+// Registers all of the built in components. This is part of the `AsciiEffectPluginGroup`, and
+// adds the ui and input plugins it relies on when they are missing.
+pub struct AsciiDefaultWidgetsPlugin;
 
-impl Plugin for AsciiDefaultComponentsPlugin {
+impl Plugin for AsciiDefaultWidgetsPlugin {
     fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<AsciiUiPlugin>() {
+            app.add_plugins(AsciiUiPlugin);
+        }
+        if !app.is_plugin_added::<AsciiInputPlugin>() {
+            app.add_plugins(AsciiInputPlugin);
+        }
+
         app
-            
             .add_plugins(AsciiComponentPlugin::<AsciiButton>::default())
+            .add_plugins(AsciiComponentPlugin::<AsciiWindow>::default())
+            .add_plugins(AsciiComponentPlugin::<AsciiSlider>::default())
+            .add_plugins(AsciiComponentPlugin::<AsciiSpinner>::default())
+            .add_plugins(AsciiComponentPlugin::<AsciiTabs>::default())
+            .add_plugins(AsciiComponentPlugin::<AsciiToggle>::default())
+            .add_plugins(AsciiComponentPlugin::<AsciiSegmented>::default())
+            .add_plugins(AsciiComponentPlugin::<AsciiNumber>::default())
+            .add_plugins(AsciiComponentPlugin::<AsciiList>::default())
+            .add_plugins(AsciiComponentPlugin::<AsciiTextInput>::default())
+            .add_plugins(AsciiComponentPlugin::<AsciiConsole>::default())
+            .add_plugins(AsciiComponentPlugin::<AsciiAutocomplete>::default())
+            .add_plugins(AsciiComponentPlugin::<AsciiReplay>::default())
+            .add_plugins(AsciiComponentPlugin::<AsciiMinimap>::default())
+            .add_plugins(AsciiComponentPlugin::<AsciiNoisePanel>::default())
+            .add_plugins(AsciiComponentPlugin::<AsciiTilemap>::default())
+            .add_plugins(AsciiComponentPlugin::<AsciiAnimatedGlyph>::default())
+            .add_plugins(AsciiComponentPlugin::<AsciiBigText>::default())
+            .add_plugins(AsciiComponentPlugin::<AsciiRichText>::default())
+            .add_plugins(AsciiComponentPlugin::<AsciiVirtualKeyboard>::default())
             .register_type::<AsciiButton>()
+            .register_type::<AsciiWindow>()
+            .register_type::<AsciiSlider>()
+            .register_type::<AsciiSpinner>()
+            .register_type::<AsciiTabs>()
+            .register_type::<AsciiToggle>()
+            .register_type::<AsciiSegmented>()
+            .register_type::<AsciiNumber>()
+            .register_type::<AsciiList>()
+            .register_type::<AsciiTextInput>()
+            .register_type::<AsciiConsole>()
+            .register_type::<AsciiAutocomplete>()
+            .register_type::<AsciiReplay>()
+            .register_type::<AsciiMinimap>()
+            .register_type::<AsciiNoisePanel>()
+            .register_type::<AsciiTilemap>()
+            .register_type::<AsciiAnimatedGlyph>()
+            .register_type::<AsciiBigText>()
+            .register_type::<AsciiRichText>()
+            .register_type::<AsciiVirtualKeyboard>()
         ;
+
+        register_widget_events(app);
     }
 }
 
@@ -52,18 +126,121 @@ impl<AC: AsciiComponent> Default for AsciiComponentPlugin<AC> {
 
 impl<AC: AsciiComponent> Plugin for AsciiComponentPlugin<AC> {
     fn build(&self, app: &mut App) {
+        // The same component can be registered by several plugins, only the first one counts.
+        let mut registry = app.world.get_resource_or_insert_with(AsciiComponentRegistry::default);
+        if !registry.components.insert(TypeId::of::<AC>()) {
+            debug!("{} is already registered", std::any::type_name::<AC>());
+            return;
+        }
+
         AC::set_up(app);
-        app.add_systems(Update, update_components::<AC>);
+        app.add_event::<AsciiUpdateRequestEvent>();
+        let update = update_components::<AC>.in_set(AsciiUiSet::Interaction);
+        match AC::update_mode() {
+            AsciiUpdateMode::EveryFrame => app.add_systems(Update, update),
+            AsciiUpdateMode::OnEvent(run_if) => app.add_systems(Update, run_if(update)),
+            AsciiUpdateMode::OnCursorMoved => app.add_systems(Update, update.run_if(cursor_moved)),
+            AsciiUpdateMode::Manual => app.add_systems(
+                Update,
+                update_requested_components::<AC>.in_set(AsciiUiSet::Interaction),
+            ),
+        };
+        app.add_systems(
+            Update,
+            update_keyboard_state::<AC>
+                .after(update_components::<AC>)
+                .after(update_requested_components::<AC>)
+                .before(fire_hotkeys)
+                .in_set(AsciiUiSet::Interaction),
+        );
+        app.add_systems(AsciiUiSchedule, mark_changed_components::<AC>.in_set(AsciiUiSet::Layout));
+        app.add_systems(
+            AsciiUiSchedule,
+            (update_content_padding::<AC>, update_intrinsic_size::<AC>)
+                .before(mark_positions_dirty)
+                .in_set(AsciiUiSet::Layout),
+        );
+        app.add_systems(
+            AsciiUiSchedule,
+            validate_component::<AC>.in_set(AsciiUiSet::Extract).run_if(validation_enabled),
+        );
+        app.add_systems(
+            AsciiUiSchedule,
+            update_accessibility_tree::<AC>
+                .run_if(resource_exists::<AsciiAccessibilityTree>)
+                .in_set(AsciiUiSet::Extract),
+        );
 
         let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
             return;
         };
 
+        // Shared by the extraction of every component.
+        if !render_app.world.contains_resource::<ExtractedUiNodes>() {
+            render_app
+                .init_resource::<ExtractedUiNodes>()
+                .add_systems(ExtractSchedule, extract_ui_nodes.after(apply_deferred));
+        }
         render_app.add_systems(
             ExtractSchedule,
-            extract_ascii_ui::<AC>.after(apply_deferred),
+            extract_ascii_ui::<AC>.after(extract_ui_nodes),
         );
     }
+
+    fn is_unique(&self) -> bool {
+        false
+    }
+}
+
+// Keeps track of which components have had their systems added.
+#[derive(Resource, Default)]
+pub struct AsciiComponentRegistry {
+    components: HashSet<TypeId>,
+}
+
+impl AsciiComponentRegistry {
+    pub fn is_registered<AC: AsciiComponent>(&self) -> bool {
+        self.components.contains(&TypeId::of::<AC>())
+    }
+
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+}
+
+// Custom components that are registered along with the built-in widgets, so they do not each need
+// an `AsciiComponentPlugin`. Given to `AsciiShaderPlugin::components`.
+#[derive(Default, Clone)]
+pub struct AsciiComponents {
+    registrations: Vec<fn(&mut App)>,
+}
+
+impl AsciiComponents {
+    pub fn add<AC: AsciiComponent>(&mut self) -> &mut Self {
+        self.registrations.push(|app| {
+            app.register_ascii_component::<AC>();
+        });
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.registrations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.registrations.is_empty()
+    }
+
+    // Adds the systems of every component to the `AsciiComponentRegistry` of the app.
+    pub fn register(&self, app: &mut App) {
+        for registration in self.registrations.iter() {
+            registration(app);
+        }
+    }
 }
 
 //=============================================================================
@@ -79,41 +256,291 @@ fn update_components<C: AsciiComponent>(
         if !is_visible {
             continue;
         }
-        component.update(&mut (*query), &global_bounds.bounds, entity);
+        // Components send their own dirty events from `update`, going through change detection
+        // here would redraw every frame.
+        component.bypass_change_detection().update(&mut (*query), &global_bounds.bounds, entity);
+    }
+}
+
+// Only the nodes an `AsciiUpdateRequestEvent` was sent for, for components with
+// `AsciiUpdateMode::Manual`.
+fn update_requested_components<C: AsciiComponent>(
+    mut requests: EventReader<AsciiUpdateRequestEvent>,
+    mut nodes: Query<(&mut C, &AsciiNode, Option<&InheritedVisibility>)>,
+    mut query: StaticSystemParam<C::UpdateQuery<'_, '_>>,
+) {
+    // A node that was asked more than once in a frame is still only updated once.
+    let requested = requests.read().map(|request| request.entity).collect::<HashSet<_>>();
+    for entity in requested {
+        let Ok((mut component, global_bounds, visibility)) = nodes.get_mut(entity) else {
+            continue;
+        };
+        if !visibility.map(|v| v.get()).unwrap_or(true) {
+            continue;
+        }
+        component.bypass_change_detection().update(&mut (*query), &global_bounds.bounds, entity);
+    }
+}
+
+// Keeps the `AsciiContentPadding` of the nodes of a container up to date, so its children are
+// laid out inside of it.
+#[allow(clippy::type_complexity)]
+fn update_content_padding<C: AsciiComponent>(
+    mut commands: Commands,
+    nodes: Query<(Entity, &C, Option<&AsciiContentPadding>), (With<AsciiNode>, Changed<C>)>,
+) {
+    for (entity, component, current) in nodes.iter() {
+        let Some(padding) = component.content_padding() else {
+            continue;
+        };
+        if current.map(|current| current.0) != Some(padding) {
+            commands.entity(entity).insert(AsciiContentPadding(padding));
+        }
     }
 }
 
+// Measures the nodes of a component again when it changed, before the ui is laid out.
+#[allow(clippy::type_complexity)]
+fn update_intrinsic_size<C: AsciiComponent>(
+    mut commands: Commands,
+    nodes: Query<(Entity, &C, Option<&AsciiIntrinsicSize>), (With<AsciiNode>, Changed<C>)>,
+) {
+    for (entity, component, current) in nodes.iter() {
+        let Some(size) = component.measure() else {
+            continue;
+        };
+        let size = AsciiIntrinsicSize { width: size.x, height: size.y };
+        if current != Some(&size) {
+            commands.entity(entity).insert(size);
+        }
+    }
+}
+
+fn cursor_moved(cursors: Query<(), Changed<AsciiCursor>>) -> bool {
+    !cursors.is_empty()
+}
+
+// Anything else that changes a component, like a system with a `Query<&mut AsciiButton>`,
+// redraws the ui.
+fn mark_changed_components<C: AsciiComponent>(
+    changed: Query<(), Changed<C>>,
+    mut removed: RemovedComponents<C>,
+    mut events: EventWriter<AsciiMarkDirtyEvent>,
+) {
+    if !changed.is_empty() || removed.read().count() > 0 {
+        events.send(AsciiMarkDirtyEvent);
+    }
+}
+
+// Where a ui node is drawn, from the nodes above it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct ExtractedUiNode {
+    // The camera the node is drawn on, see `ui_target`. `None` is every camera.
+    pub target: Option<Entity>,
+    // The part of the screen the node can draw to, see `clip_bounds`.
+    pub clip: Option<AsciiBounds>,
+    // The mask of the transition the node is part of, see `transition_mask`.
+    pub mask: Option<AsciiTransitionMask>,
+    // Whether the node is drawn on the live layer, because it or a node above it has an
+    // `AsciiRedrawTimer`.
+    pub is_live: bool,
+}
+
+// The `ExtractedUiNode` of every node of this frame. It is worked out once from the roots of the
+// ui down, instead of every component walking up the hierarchy of each of its nodes. The nodes
+// of a tree that is despawned this frame are left out, so they are not drawn where it was.
+#[derive(Resource, Default)]
+pub(crate) struct ExtractedUiNodes(pub HashMap<Entity, ExtractedUiNode>);
+
+#[allow(clippy::type_complexity)]
+fn extract_ui_nodes(
+    mut extracted: ResMut<ExtractedUiNodes>,
+    nodes: Extract<Query<(Entity, &AsciiNode, Option<&Parent>, Option<&Children>, Option<&AsciiUiTarget>, Has<AsciiPinned>)>>,
+    hierarchy: Extract<Query<(Option<&Parent>, Option<&AsciiUiTarget>, Has<AsciiCamera>)>>,
+    clip_nodes: Extract<ClipNodes>,
+    masks: Extract<Query<&AsciiTransitionMask>>,
+    timers: Extract<Query<(), With<AsciiRedrawTimer>>>,
+) {
+    extracted.0.clear();
+
+    // Only the roots look up the hierarchy, at the cameras and entities above the ui.
+    let mut stack = nodes
+        .iter()
+        .filter(|(_, _, parent, ..)| parent.map(|parent| !nodes.contains(parent.get())).unwrap_or(true))
+        .filter(|(entity, ..)| !is_detached(*entity, &hierarchy))
+        .map(|(entity, ..)| {
+            let node = ExtractedUiNode {
+                target: ui_target(entity, &hierarchy),
+                clip: clip_bounds(entity, &clip_nodes),
+                mask: transition_mask(entity, &clip_nodes, &masks),
+                is_live: timers.contains(entity),
+            };
+            (entity, node)
+        })
+        .collect::<Vec<_>>();
+
+    while let Some((entity, node)) = stack.pop() {
+        if let Ok((_, ascii_node, _, Some(children), ..)) = nodes.get(entity) {
+            // What the children of the node are clipped by, the same as `clip_bounds` works it out.
+            let children_clip = match (*ascii_node.clip_bounds, &node.clip) {
+                (true, Some(clip)) => Some(ascii_node.bounds.intersection(clip)),
+                (true, None) => Some(ascii_node.bounds.clone()),
+                (false, clip) => clip.clone(),
+            };
+            for child in children.iter() {
+                let Ok((_, _, _, _, target, is_pinned)) = nodes.get(*child) else {
+                    continue;
+                };
+                let child_node = ExtractedUiNode {
+                    target: target.map(|target| target.0).or(node.target),
+                    clip: if is_pinned { None } else { children_clip.clone() },
+                    mask: masks.get(*child).ok().copied().or(node.mask),
+                    is_live: node.is_live || timers.contains(*child),
+                };
+                stack.push((*child, child_node));
+            }
+        }
+        extracted.0.insert(entity, node);
+    }
+}
+
+#[allow(clippy::type_complexity)]
 pub fn extract_ascii_ui<C: AsciiComponent>(
-    ascii_cameras: Query<(&OverlayBuffer, Option<&RenderLayers>), With<AsciiCamera>>,
-    ui_elements: Extract<Query<(&AsciiNode, &C, Option<&RenderLayers>, Option<&InheritedVisibility>)>>,
+    ascii_cameras: Query<(Entity, Option<&OverlayBuffer>, Option<&LiveOverlayBuffer>, Option<&RenderLayers>), With<AsciiCamera>>,
+    ui_elements: Extract<Query<(Entity, &AsciiNode, &C, Option<&RenderLayers>, Option<&InheritedVisibility>, Option<&AsciiBlendMode>, Option<&AsciiInheritedOpacity>, Option<&AsciiLightmap>)>>,
+    extracted: Res<ExtractedUiNodes>,
+    stats: Option<Res<AsciiUiRenderStats>>,
 ) {    
-    for (buffer, camera_render_layers) in ascii_cameras.iter() {
-        for (global_bounds, component, component_render_layer, visibility) in ui_elements.iter() {
-            if let Some(visibility) = visibility {
-                if !visibility.get() {
-                    continue;
-                }
+    for (entity, global_bounds, component, component_render_layer, visibility, blend, opacity, lightmap) in ui_elements.iter() {
+        if let Some(visibility) = visibility {
+            if !visibility.get() {
+                continue;
             }
-            match (component_render_layer, camera_render_layers) {
-                (Some(_), None) | (None, Some(_)) => {
-                    continue;
-                }
-                (Some(component_render_layer), Some(camera_render_layers)) => {
-                    if !component_render_layer.intersects(camera_render_layers) {
-                        continue;
-                    }
-                }
-                (None, None) => {}
+        }
+        if opacity.map(|opacity| opacity.get() <= 0.0).unwrap_or(false) {
+            continue;
+        }
+
+        let Some(extracted) = extracted.0.get(&entity) else {
+            continue;
+        };
+        let target = extracted.target;
+        let is_live = extracted.is_live;
+        let clip = extracted.clip.clone();
+        let mask = extracted.mask;
+        // Like the rest of bevy, no render layers means layer 0.
+        let component_render_layer = component_render_layer.cloned().unwrap_or_default();
+
+        for (camera, buffer, live_buffer, camera_render_layers) in ascii_cameras.iter() {
+            // Live nodes are drawn with the live layer, the rest with the ui.
+            let surface = match is_live {
+                true => live_buffer.map(|buffer| &buffer.0),
+                false => buffer.map(|buffer| &buffer.0),
+            };
+            let Some(surface) = surface else {
+                continue;
+            };
+            if target.map(|target| target != camera).unwrap_or(false) {
+                continue;
+            }
+            if !component_render_layer.intersects(&camera_render_layers.cloned().unwrap_or_default()) {
+                continue;
             }
 
-            let surface = &buffer.0;
-            let mut buffer = AsciiBuffer::new(surface, &global_bounds.bounds, None);
+            let mut buffer = AsciiBuffer::new(surface, &global_bounds.bounds, clip.clone());
+            if let Some(blend) = blend {
+                buffer = buffer.blend(*blend);
+            }
+            if let Some(opacity) = opacity {
+                buffer = buffer.opacity(opacity.get());
+            }
+            if let Some(lightmap) = lightmap {
+                buffer = buffer.lightmap(lightmap, IVec2::ZERO);
+            }
+            if let Some(mask) = mask {
+                buffer = buffer.transition_mask(mask);
+            }
             
             component.render(&mut buffer);
+            if let Some(stats) = &stats {
+                stats.add_component();
+            }
         }
     }
 }
 
+// Keeps the `AsciiKeyboardFocus` and `AsciiHotkeyDisabled` of the nodes up to date. Components
+// change in `update` without change detection, so every node is looked at.
+#[allow(clippy::type_complexity)]
+fn update_keyboard_state<C: AsciiComponent>(
+    mut commands: Commands,
+    nodes: Query<(Entity, &C, &AsciiNode, Option<&AsciiKeyboardFocus>, Has<AsciiHotkeyDisabled>)>,
+    mut removed: RemovedComponents<C>,
+) {
+    for (entity, component, node, focus, is_disabled) in nodes.iter() {
+        let cell = component.keyboard_focus(&node.bounds);
+        if focus.map(|focus| focus.0) != cell {
+            match cell {
+                Some(cell) => commands.entity(entity).insert(AsciiKeyboardFocus(cell)),
+                None => commands.entity(entity).remove::<AsciiKeyboardFocus>(),
+            };
+        }
+        if component.accepts_hotkeys() == is_disabled {
+            match is_disabled {
+                true => commands.entity(entity).remove::<AsciiHotkeyDisabled>(),
+                false => commands.entity(entity).insert(AsciiHotkeyDisabled),
+            };
+        }
+    }
+
+    for entity in removed.read() {
+        if let Some(mut entity) = commands.get_entity(entity) {
+            entity.remove::<(AsciiKeyboardFocus, AsciiHotkeyDisabled)>();
+        }
+    }
+}
+
+pub(crate) type ClipNodes<'w, 's> = Query<'w, 's, (&'static AsciiNode, Option<&'static Parent>, Has<AsciiPinned>)>;
+
+// The part of the screen a node can draw to, which is the overlap of every node above it that
+// clips its children. `None` when nothing above it clips. A pinned node, and the nodes under it,
+// are not clipped by the nodes above the pinned one.
+pub(crate) fn clip_bounds(entity: Entity, nodes: &ClipNodes) -> Option<AsciiBounds> {
+    let mut clip: Option<AsciiBounds> = None;
+    let Ok((_, mut parent, mut is_pinned)) = nodes.get(entity) else {
+        return None;
+    };
+    while !is_pinned {
+        let Some((node, next_parent, next_is_pinned)) = parent.and_then(|parent| nodes.get(parent.get()).ok()) else {
+            break;
+        };
+        if *node.clip_bounds {
+            clip = Some(match clip {
+                Some(clip) => clip.intersection(&node.bounds),
+                None => node.bounds.clone(),
+            });
+        }
+        (parent, is_pinned) = (next_parent, next_is_pinned);
+    }
+    clip
+}
+
+// The mask of the screen in a transition the node is part of, if there is one.
+fn transition_mask(
+    entity: Entity,
+    nodes: &ClipNodes,
+    masks: &Query<&AsciiTransitionMask>,
+) -> Option<AsciiTransitionMask> {
+    let mut current = Some(entity);
+    while let Some(node) = current {
+        if let Ok(mask) = masks.get(node) {
+            return Some(*mask);
+        }
+        current = nodes.get(node).ok().and_then(|(_, parent, _)| parent.map(|parent| parent.get()));
+    }
+    None
+}
+
 //=============================================================================
 //             AsciiComponent Trait
 //=============================================================================
@@ -134,4 +561,321 @@ pub trait AsciiComponent: Component {
 
     #[allow(unused_variables)]
     fn set_up(app: &mut App) {}
-}
\ No newline at end of file
+
+    // When `update` runs. Components that only react to something in particular can skip the
+    // frames where it did not happen.
+    fn update_mode() -> AsciiUpdateMode {
+        AsciiUpdateMode::EveryFrame
+    }
+
+    // What the component is for screen readers, see `AsciiAccessibilityPlugin`.
+    fn accessibility(&self) -> Option<AsciiAccessible> {
+        None
+    }
+
+    // Containers, like a window with a border, keep the cells around their children to
+    // themselves. It is written to the `AsciiContentPadding` of the node whenever the component
+    // changes. `None` leaves the node as it is.
+    fn content_padding(&self) -> Option<Padding> {
+        None
+    }
+
+    // The size the component needs to show all of its content, like its text. It is written to
+    // the `AsciiIntrinsicSize` of the node whenever the component changes, for positions with a
+    // `Value::Auto` width or height.
+    fn measure(&self) -> Option<UVec2> {
+        None
+    }
+
+    // The cell of the caret while the component takes the keys that are typed, like a focused
+    // text field. Hotkeys do not fire while a node does, and the input method of the window is
+    // placed under the cell. It is written to the `AsciiKeyboardFocus` of the node.
+    #[allow(unused_variables)]
+    fn keyboard_focus(&self, bounds: &AsciiBounds) -> Option<IVec2> {
+        None
+    }
+
+    // Whether the `AsciiHotkey` of the node fires right now, a disabled button turns it off.
+    fn accepts_hotkeys(&self) -> bool {
+        true
+    }
+}
+
+//=============================================================================
+//             Update Mode
+//=============================================================================
+
+// When the `update` of every node of a component runs, see `AsciiComponent::update_mode`. Nodes
+// are not updated when they are spawned, only when their mode says so.
+#[derive(Clone, Copy, Debug)]
+pub enum AsciiUpdateMode {
+    // Every frame, for components that animate or poll the input themselves.
+    EveryFrame,
+    // Only on frames where an event was sent, made with `AsciiUpdateMode::on_event`.
+    OnEvent(fn(SystemConfigs) -> SystemConfigs),
+    // Only on frames where the `AsciiCursor` of a window moved to another cell. Clicks do not
+    // move the cursor, so components that are clicked want `on_event::<MouseButtonInput>`.
+    OnCursorMoved,
+    // Only for the nodes an `AsciiUpdateRequestEvent` was sent for.
+    Manual,
+}
+
+impl AsciiUpdateMode {
+    pub fn on_event<E: Event>() -> Self {
+        AsciiUpdateMode::OnEvent(|update| update.run_if(on_event::<E>()))
+    }
+}
+
+// Updates a node of a component with `AsciiUpdateMode::Manual` in the next `AsciiUiSet::Interaction`.
+#[derive(Event, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AsciiUpdateRequestEvent {
+    pub entity: Entity,
+}
+
+impl AsciiUpdateRequestEvent {
+    pub fn new(entity: Entity) -> Self {
+        AsciiUpdateRequestEvent { entity }
+    }
+}
+
+//=============================================================================
+//             Ascii Widget
+//=============================================================================
+
+// A simpler version of `AsciiComponent` for components that only need to draw themselves.
+// Every `AsciiWidget` is also an `AsciiComponent`, so it is registered the same way.
+pub trait AsciiWidget: Component {
+    fn render(&self, buffer: &mut AsciiBuffer);
+
+    fn accessibility(&self) -> Option<AsciiAccessible> {
+        None
+    }
+
+    fn content_padding(&self) -> Option<Padding> {
+        None
+    }
+
+    fn measure(&self) -> Option<UVec2> {
+        None
+    }
+}
+
+impl<W: AsciiWidget> AsciiComponent for W {
+    type UpdateQuery<'w, 's> = ();
+
+    // Widgets have nothing to update.
+    fn update_mode() -> AsciiUpdateMode {
+        AsciiUpdateMode::Manual
+    }
+
+    fn render(&self, buffer: &mut AsciiBuffer) {
+        AsciiWidget::render(self, buffer);
+    }
+
+    fn accessibility(&self) -> Option<AsciiAccessible> {
+        AsciiWidget::accessibility(self)
+    }
+
+    fn content_padding(&self) -> Option<Padding> {
+        AsciiWidget::content_padding(self)
+    }
+
+    fn measure(&self) -> Option<UVec2> {
+        AsciiWidget::measure(self)
+    }
+}
+
+//=============================================================================
+//             App Extention
+//=============================================================================
+
+pub trait AsciiComponentAppExtention {
+    // Adds the systems that update and render `T`. Same as adding `AsciiComponentPlugin::<T>`,
+    // registering a component more than once does nothing.
+    fn register_ascii_component<T: AsciiComponent>(&mut self) -> &mut Self;
+}
+
+impl AsciiComponentAppExtention for App {
+    fn register_ascii_component<T: AsciiComponent>(&mut self) -> &mut Self {
+        self.add_plugins(AsciiComponentPlugin::<T>::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::{RunSystemOnce, SystemState};
+
+    use super::*;
+
+    fn clip_of(world: &mut World, entity: Entity) -> Option<AsciiBounds> {
+        let mut state = SystemState::<ClipNodes>::new(world);
+        clip_bounds(entity, &state.get(world))
+    }
+
+    // A panel that clips, inside of a window that clips, with a node in the panel.
+    fn clip_world() -> (World, Entity, Entity) {
+        let mut world = World::new();
+        let window = world.spawn(AsciiNode::new(0, 0, 20, 10, 0).clipped()).id();
+        let panel = world.spawn(AsciiNode::new(5, 5, 20, 3, 1).clipped()).id();
+        let node = world.spawn(AsciiNode::new(6, 6, 40, 1, 2)).id();
+        world.entity_mut(window).add_child(panel);
+        world.entity_mut(panel).add_child(node);
+        (world, panel, node)
+    }
+
+    #[test]
+    fn clip_is_the_overlap_of_every_clipping_parent() {
+        let (mut world, panel, node) = clip_world();
+        assert_eq!(clip_of(&mut world, node), Some(AsciiBounds::new(5, 5, 15, 3, 1)));
+        assert_eq!(clip_of(&mut world, panel), Some(AsciiBounds::new(0, 0, 20, 10, 0)));
+
+        // Parents that do not clip are skipped.
+        world.get_mut::<AsciiNode>(panel).unwrap().clip_bounds = false.into();
+        assert_eq!(clip_of(&mut world, node), Some(AsciiBounds::new(0, 0, 20, 10, 0)));
+    }
+
+    #[test]
+    fn roots_are_not_clipped() {
+        let mut world = World::new();
+        let root = world.spawn(AsciiNode::new(0, 0, 5, 5, 0).clipped()).id();
+        assert_eq!(clip_of(&mut world, root), None);
+    }
+
+    #[test]
+    fn pinned_nodes_escape_the_clip() {
+        let (mut world, panel, node) = clip_world();
+        world.entity_mut(node).insert(AsciiPinned);
+        assert_eq!(clip_of(&mut world, node), None);
+
+        // The nodes under a pinned one are only clipped by the nodes up to the pinned one.
+        let label = world.spawn(AsciiNode::new(6, 6, 2, 1, 3)).id();
+        world.entity_mut(node).add_child(label);
+        assert_eq!(clip_of(&mut world, label), None);
+        world.get_mut::<AsciiNode>(node).unwrap().clip_bounds = true.into();
+        assert_eq!(clip_of(&mut world, label), Some(AsciiBounds::new(6, 6, 40, 1, 2)));
+
+        world.entity_mut(node).remove::<AsciiPinned>();
+        world.entity_mut(panel).insert(AsciiPinned);
+        assert_eq!(clip_of(&mut world, label), Some(AsciiBounds::new(6, 6, 19, 1, 2)));
+    }
+
+    #[test]
+    fn components_are_registered_once() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, bevy::input::InputPlugin, bevy::window::WindowPlugin::default()));
+
+        let mut components = AsciiComponents::default();
+        components.add::<AsciiButton>().add::<AsciiConsole>().add::<AsciiButton>();
+        assert_eq!(components.len(), 3);
+        components.register(&mut app);
+
+        let registry = app.world.resource::<AsciiComponentRegistry>();
+        assert!(registry.is_registered::<AsciiButton>());
+        assert!(registry.is_registered::<AsciiConsole>());
+        assert!(!registry.is_registered::<AsciiSlider>());
+        assert_eq!(registry.len(), 2);
+    }
+
+    fn extract_nodes(main_world: World) -> ExtractedUiNodes {
+        let mut render_world = World::new();
+        let mut main = bevy::render::MainWorld::default();
+        *main = main_world;
+        render_world.insert_resource(main);
+        render_world.init_resource::<ExtractedUiNodes>();
+        render_world.run_system_once(extract_ui_nodes);
+        render_world.remove_resource::<ExtractedUiNodes>().unwrap()
+    }
+
+    #[test]
+    fn nodes_under_a_redraw_timer_are_live() {
+        let mut world = World::new();
+        let window = world.spawn(AsciiNode::default()).id();
+        let marquee = world.spawn((AsciiNode::default(), AsciiRedrawTimer::default())).id();
+        let label = world.spawn(AsciiNode::default()).id();
+        let other = world.spawn(AsciiNode::default()).id();
+        world.entity_mut(window).push_children(&[marquee, other]);
+        world.entity_mut(marquee).add_child(label);
+
+        let extracted = extract_nodes(world);
+        assert!(!extracted.0[&window].is_live);
+        assert!(extracted.0[&marquee].is_live);
+        assert!(extracted.0[&label].is_live);
+        assert!(!extracted.0[&other].is_live);
+    }
+
+    #[test]
+    fn extracted_nodes_are_drawn_on_the_camera_above_them() {
+        let mut world = World::new();
+        let camera = world.spawn(AsciiCamera::default()).id();
+        let other_camera = world.spawn(AsciiCamera::default()).id();
+        let window = world.spawn(AsciiNode::default()).id();
+        let panel = world.spawn((AsciiNode::default(), AsciiUiTarget(other_camera))).id();
+        let label = world.spawn(AsciiNode::default()).id();
+        let floating = world.spawn(AsciiNode::default()).id();
+        world.entity_mut(camera).add_child(window);
+        world.entity_mut(window).add_child(panel);
+        world.entity_mut(panel).add_child(label);
+
+        let extracted = extract_nodes(world);
+        assert_eq!(extracted.0[&window].target, Some(camera));
+        // The closest target up the hierarchy wins.
+        assert_eq!(extracted.0[&panel].target, Some(other_camera));
+        assert_eq!(extracted.0[&label].target, Some(other_camera));
+        assert_eq!(extracted.0[&floating].target, None);
+    }
+
+    #[test]
+    fn keyboard_state_follows_the_components() {
+        let mut world = World::new();
+        let field = world.spawn((AsciiTextInput::default(), AsciiNode::new(4, 2, 10, 1, 0))).id();
+        let button = world.spawn((AsciiButton::from_string("Save").disabled(), AsciiNode::default())).id();
+        world.run_system_once(update_keyboard_state::<AsciiTextInput>);
+        world.run_system_once(update_keyboard_state::<AsciiButton>);
+        assert!(world.get::<AsciiKeyboardFocus>(field).is_none());
+        assert!(world.get::<AsciiHotkeyDisabled>(button).is_some());
+
+        world.get_mut::<AsciiTextInput>(field).unwrap().bypass_change_detection().focus();
+        world.get_mut::<AsciiButton>(button).unwrap().bypass_change_detection().set_disabled(false);
+        world.run_system_once(update_keyboard_state::<AsciiTextInput>);
+        world.run_system_once(update_keyboard_state::<AsciiButton>);
+        assert_eq!(world.get::<AsciiKeyboardFocus>(field), Some(&AsciiKeyboardFocus(IVec2::new(4, 2))));
+        assert!(world.get::<AsciiHotkeyDisabled>(button).is_none());
+
+        // Taking the component away takes its state with it.
+        world.entity_mut(field).remove::<AsciiTextInput>();
+        world.run_system_once(update_keyboard_state::<AsciiTextInput>);
+        assert!(world.get::<AsciiKeyboardFocus>(field).is_none());
+    }
+
+    #[test]
+    fn nodes_of_despawned_trees_are_not_extracted() {
+        let mut world = World::new();
+        let window = world.spawn(AsciiNode::default()).id();
+        let panel = world.spawn(AsciiNode::default()).id();
+        let label = world.spawn(AsciiNode::default()).id();
+        world.entity_mut(window).add_child(panel);
+        world.entity_mut(panel).add_child(label);
+        // Only the top of the tree is gone, like in the frame before its children are despawned.
+        world.despawn(window);
+
+        let extracted = extract_nodes(world);
+        assert!(!extracted.0.contains_key(&panel));
+        assert!(!extracted.0.contains_key(&label));
+    }
+
+    #[test]
+    fn extracted_clips_match_the_clip_of_each_node() {
+        let (mut world, panel, node) = clip_world();
+        let label = world.spawn(AsciiNode::new(6, 6, 2, 1, 3)).id();
+        let pinned = world.spawn((AsciiNode::new(6, 6, 2, 1, 3), AsciiPinned)).id();
+        world.entity_mut(node).insert(AsciiNode::new(6, 6, 40, 1, 2).clipped()).push_children(&[label, pinned]);
+
+        let entities = [panel, node, label, pinned];
+        let clips = entities.map(|entity| clip_of(&mut world, entity));
+        let extracted = extract_nodes(world);
+        for (entity, clip) in entities.iter().zip(clips) {
+            assert_eq!(extracted.0[entity].clip, clip);
+        }
+        assert_eq!(extracted.0[&label].clip, Some(AsciiBounds::new(6, 6, 14, 1, 2)));
+    }
+}
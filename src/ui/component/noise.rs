@@ -0,0 +1,135 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::ui::{
+    buffer::AsciiBuffer, character::{Character, Color}, time::AsciiUiTime, AsciiMarkDirtyEvent
+};
+
+use super::{super::bounds::AsciiBounds, AsciiComponent};
+
+//=============================================================================
+//             Ascii Noise Panel
+//=============================================================================
+
+// Fills its bounds with seeded random characters, for static, rain and background textures.
+// It can change the noise a number of times per second, and scroll it down to make it fall.
+#[derive(Component, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct AsciiNoisePanel {
+    seed: u64,
+    density: f32,
+    charset: Vec<Character>,
+    colors: Vec<Color>,
+    bg_color: Color,
+    frames_per_second: f32,
+    fall_speed: f32,
+    time: f32,
+    frame: u64,
+    offset: i32,
+}
+
+impl AsciiNoisePanel {
+    pub fn new(seed: u64) -> Self {
+        AsciiNoisePanel {
+            seed,
+            density: 0.5,
+            charset: "01".chars().map(Character::from).collect(),
+            colors: vec![Color::LightGreen, Color::Green],
+            bg_color: Color::Black,
+            frames_per_second: 0.0,
+            fall_speed: 0.0,
+            time: 0.0,
+            frame: 0,
+            offset: 0,
+        }
+    }
+
+    // How much of the panel is covered, from 0.0 to 1.0.
+    pub fn density(mut self, density: f32) -> Self {
+        self.density = density.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn charset(mut self, charset: &str) -> Self {
+        self.charset = charset.chars().map(Character::from).collect();
+        self
+    }
+
+    pub fn colors(mut self, colors: &[Color]) -> Self {
+        self.colors = colors.to_vec();
+        self
+    }
+
+    pub fn bg_color(mut self, color: Color) -> Self {
+        self.bg_color = color;
+        self
+    }
+
+    // Picks new noise this many times a second, 0.0 keeps it still.
+    pub fn animated(mut self, frames_per_second: f32) -> Self {
+        self.frames_per_second = frames_per_second.max(0.0);
+        self
+    }
+
+    // Scrolls the noise down by this many rows a second.
+    pub fn falling(mut self, rows_per_second: f32) -> Self {
+        self.fall_speed = rows_per_second;
+        self
+    }
+
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+    }
+}
+
+impl Default for AsciiNoisePanel {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+//=============================================================================
+//             Component Impl
+//=============================================================================
+
+impl AsciiComponent for AsciiNoisePanel {
+    type UpdateQuery<'w, 's> = (
+        Res<'w, AsciiUiTime>,
+        EventWriter<'w, AsciiMarkDirtyEvent>,
+    );
+
+    fn render(&self, buffer: &mut AsciiBuffer) {
+        for y in 0..buffer.bounds.height as i32 {
+            for x in 0..buffer.bounds.width as i32 {
+                buffer.set_character(x, y, (Character::Space, Color::White, self.bg_color));
+            }
+        }
+
+        buffer
+            .noise_fill(self.seed.wrapping_add(self.frame), self.density, &self.charset, &self.colors)
+            .bg_color(self.bg_color)
+            .offset(0, self.offset)
+            .draw();
+    }
+
+    fn update(
+        &mut self,
+        query: &mut <Self::UpdateQuery<'_, '_> as SystemParam>::Item<'_, '_>,
+        _bounds: &AsciiBounds,
+        _entity: Entity,
+    ) {
+        if self.frames_per_second <= 0.0 && self.fall_speed == 0.0 {
+            return;
+        }
+
+        let (time, dirty) = query;
+        self.time += time.delta_seconds();
+
+        let frame = (self.time * self.frames_per_second) as u64;
+        let offset = (self.time * self.fall_speed) as i32;
+        if frame != self.frame || offset != self.offset {
+            self.frame = frame;
+            self.offset = offset;
+            dirty.send(AsciiMarkDirtyEvent);
+        }
+    }
+}
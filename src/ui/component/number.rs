@@ -0,0 +1,294 @@
+use bevy::{
+    ecs::system::SystemParam, prelude::*
+};
+
+use crate::ui::{
+    buffer::AsciiBuffer, character::Color, time::AsciiUiTime, AsciiMarkDirtyEvent, HorizontalAlignment
+};
+
+use super::{super::{accessibility::{AsciiAccessible, AsciiAccessibleRole}, bounds::AsciiBounds}, AsciiComponent};
+
+//=============================================================================
+//             Ascii Number
+//=============================================================================
+
+// A number readout for things like scores and currency. The number is padded to a fixed width,
+// and counts up (or down) to a new value instead of jumping to it.
+#[derive(Component, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct AsciiNumber {
+    value: f64,
+    precision: usize,
+    width: usize,
+    padding: char,
+    separator: Option<char>,
+    prefix: String,
+    suffix: String,
+    count_duration: f32,
+    alignment: HorizontalAlignment,
+    text_color: Color,
+    bg_color: Color,
+    // The value the count started from and how long it has been counting, while counting.
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    counting_from: Option<(f64, f32)>,
+}
+
+impl AsciiNumber {
+    pub fn new(value: impl Into<f64>) -> Self {
+        AsciiNumber {
+            value: value.into(),
+            precision: 0,
+            width: 0,
+            padding: ' ',
+            separator: None,
+            prefix: String::new(),
+            suffix: String::new(),
+            count_duration: 0.5,
+            alignment: HorizontalAlignment::Right,
+            text_color: Color::White,
+            bg_color: Color::Black,
+            counting_from: None,
+        }
+    }
+
+    // The number of decimal places the value is displayed with.
+    pub fn precision(mut self, precision: usize) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    // The number is padded with the padding character until it is at least this many characters
+    // wide, not counting the prefix and suffix.
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    // A padding of '0' goes between the sign and the digits, so -42 becomes -0042.
+    pub fn padding(mut self, padding: char) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    // Groups the whole part of the number by thousands, like 1,234,567.
+    pub fn thousands_separator(mut self, separator: char) -> Self {
+        self.separator = Some(separator);
+        self
+    }
+
+    pub fn prefix(mut self, prefix: &str) -> Self {
+        self.prefix = prefix.to_string();
+        self
+    }
+
+    pub fn suffix(mut self, suffix: &str) -> Self {
+        self.suffix = suffix.to_string();
+        self
+    }
+
+    // How many seconds it takes to count to a new value. Zero jumps to it straight away.
+    pub fn count_duration(mut self, seconds: f32) -> Self {
+        self.count_duration = seconds.max(0.0);
+        self
+    }
+
+    pub fn alignment(mut self, alignment: HorizontalAlignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    pub fn text_color(mut self, color: Color) -> Self {
+        self.text_color = color;
+        self
+    }
+
+    pub fn bg_color(mut self, color: Color) -> Self {
+        self.bg_color = color;
+        self
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    // The value that is on screen right now, which trails `value` while counting.
+    pub fn displayed_value(&self) -> f64 {
+        match self.counting_from {
+            Some((from, elapsed)) if self.count_duration > 0.0 => {
+                let t = (elapsed / self.count_duration).clamp(0.0, 1.0) as f64;
+                let eased = 1.0 - (1.0 - t) * (1.0 - t);
+                from + (self.value - from) * eased
+            }
+            _ => self.value,
+        }
+    }
+
+    pub fn is_counting(&self) -> bool {
+        self.counting_from.is_some()
+    }
+
+    // Counts from what is currently displayed to the new value.
+    pub fn set_value(&mut self, value: impl Into<f64>) {
+        let value = value.into();
+        if value == self.value {
+            return;
+        }
+        let from = self.displayed_value();
+        self.value = value;
+        self.counting_from = Some((from, 0.0));
+    }
+
+    pub fn add(&mut self, amount: impl Into<f64>) {
+        self.set_value(self.value + amount.into());
+    }
+
+    // Jumps to the new value without counting.
+    pub fn snap_to(&mut self, value: impl Into<f64>) {
+        self.value = value.into();
+        self.counting_from = None;
+    }
+
+    pub fn text(&self) -> String {
+        format!("{}{}{}", self.prefix, self.format(self.displayed_value()), self.suffix)
+    }
+
+    fn format(&self, value: f64) -> String {
+        let formatted = format!("{:.*}", self.precision, value.abs());
+        let (whole, fraction) = match formatted.split_once('.') {
+            Some((whole, fraction)) => (whole, Some(fraction)),
+            None => (formatted.as_str(), None),
+        };
+
+        let mut digits = String::new();
+        for (i, c) in whole.chars().enumerate() {
+            if let Some(separator) = self.separator {
+                if i > 0 && (whole.len() - i) % 3 == 0 {
+                    digits.push(separator);
+                }
+            }
+            digits.push(c);
+        }
+        if let Some(fraction) = fraction {
+            digits.push('.');
+            digits.push_str(fraction);
+        }
+
+        // Rounding can turn a small negative number into zero, which should not get a sign.
+        let sign = if value < 0.0 && formatted.chars().any(|c| c.is_ascii_digit() && c != '0') { "-" } else { "" };
+        let padding_width = self.width.saturating_sub(sign.len() + digits.chars().count());
+        let padding = self.padding.to_string().repeat(padding_width);
+        if self.padding == '0' {
+            format!("{}{}{}", sign, padding, digits)
+        } else {
+            format!("{}{}{}", padding, sign, digits)
+        }
+    }
+}
+
+//=============================================================================
+//             Component Impl
+//=============================================================================
+
+impl AsciiComponent for AsciiNumber {
+    type UpdateQuery<'w, 's> = (
+        Res<'w, AsciiUiTime>,
+        EventWriter<'w, AsciiMarkDirtyEvent>,
+    );
+
+    fn render(&self, buffer: &mut AsciiBuffer) {
+        if buffer.bounds.width == 0 || buffer.bounds.height == 0 {
+            return;
+        }
+
+        let text = self.text();
+        buffer
+            .text(&text)
+            .horizontal_alignment(self.alignment)
+            .text_color(self.text_color)
+            .bg_color(self.bg_color)
+            .draw();
+    }
+
+    fn update(
+        &mut self,
+        query: &mut <Self::UpdateQuery<'_, '_> as SystemParam>::Item<'_, '_>,
+        _bounds: &AsciiBounds,
+        _entity: Entity,
+    ) {
+        let (time, dirty) = query;
+        let Some((from, elapsed)) = self.counting_from else {
+            return;
+        };
+
+        let old_text = self.text();
+        let elapsed = elapsed + time.delta_seconds();
+        self.counting_from = if elapsed < self.count_duration { Some((from, elapsed)) } else { None };
+
+        // Only redraw when the count moved far enough to change what is on screen.
+        if self.text() != old_text {
+            dirty.send(AsciiMarkDirtyEvent);
+        }
+    }
+
+    fn accessibility(&self) -> Option<AsciiAccessible> {
+        Some(AsciiAccessible::new(AsciiAccessibleRole::Text, "").value(format!("{}{}{}", self.prefix, self.format(self.value), self.suffix)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thousands_are_separated() {
+        assert_eq!(AsciiNumber::new(1234567).thousands_separator(',').text(), "1,234,567");
+        assert_eq!(AsciiNumber::new(999).thousands_separator(',').text(), "999");
+        assert_eq!(AsciiNumber::new(1000).thousands_separator('.').text(), "1.000");
+        assert_eq!(AsciiNumber::new(1234.5).precision(2).thousands_separator(',').text(), "1,234.50");
+        assert_eq!(AsciiNumber::new(1234567).text(), "1234567");
+    }
+
+    #[test]
+    fn negative_numbers_keep_their_sign_in_front() {
+        assert_eq!(AsciiNumber::new(-42).text(), "-42");
+        assert_eq!(AsciiNumber::new(-42).width(5).text(), "  -42");
+        assert_eq!(AsciiNumber::new(-42).width(5).padding('0').text(), "-0042");
+        assert_eq!(AsciiNumber::new(-1234).thousands_separator(',').text(), "-1,234");
+        // The width is a minimum, wider numbers are not cut.
+        assert_eq!(AsciiNumber::new(-12345).width(3).text(), "-12345");
+    }
+
+    #[test]
+    fn values_are_rounded_to_the_precision() {
+        assert_eq!(AsciiNumber::new(1.23456).precision(3).text(), "1.235");
+        assert_eq!(AsciiNumber::new(1.23449).precision(3).text(), "1.234");
+        // Halves go to the even digit, like `format!`.
+        assert_eq!(AsciiNumber::new(2.5).precision(0).text(), "2");
+        assert_eq!(AsciiNumber::new(9.996).precision(2).text(), "10.00");
+        // Rounding up can add a group of thousands.
+        assert_eq!(AsciiNumber::new(999.999).precision(2).thousands_separator(',').text(), "1,000.00");
+        // Numbers that round to zero are not negative.
+        assert_eq!(AsciiNumber::new(-0.004).precision(2).text(), "0.00");
+        assert_eq!(AsciiNumber::new(-0.4).text(), "0");
+    }
+
+    #[test]
+    fn prefix_and_suffix_are_not_padded() {
+        let number = AsciiNumber::new(7).width(3).padding('0').prefix("$").suffix(" gold");
+        assert_eq!(number.text(), "$007 gold");
+    }
+
+    #[test]
+    fn counting_eases_towards_the_new_value() {
+        let mut number = AsciiNumber::new(0).count_duration(1.0);
+        number.set_value(100);
+        assert_eq!(number.displayed_value(), 0.0);
+        number.counting_from = Some((0.0, 0.5));
+        assert_eq!(number.displayed_value(), 75.0);
+        assert_eq!(number.text(), "75");
+
+        number.snap_to(10);
+        assert!(!number.is_counting());
+        assert_eq!(number.text(), "10");
+    }
+}
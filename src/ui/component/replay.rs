@@ -0,0 +1,138 @@
+use std::sync::Arc;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::{
+    record::AsciiRecording,
+    ui::{buffer::AsciiBuffer, time::AsciiUiTime, AsciiMarkDirtyEvent},
+};
+
+use super::{super::bounds::AsciiBounds, AsciiComponent};
+
+//=============================================================================
+//             Ascii Replay
+//=============================================================================
+
+// Plays an `AsciiRecording` back inside its bounds. The recording is drawn from the top left
+// corner and cut off at the edges of the bounds.
+#[derive(Component, Reflect)]
+pub struct AsciiReplay {
+    #[reflect(ignore)]
+    recording: Arc<AsciiRecording>,
+    time: f32,
+    speed: f32,
+    is_playing: bool,
+    is_looping: bool,
+    #[reflect(ignore)]
+    frame: Option<usize>,
+}
+
+impl AsciiReplay {
+    pub fn new(recording: impl Into<Arc<AsciiRecording>>) -> Self {
+        AsciiReplay {
+            recording: recording.into(),
+            time: 0.0,
+            speed: 1.0,
+            is_playing: true,
+            is_looping: false,
+            frame: None,
+        }
+    }
+
+    pub fn looping(mut self) -> Self {
+        self.is_looping = true;
+        self
+    }
+
+    pub fn paused(mut self) -> Self {
+        self.is_playing = false;
+        self
+    }
+
+    pub fn speed(mut self, speed: f32) -> Self {
+        self.speed = speed.max(0.0);
+        self
+    }
+
+    pub fn play(&mut self) {
+        self.is_playing = true;
+    }
+
+    pub fn pause(&mut self) {
+        self.is_playing = false;
+    }
+
+    pub fn restart(&mut self) {
+        self.time = 0.0;
+    }
+
+    pub fn seek(&mut self, time: f32) {
+        self.time = time.clamp(0.0, self.recording.duration());
+    }
+
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.is_playing
+    }
+
+    pub fn is_finished(&self) -> bool {
+        !self.is_looping && self.time >= self.recording.duration()
+    }
+
+    pub fn recording(&self) -> &AsciiRecording {
+        &self.recording
+    }
+}
+
+//=============================================================================
+//             Component Impl
+//=============================================================================
+
+impl AsciiComponent for AsciiReplay {
+    type UpdateQuery<'w, 's> = (Res<'w, AsciiUiTime>, EventWriter<'w, AsciiMarkDirtyEvent>);
+
+    fn render(&self, buffer: &mut AsciiBuffer) {
+        let Some(frame) = self.frame.and_then(|index| self.recording.frames().get(index)) else {
+            return;
+        };
+
+        let width = self.recording.width().min(buffer.bounds.width);
+        let height = self.recording.height().min(buffer.bounds.height);
+        for y in 0..height {
+            for x in 0..width {
+                let index = (y * self.recording.width() + x) as usize;
+                if let Some(cell) = frame.cells.get(index) {
+                    buffer.set_character(x as i32, y as i32, *cell);
+                }
+            }
+        }
+    }
+
+    fn update(
+        &mut self,
+        query: &mut <Self::UpdateQuery<'_, '_> as SystemParam>::Item<'_, '_>,
+        _bounds: &AsciiBounds,
+        _entity: Entity,
+    ) {
+        let (time, dirty) = query;
+        let duration = self.recording.duration();
+
+        if self.is_playing {
+            self.time += time.delta_seconds() * self.speed;
+            if self.is_looping && duration > 0.0 {
+                self.time %= duration;
+            } else {
+                self.time = self.time.min(duration);
+            }
+        }
+
+        let frame = self.recording.frame_index_at(self.time);
+        if frame != self.frame {
+            self.frame = frame;
+            dirty.send(AsciiMarkDirtyEvent);
+        }
+    }
+}
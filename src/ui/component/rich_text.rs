@@ -0,0 +1,187 @@
+use bevy::{ecs::system::SystemParam, prelude::*, window::PrimaryWindow};
+
+use crate::{
+    ascii::AsciiCamera,
+    ui::{
+        accessibility::{AsciiAccessible, AsciiAccessibleRole}, buffer::{AsciiBuffer, AsciiTextCache}, character::Color,
+        markup::AsciiMarkup, meta::AsciiCellMeta, ui_target, util::AsciiCursor, AsciiMarkDirtyEvent, AsciiUiTarget,
+        HorizontalAlignment, VerticalAlignment,
+    },
+};
+
+use super::{super::bounds::AsciiBounds, AsciiComponent};
+
+//=============================================================================
+//             Ascii Rich Text
+//=============================================================================
+
+// Text with clickable links, written as `AsciiMarkup`. Clicking a link sends an
+// `AsciiTextSpanClicked` with its target. The links are found through the cell metadata of the
+// overlay, so a node with metadata of its own drawn over the text takes the clicks instead.
+#[derive(Component, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct AsciiRichText {
+    markup: AsciiMarkup,
+    text_color: Color,
+    bg_color: Color,
+    link_color: Color,
+    hover_link_color: Color,
+    horizontal_alignment: HorizontalAlignment,
+    vertical_alignment: VerticalAlignment,
+    should_wrap: bool,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    hovered_link: Option<usize>,
+    #[reflect(ignore)]
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    text_cache: AsciiTextCache,
+}
+
+impl AsciiRichText {
+    pub fn new(markup: &str) -> Self {
+        AsciiRichText {
+            markup: AsciiMarkup::parse(markup),
+            text_color: Color::White,
+            bg_color: Color::Black,
+            link_color: Color::LightBlue,
+            hover_link_color: Color::Cyan,
+            horizontal_alignment: HorizontalAlignment::Left,
+            vertical_alignment: VerticalAlignment::Top,
+            should_wrap: true,
+            hovered_link: None,
+            text_cache: AsciiTextCache::default(),
+        }
+    }
+
+    pub fn text_color(mut self, text_color: Color) -> Self {
+        self.text_color = text_color;
+        self
+    }
+
+    pub fn bg_color(mut self, bg_color: Color) -> Self {
+        self.bg_color = bg_color;
+        self
+    }
+
+    pub fn link_color(mut self, link_color: Color) -> Self {
+        self.link_color = link_color;
+        self
+    }
+
+    pub fn hover_link_color(mut self, color: Color) -> Self {
+        self.hover_link_color = color;
+        self
+    }
+
+    pub fn horizontal_alignment(mut self, alignment: HorizontalAlignment) -> Self {
+        self.horizontal_alignment = alignment;
+        self
+    }
+
+    pub fn vertical_alignment(mut self, alignment: VerticalAlignment) -> Self {
+        self.vertical_alignment = alignment;
+        self
+    }
+
+    // Keeps every line of the text on one row, instead of wrapping it at the edge.
+    pub fn no_wrap(mut self) -> Self {
+        self.should_wrap = false;
+        self
+    }
+
+    pub fn markup(&self) -> &AsciiMarkup {
+        &self.markup
+    }
+
+    pub fn set_markup(&mut self, markup: &str) {
+        self.markup = AsciiMarkup::parse(markup);
+        self.hovered_link = None;
+    }
+
+    // The target of the link under the cursor.
+    pub fn hovered_link(&self) -> Option<&str> {
+        self.hovered_link
+            .and_then(|link| self.markup.links().get(link))
+            .map(|link| link.target.as_str())
+    }
+}
+
+impl Default for AsciiRichText {
+    fn default() -> Self {
+        Self::new("")
+    }
+}
+
+#[derive(Event, Reflect, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AsciiTextSpanClicked {
+    pub entity: Entity,
+    pub link: String,
+}
+
+//=============================================================================
+//             Component Impl
+//=============================================================================
+
+impl AsciiComponent for AsciiRichText {
+    type UpdateQuery<'w, 's> = (
+        Query<'w, 's, &'static AsciiCursor, With<PrimaryWindow>>,
+        Res<'w, ButtonInput<MouseButton>>,
+        Res<'w, AsciiCellMeta>,
+        Query<'w, 's, (Option<&'static Parent>, Option<&'static AsciiUiTarget>, Has<AsciiCamera>)>,
+        EventWriter<'w, AsciiTextSpanClicked>,
+        EventWriter<'w, AsciiMarkDirtyEvent>,
+    );
+
+    fn set_up(app: &mut App) {
+        app.add_event::<AsciiTextSpanClicked>();
+    }
+
+    fn render(&self, buffer: &mut AsciiBuffer) {
+        let mut drawer = buffer
+            .markup(&self.markup)
+            .text_color(self.text_color)
+            .bg_color(self.bg_color)
+            .link_color(self.link_color)
+            .horizontal_alignment(self.horizontal_alignment)
+            .vertical_alignment(self.vertical_alignment)
+            .cached(&self.text_cache);
+        if self.should_wrap {
+            drawer = drawer.wrap();
+        }
+        if let Some(link) = self.hovered_link {
+            drawer = drawer.highlight_link(link, self.hover_link_color);
+        }
+        drawer.draw();
+    }
+
+    fn update(
+        &mut self,
+        query: &mut <Self::UpdateQuery<'_, '_> as SystemParam>::Item<'_, '_>,
+        bounds: &AsciiBounds,
+        entity: Entity,
+    ) {
+        let (cursor, mouse, meta, hierarchy, clicked, dirty) = query;
+
+        let hovered = match (cursor.get_single(), ui_target(entity, hierarchy)) {
+            (Ok(AsciiCursor::Some { x, y }), Some(camera)) if bounds.is_within(*x as i32, *y as i32) => meta
+                .meta_at(camera, *x as i32, *y as i32)
+                .map(|meta| meta as usize - 1)
+                .filter(|link| *link < self.markup.links().len()),
+            _ => None,
+        };
+        if hovered != self.hovered_link {
+            self.hovered_link = hovered;
+            dirty.send(AsciiMarkDirtyEvent);
+        }
+
+        if let (Some(link), true) = (hovered, mouse.just_pressed(MouseButton::Left)) {
+            clicked.send(AsciiTextSpanClicked {
+                entity,
+                link: self.markup.links()[link].target.clone(),
+            });
+        }
+    }
+
+    fn accessibility(&self) -> Option<AsciiAccessible> {
+        Some(AsciiAccessible::new(AsciiAccessibleRole::Text, self.markup.text()))
+    }
+}
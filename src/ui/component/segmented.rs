@@ -0,0 +1,254 @@
+use bevy::{
+    ecs::system::SystemParam, prelude::*, window::PrimaryWindow
+};
+
+use crate::ui::{
+    buffer::AsciiBuffer, character::{Character, Color}, util::AsciiCursor, AsciiMarkDirtyEvent
+};
+
+use super::{super::{accessibility::{AsciiAccessible, AsciiAccessibleRole}, bounds::AsciiBounds}, AsciiComponent};
+
+//=============================================================================
+//             Ascii Segmented
+//=============================================================================
+
+// A row of options where exactly one is selected, drawn as [ A | B | C ] on the middle row of
+// the bounds.
+#[derive(Component, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct AsciiSegmented {
+    options: Vec<String>,
+    selected: usize,
+    text_color: Color,
+    bg_color: Color,
+    selected_text_color: Color,
+    selected_bg_color: Color,
+    hover_text_color: Color,
+    frame_color: Color,
+    focused_frame_color: Color,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    hovered: Option<usize>,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    is_focused: bool,
+}
+
+impl AsciiSegmented {
+    pub fn new(options: &[&str]) -> Self {
+        AsciiSegmented {
+            options: options.iter().map(|option| option.to_string()).collect(),
+            selected: 0,
+            text_color: Color::LightGrey,
+            bg_color: Color::Black,
+            selected_text_color: Color::Black,
+            selected_bg_color: Color::White,
+            hover_text_color: Color::White,
+            frame_color: Color::DarkGrey,
+            focused_frame_color: Color::LightBlue,
+            hovered: None,
+            is_focused: false,
+        }
+    }
+
+    pub fn selected(mut self, index: usize) -> Self {
+        self.selected = index.min(self.options.len().saturating_sub(1));
+        self
+    }
+
+    pub fn text_color(mut self, color: Color) -> Self {
+        self.text_color = color;
+        self
+    }
+
+    pub fn bg_color(mut self, color: Color) -> Self {
+        self.bg_color = color;
+        self
+    }
+
+    pub fn selected_text_color(mut self, color: Color) -> Self {
+        self.selected_text_color = color;
+        self
+    }
+
+    pub fn selected_bg_color(mut self, color: Color) -> Self {
+        self.selected_bg_color = color;
+        self
+    }
+
+    pub fn hover_text_color(mut self, color: Color) -> Self {
+        self.hover_text_color = color;
+        self
+    }
+
+    // The color of the brackets and the separators between the options.
+    pub fn frame_color(mut self, color: Color) -> Self {
+        self.frame_color = color;
+        self
+    }
+
+    pub fn focused_frame_color(mut self, color: Color) -> Self {
+        self.focused_frame_color = color;
+        self
+    }
+
+    pub fn selected_index(&self) -> usize {
+        self.selected
+    }
+
+    pub fn selected_option(&self) -> Option<&str> {
+        self.options.get(self.selected).map(|option| option.as_str())
+    }
+
+    pub fn options(&self) -> &[String] {
+        &self.options
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.is_focused
+    }
+
+    pub fn select(&mut self, index: usize) {
+        if index < self.options.len() {
+            self.selected = index;
+        }
+    }
+
+    // The bracket takes the first column, then each option is drawn as " option " followed by a
+    // one cell separator, the last one being the closing bracket. Returns the start and
+    // (exclusive) end column of every option.
+    fn option_ranges(&self) -> Vec<(i32, i32)> {
+        let mut x = 1;
+        self.options
+            .iter()
+            .map(|option| {
+                let start = x;
+                let end = start + option.chars().count() as i32 + 2;
+                x = end + 1;
+                (start, end)
+            })
+            .collect()
+    }
+}
+
+//=============================================================================
+//             Segmented Events
+//=============================================================================
+
+#[derive(Event, Reflect, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AsciiSegmentedChangedEvent {
+    pub entity: Entity,
+    pub index: usize,
+}
+
+//=============================================================================
+//             Component Impl
+//=============================================================================
+
+impl AsciiComponent for AsciiSegmented {
+    type UpdateQuery<'w, 's> = (
+        Query<'w, 's, &'static AsciiCursor, With<PrimaryWindow>>,
+        Res<'w, ButtonInput<MouseButton>>,
+        Res<'w, ButtonInput<KeyCode>>,
+        EventWriter<'w, AsciiSegmentedChangedEvent>,
+        EventWriter<'w, AsciiMarkDirtyEvent>,
+    );
+
+    fn set_up(app: &mut App) {
+        app.add_event::<AsciiSegmentedChangedEvent>();
+    }
+
+    fn render(&self, buffer: &mut AsciiBuffer) {
+        let width = buffer.bounds.width as i32;
+        if width == 0 || buffer.bounds.height == 0 || self.options.is_empty() {
+            return;
+        }
+
+        let y = (buffer.bounds.height / 2) as i32;
+        let frame_color = if self.is_focused { self.focused_frame_color } else { self.frame_color };
+        buffer.set_character(0, y, (Character::LeftBracket, frame_color, self.bg_color));
+
+        let ranges = self.option_ranges();
+        for (index, ((start, end), option)) in ranges.iter().zip(self.options.iter()).enumerate() {
+            let (text_color, bg_color) = if index == self.selected {
+                (self.selected_text_color, self.selected_bg_color)
+            } else if self.hovered == Some(index) {
+                (self.hover_text_color, self.bg_color)
+            } else {
+                (self.text_color, self.bg_color)
+            };
+
+            let text = format!(" {} ", option);
+            for (x, c) in (*start..*end).zip(text.chars()) {
+                if x >= width {
+                    break;
+                }
+                buffer.set_character(x, y, (c, text_color, bg_color));
+            }
+
+            let separator = if index + 1 < self.options.len() { Character::Pipe } else { Character::RightBracket };
+            if *end < width {
+                buffer.set_character(*end, y, (separator, frame_color, self.bg_color));
+            }
+        }
+    }
+
+    fn update(
+        &mut self,
+        query: &mut <Self::UpdateQuery<'_, '_> as SystemParam>::Item<'_, '_>,
+        bounds: &AsciiBounds,
+        entity: Entity,
+    ) {
+        let (cursor, mouse, keys, changed, dirty) = query;
+        let old_selected = self.selected;
+        let old_hovered = self.hovered;
+        let was_focused = self.is_focused;
+
+        let cursor = match cursor.get_single() {
+            Ok(AsciiCursor::Some { x, y }) => Some((*x as i32 - bounds.x, *y as i32 - bounds.y)),
+            _ => None,
+        };
+        let is_inside = cursor
+            .map(|(x, y)| x >= 0 && y >= 0 && x < bounds.width as i32 && y < bounds.height as i32)
+            .unwrap_or(false);
+
+        let row = (bounds.height / 2) as i32;
+        self.hovered = match cursor {
+            Some((x, y)) if y == row && x < bounds.width as i32 => self
+                .option_ranges()
+                .iter()
+                .position(|(start, end)| x >= *start && x < *end),
+            _ => None,
+        };
+
+        if mouse.just_pressed(MouseButton::Left) {
+            self.is_focused = is_inside;
+            if let Some(hovered) = self.hovered {
+                self.selected = hovered;
+            }
+        }
+
+        if self.is_focused && !self.options.is_empty() {
+            if keys.just_pressed(KeyCode::ArrowLeft) {
+                self.selected = (self.selected + self.options.len() - 1) % self.options.len();
+            }
+            if keys.just_pressed(KeyCode::ArrowRight) {
+                self.selected = (self.selected + 1) % self.options.len();
+            }
+        }
+
+        if self.selected != old_selected {
+            changed.send(AsciiSegmentedChangedEvent { entity, index: self.selected });
+            dirty.send(AsciiMarkDirtyEvent);
+        } else if self.hovered != old_hovered || self.is_focused != was_focused {
+            dirty.send(AsciiMarkDirtyEvent);
+        }
+    }
+
+    fn accessibility(&self) -> Option<AsciiAccessible> {
+        let accessible = AsciiAccessible::new(AsciiAccessibleRole::RadioGroup, &self.options.join(", "))
+            .focused(self.is_focused);
+        match self.selected_option() {
+            Some(selected) => Some(accessible.value(selected)),
+            None => Some(accessible),
+        }
+    }
+}
@@ -0,0 +1,248 @@
+use bevy::{
+    ecs::system::SystemParam, prelude::*, window::PrimaryWindow
+};
+
+use crate::ui::{
+    buffer::AsciiBuffer, character::{Character, Color}, util::AsciiCursor, AsciiMarkDirtyEvent
+};
+
+use super::{super::{accessibility::{AsciiAccessible, AsciiAccessibleRole}, bounds::AsciiBounds}, AsciiComponent};
+
+//=============================================================================
+//             Ascii Slider
+//=============================================================================
+
+#[derive(Component, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct AsciiSlider {
+    value: f32,
+    min: f32,
+    max: f32,
+    step: f32,
+    track_color: Color,
+    fill_color: Color,
+    handle_color: Color,
+    focused_handle_color: Color,
+    bg_color: Color,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    is_focused: bool,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    is_dragging: bool,
+}
+
+impl AsciiSlider {
+    pub fn new(value: f32, min: f32, max: f32) -> Self {
+        let (min, max) = if min <= max { (min, max) } else { (max, min) };
+        AsciiSlider {
+            value: value.clamp(min, max),
+            min,
+            max,
+            step: 0.0,
+            track_color: Color::DarkGrey,
+            fill_color: Color::LightGrey,
+            handle_color: Color::White,
+            focused_handle_color: Color::LightBlue,
+            bg_color: Color::Black,
+            is_focused: false,
+            is_dragging: false,
+        }
+    }
+
+    // A step of 0.0 makes the slider continuous when dragged. Arrow keys always move the value by
+    // at least 1/20th of the range.
+    pub fn step(mut self, step: f32) -> Self {
+        self.step = step.max(0.0);
+        self.value = self.snap(self.value);
+        self
+    }
+
+    pub fn track_color(mut self, color: Color) -> Self {
+        self.track_color = color;
+        self
+    }
+
+    pub fn fill_color(mut self, color: Color) -> Self {
+        self.fill_color = color;
+        self
+    }
+
+    pub fn handle_color(mut self, color: Color) -> Self {
+        self.handle_color = color;
+        self
+    }
+
+    pub fn focused_handle_color(mut self, color: Color) -> Self {
+        self.focused_handle_color = color;
+        self
+    }
+
+    pub fn bg_color(mut self, color: Color) -> Self {
+        self.bg_color = color;
+        self
+    }
+
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    pub fn set_value(&mut self, value: f32) {
+        self.value = self.snap(value);
+    }
+
+    pub fn range(&self) -> (f32, f32) {
+        (self.min, self.max)
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.is_focused
+    }
+
+    fn snap(&self, value: f32) -> f32 {
+        let value = value.clamp(self.min, self.max);
+        if self.step > 0.0 {
+            (((value - self.min) / self.step).round() * self.step + self.min).clamp(self.min, self.max)
+        } else {
+            value
+        }
+    }
+
+    fn key_step(&self) -> f32 {
+        if self.step > 0.0 {
+            self.step
+        } else {
+            (self.max - self.min) / 20.0
+        }
+    }
+
+    fn normalized(&self) -> f32 {
+        if self.max > self.min {
+            (self.value - self.min) / (self.max - self.min)
+        } else {
+            0.0
+        }
+    }
+
+    fn handle_x(&self, width: u32) -> i32 {
+        (self.normalized() * width.saturating_sub(1) as f32).round() as i32
+    }
+}
+
+//=============================================================================
+//             Slider Events
+//=============================================================================
+
+#[derive(Event, Reflect, Clone, Debug, PartialEq)]
+pub struct AsciiSliderChangedEvent {
+    pub entity: Entity,
+    pub value: f32,
+}
+
+//=============================================================================
+//             Component Impl
+//=============================================================================
+
+impl AsciiComponent for AsciiSlider {
+    type UpdateQuery<'w, 's> = (
+        Query<'w, 's, &'static AsciiCursor, With<PrimaryWindow>>,
+        Res<'w, ButtonInput<MouseButton>>,
+        Res<'w, ButtonInput<KeyCode>>,
+        EventWriter<'w, AsciiSliderChangedEvent>,
+        EventWriter<'w, AsciiMarkDirtyEvent>,
+    );
+
+    fn set_up(app: &mut App) {
+        app.add_event::<AsciiSliderChangedEvent>();
+    }
+
+    fn render(&self, buffer: &mut AsciiBuffer) {
+        let width = buffer.bounds.width;
+        if width == 0 || buffer.bounds.height == 0 {
+            return;
+        }
+
+        let y = (buffer.bounds.height / 2) as i32;
+        let handle_x = self.handle_x(width);
+        let handle_color = if self.is_focused { self.focused_handle_color } else { self.handle_color };
+
+        for x in 0..width as i32 {
+            let character = if x == handle_x {
+                (Character::Ball, handle_color, self.bg_color)
+            } else if x < handle_x {
+                (Character::BorderHorizontalCenter, self.fill_color, self.bg_color)
+            } else {
+                (Character::BorderHorizontalCenter, self.track_color, self.bg_color)
+            };
+            buffer.set_character(x, y, character);
+        }
+    }
+
+    fn update(
+        &mut self,
+        query: &mut <Self::UpdateQuery<'_, '_> as SystemParam>::Item<'_, '_>,
+        bounds: &AsciiBounds,
+        entity: Entity,
+    ) {
+        let (cursor, mouse, keys, changed, dirty) = query;
+        let old_value = self.value;
+        let was_focused = self.is_focused;
+
+        let cursor = match cursor.get_single() {
+            Ok(AsciiCursor::Some { x, y }) => Some((*x as i32 - bounds.x, *y as i32 - bounds.y)),
+            _ => None,
+        };
+        let is_hovering = cursor
+            .map(|(x, y)| x >= 0 && y >= 0 && x < bounds.width as i32 && y < bounds.height as i32)
+            .unwrap_or(false);
+
+        if mouse.just_pressed(MouseButton::Left) {
+            self.is_focused = is_hovering;
+            self.is_dragging = is_hovering;
+        }
+
+        if mouse.just_released(MouseButton::Left) {
+            self.is_dragging = false;
+        }
+
+        if self.is_dragging && mouse.pressed(MouseButton::Left) {
+            if let Some((x, _)) = cursor {
+                let t = if bounds.width > 1 {
+                    (x as f32 / (bounds.width - 1) as f32).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                self.value = self.snap(self.min + t * (self.max - self.min));
+            }
+        }
+
+        if self.is_focused {
+            let step = self.key_step();
+            if keys.just_pressed(KeyCode::ArrowLeft) || keys.just_pressed(KeyCode::ArrowDown) {
+                self.value = self.snap(self.value - step);
+            }
+            if keys.just_pressed(KeyCode::ArrowRight) || keys.just_pressed(KeyCode::ArrowUp) {
+                self.value = self.snap(self.value + step);
+            }
+            if keys.just_pressed(KeyCode::Home) {
+                self.value = self.min;
+            }
+            if keys.just_pressed(KeyCode::End) {
+                self.value = self.max;
+            }
+        }
+
+        if self.value != old_value {
+            changed.send(AsciiSliderChangedEvent { entity, value: self.value });
+            dirty.send(AsciiMarkDirtyEvent);
+        } else if self.is_focused != was_focused {
+            dirty.send(AsciiMarkDirtyEvent);
+        }
+    }
+
+    fn accessibility(&self) -> Option<AsciiAccessible> {
+        Some(
+            AsciiAccessible::new(AsciiAccessibleRole::Slider, "")
+                .value(self.value)
+                .focused(self.is_focused),
+        )
+    }
+}
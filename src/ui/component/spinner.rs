@@ -0,0 +1,204 @@
+use bevy::{
+    ecs::system::SystemParam, prelude::*, window::PrimaryWindow
+};
+
+use crate::ui::{
+    buffer::AsciiBuffer, character::{Character, Color}, util::AsciiCursor, AsciiMarkDirtyEvent
+};
+
+use super::{super::{accessibility::{AsciiAccessible, AsciiAccessibleRole}, bounds::AsciiBounds}, AsciiComponent};
+
+//=============================================================================
+//             Ascii Spinner
+//=============================================================================
+
+#[derive(Component, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct AsciiSpinner {
+    value: f32,
+    min: f32,
+    max: f32,
+    step: f32,
+    precision: usize,
+    text_color: Color,
+    button_color: Color,
+    hover_button_color: Color,
+    bg_color: Color,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    hovered_button: Option<i32>,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    is_hovering: bool,
+}
+
+impl AsciiSpinner {
+    pub fn new(value: f32, min: f32, max: f32) -> Self {
+        let (min, max) = if min <= max { (min, max) } else { (max, min) };
+        AsciiSpinner {
+            value: value.clamp(min, max),
+            min,
+            max,
+            step: 1.0,
+            precision: 0,
+            text_color: Color::White,
+            button_color: Color::LightGrey,
+            hover_button_color: Color::LightBlue,
+            bg_color: Color::Black,
+            hovered_button: None,
+            is_hovering: false,
+        }
+    }
+
+    pub fn step(mut self, step: f32) -> Self {
+        self.step = step.abs();
+        self
+    }
+
+    // The number of decimal places the value is displayed with.
+    pub fn precision(mut self, precision: usize) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    pub fn text_color(mut self, color: Color) -> Self {
+        self.text_color = color;
+        self
+    }
+
+    pub fn button_color(mut self, color: Color) -> Self {
+        self.button_color = color;
+        self
+    }
+
+    pub fn hover_button_color(mut self, color: Color) -> Self {
+        self.hover_button_color = color;
+        self
+    }
+
+    pub fn bg_color(mut self, color: Color) -> Self {
+        self.bg_color = color;
+        self
+    }
+
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    pub fn set_value(&mut self, value: f32) {
+        self.value = value.clamp(self.min, self.max);
+    }
+
+    pub fn range(&self) -> (f32, f32) {
+        (self.min, self.max)
+    }
+
+    fn label(&self) -> String {
+        format!("{:.*}", self.precision, self.value)
+    }
+}
+
+//=============================================================================
+//             Spinner Events
+//=============================================================================
+
+#[derive(Event, Reflect, Clone, Debug, PartialEq)]
+pub struct AsciiSpinnerChangedEvent {
+    pub entity: Entity,
+    pub value: f32,
+}
+
+//=============================================================================
+//             Component Impl
+//=============================================================================
+
+impl AsciiComponent for AsciiSpinner {
+    type UpdateQuery<'w, 's> = (
+        Query<'w, 's, &'static AsciiCursor, With<PrimaryWindow>>,
+        Res<'w, ButtonInput<MouseButton>>,
+        Res<'w, ButtonInput<KeyCode>>,
+        EventWriter<'w, AsciiSpinnerChangedEvent>,
+        EventWriter<'w, AsciiMarkDirtyEvent>,
+    );
+
+    fn set_up(app: &mut App) {
+        app.add_event::<AsciiSpinnerChangedEvent>();
+    }
+
+    fn render(&self, buffer: &mut AsciiBuffer) {
+        let width = buffer.bounds.width as i32;
+        if width < 3 || buffer.bounds.height == 0 {
+            return;
+        }
+
+        let y = (buffer.bounds.height / 2) as i32;
+        let button_color = |x: i32| {
+            if self.hovered_button == Some(x) { self.hover_button_color } else { self.button_color }
+        };
+
+        buffer.set_character(0, y, (Character::Hyphen, button_color(0), self.bg_color));
+        buffer.set_character(width - 1, y, (Character::Plus, button_color(width - 1), self.bg_color));
+
+        let label = self.label();
+        let inner_width = width - 2;
+        let start_x = 1 + ((inner_width - label.len() as i32) / 2).max(0);
+        for x in 1..width - 1 {
+            buffer.set_character(x, y, (Character::Space, self.text_color, self.bg_color));
+        }
+        for (i, c) in label.chars().take(inner_width as usize).enumerate() {
+            buffer.set_character(start_x + i as i32, y, (c, self.text_color, self.bg_color));
+        }
+    }
+
+    fn update(
+        &mut self,
+        query: &mut <Self::UpdateQuery<'_, '_> as SystemParam>::Item<'_, '_>,
+        bounds: &AsciiBounds,
+        entity: Entity,
+    ) {
+        let (cursor, mouse, keys, changed, dirty) = query;
+        let old_value = self.value;
+        let old_hovered_button = self.hovered_button;
+
+        let cursor = match cursor.get_single() {
+            Ok(AsciiCursor::Some { x, y }) => Some((*x as i32 - bounds.x, *y as i32 - bounds.y)),
+            _ => None,
+        };
+        let width = bounds.width as i32;
+        let row = (bounds.height / 2) as i32;
+
+        self.is_hovering = cursor
+            .map(|(x, y)| x >= 0 && y >= 0 && x < width && y < bounds.height as i32)
+            .unwrap_or(false);
+        self.hovered_button = match cursor {
+            Some((x, y)) if y == row && (x == 0 || x == width - 1) => Some(x),
+            _ => None,
+        };
+
+        if mouse.just_pressed(MouseButton::Left) {
+            match self.hovered_button {
+                Some(0) => self.set_value(self.value - self.step),
+                Some(_) => self.set_value(self.value + self.step),
+                None => {}
+            }
+        }
+
+        if self.is_hovering {
+            if keys.just_pressed(KeyCode::ArrowDown) || keys.just_pressed(KeyCode::ArrowLeft) {
+                self.set_value(self.value - self.step);
+            }
+            if keys.just_pressed(KeyCode::ArrowUp) || keys.just_pressed(KeyCode::ArrowRight) {
+                self.set_value(self.value + self.step);
+            }
+        }
+
+        if self.value != old_value {
+            changed.send(AsciiSpinnerChangedEvent { entity, value: self.value });
+            dirty.send(AsciiMarkDirtyEvent);
+        } else if self.hovered_button != old_hovered_button {
+            dirty.send(AsciiMarkDirtyEvent);
+        }
+    }
+
+    fn accessibility(&self) -> Option<AsciiAccessible> {
+        Some(AsciiAccessible::new(AsciiAccessibleRole::SpinButton, "").value(self.label()))
+    }
+}
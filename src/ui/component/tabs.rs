@@ -0,0 +1,254 @@
+use bevy::{
+    ecs::system::SystemParam, prelude::*, window::PrimaryWindow
+};
+
+use crate::ui::{
+    buffer::AsciiBuffer, character::{Character, Color}, util::AsciiCursor, AsciiMarkDirtyEvent, Padding
+};
+
+use super::{super::{accessibility::{AsciiAccessible, AsciiAccessibleRole}, bounds::AsciiBounds}, AsciiComponent};
+
+//=============================================================================
+//             Ascii Tabs
+//=============================================================================
+
+// A row of tab labels along the top of the bounds. The n-th child entity is the page for the
+// n-th tab; only the selected page is visible.
+#[derive(Component, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct AsciiTabs {
+    labels: Vec<String>,
+    selected: usize,
+    text_color: Color,
+    bg_color: Color,
+    selected_text_color: Color,
+    selected_bg_color: Color,
+    hover_text_color: Color,
+    separator_color: Color,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    hovered: Option<usize>,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    is_focused: bool,
+}
+
+impl AsciiTabs {
+    pub fn new(labels: &[&str]) -> Self {
+        AsciiTabs {
+            labels: labels.iter().map(|label| label.to_string()).collect(),
+            selected: 0,
+            text_color: Color::LightGrey,
+            bg_color: Color::Black,
+            selected_text_color: Color::Black,
+            selected_bg_color: Color::White,
+            hover_text_color: Color::White,
+            separator_color: Color::DarkGrey,
+            hovered: None,
+            is_focused: false,
+        }
+    }
+
+    pub fn selected(mut self, index: usize) -> Self {
+        self.selected = index.min(self.labels.len().saturating_sub(1));
+        self
+    }
+
+    pub fn text_color(mut self, color: Color) -> Self {
+        self.text_color = color;
+        self
+    }
+
+    pub fn bg_color(mut self, color: Color) -> Self {
+        self.bg_color = color;
+        self
+    }
+
+    pub fn selected_text_color(mut self, color: Color) -> Self {
+        self.selected_text_color = color;
+        self
+    }
+
+    pub fn selected_bg_color(mut self, color: Color) -> Self {
+        self.selected_bg_color = color;
+        self
+    }
+
+    pub fn hover_text_color(mut self, color: Color) -> Self {
+        self.hover_text_color = color;
+        self
+    }
+
+    pub fn separator_color(mut self, color: Color) -> Self {
+        self.separator_color = color;
+        self
+    }
+
+    pub fn selected_index(&self) -> usize {
+        self.selected
+    }
+
+    pub fn selected_label(&self) -> Option<&str> {
+        self.labels.get(self.selected).map(|label| label.as_str())
+    }
+
+    pub fn labels(&self) -> &[String] {
+        &self.labels
+    }
+
+    pub fn select(&mut self, index: usize) {
+        if index < self.labels.len() {
+            self.selected = index;
+        }
+    }
+
+    // Each tab is drawn as " label " followed by a one cell separator. Returns the start and
+    // (exclusive) end column of every tab.
+    fn tab_ranges(&self) -> Vec<(i32, i32)> {
+        let mut x = 0;
+        self.labels
+            .iter()
+            .map(|label| {
+                let start = x;
+                let end = start + label.chars().count() as i32 + 2;
+                x = end + 1;
+                (start, end)
+            })
+            .collect()
+    }
+}
+
+//=============================================================================
+//             Tab Events
+//=============================================================================
+
+#[derive(Event, Reflect, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AsciiTabChangedEvent {
+    pub entity: Entity,
+    pub index: usize,
+}
+
+//=============================================================================
+//             Component Impl
+//=============================================================================
+
+impl AsciiComponent for AsciiTabs {
+    type UpdateQuery<'w, 's> = (
+        Query<'w, 's, &'static AsciiCursor, With<PrimaryWindow>>,
+        Res<'w, ButtonInput<MouseButton>>,
+        Res<'w, ButtonInput<KeyCode>>,
+        Query<'w, 's, &'static Children>,
+        Query<'w, 's, &'static mut Visibility>,
+        EventWriter<'w, AsciiTabChangedEvent>,
+        EventWriter<'w, AsciiMarkDirtyEvent>,
+    );
+
+    fn set_up(app: &mut App) {
+        app.add_event::<AsciiTabChangedEvent>();
+    }
+
+    fn render(&self, buffer: &mut AsciiBuffer) {
+        let width = buffer.bounds.width as i32;
+        if width == 0 || buffer.bounds.height == 0 {
+            return;
+        }
+
+        for x in 0..width {
+            buffer.set_character(x, 0, (Character::Space, self.text_color, self.bg_color));
+        }
+
+        for (index, ((start, end), label)) in self.tab_ranges().into_iter().zip(self.labels.iter()).enumerate() {
+            let (text_color, bg_color) = if index == self.selected {
+                (self.selected_text_color, self.selected_bg_color)
+            } else if self.hovered == Some(index) {
+                (self.hover_text_color, self.bg_color)
+            } else {
+                (self.text_color, self.bg_color)
+            };
+
+            let text = format!(" {} ", label);
+            for (x, c) in (start..end).zip(text.chars()) {
+                if x >= width {
+                    break;
+                }
+                buffer.set_character(x, 0, (c, text_color, bg_color));
+            }
+
+            if end < width && index + 1 < self.labels.len() {
+                buffer.set_character(end, 0, (Character::Pipe, self.separator_color, self.bg_color));
+            }
+        }
+    }
+
+    fn update(
+        &mut self,
+        query: &mut <Self::UpdateQuery<'_, '_> as SystemParam>::Item<'_, '_>,
+        bounds: &AsciiBounds,
+        entity: Entity,
+    ) {
+        let (cursor, mouse, keys, children, visibilities, changed, dirty) = query;
+        let old_selected = self.selected;
+        let old_hovered = self.hovered;
+
+        let cursor = match cursor.get_single() {
+            Ok(AsciiCursor::Some { x, y }) => Some((*x as i32 - bounds.x, *y as i32 - bounds.y)),
+            _ => None,
+        };
+        let is_inside = cursor
+            .map(|(x, y)| x >= 0 && y >= 0 && x < bounds.width as i32 && y < bounds.height as i32)
+            .unwrap_or(false);
+
+        self.hovered = match cursor {
+            Some((x, 0)) if x < bounds.width as i32 => self
+                .tab_ranges()
+                .iter()
+                .position(|(start, end)| x >= *start && x < *end),
+            _ => None,
+        };
+
+        if mouse.just_pressed(MouseButton::Left) {
+            self.is_focused = is_inside;
+            if let Some(hovered) = self.hovered {
+                self.selected = hovered;
+            }
+        }
+
+        if self.is_focused && !self.labels.is_empty() {
+            if keys.just_pressed(KeyCode::ArrowLeft) {
+                self.selected = (self.selected + self.labels.len() - 1) % self.labels.len();
+            }
+            if keys.just_pressed(KeyCode::ArrowRight) {
+                self.selected = (self.selected + 1) % self.labels.len();
+            }
+        }
+
+        if let Ok(children) = children.get(entity) {
+            for (index, child) in children.iter().enumerate() {
+                let Ok(mut visibility) = visibilities.get_mut(*child) else { continue };
+                let target = if index == self.selected { Visibility::Inherited } else { Visibility::Hidden };
+                if *visibility != target {
+                    *visibility = target;
+                }
+            }
+        }
+
+        if self.selected != old_selected {
+            changed.send(AsciiTabChangedEvent { entity, index: self.selected });
+            dirty.send(AsciiMarkDirtyEvent);
+        } else if self.hovered != old_hovered {
+            dirty.send(AsciiMarkDirtyEvent);
+        }
+    }
+
+    // The pages are laid out below the tab row.
+    fn content_padding(&self) -> Option<Padding> {
+        Some(Padding::from((1, 0, 0, 0)))
+    }
+
+    fn accessibility(&self) -> Option<AsciiAccessible> {
+        let accessible = AsciiAccessible::new(AsciiAccessibleRole::TabList, &self.labels.join(", "))
+            .focused(self.is_focused);
+        match self.selected_label() {
+            Some(selected) => Some(accessible.value(selected)),
+            None => Some(accessible),
+        }
+    }
+}
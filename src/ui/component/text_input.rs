@@ -0,0 +1,628 @@
+use std::ops::Range;
+
+use bevy::{
+    ecs::system::SystemParam,
+    input::{keyboard::{Key, KeyboardInput}, ButtonState},
+    prelude::*,
+    window::{Ime, PrimaryWindow, ReceivedCharacter},
+};
+
+use crate::ui::{
+    buffer::AsciiBuffer, character::{Character, Color}, clipboard::AsciiClipboard, util::AsciiCursor, AsciiMarkDirtyEvent
+};
+
+use super::{super::{accessibility::{AsciiAccessible, AsciiAccessibleRole}, bounds::AsciiBounds}, AsciiComponent};
+
+//=============================================================================
+//             Text Input State
+//=============================================================================
+
+// The editable text and caret shared by every widget that takes typed input. Text can be
+// selected with shift and the arrow keys or by dragging the mouse, and copied, cut and pasted
+// with the `AsciiClipboard`.
+#[derive(Default, Clone, Debug, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct TextInputState {
+    text: String,
+    cursor: usize,
+    max_length: Option<usize>,
+    // Where the selection started, the caret is the other end of it.
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    anchor: Option<usize>,
+    // Text that is still being composed, by an input method or after a dead key. It is drawn at
+    // the caret, but is not part of the text until it is committed.
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    preedit: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TextInputAction {
+    None,
+    Moved,
+    Changed,
+    Submitted(String),
+}
+
+impl TextInputAction {
+    // The more important of the two, a change over a move.
+    fn merge(self, other: Option<TextInputAction>) -> TextInputAction {
+        match (self, other) {
+            (_, Some(TextInputAction::Changed)) => TextInputAction::Changed,
+            (TextInputAction::None, Some(TextInputAction::Moved)) => TextInputAction::Moved,
+            (action, _) => action,
+        }
+    }
+}
+
+// This frame's typed text, key presses and input method events. Every focused text widget reads
+// them, so they are not consumed.
+#[derive(SystemParam)]
+pub struct AsciiTextEntry<'w> {
+    keys: Res<'w, ButtonInput<KeyCode>>,
+    keyboard: Res<'w, Events<KeyboardInput>>,
+    characters: Res<'w, Events<ReceivedCharacter>>,
+    ime: Res<'w, Events<Ime>>,
+}
+
+impl<'w> AsciiTextEntry<'w> {
+    pub fn keys(&self) -> &ButtonInput<KeyCode> {
+        &self.keys
+    }
+}
+
+impl TextInputState {
+    pub fn new(text: &str) -> Self {
+        TextInputState {
+            text: text.to_string(),
+            cursor: text.chars().count(),
+            max_length: None,
+            anchor: None,
+            preedit: String::new(),
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn len(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text.to_string();
+        self.cursor = self.len();
+        self.anchor = None;
+    }
+
+    pub fn set_max_length(&mut self, max_length: Option<usize>) {
+        self.max_length = max_length;
+    }
+
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.cursor = 0;
+        self.anchor = None;
+        self.preedit.clear();
+    }
+
+    pub fn preedit(&self) -> &str {
+        &self.preedit
+    }
+
+    // The characters that are selected, empty selections are `None`.
+    pub fn selection(&self) -> Option<Range<usize>> {
+        let anchor = self.anchor?;
+        (anchor != self.cursor).then(|| anchor.min(self.cursor)..anchor.max(self.cursor))
+    }
+
+    pub fn selected_text(&self) -> Option<&str> {
+        let selection = self.selection()?;
+        Some(&self.text[self.byte_index(selection.start)..self.byte_index(selection.end)])
+    }
+
+    // Selects from `anchor` to `cursor`, the caret ends up at `cursor`.
+    pub fn select(&mut self, anchor: usize, cursor: usize) -> bool {
+        let anchor = Some(anchor.min(self.len()));
+        let moved = self.anchor != anchor || self.cursor != cursor.min(self.len());
+        self.anchor = anchor;
+        self.cursor = cursor.min(self.len());
+        moved
+    }
+
+    pub fn select_all(&mut self) -> bool {
+        self.select(0, self.len())
+    }
+
+    pub fn clear_selection(&mut self) -> bool {
+        let had_selection = self.selection().is_some();
+        self.anchor = None;
+        had_selection
+    }
+
+    pub fn delete_selection(&mut self) -> bool {
+        let Some(selection) = self.selection() else {
+            return false;
+        };
+        let range = self.byte_index(selection.start)..self.byte_index(selection.end);
+        self.text.replace_range(range, "");
+        self.cursor = selection.start;
+        self.anchor = None;
+        true
+    }
+
+    // Replaces the selection with the character.
+    pub fn insert(&mut self, c: char) -> bool {
+        let deleted = self.delete_selection();
+        if self.max_length.map(|max| self.len() >= max).unwrap_or(false) {
+            return deleted;
+        }
+        let index = self.byte_index(self.cursor);
+        self.text.insert(index, c);
+        self.cursor += 1;
+        true
+    }
+
+    // Replaces the selection with the text, like a paste. The input is a single line, so line
+    // breaks become spaces and other control characters are left out. Characters past the
+    // `max_length` are cut off.
+    pub fn insert_str(&mut self, text: &str) -> bool {
+        let mut changed = self.delete_selection();
+        for c in text.chars() {
+            let c = match c {
+                '\n' | '\t' => ' ',
+                '\r' => continue,
+                c if c.is_control() => continue,
+                c => c,
+            };
+            if !self.insert(c) {
+                break;
+            }
+            changed = true;
+        }
+        changed
+    }
+
+    pub fn backspace(&mut self) -> bool {
+        if self.delete_selection() {
+            return true;
+        }
+        if self.cursor == 0 {
+            return false;
+        }
+        self.cursor -= 1;
+        let index = self.byte_index(self.cursor);
+        self.text.remove(index);
+        true
+    }
+
+    pub fn delete(&mut self) -> bool {
+        if self.delete_selection() {
+            return true;
+        }
+        if self.cursor >= self.len() {
+            return false;
+        }
+        let index = self.byte_index(self.cursor);
+        self.text.remove(index);
+        true
+    }
+
+    // Moves the caret and drops the selection.
+    pub fn move_cursor(&mut self, offset: i32) -> bool {
+        let new_cursor = (self.cursor as i32 + offset).clamp(0, self.len() as i32) as usize;
+        self.move_cursor_to(new_cursor)
+    }
+
+    pub fn move_cursor_to(&mut self, cursor: usize) -> bool {
+        let new_cursor = cursor.min(self.len());
+        let moved = new_cursor != self.cursor || self.selection().is_some();
+        self.cursor = new_cursor;
+        self.anchor = None;
+        moved
+    }
+
+    // Moves the caret and selects what it moved over, like with shift held.
+    pub fn select_to(&mut self, cursor: usize) -> bool {
+        let anchor = self.anchor.unwrap_or(self.cursor);
+        self.select(anchor, cursor)
+    }
+
+    // Places the caret at the clicked column of the field it was drawn in, or selects up to it
+    // when the mouse is dragged.
+    pub fn click(&mut self, column: i32, width: u32, drag: bool) -> bool {
+        let cursor = self.scroll(width as usize) + column.clamp(0, width as i32) as usize;
+        if drag {
+            self.select_to(cursor)
+        } else {
+            let moved = self.move_cursor_to(cursor);
+            self.anchor = Some(self.cursor);
+            moved
+        }
+    }
+
+    // Applies this frame's typed text and key presses to the text. Keys that are held repeat
+    // like they do in other apps. Copied text goes to the clipboard, and pasted text comes from
+    // it.
+    pub fn handle_input(&mut self, input: &AsciiTextEntry, clipboard: &mut AsciiClipboard) -> TextInputAction {
+        let keys = &input.keys;
+        let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+        // Command on macos. AltGr is sent as control and alt on windows, and types characters.
+        let shortcut = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight, KeyCode::SuperLeft, KeyCode::SuperRight])
+            && !keys.pressed(KeyCode::AltRight);
+        let mut action = TextInputAction::None;
+
+        for event in input.keyboard.iter_current_update_events() {
+            if event.state != ButtonState::Pressed {
+                continue;
+            }
+
+            let key_action = match event.key_code {
+                KeyCode::Enter | KeyCode::NumpadEnter => {
+                    self.preedit.clear();
+                    return TextInputAction::Submitted(self.text.clone());
+                }
+                KeyCode::Backspace => self.backspace().then_some(TextInputAction::Changed),
+                KeyCode::Delete => self.delete().then_some(TextInputAction::Changed),
+                KeyCode::ArrowLeft if shift => self.select_to(self.cursor.saturating_sub(1)).then_some(TextInputAction::Moved),
+                KeyCode::ArrowRight if shift => self.select_to(self.cursor + 1).then_some(TextInputAction::Moved),
+                KeyCode::Home if shift => self.select_to(0).then_some(TextInputAction::Moved),
+                KeyCode::End if shift => self.select_to(usize::MAX).then_some(TextInputAction::Moved),
+                KeyCode::ArrowLeft => self.move_cursor(-1).then_some(TextInputAction::Moved),
+                KeyCode::ArrowRight => self.move_cursor(1).then_some(TextInputAction::Moved),
+                KeyCode::Home => self.move_cursor_to(0).then_some(TextInputAction::Moved),
+                KeyCode::End => self.move_cursor_to(usize::MAX).then_some(TextInputAction::Moved),
+                KeyCode::KeyA if shortcut => self.select_all().then_some(TextInputAction::Moved),
+                KeyCode::KeyC if shortcut => {
+                    if let Some(text) = self.selected_text() {
+                        clipboard.set_text(text);
+                    }
+                    None
+                }
+                KeyCode::KeyX if shortcut => {
+                    if let Some(text) = self.selected_text() {
+                        clipboard.set_text(text);
+                    }
+                    self.delete_selection().then_some(TextInputAction::Changed)
+                }
+                KeyCode::KeyV if shortcut => self.insert_str(&clipboard.text()).then_some(TextInputAction::Changed),
+                // A dead key waits for the next key to make an accented character, which comes in
+                // as typed text. Until then the accent is shown at the caret.
+                _ => match &event.logical_key {
+                    Key::Dead(Some(accent)) => {
+                        self.preedit = accent.to_string();
+                        Some(TextInputAction::Moved)
+                    }
+                    _ => None,
+                },
+            };
+            action = action.merge(key_action);
+        }
+
+        if !shortcut {
+            for event in input.characters.iter_current_update_events() {
+                // Keys like backspace and enter are sent as control characters as well, they
+                // are handled above.
+                let text = event.char.chars().filter(|c| !c.is_control()).collect::<String>();
+                if !text.is_empty() {
+                    self.preedit.clear();
+                    action = action.merge(self.insert_str(&text).then_some(TextInputAction::Changed));
+                }
+            }
+        }
+
+        // While an input method is enabled, text comes from it instead of as typed characters.
+        for event in input.ime.iter_current_update_events() {
+            let ime_action = match event {
+                Ime::Preedit { value, .. } if *value != self.preedit => {
+                    self.preedit = value.clone();
+                    Some(TextInputAction::Moved)
+                }
+                Ime::Commit { value, .. } => {
+                    self.preedit.clear();
+                    Some(if self.insert_str(value) { TextInputAction::Changed } else { TextInputAction::Moved })
+                }
+                Ime::Disabled { .. } if !self.preedit.is_empty() => {
+                    self.preedit.clear();
+                    Some(TextInputAction::Moved)
+                }
+                _ => None,
+            };
+            action = action.merge(ime_action);
+        }
+
+        action
+    }
+
+    // Draws the text on the first row of the buffer, scrolled so the caret stays visible. The
+    // caret and the selection are drawn with the colors swapped.
+    pub fn draw(&self, buffer: &AsciiBuffer, text_color: Color, bg_color: Color, show_cursor: bool) {
+        let width = buffer.bounds.width as usize;
+        if width == 0 {
+            return;
+        }
+
+        let scroll = self.scroll(width);
+        let selection = self.selection().filter(|_| show_cursor).unwrap_or_default();
+        let mut chars: Vec<char> = self.text.chars().collect();
+        let preedit_len = self.preedit.chars().count();
+        chars.splice(self.cursor..self.cursor, self.preedit.chars());
+        let composing = self.cursor..self.cursor + preedit_len;
+        for column in 0..width {
+            let index = scroll + column;
+            let c = chars.get(index).copied().unwrap_or(' ');
+            let text_index = if index >= composing.end { index - preedit_len } else { index };
+            let is_highlighted = index == composing.end
+                || composing.contains(&index)
+                || (!composing.contains(&index) && selection.contains(&text_index));
+            let character = if show_cursor && is_highlighted {
+                (c, bg_color, text_color)
+            } else {
+                (c, text_color, bg_color)
+            };
+            buffer.set_character(column as i32, 0, character);
+        }
+    }
+
+    // The first character that is drawn in a field `width` cells wide.
+    fn scroll(&self, width: usize) -> usize {
+        (self.cursor + self.preedit.chars().count() + 1).saturating_sub(width)
+    }
+
+    fn byte_index(&self, cursor: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(cursor)
+            .map(|(index, _)| index)
+            .unwrap_or(self.text.len())
+    }
+}
+
+//=============================================================================
+//             Ascii Text Input
+//=============================================================================
+
+#[derive(Component, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct AsciiTextInput {
+    state: TextInputState,
+    placeholder: String,
+    text_color: Color,
+    placeholder_color: Color,
+    bg_color: Color,
+    focused_bg_color: Color,
+    clear_on_submit: bool,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    is_focused: bool,
+    // The mouse was pressed on the input and is selecting text while it is held.
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    is_dragging: bool,
+}
+
+impl AsciiTextInput {
+    pub fn new() -> Self {
+        AsciiTextInput {
+            state: TextInputState::default(),
+            placeholder: String::new(),
+            text_color: Color::White,
+            placeholder_color: Color::Grey,
+            bg_color: Color::DarkGrey,
+            focused_bg_color: Color::Black,
+            clear_on_submit: false,
+            is_focused: false,
+            is_dragging: false,
+        }
+    }
+
+    pub fn from_string(text: &str) -> Self {
+        let mut input = Self::new();
+        input.state.set_text(text);
+        input
+    }
+
+    pub fn placeholder(mut self, placeholder: &str) -> Self {
+        self.placeholder = placeholder.to_string();
+        self
+    }
+
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.state.set_max_length(Some(max_length));
+        self
+    }
+
+    pub fn clear_on_submit(mut self) -> Self {
+        self.clear_on_submit = true;
+        self
+    }
+
+    pub fn text_color(mut self, color: Color) -> Self {
+        self.text_color = color;
+        self
+    }
+
+    pub fn placeholder_color(mut self, color: Color) -> Self {
+        self.placeholder_color = color;
+        self
+    }
+
+    pub fn bg_color(mut self, color: Color) -> Self {
+        self.bg_color = color;
+        self
+    }
+
+    pub fn focused_bg_color(mut self, color: Color) -> Self {
+        self.focused_bg_color = color;
+        self
+    }
+
+    pub fn text(&self) -> &str {
+        self.state.text()
+    }
+
+    pub fn set_text(&mut self, text: &str) {
+        self.state.set_text(text);
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.is_focused
+    }
+
+    pub fn focus(&mut self) {
+        self.is_focused = true;
+    }
+
+    // Hands out the text like enter does, and clears it with `clear_on_submit`.
+    pub fn submit(&mut self) -> String {
+        let text = self.state.text().to_string();
+        if self.clear_on_submit {
+            self.state.clear();
+        }
+        text
+    }
+
+    pub fn state(&self) -> &TextInputState {
+        &self.state
+    }
+
+    pub fn state_mut(&mut self) -> &mut TextInputState {
+        &mut self.state
+    }
+}
+
+impl Default for AsciiTextInput {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//=============================================================================
+//             Text Input Events
+//=============================================================================
+
+#[derive(Event, Reflect, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AsciiTextInputChangedEvent {
+    pub entity: Entity,
+    pub text: String,
+}
+
+#[derive(Event, Reflect, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AsciiTextInputSubmittedEvent {
+    pub entity: Entity,
+    pub text: String,
+}
+
+//=============================================================================
+//             Component Impl
+//=============================================================================
+
+impl AsciiComponent for AsciiTextInput {
+    type UpdateQuery<'w, 's> = (
+        Query<'w, 's, &'static AsciiCursor, With<PrimaryWindow>>,
+        Res<'w, ButtonInput<MouseButton>>,
+        AsciiTextEntry<'w>,
+        ResMut<'w, AsciiClipboard>,
+        EventWriter<'w, AsciiTextInputChangedEvent>,
+        EventWriter<'w, AsciiTextInputSubmittedEvent>,
+        EventWriter<'w, AsciiMarkDirtyEvent>,
+    );
+
+    fn set_up(app: &mut App) {
+        app.add_event::<AsciiTextInputChangedEvent>()
+            .add_event::<AsciiTextInputSubmittedEvent>()
+            .init_resource::<AsciiClipboard>();
+    }
+
+    fn render(&self, buffer: &mut AsciiBuffer) {
+        if buffer.bounds.width == 0 || buffer.bounds.height == 0 {
+            return;
+        }
+
+        let bg_color = if self.is_focused { self.focused_bg_color } else { self.bg_color };
+        if self.state.is_empty() && !self.is_focused && !self.placeholder.is_empty() {
+            for x in 0..buffer.bounds.width as i32 {
+                buffer.set_character(x, 0, (Character::Space, self.placeholder_color, bg_color));
+            }
+            for (x, c) in self.placeholder.chars().take(buffer.bounds.width as usize).enumerate() {
+                buffer.set_character(x as i32, 0, (c, self.placeholder_color, bg_color));
+            }
+        } else {
+            self.state.draw(buffer, self.text_color, bg_color, self.is_focused);
+        }
+    }
+
+    fn update(
+        &mut self,
+        query: &mut <Self::UpdateQuery<'_, '_> as SystemParam>::Item<'_, '_>,
+        bounds: &AsciiBounds,
+        entity: Entity,
+    ) {
+        let (cursor, mouse, entry, clipboard, changed, submitted, dirty) = query;
+        let was_focused = self.is_focused;
+        let mut needs_redraw = false;
+
+        let cursor = match cursor.get_single() {
+            Ok(AsciiCursor::Some { x, y }) => Some((*x as i32 - bounds.x, *y as i32 - bounds.y)),
+            _ => None,
+        };
+        if mouse.just_pressed(MouseButton::Left) {
+            self.is_focused = cursor
+                .map(|(x, y)| x >= 0 && y >= 0 && x < bounds.width as i32 && y < bounds.height as i32)
+                .unwrap_or(false);
+            if let (true, Some((x, _))) = (self.is_focused, cursor) {
+                self.is_dragging = true;
+                needs_redraw |= self.state.click(x, bounds.width, false);
+            }
+        } else if self.is_dragging && mouse.pressed(MouseButton::Left) {
+            if let Some((x, _)) = cursor {
+                needs_redraw |= self.state.click(x, bounds.width, true);
+            }
+        } else {
+            self.is_dragging = false;
+        }
+
+        needs_redraw |= self.is_focused != was_focused;
+
+        if self.is_focused {
+            match self.state.handle_input(entry, clipboard) {
+                TextInputAction::Changed => {
+                    changed.send(AsciiTextInputChangedEvent { entity, text: self.state.text().to_string() });
+                    needs_redraw = true;
+                }
+                TextInputAction::Submitted(text) => {
+                    submitted.send(AsciiTextInputSubmittedEvent { entity, text });
+                    if self.clear_on_submit {
+                        self.state.clear();
+                        changed.send(AsciiTextInputChangedEvent { entity, text: String::new() });
+                    }
+                    needs_redraw = true;
+                }
+                TextInputAction::Moved => needs_redraw = true,
+                TextInputAction::None => {}
+            }
+        }
+
+        if needs_redraw {
+            dirty.send(AsciiMarkDirtyEvent);
+        }
+    }
+
+    fn keyboard_focus(&self, bounds: &AsciiBounds) -> Option<IVec2> {
+        self.is_focused().then(|| IVec2::new(bounds.x, bounds.y))
+    }
+
+    fn accessibility(&self) -> Option<AsciiAccessible> {
+        Some(
+            AsciiAccessible::new(AsciiAccessibleRole::TextInput, &self.placeholder)
+                .value(self.state.text())
+                .focused(self.is_focused),
+        )
+    }
+}
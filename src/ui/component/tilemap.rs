@@ -0,0 +1,327 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::ui::{
+    buffer::AsciiBuffer, character::{AsciiCharacter, AsciiGlyph, Character, Color}, lightmap::{line, AsciiLightmap}, AsciiMarkDirtyEvent
+};
+
+use super::{super::{accessibility::{AsciiAccessible, AsciiAccessibleRole}, bounds::AsciiBounds}, AsciiComponent};
+
+//=============================================================================
+//             Ascii Tile
+//=============================================================================
+
+// One cell of an `AsciiTilemap`.
+#[derive(Clone, Copy, PartialEq, Debug, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct AsciiTile {
+    pub character: Character,
+    pub text_color: Color,
+    pub bg_color: Color,
+    // How lit the tile is, from 0 for dark to 1 for fully lit. Both colors are multiplied by it.
+    pub light: f32,
+    // Drawn instead of `character` when it is set, for tiles of a custom font page.
+    #[cfg_attr(feature = "serialize", serde(default))]
+    pub glyph: Option<AsciiGlyph>,
+}
+
+impl Default for AsciiTile {
+    fn default() -> Self {
+        AsciiTile::new(Character::Space, Color::White, Color::Black)
+    }
+}
+
+impl AsciiTile {
+    pub fn new(character: impl Into<Character>, text_color: Color, bg_color: Color) -> Self {
+        AsciiTile {
+            character: character.into(),
+            text_color,
+            bg_color,
+            light: 1.0,
+            glyph: None,
+        }
+    }
+
+    // A tile drawn with a glyph of any page of the font, see `AsciiFontPages`.
+    pub fn glyph(glyph: AsciiGlyph, text_color: Color, bg_color: Color) -> Self {
+        AsciiTile {
+            glyph: Some(glyph),
+            ..AsciiTile::new(Character::Space, text_color, bg_color)
+        }
+    }
+
+    pub fn light(mut self, light: f32) -> Self {
+        self.light = light;
+        self
+    }
+
+    pub fn to_character(&self) -> AsciiCharacter {
+        let (text_color, bg_color) = (self.text_color.dimmed(self.light), self.bg_color.dimmed(self.light));
+        match self.glyph {
+            Some(glyph) => AsciiCharacter::from_glyph(glyph, text_color, bg_color),
+            None => AsciiCharacter::new(self.character, text_color, bg_color),
+        }
+    }
+}
+
+//=============================================================================
+//             Ascii Tilemap
+//=============================================================================
+
+// A map of tiles that is bigger than the screen, like the level of a roguelike. The part of it
+// that fits in the bounds is drawn, starting at `offset`, or centered on the followed tile. The
+// tiles are kept in chunks, and the ui is only redrawn when a chunk that can be seen changed or
+// the view moved. Edits made through `bypass_change_detection` to tiles that are off screen do
+// not redraw the ui at all.
+#[derive(Component, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct AsciiTilemap {
+    width: u32,
+    height: u32,
+    chunks: Vec<AsciiTilemapChunk>,
+    // The tile drawn where the view goes past the edge of the map.
+    empty: AsciiTile,
+    // The tile of the map in the top left corner of the bounds.
+    offset: IVec2,
+    follow: Option<IVec2>,
+    // Darkens the tiles on top of their own light, it covers the map from its top left tile.
+    lightmap: Option<AsciiLightmap>,
+    // The part of the map that was shown the last time the tilemap was updated.
+    #[reflect(ignore)]
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    view: Option<AsciiBounds>,
+}
+
+#[derive(Clone, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+struct AsciiTilemapChunk {
+    tiles: Vec<AsciiTile>,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    is_dirty: bool,
+}
+
+impl AsciiTilemap {
+    // The width and height of a chunk, in tiles.
+    pub const CHUNK_SIZE: u32 = 16;
+
+    pub fn new(width: u32, height: u32) -> Self {
+        let chunk = AsciiTilemapChunk {
+            tiles: vec![AsciiTile::default(); (Self::CHUNK_SIZE * Self::CHUNK_SIZE) as usize],
+            is_dirty: true,
+        };
+        let chunk_count = width.div_ceil(Self::CHUNK_SIZE) * height.div_ceil(Self::CHUNK_SIZE);
+        AsciiTilemap {
+            width,
+            height,
+            chunks: vec![chunk; chunk_count as usize],
+            empty: AsciiTile::default(),
+            offset: IVec2::ZERO,
+            follow: None,
+            lightmap: None,
+            view: None,
+        }
+    }
+
+    pub fn empty_tile(mut self, tile: AsciiTile) -> Self {
+        self.empty = tile;
+        self
+    }
+
+    pub fn offset(mut self, offset: IVec2) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    // Keeps the tile in the middle of the bounds. The offset is not used while a tile is followed.
+    pub fn follow(mut self, tile: IVec2) -> Self {
+        self.follow = Some(tile);
+        self
+    }
+
+    pub fn with_lightmap(mut self, lightmap: AsciiLightmap) -> Self {
+        self.lightmap = Some(lightmap);
+        self
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn set_offset(&mut self, offset: IVec2) {
+        self.offset = offset;
+    }
+
+    pub fn set_follow(&mut self, tile: Option<IVec2>) {
+        self.follow = tile;
+    }
+
+    pub fn lightmap(&self) -> Option<&AsciiLightmap> {
+        self.lightmap.as_ref()
+    }
+
+    pub fn lightmap_mut(&mut self) -> Option<&mut AsciiLightmap> {
+        self.lightmap.as_mut()
+    }
+
+    pub fn set_lightmap(&mut self, lightmap: Option<AsciiLightmap>) {
+        self.lightmap = lightmap;
+    }
+
+    // The tile of the map in the top left corner of a view of the given size.
+    pub fn origin(&self, view_width: u32, view_height: u32) -> IVec2 {
+        match self.follow {
+            Some(tile) => tile - IVec2::new(view_width as i32 / 2, view_height as i32 / 2),
+            None => self.offset,
+        }
+    }
+
+    pub fn get(&self, x: i32, y: i32) -> Option<&AsciiTile> {
+        let (chunk, index) = self.index(x, y)?;
+        self.chunks[chunk].tiles.get(index)
+    }
+
+    pub fn set(&mut self, x: i32, y: i32, tile: AsciiTile) {
+        self.modify(x, y, |current| *current = tile);
+    }
+
+    pub fn fill(&mut self, tile: AsciiTile) {
+        for chunk in self.chunks.iter_mut() {
+            chunk.tiles.fill(tile);
+            chunk.is_dirty = true;
+        }
+    }
+
+    pub fn set_light(&mut self, x: i32, y: i32, light: f32) {
+        self.modify(x, y, |tile| tile.light = light);
+    }
+
+    // Sets the light of every tile, like to the light of the tiles the player remembers but can
+    // not see before `light_line_of_sight`.
+    pub fn fill_light(&mut self, light: f32) {
+        for y in 0..self.height as i32 {
+            for x in 0..self.width as i32 {
+                self.set_light(x, y, light);
+            }
+        }
+    }
+
+    // Lights the tiles within `radius` of `origin` that can be seen from it, fading out towards
+    // the edge. A tile can be seen when the line to it does not go through an opaque tile, the
+    // opaque tile itself is lit. Tiles are never made darker than they already are.
+    pub fn light_line_of_sight(&mut self, origin: IVec2, radius: u32, is_opaque: impl Fn(&AsciiTile) -> bool) {
+        let radius = radius as i32;
+        for y in origin.y - radius..=origin.y + radius {
+            for x in origin.x - radius..=origin.x + radius {
+                let target = IVec2::new(x, y);
+                let distance = (target - origin).as_vec2().length();
+                if distance > radius as f32 || self.get(x, y).is_none() {
+                    continue;
+                }
+                let is_visible = line(origin, target)
+                    .filter(|cell| *cell != origin && *cell != target)
+                    .all(|cell| !self.get(cell.x, cell.y).map(&is_opaque).unwrap_or(true));
+                if !is_visible {
+                    continue;
+                }
+
+                let light = 1.0 - distance / (radius + 1) as f32;
+                self.modify(x, y, |tile| tile.light = tile.light.max(light));
+            }
+        }
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<(usize, usize)> {
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return None;
+        }
+        let (x, y) = (x as u32, y as u32);
+        let chunks_per_row = self.width.div_ceil(Self::CHUNK_SIZE);
+        let chunk = (y / Self::CHUNK_SIZE) * chunks_per_row + x / Self::CHUNK_SIZE;
+        let index = (y % Self::CHUNK_SIZE) * Self::CHUNK_SIZE + x % Self::CHUNK_SIZE;
+        Some((chunk as usize, index as usize))
+    }
+
+    // Only chunks where a tile really changed have to be drawn again.
+    fn modify(&mut self, x: i32, y: i32, change: impl FnOnce(&mut AsciiTile)) {
+        let Some((chunk, index)) = self.index(x, y) else {
+            return;
+        };
+        let chunk = &mut self.chunks[chunk];
+        let Some(tile) = chunk.tiles.get_mut(index) else {
+            return;
+        };
+        let old = *tile;
+        change(tile);
+        chunk.is_dirty |= *tile != old;
+    }
+
+    // Whether a chunk that changed overlaps the part of the map in view.
+    fn is_view_dirty(&self, view: &AsciiBounds) -> bool {
+        let chunks_per_row = self.width.div_ceil(Self::CHUNK_SIZE) as i32;
+        let size = Self::CHUNK_SIZE as i32;
+        self.chunks.iter().enumerate().any(|(index, chunk)| {
+            let x = index as i32 % chunks_per_row * size;
+            let y = index as i32 / chunks_per_row * size;
+            chunk.is_dirty && !view.intersection(&AsciiBounds::new(x, y, size as u32, size as u32, 0)).is_empty()
+        })
+    }
+}
+
+impl Default for AsciiTilemap {
+    fn default() -> Self {
+        AsciiTilemap::new(0, 0)
+    }
+}
+
+//=============================================================================
+//             Component Impl
+//=============================================================================
+
+impl AsciiComponent for AsciiTilemap {
+    type UpdateQuery<'w, 's> = EventWriter<'w, AsciiMarkDirtyEvent>;
+
+    fn set_up(app: &mut App) {
+        app.register_type::<AsciiTile>();
+    }
+
+    fn render(&self, buffer: &mut AsciiBuffer) {
+        let width = buffer.bounds.width as i32;
+        let height = buffer.bounds.height as i32;
+        let origin = self.origin(buffer.bounds.width, buffer.bounds.height);
+        let buffer = match &self.lightmap {
+            Some(lightmap) => buffer.lightmap(lightmap, origin),
+            None => buffer.clone(),
+        };
+
+        buffer.set_characters((0..height).flat_map(|y| (0..width).map(move |x| (x, y))).map(|(x, y)| {
+            let tile = self.get(origin.x + x, origin.y + y).unwrap_or(&self.empty);
+            (x, y, tile.to_character())
+        }));
+    }
+
+    fn update(
+        &mut self,
+        query: &mut <Self::UpdateQuery<'_, '_> as SystemParam>::Item<'_, '_>,
+        bounds: &AsciiBounds,
+        _entity: Entity,
+    ) {
+        let origin = self.origin(bounds.width, bounds.height);
+        let view = AsciiBounds::new(origin.x, origin.y, bounds.width, bounds.height, 0);
+        if self.view.as_ref() != Some(&view) || self.is_view_dirty(&view) {
+            query.send(AsciiMarkDirtyEvent);
+        }
+
+        // Chunks that changed out of view are drawn when the view moves onto them.
+        for chunk in self.chunks.iter_mut() {
+            chunk.is_dirty = false;
+        }
+        self.view = Some(view);
+    }
+
+    fn accessibility(&self) -> Option<AsciiAccessible> {
+        Some(AsciiAccessible::new(AsciiAccessibleRole::Image, "Map"))
+    }
+}
@@ -0,0 +1,194 @@
+use bevy::{
+    ecs::system::SystemParam, prelude::*, window::PrimaryWindow
+};
+
+use crate::ui::{
+    buffer::AsciiBuffer, character::{Character, Color}, util::AsciiCursor, AsciiMarkDirtyEvent
+};
+
+use super::{super::{accessibility::{AsciiAccessible, AsciiAccessibleRole}, bounds::AsciiBounds}, AsciiComponent};
+
+//=============================================================================
+//             Ascii Toggle
+//=============================================================================
+
+// An on and off switch with an optional label after it. The switch is four cells wide, with a
+// two cell knob that sits on the left when off and on the right when on.
+#[derive(Component, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct AsciiToggle {
+    is_on: bool,
+    label: String,
+    on_color: Color,
+    off_color: Color,
+    knob_color: Color,
+    focused_knob_color: Color,
+    text_color: Color,
+    bg_color: Color,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    is_focused: bool,
+}
+
+impl AsciiToggle {
+    pub const SWITCH_WIDTH: i32 = 4;
+
+    pub fn new(is_on: bool) -> Self {
+        AsciiToggle {
+            is_on,
+            label: String::new(),
+            on_color: Color::Green,
+            off_color: Color::DarkGrey,
+            knob_color: Color::White,
+            focused_knob_color: Color::LightBlue,
+            text_color: Color::White,
+            bg_color: Color::Black,
+            is_focused: false,
+        }
+    }
+
+    pub fn label(mut self, label: &str) -> Self {
+        self.label = label.to_string();
+        self
+    }
+
+    pub fn on_color(mut self, color: Color) -> Self {
+        self.on_color = color;
+        self
+    }
+
+    pub fn off_color(mut self, color: Color) -> Self {
+        self.off_color = color;
+        self
+    }
+
+    pub fn knob_color(mut self, color: Color) -> Self {
+        self.knob_color = color;
+        self
+    }
+
+    pub fn focused_knob_color(mut self, color: Color) -> Self {
+        self.focused_knob_color = color;
+        self
+    }
+
+    pub fn text_color(mut self, color: Color) -> Self {
+        self.text_color = color;
+        self
+    }
+
+    pub fn bg_color(mut self, color: Color) -> Self {
+        self.bg_color = color;
+        self
+    }
+
+    pub fn is_on(&self) -> bool {
+        self.is_on
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.is_focused
+    }
+
+    pub fn set(&mut self, is_on: bool) {
+        self.is_on = is_on;
+    }
+
+    pub fn toggle(&mut self) {
+        self.is_on = !self.is_on;
+    }
+}
+
+//=============================================================================
+//             Toggle Events
+//=============================================================================
+
+#[derive(Event, Reflect, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AsciiToggleChangedEvent {
+    pub entity: Entity,
+    pub is_on: bool,
+}
+
+//=============================================================================
+//             Component Impl
+//=============================================================================
+
+impl AsciiComponent for AsciiToggle {
+    type UpdateQuery<'w, 's> = (
+        Query<'w, 's, &'static AsciiCursor, With<PrimaryWindow>>,
+        Res<'w, ButtonInput<MouseButton>>,
+        Res<'w, ButtonInput<KeyCode>>,
+        EventWriter<'w, AsciiToggleChangedEvent>,
+        EventWriter<'w, AsciiMarkDirtyEvent>,
+    );
+
+    fn set_up(app: &mut App) {
+        app.add_event::<AsciiToggleChangedEvent>();
+    }
+
+    fn render(&self, buffer: &mut AsciiBuffer) {
+        let width = buffer.bounds.width as i32;
+        if width == 0 || buffer.bounds.height == 0 {
+            return;
+        }
+
+        let y = (buffer.bounds.height / 2) as i32;
+        let knob_color = if self.is_focused { self.focused_knob_color } else { self.knob_color };
+        let (track_color, knob_start) = if self.is_on { (self.on_color, 2) } else { (self.off_color, 0) };
+
+        for x in 0..Self::SWITCH_WIDTH.min(width) {
+            let character = if x >= knob_start && x < knob_start + 2 {
+                (Character::Space, knob_color, knob_color)
+            } else {
+                (Character::Dither, track_color, self.bg_color)
+            };
+            buffer.set_character(x, y, character);
+        }
+
+        let label_start = Self::SWITCH_WIDTH + 1;
+        for (x, c) in (label_start..width).zip(self.label.chars()) {
+            buffer.set_character(x, y, (c, self.text_color, self.bg_color));
+        }
+    }
+
+    fn update(
+        &mut self,
+        query: &mut <Self::UpdateQuery<'_, '_> as SystemParam>::Item<'_, '_>,
+        bounds: &AsciiBounds,
+        entity: Entity,
+    ) {
+        let (cursor, mouse, keys, changed, dirty) = query;
+        let was_on = self.is_on;
+        let was_focused = self.is_focused;
+
+        let is_hovering = match cursor.get_single() {
+            Ok(AsciiCursor::Some { x, y }) => bounds.is_within(*x as i32, *y as i32),
+            _ => false,
+        };
+
+        if mouse.just_pressed(MouseButton::Left) {
+            self.is_focused = is_hovering;
+            if is_hovering {
+                self.toggle();
+            }
+        }
+
+        if self.is_focused && (keys.just_pressed(KeyCode::Space) || keys.just_pressed(KeyCode::Enter)) {
+            self.toggle();
+        }
+
+        if self.is_on != was_on {
+            changed.send(AsciiToggleChangedEvent { entity, is_on: self.is_on });
+            dirty.send(AsciiMarkDirtyEvent);
+        } else if self.is_focused != was_focused {
+            dirty.send(AsciiMarkDirtyEvent);
+        }
+    }
+
+    fn accessibility(&self) -> Option<AsciiAccessible> {
+        Some(
+            AsciiAccessible::new(AsciiAccessibleRole::Switch, &self.label)
+                .value(if self.is_on { "on" } else { "off" })
+                .focused(self.is_focused),
+        )
+    }
+}
@@ -0,0 +1,377 @@
+use bevy::{
+    ecs::system::SystemParam, prelude::*, window::PrimaryWindow
+};
+
+use crate::ui::{
+    buffer::AsciiBuffer, character::Color, util::AsciiCursor, AsciiMarkDirtyEvent, AsciiUiSet
+};
+
+use super::{
+    super::{accessibility::{AsciiAccessible, AsciiAccessibleRole}, bounds::AsciiBounds},
+    text_input::{AsciiTextInput, AsciiTextInputChangedEvent, AsciiTextInputSubmittedEvent},
+    AsciiComponent,
+};
+
+//=============================================================================
+//             Ascii Virtual Keyboard
+//=============================================================================
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum AsciiVirtualKey {
+    Char(char),
+    Shift,
+    Space,
+    Backspace,
+    Enter,
+}
+
+impl AsciiVirtualKey {
+    pub fn label(&self, is_shifted: bool) -> String {
+        match self {
+            AsciiVirtualKey::Char(c) if is_shifted => c.to_uppercase().collect(),
+            AsciiVirtualKey::Char(c) => c.to_string(),
+            AsciiVirtualKey::Shift => "Shift".to_string(),
+            AsciiVirtualKey::Space => "Space".to_string(),
+            AsciiVirtualKey::Backspace => "Del".to_string(),
+            AsciiVirtualKey::Enter => "Done".to_string(),
+        }
+    }
+
+    // Keys are drawn as " key ".
+    fn width(&self) -> i32 {
+        self.label(false).chars().count() as i32 + 2
+    }
+}
+
+// An on screen keyboard for platforms without one, like consoles and phones. It types into the
+// `AsciiTextInput` that is focused, or was focused last. Keys are picked with the d-pad and
+// pressed with the south button, or tapped and clicked. East is backspace, west is space and
+// start is enter. Every row of characters is one row of keys, the last row holds shift, space,
+// backspace and enter.
+#[derive(Component, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct AsciiVirtualKeyboard {
+    rows: Vec<Vec<AsciiVirtualKey>>,
+    text_color: Color,
+    bg_color: Color,
+    selected_text_color: Color,
+    selected_bg_color: Color,
+    hover_text_color: Color,
+    // The row and column of the key the d-pad is on.
+    selected: (usize, usize),
+    // The next character is typed in upper case.
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    is_shifted: bool,
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    hovered: Option<(usize, usize)>,
+}
+
+impl AsciiVirtualKeyboard {
+    pub fn new() -> Self {
+        AsciiVirtualKeyboard {
+            rows: Vec::new(),
+            text_color: Color::LightGrey,
+            bg_color: Color::Black,
+            selected_text_color: Color::Black,
+            selected_bg_color: Color::White,
+            hover_text_color: Color::White,
+            selected: (0, 0),
+            is_shifted: false,
+            hovered: None,
+        }
+        .rows(&["1234567890", "qwertyuiop", "asdfghjkl", "zxcvbnm"])
+    }
+
+    // The rows of characters, like `&["abc", "def"]`. Empty rows are left out.
+    pub fn rows(mut self, rows: &[&str]) -> Self {
+        self.rows = rows
+            .iter()
+            .filter(|row| !row.is_empty())
+            .map(|row| row.chars().map(AsciiVirtualKey::Char).collect())
+            .collect();
+        self.rows.push(vec![AsciiVirtualKey::Shift, AsciiVirtualKey::Space, AsciiVirtualKey::Backspace, AsciiVirtualKey::Enter]);
+        self.selected = (0, 0);
+        self
+    }
+
+    pub fn text_color(mut self, color: Color) -> Self {
+        self.text_color = color;
+        self
+    }
+
+    pub fn bg_color(mut self, color: Color) -> Self {
+        self.bg_color = color;
+        self
+    }
+
+    pub fn selected_text_color(mut self, color: Color) -> Self {
+        self.selected_text_color = color;
+        self
+    }
+
+    pub fn selected_bg_color(mut self, color: Color) -> Self {
+        self.selected_bg_color = color;
+        self
+    }
+
+    pub fn hover_text_color(mut self, color: Color) -> Self {
+        self.hover_text_color = color;
+        self
+    }
+
+    pub fn selected_key(&self) -> Option<AsciiVirtualKey> {
+        self.rows.get(self.selected.0)?.get(self.selected.1).copied()
+    }
+
+    pub fn is_shifted(&self) -> bool {
+        self.is_shifted
+    }
+
+    // The start and (exclusive) end column of every key, row by row. The rows are centered under
+    // each other, and the keyboard is centered in a `width` wide node.
+    fn key_ranges(&self, width: u32) -> Vec<Vec<(i32, i32)>> {
+        let row_width = |row: &Vec<AsciiVirtualKey>| row.iter().map(|key| key.width()).sum::<i32>();
+        let widest = self.rows.iter().map(row_width).max().unwrap_or(0);
+        let left = (width as i32 - widest).max(0) / 2;
+        self.rows
+            .iter()
+            .map(|row| {
+                let mut x = left + (widest - row_width(row)) / 2;
+                row.iter()
+                    .map(|key| {
+                        let start = x;
+                        x += key.width();
+                        (start, x)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    // Moves the selection up or down, to the key in the other row that is closest to it.
+    fn move_rows(&mut self, rows: i32) {
+        let ranges = self.key_ranges(0);
+        let Some((start, end)) = ranges.get(self.selected.0).and_then(|row| row.get(self.selected.1)) else {
+            return;
+        };
+        let center = start + end;
+        let row = (self.selected.0 as i32 + rows).rem_euclid(self.rows.len() as i32) as usize;
+        let column = ranges[row]
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, (start, end))| (start + end - center).abs())
+            .map(|(column, _)| column)
+            .unwrap_or(0);
+        self.selected = (row, column);
+    }
+
+    fn move_columns(&mut self, columns: i32) {
+        let Some(row) = self.rows.get(self.selected.0) else {
+            return;
+        };
+        self.selected.1 = (self.selected.1 as i32 + columns).rem_euclid(row.len() as i32) as usize;
+    }
+
+    // Presses a key. Shift only changes the case of the next character, it is not sent.
+    fn press(&mut self, key: AsciiVirtualKey) -> Option<AsciiVirtualKey> {
+        match key {
+            AsciiVirtualKey::Shift => {
+                self.is_shifted = !self.is_shifted;
+                None
+            }
+            AsciiVirtualKey::Char(c) if self.is_shifted => {
+                self.is_shifted = false;
+                Some(AsciiVirtualKey::Char(c.to_uppercase().next().unwrap_or(c)))
+            }
+            key => Some(key),
+        }
+    }
+}
+
+impl Default for AsciiVirtualKeyboard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//=============================================================================
+//             Virtual Keyboard Events
+//=============================================================================
+
+// Characters are sent in the case they are typed in.
+#[derive(Event, Reflect, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AsciiVirtualKeyPressedEvent {
+    pub entity: Entity,
+    pub key: AsciiVirtualKey,
+}
+
+// Types the pressed keys into the text input that is focused, or was focused last. Tapping a key
+// takes the focus away from the input, so it is given back.
+fn type_virtual_keys(
+    mut keys: EventReader<AsciiVirtualKeyPressedEvent>,
+    mut inputs: Query<(Entity, &mut AsciiTextInput)>,
+    mut changed: EventWriter<AsciiTextInputChangedEvent>,
+    mut submitted: EventWriter<AsciiTextInputSubmittedEvent>,
+    mut dirty: EventWriter<AsciiMarkDirtyEvent>,
+    mut last_focused: Local<Option<Entity>>,
+) {
+    let focused = inputs.iter().find(|(_, input)| input.is_focused()).map(|(entity, _)| entity);
+    let target = focused.or(*last_focused);
+    *last_focused = target;
+
+    for event in keys.read() {
+        let Some((entity, mut input)) = target.and_then(|target| inputs.get_mut(target).ok()) else {
+            continue;
+        };
+        if !input.is_focused() {
+            input.focus();
+            dirty.send(AsciiMarkDirtyEvent);
+        }
+
+        let state = input.state_mut();
+        let is_changed = match event.key {
+            AsciiVirtualKey::Char(c) => state.insert(c),
+            AsciiVirtualKey::Space => state.insert(' '),
+            AsciiVirtualKey::Backspace => state.backspace(),
+            AsciiVirtualKey::Shift => false,
+            AsciiVirtualKey::Enter => {
+                let text = input.submit();
+                let is_cleared = input.text() != text;
+                submitted.send(AsciiTextInputSubmittedEvent { entity, text });
+                is_cleared
+            }
+        };
+        if is_changed {
+            changed.send(AsciiTextInputChangedEvent { entity, text: input.text().to_string() });
+            dirty.send(AsciiMarkDirtyEvent);
+        }
+    }
+}
+
+//=============================================================================
+//             Component Impl
+//=============================================================================
+
+impl AsciiComponent for AsciiVirtualKeyboard {
+    type UpdateQuery<'w, 's> = (
+        Query<'w, 's, &'static AsciiCursor, With<PrimaryWindow>>,
+        Res<'w, ButtonInput<MouseButton>>,
+        Res<'w, Gamepads>,
+        Res<'w, ButtonInput<GamepadButton>>,
+        EventWriter<'w, AsciiVirtualKeyPressedEvent>,
+        EventWriter<'w, AsciiMarkDirtyEvent>,
+    );
+
+    fn set_up(app: &mut App) {
+        app.add_event::<AsciiVirtualKeyPressedEvent>()
+            .add_systems(Update, type_virtual_keys.after(AsciiUiSet::Interaction));
+    }
+
+    fn render(&self, buffer: &mut AsciiBuffer) {
+        let width = buffer.bounds.width as i32;
+        if width == 0 || buffer.bounds.height == 0 {
+            return;
+        }
+
+        let ranges = self.key_ranges(buffer.bounds.width);
+        for (y, (keys, ranges)) in self.rows.iter().zip(ranges).enumerate() {
+            if y >= buffer.bounds.height as usize {
+                break;
+            }
+            for (column, (key, (start, end))) in keys.iter().zip(ranges).enumerate() {
+                let (text_color, bg_color) = if self.selected == (y, column) {
+                    (self.selected_text_color, self.selected_bg_color)
+                } else if self.hovered == Some((y, column)) || (*key == AsciiVirtualKey::Shift && self.is_shifted) {
+                    (self.hover_text_color, self.bg_color)
+                } else {
+                    (self.text_color, self.bg_color)
+                };
+
+                let label = format!(" {} ", key.label(self.is_shifted));
+                for (x, c) in (start..end.min(width)).zip(label.chars()) {
+                    buffer.set_character(x, y as i32, (c, text_color, bg_color));
+                }
+            }
+        }
+    }
+
+    fn update(
+        &mut self,
+        query: &mut <Self::UpdateQuery<'_, '_> as SystemParam>::Item<'_, '_>,
+        bounds: &AsciiBounds,
+        entity: Entity,
+    ) {
+        let (cursor, mouse, gamepads, buttons, pressed, dirty) = query;
+        let old_selected = self.selected;
+        let old_hovered = self.hovered;
+        let was_shifted = self.is_shifted;
+
+        let cursor = match cursor.get_single() {
+            Ok(AsciiCursor::Some { x, y }) => Some((*x as i32 - bounds.x, *y as i32 - bounds.y)),
+            _ => None,
+        };
+        let ranges = self.key_ranges(bounds.width);
+        self.hovered = cursor.and_then(|(x, y)| {
+            let row = usize::try_from(y).ok().filter(|_| x < bounds.width as i32)?;
+            let column = ranges.get(row)?.iter().position(|(start, end)| x >= *start && x < *end)?;
+            Some((row, column))
+        });
+
+        let just_pressed = |button_type: GamepadButtonType| {
+            gamepads.iter().any(|gamepad| buttons.just_pressed(GamepadButton::new(gamepad, button_type)))
+        };
+        if just_pressed(GamepadButtonType::DPadUp) {
+            self.move_rows(-1);
+        }
+        if just_pressed(GamepadButtonType::DPadDown) {
+            self.move_rows(1);
+        }
+        if just_pressed(GamepadButtonType::DPadLeft) {
+            self.move_columns(-1);
+        }
+        if just_pressed(GamepadButtonType::DPadRight) {
+            self.move_columns(1);
+        }
+
+        let mut keys = Vec::new();
+        if let (true, Some(hovered)) = (mouse.just_pressed(MouseButton::Left), self.hovered) {
+            self.selected = hovered;
+            keys.extend(self.selected_key());
+        }
+        if just_pressed(GamepadButtonType::South) {
+            keys.extend(self.selected_key());
+        }
+        if just_pressed(GamepadButtonType::East) {
+            keys.push(AsciiVirtualKey::Backspace);
+        }
+        if just_pressed(GamepadButtonType::West) {
+            keys.push(AsciiVirtualKey::Space);
+        }
+        if just_pressed(GamepadButtonType::Start) {
+            keys.push(AsciiVirtualKey::Enter);
+        }
+        for key in keys {
+            if let Some(key) = self.press(key) {
+                pressed.send(AsciiVirtualKeyPressedEvent { entity, key });
+            }
+        }
+
+        if self.selected != old_selected || self.hovered != old_hovered || self.is_shifted != was_shifted {
+            dirty.send(AsciiMarkDirtyEvent);
+        }
+    }
+
+    fn measure(&self) -> Option<UVec2> {
+        let width = self.key_ranges(0).iter().filter_map(|row| row.last()).map(|(_, end)| *end).max().unwrap_or(0);
+        Some(UVec2::new(width as u32, self.rows.len() as u32))
+    }
+
+    fn accessibility(&self) -> Option<AsciiAccessible> {
+        let accessible = AsciiAccessible::new(AsciiAccessibleRole::Group, "Keyboard");
+        match self.selected_key() {
+            Some(key) => Some(accessible.value(key.label(self.is_shifted))),
+            None => Some(accessible),
+        }
+    }
+}
@@ -0,0 +1,398 @@
+use bevy::{
+    ecs::system::SystemParam, prelude::*, window::PrimaryWindow
+};
+
+use crate::ui::{
+    bounds::AsciiNode, buffer::AsciiBuffer, character::{Character, Color}, position::{AsciiContentPadding, AsciiPosition}, util::{AsciiCursor, Value}, AsciiMarkDirtyEvent, BorderType, Padding
+};
+
+use super::{super::{accessibility::{AsciiAccessible, AsciiAccessibleRole}, bounds::AsciiBounds}, AsciiComponent};
+
+// Every time a window is brought to the front it is moved this many layers above the last one,
+// leaving room for the layers its children add on top of it.
+const WINDOW_LAYER_STEP: u32 = 32;
+
+// The border and title bar around the content of a window.
+const WINDOW_PADDING: Padding = Padding { top: 1, right: 1, bottom: 1, left: 1 };
+
+//=============================================================================
+//             Ascii Window
+//=============================================================================
+
+#[derive(Component, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct AsciiWindow {
+    title: String,
+    closable: bool,
+    minimizable: bool,
+    resizable: bool,
+    draggable: bool,
+    is_minimized: bool,
+    min_width: u32,
+    min_height: u32,
+    bg_color: Color,
+    border_color: Color,
+    title_color: Color,
+    active_border_color: Color,
+    #[reflect(ignore)]
+    #[cfg_attr(feature = "serialize", serde(skip))]
+    interaction: WindowInteraction,
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+enum WindowInteraction {
+    #[default]
+    None,
+    Dragging {
+        offset_x: i32,
+        offset_y: i32,
+    },
+    Resizing,
+}
+
+impl AsciiWindow {
+    pub fn new(title: &str) -> Self {
+        AsciiWindow {
+            title: title.to_string(),
+            closable: true,
+            minimizable: true,
+            resizable: true,
+            draggable: true,
+            is_minimized: false,
+            min_width: 8,
+            min_height: 3,
+            bg_color: Color::Black,
+            border_color: Color::White,
+            title_color: Color::Black,
+            active_border_color: Color::LightBlue,
+            interaction: WindowInteraction::None,
+        }
+    }
+
+    pub fn closable(mut self, closable: bool) -> Self {
+        self.closable = closable;
+        self
+    }
+
+    pub fn minimizable(mut self, minimizable: bool) -> Self {
+        self.minimizable = minimizable;
+        self
+    }
+
+    pub fn resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    pub fn draggable(mut self, draggable: bool) -> Self {
+        self.draggable = draggable;
+        self
+    }
+
+    pub fn min_size(mut self, width: u32, height: u32) -> Self {
+        self.min_width = width.max(3);
+        self.min_height = height.max(1);
+        self
+    }
+
+    pub fn bg_color(mut self, color: Color) -> Self {
+        self.bg_color = color;
+        self
+    }
+
+    pub fn border_color(mut self, color: Color) -> Self {
+        self.border_color = color;
+        self
+    }
+
+    pub fn title_color(mut self, color: Color) -> Self {
+        self.title_color = color;
+        self
+    }
+
+    pub fn active_border_color(mut self, color: Color) -> Self {
+        self.active_border_color = color;
+        self
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn set_title(&mut self, title: &str) {
+        self.title = title.to_string();
+    }
+
+    pub fn is_minimized(&self) -> bool {
+        self.is_minimized
+    }
+
+    // The area inside the window's border and title bar that its children are laid out in.
+    pub fn content_bounds(bounds: &AsciiBounds) -> AsciiBounds {
+        AsciiContentPadding(WINDOW_PADDING).content_bounds(bounds)
+    }
+
+    fn close_button_x(&self, width: u32) -> Option<i32> {
+        if self.closable && width >= 6 {
+            Some(width as i32 - 2)
+        } else {
+            None
+        }
+    }
+
+    fn minimize_button_x(&self, width: u32) -> Option<i32> {
+        if !self.minimizable || width < 8 {
+            return None;
+        }
+        if self.close_button_x(width).is_some() {
+            Some(width as i32 - 4)
+        } else {
+            Some(width as i32 - 2)
+        }
+    }
+}
+
+//=============================================================================
+//             Window Stack
+//=============================================================================
+
+// Keeps track of which window is on top and which window is currently being dragged or resized,
+// so overlapping windows don't all react to the same click.
+#[derive(Resource, Default)]
+pub struct AsciiWindowStack {
+    top: Option<Entity>,
+    next_layer: u32,
+    active: Option<Entity>,
+}
+
+impl AsciiWindowStack {
+    pub fn top(&self) -> Option<Entity> {
+        self.top
+    }
+
+    fn raise(&mut self, entity: Entity) -> Option<u32> {
+        if self.top == Some(entity) {
+            return None;
+        }
+        self.top = Some(entity);
+        self.next_layer += WINDOW_LAYER_STEP;
+        Some(self.next_layer)
+    }
+}
+
+//=============================================================================
+//             Window Events
+//=============================================================================
+
+#[derive(Event, Reflect, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AsciiWindowClosedEvent(pub Entity);
+
+#[derive(Event, Reflect, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AsciiWindowMinimizedEvent {
+    pub window: Entity,
+    pub minimized: bool,
+}
+
+//=============================================================================
+//             Component Impl
+//=============================================================================
+
+impl AsciiComponent for AsciiWindow {
+    type UpdateQuery<'w, 's> = (
+        Query<'w, 's, &'static AsciiCursor, With<PrimaryWindow>>,
+        Res<'w, ButtonInput<MouseButton>>,
+        Query<'w, 's, &'static mut AsciiPosition>,
+        Query<'w, 's, &'static mut Visibility>,
+        Query<'w, 's, &'static Children>,
+        Query<'w, 's, &'static Parent>,
+        Query<'w, 's, &'static AsciiNode>,
+        ResMut<'w, AsciiWindowStack>,
+        EventWriter<'w, AsciiWindowClosedEvent>,
+        EventWriter<'w, AsciiWindowMinimizedEvent>,
+        EventWriter<'w, AsciiMarkDirtyEvent>,
+    );
+
+    fn set_up(app: &mut App) {
+        app.init_resource::<AsciiWindowStack>()
+            .add_event::<AsciiWindowClosedEvent>()
+            .add_event::<AsciiWindowMinimizedEvent>();
+    }
+
+    fn render(&self, buffer: &mut AsciiBuffer) {
+        let width = buffer.bounds.width;
+        let height = buffer.bounds.height;
+        if width < 3 || height == 0 {
+            return;
+        }
+
+        let border_color = if self.interaction != WindowInteraction::None {
+            self.active_border_color
+        } else {
+            self.border_color
+        };
+
+        let frame = if self.is_minimized { buffer.top(1) } else { buffer.clone() };
+        frame
+            .square()
+            .border(BorderType::Full)
+            .title(&self.title)
+            .title_text_color(self.title_color)
+            .border_color(border_color)
+            .bg_color(self.bg_color)
+            .draw();
+
+        if let Some(x) = self.minimize_button_x(width) {
+            let glyph = if self.is_minimized { Character::Plus } else { Character::Hyphen };
+            buffer.set_character(x, 0, (glyph, self.title_color, border_color));
+        }
+
+        if let Some(x) = self.close_button_x(width) {
+            buffer.set_character(x, 0, (Character::X, self.title_color, border_color));
+        }
+
+        if self.resizable && !self.is_minimized && height > 1 {
+            buffer.set_character(
+                width as i32 - 1,
+                height as i32 - 1,
+                (Character::QuadSE, border_color, self.bg_color),
+            );
+        }
+    }
+
+    fn update(
+        &mut self,
+        query: &mut <Self::UpdateQuery<'_, '_> as SystemParam>::Item<'_, '_>,
+        bounds: &AsciiBounds,
+        entity: Entity,
+    ) {
+        let (cursor, mouse, positions, visibilities, children, parents, nodes, stack, closed, minimized, dirty) = query;
+
+        if mouse.just_released(MouseButton::Left) && self.interaction != WindowInteraction::None {
+            self.interaction = WindowInteraction::None;
+            if stack.active == Some(entity) {
+                stack.active = None;
+            }
+            dirty.send(AsciiMarkDirtyEvent);
+        }
+
+        let Ok(AsciiCursor::Some { x, y }) = cursor.get_single() else { return };
+        let (cursor_x, cursor_y) = (*x as i32, *y as i32);
+        let local_x = cursor_x - bounds.x;
+        let local_y = cursor_y - bounds.y;
+        let height = if self.is_minimized { bounds.height.min(1) } else { bounds.height };
+        let is_inside = local_x >= 0
+            && local_y >= 0
+            && local_x < bounds.width as i32
+            && local_y < height as i32;
+
+        if mouse.just_pressed(MouseButton::Left) && is_inside && stack.active.is_none() {
+            if let Some(layer) = stack.raise(entity) {
+                set_relative_bounds(entity, bounds, Some(layer), positions, parents, nodes);
+            }
+
+            if local_y == 0 && self.close_button_x(bounds.width) == Some(local_x) {
+                if let Ok(mut visibility) = visibilities.get_mut(entity) {
+                    *visibility = Visibility::Hidden;
+                }
+                closed.send(AsciiWindowClosedEvent(entity));
+            } else if local_y == 0 && self.minimize_button_x(bounds.width) == Some(local_x) {
+                self.is_minimized = !self.is_minimized;
+                let child_visibility = if self.is_minimized { Visibility::Hidden } else { Visibility::Inherited };
+                if let Ok(children) = children.get(entity) {
+                    for child in children.iter() {
+                        if let Ok(mut visibility) = visibilities.get_mut(*child) {
+                            *visibility = child_visibility;
+                        }
+                    }
+                }
+                minimized.send(AsciiWindowMinimizedEvent { window: entity, minimized: self.is_minimized });
+            } else if self.resizable
+                && !self.is_minimized
+                && local_x == bounds.width as i32 - 1
+                && local_y == bounds.height as i32 - 1
+            {
+                self.interaction = WindowInteraction::Resizing;
+                stack.active = Some(entity);
+            } else if self.draggable && local_y == 0 {
+                self.interaction = WindowInteraction::Dragging { offset_x: local_x, offset_y: local_y };
+                stack.active = Some(entity);
+            }
+
+            dirty.send(AsciiMarkDirtyEvent);
+        }
+
+        if !mouse.pressed(MouseButton::Left) {
+            return;
+        }
+
+        match self.interaction {
+            WindowInteraction::Dragging { offset_x, offset_y } => {
+                let new_x = cursor_x - offset_x;
+                let new_y = cursor_y - offset_y;
+                if new_x != bounds.x || new_y != bounds.y {
+                    let mut moved = bounds.clone();
+                    moved.x = new_x;
+                    moved.y = new_y;
+                    set_relative_bounds(entity, &moved, None, positions, parents, nodes);
+                }
+            }
+            WindowInteraction::Resizing => {
+                let new_width = (local_x + 1).max(self.min_width as i32) as u32;
+                let new_height = (local_y + 1).max(self.min_height as i32) as u32;
+                if new_width != bounds.width || new_height != bounds.height {
+                    let mut resized = bounds.clone();
+                    resized.width = new_width;
+                    resized.height = new_height;
+                    set_relative_bounds(entity, &resized, None, positions, parents, nodes);
+                }
+            }
+            WindowInteraction::None => {}
+        }
+    }
+
+    fn content_padding(&self) -> Option<Padding> {
+        Some(WINDOW_PADDING)
+    }
+
+    fn accessibility(&self) -> Option<AsciiAccessible> {
+        let accessible = AsciiAccessible::new(AsciiAccessibleRole::Window, &self.title);
+        if self.is_minimized {
+            Some(accessible.value("minimized"))
+        } else {
+            Some(accessible)
+        }
+    }
+}
+
+// Rewrites the window's position as a relative position that results in the given global bounds.
+// Windows can be spawned with any position, but once they are moved, resized or raised they
+// are tracked as an offset from their parent.
+fn set_relative_bounds(
+    entity: Entity,
+    bounds: &AsciiBounds,
+    layer: Option<u32>,
+    positions: &mut Query<&mut AsciiPosition>,
+    parents: &Query<&Parent>,
+    nodes: &Query<&AsciiNode>,
+) {
+    let (parent_x, parent_y) = parents
+        .get(entity)
+        .ok()
+        .and_then(|parent| nodes.get(**parent).ok())
+        .map(|node| (node.bounds.x, node.bounds.y))
+        .unwrap_or((0, 0));
+
+    let Ok(mut position) = positions.get_mut(entity) else { return };
+    let current_layer = match *position {
+        AsciiPosition::Relative { layer, .. } => layer,
+        _ => 0,
+    };
+
+    *position = AsciiPosition::Relative {
+        x: bounds.x - parent_x,
+        y: bounds.y - parent_y,
+        width: Value::Px(bounds.width as i32),
+        height: Value::Px(bounds.height as i32),
+        layer: layer.unwrap_or(current_layer),
+    };
+}
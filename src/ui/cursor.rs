@@ -0,0 +1,168 @@
+use bevy::{prelude::*, render::camera::NormalizedRenderTarget, utils::HashSet, window::PrimaryWindow};
+
+use crate::ascii::AsciiCamera;
+
+use super::{
+    character::{AsciiBlendMode, Character, Color},
+    overlay::{mark_overlay_dirty, AsciiOverlay},
+    touch::AsciiTouch,
+    util::cursor_cell,
+    AsciiUiSchedule, AsciiUiSet,
+};
+
+//=============================================================================
+//             Plugin and Systems
+//=============================================================================
+
+pub(crate) struct AsciiCursorGlyphPlugin;
+
+impl Plugin for AsciiCursorGlyphPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .register_type::<AsciiCursorGlyph>()
+            .add_systems(
+                AsciiUiSchedule,
+                (hide_system_cursors, draw_cursor_glyphs)
+                    .in_set(AsciiUiSet::Layout)
+                    .before(mark_overlay_dirty),
+            )
+        ;
+    }
+}
+
+//=============================================================================
+//             Ascii Cursor Glyph
+//=============================================================================
+
+// Draws the mouse cursor as a character on the overlay of the camera it is added to, in the cell
+// the hit test uses. The ui is redrawn every frame the cursor is over the camera.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct AsciiCursorGlyph {
+    pub character: Character,
+    pub text_color: Color,
+    pub bg_color: Color,
+    pub blend: AsciiBlendMode,
+    // Hides the cursor of the system while it is over the camera, so only the glyph is seen.
+    pub hide_system_cursor: bool,
+}
+
+impl Default for AsciiCursorGlyph {
+    fn default() -> Self {
+        AsciiCursorGlyph::block(Color::White)
+    }
+}
+
+impl AsciiCursorGlyph {
+    // Draws over everything, the debug overlay included.
+    pub const LAYER: u32 = u32::MAX;
+
+    // A cell filled with the color.
+    pub fn block(color: Color) -> Self {
+        AsciiCursorGlyph {
+            character: Character::Dither,
+            text_color: color,
+            bg_color: color,
+            blend: AsciiBlendMode::Replace,
+            hide_system_cursor: true,
+        }
+    }
+
+    // An arrow of the color on a black cell.
+    pub fn arrow(color: Color) -> Self {
+        AsciiCursorGlyph {
+            character: Character::ArrowUp,
+            text_color: color,
+            bg_color: Color::Black,
+            blend: AsciiBlendMode::Replace,
+            hide_system_cursor: true,
+        }
+    }
+
+    pub fn character(mut self, character: Character) -> Self {
+        self.character = character;
+        self
+    }
+
+    pub fn bg_color(mut self, bg_color: Color) -> Self {
+        self.bg_color = bg_color;
+        self
+    }
+
+    pub fn blend(mut self, blend: AsciiBlendMode) -> Self {
+        self.blend = blend;
+        self
+    }
+
+    pub fn show_system_cursor(mut self) -> Self {
+        self.hide_system_cursor = false;
+        self
+    }
+}
+
+fn camera_window(camera: &Camera, primary_window: Option<Entity>) -> Option<Entity> {
+    match camera.target.normalize(primary_window)? {
+        NormalizedRenderTarget::Window(window) => Some(window.entity()),
+        _ => None,
+    }
+}
+
+fn draw_cursor_glyphs(
+    cameras: Query<(Entity, &Camera, &AsciiCamera, &AsciiCursorGlyph)>,
+    primary_window: Query<Entity, With<PrimaryWindow>>,
+    windows: Query<&Window>,
+    overlay: Res<AsciiOverlay>,
+    touch: Option<Res<AsciiTouch>>,
+) {
+    for (entity, camera, ascii_camera, glyph) in cameras.iter() {
+        let Some((window_entity, window)) = camera_window(camera, primary_window.get_single().ok())
+            .and_then(|window| Some((window, windows.get(window).ok()?)))
+        else {
+            continue;
+        };
+        let Some(cell) = cursor_cell(window_entity, window, touch.as_deref(), camera, ascii_camera) else {
+            continue;
+        };
+
+        let glyph = *glyph;
+        overlay.draw_on_layer(entity, AsciiCursorGlyph::LAYER, move |buffer| {
+            buffer.blend(glyph.blend).set_character(cell.x, cell.y, (glyph.character, glyph.text_color, glyph.bg_color));
+        });
+    }
+}
+
+// Only windows with a glyph camera are touched, and they get their cursor back once the last
+// glyph is removed.
+fn hide_system_cursors(
+    cameras: Query<(&Camera, &AsciiCamera, &AsciiCursorGlyph)>,
+    primary_window: Query<Entity, With<PrimaryWindow>>,
+    mut windows: Query<(Entity, &mut Window)>,
+    mut managed: Local<HashSet<Entity>>,
+) {
+    let primary_window = primary_window.get_single().ok();
+    let mut hidden = HashSet::new();
+    let mut with_glyph = HashSet::new();
+    for (camera, ascii_camera, glyph) in cameras.iter() {
+        let Some(window_entity) = camera_window(camera, primary_window) else {
+            continue;
+        };
+        with_glyph.insert(window_entity);
+        let Ok((_, window)) = windows.get(window_entity) else {
+            continue;
+        };
+        if glyph.hide_system_cursor && cursor_cell(window_entity, window, None, camera, ascii_camera).is_some() {
+            hidden.insert(window_entity);
+        }
+    }
+
+    for (entity, mut window) in windows.iter_mut() {
+        if !with_glyph.contains(&entity) && !managed.contains(&entity) {
+            continue;
+        }
+        let visible = !hidden.contains(&entity);
+        if window.cursor.visible != visible {
+            window.cursor.visible = visible;
+        }
+    }
+    *managed = with_glyph;
+}
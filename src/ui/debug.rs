@@ -0,0 +1,375 @@
+use bevy::prelude::*;
+
+use crate::ascii::AsciiCamera;
+
+use super::{
+    bounds::{AsciiBounds, AsciiNode},
+    character::Color,
+    overlay::{mark_overlay_dirty, AsciiOverlay},
+    position::update_positions,
+    ui_target,
+    util::AsciiHitTest,
+    AsciiUi, AsciiUiSchedule, AsciiUiSet, AsciiUiTarget, BorderStyle, HorizontalAlignment,
+};
+
+//=============================================================================
+//             Debug Overlay Plugin
+//=============================================================================
+
+// Draws the bounds and layer of every ui node, which nodes were laid out again and the cell under
+// the cursor straight onto the overlay. `toggle_key` turns it on and off.
+pub struct AsciiDebugOverlayPlugin {
+    pub toggle_key: Option<KeyCode>,
+    pub is_enabled: bool,
+}
+
+impl Default for AsciiDebugOverlayPlugin {
+    fn default() -> Self {
+        AsciiDebugOverlayPlugin {
+            toggle_key: Some(KeyCode::F3),
+            is_enabled: false,
+        }
+    }
+}
+
+impl Plugin for AsciiDebugOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        // A debug overlay that was inserted before the plugin, like `bounds_gizmos`, is kept.
+        if !app.world.contains_resource::<AsciiDebugOverlay>() {
+            app.insert_resource(AsciiDebugOverlay {
+                toggle_key: self.toggle_key,
+                is_enabled: self.is_enabled,
+                ..Default::default()
+            });
+        }
+
+        app
+            .register_type::<AsciiDebugOverlay>()
+            .register_type::<AsciiDebugColors>()
+            .add_systems(Update, toggle_debug_overlay.in_set(AsciiUiSet::Interaction))
+            .add_systems(
+                AsciiUiSchedule,
+                draw_debug_overlay
+                    .in_set(AsciiUiSet::Layout)
+                    .after(update_positions)
+                    .before(mark_overlay_dirty)
+                    .run_if(|debug: Res<AsciiDebugOverlay>| debug.is_enabled),
+            )
+        ;
+    }
+}
+
+fn toggle_debug_overlay(
+    mut debug: ResMut<AsciiDebugOverlay>,
+    keys: Option<Res<ButtonInput<KeyCode>>>,
+) {
+    let (Some(key), Some(keys)) = (debug.toggle_key, keys) else {
+        return;
+    };
+    if keys.just_pressed(key) {
+        debug.is_enabled = !debug.is_enabled;
+    }
+}
+
+fn draw_debug_overlay(
+    debug: Res<AsciiDebugOverlay>,
+    cameras: Query<Entity, (With<AsciiCamera>, With<AsciiUi>)>,
+    nodes: Query<(Entity, Ref<AsciiNode>, Option<&InheritedVisibility>)>,
+    hierarchy: Query<(Option<&Parent>, Option<&AsciiUiTarget>, Has<AsciiCamera>)>,
+    parents: Query<(Option<&Parent>, Has<AsciiNode>)>,
+    hit_test: AsciiHitTest,
+    overlay: Res<AsciiOverlay>,
+) {
+    let colors = debug.colors;
+    for camera in cameras.iter() {
+        let mut outlines = Vec::new();
+        let mut laid_out = 0;
+        for (entity, node, visibility) in nodes.iter() {
+            if !visibility.map(|v| v.get()).unwrap_or(true) {
+                continue;
+            }
+            if !ui_target(entity, &hierarchy).map(|target| target == camera).unwrap_or(true) {
+                continue;
+            }
+            // Nodes are changed when they get new bounds, which is what happens when they are dirty.
+            let is_dirty = node.is_changed();
+            laid_out += is_dirty as usize;
+            let color = match colors {
+                AsciiDebugColors::Dirty if is_dirty && debug.show_dirty => Color::Red,
+                AsciiDebugColors::Dirty => Color::LightGreen,
+                AsciiDebugColors::Depth => AsciiDebugOverlay::DEPTH_COLORS[node_depth(entity, &parents) % AsciiDebugOverlay::DEPTH_COLORS.len()],
+            };
+            outlines.push((entity, node.bounds.clone(), color));
+        }
+        // Lower layers first, so the labels of the nodes on top are the ones that can be read.
+        outlines.sort_by_key(|(_, bounds, _)| bounds.layer);
+
+        let cursor = hit_test.cell_under_cursor(camera);
+        let status = match cursor {
+            Some(cell) => format!(" nodes {} | laid out {} | cursor {},{} ", outlines.len(), laid_out, cell.x, cell.y),
+            None => format!(" nodes {} | laid out {} ", outlines.len(), laid_out),
+        };
+        let show_bounds = debug.show_bounds;
+        let show_layers = debug.show_layers;
+        let show_entities = debug.show_entities;
+        let show_cursor = debug.show_cursor;
+
+        overlay.draw_on_layer(camera, AsciiDebugOverlay::LAYER, move |buffer| {
+            for (entity, bounds, color) in outlines {
+                let mut node_buffer = buffer.clone();
+                node_buffer.bounds = bounds.clone();
+                node_buffer.bounds.layer = buffer.bounds.layer;
+
+                if show_bounds {
+                    node_buffer.border(BorderStyle::SINGLE).border_color(color).all().draw();
+                }
+                if show_layers && bounds.width > 2 {
+                    let mut label = node_buffer.clone();
+                    label.bounds = AsciiBounds::new(bounds.x + 1, bounds.y, bounds.width - 2, 1, buffer.bounds.layer);
+                    label.text(&bounds.layer.to_string()).text_color(Color::Black).bg_color(color).draw();
+                }
+                if show_entities && bounds.width > 2 && bounds.height > 1 {
+                    let mut label = node_buffer.clone();
+                    label.bounds = AsciiBounds::new(bounds.x + 1, bounds.y + bounds.height as i32 - 1, bounds.width - 2, 1, buffer.bounds.layer);
+                    label
+                        .text(&entity.index().to_string())
+                        .horizontal_alignment(HorizontalAlignment::Right)
+                        .text_color(Color::Black)
+                        .bg_color(color)
+                        .draw();
+                }
+            }
+
+            if show_cursor {
+                if let Some(cell) = cursor {
+                    buffer.set_character(cell.x, cell.y, ('+', Color::Black, Color::Yellow));
+                }
+            }
+
+            let height = buffer.bounds.height as i32;
+            for (column, c) in status.chars().enumerate() {
+                buffer.set_character(column as i32, height - 1, (c, Color::Black, Color::LightGrey));
+            }
+        });
+    }
+}
+
+// How many ui nodes are above the node in the hierarchy. The camera is not a ui node, so the
+// nodes right under it are at depth 0.
+fn node_depth(entity: Entity, parents: &Query<(Option<&Parent>, Has<AsciiNode>)>) -> usize {
+    let mut depth = 0;
+    let mut current = entity;
+    while let Ok((Some(parent), _)) = parents.get(current) {
+        current = parent.get();
+        if !parents.get(current).is_ok_and(|(_, is_node)| is_node) {
+            break;
+        }
+        depth += 1;
+    }
+    depth
+}
+
+//=============================================================================
+//             Debug Overlay
+//=============================================================================
+
+// How the outlines of the nodes are colored.
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug, Reflect)]
+pub enum AsciiDebugColors {
+    // Nodes that were laid out again this frame are red, the rest are green.
+    #[default]
+    Dirty,
+    // Each depth in the hierarchy gets its own color, so nested nodes can be told apart.
+    Depth,
+}
+
+#[derive(Resource, Reflect)]
+pub struct AsciiDebugOverlay {
+    pub is_enabled: bool,
+    pub toggle_key: Option<KeyCode>,
+    pub show_bounds: bool,
+    // Writes the layer of each node on the top edge of its bounds.
+    pub show_layers: bool,
+    // Outlines nodes that were laid out again this frame in red.
+    pub show_dirty: bool,
+    // Writes the entity index of each node in the bottom right corner of its bounds.
+    pub show_entities: bool,
+    pub show_cursor: bool,
+    pub colors: AsciiDebugColors,
+}
+
+impl AsciiDebugOverlay {
+    // Drawn over everything, including other draws on the `AsciiOverlay`.
+    pub const LAYER: u32 = AsciiOverlay::LAYER + 1_000;
+
+    pub const DEPTH_COLORS: [Color; 8] = [
+        Color::LightGreen,
+        Color::LightBlue,
+        Color::Yellow,
+        Color::Violet,
+        Color::Orange,
+        Color::Cyan,
+        Color::LightRed,
+        Color::White,
+    ];
+
+    // Outlines every node with a color for its depth and labels it with its entity index, for
+    // finding out which node ended up where.
+    pub fn bounds_gizmos() -> Self {
+        AsciiDebugOverlay {
+            is_enabled: true,
+            show_dirty: false,
+            show_entities: true,
+            colors: AsciiDebugColors::Depth,
+            ..Default::default()
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.is_enabled = !self.is_enabled;
+    }
+}
+
+impl Default for AsciiDebugOverlay {
+    fn default() -> Self {
+        AsciiDebugOverlay {
+            is_enabled: false,
+            toggle_key: Some(KeyCode::F3),
+            show_bounds: true,
+            show_layers: true,
+            show_dirty: true,
+            show_entities: false,
+            show_cursor: true,
+            colors: AsciiDebugColors::Dirty,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::system::RunSystemOnce, render::MainWorld};
+
+    use crate::{
+        render::ascii::LiveOverlayBuffer,
+        ui::{buffer::AsciiSurface, character::AsciiCharacter, overlay::extract_overlay, AsciiUiPlugin},
+    };
+
+    use super::*;
+
+    // Runs the queued overlay draws onto the live layer of the camera, like the render app does.
+    fn extract(world: &mut World, camera: Entity, live: &AsciiSurface) {
+        let mut render_world = World::new();
+        let mut main = MainWorld::default();
+        *main = std::mem::take(world);
+        render_world.insert_resource(main);
+        render_world.get_or_spawn(camera).unwrap().insert((AsciiCamera::default(), LiveOverlayBuffer(live.clone())));
+        render_world.run_system_once(extract_overlay);
+        *world = std::mem::take(&mut *render_world.resource_mut::<MainWorld>());
+    }
+
+    fn cell(surface: &AsciiSurface, x: u32, y: u32) -> Option<(char, Color)> {
+        match surface.characters()[(y * surface.width() + x) as usize] {
+            AsciiCharacter::Set { index, text_color, .. } => Some((char::from(index), text_color)),
+            AsciiCharacter::Unset => None,
+        }
+    }
+
+    fn row(surface: &AsciiSurface, y: u32) -> String {
+        (0..surface.width()).map(|x| cell(surface, x, y).map(|(c, _)| c).unwrap_or(' ')).collect()
+    }
+
+    #[test]
+    fn nodes_are_outlined_with_their_layer_and_a_status_line() {
+        let mut world = World::new();
+        world.insert_resource(AsciiDebugOverlay { is_enabled: true, ..Default::default() });
+        world.init_resource::<AsciiOverlay>();
+        let camera = world.spawn((AsciiCamera::default(), AsciiUi::default())).id();
+        let node = world.spawn(AsciiNode::new(0, 0, 6, 3, 2)).id();
+        world.entity_mut(camera).add_child(node);
+
+        let mut draw = IntoSystem::into_system(draw_debug_overlay);
+        draw.initialize(&mut world);
+        draw.run((), &mut world);
+        let live = AsciiSurface::new(24, 6);
+        extract(&mut world, camera, &live);
+
+        // The node was just laid out, so it is red.
+        assert_eq!(cell(&live, 0, 0).map(|(_, color)| color), Some(Color::Red));
+        assert_eq!(cell(&live, 1, 0).map(|(c, _)| c), Some('2'));
+        // The font only has upper case letters.
+        assert!(row(&live, 5).starts_with(" NODES 1 | LAID OUT 1 "));
+
+        // Nothing moved the next frame.
+        live.reset();
+        draw.run((), &mut world);
+        extract(&mut world, camera, &live);
+        assert_eq!(cell(&live, 0, 0).map(|(_, color)| color), Some(Color::LightGreen));
+        assert!(row(&live, 5).starts_with(" NODES 1 | LAID OUT 0 "));
+    }
+
+    #[test]
+    fn bounds_gizmos_color_by_depth_and_label_entities() {
+        let mut world = World::new();
+        world.insert_resource(AsciiDebugOverlay::bounds_gizmos());
+        world.init_resource::<AsciiOverlay>();
+        let camera = world.spawn((AsciiCamera::default(), AsciiUi::default())).id();
+        let outer = world.spawn(AsciiNode::new(0, 0, 12, 5, 0)).id();
+        let inner = world.spawn(AsciiNode::new(2, 1, 8, 3, 1)).id();
+        world.entity_mut(camera).add_child(outer);
+        world.entity_mut(outer).add_child(inner);
+
+        world.run_system_once(draw_debug_overlay);
+        let live = AsciiSurface::new(12, 6);
+        extract(&mut world, camera, &live);
+
+        assert_eq!(cell(&live, 0, 0).map(|(_, color)| color), Some(AsciiDebugOverlay::DEPTH_COLORS[0]));
+        assert_eq!(cell(&live, 2, 1).map(|(_, color)| color), Some(AsciiDebugOverlay::DEPTH_COLORS[1]));
+        // The entity index of the inner node in its bottom right corner.
+        let index = inner.index().to_string();
+        assert!(row(&live, 3).chars().take(9).collect::<String>().ends_with(&index));
+    }
+
+    #[derive(Resource, Default)]
+    struct Redraws {
+        full: u32,
+        live: u32,
+    }
+
+    fn count_redraws(ui: Query<&AsciiUi>, mut redraws: ResMut<Redraws>) {
+        for ui in ui.iter() {
+            if ui.is_dirty() {
+                redraws.full += 1;
+            } else if ui.is_live_dirty() {
+                redraws.live += 1;
+            }
+        }
+    }
+
+    #[test]
+    fn the_debug_overlay_only_redraws_the_live_layer() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), bevy::input::InputPlugin, bevy::window::WindowPlugin::default(), AsciiUiPlugin))
+            .add_plugins(AsciiDebugOverlayPlugin { toggle_key: None, is_enabled: true })
+            .init_asset::<Image>()
+            .init_resource::<Redraws>()
+            .add_systems(AsciiUiSchedule, count_redraws.in_set(AsciiUiSet::Extract));
+        app.world.spawn((AsciiCamera::default(), AsciiUi::default()));
+        app.update();
+        app.update();
+        *app.world.resource_mut::<Redraws>() = Redraws::default();
+
+        for _ in 0..3 {
+            app.update();
+        }
+        let redraws = app.world.resource::<Redraws>();
+        assert_eq!((redraws.full, redraws.live), (0, 3));
+
+        // Turning it off clears the live layer once.
+        app.world.resource_mut::<AsciiDebugOverlay>().toggle();
+        for _ in 0..3 {
+            app.update();
+        }
+        let redraws = app.world.resource::<Redraws>();
+        assert_eq!((redraws.full, redraws.live), (0, 4));
+    }
+}
@@ -0,0 +1,202 @@
+use std::sync::{
+    atomic::{AtomicU32, AtomicU64, Ordering},
+    mpsc::{channel, Receiver, Sender},
+    Mutex,
+};
+
+use bevy::{
+    diagnostic::{Diagnostic, DiagnosticPath, Diagnostics, RegisterDiagnostic},
+    prelude::*,
+    render::{Render, RenderApp, RenderSet},
+};
+
+use crate::render::ascii::OverlayBuffer;
+
+use super::{
+    bounds::AsciiNode,
+    command::AsciiUiCommandExtention,
+    component::{diagnostics::AsciiUiDiagnosticsPanel, AsciiComponentPlugin},
+    position::{mark_positions_dirty, update_positions},
+    AsciiMarkDirtyEvent, AsciiUi, AsciiUiSchedule, AsciiUiSet, HorizontalAlignment, VerticalAlignment,
+};
+
+//=============================================================================
+//             Plugin and Systems
+//=============================================================================
+
+// Counts the work the ui does every frame in the `AsciiUiDiagnostics` resource, and measures it as
+// bevy diagnostics, so it can be logged like the rest. `with_panel` also shows the counts in a
+// corner of every camera with an `AsciiUi`.
+#[derive(Default)]
+pub struct AsciiUiDiagnosticsPlugin {
+    pub panel: Option<(HorizontalAlignment, VerticalAlignment)>,
+}
+
+impl AsciiUiDiagnosticsPlugin {
+    pub const COMPONENTS_RENDERED: DiagnosticPath = DiagnosticPath::const_new("ascii_ui/components_rendered");
+    pub const CELLS_WRITTEN: DiagnosticPath = DiagnosticPath::const_new("ascii_ui/cells_written");
+    pub const BYTES_UPLOADED: DiagnosticPath = DiagnosticPath::const_new("ascii_ui/bytes_uploaded");
+    pub const DIRTY_EVENTS: DiagnosticPath = DiagnosticPath::const_new("ascii_ui/dirty_events");
+    pub const LAYOUT_RECOMPUTES: DiagnosticPath = DiagnosticPath::const_new("ascii_ui/layout_recomputes");
+
+    pub fn with_panel(horizontal: HorizontalAlignment, vertical: VerticalAlignment) -> Self {
+        AsciiUiDiagnosticsPlugin {
+            panel: Some((horizontal, vertical)),
+        }
+    }
+}
+
+impl Plugin for AsciiUiDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        let (sender, receiver) = channel();
+
+        app.init_resource::<AsciiUiDiagnostics>()
+            .insert_resource(UiStatsReceiver(Mutex::new(receiver)))
+            .register_diagnostic(Diagnostic::new(Self::COMPONENTS_RENDERED))
+            .register_diagnostic(Diagnostic::new(Self::CELLS_WRITTEN))
+            .register_diagnostic(Diagnostic::new(Self::BYTES_UPLOADED).with_suffix("B"))
+            .register_diagnostic(Diagnostic::new(Self::DIRTY_EVENTS))
+            .register_diagnostic(Diagnostic::new(Self::LAYOUT_RECOMPUTES))
+            .add_plugins(AsciiComponentPlugin::<AsciiUiDiagnosticsPanel>::default())
+            .register_type::<AsciiUiDiagnosticsPanel>()
+            .add_systems(PreUpdate, receive_render_stats)
+            .add_systems(
+                AsciiUiSchedule,
+                count_layout_recomputes
+                    .in_set(AsciiUiSet::Layout)
+                    .after(mark_positions_dirty)
+                    .before(update_positions),
+            )
+            .add_systems(AsciiUiSchedule, measure_ui_diagnostics.in_set(AsciiUiSet::Extract))
+        ;
+
+        if let Some((horizontal, vertical)) = self.panel {
+            app.insert_resource(UiDiagnosticsPanelConfig { horizontal, vertical })
+                .add_systems(Update, spawn_ui_diagnostics_panels);
+        }
+
+        let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app
+            .init_resource::<AsciiUiRenderStats>()
+            .insert_resource(UiStatsSender(sender))
+            .add_systems(Render, send_render_stats.in_set(RenderSet::Cleanup));
+    }
+}
+
+//=============================================================================
+//             Ascii Ui Diagnostics
+//=============================================================================
+
+// The work the ui did in the last frame. The components, cells and bytes are counted in the render
+// world, where the ui is drawn and uploaded, so they arrive a frame or two after the rest.
+#[derive(Resource, Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AsciiUiDiagnostics {
+    // Every time a component was drawn on a camera. Nothing is drawn on frames the ui is clean.
+    pub components_rendered: u32,
+    // Every cell the components drew, see `AsciiSurface::cells_written`.
+    pub cells_written: u32,
+    // The bytes of the overlay textures written to the gpu.
+    pub bytes_uploaded: u64,
+    pub dirty_events: u32,
+    // The nodes whose bounds were worked out again. Their children are laid out again with them,
+    // but are not counted.
+    pub layout_recomputes: u32,
+}
+
+// Filled in by the render world while the ui is drawn and uploaded, and taken every frame.
+#[derive(Resource, Default)]
+pub(crate) struct AsciiUiRenderStats {
+    components_rendered: AtomicU32,
+    bytes_uploaded: AtomicU64,
+}
+
+impl AsciiUiRenderStats {
+    pub(crate) fn add_component(&self) {
+        self.components_rendered.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn add_upload(&self, bytes: usize) {
+        self.bytes_uploaded.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+}
+
+#[derive(Clone, Copy)]
+struct RenderStats {
+    components_rendered: u32,
+    cells_written: u32,
+    bytes_uploaded: u64,
+}
+
+#[derive(Resource)]
+struct UiStatsReceiver(Mutex<Receiver<RenderStats>>);
+
+#[derive(Resource)]
+struct UiStatsSender(Sender<RenderStats>);
+
+#[derive(Resource)]
+struct UiDiagnosticsPanelConfig {
+    horizontal: HorizontalAlignment,
+    vertical: VerticalAlignment,
+}
+
+// Overlays are only there on frames where the ui was drawn again.
+fn send_render_stats(sender: Res<UiStatsSender>, stats: Res<AsciiUiRenderStats>, overlays: Query<&OverlayBuffer>) {
+    let _ = sender.0.send(RenderStats {
+        components_rendered: stats.components_rendered.swap(0, Ordering::Relaxed),
+        cells_written: overlays.iter().map(|overlay| overlay.0.cells_written()).sum(),
+        bytes_uploaded: stats.bytes_uploaded.swap(0, Ordering::Relaxed),
+    });
+}
+
+// Only the last frame the render world finished is kept.
+fn receive_render_stats(receiver: Res<UiStatsReceiver>, mut diagnostics: ResMut<AsciiUiDiagnostics>) {
+    let Ok(receiver) = receiver.0.lock() else {
+        return;
+    };
+    if let Some(stats) = receiver.try_iter().last() {
+        diagnostics.components_rendered = stats.components_rendered;
+        diagnostics.cells_written = stats.cells_written;
+        diagnostics.bytes_uploaded = stats.bytes_uploaded;
+    }
+}
+
+fn count_layout_recomputes(nodes: Query<&AsciiNode>, mut diagnostics: ResMut<AsciiUiDiagnostics>) {
+    diagnostics.layout_recomputes = nodes.iter().filter(|node| node.is_dirty).count() as u32;
+}
+
+fn measure_ui_diagnostics(
+    mut diagnostics: ResMut<AsciiUiDiagnostics>,
+    mut dirty_events: EventReader<AsciiMarkDirtyEvent>,
+    mut store: Diagnostics,
+) {
+    diagnostics.dirty_events = dirty_events.read().count() as u32;
+
+    let diagnostics = *diagnostics;
+    store.add_measurement(&AsciiUiDiagnosticsPlugin::COMPONENTS_RENDERED, || diagnostics.components_rendered as f64);
+    store.add_measurement(&AsciiUiDiagnosticsPlugin::CELLS_WRITTEN, || diagnostics.cells_written as f64);
+    store.add_measurement(&AsciiUiDiagnosticsPlugin::BYTES_UPLOADED, || diagnostics.bytes_uploaded as f64);
+    store.add_measurement(&AsciiUiDiagnosticsPlugin::DIRTY_EVENTS, || diagnostics.dirty_events as f64);
+    store.add_measurement(&AsciiUiDiagnosticsPlugin::LAYOUT_RECOMPUTES, || diagnostics.layout_recomputes as f64);
+}
+
+fn spawn_ui_diagnostics_panels(
+    mut commands: Commands,
+    cameras: Query<Entity, Added<AsciiUi>>,
+    config: Res<UiDiagnosticsPanelConfig>,
+) {
+    for camera in cameras.iter() {
+        commands
+            .ascii_ui_with_parent(camera)
+            .aligned(
+                AsciiUiDiagnosticsPanel::WIDTH as i32,
+                AsciiUiDiagnosticsPanel::HEIGHT as i32,
+                config.horizontal,
+                config.vertical,
+                AsciiUiDiagnosticsPanel::default(),
+            )
+            .named("Ascii Ui Diagnostics");
+    }
+}
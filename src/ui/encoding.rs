@@ -0,0 +1,231 @@
+use bevy::render::render_resource::ShaderDefVal;
+
+use super::character::{AsciiBlendMode, AsciiGlyph, Color};
+
+//=============================================================================
+//             Cell Encoding
+//=============================================================================
+
+// How a cell of the overlay is packed into the four channels of its texel. The first channel is
+// the glyph on its page, the next two are the palette index of the text and background color.
+// The last channel is 0 for unset cells, otherwise it holds the blend mode + 1, the transparency
+// from 0 (opaque) to `MAX_TRANSPARENCY` (invisible), whether the glyph is a tile and the page of
+// the glyph, from the low bits up.
+//
+// ascii.wgsl gets the layout of the last channel from `shader_defs`, so the shader and the surface
+// can not disagree on it.
+pub struct CellEncoding;
+
+impl CellEncoding {
+    pub const UNSET: [u8; 4] = [0; 4];
+
+    pub const BLEND_BITS: u32 = 2;
+    pub const TRANSPARENCY_BITS: u32 = 3;
+    pub const TILE_BITS: u32 = 1;
+    pub const PAGE_BITS: u32 = 2;
+
+    pub const BLEND_SHIFT: u32 = 0;
+    pub const TRANSPARENCY_SHIFT: u32 = Self::BLEND_SHIFT + Self::BLEND_BITS;
+    pub const TILE_SHIFT: u32 = Self::TRANSPARENCY_SHIFT + Self::TRANSPARENCY_BITS;
+    pub const PAGE_SHIFT: u32 = Self::TILE_SHIFT + Self::TILE_BITS;
+
+    pub const MAX_TRANSPARENCY: u8 = (1 << Self::TRANSPARENCY_BITS) - 1;
+
+    // Set in the last channel of an rgb color of `encode_rgb`, when the color is used instead of
+    // the palette index.
+    pub const RGB_SET: u8 = 255;
+
+    // Cells with a glyph past the last page can not be drawn, and are encoded as unset. Tiles do
+    // not use their page, but one that does not fit would spill out of the page bits, so they are
+    // held to the same rule.
+    pub fn encode(cell: &EncodedCell) -> [u8; 4] {
+        let glyph = cell.glyph;
+        if glyph.page >= AsciiGlyph::PAGE_COUNT {
+            return Self::UNSET;
+        }
+        let info = ((cell.blend as u8 + 1) << Self::BLEND_SHIFT)
+            | (cell.transparency.min(Self::MAX_TRANSPARENCY) << Self::TRANSPARENCY_SHIFT)
+            | ((glyph.is_tile as u8) << Self::TILE_SHIFT)
+            | (glyph.page << Self::PAGE_SHIFT);
+        [glyph.index, cell.text_color, cell.background_color, info]
+    }
+
+    // The cell a texel of the overlay holds, or `None` when it is unset.
+    pub fn decode(bytes: [u8; 4]) -> Option<EncodedCell> {
+        let [index, text_color, background_color, info] = bytes;
+        let field = |shift: u32, bits: u32| (info >> shift) & ((1 << bits) - 1);
+        let blend = match field(Self::BLEND_SHIFT, Self::BLEND_BITS) {
+            0 => return None,
+            1 => AsciiBlendMode::Replace,
+            2 => AsciiBlendMode::Tint,
+            _ => AsciiBlendMode::Behind,
+        };
+        Some(EncodedCell {
+            glyph: AsciiGlyph {
+                page: field(Self::PAGE_SHIFT, Self::PAGE_BITS),
+                index,
+                is_tile: field(Self::TILE_SHIFT, Self::TILE_BITS) != 0,
+            },
+            text_color,
+            background_color,
+            blend,
+            transparency: field(Self::TRANSPARENCY_SHIFT, Self::TRANSPARENCY_BITS),
+        })
+    }
+
+    // The exact text and background color of `Color::Rgb` colors, for the true color overlay.
+    // Other colors are left to the palette index of `encode`.
+    pub fn encode_rgb(text_color: &Color, background_color: &Color) -> [u8; 8] {
+        let channels = |color: &Color| match color.resolve() {
+            Color::Rgb(r, g, b) => [r, g, b, Self::RGB_SET],
+            _ => [0, 0, 0, 0],
+        };
+        let [tr, tg, tb, ta] = channels(text_color);
+        let [br, bg, bb, ba] = channels(background_color);
+        [tr, tg, tb, ta, br, bg, bb, ba]
+    }
+
+    // The colors of `encode_rgb`, `None` for the ones that use the palette.
+    pub fn decode_rgb(bytes: [u8; 8]) -> (Option<Color>, Option<Color>) {
+        let color = |[r, g, b, set]: [u8; 4]| (set == Self::RGB_SET).then_some(Color::Rgb(r, g, b));
+        (
+            color([bytes[0], bytes[1], bytes[2], bytes[3]]),
+            color([bytes[4], bytes[5], bytes[6], bytes[7]]),
+        )
+    }
+
+    // The layout for ascii.wgsl, where `#{CELL_PAGE_SHIFT}` and the others are replaced by it.
+    pub fn shader_defs() -> Vec<ShaderDefVal> {
+        let mask = |bits: u32| (1u32 << bits) - 1;
+        vec![
+            ShaderDefVal::UInt("CELL_BLEND_SHIFT".into(), Self::BLEND_SHIFT),
+            ShaderDefVal::UInt("CELL_BLEND_MASK".into(), mask(Self::BLEND_BITS)),
+            ShaderDefVal::UInt("CELL_TRANSPARENCY_SHIFT".into(), Self::TRANSPARENCY_SHIFT),
+            ShaderDefVal::UInt("CELL_TRANSPARENCY_MASK".into(), mask(Self::TRANSPARENCY_BITS)),
+            ShaderDefVal::UInt("CELL_TILE_SHIFT".into(), Self::TILE_SHIFT),
+            ShaderDefVal::UInt("CELL_PAGE_SHIFT".into(), Self::PAGE_SHIFT),
+            ShaderDefVal::UInt("CELL_PAGE_MASK".into(), mask(Self::PAGE_BITS)),
+            ShaderDefVal::UInt("CELL_RGB_SET".into(), Self::RGB_SET as u32),
+            ShaderDefVal::UInt("FONT_PAGES".into(), AsciiGlyph::PAGE_COUNT as u32),
+        ]
+    }
+}
+
+// Every page has to fit in the page bits, and all of the bits in the last channel.
+const _: () = assert!(
+    AsciiGlyph::PAGE_COUNT as u32 <= 1 << CellEncoding::PAGE_BITS
+        && CellEncoding::PAGE_SHIFT + CellEncoding::PAGE_BITS <= 8
+);
+
+// A cell of the overlay as it is stored on the gpu. The layer of the character is only used on
+// the surface and is not part of it, and the opacity is rounded to a transparency step.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct EncodedCell {
+    pub glyph: AsciiGlyph,
+    pub text_color: u8,
+    pub background_color: u8,
+    pub blend: AsciiBlendMode,
+    pub transparency: u8,
+}
+
+impl EncodedCell {
+    pub fn transparency_of(opacity: f32) -> u8 {
+        ((1.0 - opacity.clamp(0.0, 1.0)) * CellEncoding::MAX_TRANSPARENCY as f32).round() as u8
+    }
+
+    pub fn opacity(&self) -> f32 {
+        1.0 - self.transparency as f32 / CellEncoding::MAX_TRANSPARENCY as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLEND_MODES: [AsciiBlendMode; 3] = [AsciiBlendMode::Replace, AsciiBlendMode::Tint, AsciiBlendMode::Behind];
+
+    fn cell(glyph: AsciiGlyph, blend: AsciiBlendMode, transparency: u8) -> EncodedCell {
+        EncodedCell {
+            glyph,
+            text_color: 7,
+            background_color: 200,
+            blend,
+            transparency,
+        }
+    }
+
+    #[test]
+    fn every_cell_round_trips() {
+        for page in 0..AsciiGlyph::PAGE_COUNT {
+            for is_tile in [false, true] {
+                for blend in BLEND_MODES {
+                    for transparency in 0..=CellEncoding::MAX_TRANSPARENCY {
+                        for index in [0, 1, 127, 255] {
+                            let cell = cell(AsciiGlyph { page, index, is_tile }, blend, transparency);
+                            let bytes = CellEncoding::encode(&cell);
+                            assert_ne!(bytes, CellEncoding::UNSET);
+                            assert_eq!(CellEncoding::decode(bytes), Some(cell));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn unset_decodes_to_nothing() {
+        assert_eq!(CellEncoding::decode(CellEncoding::UNSET), None);
+    }
+
+    #[test]
+    fn transparency_is_clamped() {
+        let glyph = AsciiGlyph { page: 0, index: 65, is_tile: false };
+        let bytes = CellEncoding::encode(&cell(glyph, AsciiBlendMode::Tint, 200));
+        let decoded = CellEncoding::decode(bytes).unwrap();
+        assert_eq!(decoded.transparency, CellEncoding::MAX_TRANSPARENCY);
+        assert_eq!(decoded.blend, AsciiBlendMode::Tint);
+        assert_eq!(decoded.glyph, glyph);
+    }
+
+    #[test]
+    fn pages_past_the_last_are_unset() {
+        for is_tile in [false, true] {
+            for page in [AsciiGlyph::PAGE_COUNT, 8, 255] {
+                let glyph = AsciiGlyph { page, index: 3, is_tile };
+                let bytes = CellEncoding::encode(&cell(glyph, AsciiBlendMode::Replace, 0));
+                assert_eq!(bytes, CellEncoding::UNSET);
+            }
+        }
+    }
+
+    #[test]
+    fn opacity_round_trips_through_transparency() {
+        for transparency in 0..=CellEncoding::MAX_TRANSPARENCY {
+            let cell = cell(AsciiGlyph { page: 0, index: 0, is_tile: false }, AsciiBlendMode::Replace, transparency);
+            assert_eq!(EncodedCell::transparency_of(cell.opacity()), transparency);
+        }
+        assert_eq!(EncodedCell::transparency_of(1.0), 0);
+        assert_eq!(EncodedCell::transparency_of(0.0), CellEncoding::MAX_TRANSPARENCY);
+        assert_eq!(EncodedCell::transparency_of(-1.0), CellEncoding::MAX_TRANSPARENCY);
+    }
+
+    #[test]
+    fn rgb_colors_round_trip() {
+        let colors = [Color::Rgb(0, 0, 0), Color::Rgb(255, 255, 255), Color::Rgb(12, 200, 99)];
+        for text_color in colors {
+            for background_color in colors {
+                let bytes = CellEncoding::encode_rgb(&text_color, &background_color);
+                assert_eq!(CellEncoding::decode_rgb(bytes), (Some(text_color), Some(background_color)));
+            }
+        }
+    }
+
+    #[test]
+    fn palette_colors_are_left_to_the_palette() {
+        let bytes = CellEncoding::encode_rgb(&Color::Red, &Color::Rgb(1, 2, 3));
+        assert_eq!(CellEncoding::decode_rgb(bytes), (None, Some(Color::Rgb(1, 2, 3))));
+        let bytes = CellEncoding::encode_rgb(&Color::Rgb(1, 2, 3), &Color::Black);
+        assert_eq!(CellEncoding::decode_rgb(bytes), (Some(Color::Rgb(1, 2, 3)), None));
+        assert_eq!(CellEncoding::decode_rgb([0; 8]), (None, None));
+    }
+}
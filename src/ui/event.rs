@@ -0,0 +1,282 @@
+use std::marker::PhantomData;
+
+use bevy::prelude::*;
+
+use super::{
+    accessibility::AsciiAccessibilityValueChangedEvent,
+    component::{
+        animation::AsciiAnimationFinishedEvent,
+        autocomplete::{AsciiAutocompleteSelectedEvent, AsciiAutocompleteSubmittedEvent},
+        console::AsciiConsoleCommandEvent, rich_text::AsciiTextSpanClicked, list::{AsciiListSelectedEvent, AsciiListSubmittedEvent},
+        segmented::AsciiSegmentedChangedEvent, slider::AsciiSliderChangedEvent,
+        spinner::AsciiSpinnerChangedEvent, tabs::AsciiTabChangedEvent,
+        text_input::{AsciiTextInputChangedEvent, AsciiTextInputSubmittedEvent},
+        toggle::AsciiToggleChangedEvent, virtual_keyboard::AsciiVirtualKeyPressedEvent,
+        window::{AsciiWindowClosedEvent, AsciiWindowMinimizedEvent},
+    },
+    util::{AsciiComponentButtonClicked, AsciiComponentHoverEnteredEvent, AsciiComponentHoverExitedEvent},
+    AsciiUiSet,
+};
+
+//=============================================================================
+//             Ui Event Propagation
+//=============================================================================
+
+// An event that belongs to a ui node. Events registered with `AsciiUiEventPlugin` are still
+// broadcast like any other event, and are also handed to the `AsciiEventListener`s of the target
+// and everything above it in the hierarchy.
+pub trait AsciiUiEvent: Event {
+    fn target(&self) -> Entity;
+}
+
+pub struct AsciiUiEventPlugin<E: AsciiUiEvent>(PhantomData<E>);
+
+impl<E: AsciiUiEvent> Default for AsciiUiEventPlugin<E> {
+    fn default() -> Self {
+        AsciiUiEventPlugin(PhantomData)
+    }
+}
+
+impl<E: AsciiUiEvent> Plugin for AsciiUiEventPlugin<E> {
+    fn build(&self, app: &mut App) {
+        // Widgets send their events in `Update`, so every one of them has been sent by now.
+        app
+            .add_event::<E>()
+            .add_systems(PostUpdate, propagate_ui_events::<E>.in_set(AsciiUiSet::Propagate))
+        ;
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Reflect)]
+pub enum AsciiEventPhase {
+    // On the way down, from the root of the hierarchy to the parent of the target.
+    Capture,
+    Target,
+    // On the way back up, from the parent of the target to the root.
+    Bubble,
+}
+
+pub struct AsciiEventContext<'a, E: AsciiUiEvent> {
+    pub event: &'a E,
+    // The entity the listener is on.
+    pub current: Entity,
+    pub phase: AsciiEventPhase,
+    is_stopped: bool,
+}
+
+impl<'a, E: AsciiUiEvent> AsciiEventContext<'a, E> {
+    pub fn target(&self) -> Entity {
+        self.event.target()
+    }
+
+    // No listeners after this one get the event. The broadcast event is not affected.
+    pub fn stop_propagation(&mut self) {
+        self.is_stopped = true;
+    }
+
+    pub fn is_propagation_stopped(&self) -> bool {
+        self.is_stopped
+    }
+}
+
+type AsciiEventCallback<E> = Box<dyn Fn(&mut AsciiEventContext<E>, &mut Commands) + Send + Sync>;
+
+// Handles the events of type `E` that target this entity or anything below it. A listener gets
+// the event after its children by default, `capture` gets it before them instead.
+#[derive(Component)]
+pub struct AsciiEventListener<E: AsciiUiEvent> {
+    is_capture: bool,
+    callback: AsciiEventCallback<E>,
+}
+
+impl<E: AsciiUiEvent> AsciiEventListener<E> {
+    pub fn new(callback: impl Fn(&mut AsciiEventContext<E>, &mut Commands) + Send + Sync + 'static) -> Self {
+        AsciiEventListener {
+            is_capture: false,
+            callback: Box::new(callback),
+        }
+    }
+
+    pub fn capture(callback: impl Fn(&mut AsciiEventContext<E>, &mut Commands) + Send + Sync + 'static) -> Self {
+        AsciiEventListener {
+            is_capture: true,
+            callback: Box::new(callback),
+        }
+    }
+
+    pub fn is_capture(&self) -> bool {
+        self.is_capture
+    }
+}
+
+fn propagate_ui_events<E: AsciiUiEvent>(
+    mut events: EventReader<E>,
+    listeners: Query<&AsciiEventListener<E>>,
+    parents: Query<&Parent>,
+    mut commands: Commands,
+) {
+    if listeners.is_empty() {
+        events.clear();
+        return;
+    }
+
+    for event in events.read() {
+        let target = event.target();
+        let mut ancestors = Vec::new();
+        let mut current = target;
+        while let Ok(parent) = parents.get(current) {
+            current = parent.get();
+            ancestors.push(current);
+        }
+
+        let capture = ancestors.iter().rev().map(|entity| (*entity, AsciiEventPhase::Capture));
+        let bubble = ancestors.iter().map(|entity| (*entity, AsciiEventPhase::Bubble));
+        let path = capture
+            .chain(std::iter::once((target, AsciiEventPhase::Target)))
+            .chain(bubble);
+
+        let mut context = AsciiEventContext {
+            event,
+            current: target,
+            phase: AsciiEventPhase::Target,
+            is_stopped: false,
+        };
+        for (entity, phase) in path {
+            let Ok(listener) = listeners.get(entity) else {
+                continue;
+            };
+            // The target gets the event once, whichever way its listener listens.
+            let listens = match phase {
+                AsciiEventPhase::Capture => listener.is_capture,
+                AsciiEventPhase::Target => true,
+                AsciiEventPhase::Bubble => !listener.is_capture,
+            };
+            if !listens {
+                continue;
+            }
+
+            context.current = entity;
+            context.phase = phase;
+            (listener.callback)(&mut context, &mut commands);
+            if context.is_stopped {
+                break;
+            }
+        }
+    }
+}
+
+//=============================================================================
+//             App Extention
+//=============================================================================
+
+pub trait AsciiUiEventAppExtention {
+    // Hands `E` to the `AsciiEventListener<E>`s of its target and the target's ancestors.
+    // Registering an event more than once does nothing.
+    fn register_ascii_ui_event<E: AsciiUiEvent>(&mut self) -> &mut Self;
+}
+
+impl AsciiUiEventAppExtention for App {
+    fn register_ascii_ui_event<E: AsciiUiEvent>(&mut self) -> &mut Self {
+        if !self.is_plugin_added::<AsciiUiEventPlugin<E>>() {
+            self.add_plugins(AsciiUiEventPlugin::<E>::default());
+        }
+        self
+    }
+}
+
+//=============================================================================
+//             Built In Ui Events
+//=============================================================================
+
+// Adds propagation for the events of the built in widgets.
+pub(crate) fn register_widget_events(app: &mut App) {
+    app
+        .register_ascii_ui_event::<AsciiComponentButtonClicked>()
+        .register_ascii_ui_event::<AsciiComponentHoverEnteredEvent>()
+        .register_ascii_ui_event::<AsciiComponentHoverExitedEvent>()
+        .register_ascii_ui_event::<AsciiWindowClosedEvent>()
+        .register_ascii_ui_event::<AsciiWindowMinimizedEvent>()
+        .register_ascii_ui_event::<AsciiSliderChangedEvent>()
+        .register_ascii_ui_event::<AsciiSpinnerChangedEvent>()
+        .register_ascii_ui_event::<AsciiTabChangedEvent>()
+        .register_ascii_ui_event::<AsciiToggleChangedEvent>()
+        .register_ascii_ui_event::<AsciiSegmentedChangedEvent>()
+        .register_ascii_ui_event::<AsciiListSelectedEvent>()
+        .register_ascii_ui_event::<AsciiListSubmittedEvent>()
+        .register_ascii_ui_event::<AsciiTextInputChangedEvent>()
+        .register_ascii_ui_event::<AsciiTextInputSubmittedEvent>()
+        .register_ascii_ui_event::<AsciiConsoleCommandEvent>()
+        .register_ascii_ui_event::<AsciiAutocompleteSelectedEvent>()
+        .register_ascii_ui_event::<AsciiAutocompleteSubmittedEvent>()
+        .register_ascii_ui_event::<AsciiAnimationFinishedEvent>()
+        .register_ascii_ui_event::<AsciiTextSpanClicked>()
+        .register_ascii_ui_event::<AsciiVirtualKeyPressedEvent>()
+    ;
+}
+
+impl AsciiUiEvent for AsciiComponentButtonClicked {
+    fn target(&self) -> Entity {
+        self.0
+    }
+}
+
+impl AsciiUiEvent for AsciiComponentHoverEnteredEvent {
+    fn target(&self) -> Entity {
+        self.0
+    }
+}
+
+impl AsciiUiEvent for AsciiComponentHoverExitedEvent {
+    fn target(&self) -> Entity {
+        self.0
+    }
+}
+
+impl AsciiUiEvent for AsciiWindowClosedEvent {
+    fn target(&self) -> Entity {
+        self.0
+    }
+}
+
+impl AsciiUiEvent for AsciiWindowMinimizedEvent {
+    fn target(&self) -> Entity {
+        self.window
+    }
+}
+
+impl AsciiUiEvent for AsciiAccessibilityValueChangedEvent {
+    fn target(&self) -> Entity {
+        self.entity
+    }
+}
+
+// The rest of the widget events name their widget `entity`.
+macro_rules! impl_ui_event {
+    ($($event:ty),* $(,)?) => {
+        $(
+            impl AsciiUiEvent for $event {
+                fn target(&self) -> Entity {
+                    self.entity
+                }
+            }
+        )*
+    };
+}
+
+impl_ui_event!(
+    AsciiSliderChangedEvent,
+    AsciiSpinnerChangedEvent,
+    AsciiTabChangedEvent,
+    AsciiToggleChangedEvent,
+    AsciiSegmentedChangedEvent,
+    AsciiListSelectedEvent,
+    AsciiListSubmittedEvent,
+    AsciiTextInputChangedEvent,
+    AsciiTextInputSubmittedEvent,
+    AsciiConsoleCommandEvent,
+    AsciiAutocompleteSelectedEvent,
+    AsciiAutocompleteSubmittedEvent,
+    AsciiAnimationFinishedEvent,
+    AsciiTextSpanClicked,
+    AsciiVirtualKeyPressedEvent,
+);
@@ -0,0 +1,312 @@
+use bevy::prelude::*;
+
+use super::bounds::AsciiBounds;
+
+//=============================================================================
+//             Ascii Flex
+//=============================================================================
+
+// Lays the children of a node out in a row or a column, like a small flexbox. Only children with
+// an `AsciiPosition::Flex` take part, the other children are positioned like before. A row of
+// three buttons that share the width with a cell between them is an `AsciiFlex::row().gap(1)`
+// with three `AsciiPosition::grow(1)` children.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct AsciiFlex {
+    pub direction: AsciiFlexDirection,
+    // Cells between two children.
+    pub gap: u32,
+    // Where the children go on the main axis when none of them grow to fill it.
+    pub justify: AsciiFlexJustify,
+    // Where the children go on the cross axis. Children are as wide as the row is tall, or as
+    // tall as the column is wide, unless they have an `AsciiDesiredSize` and are not stretched.
+    pub align: AsciiFlexAlign,
+}
+
+impl Default for AsciiFlex {
+    fn default() -> Self {
+        AsciiFlex::row()
+    }
+}
+
+impl AsciiFlex {
+    pub fn row() -> Self {
+        AsciiFlex {
+            direction: AsciiFlexDirection::Row,
+            gap: 0,
+            justify: AsciiFlexJustify::Start,
+            align: AsciiFlexAlign::Stretch,
+        }
+    }
+
+    pub fn column() -> Self {
+        AsciiFlex {
+            direction: AsciiFlexDirection::Column,
+            ..AsciiFlex::row()
+        }
+    }
+
+    pub fn gap(mut self, gap: u32) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    pub fn justify(mut self, justify: AsciiFlexJustify) -> Self {
+        self.justify = justify;
+        self
+    }
+
+    pub fn align(mut self, align: AsciiFlexAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    // The bounds of every item inside of `parent`, in the same order as the items.
+    pub fn layout(&self, parent: &AsciiBounds, items: &[AsciiFlexItem]) -> Vec<AsciiBounds> {
+        if items.is_empty() {
+            return Vec::new();
+        }
+
+        let (main, cross) = match self.direction {
+            AsciiFlexDirection::Row => (parent.width, parent.height),
+            AsciiFlexDirection::Column => (parent.height, parent.width),
+        };
+        let gaps = self.gap as i64 * (items.len() as i64 - 1);
+        let mut sizes = items.iter().map(|item| item.basis as i64).collect::<Vec<_>>();
+        let free = main as i64 - gaps - sizes.iter().sum::<i64>();
+
+        if free > 0 {
+            let weights = items.iter().map(|item| item.grow as i64).collect::<Vec<_>>();
+            distribute(&mut sizes, &weights, free);
+        } else if free < 0 {
+            // Like css, bigger items give up more of the space.
+            let weights = items.iter().map(|item| item.shrink as i64 * item.basis as i64).collect::<Vec<_>>();
+            distribute(&mut sizes, &weights, free);
+        }
+
+        let leftover = (main as i64 - gaps - sizes.iter().sum::<i64>()).max(0);
+        let (mut offset, spacing) = match self.justify {
+            AsciiFlexJustify::Start => (0, 0),
+            AsciiFlexJustify::Center => (leftover / 2, 0),
+            AsciiFlexJustify::End => (leftover, 0),
+            AsciiFlexJustify::SpaceBetween if items.len() > 1 => (0, leftover / (items.len() as i64 - 1)),
+            AsciiFlexJustify::SpaceBetween => (0, 0),
+        };
+
+        items
+            .iter()
+            .zip(sizes)
+            .map(|(item, size)| {
+                let item_cross = match (self.align, item.cross) {
+                    (AsciiFlexAlign::Stretch, _) | (_, None) => cross,
+                    (_, Some(item_cross)) => item_cross.min(cross),
+                };
+                let cross_offset = match self.align {
+                    AsciiFlexAlign::Start | AsciiFlexAlign::Stretch => 0,
+                    AsciiFlexAlign::Center => (cross - item_cross) / 2,
+                    AsciiFlexAlign::End => cross - item_cross,
+                };
+
+                let mut bounds = AsciiBounds {
+                    layer: parent.layer + 1,
+                    ..Default::default()
+                };
+                match self.direction {
+                    AsciiFlexDirection::Row => {
+                        bounds.x = parent.x + offset as i32;
+                        bounds.y = parent.y + cross_offset as i32;
+                        bounds.width = size as u32;
+                        bounds.height = item_cross;
+                    }
+                    AsciiFlexDirection::Column => {
+                        bounds.x = parent.x + cross_offset as i32;
+                        bounds.y = parent.y + offset as i32;
+                        bounds.width = item_cross;
+                        bounds.height = size as u32;
+                    }
+                }
+                offset += size + self.gap as i64 + spacing;
+                bounds
+            })
+            .collect()
+    }
+}
+
+// Hands `amount` cells to the sizes, or takes them away when it is negative, in proportion to the
+// weights. Cells left over from rounding go one at a time to the first items, so the layout does
+// not jitter as the parent is resized. No size goes below zero.
+fn distribute(sizes: &mut [i64], weights: &[i64], amount: i64) {
+    let total = weights.iter().sum::<i64>();
+    if total <= 0 {
+        return;
+    }
+
+    let mut given = 0;
+    for (size, weight) in sizes.iter_mut().zip(weights) {
+        let share = amount * weight / total;
+        *size += share;
+        given += share;
+    }
+
+    let step = amount.signum();
+    let mut remainder = amount - given;
+    for index in (0..sizes.len()).cycle().take(sizes.len() * 2) {
+        if remainder == 0 {
+            break;
+        }
+        if weights[index] > 0 && sizes[index] + step >= 0 {
+            sizes[index] += step;
+            remainder -= step;
+        }
+    }
+
+    for size in sizes.iter_mut() {
+        *size = (*size).max(0);
+    }
+}
+
+// What the layout needs to know about a child, taken from its `AsciiPosition::Flex` and
+// `AsciiDesiredSize`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AsciiFlexItem {
+    pub basis: u32,
+    pub grow: u32,
+    pub shrink: u32,
+    // The size the item would like on the cross axis.
+    pub cross: Option<u32>,
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum AsciiFlexDirection {
+    #[default]
+    Row,
+    Column,
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum AsciiFlexJustify {
+    #[default]
+    Start,
+    Center,
+    End,
+    // The first child at the start, the last at the end and the space shared between them.
+    SpaceBetween,
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum AsciiFlexAlign {
+    Start,
+    Center,
+    End,
+    #[default]
+    Stretch,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PARENT: AsciiBounds = AsciiBounds {
+        x: 2,
+        y: 3,
+        width: 20,
+        height: 5,
+        layer: 1,
+    };
+
+    fn item(basis: u32, grow: u32, shrink: u32) -> AsciiFlexItem {
+        AsciiFlexItem {
+            basis,
+            grow,
+            shrink,
+            cross: None,
+        }
+    }
+
+    // The x and the width of every item of a row.
+    fn spans(bounds: &[AsciiBounds]) -> Vec<(i32, u32)> {
+        bounds.iter().map(|bounds| (bounds.x, bounds.width)).collect()
+    }
+
+    #[test]
+    fn nothing_to_lay_out() {
+        assert!(AsciiFlex::row().layout(&PARENT, &[]).is_empty());
+    }
+
+    #[test]
+    fn growing_items_share_the_row_around_the_gaps() {
+        let bounds = AsciiFlex::row().gap(1).layout(&PARENT, &[item(0, 1, 1); 3]);
+        assert_eq!(spans(&bounds), vec![(2, 6), (9, 6), (16, 6)]);
+        for bounds in bounds {
+            assert_eq!((bounds.y, bounds.height, bounds.layer), (3, 5, 2));
+        }
+
+        // The cells left over from rounding go to the first items.
+        let bounds = AsciiFlex::row().layout(&PARENT, &[item(0, 1, 1); 3]);
+        assert_eq!(spans(&bounds), vec![(2, 7), (9, 7), (16, 6)]);
+
+        let bounds = AsciiFlex::row().layout(&PARENT, &[item(0, 1, 1), item(0, 3, 1)]);
+        assert_eq!(spans(&bounds), vec![(2, 5), (7, 15)]);
+
+        // Items that do not grow keep their basis.
+        let bounds = AsciiFlex::row().layout(&PARENT, &[item(4, 0, 1), item(0, 1, 1)]);
+        assert_eq!(spans(&bounds), vec![(2, 4), (6, 16)]);
+    }
+
+    #[test]
+    fn bigger_items_shrink_more() {
+        let bounds = AsciiFlex::row().layout(&PARENT, &[item(15, 0, 1), item(10, 0, 1)]);
+        assert_eq!(spans(&bounds), vec![(2, 12), (14, 8)]);
+
+        let bounds = AsciiFlex::row().layout(&PARENT, &[item(15, 0, 0), item(10, 0, 1)]);
+        assert_eq!(spans(&bounds), vec![(2, 15), (17, 5)]);
+    }
+
+    #[test]
+    fn items_that_do_not_shrink_overflow() {
+        let bounds = AsciiFlex::row().justify(AsciiFlexJustify::End).layout(&PARENT, &[item(15, 0, 0), item(10, 0, 0)]);
+        assert_eq!(spans(&bounds), vec![(2, 15), (17, 10)]);
+
+        // Items shrink to nothing, not below it.
+        let bounds = AsciiFlex::row().gap(30).layout(&PARENT, &[item(3, 0, 1), item(3, 0, 1)]);
+        assert_eq!(spans(&bounds), vec![(2, 0), (32, 0)]);
+    }
+
+    #[test]
+    fn justify_places_the_leftover_space() {
+        let items = [item(4, 0, 1), item(4, 0, 1)];
+        let layout = |justify| spans(&AsciiFlex::row().justify(justify).layout(&PARENT, &items));
+        assert_eq!(layout(AsciiFlexJustify::Start), vec![(2, 4), (6, 4)]);
+        assert_eq!(layout(AsciiFlexJustify::Center), vec![(8, 4), (12, 4)]);
+        assert_eq!(layout(AsciiFlexJustify::End), vec![(14, 4), (18, 4)]);
+        assert_eq!(layout(AsciiFlexJustify::SpaceBetween), vec![(2, 4), (18, 4)]);
+
+        // An odd leftover puts the extra cell after the items.
+        let bounds = AsciiFlex::row().justify(AsciiFlexJustify::Center).layout(&PARENT, &[item(4, 0, 1), item(3, 0, 1)]);
+        assert_eq!(spans(&bounds), vec![(8, 4), (12, 3)]);
+
+        let bounds = AsciiFlex::row().justify(AsciiFlexJustify::SpaceBetween).layout(&PARENT, &[item(4, 0, 1)]);
+        assert_eq!(spans(&bounds), vec![(2, 4)]);
+    }
+
+    #[test]
+    fn align_places_items_on_the_cross_axis() {
+        let items = [
+            AsciiFlexItem { cross: Some(8), ..item(1, 0, 1) },
+            item(1, 0, 1),
+            AsciiFlexItem { cross: Some(30), ..item(1, 0, 1) },
+        ];
+        let layout = |align| spans(&AsciiFlex::column().align(align).layout(&PARENT, &items));
+        assert_eq!(layout(AsciiFlexAlign::Stretch), vec![(2, 20), (2, 20), (2, 20)]);
+        // Items without a size of their own, or that are too big, fill the column.
+        assert_eq!(layout(AsciiFlexAlign::Start), vec![(2, 8), (2, 20), (2, 20)]);
+        assert_eq!(layout(AsciiFlexAlign::Center), vec![(8, 8), (2, 20), (2, 20)]);
+        assert_eq!(layout(AsciiFlexAlign::End), vec![(14, 8), (2, 20), (2, 20)]);
+
+        let rows = AsciiFlex::column().layout(&PARENT, &items).iter().map(|bounds| (bounds.y, bounds.height)).collect::<Vec<_>>();
+        assert_eq!(rows, vec![(3, 1), (4, 1), (5, 1)]);
+    }
+}
@@ -0,0 +1,159 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::ascii::AsciiCamera;
+
+use super::{
+    buffer::AsciiBuffer,
+    character::{AsciiCharacter, Character, Color},
+    overlay::AsciiOverlay,
+    BorderStyle,
+};
+
+//=============================================================================
+//             Ascii Gizmos
+//=============================================================================
+
+// Draws lines, rectangles and labels on the character grid, the same way bevy's `Gizmos` draws
+// in the world. Positions are cells, with 0,0 in the top left corner of the camera. Like
+// everything on the `AsciiOverlay`, what is drawn is only seen for one frame.
+#[derive(SystemParam)]
+pub struct AsciiGizmos<'w, 's> {
+    overlay: Res<'w, AsciiOverlay>,
+    cameras: Query<'w, 's, Entity, With<AsciiCamera>>,
+}
+
+impl<'w, 's> AsciiGizmos<'w, 's> {
+    // Under the debug overlay, over the rest of the `AsciiOverlay`.
+    pub const LAYER: u32 = AsciiOverlay::LAYER + 500;
+
+    // Only draws on one camera, instead of on every ascii camera.
+    pub fn camera(&self, camera: Entity) -> AsciiCameraGizmos<'_> {
+        AsciiCameraGizmos {
+            overlay: &self.overlay,
+            camera,
+        }
+    }
+}
+
+impl<'w, 's> AsciiGizmoDraw for AsciiGizmos<'w, 's> {
+    fn draw(&self, draw: impl Fn(&AsciiBuffer) + Clone + Send + Sync + 'static) {
+        for camera in self.cameras.iter() {
+            let draw = draw.clone();
+            self.overlay.draw_on_layer(camera, Self::LAYER, move |buffer| draw(buffer));
+        }
+    }
+}
+
+pub struct AsciiCameraGizmos<'a> {
+    overlay: &'a AsciiOverlay,
+    camera: Entity,
+}
+
+impl<'a> AsciiGizmoDraw for AsciiCameraGizmos<'a> {
+    fn draw(&self, draw: impl Fn(&AsciiBuffer) + Clone + Send + Sync + 'static) {
+        self.overlay.draw_on_layer(self.camera, AsciiGizmos::LAYER, move |buffer| draw(buffer));
+    }
+}
+
+//=============================================================================
+//             Gizmo Shapes
+//=============================================================================
+
+pub trait AsciiGizmoDraw {
+    // Queues a draw on the overlay. The shapes below are all drawn through this.
+    fn draw(&self, draw: impl Fn(&AsciiBuffer) + Clone + Send + Sync + 'static);
+
+    fn cell(&self, position: IVec2, character: impl Into<AsciiCharacter>) {
+        let character = character.into();
+        self.draw(move |buffer| buffer.set_character(position.x, position.y, character));
+    }
+
+    // A line between two cells, both ends included. Each cell gets `-`, `|` or a diagonal depending
+    // on which way the line goes through it.
+    fn line(&self, start: IVec2, end: IVec2, color: Color) {
+        self.draw(move |buffer| draw_line(buffer, start, end, color));
+    }
+
+    // Lines through each of the points in order.
+    fn path(&self, points: impl IntoIterator<Item = IVec2>, color: Color) {
+        let points: Vec<IVec2> = points.into_iter().collect();
+        self.draw(move |buffer| {
+            for pair in points.windows(2) {
+                draw_line(buffer, pair[0], pair[1], color);
+            }
+            if let [point] = points.as_slice() {
+                buffer.set_character(point.x, point.y, (Character::Period, color));
+            }
+        });
+    }
+
+    // The outline of the cells from `min` to `min + size`, the inside is left as it is.
+    fn rect(&self, min: IVec2, size: UVec2, color: Color) {
+        self.rect_with_style(min, size, color, BorderStyle::SINGLE);
+    }
+
+    fn rect_with_style(&self, min: IVec2, size: UVec2, color: Color, style: BorderStyle) {
+        if size.x == 0 || size.y == 0 {
+            return;
+        }
+        self.draw(move |buffer| {
+            buffer
+                .relative(min.x, min.y, size.x as i32, size.y as i32)
+                .border(style)
+                .border_color(color)
+                .all()
+                .draw();
+        });
+    }
+
+    // Text starting at the cell and going right.
+    fn label(&self, position: IVec2, text: &str, color: Color) {
+        let text = text.to_string();
+        self.draw(move |buffer| {
+            for (i, c) in text.chars().enumerate() {
+                buffer.set_character(position.x + i as i32, position.y, (c, color));
+            }
+        });
+    }
+}
+
+// Bresenham's line, picking the character from the step it took to get to each cell.
+fn draw_line(buffer: &AsciiBuffer, start: IVec2, end: IVec2, color: Color) {
+    let delta = IVec2::new((end.x - start.x).abs(), -(end.y - start.y).abs());
+    let step = IVec2::new((end.x - start.x).signum(), (end.y - start.y).signum());
+    let mut error = delta.x + delta.y;
+    let mut current = start;
+
+    // The first cell uses the direction of the whole line, since no step was taken yet.
+    let mut character = line_character(step.x != 0 && delta.x >= -delta.y, step.y != 0 && -delta.y >= delta.x, step);
+    loop {
+        buffer.set_character(current.x, current.y, (character, color));
+        if current == end {
+            break;
+        }
+
+        let doubled = error * 2;
+        let moves_x = doubled >= delta.y;
+        let moves_y = doubled <= delta.x;
+        if moves_x {
+            error += delta.y;
+            current.x += step.x;
+        }
+        if moves_y {
+            error += delta.x;
+            current.y += step.y;
+        }
+        character = line_character(moves_x, moves_y, step);
+    }
+}
+
+fn line_character(moves_x: bool, moves_y: bool, step: IVec2) -> Character {
+    match (moves_x, moves_y) {
+        // The grid goes down, so right and down is a `\`.
+        (true, true) if step.x == step.y => Character::DiagonalEB,
+        (true, true) => Character::DiagonalWB,
+        (false, true) => Character::Pipe,
+        (true, false) => Character::Hyphen,
+        (false, false) => Character::Period,
+    }
+}
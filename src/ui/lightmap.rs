@@ -0,0 +1,149 @@
+use bevy::prelude::*;
+
+use super::character::AsciiCharacter;
+
+//=============================================================================
+//             Ascii Lightmap
+//=============================================================================
+
+// How lit every cell is, from 0 for dark to 255 for fully lit. The text and background color of
+// whatever is drawn in a cell are darkened by it when it is drawn, so the same glyphs can be used
+// for lit and unlit cells. Added to a ui node, it covers the cells of the node from its top left
+// corner. An `AsciiTilemap` can also have one that covers its tiles.
+#[derive(Component, Clone, Debug, PartialEq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct AsciiLightmap {
+    width: u32,
+    height: u32,
+    intensities: Vec<u8>,
+    // The intensity of the cells that are not on the lightmap.
+    outside: u8,
+}
+
+impl Default for AsciiLightmap {
+    fn default() -> Self {
+        AsciiLightmap::new(0, 0)
+    }
+}
+
+impl AsciiLightmap {
+    // A lightmap that is fully lit everywhere.
+    pub fn new(width: u32, height: u32) -> Self {
+        AsciiLightmap {
+            width,
+            height,
+            intensities: vec![u8::MAX; (width * height) as usize],
+            outside: u8::MAX,
+        }
+    }
+
+    pub fn outside(mut self, intensity: u8) -> Self {
+        self.outside = intensity;
+        self
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn get(&self, x: i32, y: i32) -> u8 {
+        self.index(x, y)
+            .map(|index| self.intensities[index])
+            .unwrap_or(self.outside)
+    }
+
+    pub fn set(&mut self, x: i32, y: i32, intensity: u8) {
+        if let Some(index) = self.index(x, y) {
+            self.intensities[index] = intensity;
+        }
+    }
+
+    pub fn fill(&mut self, intensity: u8) {
+        self.intensities.fill(intensity);
+    }
+
+    // Lights the cells within `radius` of `origin` that can be seen from it, fading out towards
+    // the edge. A cell can be seen when the line to it does not go through an opaque cell, the
+    // opaque cell itself is lit. Cells are never made darker than they already are, so the cells
+    // that were seen before can be left dim with a `fill` first.
+    pub fn light_line_of_sight(&mut self, origin: IVec2, radius: u32, is_opaque: impl Fn(IVec2) -> bool) {
+        let radius = radius as i32;
+        for y in origin.y - radius..=origin.y + radius {
+            for x in origin.x - radius..=origin.x + radius {
+                let target = IVec2::new(x, y);
+                let distance = (target - origin).as_vec2().length();
+                if distance > radius as f32 || self.index(x, y).is_none() {
+                    continue;
+                }
+                let is_visible = line(origin, target)
+                    .filter(|cell| *cell != origin && *cell != target)
+                    .all(|cell| !is_opaque(cell));
+                if !is_visible {
+                    continue;
+                }
+
+                let intensity = ((1.0 - distance / (radius + 1) as f32) * u8::MAX as f32).round() as u8;
+                if intensity > self.get(x, y) {
+                    self.set(x, y, intensity);
+                }
+            }
+        }
+    }
+
+    // The character darkened by the intensity of the cell.
+    pub fn darken(&self, x: i32, y: i32, character: AsciiCharacter) -> AsciiCharacter {
+        let intensity = self.get(x, y);
+        if intensity == u8::MAX {
+            return character;
+        }
+        let light = intensity as f32 / u8::MAX as f32;
+        match character {
+            AsciiCharacter::Set { index, text_color, background_color, layer, blend, opacity, glyph } => AsciiCharacter::Set {
+                index,
+                text_color: text_color.dimmed(light),
+                background_color: background_color.dimmed(light),
+                layer,
+                blend,
+                opacity,
+                glyph,
+            },
+            AsciiCharacter::Unset => AsciiCharacter::Unset,
+        }
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return None;
+        }
+        Some((x as u32 + y as u32 * self.width) as usize)
+    }
+}
+
+// The cells on the line from `from` to `to`, both included.
+pub(crate) fn line(from: IVec2, to: IVec2) -> impl Iterator<Item = IVec2> {
+    let delta = (to - from).abs();
+    let step = (to - from).signum();
+    let mut error = delta.x - delta.y;
+    let mut current = Some(from);
+    std::iter::from_fn(move || {
+        let cell = current?;
+        current = (cell != to).then(|| {
+            let mut next = cell;
+            let doubled = error * 2;
+            if doubled > -delta.y {
+                error -= delta.y;
+                next.x += step.x;
+            }
+            if doubled < delta.x {
+                error += delta.x;
+                next.y += step.y;
+            }
+            next
+        });
+        Some(cell)
+    })
+}
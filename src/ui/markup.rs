@@ -0,0 +1,89 @@
+use bevy::prelude::*;
+
+//=============================================================================
+//             Ascii Markup
+//=============================================================================
+
+// Text with inline tags. `{link=quest_42}details{/link}` makes `details` a link to `quest_42`,
+// and `{{` is a `{` that does not start a tag. Tags that are not known are kept as text.
+#[derive(Clone, Debug, PartialEq, Eq, Default, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct AsciiMarkup {
+    text: String,
+    links: Vec<AsciiTextLink>,
+}
+
+// A span of the text without its tags, in bytes, and what it links to.
+#[derive(Clone, Debug, PartialEq, Eq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct AsciiTextLink {
+    pub target: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl AsciiMarkup {
+    const LINK_START: &'static str = "{link=";
+    const LINK_END: &'static str = "{/link}";
+
+    pub fn parse(markup: &str) -> Self {
+        let mut text = String::new();
+        let mut links = Vec::new();
+        let mut open: Option<(String, usize)> = None;
+        let mut rest = markup;
+
+        while let Some(c) = rest.chars().next() {
+            if rest.starts_with("{{") {
+                text.push('{');
+                rest = &rest[2..];
+                continue;
+            }
+            if rest.starts_with(Self::LINK_END) {
+                if let Some((target, start)) = open.take() {
+                    links.push(AsciiTextLink { target, start, end: text.len() });
+                }
+                rest = &rest[Self::LINK_END.len()..];
+                continue;
+            }
+            if let Some(tag) = rest.strip_prefix(Self::LINK_START) {
+                if let Some(close) = tag.find('}') {
+                    // Links do not nest, a new one ends the one before it.
+                    if let Some((target, start)) = open.take() {
+                        links.push(AsciiTextLink { target, start, end: text.len() });
+                    }
+                    open = Some((tag[..close].to_string(), text.len()));
+                    rest = &tag[close + 1..];
+                    continue;
+                }
+            }
+            text.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+
+        if let Some((target, start)) = open {
+            links.push(AsciiTextLink { target, start, end: text.len() });
+        }
+        links.retain(|link| link.start < link.end);
+        AsciiMarkup { text, links }
+    }
+
+    // The text without its tags.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn links(&self) -> &[AsciiTextLink] {
+        &self.links
+    }
+
+    // The index of the link the byte of the text is part of.
+    pub fn link_at(&self, byte: usize) -> Option<usize> {
+        self.links.iter().position(|link| link.start <= byte && byte < link.end)
+    }
+}
+
+impl From<&str> for AsciiMarkup {
+    fn from(markup: &str) -> Self {
+        AsciiMarkup::parse(markup)
+    }
+}
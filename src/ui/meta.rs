@@ -0,0 +1,132 @@
+use std::sync::{
+    mpsc::{channel, Receiver, Sender},
+    Mutex,
+};
+
+use bevy::{
+    prelude::*,
+    render::{Render, RenderApp, RenderSet},
+    utils::{HashMap, HashSet},
+};
+
+use crate::render::ascii::OverlayBuffer;
+
+//=============================================================================
+//             Plugin and Systems
+//=============================================================================
+
+// Sends the cell metadata of every overlay that was drawn back to the main world, so it can be
+// read from the `AsciiCellMeta` resource.
+pub(crate) struct AsciiCellMetaPlugin;
+
+impl Plugin for AsciiCellMetaPlugin {
+    fn build(&self, app: &mut App) {
+        let (sender, receiver) = channel();
+
+        app.init_resource::<AsciiCellMeta>()
+            .insert_resource(CellMetaReceiver(Mutex::new(receiver)))
+            .add_systems(PreUpdate, receive_cell_meta);
+
+        let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app
+            .insert_resource(CellMetaSender(sender))
+            .add_systems(Render, send_cell_meta.in_set(RenderSet::Cleanup));
+    }
+}
+
+//=============================================================================
+//             Ascii Cell Meta
+//=============================================================================
+
+// The metadata of the cells of a camera's ui, as drawn with `AsciiBuffer::with_meta`.
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct AsciiMetaGrid {
+    width: u32,
+    height: u32,
+    values: Vec<u16>,
+}
+
+impl AsciiMetaGrid {
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    // The metadata of the ui cell, or `None` when it was drawn without any.
+    pub fn meta_at(&self, x: i32, y: i32) -> Option<u16> {
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return None;
+        }
+        self.values
+            .get((x as u32 + y as u32 * self.width) as usize)
+            .copied()
+            .filter(|meta| *meta != 0)
+    }
+}
+
+// The cell metadata of every camera with a ui that has any, by camera. It is updated the frame
+// after the ui is drawn.
+#[derive(Resource, Default, Clone, Debug)]
+pub struct AsciiCellMeta {
+    grids: HashMap<Entity, AsciiMetaGrid>,
+}
+
+impl AsciiCellMeta {
+    pub fn get(&self, camera: Entity) -> Option<&AsciiMetaGrid> {
+        self.grids.get(&camera)
+    }
+
+    // The metadata of the ui cell of the camera, or `None` when it was drawn without any.
+    pub fn meta_at(&self, camera: Entity, x: i32, y: i32) -> Option<u16> {
+        self.get(camera)?.meta_at(x, y)
+    }
+}
+
+#[derive(Resource)]
+struct CellMetaReceiver(Mutex<Receiver<(Entity, Option<AsciiMetaGrid>)>>);
+
+#[derive(Resource)]
+struct CellMetaSender(Sender<(Entity, Option<AsciiMetaGrid>)>);
+
+fn receive_cell_meta(receiver: Res<CellMetaReceiver>, mut meta: ResMut<AsciiCellMeta>) {
+    let Ok(receiver) = receiver.0.lock() else {
+        return;
+    };
+    for (camera, grid) in receiver.try_iter() {
+        match grid {
+            Some(grid) => meta.grids.insert(camera, grid),
+            None => meta.grids.remove(&camera),
+        };
+    }
+}
+
+// Overlays are only there on frames where the ui was drawn again.
+fn send_cell_meta(
+    sender: Res<CellMetaSender>,
+    overlays: Query<(Entity, &OverlayBuffer)>,
+    // The cameras whose last overlay had metadata, so it is cleared when the next one has none.
+    mut with_meta: Local<HashSet<Entity>>,
+) {
+    for (camera, overlay) in overlays.iter() {
+        let values = overlay.0.meta();
+        if values.is_empty() {
+            if with_meta.remove(&camera) {
+                let _ = sender.0.send((camera, None));
+            }
+            continue;
+        }
+        with_meta.insert(camera);
+        let grid = AsciiMetaGrid {
+            width: overlay.0.width(),
+            height: overlay.0.height(),
+            values,
+        };
+        let _ = sender.0.send((camera, Some(grid)));
+    }
+}
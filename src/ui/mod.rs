@@ -1,19 +1,50 @@
+pub mod accessibility;
+pub mod big_text;
+pub mod billboard;
 pub mod bounds;
+pub mod breakpoint;
 pub mod buffer;
 pub mod component;
 pub mod character;
+pub mod clipboard;
 pub mod command;
+pub mod cursor;
+pub mod debug;
+pub mod diagnostics;
+pub mod encoding;
+pub mod event;
+pub mod flex;
+pub mod gizmos;
+pub mod lightmap;
+pub mod markup;
+pub mod meta;
+pub mod opacity;
+pub mod overlay;
+pub mod palette;
+pub mod particles;
 pub mod position;
+pub mod screen;
+#[cfg(feature = "serialize")]
+pub mod serialize;
+pub mod time;
+pub mod touch;
+pub mod transition;
 pub mod util;
+pub mod validation;
 
 
 use self::{
-    bounds::AsciiBoundsPlugin, character::Character,
-    component::AsciiComponentPlugin, position::AsciiPositionPlugin, util::AsciiUtils,
+    bounds::{AsciiBoundsPlugin, AsciiNode}, breakpoint::AsciiBreakpointPlugin, character::{AsciiBlendMode, Character, Color}, cursor::AsciiCursorGlyphPlugin,
+    lightmap::AsciiLightmap, meta::AsciiCellMetaPlugin, opacity::AsciiOpacityPlugin, overlay::AsciiOverlayPlugin, palette::AsciiPalettePlugin, particles::AsciiParticlesPlugin, billboard::AsciiBillboardPlugin, position::AsciiPositionPlugin,
+    time::{AsciiUiTime, AsciiUiTimePlugin}, transition::AsciiTransitionPlugin, validation::AsciiValidationPlugin,
 };
 
-use self::component::button::AsciiButton;
+use crate::ascii::{AsciiCamera, AsciiCameraPlugin, AsciiCameraSet};
 
+use bevy::app::MainScheduleOrder;
+use bevy::ecs::entity::Entities;
+use bevy::ecs::schedule::ScheduleLabel;
+use bevy::input::InputSystem;
 use bevy::prelude::*;
 use bevy::window::WindowResized;
 
@@ -21,26 +52,91 @@ use bevy::window::WindowResized;
 //             Ascii UI Plugin
 //=============================================================================
 
+// The overlay ui on its own: layout, dirty tracking and opacity. It works without the
+// `AsciiRenderPlugin`, the widgets are added by the `AsciiDefaultWidgetsPlugin`.
 pub struct AsciiUiPlugin;
 
 impl Plugin for AsciiUiPlugin {
     fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<AsciiCameraPlugin>() {
+            app.add_plugins(AsciiCameraPlugin);
+        }
+
+        app.init_schedule(AsciiUiSchedule);
+        app.configure_sets(PreUpdate, AsciiUiSet::Input.after(InputSystem).after(AsciiCameraSet));
+        app.world
+            .resource_mut::<MainScheduleOrder>()
+            .insert_after(PostUpdate, AsciiUiSchedule);
+
         app
+            .configure_sets(
+                AsciiUiSchedule,
+                (AsciiUiSet::Layout, AsciiUiSet::Draw, AsciiUiSet::Extract).chain(),
+            )
             .add_plugins(AsciiBoundsPlugin)
             .add_plugins(AsciiPositionPlugin)
-            .add_plugins(AsciiUtils)
-            .add_plugins(AsciiComponentPlugin::<AsciiButton>::default())
+            .add_plugins(AsciiBreakpointPlugin)
+            .add_plugins(AsciiOpacityPlugin)
+            .add_plugins(AsciiOverlayPlugin)
+            .add_plugins(AsciiPalettePlugin)
+            .add_plugins(AsciiParticlesPlugin)
+            .add_plugins(AsciiBillboardPlugin)
+            .add_plugins(AsciiCursorGlyphPlugin)
+            .add_plugins(AsciiTransitionPlugin)
+            .add_plugins(AsciiUiTimePlugin)
+            .add_plugins(AsciiCellMetaPlugin)
+            .add_plugins(AsciiValidationPlugin)
         
             .add_event::<AsciiMarkDirtyEvent>()
-            .add_systems(PreUpdate, clean_ui)
-            .add_systems(PostUpdate, mark_ui_dirty)
+            .add_event::<AsciiMarkLiveDirtyEvent>()
+            .add_systems(AsciiUiSchedule, tick_redraw_timers.in_set(AsciiUiSet::Layout))
+            .add_systems(AsciiUiSchedule, despawn_detached_nodes.in_set(AsciiUiSet::Layout))
+            .add_systems(AsciiUiSchedule, mark_ui_dirty.in_set(AsciiUiSet::Draw))
+            .add_systems(AsciiUiSchedule, warn_ui_without_camera.in_set(AsciiUiSet::Extract))
             
             .register_type::<AsciiUi>()
-            .register_type::<AsciiButton>()
+            .register_type::<AsciiUiScale>()
+            .register_type::<AsciiUiTarget>()
+            .register_type::<AsciiUiRoot>()
+            .register_type::<AsciiRedrawTimer>()
+            .register_type::<AsciiLightmap>()
+            .register_type::<TextOverflow>()
+            .register_type::<AsciiBlendMode>()
         ;
     }
 }
 
+//=============================================================================
+//             Ascii Ui Schedule
+//=============================================================================
+
+// Runs once a frame right after `PostUpdate`, so everything the game did this frame, including
+// transform and visibility propagation, is seen before the ui is drawn.
+#[derive(ScheduleLabel, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct AsciiUiSchedule;
+
+// The systems of the crate, for ordering systems of the game around them. The first three are in
+// the main schedules, the rest in the `AsciiUiSchedule`.
+#[derive(SystemSet, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AsciiUiSet {
+    // In `PreUpdate`, after bevy's input and the `AsciiCameraSet`: the cursor on the grid.
+    Input,
+    // In `Update`: widgets react to input, hotkeys fire and widget events are sent. Systems that
+    // change a widget in `Update` go before this to have it react in the same frame, systems that
+    // read widget events go after it.
+    Interaction,
+    // In `PostUpdate`: widget events are handed to their `AsciiEventListener`s.
+    Propagate,
+    // Positions, opacity and anything else that changes how nodes are laid out. Systems here can
+    // send `AsciiMarkDirtyEvent`s.
+    Layout,
+    // All of the dirty events of the frame are turned into a single redraw.
+    Draw,
+    // The dirty state is final. The render world extracts the ui after this, so systems that
+    // read `AsciiUi::is_dirty` belong here.
+    Extract,
+}
+
 //=============================================================================
 //             Ascii UiComponent
 //=============================================================================
@@ -49,40 +145,182 @@ impl Plugin for AsciiUiPlugin {
 pub struct AsciiUi {
     // nodes: Vec<Arc<Mutex<Box<dyn AsciiUiNode + Send + Sync>>>>,
     is_dirty: bool,
+    is_live_dirty: bool,
+    // Draws `Color::Rgb` cells in their exact color. This uploads a second texture with the
+    // overlay, without it rgb colors are drawn as the closest color of the palette.
+    pub true_color: bool,
 }
 
 impl AsciiUi {
+    pub fn with_true_color(mut self) -> Self {
+        self.true_color = true;
+        self
+    }
+
     pub fn is_dirty(&self) -> bool {
         self.is_dirty
     }
+
+    // Whether the live layer, with the nodes that have an `AsciiRedrawTimer`, is drawn again this
+    // frame. It always is when the rest of the ui is.
+    pub fn is_live_dirty(&self) -> bool {
+        self.is_live_dirty
+    }
+}
+
+// Draws the ui of the camera with cells this many times as wide and tall as the cells of the
+// scene, so a scene of 120 columns can have a ui of 40 with a scale of 3. The ui is laid out on
+// its own smaller grid and the shader scales it up without smoothing. Without it the ui shares
+// the grid of the scene.
+#[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AsciiUiScale(pub u32);
+
+impl Default for AsciiUiScale {
+    fn default() -> Self {
+        AsciiUiScale(1)
+    }
+}
+
+// Draws a ui node and its children only on the given camera. Without it, nodes are drawn on the
+// camera they were spawned under.
+#[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AsciiUiTarget(pub Entity);
+
+// The top of a ui tree spawned with `ascii_ui` or `ascii_ui_on`. `despawn_ascii_tree` takes it away
+// together with everything under it.
+#[derive(Component, Reflect, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct AsciiUiRoot;
+
+// Whether a node hangs under an entity that is gone, because it was despawned without its
+// children. The children keep their `Parent`, and would be drawn on every camera otherwise.
+pub(crate) fn is_detached(entity: Entity, hierarchy: &Query<(Option<&Parent>, Option<&AsciiUiTarget>, Has<AsciiCamera>)>) -> bool {
+    let mut current = Some(entity);
+    while let Some(node) = current {
+        let Ok((parent, ..)) = hierarchy.get(node) else {
+            return true;
+        };
+        current = parent.map(|parent| parent.get());
+    }
+    false
+}
+
+// The camera a ui node is drawn on. This is the closest `AsciiUiTarget` up the hierarchy, or the
+// camera the node was spawned under. Nodes without either are drawn on every camera.
+pub(crate) fn ui_target(entity: Entity, hierarchy: &Query<(Option<&Parent>, Option<&AsciiUiTarget>, Has<AsciiCamera>)>) -> Option<Entity> {
+    let mut current = Some(entity);
+    while let Some(node) = current {
+        let Ok((parent, target, is_camera)) = hierarchy.get(node) else {
+            return None;
+        };
+        if let Some(target) = target {
+            return Some(target.0);
+        }
+        if is_camera {
+            return Some(node);
+        }
+        current = parent.map(|parent| parent.get());
+    }
+    None
 }
 
 //=============================================================================
 //             Rerender Ui Event
 //=============================================================================
 
+// For nodes that change over time on their own, like a `TextOverflow::Marquee`. The node and the
+// nodes under it are drawn on the live layer of the overlay, and every time the timer finishes
+// only that layer is drawn again, not the rest of the ui.
+#[derive(Component, Reflect, Clone, Debug)]
+pub struct AsciiRedrawTimer(pub Timer);
+
+impl AsciiRedrawTimer {
+    pub fn new(seconds: f32) -> Self {
+        AsciiRedrawTimer(Timer::from_seconds(seconds, TimerMode::Repeating))
+    }
+}
+
+impl Default for AsciiRedrawTimer {
+    fn default() -> Self {
+        AsciiRedrawTimer::new(0.1)
+    }
+}
+
+fn tick_redraw_timers(
+    mut timers: Query<(&mut AsciiRedrawTimer, Option<&InheritedVisibility>)>,
+    mut events: EventWriter<AsciiMarkLiveDirtyEvent>,
+    time: Res<AsciiUiTime>,
+) {
+    let mut is_dirty = false;
+    for (mut timer, visibility) in timers.iter_mut() {
+        timer.0.tick(time.delta());
+        if timer.0.just_finished() && visibility.map(|v| v.get()).unwrap_or(true) {
+            is_dirty = true;
+        }
+    }
+
+    if is_dirty {
+        events.send(AsciiMarkLiveDirtyEvent);
+    }
+}
+
 #[derive(Event, Debug, Clone, Reflect, PartialEq, Eq)]
 pub struct AsciiMarkDirtyEvent;
 
-fn mark_ui_dirty(
-    mut ui: Query<&mut AsciiUi>,
-    mut events : EventReader<AsciiMarkDirtyEvent>,
-    window_events : EventReader<WindowResized>
+// Draws only the live layer of the ui again, see `AsciiRedrawTimer`.
+#[derive(Event, Debug, Clone, Reflect, PartialEq, Eq)]
+pub struct AsciiMarkLiveDirtyEvent;
+
+// Nodes that were left behind when the node above them was despawned on its own are despawned
+// too, and nodes that are gone take their glyphs off the screen with a redraw.
+fn despawn_detached_nodes(
+    mut commands: Commands,
+    nodes: Query<(Entity, &Parent), With<AsciiNode>>,
+    entities: &Entities,
+    mut removed: RemovedComponents<AsciiNode>,
+    mut events: EventWriter<AsciiMarkDirtyEvent>,
 ) {
-    if !events.is_empty() || !window_events.is_empty() {
-        for mut ui in ui.iter_mut() {
-            ui.is_dirty = true;
+    let mut is_dirty = removed.read().count() > 0;
+    for (entity, parent) in nodes.iter() {
+        if !entities.contains(parent.get()) {
+            commands.entity(entity).despawn_recursive();
+            is_dirty = true;
         }
     }
-    
-    events.clear();
+
+    if is_dirty {
+        events.send(AsciiMarkDirtyEvent);
+    }
 }
 
-fn clean_ui( 
-    mut ui: Query<&mut AsciiUi>
+// Ui nodes are only drawn on cameras with both an `AsciiCamera` and an `AsciiUi`. Without one
+// nothing shows up, which is easy to miss, so it is pointed out once.
+fn warn_ui_without_camera(
+    nodes: Query<(), With<AsciiNode>>,
+    cameras: Query<(), (With<AsciiCamera>, With<AsciiUi>)>,
+    mut has_warned: Local<bool>,
 ) {
+    if *has_warned || nodes.is_empty() || !cameras.is_empty() {
+        return;
+    }
+    warn!("There are ascii ui nodes, but no camera with an AsciiCamera and an AsciiUi to draw them on");
+    *has_warned = true;
+}
+
+// Every dirty event sent since the last frame ends up as exactly one redraw, uis that were not
+// marked are clean again.
+fn mark_ui_dirty(
+    mut ui: Query<&mut AsciiUi>,
+    mut events : EventReader<AsciiMarkDirtyEvent>,
+    mut live_events : EventReader<AsciiMarkLiveDirtyEvent>,
+    mut window_events : EventReader<WindowResized>
+) {
+    let is_dirty = events.read().count() > 0 || window_events.read().count() > 0;
+    let is_live_dirty = live_events.read().count() > 0 || is_dirty;
     for mut ui in ui.iter_mut() {
-        ui.is_dirty = false;
+        if ui.is_dirty != is_dirty || ui.is_live_dirty != is_live_dirty {
+            ui.is_dirty = is_dirty;
+            ui.is_live_dirty = is_live_dirty;
+        }
     }
 }
 
@@ -90,6 +328,8 @@ fn clean_ui(
 //             Styling Constants
 //=============================================================================
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum BorderType {
     Full,
     Half,
@@ -98,109 +338,230 @@ pub enum BorderType {
 }
 
 impl BorderType {
-    fn get_character(&self, x: u32, y: u32, width: u32, height: u32) -> Character {
-        if x == 0 && y == 0 {
-            self.top_left()
-        } else if x == width - 1 && y == 0 {
-            self.top_right()
-        } else if x == 0 && y == height - 1 {
-            self.bottom_left()
-        } else if x == width - 1 && y == height - 1 {
-            self.bottom_right()
-        } else if x == 0 {
-            self.left()
-        } else if x == width - 1 {
-            self.right()
-        } else if y == 0 {
-            self.top()
-        } else if y == height - 1 {
-            self.bottom()
-        } else {
-            Character::Nil
+    pub fn style(&self) -> BorderStyle {
+        match self {
+            BorderType::Full => BorderStyle::SINGLE,
+            BorderType::Half => BorderStyle::THICK,
+            BorderType::Dashed => BorderStyle::DASHED,
+            BorderType::None => BorderStyle::NONE,
         }
     }
-    
+
     pub fn top(&self) -> Character {
-        match self {
-            BorderType::Full => Character::BorderN,
-            BorderType::Half => todo!(),
-            BorderType::Dashed => todo!(),
-            BorderType::None => Character::Nil,
-        }
+        self.style().top
     }
-    
+
     pub fn bottom(&self) -> Character {
-        match self {
-            BorderType::Full => Character::BorderS,
-            BorderType::Half => todo!(),
-            BorderType::Dashed => todo!(),
-            BorderType::None => Character::Nil,
-        }
+        self.style().bottom
     }
-    
+
     pub fn left(&self) -> Character {
-        match self {
-            BorderType::Full => Character::BorderW,
-            BorderType::Half => todo!(),
-            BorderType::Dashed => todo!(),
-            BorderType::None => Character::Nil,
-        }
+        self.style().left
     }
-    
+
     pub fn right(&self) -> Character {
-        match self {
-            BorderType::Full => Character::BorderE,
-            BorderType::Half => todo!(),
-            BorderType::Dashed => todo!(),
-            BorderType::None => Character::Nil,
-        }
+        self.style().right
     }
-    
+
     pub fn top_left(&self) -> Character {
-        match self {
-            BorderType::Full => Character::LBorderNW,
-            BorderType::Half => todo!(),
-            BorderType::Dashed => todo!(),
-            BorderType::None => Character::Nil,
-        }
+        self.style().top_left
     }
-    
+
     pub fn top_right(&self) -> Character {
-        match self {
-            BorderType::Full => Character::LBorderNE,
-            BorderType::Half => todo!(),
-            BorderType::Dashed => todo!(),
-            BorderType::None => Character::Nil,
-        }
+        self.style().top_right
     }
-    
+
     pub fn bottom_left(&self) -> Character {
-        match self {
-            BorderType::Full => Character::LBorderSW,
-            BorderType::Half => todo!(),
-            BorderType::Dashed => todo!(),
-            BorderType::None => Character::Nil,
-        }
+        self.style().bottom_left
     }
-    
+
     pub fn bottom_right(&self) -> Character {
-        match self {
-            BorderType::Full => Character::LBorderSE,
-            BorderType::Half => todo!(),
-            BorderType::Dashed => todo!(),
-            BorderType::None => Character::Nil,
+        self.style().bottom_right
+    }
+}
+
+impl From<BorderType> for BorderStyle {
+    fn from(value: BorderType) -> Self {
+        value.style()
+    }
+}
+
+// The characters a border is drawn with. If `color` or `bg_color` are set they take priority
+// over the colors of the drawer the style is used with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct BorderStyle {
+    pub top: Character,
+    pub bottom: Character,
+    pub left: Character,
+    pub right: Character,
+    pub top_left: Character,
+    pub top_right: Character,
+    pub bottom_left: Character,
+    pub bottom_right: Character,
+    pub color: Option<Color>,
+    pub bg_color: Option<Color>,
+}
+
+impl BorderStyle {
+    // Lines along the outer edge of the cells.
+    pub const SINGLE: BorderStyle = BorderStyle::new(
+        Character::BorderN,
+        Character::BorderS,
+        Character::BorderW,
+        Character::BorderE,
+        [Character::LBorderNW, Character::LBorderNE, Character::LBorderSW, Character::LBorderSE],
+    );
+
+    // Lines through the middle of the cells, like the unicode box drawing characters.
+    pub const CENTERED: BorderStyle = BorderStyle::new(
+        Character::BorderHorizontalCenter,
+        Character::BorderHorizontalCenter,
+        Character::BorderVerticalCenter,
+        Character::BorderVerticalCenter,
+        [Character::CornerNW, Character::CornerWS, Character::CornerNE, Character::CornerSE],
+    );
+
+    // '=' along the top and bottom, '|' down the sides and '#' in the corners. This is not a
+    // double line box, the built-in font has no double line characters.
+    pub const ASCII_DOUBLE: BorderStyle = BorderStyle::new(
+        Character::Equal,
+        Character::Equal,
+        Character::Pipe,
+        Character::Pipe,
+        [Character::Hashtag, Character::Hashtag, Character::Hashtag, Character::Hashtag],
+    );
+
+    pub const THICK: BorderStyle = BorderStyle::new(
+        Character::ThickBorderN,
+        Character::ThickBorderS,
+        Character::ThickBorderW,
+        Character::ThickBorderE,
+        [Character::QuadNW, Character::QuadNE, Character::QuadSW, Character::QuadSE],
+    );
+
+    pub const ROUNDED: BorderStyle = BorderStyle::new(
+        Character::BorderHorizontalCenter,
+        Character::BorderHorizontalCenter,
+        Character::BorderVerticalCenter,
+        Character::BorderVerticalCenter,
+        [
+            Character::RoundedCornerNW,
+            Character::RoundedCornerCenterNE,
+            Character::RoundedCornerCenterSW,
+            Character::RoundedCornerCenterSE,
+        ],
+    );
+
+    pub const DASHED: BorderStyle = BorderStyle::new(
+        Character::DashedHorizontalCenter,
+        Character::DashedHorizontalCenter,
+        Character::DashedW,
+        Character::DashedE,
+        [Character::CornerNW, Character::CornerWS, Character::CornerNE, Character::CornerSE],
+    );
+
+    pub const NONE: BorderStyle = BorderStyle::uniform(Character::Nil);
+
+    // Corners are in the order top left, top right, bottom left, bottom right.
+    pub const fn new(top: Character, bottom: Character, left: Character, right: Character, corners: [Character; 4]) -> Self {
+        BorderStyle {
+            top,
+            bottom,
+            left,
+            right,
+            top_left: corners[0],
+            top_right: corners[1],
+            bottom_left: corners[2],
+            bottom_right: corners[3],
+            color: None,
+            bg_color: None,
+        }
+    }
+
+    // A border that uses the same character all the way around.
+    pub const fn uniform(character: Character) -> Self {
+        BorderStyle::new(character, character, character, character, [character; 4])
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn bg_color(mut self, color: Color) -> Self {
+        self.bg_color = Some(color);
+        self
+    }
+
+    pub(crate) fn get_character(&self, x: u32, y: u32, width: u32, height: u32) -> Character {
+        if x == 0 && y == 0 {
+            self.top_left
+        } else if x + 1 == width && y == 0 {
+            self.top_right
+        } else if x == 0 && y + 1 == height {
+            self.bottom_left
+        } else if x + 1 == width && y + 1 == height {
+            self.bottom_right
+        } else if x == 0 {
+            self.left
+        } else if x + 1 == width {
+            self.right
+        } else if y == 0 {
+            self.top
+        } else if y + 1 == height {
+            self.bottom
+        } else {
+            Character::Nil
         }
     }
 }
 
-#[derive(Default)]
+#[derive(Default, Clone, Copy, PartialEq, Debug, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum TextOverflow {
     #[default]
     Hidden,
     Elipses,
+    // Scrolls text that does not fit to the left by `speed` characters per second. The ui only
+    // redraws when it is dirty, so give the node an `AsciiRedrawTimer` to keep it moving.
+    Marquee {
+        speed: f32,
+    },
+}
+
+impl TextOverflow {
+    const ELIPSES: &'static str = "...";
+    const MARQUEE_GAP: &'static str = "   ";
+
+    // Fits a single line of text into `width` characters. `time` is in seconds and moves the
+    // marquee along.
+    pub fn fit(&self, text: &str, width: usize, time: f32) -> String {
+        let length = text.chars().count();
+        if length <= width {
+            return text.to_string();
+        }
+
+        match self {
+            TextOverflow::Hidden => text.chars().take(width).collect(),
+            TextOverflow::Elipses => {
+                if width <= Self::ELIPSES.len() {
+                    Self::ELIPSES.chars().take(width).collect()
+                } else {
+                    text.chars().take(width - Self::ELIPSES.len()).chain(Self::ELIPSES.chars()).collect()
+                }
+            }
+            TextOverflow::Marquee { speed } => {
+                let looped: Vec<char> = text.chars().chain(Self::MARQUEE_GAP.chars()).collect();
+                let offset = (time * speed.max(0.0)) as usize % looped.len();
+                looped.iter().cycle().skip(offset).take(width).collect()
+            }
+        }
+    }
 }
 
 #[derive(Default, Clone, Copy, PartialEq, Eq, Debug, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum HorizontalAlignment {
     #[default]
     Left,
@@ -209,6 +570,7 @@ pub enum HorizontalAlignment {
 }
 
 #[derive(Default, Clone, Copy, PartialEq, Eq, Debug, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum VerticalAlignment {
     #[default]
     Top,
@@ -216,7 +578,8 @@ pub enum VerticalAlignment {
     Bottom,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct Padding {
     pub top: u32,
     pub right: u32,
@@ -224,17 +587,6 @@ pub struct Padding {
     pub left: u32,
 }
 
-impl Default for Padding {
-    fn default() -> Self {
-        Padding {
-            top: 0,
-            right: 0,
-            bottom: 0,
-            left: 0,
-        }
-    }
-}
-
 impl From<u32> for Padding {
     fn from(padding: u32) -> Self {
         Padding {
@@ -267,3 +619,120 @@ impl From<(u32, u32, u32, u32)> for Padding {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+
+    fn fit(overflow: TextOverflow, widths: &[usize], time: f32) -> Vec<String> {
+        widths.iter().map(|width| overflow.fit("hello", *width, time)).collect()
+    }
+
+    #[test]
+    fn hidden_overflow_cuts_the_text_off() {
+        assert_eq!(fit(TextOverflow::Hidden, &[0, 1, 2, 3, 5, 6], 0.0), vec!["", "h", "he", "hel", "hello", "hello"]);
+    }
+
+    #[test]
+    fn elipses_take_the_place_of_the_last_characters() {
+        assert_eq!(fit(TextOverflow::Elipses, &[0, 1, 2, 3, 4, 5], 0.0), vec!["", ".", "..", "...", "h...", "hello"]);
+    }
+
+    #[test]
+    fn marquee_scrolls_and_starts_over() {
+        let marquee = TextOverflow::Marquee { speed: 1.0 };
+        assert_eq!(fit(marquee, &[0, 1, 2, 3, 5], 0.0), vec!["", "h", "he", "hel", "hello"]);
+        assert_eq!(fit(marquee, &[3], 2.0), vec!["llo"]);
+        // A gap is left before the text comes around again.
+        assert_eq!(fit(marquee, &[3], 4.0), vec!["o  "]);
+        assert_eq!(fit(marquee, &[3], 8.0), vec!["hel"]);
+        // Text that fits does not move.
+        assert_eq!(fit(marquee, &[5], 2.0), vec!["hello"]);
+        // Nor does text without a speed.
+        assert_eq!(fit(TextOverflow::Marquee { speed: -1.0 }, &[3], 2.0), vec!["hel"]);
+    }
+
+    fn ui_world() -> (World, Entity) {
+        let mut world = World::new();
+        world.init_resource::<Events<AsciiMarkDirtyEvent>>();
+        world.init_resource::<Events<AsciiMarkLiveDirtyEvent>>();
+        world.init_resource::<Events<WindowResized>>();
+        world.init_resource::<AsciiUiTime>();
+        let ui = world.spawn(AsciiUi::default()).id();
+        (world, ui)
+    }
+
+    #[test]
+    fn redraw_timers_only_draw_the_live_layer_again() {
+        let (mut world, ui) = ui_world();
+        let mut timer = AsciiRedrawTimer::new(0.1);
+        timer.0.set_elapsed(timer.0.duration());
+        world.spawn(timer);
+        world.spawn(AsciiRedrawTimer::new(0.1));
+
+        world.run_system_once(tick_redraw_timers);
+        world.run_system_once(mark_ui_dirty);
+        let ascii_ui = world.get::<AsciiUi>(ui).unwrap();
+        assert!(ascii_ui.is_live_dirty());
+        assert!(!ascii_ui.is_dirty());
+    }
+
+    #[test]
+    fn the_live_layer_is_drawn_with_the_rest_of_the_ui() {
+        let (mut world, ui) = ui_world();
+        world.send_event(AsciiMarkDirtyEvent);
+        world.run_system_once(mark_ui_dirty);
+        let ascii_ui = world.get::<AsciiUi>(ui).unwrap();
+        assert!(ascii_ui.is_dirty() && ascii_ui.is_live_dirty());
+
+        world.resource_mut::<Events<AsciiMarkDirtyEvent>>().clear();
+        world.run_system_once(mark_ui_dirty);
+        let ascii_ui = world.get::<AsciiUi>(ui).unwrap();
+        assert!(!ascii_ui.is_dirty() && !ascii_ui.is_live_dirty());
+    }
+
+    #[derive(Resource, Default)]
+    struct Redraws(u32);
+
+    fn count_redraws(ui: Query<&AsciiUi>, mut redraws: ResMut<Redraws>) {
+        if ui.iter().any(|ui| ui.is_dirty()) {
+            redraws.0 += 1;
+        }
+    }
+
+    fn send_dirty_event(mut events: EventWriter<AsciiMarkDirtyEvent>, mut is_sent: Local<bool>) {
+        if !std::mem::replace(&mut *is_sent, true) {
+            events.send(AsciiMarkDirtyEvent);
+        }
+    }
+
+    #[test]
+    fn changes_of_a_frame_are_drawn_exactly_once() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), bevy::input::InputPlugin, bevy::window::WindowPlugin::default(), AsciiUiPlugin))
+            .init_asset::<Image>()
+            .init_resource::<Redraws>()
+            .add_systems(AsciiUiSchedule, count_redraws.in_set(AsciiUiSet::Extract));
+        app.world.spawn(AsciiUi::default());
+        let button = app.world.spawn((AsciiNode::default(), component::button::AsciiButton::from_string("Ok"))).id();
+        app.update();
+        app.update();
+        app.world.resource_mut::<Redraws>().0 = 0;
+
+        // A component changed outside of the ui, and dirty events from the game in `Update`, from
+        // the ui schedule itself and from before the frame.
+        app.world.get_mut::<component::button::AsciiButton>(button).unwrap().set_changed();
+        app.world.send_event(AsciiMarkDirtyEvent);
+        app.add_systems(Update, send_dirty_event);
+        app.add_systems(AsciiUiSchedule, send_dirty_event.in_set(AsciiUiSet::Layout));
+        app.update();
+        assert_eq!(app.world.resource::<Redraws>().0, 1);
+
+        // Nothing changed after that, so nothing is drawn again.
+        app.update();
+        app.update();
+        assert_eq!(app.world.resource::<Redraws>().0, 1);
+    }
+}
@@ -0,0 +1,217 @@
+use bevy::prelude::*;
+
+use super::{bounds::AsciiNode, time::AsciiUiTime, AsciiMarkDirtyEvent, AsciiUiSchedule, AsciiUiSet};
+
+//=============================================================================
+//             Plugin and Systems
+//=============================================================================
+
+pub(crate) struct AsciiOpacityPlugin;
+
+impl Plugin for AsciiOpacityPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .register_type::<AsciiOpacity>()
+            .register_type::<AsciiInheritedOpacity>()
+            .register_type::<AsciiFade>()
+            .add_systems(AsciiUiSchedule, (fade_opacity, propagate_opacity).chain().in_set(AsciiUiSet::Layout))
+        ;
+    }
+}
+
+//=============================================================================
+//             Ascii Opacity
+//=============================================================================
+
+// The opacity of a ui node and all of its children, from 0.0 (invisible) to 1.0. Opacities
+// multiply down the hierarchy, so a child at 0.5 inside a parent at 0.5 is drawn at 0.25.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct AsciiOpacity(pub f32);
+
+impl Default for AsciiOpacity {
+    fn default() -> Self {
+        AsciiOpacity(1.0)
+    }
+}
+
+// The opacity a node is drawn with after its parents are taken into account. This is kept up to
+// date automatically and should not be inserted by hand.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Reflect)]
+pub struct AsciiInheritedOpacity(f32);
+
+impl AsciiInheritedOpacity {
+    pub fn get(&self) -> f32 {
+        self.0
+    }
+}
+
+// Works the inherited opacity out top down from the roots of the ui, so every node is visited
+// once. It only runs on frames where an opacity or the hierarchy changed.
+#[allow(clippy::type_complexity)]
+fn propagate_opacity(
+    mut commands: Commands,
+    changed: Query<(), Or<(Changed<AsciiOpacity>, Changed<Parent>, Added<AsciiNode>)>>,
+    mut removed_opacities: RemovedComponents<AsciiOpacity>,
+    mut removed_parents: RemovedComponents<Parent>,
+    nodes: Query<(Entity, Option<&Parent>, Option<&AsciiInheritedOpacity>, Option<&AsciiOpacity>, Option<&Children>), With<AsciiNode>>,
+    ancestors: Query<(Option<&AsciiOpacity>, Option<&Parent>)>,
+    mut dirty: EventWriter<AsciiMarkDirtyEvent>,
+) {
+    let removed = removed_opacities.read().count() + removed_parents.read().count() > 0;
+    if changed.is_empty() && !removed {
+        return;
+    }
+
+    // Roots start from the opacity of the entities above them that are not nodes, like a camera.
+    let mut stack = nodes
+        .iter()
+        .filter(|(_, parent, ..)| parent.map(|parent| !nodes.contains(parent.get())).unwrap_or(true))
+        .map(|(entity, parent, ..)| {
+            let mut opacity = 1.0;
+            let mut current = parent.map(|parent| parent.get());
+            while let Some((ancestor_opacity, ancestor_parent)) = current.and_then(|ancestor| ancestors.get(ancestor).ok()) {
+                opacity *= ancestor_opacity.map(|opacity| opacity.0.clamp(0.0, 1.0)).unwrap_or(1.0);
+                current = ancestor_parent.map(|parent| parent.get());
+            }
+            (entity, opacity)
+        })
+        .collect::<Vec<_>>();
+
+    let mut is_dirty = false;
+    while let Some((entity, parent_opacity)) = stack.pop() {
+        let Ok((_, _, inherited, opacity, children)) = nodes.get(entity) else {
+            continue;
+        };
+        let opacity = parent_opacity * opacity.map(|opacity| opacity.0.clamp(0.0, 1.0)).unwrap_or(1.0);
+        let is_unchanged = match inherited {
+            Some(inherited) => inherited.0 == opacity,
+            None => opacity == 1.0,
+        };
+        if !is_unchanged {
+            commands.entity(entity).insert(AsciiInheritedOpacity(opacity));
+            is_dirty = true;
+        }
+        if let Some(children) = children {
+            stack.extend(children.iter().map(|child| (*child, opacity)));
+        }
+    }
+
+    if is_dirty {
+        dirty.send(AsciiMarkDirtyEvent);
+    }
+}
+
+//=============================================================================
+//             Ascii Fade
+//=============================================================================
+
+// Moves the `AsciiOpacity` of the node towards `target` by `speed` every second. The fade is
+// removed once the target is reached.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct AsciiFade {
+    pub target: f32,
+    pub speed: f32,
+}
+
+impl AsciiFade {
+    pub fn new(target: f32, duration: f32) -> Self {
+        AsciiFade {
+            target: target.clamp(0.0, 1.0),
+            speed: 1.0 / duration.max(f32::EPSILON),
+        }
+    }
+
+    pub fn fade_in(duration: f32) -> Self {
+        AsciiFade::new(1.0, duration)
+    }
+
+    pub fn fade_out(duration: f32) -> Self {
+        AsciiFade::new(0.0, duration)
+    }
+}
+
+fn fade_opacity(
+    mut commands: Commands,
+    mut fades: Query<(Entity, &AsciiFade, Option<&mut AsciiOpacity>)>,
+    time: Res<AsciiUiTime>,
+) {
+    for (entity, fade, opacity) in fades.iter_mut() {
+        let current = opacity.as_ref().map(|opacity| opacity.0).unwrap_or(1.0);
+        let step = fade.speed * time.delta_seconds();
+        let next = if current < fade.target {
+            (current + step).min(fade.target)
+        } else {
+            (current - step).max(fade.target)
+        };
+
+        match opacity {
+            Some(mut opacity) => opacity.0 = next,
+            None => {
+                commands.entity(entity).insert(AsciiOpacity(next));
+            }
+        }
+
+        if next == fade.target {
+            commands.entity(entity).remove::<AsciiFade>();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+
+    fn opacity_of(world: &World, entity: Entity) -> Option<f32> {
+        world.get::<AsciiInheritedOpacity>(entity).map(|opacity| opacity.get())
+    }
+
+    fn dirty_events(world: &mut World) -> usize {
+        world.resource_mut::<Events<AsciiMarkDirtyEvent>>().drain().count()
+    }
+
+    // A camera that is half see through, with a node at 0.5 and a child of it without an opacity.
+    fn opacity_world() -> (World, Entity, Entity) {
+        let mut world = World::new();
+        world.init_resource::<Events<AsciiMarkDirtyEvent>>();
+        let camera = world.spawn(AsciiOpacity(0.5)).id();
+        let panel = world.spawn((AsciiNode::default(), AsciiOpacity(0.5))).id();
+        let label = world.spawn(AsciiNode::default()).id();
+        world.entity_mut(camera).add_child(panel);
+        world.entity_mut(panel).add_child(label);
+        (world, panel, label)
+    }
+
+    #[test]
+    fn opacities_multiply_down_the_hierarchy() {
+        let (mut world, panel, label) = opacity_world();
+        world.run_system_once(propagate_opacity);
+
+        assert_eq!(opacity_of(&world, panel), Some(0.25));
+        assert_eq!(opacity_of(&world, label), Some(0.25));
+        assert_eq!(dirty_events(&mut world), 1);
+    }
+
+    #[test]
+    fn nothing_is_done_until_an_opacity_changes() {
+        let (mut world, panel, label) = opacity_world();
+        let mut system = IntoSystem::into_system(propagate_opacity);
+        system.initialize(&mut world);
+        system.run((), &mut world);
+        system.apply_deferred(&mut world);
+        dirty_events(&mut world);
+
+        system.run((), &mut world);
+        system.apply_deferred(&mut world);
+        assert_eq!(dirty_events(&mut world), 0);
+
+        world.get_mut::<AsciiOpacity>(panel).unwrap().0 = 1.0;
+        system.run((), &mut world);
+        system.apply_deferred(&mut world);
+        assert_eq!(opacity_of(&world, label), Some(0.5));
+        assert_eq!(dirty_events(&mut world), 1);
+    }
+}
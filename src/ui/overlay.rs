@@ -0,0 +1,164 @@
+use std::sync::{Arc, Mutex};
+
+use bevy::{
+    prelude::*,
+    render::{Extract, RenderApp},
+};
+
+use crate::{ascii::AsciiCamera, render::ascii::LiveOverlayBuffer};
+
+use super::{bounds::AsciiBounds, buffer::AsciiBuffer, AsciiMarkLiveDirtyEvent, AsciiUiSchedule, AsciiUiSet};
+
+//=============================================================================
+//             Plugin and Systems
+//=============================================================================
+
+pub(crate) struct AsciiOverlayPlugin;
+
+impl Plugin for AsciiOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<AsciiOverlay>()
+            .add_systems(First, clear_overlay)
+            .add_systems(AsciiUiSchedule, mark_overlay_dirty.in_set(AsciiUiSet::Layout))
+        ;
+
+        let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app.add_systems(ExtractSchedule, extract_overlay.after(apply_deferred));
+    }
+}
+
+//=============================================================================
+//             Ascii Overlay
+//=============================================================================
+
+type OverlayDraw = Box<dyn FnOnce(&mut AsciiBuffer) + Send + Sync>;
+
+// Draws straight onto the overlay of a camera from any system, without spawning a ui node. The
+// draws are queued and run when the ui is extracted, so they have to be queued again every frame
+// they should be seen. They are drawn on the live layer, which is drawn again without the rest of
+// the ui.
+#[derive(Resource, Default)]
+pub struct AsciiOverlay {
+    draws: Arc<Mutex<Vec<(Entity, u32, OverlayDraw)>>>,
+    was_drawn: bool,
+}
+
+impl AsciiOverlay {
+    // Draws on top of the ui nodes of the camera.
+    pub const LAYER: u32 = 1_000_000;
+
+    // Queues a draw that covers the whole overlay of the camera.
+    pub fn draw(&self, camera: Entity, draw: impl FnOnce(&mut AsciiBuffer) + Send + Sync + 'static) {
+        self.draw_on_layer(camera, Self::LAYER, draw);
+    }
+
+    // Same as `draw`, but characters on a higher layer are not drawn over.
+    pub fn draw_on_layer(&self, camera: Entity, layer: u32, draw: impl FnOnce(&mut AsciiBuffer) + Send + Sync + 'static) {
+        let Ok(mut draws) = self.draws.lock() else {
+            return;
+        };
+        draws.push((camera, layer, Box::new(draw)));
+    }
+
+    fn has_draws(&self) -> bool {
+        self.draws.lock().map(|draws| !draws.is_empty()).unwrap_or(false)
+    }
+}
+
+// Draws that were never extracted, like when there is no render app, are thrown away.
+fn clear_overlay(overlay: Res<AsciiOverlay>) {
+    if let Ok(mut draws) = overlay.draws.lock() {
+        draws.clear();
+    }
+}
+
+// The live layer is drawn again every frame something is queued, and once more after that to
+// remove what was drawn.
+pub(crate) fn mark_overlay_dirty(
+    mut overlay: ResMut<AsciiOverlay>,
+    mut events: EventWriter<AsciiMarkLiveDirtyEvent>,
+) {
+    let has_draws = overlay.has_draws();
+    if has_draws || overlay.was_drawn {
+        events.send(AsciiMarkLiveDirtyEvent);
+    }
+    overlay.was_drawn = has_draws;
+}
+
+pub(crate) fn extract_overlay(
+    ascii_cameras: Query<(Entity, &LiveOverlayBuffer), With<AsciiCamera>>,
+    overlay: Extract<Res<AsciiOverlay>>,
+) {
+    let Ok(mut draws) = overlay.draws.lock() else {
+        return;
+    };
+
+    for (camera, layer, draw) in draws.drain(..) {
+        let Ok((_, buffer)) = ascii_cameras.get(camera) else {
+            continue;
+        };
+
+        let surface = &buffer.0;
+        let bounds = AsciiBounds::new(0, 0, surface.width(), surface.height(), layer);
+        let mut buffer = AsciiBuffer::new(surface, &bounds, None);
+        draw(&mut buffer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{ecs::system::RunSystemOnce, render::MainWorld};
+
+    use crate::ui::{buffer::AsciiSurface, character::Character};
+
+    use super::*;
+
+    fn live_events(world: &mut World) -> usize {
+        world.resource_mut::<Events<AsciiMarkLiveDirtyEvent>>().drain().count()
+    }
+
+    #[test]
+    fn draws_only_mark_the_live_layer() {
+        let mut world = World::new();
+        world.init_resource::<Events<AsciiMarkLiveDirtyEvent>>();
+        world.init_resource::<AsciiOverlay>();
+        let camera = world.spawn_empty().id();
+
+        world.resource::<AsciiOverlay>().draw(camera, |_| {});
+        world.run_system_once(mark_overlay_dirty);
+        assert_eq!(live_events(&mut world), 1);
+
+        // Once more to take the draws away, then nothing until something is drawn again.
+        world.run_system_once(clear_overlay);
+        world.run_system_once(mark_overlay_dirty);
+        assert_eq!(live_events(&mut world), 1);
+        world.run_system_once(mark_overlay_dirty);
+        assert_eq!(live_events(&mut world), 0);
+    }
+
+    #[test]
+    fn draws_are_extracted_onto_the_live_layer_of_their_camera() {
+        let mut main_world = World::new();
+        main_world.init_resource::<AsciiOverlay>();
+        let camera = main_world.spawn_empty().id();
+        let other_camera = main_world.spawn_empty().id();
+        main_world.resource::<AsciiOverlay>().draw(camera, |buffer| buffer.set_character(1, 0, Character::A));
+
+        let mut render_world = World::new();
+        let mut main = MainWorld::default();
+        *main = main_world;
+        render_world.insert_resource(main);
+        let live = AsciiSurface::new(2, 1);
+        let other_live = AsciiSurface::new(2, 1);
+        render_world.get_or_spawn(camera).unwrap().insert((AsciiCamera::default(), LiveOverlayBuffer(live.clone())));
+        render_world.get_or_spawn(other_camera).unwrap().insert((AsciiCamera::default(), LiveOverlayBuffer(other_live.clone())));
+        render_world.run_system_once(extract_overlay);
+
+        assert_eq!(live.changed_region(), Some(AsciiBounds::new(1, 0, 1, 1, 0)));
+        assert_eq!(other_live.changed_region(), None);
+    }
+}
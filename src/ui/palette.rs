@@ -0,0 +1,401 @@
+use std::{io, sync::RwLock};
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+    utils::BoxedFuture,
+};
+
+use super::{character::Color, AsciiMarkDirtyEvent};
+
+//=============================================================================
+//             Ascii Palette Plugin
+//=============================================================================
+
+// Loads `AsciiPalette`s from the assets folder. Every name of a loaded palette can be drawn with
+// `Color::named`, and editing the file while the game runs redraws the ui with the new colors.
+pub struct AsciiPalettePlugin;
+
+impl Plugin for AsciiPalettePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_asset::<AsciiPalette>()
+            .register_asset_loader(AsciiPaletteLoader)
+            .add_systems(PreUpdate, register_loaded_palettes)
+            .register_type::<ColorRef>()
+        ;
+    }
+}
+
+fn register_loaded_palettes(
+    mut events: EventReader<AssetEvent<AsciiPalette>>,
+    palettes: Res<Assets<AsciiPalette>>,
+    mut dirty: EventWriter<AsciiMarkDirtyEvent>,
+) {
+    for event in events.read() {
+        let (AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id }) = event else {
+            continue;
+        };
+        if let Some(palette) = palettes.get(*id) {
+            palette.register();
+            dirty.send(AsciiMarkDirtyEvent);
+        }
+    }
+}
+
+//=============================================================================
+//             Ascii Palette
+//=============================================================================
+
+// A list of named colors. Palettes are loaded from three formats:
+//
+// `.palette` has a name and a color on each line, like `blood_red #8a0303`. The color is either a
+// hex color or an index of the 256 color palette. Lines starting with `//` are comments.
+//
+// `.gpl` is the GIMP palette format that most pixel art tools export. Colors without a name are
+// named after their position in the file.
+//
+// `.palette.ron` is a map of names to colors, like `{ "blood_red": "#8a0303" }`, and needs the
+// `serialize` feature.
+#[derive(Asset, TypePath, Clone, Default, Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct AsciiPalette {
+    colors: Vec<(String, Color)>,
+}
+
+impl AsciiPalette {
+    pub fn new() -> Self {
+        AsciiPalette::default()
+    }
+
+    pub fn with_color(mut self, name: &str, color: Color) -> Self {
+        self.insert(name, color);
+        self
+    }
+
+    // Replaces the color if the name is already in the palette.
+    pub fn insert(&mut self, name: &str, color: Color) {
+        match self.colors.iter_mut().find(|(existing, _)| existing == name) {
+            Some((_, existing)) => *existing = color,
+            None => self.colors.push((name.to_string(), color)),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<Color> {
+        self.colors.iter().find(|(existing, _)| existing == name).map(|(_, color)| *color)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, Color)> {
+        self.colors.iter().map(|(name, color)| (name.as_str(), *color))
+    }
+
+    pub fn len(&self) -> usize {
+        self.colors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.colors.is_empty()
+    }
+
+    // Makes every color of the palette drawable with `Color::named`. Loaded palettes are
+    // registered by the `AsciiPalettePlugin`, so this is only needed for palettes made in code.
+    pub fn register(&self) {
+        for (name, color) in self.iter() {
+            register_color(name, color);
+        }
+    }
+
+    // Every name that has a color right now, from the loaded palettes and `register_color`.
+    pub fn registered() -> Self {
+        let mut palette = AsciiPalette::new();
+        if let Ok(colors) = NAMED_COLORS.read() {
+            for (name, color) in colors.iter() {
+                if let Some(color) = color {
+                    palette.insert(name, *color);
+                }
+            }
+        }
+        palette
+    }
+
+    // Reads the `.palette` format.
+    pub fn from_hex_list(text: &str) -> io::Result<Self> {
+        let mut palette = AsciiPalette::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with("//") {
+                continue;
+            }
+            let (name, value) = line
+                .split_once(|c: char| c.is_whitespace() || c == '=' || c == ':')
+                .ok_or_else(|| parse_error(number, "expected a name and a color"))?;
+            let value = value.trim_start_matches(|c: char| c.is_whitespace() || c == '=' || c == ':').trim();
+            let color = parse_color(value).ok_or_else(|| parse_error(number, &format!("'{}' is not a color", value)))?;
+            palette.insert(name, color);
+        }
+        Ok(palette)
+    }
+
+    // Reads the GIMP `.gpl` format.
+    pub fn from_gpl(text: &str) -> io::Result<Self> {
+        let mut lines = text.lines().enumerate();
+        if lines.next().map(|(_, header)| header.trim()) != Some("GIMP Palette") {
+            return Err(parse_error(0, "expected 'GIMP Palette'"));
+        }
+
+        let mut palette = AsciiPalette::new();
+        for (number, line) in lines {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with("Name:") || line.starts_with("Columns:") {
+                continue;
+            }
+            let mut parts = line.split_whitespace();
+            let mut channel = || {
+                parts
+                    .next()
+                    .and_then(|value| value.parse::<u8>().ok())
+                    .ok_or_else(|| parse_error(number, "expected a red, green and blue value from 0 to 255"))
+            };
+            let color = Color::Rgb(channel()?, channel()?, channel()?);
+            let name = parts.collect::<Vec<_>>().join(" ");
+            let name = if name.is_empty() || name == "Untitled" { palette.len().to_string() } else { name };
+            palette.insert(&name, color);
+        }
+        Ok(palette)
+    }
+
+    // Reads the `.palette.ron` format.
+    #[cfg(feature = "serialize")]
+    pub fn from_ron(text: &str) -> io::Result<Self> {
+        let entries: Vec<(String, String)> = ron::from_str::<std::collections::BTreeMap<String, String>>(text)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?
+            .into_iter()
+            .collect();
+
+        let mut palette = AsciiPalette::new();
+        for (name, value) in entries {
+            let color = parse_color(&value)
+                .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, format!("{}: '{}' is not a color", name, value)))?;
+            palette.insert(&name, color);
+        }
+        Ok(palette)
+    }
+}
+
+// A hex color like `#8a0303` or `8a0303`, or an index of the 256 color palette like `196`.
+fn parse_color(value: &str) -> Option<Color> {
+    if let Ok(index) = value.parse::<u8>() {
+        return Some(Color::from_index(index));
+    }
+    let hex = value.strip_prefix('#').unwrap_or(value);
+    // `from_str_radix` also takes a sign, like the `+1` in `+1+2+3`.
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?))
+}
+
+fn parse_error(line: usize, message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("line {}: {}", line + 1, message))
+}
+
+#[derive(Default)]
+pub struct AsciiPaletteLoader;
+
+impl AssetLoader for AsciiPaletteLoader {
+    type Asset = AsciiPalette;
+    type Settings = ();
+    type Error = io::Error;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a Self::Settings,
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut text = String::new();
+            reader.read_to_string(&mut text).await?;
+
+            let path = load_context.path().to_string_lossy().to_lowercase();
+            if path.ends_with(".gpl") {
+                AsciiPalette::from_gpl(&text)
+            } else if path.ends_with(".ron") {
+                #[cfg(feature = "serialize")]
+                return AsciiPalette::from_ron(&text);
+                #[cfg(not(feature = "serialize"))]
+                Err(io::Error::new(io::ErrorKind::Unsupported, "loading .palette.ron needs the serialize feature"))
+            } else {
+                AsciiPalette::from_hex_list(&text)
+            }
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["palette", "gpl", "palette.ron"]
+    }
+}
+
+//=============================================================================
+//             Named Colors
+//=============================================================================
+
+// Every name that has been given to `ColorRef::new`, with the color it was last registered as.
+static NAMED_COLORS: RwLock<Vec<(String, Option<Color>)>> = RwLock::new(Vec::new());
+
+// Gives a name a color, or changes the color of a name. Everything drawn with the name uses the
+// new color the next time it is drawn.
+pub fn register_color(name: &str, color: Color) {
+    // A name registered as another name gets that name's color, so looking up a name never has
+    // to follow a chain.
+    let color = color.resolve();
+    let id = ColorRef::new(name).0 as usize;
+    if let Ok(mut colors) = NAMED_COLORS.write() {
+        colors[id].1 = Some(color);
+    }
+}
+
+// A color that is looked up by name when it is drawn, so it follows the loaded palettes. It is
+// used as a `Color` through `Color::Named`. Names that no palette has registered are drawn
+// violet, so they stand out.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Reflect)]
+pub struct ColorRef(u32);
+
+impl ColorRef {
+    pub const MISSING: Color = Color::Violet;
+
+    pub fn new(name: &str) -> Self {
+        if let Some(id) = NAMED_COLORS
+            .read()
+            .ok()
+            .and_then(|colors| colors.iter().position(|(existing, _)| existing == name))
+        {
+            return ColorRef(id as u32);
+        }
+
+        let mut colors = NAMED_COLORS.write().unwrap_or_else(|error| error.into_inner());
+        // Another thread could have added the name between the read and the write.
+        let id = match colors.iter().position(|(existing, _)| existing == name) {
+            Some(id) => id,
+            None => {
+                colors.push((name.to_string(), None));
+                colors.len() - 1
+            }
+        };
+        ColorRef(id as u32)
+    }
+
+    pub fn name(&self) -> String {
+        NAMED_COLORS
+            .read()
+            .ok()
+            .and_then(|colors| colors.get(self.0 as usize).map(|(name, _)| name.clone()))
+            .unwrap_or_default()
+    }
+
+    // The color the name is registered as, if a palette has registered it yet.
+    pub fn get(&self) -> Option<Color> {
+        NAMED_COLORS.read().ok().and_then(|colors| colors.get(self.0 as usize).and_then(|(_, color)| *color))
+    }
+
+    pub fn color(&self) -> Color {
+        self.get().unwrap_or(ColorRef::MISSING)
+    }
+}
+
+impl From<&str> for ColorRef {
+    fn from(value: &str) -> Self {
+        ColorRef::new(value)
+    }
+}
+
+impl From<ColorRef> for Color {
+    fn from(value: ColorRef) -> Self {
+        Color::Named(value)
+    }
+}
+
+// Ids are only the same for one run of the game, so the name is saved instead.
+#[cfg(feature = "serialize")]
+impl serde::Serialize for ColorRef {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.name())
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl<'de> serde::Deserialize<'de> for ColorRef {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(ColorRef::new(&name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_of(result: io::Result<AsciiPalette>) -> String {
+        result.expect_err("the palette should not parse").to_string()
+    }
+
+    #[test]
+    fn hex_lists_are_parsed() {
+        let text = "// The colors of the shop\n\nblood_red #8a0303\nsky = 87ceeb\n  warning: 196\nsky #000000\n";
+        let palette = AsciiPalette::from_hex_list(text).unwrap();
+        assert_eq!(palette.get("blood_red"), Some(Color::Rgb(0x8a, 0x03, 0x03)));
+        assert_eq!(palette.get("warning"), Some(Color::from_index(196)));
+        // A name that is given again takes the last color.
+        assert_eq!(palette.get("sky"), Some(Color::Rgb(0, 0, 0)));
+        assert_eq!(palette.len(), 3);
+    }
+
+    #[test]
+    fn empty_palettes_are_empty() {
+        assert!(AsciiPalette::from_hex_list("").unwrap().is_empty());
+        assert!(AsciiPalette::from_hex_list("// nothing here\n\n").unwrap().is_empty());
+        assert!(AsciiPalette::from_gpl("GIMP Palette\n").unwrap().is_empty());
+    }
+
+    #[test]
+    fn malformed_colors_are_errors() {
+        assert_eq!(error_of(AsciiPalette::from_hex_list("ok #000000\nshort #12345")), "line 2: '#12345' is not a color");
+        assert_eq!(error_of(AsciiPalette::from_hex_list("bad #gg0000")), "line 1: '#gg0000' is not a color");
+        assert_eq!(error_of(AsciiPalette::from_hex_list("signed #+1+2+3")), "line 1: '#+1+2+3' is not a color");
+        assert_eq!(error_of(AsciiPalette::from_hex_list("wide #ééé")), "line 1: '#ééé' is not a color");
+        assert_eq!(error_of(AsciiPalette::from_hex_list("big 256")), "line 1: '256' is not a color");
+        assert_eq!(error_of(AsciiPalette::from_hex_list("lonely")), "line 1: expected a name and a color");
+    }
+
+    #[test]
+    fn gpl_palettes_are_parsed() {
+        let text = "GIMP Palette\nName: Shop\nColumns: 4\n# a comment\n255 0 0 Red\n  0 255 0\n0 0 255 Untitled\n";
+        let palette = AsciiPalette::from_gpl(text).unwrap();
+        assert_eq!(palette.get("Red"), Some(Color::Rgb(255, 0, 0)));
+        // Colors without a name are named after their position.
+        assert_eq!(palette.get("1"), Some(Color::Rgb(0, 255, 0)));
+        assert_eq!(palette.get("2"), Some(Color::Rgb(0, 0, 255)));
+    }
+
+    #[test]
+    fn malformed_gpl_palettes_are_errors() {
+        assert_eq!(error_of(AsciiPalette::from_gpl("255 0 0 Red")), "line 1: expected 'GIMP Palette'");
+        assert_eq!(error_of(AsciiPalette::from_gpl("")), "line 1: expected 'GIMP Palette'");
+        let expected = "line 2: expected a red, green and blue value from 0 to 255";
+        assert_eq!(error_of(AsciiPalette::from_gpl("GIMP Palette\n256 0 0 Red")), expected);
+        assert_eq!(error_of(AsciiPalette::from_gpl("GIMP Palette\n255 0")), expected);
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn ron_palettes_are_parsed() {
+        let palette = AsciiPalette::from_ron("{ \"blood_red\": \"#8a0303\", \"warning\": \"196\" }").unwrap();
+        assert_eq!(palette.get("blood_red"), Some(Color::Rgb(0x8a, 0x03, 0x03)));
+        assert_eq!(palette.get("warning"), Some(Color::from_index(196)));
+        assert!(AsciiPalette::from_ron("{}").unwrap().is_empty());
+
+        assert_eq!(error_of(AsciiPalette::from_ron("{ \"bad\": \"#gg0000\" }")), "bad: '#gg0000' is not a color");
+        assert!(AsciiPalette::from_ron("{ \"unclosed\": ").is_err());
+    }
+}
@@ -0,0 +1,346 @@
+use bevy::prelude::*;
+
+use crate::ascii::AsciiCamera;
+
+use super::{
+    character::{AsciiBlendMode, Character, Color},
+    overlay::{mark_overlay_dirty, AsciiOverlay},
+    time::AsciiUiTime,
+    util::noise_hash,
+    AsciiUiSchedule, AsciiUiSet,
+};
+
+//=============================================================================
+//             Plugin and Systems
+//=============================================================================
+
+pub(crate) struct AsciiParticlesPlugin;
+
+impl Plugin for AsciiParticlesPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .register_type::<AsciiParticleEmitter>()
+            .register_type::<AsciiEmitterPosition>()
+            .add_systems(
+                AsciiUiSchedule,
+                (simulate_particles, draw_particles)
+                    .chain()
+                    .in_set(AsciiUiSet::Layout)
+                    .before(mark_overlay_dirty),
+            )
+        ;
+    }
+}
+
+fn simulate_particles(
+    mut emitters: Query<(&mut AsciiParticleEmitter, Option<&GlobalTransform>)>,
+    cameras: Query<(&Camera, &GlobalTransform, &AsciiCamera)>,
+    time: Res<AsciiUiTime>,
+) {
+    let delta = time.delta_seconds();
+    for (mut emitter, transform) in emitters.iter_mut() {
+        let emitter = emitter.as_mut();
+
+        for particle in emitter.particles.iter_mut() {
+            particle.velocity += emitter.gravity * delta;
+            particle.position += particle.velocity * delta;
+            particle.age += delta;
+        }
+        emitter.particles.retain(|particle| particle.age < particle.lifetime);
+
+        let mut count = std::mem::take(&mut emitter.pending_burst);
+        if emitter.is_emitting && emitter.rate > 0.0 {
+            emitter.spawn_time += delta * emitter.rate;
+            count += emitter.spawn_time as u32;
+            emitter.spawn_time = emitter.spawn_time.fract();
+        }
+        if count == 0 {
+            continue;
+        }
+
+        let origin = match emitter.position {
+            AsciiEmitterPosition::Cell(cell) => Some(cell),
+            AsciiEmitterPosition::Entity => transform.and_then(|transform| {
+                let (camera, camera_transform, ascii_camera) = cameras.get(emitter.camera).ok()?;
+                let position = camera.world_to_viewport(camera_transform, transform.translation())?;
+                let size = camera.logical_viewport_size()?;
+                Some(position / size * *ascii_camera.target_res() / ascii_camera.ui_scale() as f32)
+            }),
+        };
+        let Some(origin) = origin else { continue };
+
+        for _ in 0..count {
+            let lifetime = emitter.lifetime.0 + (emitter.lifetime.1 - emitter.lifetime.0) * emitter.random();
+            let spread = Vec2::new(emitter.random() * 2.0 - 1.0, emitter.random() * 2.0 - 1.0) * emitter.spread;
+            emitter.particles.push(AsciiParticle {
+                position: origin,
+                velocity: emitter.velocity + spread,
+                age: 0.0,
+                lifetime: lifetime.max(f32::EPSILON),
+            });
+        }
+    }
+}
+
+fn draw_particles(
+    emitters: Query<&AsciiParticleEmitter>,
+    overlay: Res<AsciiOverlay>,
+) {
+    for emitter in emitters.iter() {
+        if emitter.particles.is_empty() || emitter.glyphs.is_empty() || emitter.colors.is_empty() {
+            continue;
+        }
+
+        let cells = emitter
+            .particles
+            .iter()
+            .map(|particle| {
+                let age = (particle.age / particle.lifetime).clamp(0.0, 1.0);
+                let glyph = emitter.glyphs[((age * emitter.glyphs.len() as f32) as usize).min(emitter.glyphs.len() - 1)];
+                let color = emitter.colors[((age * emitter.colors.len() as f32) as usize).min(emitter.colors.len() - 1)];
+                (particle.position.floor().as_ivec2(), glyph, color)
+            })
+            .collect::<Vec<_>>();
+        let bg_color = emitter.bg_color;
+        let blend = emitter.blend;
+
+        overlay.draw_on_layer(emitter.camera, emitter.layer, move |buffer| {
+            let buffer = buffer.blend(blend);
+            for (cell, glyph, color) in cells {
+                buffer.set_character(cell.x, cell.y, (glyph, color, bg_color));
+            }
+        });
+    }
+}
+
+//=============================================================================
+//             Particle Emitter
+//=============================================================================
+
+// Where new particles of an `AsciiParticleEmitter` start.
+#[derive(Clone, Copy, Debug, PartialEq, Reflect)]
+pub enum AsciiEmitterPosition {
+    // A cell on the character grid of the camera.
+    Cell(Vec2),
+    // The `GlobalTransform` of the emitter entity, projected through the camera.
+    Entity,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct AsciiParticle {
+    position: Vec2,
+    velocity: Vec2,
+    age: f32,
+    lifetime: f32,
+}
+
+// Spawns particles that move in cell space and are drawn on the overlay of a camera, through
+// `AsciiOverlay`, so moving them does not draw the ui nodes again. Particles go through the
+// glyphs and colors from first to last over their lifetime.
+#[derive(Component, Reflect)]
+pub struct AsciiParticleEmitter {
+    camera: Entity,
+    position: AsciiEmitterPosition,
+    rate: f32,
+    lifetime: (f32, f32),
+    velocity: Vec2,
+    spread: f32,
+    gravity: Vec2,
+    glyphs: Vec<Character>,
+    colors: Vec<Color>,
+    bg_color: Color,
+    blend: AsciiBlendMode,
+    layer: u32,
+    is_emitting: bool,
+    seed: u64,
+    #[reflect(ignore)]
+    particles: Vec<AsciiParticle>,
+    #[reflect(ignore)]
+    spawn_time: f32,
+    #[reflect(ignore)]
+    pending_burst: u32,
+}
+
+impl AsciiParticleEmitter {
+    pub fn new(camera: Entity) -> Self {
+        AsciiParticleEmitter {
+            camera,
+            position: AsciiEmitterPosition::Cell(Vec2::ZERO),
+            rate: 10.0,
+            lifetime: (1.0, 1.0),
+            velocity: Vec2::new(0.0, -5.0),
+            spread: 2.0,
+            gravity: Vec2::ZERO,
+            glyphs: vec![Character::Asterisk, Character::Plus, Character::Period],
+            colors: vec![Color::Yellow, Color::Orange, Color::Red],
+            bg_color: Color::Black,
+            blend: AsciiBlendMode::Replace,
+            layer: AsciiOverlay::LAYER,
+            is_emitting: true,
+            seed: 0,
+            particles: Vec::new(),
+            spawn_time: 0.0,
+            pending_burst: 0,
+        }
+    }
+
+    // Spawns `count` particles at once without emitting over time, like an explosion.
+    pub fn burst(camera: Entity, count: u32) -> Self {
+        AsciiParticleEmitter {
+            rate: 0.0,
+            is_emitting: false,
+            pending_burst: count,
+            ..Self::new(camera)
+        }
+    }
+
+    pub fn at_cell(mut self, x: f32, y: f32) -> Self {
+        self.position = AsciiEmitterPosition::Cell(Vec2::new(x, y));
+        self
+    }
+
+    // Emits from the position of the entity on screen. The entity needs a `GlobalTransform`.
+    pub fn at_entity(mut self) -> Self {
+        self.position = AsciiEmitterPosition::Entity;
+        self
+    }
+
+    // Particles spawned every second.
+    pub fn rate(mut self, rate: f32) -> Self {
+        self.rate = rate.max(0.0);
+        self
+    }
+
+    // Each particle lives a random number of seconds between `min` and `max`.
+    pub fn lifetime(mut self, min: f32, max: f32) -> Self {
+        self.lifetime = (min.min(max), min.max(max));
+        self
+    }
+
+    // The starting velocity in cells per second, +y is down.
+    pub fn velocity(mut self, x: f32, y: f32) -> Self {
+        self.velocity = Vec2::new(x, y);
+        self
+    }
+
+    // A random amount up to this is added to the velocity on both axes.
+    pub fn spread(mut self, spread: f32) -> Self {
+        self.spread = spread.abs();
+        self
+    }
+
+    pub fn gravity(mut self, x: f32, y: f32) -> Self {
+        self.gravity = Vec2::new(x, y);
+        self
+    }
+
+    pub fn glyphs(mut self, glyphs: &str) -> Self {
+        self.glyphs = glyphs.chars().map(Character::from).collect();
+        self
+    }
+
+    pub fn colors(mut self, colors: &[Color]) -> Self {
+        self.colors = colors.to_vec();
+        self
+    }
+
+    pub fn bg_color(mut self, color: Color) -> Self {
+        self.bg_color = color;
+        self
+    }
+
+    pub fn blend(mut self, blend: AsciiBlendMode) -> Self {
+        self.blend = blend;
+        self
+    }
+
+    pub fn layer(mut self, layer: u32) -> Self {
+        self.layer = layer;
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn start(&mut self) {
+        self.is_emitting = true;
+    }
+
+    pub fn stop(&mut self) {
+        self.is_emitting = false;
+    }
+
+    pub fn emit(&mut self, count: u32) {
+        self.pending_burst += count;
+    }
+
+    pub fn particle_count(&self) -> usize {
+        self.particles.len()
+    }
+
+    // Whether the emitter stopped and all of its particles are gone.
+    pub fn is_finished(&self) -> bool {
+        !self.is_emitting && self.pending_burst == 0 && self.particles.is_empty()
+    }
+
+    // A random number from 0.0 up to 1.0. The same seed always gives the same particles.
+    fn random(&mut self) -> f32 {
+        self.seed = noise_hash(self.seed, 0, 0);
+        (self.seed >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ui::{AsciiUi, AsciiUiPlugin};
+
+    use super::*;
+
+    #[derive(Resource, Default)]
+    struct Redraws {
+        full: u32,
+        live: u32,
+    }
+
+    fn count_redraws(ui: Query<&AsciiUi>, mut redraws: ResMut<Redraws>) {
+        for ui in ui.iter() {
+            if ui.is_dirty() {
+                redraws.full += 1;
+            } else if ui.is_live_dirty() {
+                redraws.live += 1;
+            }
+        }
+    }
+
+    #[test]
+    fn particles_only_redraw_the_live_layer() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), bevy::input::InputPlugin, bevy::window::WindowPlugin::default(), AsciiUiPlugin))
+            .init_asset::<Image>()
+            .init_resource::<Redraws>()
+            .add_systems(AsciiUiSchedule, count_redraws.in_set(AsciiUiSet::Extract));
+        let camera = app.world.spawn(AsciiUi::default()).id();
+        app.update();
+        let emitter = app.world.spawn(AsciiParticleEmitter::burst(camera, 10).at_cell(5.0, 5.0).lifetime(1000.0, 1000.0)).id();
+        app.update();
+        assert_eq!(app.world.get::<AsciiParticleEmitter>(emitter).unwrap().particle_count(), 10);
+        *app.world.resource_mut::<Redraws>() = Redraws::default();
+
+        // The particles move every frame, without drawing the ui nodes again.
+        for _ in 0..5 {
+            app.update();
+        }
+        let redraws = app.world.resource::<Redraws>();
+        assert_eq!((redraws.full, redraws.live), (0, 5));
+
+        // Once they are gone the live layer is cleared a last time.
+        app.world.despawn(emitter);
+        for _ in 0..3 {
+            app.update();
+        }
+        let redraws = app.world.resource::<Redraws>();
+        assert_eq!((redraws.full, redraws.live), (0, 6));
+    }
+}
@@ -1,9 +1,9 @@
 use bevy::{prelude::*, utils::HashSet, window::WindowResized};
 
-use crate::ascii::AsciiCamera;
+use crate::ascii::{AsciiCamera, AsciiGridResized};
 
 use super::{
-    bounds::{AsciiBounds, AsciiNode}, util::Value, AsciiMarkDirtyEvent, HorizontalAlignment, Padding, VerticalAlignment
+    bounds::{AsciiBounds, AsciiNode}, flex::{AsciiFlex, AsciiFlexAlign, AsciiFlexDirection, AsciiFlexItem, AsciiFlexJustify}, util::Value, AsciiMarkDirtyEvent, AsciiUiSchedule, AsciiUiSet, HorizontalAlignment, Padding, VerticalAlignment
 };
 
 //=============================================================================
@@ -15,7 +15,14 @@ pub struct AsciiPositionPlugin;
 impl Plugin for AsciiPositionPlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<AsciiPosition>()
-            .add_systems(PostUpdate, (mark_positions_dirty, update_positions).chain());
+            .register_type::<AsciiDesiredSize>()
+            .register_type::<AsciiContentPadding>()
+            .register_type::<AsciiIntrinsicSize>()
+            .register_type::<AsciiFlex>()
+            .register_type::<AsciiFlexDirection>()
+            .register_type::<AsciiFlexJustify>()
+            .register_type::<AsciiFlexAlign>()
+            .add_systems(AsciiUiSchedule, (mark_positions_dirty, update_positions).chain().in_set(AsciiUiSet::Layout));
     }
 }
 
@@ -23,22 +30,32 @@ impl Plugin for AsciiPositionPlugin {
 //            System for Marking Positions Dirty
 //=============================================================================
 
-fn mark_positions_dirty(
+// Marks the nodes whose position has to be worked out again. Their children are not marked, the
+// layout goes on to them on its own when the bounds of a node change.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub(crate) fn mark_positions_dirty(
     mut changed_bounds: Query<(
         Entity,
         &mut AsciiNode,
         Ref<AsciiPosition>,
         Option<Ref<InheritedVisibility>>,
-        Option<&Children>,
     )>,
+    desired_sizes: Query<Entity, Or<(Changed<AsciiDesiredSize>, Changed<AsciiIntrinsicSize>)>>,
+    changed_padding: Query<&Children, Changed<AsciiContentPadding>>,
+    mut removed_padding: RemovedComponents<AsciiContentPadding>,
+    changed_flex: Query<Entity, (With<AsciiFlex>, Or<(Changed<AsciiFlex>, Changed<Children>)>)>,
+    parents: Query<&Parent>,
+    children: Query<&Children>,
     mut ui_rerender_event : EventWriter<AsciiMarkDirtyEvent>,
-    windows_resize: EventReader<WindowResized>
+    mut windows_resize: EventReader<WindowResized>,
+    mut grids_resized: EventReader<AsciiGridResized>,
 ) {
-    let entities = changed_bounds
+    let is_resized = windows_resize.read().count() + grids_resized.read().count() > 0;
+    let mut dirty = changed_bounds
         .iter()
         .filter_map(|value| {
             let v = value.3.map(|value| value.is_changed()).unwrap_or(false);
-            if value.2.is_changed() || v || !windows_resize.is_empty()  {
+            if value.2.is_changed() || v || is_resized || desired_sizes.contains(value.0) {
                 Some(value.0)
             } else {
                 None
@@ -46,22 +63,39 @@ fn mark_positions_dirty(
         })
         .collect::<HashSet<_>>();
 
-    let mut dirty = HashSet::new();
+    // The children of a node are laid out again when the space it keeps around them changes.
+    let padded = removed_padding
+        .read()
+        .filter_map(|entity| children.get(entity).ok())
+        .chain(changed_padding.iter())
+        .flat_map(|children| children.iter().copied())
+        .collect::<Vec<_>>();
+    dirty.extend(padded);
 
-    for entity in entities {
-        let mut children = Vec::new();
-        get_children(entity, &mut children, &changed_bounds);
-        children.into_iter().for_each(|child| {
-            dirty.insert(child);
-        });
-        dirty.insert(entity);
+    // A flex child moves its siblings when it changes, so the whole row is laid out again. The
+    // parent can be the camera, which is not a node itself.
+    let flex_parents = dirty
+        .iter()
+        .filter(|entity| {
+            changed_bounds
+                .get(**entity)
+                .map(|(_, _, position, ..)| matches!(*position, AsciiPosition::Flex { .. }))
+                .unwrap_or(false)
+        })
+        .filter_map(|entity| parents.get(*entity).ok().map(|parent| parent.get()))
+        .chain(changed_flex.iter())
+        .collect::<Vec<_>>();
+    for parent in flex_parents {
+        if let Ok(siblings) = children.get(parent) {
+            dirty.extend(siblings.iter().copied());
+        }
     }
 
     if !dirty.is_empty() {
         ui_rerender_event.send(AsciiMarkDirtyEvent);
     }
     
-    for (entity, mut global_bounds, _, vis, _) in changed_bounds.iter_mut() {
+    for (entity, mut global_bounds, _, _) in changed_bounds.iter_mut() {
         if dirty.contains(&entity) || global_bounds.changed() {
             global_bounds.is_dirty = true;
             global_bounds.clear_changed();
@@ -69,105 +103,204 @@ fn mark_positions_dirty(
     }
 }
 
-fn get_children(
-    current: Entity,
-    children_collection: &mut Vec<Entity>,
-    query: &Query<(
-        Entity,
-        &mut AsciiNode,
-        Ref<AsciiPosition>,
-        Option<Ref<InheritedVisibility>>,
-        Option<&Children>,
-    )>,
+//=============================================================================
+//            System for Updating Positions
+//=============================================================================
+
+type LayoutNodes<'w, 's> = Query<'w, 's, (
+    &'static mut AsciiNode,
+    Option<&'static AsciiPosition>,
+    Option<&'static AsciiDesiredSize>,
+    Option<&'static Children>,
+    Option<&'static AsciiContentPadding>,
+    Option<&'static AsciiIntrinsicSize>,
+)>;
+
+// Lays the ui out from the top down, like the transform propagation of bevy. Every node is
+// visited once, and its bounds are only worked out again when it is dirty or its parent moved.
+pub(crate) fn update_positions(
+    mut nodes: LayoutNodes,
+    roots: Query<(Entity, Option<&Parent>), With<AsciiNode>>,
+    cameras: Query<(&AsciiCamera, Option<&Children>)>,
+    flex_query: Query<&AsciiFlex>,
+    visibilities: Query<&Visibility>,
 ) {
-    let Ok((_, _, _, _, children)) = query.get(current) else {
+    if !nodes.iter().any(|(node, ..)| node.is_dirty) {
         return;
-    };
+    }
 
-    if let Some(children) = children {
-        for child in children.iter() {
-            children_collection.push(*child);
-            get_children(*child, children_collection, query);
+    for (ascii_camera, children) in cameras.iter() {
+        let dims = ascii_camera.ui_res();
+        let bounds = AsciiBounds::from_dims(dims.x as u32, dims.y as u32);
+        if let Some(children) = children {
+            let children = children.iter().copied().collect::<Vec<_>>();
+            layout_children(&children, None, &bounds, false, dims.as_uvec2(), &mut nodes, &flex_query, &visibilities);
         }
     }
+
+    // Nodes that are not under a camera or another node keep the bounds they were given, only the
+    // nodes under them are laid out. Their bounds take the place of the grid for the values
+    // relative to the screen.
+    let other_roots = roots
+        .iter()
+        .filter(|(_, parent)| {
+            parent
+                .map(|parent| !nodes.contains(parent.get()) && !cameras.contains(parent.get()))
+                .unwrap_or(true)
+        })
+        .map(|(entity, _)| entity)
+        .collect::<Vec<_>>();
+    for root in other_roots {
+        let Ok((mut node, _, _, children, padding, _)) = nodes.get_mut(root) else { continue };
+        node.is_dirty = false;
+        let bounds = content_bounds(&node.bounds, padding);
+        let children = children.map(|children| children.iter().copied().collect::<Vec<_>>()).unwrap_or_default();
+        let screen = UVec2::new(node.bounds.width, node.bounds.height);
+        layout_children(&children, flex_query.get(root).ok(), &bounds, false, screen, &mut nodes, &flex_query, &visibilities);
+    }
 }
 
-//=============================================================================
-//            System for Updating Positions
-//=============================================================================
+// The bounds the children of a node are laid out in.
+fn content_bounds(bounds: &AsciiBounds, padding: Option<&AsciiContentPadding>) -> AsciiBounds {
+    match padding {
+        Some(padding) => padding.content_bounds(bounds),
+        None => bounds.clone(),
+    }
+}
 
-fn update_positions(
-    mut bounded_entities: Query<(
-        Entity,
-        &mut AsciiNode,
-        Option<&AsciiPosition>,
-        Option<&Parent>,
-    )>,
-    acsii_cam_query: Query<&AsciiCamera>,
+// Works out the bounds of the children of a node with the given bounds, then goes on to their
+// children. `parent_moved` is true when the bounds of the parent changed this frame, and
+// `screen` is the size of the grid of the camera the nodes are drawn to.
+#[allow(clippy::too_many_arguments)]
+fn layout_children(
+    children: &[Entity],
+    flex: Option<&AsciiFlex>,
+    parent_bounds: &AsciiBounds,
+    parent_moved: bool,
+    screen: UVec2,
+    nodes: &mut LayoutNodes,
+    flex_query: &Query<&AsciiFlex>,
+    visibilities: &Query<&Visibility>,
 ) {
-    let entities_to_update = bounded_entities
-        .iter_mut()
-        .filter_map(|(entity, mut global_bounds, _, _)| {
-            if global_bounds.is_dirty {
-                global_bounds.is_dirty = false;
-                Some(entity)
-            } else {
-                None
-            }
-        })
-        .collect::<Vec<_>>();
-    // let global_bounds = HashMap::new();
-
-    for entity in entities_to_update {
-        let new_global_bounds = get_global_bounds(entity, &bounded_entities, &acsii_cam_query);
-        if let Ok((_, mut global_bounds, _, _)) = bounded_entities.get_mut(entity) {
-            if let Some(new_global_bounds) = new_global_bounds {
-                if new_global_bounds != global_bounds.bounds {
-                    global_bounds.bounds = new_global_bounds;
+    // The flex children are laid out together, as they share the space of the parent.
+    let flex_bounds = flex.map(|flex| flex_layout(children, flex, parent_bounds, nodes, visibilities));
+
+    for child in children {
+        let Ok((mut node, position, desired_size, grandchildren, padding, intrinsic_size)) = nodes.get_mut(*child) else {
+            continue;
+        };
+
+        let mut moved = false;
+        if node.is_dirty || parent_moved {
+            let flex_bound = flex_bounds.as_ref().and_then(|bounds| {
+                bounds.iter().find(|(entity, _)| entity == child).map(|(_, bounds)| bounds.clone())
+            });
+            let position = position.map(|position| position.on_screen(screen));
+            let new_bounds = match (position.as_ref(), flex_bound) {
+                (Some(AsciiPosition::Flex { .. }), Some(bounds)) => Some(bounds),
+                (Some(position), _) => Some(match (desired_size, intrinsic_size) {
+                    (Some(size), _) => position.create_bounds_sized(parent_bounds, size.width, size.height),
+                    (None, Some(size)) => position.with_measured_size(size.width, size.height).create_bounds(parent_bounds),
+                    (None, None) => position.create_bounds(parent_bounds),
+                }),
+                (None, _) => None,
+            };
+            if let Some(new_bounds) = new_bounds {
+                if new_bounds != node.bounds {
+                    node.bounds = new_bounds;
+                    moved = true;
                 }
             }
+            if node.is_dirty {
+                node.is_dirty = false;
+            }
         }
+
+        let bounds = content_bounds(&node.bounds, padding);
+        let Some(grandchildren) = grandchildren.map(|children| children.iter().copied().collect::<Vec<_>>()) else {
+            continue;
+        };
+        layout_children(&grandchildren, flex_query.get(*child).ok(), &bounds, moved, screen, nodes, flex_query, visibilities);
     }
 }
 
-fn get_global_bounds(
-    current: Entity,
-    global_bounds_query: &Query<(
-        Entity,
-        &mut AsciiNode,
-        Option<&AsciiPosition>,
-        Option<&Parent>,
-    )>,
-    acsii_cam_query: &Query<&AsciiCamera>,
-) -> Option<AsciiBounds> {
-    if let Ok(ascii_cam) = acsii_cam_query.get(current) {
-        let dims = ascii_cam.target_res();
-        return Some(AsciiBounds::from_dims(dims.x as u32, dims.y as u32));
-    }
-
-    let Ok((_, global_bounds, local_position, parent)) = global_bounds_query.get(current) else {
-        return None
-    };
-
-    if let Some(parent) = parent {
-        let parent_bounds = get_global_bounds(**parent, global_bounds_query, acsii_cam_query)
-            .unwrap_or(global_bounds.bounds.clone());
-        if let Some(position) = local_position {
-            let new_bound = position.create_bounds(&parent_bounds);
-            Some(new_bound)
-        } else {
-            Some(global_bounds.bounds.clone())
+// The bounds of every flex child of a node. Hidden children take up no space, and get empty
+// bounds as they are not drawn.
+fn flex_layout(
+    children: &[Entity],
+    flex: &AsciiFlex,
+    parent_bounds: &AsciiBounds,
+    nodes: &LayoutNodes,
+    visibilities: &Query<&Visibility>,
+) -> Vec<(Entity, AsciiBounds)> {
+    let mut entities = Vec::new();
+    let mut items = Vec::new();
+    let mut hidden = Vec::new();
+    for child in children {
+        let Ok((_, Some(AsciiPosition::Flex { basis, grow, shrink }), desired_size, ..)) = nodes.get(*child) else {
+            continue;
+        };
+        if visibilities.get(*child) == Ok(&Visibility::Hidden) {
+            hidden.push((*child, AsciiBounds::new(parent_bounds.x, parent_bounds.y, 0, 0, parent_bounds.layer + 1)));
+            continue;
         }
-    } else {
-        Some(global_bounds.bounds.clone())
+        let cross = desired_size.map(|size| match flex.direction {
+            AsciiFlexDirection::Row => size.height,
+            AsciiFlexDirection::Column => size.width,
+        });
+        entities.push(*child);
+        items.push(AsciiFlexItem { basis: *basis, grow: *grow, shrink: *shrink, cross });
     }
+
+    entities.into_iter().zip(flex.layout(parent_bounds, &items)).chain(hidden).collect()
 }
 
 //=============================================================================
 //            AsciiLayouts
 //=============================================================================
 
-#[derive(Component, Reflect)]
+// The size in cells a node would like to be. It is used in place of the width and height of an
+// `Aligned`, `Relative` or `Anchored` position, the other positions ignore it. Widgets that size
+// themselves to their content, like a fitted `AsciiButton`, keep it up to date.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct AsciiDesiredSize {
+    pub width: u32,
+    pub height: u32,
+}
+
+// The size a node needs to show all of its content, like the label of a button. Components
+// report it through `AsciiComponent::measure`, and it is what `Value::Auto` widths and heights
+// of the position of the node come out as.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct AsciiIntrinsicSize {
+    pub width: u32,
+    pub height: u32,
+}
+
+// The cells a node keeps to itself around its children, like the border and title bar of a
+// window. Its children are laid out in the bounds inside of it. Containers get it from
+// `AsciiComponent::content_padding`, any other node can be given one by hand.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct AsciiContentPadding(pub Padding);
+
+impl AsciiContentPadding {
+    // The bounds inside of the padding, which are empty when the padding does not fit.
+    pub fn content_bounds(&self, bounds: &AsciiBounds) -> AsciiBounds {
+        let padding = self.0;
+        let width = bounds.width.saturating_sub(padding.left + padding.right);
+        let height = bounds.height.saturating_sub(padding.top + padding.bottom);
+        if width == 0 || height == 0 {
+            return AsciiBounds::new(bounds.x, bounds.y, 0, 0, bounds.layer);
+        }
+        AsciiBounds::new(bounds.x + padding.left as i32, bounds.y + padding.top as i32, width, height, bounds.layer)
+    }
+}
+
+#[derive(Component, Clone, PartialEq, Debug, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum AsciiPosition {
     Aligned {
         width: Value,
@@ -193,9 +326,29 @@ pub enum AsciiPosition {
         height: Value,
         layer : u32,
     },
+    // Cells on the screen, from the top left of the ui, no matter where the parent is. Add
+    // `AsciiPinned` to also draw outside of a parent that clips its children.
     Absolute {
         bounds: AsciiBounds,
     },
+    // Sits `offset` cells in from a corner or an edge of the parent, and stays there when the
+    // parent is resized. The offset moves away from the sides the node is anchored to, so
+    // `(Right, Bottom)` with an offset of `(3, 1)` is 3 cells from the right and 1 from the
+    // bottom. Centered axes move right and down.
+    Anchored {
+        anchor: (HorizontalAlignment, VerticalAlignment),
+        offset: IVec2,
+        width: Value,
+        height: Value,
+    },
+    // A child of an `AsciiFlex`. It starts out `basis` cells long on the main axis, then takes
+    // the space that is left in proportion to `grow`, or gives space up in proportion to
+    // `shrink` when there is too little. Without an `AsciiFlex` parent it fills the parent.
+    Flex {
+        basis: u32,
+        grow: u32,
+        shrink: u32,
+    },
 }
 
 impl AsciiPosition {
@@ -254,6 +407,12 @@ impl AsciiPosition {
         }
     }
     
+    pub fn padded(padding : impl Into<Padding>) -> Self {
+        AsciiPosition::Padded { 
+            padding: padding.into() 
+        }
+    }
+    
     pub fn fill() -> Self {
         AsciiPosition::Aligned { 
             width: 1.0.into(),
@@ -263,6 +422,41 @@ impl AsciiPosition {
         }
     }
 
+    pub fn absolute(x: i32, y: i32, width: u32, height: u32) -> Self {
+        AsciiPosition::Absolute {
+            bounds: AsciiBounds::new(x, y, width, height, 0),
+        }
+    }
+
+    pub fn anchored(
+        horizontal: HorizontalAlignment,
+        vertical: VerticalAlignment,
+        offset: IVec2,
+        width: impl Into<Value>,
+        height: impl Into<Value>,
+    ) -> Self {
+        AsciiPosition::Anchored {
+            anchor: (horizontal, vertical),
+            offset,
+            width: width.into(),
+            height: height.into(),
+        }
+    }
+
+    pub fn flex(basis: u32, grow: u32, shrink: u32) -> Self {
+        AsciiPosition::Flex { basis, grow, shrink }
+    }
+
+    // A flex child that only takes its share of the space.
+    pub fn grow(grow: u32) -> Self {
+        AsciiPosition::Flex { basis: 0, grow, shrink: 1 }
+    }
+
+    // A flex child that is always `size` cells long, unless there is no room.
+    pub fn fixed(size: u32) -> Self {
+        AsciiPosition::Flex { basis: size, grow: 0, shrink: 1 }
+    }
+
     pub fn aligned(
         width: impl Into<Value>,
         height: impl Into<Value>,
@@ -299,20 +493,29 @@ impl AsciiPosition {
                 total_silces,
                 slice,
             } => {
-                // Self::format_bounds_vertical_slice(*total_silces, *slice, parent_bounds)
-                todo!()
+                Self::format_bounds_vertical_slice(*total_silces, *slice, parent_bounds, child_bounds)
             }
             AsciiPosition::HorizontalSlice {
                 total_silces,
                 slice,
             } => {
-                // Self::create_bounds_horizontal_slice(*total_silces, *slice, parent_bounds)
-                todo!()
+                Self::format_bounds_horizontal_slice(*total_silces, *slice, parent_bounds, child_bounds)
             }
             AsciiPosition::Relative { x, y, width, height, layer } => {
                 Self::format_bounds_relative(*x, *y, *width, *height, *layer, parent_bounds, child_bounds)
             }
-            AsciiPosition::Absolute { bounds } => todo!(),
+            AsciiPosition::Absolute { bounds } => Self::format_bounds_absolute(bounds, parent_bounds, child_bounds),
+            AsciiPosition::Anchored { anchor, offset, width, height } => {
+                Self::format_bounds_anchored(*anchor, *offset, *width, *height, parent_bounds, child_bounds)
+            }
+            AsciiPosition::Flex { .. } => Self::format_bounds_aligned(
+                1.0,
+                1.0,
+                HorizontalAlignment::Left,
+                VerticalAlignment::Top,
+                parent_bounds,
+                child_bounds,
+            ),
         }
     }
 
@@ -327,26 +530,85 @@ impl AsciiPosition {
                 Self::create_bounds_aligned(*width, *height, *horizontal, *vertical, parent_bounds)
             }
             AsciiPosition::Padded { padding } => {
-                todo!()
+                Self::create_bounds_padded(*padding, parent_bounds)
             }
             AsciiPosition::VerticalSlice {
                 total_silces,
                 slice,
             } => {
-                todo!()
+                let mut child = AsciiBounds::default();
+                Self::format_bounds_vertical_slice(*total_silces, *slice, parent_bounds, &mut child);
+                child
             }
             AsciiPosition::HorizontalSlice {
                 total_silces,
                 slice,
             } => {
-                todo!()
+                let mut child = AsciiBounds::default();
+                Self::format_bounds_horizontal_slice(*total_silces, *slice, parent_bounds, &mut child);
+                child
             }
             AsciiPosition::Relative { x, y, width, height, layer  } => {
                 Self::create_bounds_relative(*x, *y, *width, *height, *layer, parent_bounds)
             }
             AsciiPosition::Absolute { bounds } => {
-                todo!()
+                let mut child = AsciiBounds::default();
+                Self::format_bounds_absolute(bounds, parent_bounds, &mut child);
+                child
+            }
+            AsciiPosition::Anchored { anchor, offset, width, height } => {
+                Self::create_bounds_anchored(*anchor, *offset, *width, *height, parent_bounds)
+            }
+            AsciiPosition::Flex { .. } => {
+                Self::create_bounds_aligned(1.0, 1.0, HorizontalAlignment::Left, VerticalAlignment::Top, parent_bounds)
+            }
+        }
+    }
+
+    // The position with its `Value::Auto` width and height replaced by the measured size.
+    pub fn with_measured_size(&self, width: u32, height: u32) -> AsciiPosition {
+        let mut position = self.clone();
+        match &mut position {
+            AsciiPosition::Aligned { width: w, height: h, .. }
+            | AsciiPosition::Relative { width: w, height: h, .. }
+            | AsciiPosition::Anchored { width: w, height: h, .. } => {
+                *w = w.or_measured(width);
+                *h = h.or_measured(height);
             }
+            _ => {}
+        }
+        position
+    }
+
+    // The position with its width and height relative to the grid of the camera in cells.
+    pub fn on_screen(&self, screen: UVec2) -> AsciiPosition {
+        let mut position = self.clone();
+        match &mut position {
+            AsciiPosition::Aligned { width, height, .. }
+            | AsciiPosition::Relative { width, height, .. }
+            | AsciiPosition::Anchored { width, height, .. } => {
+                *width = width.on_screen(screen.x, screen);
+                *height = height.on_screen(screen.y, screen);
+            }
+            _ => {}
+        }
+        position
+    }
+
+    // Same as `create_bounds`, but with the width and height of the position replaced.
+    pub fn create_bounds_sized(&self, parent_bounds: &AsciiBounds, width: u32, height: u32) -> AsciiBounds {
+        let (width, height) = (Value::Px(width as i32), Value::Px(height as i32));
+        match self {
+            AsciiPosition::Aligned { horizontal, vertical, .. } => {
+                Self::create_bounds_aligned(width, height, *horizontal, *vertical, parent_bounds)
+            }
+            AsciiPosition::Relative { x, y, layer, .. } => {
+                Self::create_bounds_relative(*x, *y, width, height, *layer, parent_bounds)
+            }
+            AsciiPosition::Anchored { anchor, offset, .. } => {
+                Self::create_bounds_anchored(*anchor, *offset, width, height, parent_bounds)
+            }
+            _ => self.create_bounds(parent_bounds),
         }
     }
 
@@ -413,31 +675,64 @@ impl AsciiPosition {
             parent_bounds.width as i32 - padding.left as i32 - padding.right as i32;
         let vertical_difference =
             parent_bounds.height as i32 - padding.top as i32 - padding.bottom as i32;
+        child_bounds.layer = parent_bounds.layer + 1;
         if horizontal_difference <= 0 || vertical_difference <= 0 {
+            child_bounds.x = parent_bounds.x;
+            child_bounds.y = parent_bounds.y;
+            child_bounds.width = 0;
+            child_bounds.height = 0;
             return;
         }
-        child_bounds.x = padding.left as i32;
-        child_bounds.y = padding.top as i32;
+        child_bounds.x = parent_bounds.x + padding.left as i32;
+        child_bounds.y = parent_bounds.y + padding.top as i32;
         child_bounds.width = parent_bounds.width - (padding.left + padding.right);
         child_bounds.height = parent_bounds.height - (padding.top + padding.bottom);
     }
 
+    fn create_bounds_padded(padding: impl Into<Padding>, parent_bounds: &AsciiBounds) -> AsciiBounds {
+        let mut child = AsciiBounds::default();
+        Self::format_bounds_padded(padding, parent_bounds, &mut child);
+        child
+    }
+
+    // Where the slice starts and how long it is, when `length` cells are cut into `total_slices`.
+    // The slices that do not divide evenly are spread out, so they differ by one cell at most.
+    fn slice_span(length: u32, total_slices: u32, slice: u32) -> (u32, u32) {
+        let total_slices = total_slices.max(1);
+        let slice = slice.min(total_slices - 1);
+        let start = (length as u64 * slice as u64 / total_slices as u64) as u32;
+        let end = (length as u64 * (slice as u64 + 1) / total_slices as u64) as u32;
+        (start, end - start)
+    }
+
+    // The slices are columns side by side, so the parent is cut vertically.
     fn format_bounds_vertical_slice(
         total_silces: u32,
         slice: u32,
         parent_bounds: &AsciiBounds,
         child_bounds: &mut AsciiBounds,
     ) {
-        todo!()
+        let (start, width) = Self::slice_span(parent_bounds.width, total_silces, slice);
+        child_bounds.x = parent_bounds.x + start as i32;
+        child_bounds.y = parent_bounds.y;
+        child_bounds.width = width;
+        child_bounds.height = parent_bounds.height;
+        child_bounds.layer = parent_bounds.layer + 1;
     }
 
+    // The slices are rows on top of each other, so the parent is cut horizontally.
     fn format_bounds_horizontal_slice(
         total_silces: u32,
         slice: u32,
         parent_bounds: &AsciiBounds,
         child_bounds: &mut AsciiBounds,
     ) {
-        todo!()
+        let (start, height) = Self::slice_span(parent_bounds.height, total_silces, slice);
+        child_bounds.x = parent_bounds.x;
+        child_bounds.y = parent_bounds.y + start as i32;
+        child_bounds.width = parent_bounds.width;
+        child_bounds.height = height;
+        child_bounds.layer = parent_bounds.layer + 1;
     }
 
     fn format_bounds_relative(
@@ -455,9 +750,362 @@ impl AsciiPosition {
         child_bounds.layer = layer + parent_bounds.layer + 1;
     }
 
+    // The bounds are on the screen and do not move with the parent. The layer is still above the
+    // parent, so the node is drawn over it.
+    fn format_bounds_absolute(bounds: &AsciiBounds, parent_bounds: &AsciiBounds, child_bounds: &mut AsciiBounds) {
+        *child_bounds = bounds.clone();
+        child_bounds.layer = bounds.layer + parent_bounds.layer + 1;
+    }
+
+    pub fn format_bounds_anchored(
+        anchor: (HorizontalAlignment, VerticalAlignment),
+        offset: IVec2,
+        width: impl Into<Value>,
+        height: impl Into<Value>,
+        parent_bounds: &AsciiBounds,
+        child_bounds: &mut AsciiBounds,
+    ) {
+        let (horizontal, vertical) = anchor;
+        Self::format_bounds_aligned(width, height, horizontal, vertical, parent_bounds, child_bounds);
+        child_bounds.x += match horizontal {
+            HorizontalAlignment::Left | HorizontalAlignment::Center => offset.x,
+            HorizontalAlignment::Right => -offset.x,
+        };
+        child_bounds.y += match vertical {
+            VerticalAlignment::Top | VerticalAlignment::Center => offset.y,
+            VerticalAlignment::Bottom => -offset.y,
+        };
+    }
+
+    pub fn create_bounds_anchored(
+        anchor: (HorizontalAlignment, VerticalAlignment),
+        offset: IVec2,
+        width: impl Into<Value>,
+        height: impl Into<Value>,
+        parent_bounds: &AsciiBounds,
+    ) -> AsciiBounds {
+        let mut child = AsciiBounds::default();
+        Self::format_bounds_anchored(anchor, offset, width, height, parent_bounds, &mut child);
+        child
+    }
+
     fn create_bounds_relative(x: i32, y : i32, width : impl Into<Value>, height : impl Into<Value>, layer : u32, parent_bounds: &AsciiBounds) -> AsciiBounds {
         let mut child = AsciiBounds::default();
         Self::format_bounds_relative(x, y, width, height, layer, parent_bounds, &mut child);
         child
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+
+    fn node() -> AsciiNode {
+        AsciiNode { is_dirty: true, ..default() }
+    }
+
+    fn bounds_of(world: &World, entity: Entity) -> AsciiBounds {
+        world.get::<AsciiNode>(entity).unwrap().bounds.clone()
+    }
+
+    // A root node at (10, 5), which keeps its bounds, with a padded child inside of it.
+    fn layout_world() -> (World, Entity) {
+        let mut world = World::new();
+        let root = world.spawn(AsciiNode { bounds: AsciiBounds::new(10, 5, 20, 10, 0), ..node() }).id();
+        let panel = world.spawn((node(), AsciiPosition::padded(2))).id();
+        world.entity_mut(root).add_child(panel);
+        (world, panel)
+    }
+
+    #[test]
+    fn absolute_ignores_the_parent_offset() {
+        let (mut world, panel) = layout_world();
+        let hud = world.spawn((node(), AsciiPosition::absolute(1, 2, 4, 3))).id();
+        world.entity_mut(panel).add_child(hud);
+        world.run_system_once(update_positions);
+
+        assert_eq!(bounds_of(&world, panel), AsciiBounds::new(12, 7, 16, 6, 1));
+        let hud = bounds_of(&world, hud);
+        assert_eq!((hud.x, hud.y, hud.width, hud.height), (1, 2, 4, 3));
+    }
+
+    #[test]
+    fn absolute_nodes_stack_above_their_parents() {
+        let (mut world, panel) = layout_world();
+        let hud = world
+            .spawn((node(), AsciiPosition::Absolute { bounds: AsciiBounds::new(0, 0, 8, 2, 5) }))
+            .id();
+        let label = world
+            .spawn((node(), AsciiPosition::Absolute { bounds: AsciiBounds::new(1, 0, 3, 1, 2) }))
+            .id();
+        let sibling = world.spawn((node(), AsciiPosition::fill())).id();
+        world.entity_mut(panel).push_children(&[hud, sibling]);
+        world.entity_mut(hud).add_child(label);
+        world.run_system_once(update_positions);
+
+        // The layer of an absolute node is its own layer on top of the one of its parent.
+        assert_eq!(bounds_of(&world, panel).layer, 1);
+        assert_eq!(bounds_of(&world, hud).layer, 5 + 1 + 1);
+        assert_eq!(bounds_of(&world, label).layer, 2 + 7 + 1);
+        assert_eq!(bounds_of(&world, sibling).layer, 2);
+        // The nested one is not moved by its absolute parent either.
+        assert_eq!((bounds_of(&world, label).x, bounds_of(&world, label).y), (1, 0));
+    }
+
+    fn slices(total_silces: u32, parent: &AsciiBounds, vertical: bool) -> Vec<AsciiBounds> {
+        (0..total_silces)
+            .map(|slice| {
+                let position = if vertical {
+                    AsciiPosition::VerticalSlice { total_silces, slice }
+                } else {
+                    AsciiPosition::HorizontalSlice { total_silces, slice }
+                };
+                position.create_bounds(parent)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn vertical_slices_are_columns_side_by_side() {
+        let parent = AsciiBounds::new(4, 2, 12, 5, 3);
+        let columns = slices(3, &parent, true);
+
+        assert_eq!(columns[0], AsciiBounds::new(4, 2, 4, 5, 4));
+        assert_eq!(columns[1], AsciiBounds::new(8, 2, 4, 5, 4));
+        assert_eq!(columns[2], AsciiBounds::new(12, 2, 4, 5, 4));
+    }
+
+    #[test]
+    fn horizontal_slices_are_rows_on_top_of_each_other() {
+        let parent = AsciiBounds::new(4, 2, 12, 6, 0);
+        let rows = slices(2, &parent, false);
+
+        assert_eq!(rows[0], AsciiBounds::new(4, 2, 12, 3, 1));
+        assert_eq!(rows[1], AsciiBounds::new(4, 5, 12, 3, 1));
+    }
+
+    #[test]
+    fn uneven_slices_cover_the_parent_without_gaps() {
+        let parent = AsciiBounds::new(0, 0, 10, 1, 0);
+        let columns = slices(3, &parent, true);
+
+        // The cells left over are spread out, the slices differ by one cell at most.
+        let widths = columns.iter().map(|column| column.width).collect::<Vec<_>>();
+        assert_eq!(widths, vec![3, 3, 4]);
+        for pair in columns.windows(2) {
+            assert_eq!(pair[0].right(), pair[1].x);
+        }
+        assert_eq!(columns.last().unwrap().right(), parent.right());
+    }
+
+    #[test]
+    fn slices_out_of_range_are_clamped() {
+        let parent = AsciiBounds::new(0, 0, 9, 4, 0);
+
+        // No slices at all is one slice of the whole parent.
+        let whole = AsciiPosition::VerticalSlice { total_silces: 0, slice: 0 }.create_bounds(&parent);
+        assert_eq!((whole.x, whole.width), (0, 9));
+        // A slice past the end is the last one.
+        let last = AsciiPosition::HorizontalSlice { total_silces: 2, slice: 5 }.create_bounds(&parent);
+        assert_eq!((last.y, last.height), (2, 2));
+    }
+
+    #[test]
+    fn slices_are_laid_out_under_their_parent() {
+        let mut world = World::new();
+        let root = world.spawn(AsciiNode { bounds: AsciiBounds::new(0, 0, 20, 8, 0), ..node() }).id();
+        let left = world.spawn((node(), AsciiPosition::VerticalSlice { total_silces: 2, slice: 0 })).id();
+        let right = world.spawn((node(), AsciiPosition::VerticalSlice { total_silces: 2, slice: 1 })).id();
+        world.entity_mut(root).push_children(&[left, right]);
+        world.run_system_once(update_positions);
+
+        assert_eq!(bounds_of(&world, left), AsciiBounds::new(0, 0, 10, 8, 1));
+        assert_eq!(bounds_of(&world, right), AsciiBounds::new(10, 0, 10, 8, 1));
+    }
+
+    fn anchored(horizontal: HorizontalAlignment, vertical: VerticalAlignment, offset: IVec2, parent: &AsciiBounds) -> (i32, i32) {
+        let bounds = AsciiPosition::anchored(horizontal, vertical, offset, 6, 2).create_bounds(parent);
+        assert_eq!((bounds.width, bounds.height, bounds.layer), (6, 2, parent.layer + 1));
+        (bounds.x, bounds.y)
+    }
+
+    #[test]
+    fn anchored_without_an_offset_sits_in_the_corner() {
+        use HorizontalAlignment as H;
+        use VerticalAlignment as V;
+        let parent = AsciiBounds::new(4, 2, 20, 10, 1);
+
+        assert_eq!(anchored(H::Left, V::Top, IVec2::ZERO, &parent), (4, 2));
+        assert_eq!(anchored(H::Right, V::Top, IVec2::ZERO, &parent), (18, 2));
+        assert_eq!(anchored(H::Left, V::Bottom, IVec2::ZERO, &parent), (4, 10));
+        assert_eq!(anchored(H::Right, V::Bottom, IVec2::ZERO, &parent), (18, 10));
+        assert_eq!(anchored(H::Center, V::Center, IVec2::ZERO, &parent), (11, 6));
+    }
+
+    #[test]
+    fn anchored_offsets_move_away_from_the_anchored_sides() {
+        use HorizontalAlignment as H;
+        use VerticalAlignment as V;
+        let parent = AsciiBounds::new(4, 2, 20, 10, 1);
+        let offset = IVec2::new(3, 1);
+
+        assert_eq!(anchored(H::Left, V::Top, offset, &parent), (7, 3));
+        assert_eq!(anchored(H::Right, V::Top, offset, &parent), (15, 3));
+        assert_eq!(anchored(H::Left, V::Bottom, offset, &parent), (7, 9));
+        assert_eq!(anchored(H::Right, V::Bottom, offset, &parent), (15, 9));
+        // Centered axes move right and down.
+        assert_eq!(anchored(H::Center, V::Center, offset, &parent), (14, 7));
+        // A negative offset moves out of the parent.
+        assert_eq!(anchored(H::Right, V::Bottom, IVec2::new(-1, -2), &parent), (19, 12));
+
+        // The node keeps its distance to the corner when the parent grows.
+        let wider = AsciiBounds::new(4, 2, 30, 12, 1);
+        assert_eq!(anchored(H::Right, V::Bottom, offset, &wider), (25, 11));
+    }
+
+    #[test]
+    fn anchored_nodes_are_laid_out_under_their_parent() {
+        let (mut world, panel) = layout_world();
+        let badge = world
+            .spawn((node(), AsciiPosition::anchored(HorizontalAlignment::Right, VerticalAlignment::Bottom, IVec2::new(1, 1), 4, 2)))
+            .id();
+        world.entity_mut(panel).add_child(badge);
+        world.run_system_once(update_positions);
+
+        assert_eq!(bounds_of(&world, panel), AsciiBounds::new(12, 7, 16, 6, 1));
+        assert_eq!(bounds_of(&world, badge), AsciiBounds::new(23, 10, 4, 2, 2));
+    }
+
+    // A flex row of two halves inside of a padded panel, with nodes nested under both halves.
+    struct Tree {
+        panel: Entity,
+        left: Entity,
+        label: Entity,
+        right: Entity,
+        inner: Entity,
+        leaf: Entity,
+    }
+
+    fn nested_world() -> (World, Tree) {
+        let mut world = World::new();
+        let root = world.spawn(AsciiNode { bounds: AsciiBounds::new(0, 0, 40, 20, 0), ..node() }).id();
+        let panel = world.spawn((node(), AsciiPosition::padded(2), AsciiFlex::row())).id();
+        let left = world.spawn((node(), AsciiPosition::flex(0, 1, 1))).id();
+        let label = world.spawn((node(), AsciiPosition::padded(1))).id();
+        let right = world.spawn((node(), AsciiPosition::flex(0, 1, 1))).id();
+        let inner = world.spawn((node(), AsciiPosition::padded(1))).id();
+        let leaf = world.spawn((node(), AsciiPosition::padded(1))).id();
+        world.entity_mut(root).add_child(panel);
+        world.entity_mut(panel).push_children(&[left, right]);
+        world.entity_mut(left).add_child(label);
+        world.entity_mut(right).add_child(inner);
+        world.entity_mut(inner).add_child(leaf);
+        (world, Tree { panel, left, label, right, inner, leaf })
+    }
+
+    fn is_changed(world: &World, entity: Entity) -> bool {
+        world.entity(entity).get_ref::<AsciiNode>().unwrap().is_changed()
+    }
+
+    #[test]
+    fn nested_layouts_are_laid_out_in_one_frame() {
+        let (mut world, tree) = nested_world();
+        world.run_system_once(update_positions);
+
+        assert_eq!(bounds_of(&world, tree.panel), AsciiBounds::new(2, 2, 36, 16, 1));
+        assert_eq!(bounds_of(&world, tree.left), AsciiBounds::new(2, 2, 18, 16, 2));
+        assert_eq!(bounds_of(&world, tree.label), AsciiBounds::new(3, 3, 16, 14, 3));
+        assert_eq!(bounds_of(&world, tree.right), AsciiBounds::new(20, 2, 18, 16, 2));
+        assert_eq!(bounds_of(&world, tree.inner), AsciiBounds::new(21, 3, 16, 14, 3));
+        assert_eq!(bounds_of(&world, tree.leaf), AsciiBounds::new(22, 4, 14, 12, 4));
+        assert!(world.query::<&AsciiNode>().iter(&world).all(|node| !node.is_dirty));
+    }
+
+    #[test]
+    fn changes_deep_in_the_tree_move_only_the_nodes_under_them() {
+        let (mut world, tree) = nested_world();
+        world.run_system_once(update_positions);
+        world.clear_trackers();
+
+        // Like `mark_positions_dirty` does when the position changes.
+        world.entity_mut(tree.inner).insert(AsciiPosition::padded(3));
+        world.get_mut::<AsciiNode>(tree.inner).unwrap().is_dirty = true;
+        world.run_system_once(update_positions);
+
+        assert_eq!(bounds_of(&world, tree.inner), AsciiBounds::new(23, 5, 12, 10, 3));
+        // The leaf is not dirty itself, it follows its parent.
+        assert_eq!(bounds_of(&world, tree.leaf), AsciiBounds::new(24, 6, 10, 8, 4));
+        assert!(is_changed(&world, tree.leaf));
+        for entity in [tree.panel, tree.left, tree.label, tree.right] {
+            assert!(!is_changed(&world, entity));
+        }
+
+        world.clear_trackers();
+        world.entity_mut(tree.leaf).insert(AsciiPosition::padded(0));
+        world.get_mut::<AsciiNode>(tree.leaf).unwrap().is_dirty = true;
+        world.run_system_once(update_positions);
+
+        assert_eq!(bounds_of(&world, tree.leaf), AsciiBounds::new(23, 5, 12, 10, 4));
+        for entity in [tree.panel, tree.left, tree.label, tree.right, tree.inner] {
+            assert!(!is_changed(&world, entity));
+        }
+    }
+
+    #[test]
+    fn nothing_is_laid_out_without_a_dirty_node() {
+        let (mut world, tree) = nested_world();
+        world.run_system_once(update_positions);
+        world.clear_trackers();
+
+        world.run_system_once(update_positions);
+        for entity in [tree.panel, tree.left, tree.label, tree.right, tree.inner, tree.leaf] {
+            assert!(!is_changed(&world, entity));
+        }
+    }
+
+    #[test]
+    fn positions_on_screen_replace_screen_values_with_cells() {
+        let screen = UVec2::new(80, 24);
+        let centered = AsciiPosition::centered(Value::ScreenPercent(0.5), Value::Vmin(0.5));
+        assert_eq!(centered.on_screen(screen), AsciiPosition::centered(40, 12));
+        let relative = AsciiPosition::relative(1, 2, Value::Vmax(0.1), 0.5, 3);
+        assert_eq!(relative.on_screen(screen), AsciiPosition::relative(1, 2, 8, 0.5, 3));
+        let anchored = AsciiPosition::anchored(
+            HorizontalAlignment::Right,
+            VerticalAlignment::Bottom,
+            IVec2::ONE,
+            Value::ScreenPercent(1.0),
+            Value::ScreenPercent(-0.5),
+        );
+        let expected = AsciiPosition::anchored(HorizontalAlignment::Right, VerticalAlignment::Bottom, IVec2::ONE, 80, -12);
+        assert_eq!(anchored.on_screen(screen), expected);
+
+        // Positions without a width and height stay as they are.
+        for position in [AsciiPosition::padded(2), AsciiPosition::absolute(1, 2, 3, 4), AsciiPosition::flex(1, 1, 1)] {
+            assert_eq!(position.on_screen(screen), position);
+        }
+    }
+
+    #[test]
+    fn screen_values_are_laid_out_against_the_screen() {
+        let mut world = World::new();
+        let root = world.spawn(AsciiNode { bounds: AsciiBounds::new(0, 0, 40, 20, 0), ..node() }).id();
+        let panel = world.spawn((node(), AsciiPosition::padded(2))).id();
+        let half = world.spawn((node(), AsciiPosition::centered(Value::ScreenPercent(0.5), Value::ScreenPercent(0.5)))).id();
+        let wide = world.spawn((node(), AsciiPosition::centered(Value::ScreenPercent(2.0), 1))).id();
+        let negative = world
+            .spawn((node(), AsciiPosition::anchored(HorizontalAlignment::Right, VerticalAlignment::Bottom, IVec2::ZERO, Value::ScreenPercent(-0.5), 1)))
+            .id();
+        world.entity_mut(root).add_child(panel);
+        world.entity_mut(panel).push_children(&[half, wide, negative]);
+        world.run_system_once(update_positions);
+
+        // Half of the 40 by 20 root, not of the panel inside of it.
+        assert_eq!(bounds_of(&world, half), AsciiBounds::new(10, 5, 20, 10, 2));
+        // Too wide is as wide as the parent, and a negative width is empty at the edge.
+        assert_eq!(bounds_of(&world, wide), AsciiBounds::new(2, 9, 36, 1, 2));
+        assert_eq!(bounds_of(&world, negative), AsciiBounds::new(38, 17, 0, 1, 2));
+    }
+}
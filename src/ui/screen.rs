@@ -0,0 +1,162 @@
+use std::marker::PhantomData;
+
+use bevy::prelude::*;
+
+use super::{
+    command::AsciiUiCommandExtention,
+    transition::{update_transitions, AsciiTransitionEffect, AsciiTransitionEvent, AsciiTransitionFinishedEvent},
+    AsciiUiSet,
+};
+
+//=============================================================================
+//             Plugin and Systems
+//=============================================================================
+
+// Shows the ui roots with an `AsciiScreen<S>` while the state is theirs, and hides or despawns
+// them when it is left. One is needed for every states type that has screens.
+pub struct AsciiScreenPlugin<S: States>(PhantomData<S>);
+
+impl<S: States> Default for AsciiScreenPlugin<S> {
+    fn default() -> Self {
+        AsciiScreenPlugin(PhantomData)
+    }
+}
+
+impl<S: States> Plugin for AsciiScreenPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app
+            .register_type::<AsciiScreenMode>()
+            // Before the transitions, so the ones started here run the same frame.
+            .add_systems(
+                PostUpdate,
+                update_screens::<S>
+                    .in_set(AsciiUiSet::Propagate)
+                    .before(update_transitions),
+            )
+        ;
+    }
+}
+
+//=============================================================================
+//             Ascii Screen
+//=============================================================================
+
+// Ties a ui root to a value of the state `S`. Screens can be spawned once and kept around, they
+// are then hidden while the state is something else. Screens that are spawned in `OnEnter` should
+// use `despawn_on_exit` instead, so a new one is not stacked on top every time.
+#[derive(Component, Clone, Debug, PartialEq)]
+pub struct AsciiScreen<S: States> {
+    state: S,
+    mode: AsciiScreenMode,
+    // The transition from the screen that was left to this one, and how long it takes.
+    transition: Option<(AsciiTransitionEffect, f32)>,
+}
+
+// What happens to a screen when its state is left.
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug, Reflect)]
+pub enum AsciiScreenMode {
+    #[default]
+    Hide,
+    Despawn,
+}
+
+impl<S: States> AsciiScreen<S> {
+    pub fn new(state: S) -> Self {
+        AsciiScreen {
+            state,
+            mode: AsciiScreenMode::default(),
+            transition: None,
+        }
+    }
+
+    pub fn despawn_on_exit(mut self) -> Self {
+        self.mode = AsciiScreenMode::Despawn;
+        self
+    }
+
+    // Takes over the screen of the state that was left with a transition when this screen is
+    // entered, instead of swapping them right away.
+    pub fn transition(mut self, effect: AsciiTransitionEffect, duration: f32) -> Self {
+        self.transition = Some((effect, duration));
+        self
+    }
+
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    pub fn mode(&self) -> AsciiScreenMode {
+        self.mode
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn update_screens<S: States>(
+    mut commands: Commands,
+    state: Option<Res<State<S>>>,
+    mut changes: EventReader<StateTransitionEvent<S>>,
+    mut finished: EventReader<AsciiTransitionFinishedEvent>,
+    mut transitions: EventWriter<AsciiTransitionEvent>,
+    added: Query<(Entity, &AsciiScreen<S>), Added<AsciiScreen<S>>>,
+    screens: Query<(Entity, &AsciiScreen<S>)>,
+    mut visibilities: Query<&mut Visibility>,
+    // Screens that are despawned once the transition away from them is done.
+    mut leaving: Local<Vec<Entity>>,
+) {
+    for event in finished.read() {
+        if let Some(index) = leaving.iter().position(|entity| *entity == event.from) {
+            commands.despawn_ascii_tree(leaving.swap_remove(index));
+        }
+    }
+
+    let Some(state) = state else {
+        return;
+    };
+
+    let mut set_visibility = |entity: Entity, visibility: Visibility| {
+        if let Ok(mut current) = visibilities.get_mut(entity) {
+            if *current != visibility {
+                *current = visibility;
+            }
+        }
+    };
+
+    for (entity, screen) in added.iter() {
+        let visibility = if screen.state == *state.get() { Visibility::Inherited } else { Visibility::Hidden };
+        set_visibility(entity, visibility);
+    }
+
+    for change in changes.read() {
+        let mut outgoing = screens
+            .iter()
+            .filter(|(entity, screen)| screen.state == change.before && !leaving.contains(entity))
+            .collect::<Vec<_>>();
+        let incoming = screens
+            .iter()
+            .filter(|(_, screen)| screen.state == change.after)
+            .collect::<Vec<_>>();
+
+        for (entity, screen) in incoming {
+            set_visibility(entity, Visibility::Inherited);
+            let Some((effect, duration)) = screen.transition else {
+                continue;
+            };
+            // Every screen that is entered takes over one of the screens that were left.
+            if outgoing.is_empty() {
+                continue;
+            }
+            let (from, from_screen) = outgoing.remove(0);
+            transitions.send(AsciiTransitionEvent::new(from, entity, effect).duration(duration));
+            if from_screen.mode == AsciiScreenMode::Despawn {
+                leaving.push(from);
+            }
+        }
+
+        for (entity, screen) in outgoing {
+            match screen.mode {
+                AsciiScreenMode::Hide => set_visibility(entity, Visibility::Hidden),
+                AsciiScreenMode::Despawn => commands.despawn_ascii_tree(entity),
+            }
+        }
+    }
+}
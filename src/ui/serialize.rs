@@ -0,0 +1,276 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    bounds::AsciiNode,
+    character::AsciiBlendMode,
+    component::{
+        autocomplete::AsciiAutocomplete, button::AsciiButton, console::AsciiConsole, minimap::AsciiMinimap, noise::AsciiNoisePanel,
+        list::AsciiList, number::AsciiNumber,
+        rich_text::AsciiRichText, segmented::AsciiSegmented, slider::AsciiSlider, spinner::AsciiSpinner, tabs::AsciiTabs,
+        text_input::AsciiTextInput, tilemap::AsciiTilemap, toggle::AsciiToggle, virtual_keyboard::AsciiVirtualKeyboard,
+        window::AsciiWindow,
+    },
+    flex::AsciiFlex,
+    opacity::{AsciiFade, AsciiOpacity},
+    palette::AsciiPalette,
+    position::AsciiPosition,
+    util::AsciiHotkey,
+};
+
+//=============================================================================
+//             Ascii Ui Snapshot
+//=============================================================================
+
+// A copy of the ui tree under a camera that can be saved to RON and spawned again later, like the
+// window layout of an in game desktop. Only the settings of the widgets are kept, things like
+// hovering and focus start over when the snapshot is spawned. The named colors are kept as the
+// theme, so the ui looks the same in a run where the palettes were not loaded.
+#[derive(Serialize, Deserialize, Default)]
+pub struct AsciiUiSnapshot {
+    pub nodes: Vec<AsciiUiSnapshotNode>,
+    #[serde(default)]
+    pub theme: AsciiPalette,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AsciiUiSnapshotNode {
+    #[serde(default)]
+    pub name: Option<String>,
+    pub position: AsciiPosition,
+    // Nodes without a widget the snapshot knows, like plain containers and custom components,
+    // keep their place in the tree without the widget.
+    #[serde(default)]
+    pub widget: Option<AsciiWidgetSnapshot>,
+    #[serde(default)]
+    pub is_hidden: bool,
+    #[serde(default)]
+    pub opacity: Option<AsciiOpacity>,
+    #[serde(default)]
+    pub fade: Option<AsciiFade>,
+    #[serde(default)]
+    pub blend: Option<AsciiBlendMode>,
+    #[serde(default)]
+    pub hotkey: Option<AsciiHotkey>,
+    #[serde(default)]
+    pub flex: Option<AsciiFlex>,
+    #[serde(default)]
+    pub children: Vec<AsciiUiSnapshotNode>,
+}
+
+// The built in widgets a snapshot knows about.
+#[derive(Serialize, Deserialize)]
+pub enum AsciiWidgetSnapshot {
+    Button(AsciiButton),
+    Window(AsciiWindow),
+    Slider(AsciiSlider),
+    Spinner(AsciiSpinner),
+    Tabs(AsciiTabs),
+    Toggle(AsciiToggle),
+    Segmented(AsciiSegmented),
+    Number(AsciiNumber),
+    List(AsciiList),
+    TextInput(AsciiTextInput),
+    Console(AsciiConsole),
+    Autocomplete(AsciiAutocomplete),
+    Minimap(AsciiMinimap),
+    NoisePanel(AsciiNoisePanel),
+    Tilemap(AsciiTilemap),
+    RichText(AsciiRichText),
+    VirtualKeyboard(AsciiVirtualKeyboard),
+}
+
+impl AsciiUiSnapshot {
+    // Copies every ui node under `root`, which is usually the camera, and the named colors.
+    pub fn capture(world: &World, root: Entity) -> Self {
+        AsciiUiSnapshot {
+            nodes: capture_children(world, root),
+            theme: AsciiPalette::registered(),
+        }
+    }
+
+    // Registers the theme and spawns the nodes as children of `parent`, returning the top level
+    // entities.
+    pub fn spawn(self, commands: &mut Commands, parent: Entity) -> Vec<Entity> {
+        self.theme.register();
+        self.nodes
+            .into_iter()
+            .map(|node| node.spawn(commands, parent))
+            .collect()
+    }
+
+    pub fn to_ron(&self) -> Result<String, ron::Error> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+    }
+
+    pub fn from_ron(ron: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str(ron)
+    }
+}
+
+impl AsciiUiSnapshotNode {
+    fn spawn(self, commands: &mut Commands, parent: Entity) -> Entity {
+        let mut entity = commands.spawn((self.position, AsciiNode::default(), VisibilityBundle::default()));
+        if let Some(widget) = self.widget {
+            match widget {
+                AsciiWidgetSnapshot::Button(widget) => entity.insert(widget),
+                AsciiWidgetSnapshot::Window(widget) => entity.insert(widget),
+                AsciiWidgetSnapshot::Slider(widget) => entity.insert(widget),
+                AsciiWidgetSnapshot::Spinner(widget) => entity.insert(widget),
+                AsciiWidgetSnapshot::Tabs(widget) => entity.insert(widget),
+                AsciiWidgetSnapshot::Toggle(widget) => entity.insert(widget),
+                AsciiWidgetSnapshot::Segmented(widget) => entity.insert(widget),
+                AsciiWidgetSnapshot::Number(widget) => entity.insert(widget),
+                AsciiWidgetSnapshot::List(widget) => entity.insert(widget),
+                AsciiWidgetSnapshot::TextInput(widget) => entity.insert(widget),
+                AsciiWidgetSnapshot::Console(widget) => entity.insert(widget),
+                AsciiWidgetSnapshot::Autocomplete(widget) => entity.insert(widget),
+                AsciiWidgetSnapshot::Minimap(widget) => entity.insert(widget),
+                AsciiWidgetSnapshot::NoisePanel(widget) => entity.insert(widget),
+                AsciiWidgetSnapshot::Tilemap(widget) => entity.insert(widget),
+                AsciiWidgetSnapshot::RichText(widget) => entity.insert(widget),
+                AsciiWidgetSnapshot::VirtualKeyboard(widget) => entity.insert(widget),
+            };
+        }
+
+        if let Some(name) = self.name {
+            entity.insert(Name::new(name));
+        }
+        if self.is_hidden {
+            entity.insert(Visibility::Hidden);
+        }
+        if let Some(opacity) = self.opacity {
+            entity.insert(opacity);
+        }
+        if let Some(fade) = self.fade {
+            entity.insert(fade);
+        }
+        if let Some(blend) = self.blend {
+            entity.insert(blend);
+        }
+        if let Some(hotkey) = self.hotkey {
+            entity.insert(hotkey);
+        }
+        if let Some(flex) = self.flex {
+            entity.insert(flex);
+        }
+
+        let entity = entity.id();
+        commands.entity(parent).add_child(entity);
+        for child in self.children {
+            child.spawn(commands, entity);
+        }
+        entity
+    }
+}
+
+fn capture_children(world: &World, parent: Entity) -> Vec<AsciiUiSnapshotNode> {
+    let Some(children) = world.get::<Children>(parent) else {
+        return Vec::new();
+    };
+    children
+        .iter()
+        .filter_map(|child| capture_node(world, *child))
+        .collect()
+}
+
+fn capture_node(world: &World, entity: Entity) -> Option<AsciiUiSnapshotNode> {
+    let position = copy::<AsciiPosition>(world, entity)?;
+    let widget = capture_widget(world, entity);
+
+    Some(AsciiUiSnapshotNode {
+        name: world.get::<Name>(entity).map(|name| name.to_string()),
+        position,
+        widget,
+        is_hidden: world.get::<Visibility>(entity) == Some(&Visibility::Hidden),
+        opacity: world.get::<AsciiOpacity>(entity).copied(),
+        fade: world.get::<AsciiFade>(entity).copied(),
+        blend: world.get::<AsciiBlendMode>(entity).copied(),
+        hotkey: world.get::<AsciiHotkey>(entity).copied(),
+        flex: world.get::<AsciiFlex>(entity).copied(),
+        children: capture_children(world, entity),
+    })
+}
+
+fn capture_widget(world: &World, entity: Entity) -> Option<AsciiWidgetSnapshot> {
+    copy(world, entity)
+        .map(AsciiWidgetSnapshot::Button)
+        .or_else(|| copy(world, entity).map(AsciiWidgetSnapshot::Window))
+        .or_else(|| copy(world, entity).map(AsciiWidgetSnapshot::Slider))
+        .or_else(|| copy(world, entity).map(AsciiWidgetSnapshot::Spinner))
+        .or_else(|| copy(world, entity).map(AsciiWidgetSnapshot::Tabs))
+        .or_else(|| copy(world, entity).map(AsciiWidgetSnapshot::Toggle))
+        .or_else(|| copy(world, entity).map(AsciiWidgetSnapshot::Segmented))
+        .or_else(|| copy(world, entity).map(AsciiWidgetSnapshot::Number))
+        .or_else(|| copy(world, entity).map(AsciiWidgetSnapshot::List))
+        .or_else(|| copy(world, entity).map(AsciiWidgetSnapshot::TextInput))
+        .or_else(|| copy(world, entity).map(AsciiWidgetSnapshot::Console))
+        .or_else(|| copy(world, entity).map(AsciiWidgetSnapshot::Autocomplete))
+        .or_else(|| copy(world, entity).map(AsciiWidgetSnapshot::Minimap))
+        .or_else(|| copy(world, entity).map(AsciiWidgetSnapshot::NoisePanel))
+        .or_else(|| copy(world, entity).map(AsciiWidgetSnapshot::Tilemap))
+        .or_else(|| copy(world, entity).map(AsciiWidgetSnapshot::RichText))
+        .or_else(|| copy(world, entity).map(AsciiWidgetSnapshot::VirtualKeyboard))
+}
+
+// The widgets are not `Clone` because of their caches, so they are copied through reflection.
+// Fields that are not reflected start out empty.
+fn copy<C: Component + FromReflect>(world: &World, entity: Entity) -> Option<C> {
+    world.get::<C>(entity).and_then(|component| C::from_reflect(component))
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::CommandQueue;
+
+    use crate::ui::{character::Color, palette::register_color};
+
+    use super::*;
+
+    fn spawn_node(world: &mut World, parent: Entity, node: impl Bundle) -> Entity {
+        let entity = world.spawn((AsciiPosition::relative(1, 1, 10, 5, 0), node)).id();
+        world.entity_mut(parent).add_child(entity);
+        entity
+    }
+
+    fn nodes_ron(snapshot: &AsciiUiSnapshot) -> String {
+        ron::to_string(&snapshot.nodes).unwrap()
+    }
+
+    #[test]
+    fn nested_trees_survive_a_round_trip_through_ron() {
+        register_color("snapshot_ink", Color::Rgb(1, 2, 3));
+
+        let mut world = World::new();
+        let root = world.spawn_empty().id();
+        let window = spawn_node(&mut world, root, (AsciiWindow::new("Files"), Name::new("files")));
+        spawn_node(&mut world, window, (AsciiButton::from_string("Open"), AsciiHotkey(KeyCode::KeyO)));
+        // A plain container between the window and a slider.
+        let group = spawn_node(&mut world, window, Visibility::Hidden);
+        spawn_node(&mut world, group, AsciiSlider::new(0.5, 0.0, 1.0));
+
+        let snapshot = AsciiUiSnapshot::capture(&world, root);
+        assert_eq!(snapshot.nodes.len(), 1);
+        let window_node = &snapshot.nodes[0];
+        assert_eq!(window_node.name.as_deref(), Some("files"));
+        assert_eq!(window_node.children.len(), 2);
+        assert!(window_node.children[1].widget.is_none());
+        assert!(window_node.children[1].is_hidden);
+        assert!(matches!(window_node.children[1].children[0].widget, Some(AsciiWidgetSnapshot::Slider(_))));
+        assert_eq!(snapshot.theme.get("snapshot_ink"), Some(Color::Rgb(1, 2, 3)));
+
+        let ron = snapshot.to_ron().unwrap();
+        let loaded = AsciiUiSnapshot::from_ron(&ron).unwrap();
+        assert_eq!(nodes_ron(&loaded), nodes_ron(&snapshot));
+        assert_eq!(loaded.theme.get("snapshot_ink"), Some(Color::Rgb(1, 2, 3)));
+
+        // Spawning the loaded snapshot gives the same tree again.
+        let mut spawned = World::new();
+        let root = spawned.spawn_empty().id();
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &spawned);
+        loaded.spawn(&mut commands, root);
+        queue.apply(&mut spawned);
+        assert_eq!(nodes_ron(&AsciiUiSnapshot::capture(&spawned, root)), nodes_ron(&snapshot));
+    }
+}
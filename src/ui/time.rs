@@ -0,0 +1,127 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeSystem};
+
+//=============================================================================
+//             Plugin and Systems
+//=============================================================================
+
+pub(crate) struct AsciiUiTimePlugin;
+
+impl Plugin for AsciiUiTimePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<AsciiUiTime>()
+            .register_type::<AsciiUiTimeSource>()
+            .add_systems(First, update_ui_time.after(TimeSystem))
+        ;
+    }
+}
+
+fn update_ui_time(mut ui_time: ResMut<AsciiUiTime>, real: Res<Time<Real>>, virtual_time: Res<Time<Virtual>>) {
+    let source = match ui_time.source {
+        AsciiUiTimeSource::Real => real.delta(),
+        AsciiUiTimeSource::Virtual => virtual_time.delta(),
+    };
+    ui_time.advance(source);
+}
+
+//=============================================================================
+//             Ascii Ui Time
+//=============================================================================
+
+// The clock the game time of the ui follows.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Reflect)]
+pub enum AsciiUiTimeSource {
+    // Keeps going while `Time<Virtual>` is paused, so menus over a paused game still animate.
+    #[default]
+    Real,
+    // Pauses and speeds up with the game.
+    Virtual,
+}
+
+// The clock every animation of the ui runs on, like marquees, fades, transitions, particles and
+// redraw timers. It can be paused on its own, to freeze the ui for a screenshot, and stepped a
+// frame at a time while it is.
+#[derive(Resource, Clone, Debug)]
+pub struct AsciiUiTime {
+    source: AsciiUiTimeSource,
+    paused: bool,
+    relative_speed: f32,
+    // Added to the next frame, even when paused.
+    pending_step: Duration,
+    delta: Duration,
+    elapsed: Duration,
+}
+
+impl Default for AsciiUiTime {
+    fn default() -> Self {
+        AsciiUiTime {
+            source: AsciiUiTimeSource::default(),
+            paused: false,
+            relative_speed: 1.0,
+            pending_step: Duration::ZERO,
+            delta: Duration::ZERO,
+            elapsed: Duration::ZERO,
+        }
+    }
+}
+
+impl AsciiUiTime {
+    pub fn source(&self) -> AsciiUiTimeSource {
+        self.source
+    }
+
+    pub fn set_source(&mut self, source: AsciiUiTimeSource) {
+        self.source = source;
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn unpause(&mut self) {
+        self.paused = false;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    // Moves the clock on by `duration` on the next frame, on top of the time of the source.
+    // While paused this is the only way the ui moves.
+    pub fn step(&mut self, duration: Duration) {
+        self.pending_step += duration;
+    }
+
+    pub fn relative_speed(&self) -> f32 {
+        self.relative_speed
+    }
+
+    // Values below 0 are taken as 0.
+    pub fn set_relative_speed(&mut self, speed: f32) {
+        self.relative_speed = speed.max(0.0);
+    }
+
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+
+    pub fn delta_seconds(&self) -> f32 {
+        self.delta.as_secs_f32()
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn elapsed_seconds(&self) -> f32 {
+        self.elapsed.as_secs_f32()
+    }
+
+    fn advance(&mut self, source: Duration) {
+        let running = if self.paused { Duration::ZERO } else { source.mul_f32(self.relative_speed) };
+        self.delta = running + std::mem::take(&mut self.pending_step);
+        self.elapsed += self.delta;
+    }
+}
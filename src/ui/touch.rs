@@ -0,0 +1,157 @@
+use bevy::{
+    input::{
+        mouse::MouseButtonInput,
+        touch::{TouchInput, TouchPhase},
+        ButtonState, InputSystem,
+    },
+    prelude::*,
+};
+
+use super::{util::AsciiHitTest, AsciiUiSet};
+
+//=============================================================================
+//             Plugin and Systems
+//=============================================================================
+
+// Lets a finger use the ui like a mouse. The first finger on the screen moves the cursor and holds
+// the left mouse button, so widgets are tapped the same way they are clicked. Holding it still
+// also presses the right button, like a right click. Fingers that touch the screen while another
+// one is down are ignored, so a pinch does not click anything.
+pub struct AsciiTouchPlugin;
+
+impl Plugin for AsciiTouchPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<AsciiTouch>()
+            .init_resource::<AsciiTouchSettings>()
+            .register_type::<AsciiTouchSettings>()
+            .add_event::<AsciiComponentLongPressedEvent>()
+            // Before the input of bevy, which turns the mouse buttons into `ButtonInput` in the
+            // same frame.
+            .add_systems(PreUpdate, update_touch.before(InputSystem))
+            .add_systems(Update, send_long_presses.in_set(AsciiUiSet::Interaction))
+        ;
+    }
+}
+
+#[derive(Resource, Reflect, Clone, Debug)]
+pub struct AsciiTouchSettings {
+    // How many seconds a finger has to stay down for a long press, `None` turns them off.
+    pub long_press: Option<f32>,
+    // How far a finger can move in logical pixels before it is no longer a long press.
+    pub long_press_slop: f32,
+}
+
+impl Default for AsciiTouchSettings {
+    fn default() -> Self {
+        AsciiTouchSettings {
+            long_press: Some(0.5),
+            long_press_slop: 12.0,
+        }
+    }
+}
+
+// The finger that acts as the mouse. Once it is lifted the cursor stays where it was, so the
+// widget that was tapped stays hovered, like on the web.
+#[derive(Resource, Default, Clone, Debug)]
+pub struct AsciiTouch {
+    finger: Option<u64>,
+    window: Option<Entity>,
+    position: Option<Vec2>,
+    start: Vec2,
+    held_for: f32,
+    can_long_press: bool,
+    is_long_press: bool,
+    just_long_pressed: bool,
+}
+
+impl AsciiTouch {
+    pub fn is_down(&self) -> bool {
+        self.finger.is_some()
+    }
+
+    // The window of the finger and where it is, or where it was lifted, in logical pixels.
+    pub fn position(&self) -> Option<(Entity, Vec2)> {
+        Some((self.window?, self.position?))
+    }
+
+    pub fn just_long_pressed(&self) -> bool {
+        self.just_long_pressed
+    }
+}
+
+// Sent for the top most node under a finger that is held down long enough.
+#[derive(Event, Reflect, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AsciiComponentLongPressedEvent(pub Entity);
+
+fn update_touch(
+    mut touches: EventReader<TouchInput>,
+    mut buttons: EventWriter<MouseButtonInput>,
+    mut touch: ResMut<AsciiTouch>,
+    settings: Res<AsciiTouchSettings>,
+    time: Res<Time>,
+) {
+    touch.just_long_pressed = false;
+    let mut send = |button: MouseButton, state: ButtonState, window: Entity| {
+        buttons.send(MouseButtonInput { button, state, window });
+    };
+
+    for event in touches.read() {
+        match event.phase {
+            TouchPhase::Started if touch.finger.is_none() => {
+                *touch = AsciiTouch {
+                    finger: Some(event.id),
+                    window: Some(event.window),
+                    position: Some(event.position),
+                    start: event.position,
+                    can_long_press: true,
+                    ..default()
+                };
+                send(MouseButton::Left, ButtonState::Pressed, event.window);
+            }
+            // A second finger makes it a gesture.
+            TouchPhase::Started => touch.can_long_press = false,
+            _ if touch.finger != Some(event.id) => {}
+            TouchPhase::Moved => {
+                touch.position = Some(event.position);
+                if event.position.distance(touch.start) > settings.long_press_slop {
+                    touch.can_long_press = false;
+                }
+            }
+            TouchPhase::Ended | TouchPhase::Canceled => {
+                touch.position = Some(event.position);
+                touch.finger = None;
+                send(MouseButton::Left, ButtonState::Released, event.window);
+                if touch.is_long_press {
+                    send(MouseButton::Right, ButtonState::Released, event.window);
+                }
+            }
+        }
+    }
+
+    let (Some(long_press), Some(window)) = (settings.long_press, touch.window) else {
+        return;
+    };
+    if !touch.is_down() || !touch.can_long_press || touch.is_long_press {
+        return;
+    }
+    touch.held_for += time.delta_seconds();
+    if touch.held_for >= long_press {
+        touch.is_long_press = true;
+        touch.just_long_pressed = true;
+        send(MouseButton::Right, ButtonState::Pressed, window);
+    }
+}
+
+fn send_long_presses(
+    touch: Res<AsciiTouch>,
+    hit_test: AsciiHitTest,
+    mut events: EventWriter<AsciiComponentLongPressedEvent>,
+) {
+    if !touch.just_long_pressed() {
+        return;
+    }
+    if let Some(entity) = hit_test.component_under_cursor() {
+        events.send(AsciiComponentLongPressedEvent(entity));
+    }
+}
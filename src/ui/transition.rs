@@ -0,0 +1,199 @@
+use bevy::{prelude::*, render::view::VisibilitySystems};
+
+use super::{time::AsciiUiTime, util::noise_hash, AsciiMarkDirtyEvent, AsciiUiSet};
+
+//=============================================================================
+//             Plugin and Systems
+//=============================================================================
+
+pub(crate) struct AsciiTransitionPlugin;
+
+impl Plugin for AsciiTransitionPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .register_type::<AsciiTransitionEffect>()
+            .init_resource::<AsciiTransitions>()
+            .add_event::<AsciiTransitionEvent>()
+            .add_event::<AsciiTransitionFinishedEvent>()
+            // Before visibility is propagated, so the screens are shown and hidden this frame.
+            .add_systems(
+                PostUpdate,
+                update_transitions
+                    .in_set(AsciiUiSet::Propagate)
+                    .before(VisibilitySystems::VisibilityPropagate),
+            )
+        ;
+    }
+}
+
+//=============================================================================
+//             Ascii Transition
+//=============================================================================
+
+// How the new screen of a transition takes over the old one, cell by cell.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum AsciiTransitionEffect {
+    // An edge that moves across the screen in the direction.
+    Wipe(AsciiWipeDirection),
+    // Cells are swapped in a random order.
+    Dissolve,
+    // Cells are swapped one after the other, row by row, like the screen is being typed.
+    Typewriter,
+    // The old screen opens from the middle to both sides.
+    Curtain,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum AsciiWipeDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl AsciiTransitionEffect {
+    // Whether the cell of a screen of the given size shows the new screen at `progress`, from 0
+    // at the start to 1 at the end of the transition.
+    pub fn is_revealed(&self, progress: f32, x: i32, y: i32, width: u32, height: u32, seed: u64) -> bool {
+        let (width, height) = (width as f32, height as f32);
+        let (x, y) = (x as f32, y as f32);
+        match self {
+            AsciiTransitionEffect::Wipe(AsciiWipeDirection::Right) => x < progress * width,
+            AsciiTransitionEffect::Wipe(AsciiWipeDirection::Left) => x >= (1.0 - progress) * width,
+            AsciiTransitionEffect::Wipe(AsciiWipeDirection::Down) => y < progress * height,
+            AsciiTransitionEffect::Wipe(AsciiWipeDirection::Up) => y >= (1.0 - progress) * height,
+            AsciiTransitionEffect::Dissolve => {
+                let value = (noise_hash(seed, x as i32, y as i32) % 10_000) as f32 / 10_000.0;
+                value < progress
+            }
+            AsciiTransitionEffect::Typewriter => y * width + x < progress * width * height,
+            AsciiTransitionEffect::Curtain => (x * 2.0 + 1.0 - width).abs() < progress * width,
+        }
+    }
+}
+
+// Switches from the ui root `from` to the ui root `to`. `to` is made visible and drawn over
+// `from` one cell at a time, and once every cell is showing it `from` is hidden and an
+// `AsciiTransitionFinishedEvent` is sent. A transition that starts while one of the screens is
+// still in another one finishes the other one right away.
+#[derive(Event, Clone, Copy, PartialEq, Debug)]
+pub struct AsciiTransitionEvent {
+    pub from: Entity,
+    pub to: Entity,
+    pub effect: AsciiTransitionEffect,
+    // How long it takes, in seconds.
+    pub duration: f32,
+}
+
+impl AsciiTransitionEvent {
+    pub fn new(from: Entity, to: Entity, effect: AsciiTransitionEffect) -> Self {
+        AsciiTransitionEvent {
+            from,
+            to,
+            effect,
+            duration: 0.5,
+        }
+    }
+
+    pub fn duration(mut self, seconds: f32) -> Self {
+        self.duration = seconds;
+        self
+    }
+}
+
+#[derive(Event, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct AsciiTransitionFinishedEvent {
+    pub from: Entity,
+    pub to: Entity,
+}
+
+// Added to both roots of a running transition, it hides the cells that belong to the other
+// screen. This is kept up to date automatically and should not be inserted by hand.
+#[derive(Component, Clone, Copy, PartialEq, Debug)]
+pub struct AsciiTransitionMask {
+    effect: AsciiTransitionEffect,
+    progress: f32,
+    seed: u64,
+    is_incoming: bool,
+}
+
+impl AsciiTransitionMask {
+    // Whether the global cell of a surface of the given size is drawn.
+    pub fn shows(&self, x: i32, y: i32, width: u32, height: u32) -> bool {
+        self.effect.is_revealed(self.progress, x, y, width, height, self.seed) == self.is_incoming
+    }
+}
+
+pub(crate) struct AsciiActiveTransition {
+    event: AsciiTransitionEvent,
+    elapsed: f32,
+}
+
+#[derive(Resource, Default)]
+pub(crate) struct AsciiTransitions(Vec<AsciiActiveTransition>);
+
+pub(crate) fn update_transitions(
+    mut commands: Commands,
+    mut transitions: ResMut<AsciiTransitions>,
+    mut events: EventReader<AsciiTransitionEvent>,
+    mut finished: EventWriter<AsciiTransitionFinishedEvent>,
+    mut dirty: EventWriter<AsciiMarkDirtyEvent>,
+    mut visibilities: Query<&mut Visibility>,
+    time: Res<AsciiUiTime>,
+) {
+    for event in events.read() {
+        let screens = [event.from, event.to];
+        for transition in transitions.0.iter_mut() {
+            if screens.contains(&transition.event.from) || screens.contains(&transition.event.to) {
+                transition.elapsed = f32::INFINITY;
+            }
+        }
+        transitions.0.push(AsciiActiveTransition { event: *event, elapsed: -time.delta_seconds() });
+    }
+    if transitions.0.is_empty() {
+        return;
+    }
+
+    let mut set_visibility = |entity: Entity, visibility: Visibility| {
+        if let Ok(mut current) = visibilities.get_mut(entity) {
+            if *current != visibility {
+                *current = visibility;
+            }
+        }
+    };
+
+    // A transition that was cut short finishes before the one that replaced it starts.
+    for transition in transitions.0.iter_mut() {
+        transition.elapsed += time.delta_seconds();
+        let AsciiTransitionEvent { from, to, effect, duration } = transition.event;
+        let progress = if duration > 0.0 { (transition.elapsed / duration).clamp(0.0, 1.0) } else { 1.0 };
+
+        if progress >= 1.0 {
+            set_visibility(from, Visibility::Hidden);
+            set_visibility(to, Visibility::Inherited);
+            for screen in [from, to] {
+                if let Some(mut entity) = commands.get_entity(screen) {
+                    entity.remove::<AsciiTransitionMask>();
+                }
+            }
+            finished.send(AsciiTransitionFinishedEvent { from, to });
+            continue;
+        }
+
+        set_visibility(to, Visibility::Inherited);
+        let seed = to.to_bits();
+        for (screen, is_incoming) in [(from, false), (to, true)] {
+            if let Some(mut entity) = commands.get_entity(screen) {
+                entity.insert(AsciiTransitionMask { effect, progress, seed, is_incoming });
+            }
+        }
+    }
+
+    transitions.0.retain(|transition| {
+        let duration = transition.event.duration;
+        duration > 0.0 && transition.elapsed < duration
+    });
+    dirty.send(AsciiMarkDirtyEvent);
+}
@@ -1,24 +1,34 @@
 
 use std::ops::{Deref, DerefMut};
 
-use bevy::{prelude::*, render::camera::RenderTarget, window::PrimaryWindow};
+use bevy::{ecs::system::SystemParam, prelude::*, utils::HashMap, render::camera::RenderTarget, window::{PrimaryWindow, WindowRef}};
 
-use crate::ascii::AsciiCamera;
+use crate::ascii::{update_target_resolution, AsciiCamera, AsciiGridInfo};
+
+use super::{
+    bounds::AsciiNode, touch::{AsciiTouch, AsciiTouchPlugin}, ui_target, AsciiUiSet, AsciiUiTarget
+};
 
 //=============================================================================
-//            UtilPlugin
+//            Input Plugin
 //=============================================================================
 
-pub struct AsciiUtils;
+// Tracks the cursor on the character grid and adds the hover and click events of the widgets.
+// Fingers on a touch screen move the cursor too, see `AsciiTouchPlugin`.
+pub struct AsciiInputPlugin;
 
-impl Plugin for AsciiUtils {
+impl Plugin for AsciiInputPlugin {
     fn build(&self, app: &mut App) {
         app
+            .add_plugins(AsciiTouchPlugin)
             .register_type::<AsciiCursor>()
             .add_event::<AsciiComponentHoverEnteredEvent>()
             .add_event::<AsciiComponentHoverExitedEvent>()
             .add_event::<AsciiComponentButtonClicked>()
-            .add_systems(PreUpdate, update_ascii_cursor)
+            .register_type::<AsciiHotkey>()
+            .add_systems(PreUpdate, update_ascii_cursor.in_set(AsciiUiSet::Input).after(update_target_resolution))
+            .add_systems(Update, fire_hotkeys.in_set(AsciiUiSet::Interaction))
+            .add_systems(Update, update_ime.after(AsciiUiSet::Interaction))
         ;
     }
 }
@@ -41,41 +51,230 @@ fn update_ascii_cursor(
     mut primary_window : Query<(Entity, &Window, Option<&mut AsciiCursor>), With<PrimaryWindow>>,
     mut windows : Query<(&Window, Option<&mut AsciiCursor>), Without<PrimaryWindow>>,
     cameras : Query<(&Camera, &AsciiCamera)>,
+    touch : Option<Res<AsciiTouch>>,
 ) {
+    // Several cameras can share a window in split screen, the cursor belongs to the one whose
+    // viewport it is in.
+    let mut cells : HashMap<Entity, Option<IVec2>> = HashMap::new();
     for (camera, ascii) in cameras.iter() {
         if let RenderTarget::Window(window_ref ) = camera.target {
-            let (window_entity, window, cursor) = match window_ref {
-                bevy::window::WindowRef::Primary => {
-                    let Ok(window) = primary_window.get_single_mut() else {
-                        return;
+            let (window_entity, window) = match window_ref {
+                WindowRef::Primary => {
+                    let Ok((entity, window, _)) = primary_window.get_single() else {
+                        continue;
                     };
-                    window
+                    (entity, window)
                 },
-                bevy::window::WindowRef::Entity(entity) => {
-                    let Ok((window, cursor)) = windows.get_mut(entity) else {
-                        return;
+                WindowRef::Entity(entity) => {
+                    let Ok((window, _)) = windows.get(entity) else {
+                        continue;
                     };
-                    (entity, window, cursor)
+                    (entity, window)
                 },
             };
             
-            match (window.cursor_position(), cursor) {
-                (None, None) => {commands.entity(window_entity).insert(AsciiCursor::None);},
-                (None, Some(mut cursor)) => *cursor = AsciiCursor::None,
-                (Some(pos), None) => {
-                    let target_res = ascii.target_res();
-                    let x = (pos.x / (window.physical_width() as f32 /  target_res.x)).floor() as u32;
-                    let y = (pos.y / (window.physical_height() as f32 /  target_res.y)).floor() as u32;
-                    commands.entity(window_entity).insert(AsciiCursor::Some { x, y });
-                },
-                (Some(pos), Some(mut cursor)) => {
-                    let target_res = ascii.target_res();
-                    let x = ((pos.x * 2.0) / (window.physical_width() as f32 /  target_res.x)).floor() as u32;
-                    let y = ((pos.y * 2.0) / (window.physical_height() as f32 /  target_res.y)).floor() as u32 ;
-                    *cursor = AsciiCursor::Some { x, y };
-                },
-            };
+            let cell = cursor_cell(window_entity, window, touch.as_deref(), camera, ascii);
+            let entry = cells.entry(window_entity).or_insert(None);
+            if entry.is_none() {
+                *entry = cell;
+            }
+        }
+    }
+
+    for (window_entity, cell) in cells {
+        let cursor = match primary_window.get_mut(window_entity) {
+            Ok((_, _, cursor)) => cursor,
+            Err(_) => match windows.get_mut(window_entity) {
+                Ok((_, cursor)) => cursor,
+                Err(_) => continue,
+            },
+        };
+
+        let new_cursor = match cell {
+            Some(cell) => AsciiCursor::Some { x: cell.x as u32, y: cell.y as u32 },
+            None => AsciiCursor::None,
+        };
+        match cursor {
+            Some(mut cursor) => {
+                if *cursor != new_cursor {
+                    *cursor = new_cursor;
+                }
+            },
+            None => {commands.entity(window_entity).insert(new_cursor);},
+        };
+    }
+}
+
+// Where the cursor is on the window in physical pixels. A finger that is down comes first, then
+// the mouse, then the place the last finger was lifted.
+pub(crate) fn pointer_position(window_entity: Entity, window: &Window, touch: Option<&AsciiTouch>) -> Option<Vec2> {
+    let finger = touch
+        .and_then(|touch| touch.position())
+        .filter(|(entity, _)| *entity == window_entity)
+        .map(|(_, position)| position * window.scale_factor());
+    match (finger, window.physical_cursor_position()) {
+        (Some(finger), _) if touch.is_some_and(|touch| touch.is_down()) => Some(finger),
+        (_, Some(mouse)) => Some(mouse),
+        (finger, None) => finger,
+    }
+}
+
+// The cell of the ui of the camera under the cursor, using the physical size of a cell so it
+// stays correct on scaled displays. Cursors outside of the camera viewport are ignored.
+pub(crate) fn cursor_cell(window_entity: Entity, window: &Window, touch: Option<&AsciiTouch>, camera: &Camera, ascii: &AsciiCamera) -> Option<IVec2> {
+    let mut position = pointer_position(window_entity, window, touch)?;
+    let target_res = ascii.target_res();
+    let ui_res = ascii.ui_res();
+    if ui_res.x <= 0.0 || ui_res.y <= 0.0 {
+        return None;
+    }
+
+    let mut size = Vec2::new(window.physical_width() as f32, window.physical_height() as f32);
+    if let Some(viewport) = &camera.viewport {
+        position -= viewport.physical_position.as_vec2();
+        size = viewport.physical_size.as_vec2();
+    }
+
+    let cell_width = size.x / target_res.x * ascii.ui_scale() as f32;
+    let cell_height = size.y / target_res.y * ascii.ui_scale() as f32;
+    let cell = IVec2::new((position.x / cell_width).floor() as i32, (position.y / cell_height).floor() as i32);
+    if cell.x < 0 || cell.y < 0 || cell.x >= ui_res.x as i32 || cell.y >= ui_res.y as i32 {
+        return None;
+    }
+    Some(cell)
+}
+
+//=============================================================================
+//            AsciiHitTest
+//=============================================================================
+
+// Finds out what is under the mouse cursor. Interactive components should use this instead of
+// testing the cursor against their bounds themselves.
+#[derive(SystemParam)]
+pub struct AsciiHitTest<'w, 's> {
+    cameras: Query<'w, 's, (Entity, &'static Camera, &'static AsciiCamera)>,
+    primary_window: Query<'w, 's, (Entity, &'static Window), With<PrimaryWindow>>,
+    windows: Query<'w, 's, &'static Window>,
+    touch: Option<Res<'w, AsciiTouch>>,
+    nodes: Query<'w, 's, (Entity, &'static AsciiNode, Option<&'static InheritedVisibility>)>,
+    hierarchy: Query<'w, 's, (Option<&'static Parent>, Option<&'static AsciiUiTarget>, Has<AsciiCamera>)>,
+}
+
+impl<'w, 's> AsciiHitTest<'w, 's> {
+    // The cell under the cursor in the character grid of the given camera.
+    pub fn cell_under_cursor(&self, camera: Entity) -> Option<IVec2> {
+        let (_, camera, ascii) = self.cameras.get(camera).ok()?;
+        let RenderTarget::Window(window_ref) = camera.target else {
+            return None;
+        };
+        let (window_entity, window) = match window_ref {
+            WindowRef::Primary => self.primary_window.get_single().ok()?,
+            WindowRef::Entity(entity) => (entity, self.windows.get(entity).ok()?),
+        };
+        cursor_cell(window_entity, window, self.touch.as_deref(), camera, ascii)
+    }
+
+    // The camera on the primary window whose viewport the cursor is in, and the cell under it.
+    pub fn camera_under_cursor(&self) -> Option<(Entity, IVec2)> {
+        let (window_entity, window) = self.primary_window.get_single().ok()?;
+        self.cameras.iter().find_map(|(entity, camera, ascii)| match camera.target {
+            RenderTarget::Window(WindowRef::Primary) => {
+                cursor_cell(window_entity, window, self.touch.as_deref(), camera, ascii).map(|cell| (entity, cell))
+            }
+            _ => None,
+        })
+    }
+
+    // The cell under the cursor on the primary window.
+    pub fn cursor(&self) -> Option<IVec2> {
+        self.camera_under_cursor().map(|(_, cell)| cell)
+    }
+
+    // Whether the node is drawn on the camera.
+    fn is_on_camera(&self, entity: Entity, camera: Entity) -> bool {
+        ui_target(entity, &self.hierarchy).map(|target| target == camera).unwrap_or(true)
+    }
+
+    // The cursor relative to the top left corner of the node, if the cursor is inside of it.
+    pub fn local_cursor(&self, entity: Entity) -> Option<IVec2> {
+        let (camera, cursor) = self.camera_under_cursor()?;
+        if !self.is_on_camera(entity, camera) {
+            return None;
         }
+        let (_, node, _) = self.nodes.get(entity).ok()?;
+        let bounds = &node.bounds;
+        bounds
+            .is_within(cursor.x, cursor.y)
+            .then(|| IVec2::new(cursor.x - bounds.x, cursor.y - bounds.y))
+    }
+
+    // Whether the cursor is inside the bounds of the node, even if another node is on top of it.
+    pub fn is_under_cursor(&self, entity: Entity) -> bool {
+        self.local_cursor(entity).is_some()
+    }
+
+    // The visible node with the highest layer under the cursor.
+    pub fn component_under_cursor(&self) -> Option<Entity> {
+        let (camera, cursor) = self.camera_under_cursor()?;
+        self.nodes
+            .iter()
+            .filter(|(entity, node, visibility)| {
+                visibility.map(|v| v.get()).unwrap_or(true)
+                    && node.bounds.is_within(cursor.x, cursor.y)
+                    && self.is_on_camera(*entity, camera)
+            })
+            .max_by_key(|(_, node, _)| node.bounds.layer)
+            .map(|(entity, _, _)| entity)
+    }
+
+    // Whether the node is the top most node under the cursor.
+    pub fn is_hovered(&self, entity: Entity) -> bool {
+        self.component_under_cursor() == Some(entity)
+    }
+}
+
+//=============================================================================
+//            Input Method
+//=============================================================================
+
+// Added to a node while its component takes the keys that are typed, at the cell of its caret.
+// Kept up to date from `AsciiComponent::keyboard_focus`.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AsciiKeyboardFocus(pub IVec2);
+
+// Turns the input method of the primary window on while a text field is focused, so languages
+// that are composed, like japanese, can be typed. Its candidate box is placed under the row the
+// caret is on.
+fn update_ime(
+    focused : Query<(Entity, &AsciiKeyboardFocus)>,
+    hierarchy : Query<(Option<&Parent>, Option<&AsciiUiTarget>, Has<AsciiCamera>)>,
+    cameras : Query<(&Camera, &AsciiCamera, &AsciiGridInfo)>,
+    mut windows : Query<&mut Window, With<PrimaryWindow>>,
+) {
+    let Ok(mut window) = windows.get_single_mut() else {
+        return;
+    };
+
+    let focused = focused.iter().next().map(|(entity, focus)| (entity, focus.0.x, focus.0.y));
+    if window.ime_enabled != focused.is_some() {
+        window.ime_enabled = focused.is_some();
+    }
+
+    let Some((entity, x, y)) = focused else {
+        return;
+    };
+    let Some((camera, ascii, grid)) = ui_target(entity, &hierarchy).and_then(|camera| cameras.get(camera).ok()) else {
+        return;
+    };
+    let cell_size = grid.logical_cell_pixels() * ascii.ui_scale() as f32;
+    let offset = camera
+        .viewport
+        .as_ref()
+        .map(|viewport| viewport.physical_position.as_vec2() / grid.scale_factor.max(f32::EPSILON))
+        .unwrap_or(Vec2::ZERO);
+    let position = offset + Vec2::new(x as f32, (y + 1) as f32) * cell_size;
+    if window.ime_position != position {
+        window.ime_position = position;
     }
 }
 
@@ -92,21 +291,144 @@ pub struct AsciiComponentHoverExitedEvent(pub Entity);
 #[derive(Event, Reflect, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct AsciiComponentButtonClicked(pub Entity);
 
+//=============================================================================
+//            Hotkeys
+//=============================================================================
+
+// Sends an `AsciiComponentButtonClicked` for the entity when the key is pressed, the same as
+// clicking it. Buttons show the key in their label when it is one of the letters of the label.
+#[derive(Component, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct AsciiHotkey(pub KeyCode);
+
+impl AsciiHotkey {
+    // The character of the key, for letter and digit keys.
+    pub fn character(&self) -> Option<char> {
+        let character = match self.0 {
+            KeyCode::KeyA => 'A',
+            KeyCode::KeyB => 'B',
+            KeyCode::KeyC => 'C',
+            KeyCode::KeyD => 'D',
+            KeyCode::KeyE => 'E',
+            KeyCode::KeyF => 'F',
+            KeyCode::KeyG => 'G',
+            KeyCode::KeyH => 'H',
+            KeyCode::KeyI => 'I',
+            KeyCode::KeyJ => 'J',
+            KeyCode::KeyK => 'K',
+            KeyCode::KeyL => 'L',
+            KeyCode::KeyM => 'M',
+            KeyCode::KeyN => 'N',
+            KeyCode::KeyO => 'O',
+            KeyCode::KeyP => 'P',
+            KeyCode::KeyQ => 'Q',
+            KeyCode::KeyR => 'R',
+            KeyCode::KeyS => 'S',
+            KeyCode::KeyT => 'T',
+            KeyCode::KeyU => 'U',
+            KeyCode::KeyV => 'V',
+            KeyCode::KeyW => 'W',
+            KeyCode::KeyX => 'X',
+            KeyCode::KeyY => 'Y',
+            KeyCode::KeyZ => 'Z',
+            KeyCode::Digit0 => '0',
+            KeyCode::Digit1 => '1',
+            KeyCode::Digit2 => '2',
+            KeyCode::Digit3 => '3',
+            KeyCode::Digit4 => '4',
+            KeyCode::Digit5 => '5',
+            KeyCode::Digit6 => '6',
+            KeyCode::Digit7 => '7',
+            KeyCode::Digit8 => '8',
+            KeyCode::Digit9 => '9',
+            _ => return None,
+        };
+        Some(character)
+    }
+}
+
+// Added to a node whose component does not take hotkeys right now, see
+// `AsciiComponent::accepts_hotkeys`.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AsciiHotkeyDisabled;
+
+pub(crate) fn fire_hotkeys(
+    hotkeys : Query<(Entity, &AsciiHotkey, Option<&InheritedVisibility>), Without<AsciiHotkeyDisabled>>,
+    focused : Query<(), With<AsciiKeyboardFocus>>,
+    keys : Res<ButtonInput<KeyCode>>,
+    mut clicked : EventWriter<AsciiComponentButtonClicked>,
+) {
+    // Keys typed into a text field are not hotkeys.
+    if !focused.is_empty() {
+        return;
+    }
+
+    for (entity, hotkey, visibility) in hotkeys.iter() {
+        if !visibility.map(|v| v.get()).unwrap_or(true) {
+            continue;
+        }
+        if keys.just_pressed(hotkey.0) {
+            clicked.send(AsciiComponentButtonClicked(entity));
+        }
+    }
+}
+
 //=============================================================================
 //            Ui Events
 //=============================================================================
 
 #[derive(Debug, Clone, PartialEq, Copy, Reflect)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub enum Value {
     Px(i32),
     Percent(f32),
+    // The size the node measured for its content, see `AsciiIntrinsicSize`. Nodes that did not
+    // measure themselves fill their parent.
+    Auto,
+    // Like `Percent`, but of the grid of the camera along the same axis instead of the parent. The
+    // layout and the `AsciiBuffer` resolve it with `on_screen`.
+    ScreenPercent(f32),
+    // Part of the shorter and the longer side of the grid of the camera.
+    Vmin(f32),
+    Vmax(f32),
 }
 
 impl Value {
     pub fn pixel_u32(&self, parent_dim : u32) -> u32 {
         match self {
-            Value::Px(v) => *v as u32,
+            // A negative size is no cells, not a width that wrapped around.
+            Value::Px(v) => (*v).max(0) as u32,
             Value::Percent(v) => (parent_dim as f32 * v) as u32,
+            Value::Auto => parent_dim,
+            // Only known once they are replaced with `on_screen`. Without a grid they are taken as
+            // parts of the parent, which is most likely not what was meant.
+            Value::ScreenPercent(v) | Value::Vmin(v) | Value::Vmax(v) => {
+                static WARNED: std::sync::Once = std::sync::Once::new();
+                WARNED.call_once(|| {
+                    warn!("{self:?} was sized without the grid of a camera and is taken as a part of the parent, resolve it with `Value::on_screen` first");
+                });
+                (parent_dim as f32 * v) as u32
+            }
+        }
+    }
+
+    // The values relative to the grid of the camera replaced by cells. `screen_dim` is the side
+    // of the grid along the axis of the value.
+    pub fn on_screen(self, screen_dim: u32, screen: UVec2) -> Value {
+        let cells = |dim: u32, v: f32| Value::Px((dim as f32 * v) as i32);
+        match self {
+            Value::ScreenPercent(v) => cells(screen_dim, v),
+            Value::Vmin(v) => cells(screen.min_element(), v),
+            Value::Vmax(v) => cells(screen.max_element(), v),
+            value => value,
+        }
+    }
+
+    // `Auto` replaced by the measured size, other values stay as they are.
+    pub fn or_measured(self, measured: u32) -> Value {
+        match self {
+            Value::Auto => Value::Px(measured as i32),
+            value => value,
         }
     }
 }
@@ -183,4 +505,89 @@ impl <T> DerefMut for Variable<T> {
 
 pub fn break_string_into_lines(string : &str, max_width : usize) -> Vec<String> {
     textwrap::wrap(string, max_width).iter().map(|s| s.to_string()).collect()
-}
\ No newline at end of file
+}
+
+// A cheap, stable hash of a cell, used to place noise. The same seed and cell always give the
+// same value on every platform.
+pub fn noise_hash(seed: u64, x: i32, y: i32) -> u64 {
+    let mut value = seed
+        ^ (x as u32 as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (y as u32 as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F);
+    // splitmix64 finalizer
+    value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    value ^ (value >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+
+    #[test]
+    fn screen_values_are_cells_of_the_grid() {
+        let screen = UVec2::new(80, 24);
+        assert_eq!(Value::ScreenPercent(0.5).on_screen(80, screen), Value::Px(40));
+        assert_eq!(Value::ScreenPercent(0.5).on_screen(24, screen), Value::Px(12));
+        assert_eq!(Value::Vmin(0.5).on_screen(80, screen), Value::Px(12));
+        assert_eq!(Value::Vmax(0.25).on_screen(24, screen), Value::Px(20));
+        // Parts of a cell are dropped.
+        assert_eq!(Value::ScreenPercent(0.33).on_screen(80, screen), Value::Px(26));
+        // The other values do not depend on the screen.
+        for value in [Value::Px(3), Value::Percent(0.5), Value::Auto] {
+            assert_eq!(value.on_screen(80, screen), value);
+        }
+    }
+
+    #[test]
+    fn screen_values_at_the_edges() {
+        let screen = UVec2::new(80, 24);
+        assert_eq!(Value::ScreenPercent(0.0).on_screen(80, screen), Value::Px(0));
+        assert_eq!(Value::ScreenPercent(1.0).on_screen(80, screen), Value::Px(80));
+        assert_eq!(Value::Vmax(1.5).on_screen(24, screen), Value::Px(120));
+        assert_eq!(Value::ScreenPercent(-0.25).on_screen(80, screen), Value::Px(-20));
+        assert_eq!(Value::Vmin(0.5).on_screen(0, UVec2::ZERO), Value::Px(0));
+
+        // Negative sizes are empty.
+        assert_eq!(Value::Px(-20).pixel_u32(80), 0);
+        assert_eq!(Value::Percent(-0.25).pixel_u32(80), 0);
+        assert_eq!(Value::ScreenPercent(-0.25).pixel_u32(80), 0);
+    }
+
+    #[test]
+    fn hotkeys_know_the_character_of_letters_and_digits() {
+        assert_eq!(AsciiHotkey(KeyCode::KeyA).character(), Some('A'));
+        assert_eq!(AsciiHotkey(KeyCode::KeyZ).character(), Some('Z'));
+        assert_eq!(AsciiHotkey(KeyCode::Digit0).character(), Some('0'));
+        assert_eq!(AsciiHotkey(KeyCode::Digit9).character(), Some('9'));
+        assert_eq!(AsciiHotkey(KeyCode::Space).character(), None);
+        assert_eq!(AsciiHotkey(KeyCode::F1).character(), None);
+        assert_eq!(AsciiHotkey(KeyCode::Numpad1).character(), None);
+    }
+
+    fn fired(world: &mut World) -> Vec<Entity> {
+        world.run_system_once(fire_hotkeys);
+        world.resource_mut::<Events<AsciiComponentButtonClicked>>().drain().map(|clicked| clicked.0).collect()
+    }
+
+    #[test]
+    fn hotkeys_do_not_fire_while_typing_or_disabled() {
+        let mut world = World::new();
+        world.init_resource::<Events<AsciiComponentButtonClicked>>();
+        let mut keys = ButtonInput::<KeyCode>::default();
+        keys.press(KeyCode::KeyS);
+        world.insert_resource(keys);
+
+        let save = world.spawn(AsciiHotkey(KeyCode::KeyS)).id();
+        world.spawn((AsciiHotkey(KeyCode::KeyS), AsciiHotkeyDisabled));
+        world.spawn(AsciiHotkey(KeyCode::KeyQ));
+        assert_eq!(fired(&mut world), vec![save]);
+
+        let field = world.spawn(AsciiKeyboardFocus(IVec2::new(2, 3))).id();
+        assert!(fired(&mut world).is_empty());
+
+        world.entity_mut(field).remove::<AsciiKeyboardFocus>();
+        assert_eq!(fired(&mut world), vec![save]);
+    }
+}
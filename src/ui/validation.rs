@@ -0,0 +1,163 @@
+use bevy::{prelude::*, utils::HashSet};
+
+use crate::ascii::AsciiCamera;
+
+use super::{
+    bounds::AsciiNode, buffer::AsciiBuffer, position::AsciiIntrinsicSize, ui_target, AsciiUi, AsciiUiSchedule,
+    AsciiUiSet, AsciiUiTarget,
+};
+
+//=============================================================================
+//             Plugin and Systems
+//=============================================================================
+
+pub(crate) struct AsciiValidationPlugin;
+
+impl Plugin for AsciiValidationPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<AsciiUiValidation>()
+            .init_resource::<ReportedIssues>()
+            .register_type::<AsciiUiValidation>()
+            .add_systems(
+                AsciiUiSchedule,
+                validate_nodes.in_set(AsciiUiSet::Extract).run_if(validation_enabled),
+            )
+        ;
+    }
+}
+
+// Looks for ui that draws nothing, or not all of itself, and warns about every node once. It is
+// on in debug builds, and can be turned on for release builds or off while it is too noisy.
+#[derive(Resource, Clone, Debug, Reflect)]
+pub struct AsciiUiValidation {
+    pub is_enabled: bool,
+}
+
+impl Default for AsciiUiValidation {
+    fn default() -> Self {
+        AsciiUiValidation { is_enabled: cfg!(debug_assertions) }
+    }
+}
+
+pub(crate) fn validation_enabled(validation: Res<AsciiUiValidation>) -> bool {
+    validation.is_enabled
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+enum Issue {
+    NoNode,
+    Empty,
+    Layer,
+    CutOff,
+    Target,
+}
+
+// The issues that were warned about. An issue that is fixed is forgotten, so it is warned about
+// again if it comes back.
+#[derive(Resource, Default)]
+pub(crate) struct ReportedIssues(HashSet<(Entity, Issue)>);
+
+impl ReportedIssues {
+    fn check(&mut self, entity: Entity, issue: Issue, message: Option<String>) {
+        match message {
+            Some(message) if self.0.insert((entity, issue)) => warn!("{}", message),
+            Some(_) => {}
+            None => {
+                self.0.remove(&(entity, issue));
+            }
+        }
+    }
+}
+
+fn describe(entity: Entity, name: Option<&Name>) -> String {
+    match name {
+        Some(name) => format!("The ui node {:?} ({:?})", name.as_str(), entity),
+        None => format!("The ui node {:?}", entity),
+    }
+}
+
+// Components that are not on a node are never laid out or drawn.
+#[allow(clippy::type_complexity)]
+pub(crate) fn validate_component<C: Component>(
+    components: Query<(Entity, Option<&Name>), (With<C>, Without<AsciiNode>)>,
+    mut reported: ResMut<ReportedIssues>,
+) {
+    let component = std::any::type_name::<C>().rsplit("::").next().unwrap_or_default();
+    for (entity, name) in components.iter() {
+        reported.check(entity, Issue::NoNode, Some(format!(
+            "{} has an {} but no AsciiNode, so it is never drawn. Spawn it with `commands.ascii_ui(..)`, or add an AsciiNode and an AsciiPosition",
+            describe(entity, name),
+            component,
+        )));
+    }
+}
+
+type ValidatedNodes<'w, 's> = Query<'w, 's, (
+    Entity,
+    &'static AsciiNode,
+    Option<&'static AsciiIntrinsicSize>,
+    Option<&'static InheritedVisibility>,
+    Option<&'static Name>,
+)>;
+
+fn validate_nodes(
+    nodes: ValidatedNodes,
+    cameras: Query<Has<AsciiUi>, With<AsciiCamera>>,
+    hierarchy: Query<(Option<&Parent>, Option<&AsciiUiTarget>, Has<AsciiCamera>)>,
+    mut reported: ResMut<ReportedIssues>,
+) {
+    // Components that got a node are fine now, and nodes that are gone do not need warnings.
+    reported.0.retain(|(entity, issue)| (*issue == Issue::NoNode) != nodes.contains(*entity));
+
+    for (entity, node, intrinsic_size, visibility, name) in nodes.iter() {
+        let bounds = &node.bounds;
+        let is_visible = visibility.map(|visibility| visibility.get()).unwrap_or(true);
+
+        let is_empty = is_visible && (bounds.width == 0 || bounds.height == 0);
+        reported.check(entity, Issue::Empty, is_empty.then(|| format!(
+            "{} is {} by {} cells, so nothing of it is drawn. Its position may be outside of its parent, or its parent may be too small",
+            describe(entity, name),
+            bounds.width,
+            bounds.height,
+        )));
+
+        let is_too_high = bounds.layer >= AsciiBuffer::POPUP_LAYER;
+        reported.check(entity, Issue::Layer, is_too_high.then(|| format!(
+            "{} is on layer {}, but ui nodes should stay below {}. Above it they cover popups, the AsciiOverlay and the cursor",
+            describe(entity, name),
+            bounds.layer,
+            AsciiBuffer::POPUP_LAYER,
+        )));
+
+        let is_cut_off = !is_empty
+            && intrinsic_size.is_some_and(|size| size.width > bounds.width || size.height > bounds.height);
+        reported.check(entity, Issue::CutOff, is_cut_off.then(|| {
+            let size = intrinsic_size.copied().unwrap_or_default();
+            format!(
+                "{} needs {} by {} cells for its content, but is {} by {}, so part of it is cut off. `Value::Auto` sizes it to its content",
+                describe(entity, name),
+                size.width,
+                size.height,
+                bounds.width,
+                bounds.height,
+            )
+        }));
+
+        let target = ui_target(entity, &hierarchy);
+        let target_issue = match target.map(|target| (target, cameras.get(target))) {
+            Some((target, Ok(false))) => Some(format!(
+                "{} is drawn on the camera {:?}, which has no AsciiUi, so it is never drawn",
+                describe(entity, name),
+                target,
+            )),
+            Some((target, Err(_))) => Some(format!(
+                "{} is drawn on {:?}, which is not an AsciiCamera, so it is never drawn",
+                describe(entity, name),
+                target,
+            )),
+            _ => None,
+        };
+        reported.check(entity, Issue::Target, target_issue);
+    }
+}